@@ -0,0 +1,55 @@
+// Generated by `just gen-grammars` from the analyzer's token tables; do not edit.
+
+/** highlight.js language: `hljs.registerLanguage("notion-formula", notionFormula)`. */
+export default function notionFormula() {
+  return {
+    "aliases": [
+      "notion-formula",
+      "formula"
+    ],
+    "contains": [
+      {
+        "begin": "//",
+        "end": "$",
+        "scope": "comment"
+      },
+      {
+        "begin": "/\\*",
+        "end": "\\*/",
+        "scope": "comment"
+      },
+      {
+        "begin": "\"",
+        "contains": [
+          {
+            "match": "\\\\[nt\"\\\\]",
+            "scope": "char.escape"
+          }
+        ],
+        "end": "\"",
+        "scope": "string"
+      },
+      {
+        "match": "\\b\\d+(?:\\.\\d+)?(?:[eE][+-]?\\d+)?",
+        "relevance": 0,
+        "scope": "number"
+      },
+      {
+        "match": "!=|&&|<=|==|>=|\\|\\||!|%|\\*|\\+|-|\\/|:|<|>|\\?|\\^",
+        "relevance": 0,
+        "scope": "operator"
+      },
+      {
+        "match": "#|,|\\.|\\(|\\)|\\[|\\]",
+        "relevance": 0,
+        "scope": "punctuation"
+      }
+    ],
+    "keywords": {
+      "built_in": "abs add at cbrt ceil concat contains date dateAdd dateBetween dateSubtract day divide e email empty equal exp first flat floor format formatDate formatNumber fromTimestamp hour id if ifs includes join last length ln log10 log2 lower match max mean median min minute mod month multiply name now padEnd padStart parseDate pi pow prop repeat replace replaceAll reverse round sign slice sort splice split sqrt substring subtract sum test timestamp toNumber today trim unequal unique upper week year",
+      "keyword": "not",
+      "literal": "true false"
+    },
+    "name": "Notion Formula"
+  };
+}
@@ -1,4 +1,5 @@
 use super::super::{FunctionCategory, FunctionSig, Ty};
+use crate::prelude::*;
 
 pub(super) fn builtins() -> Vec<FunctionSig> {
     vec![
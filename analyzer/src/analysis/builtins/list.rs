@@ -1,4 +1,5 @@
-use super::super::{normalize_union, FunctionCategory, FunctionSig, GenericId, Ty};
+use super::super::{FunctionCategory, FunctionSig, GenericId, Ty, normalize_union};
+use crate::prelude::*;
 
 /// Custom resolver for `flat(list)`.
 ///
@@ -97,16 +97,43 @@ macro_rules! generics {
     };
 }
 
+/// Builtin `detail` text; empty without the `docs` feature so the strings are not linked.
+#[cfg(feature = "docs")]
+macro_rules! detail {
+    ($detail:expr) => {
+        $detail
+    };
+}
+
+#[cfg(not(feature = "docs"))]
+macro_rules! detail {
+    ($detail:expr) => {
+        ""
+    };
+}
+
 macro_rules! func {
     ($category:expr, $detail:expr, $name:literal, $params:expr, $ret:expr $(,)?) => {
-        $crate::semantic::FunctionSig::new_builtin($category, $detail, $name, $params, $ret, vec![])
+        $crate::semantic::FunctionSig::new_builtin(
+            $category,
+            detail!($detail),
+            $name,
+            $params,
+            $ret,
+            vec![],
+        )
     };
 }
 
 macro_rules! func_g {
     ($category:expr, $detail:expr, $generics:expr, $name:literal, $params:expr, $ret:expr $(,)?) => {
         $crate::semantic::FunctionSig::new_builtin(
-            $category, $detail, $name, $params, $ret, $generics,
+            $category,
+            detail!($detail),
+            $name,
+            $params,
+            $ret,
+            $generics,
         )
     };
 }
@@ -114,7 +141,13 @@ macro_rules! func_g {
 macro_rules! func_gr {
     ($category:expr, $detail:expr, $generics:expr, $name:literal, $params:expr, $ret:expr, $resolver:expr $(,)?) => {
         $crate::semantic::FunctionSig::new_builtin_with_resolver(
-            $category, $detail, $name, $params, $ret, $generics, $resolver,
+            $category,
+            detail!($detail),
+            $name,
+            $params,
+            $ret,
+            $generics,
+            $resolver,
         )
     };
 }
@@ -1,19 +1,46 @@
-use super::FunctionSig;
+use crate::prelude::*;
+use crate::sync::LazyLock;
 
+use super::{Context, FunctionSig};
+
+#[cfg(feature = "builtins")]
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "builtins")]
 mod date;
+#[cfg(feature = "builtins")]
 mod general;
+#[cfg(feature = "builtins")]
 mod list;
+#[cfg(feature = "builtins")]
 mod math;
+#[cfg(feature = "builtins")]
 mod people;
+#[cfg(feature = "builtins")]
 mod special;
+#[cfg(feature = "builtins")]
 mod text;
 
+static BUILTINS: LazyLock<Vec<FunctionSig>> = LazyLock::new(build);
+
+static BUILTINS_CONTEXT: LazyLock<Context> = LazyLock::new(|| Context::with_builtins(Vec::new()));
+
+/// Every builtin function signature, built once per process. Empty without the `builtins`
+/// feature.
+pub fn builtins_functions() -> &'static [FunctionSig] {
+    &BUILTINS
+}
+
+/// A context with the builtin functions and no properties.
+pub fn builtins_context() -> &'static Context {
+    &BUILTINS_CONTEXT
+}
+
 // Category order is intentionally deterministic, matching the historical order in
 // `analysis/functions.rs`: General, Text, Number, Date, People, List, Special.
-pub fn builtins_functions() -> Vec<FunctionSig> {
+#[cfg(feature = "builtins")]
+fn build() -> Vec<FunctionSig> {
     let mut out = Vec::new();
     out.extend(general::builtins());
     out.extend(text::builtins());
@@ -24,3 +51,8 @@ pub fn builtins_functions() -> Vec<FunctionSig> {
     out.extend(special::builtins());
     out
 }
+
+#[cfg(not(feature = "builtins"))]
+fn build() -> Vec<FunctionSig> {
+    Vec::new()
+}
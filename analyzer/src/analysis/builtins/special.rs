@@ -1,4 +1,5 @@
 use super::super::{FunctionCategory, FunctionSig, GenericId, Ty};
+use crate::prelude::*;
 
 pub(super) fn builtins() -> Vec<FunctionSig> {
     let t0 = GenericId(0);
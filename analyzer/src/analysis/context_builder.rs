@@ -0,0 +1,153 @@
+//! Fluent construction of a [`Context`].
+
+use crate::prelude::*;
+use alloc::borrow::Cow;
+
+use super::{
+    Context, FormulaLimits, FunctionSig, Property, PropertyLookup, PropertyResolver,
+    SharedPropertyResolver, Ty, builtins_functions,
+};
+
+/// Builds a [`Context`] one property or function at a time.
+///
+/// Starts empty. Lookups return the first item with a name, so functions added before
+/// [`with_builtins`](Self::with_builtins) shadow builtins of the same name.
+///
+/// ```
+/// use analyzer::semantic::{Context, Ty};
+///
+/// let ctx = Context::builder()
+///     .prop("Age", Ty::Number)
+///     .disabled_prop("Owner", Ty::String, "rollups are not supported")
+///     .with_builtins()
+///     .without(&["now"])
+///     .build();
+/// assert_eq!(ctx.lookup("Age"), Some(Ty::Number));
+/// assert!(ctx.function("now").is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ContextBuilder {
+    properties: Vec<Property>,
+    /// Borrows the builtin table until something else is added or removed.
+    functions: Cow<'static, [FunctionSig]>,
+    property_resolver: Option<SharedPropertyResolver>,
+    property_lookup: PropertyLookup,
+    limits: FormulaLimits,
+}
+
+impl ContextBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an enabled property.
+    pub fn prop(mut self, name: impl Into<String>, ty: Ty) -> Self {
+        self.properties.push(Property {
+            name: name.into(),
+            ty,
+            disabled_reason: None,
+            options: Vec::new(),
+        });
+        self
+    }
+
+    /// Adds an enabled select, status, or multi-select property with its option names.
+    pub fn prop_with_options<S: Into<String>>(
+        mut self,
+        name: impl Into<String>,
+        ty: Ty,
+        options: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.properties.push(Property {
+            name: name.into(),
+            ty,
+            disabled_reason: None,
+            options: options.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Adds a property that editor completion shows as disabled, with `reason`.
+    pub fn disabled_prop(
+        mut self,
+        name: impl Into<String>,
+        ty: Ty,
+        reason: impl Into<String>,
+    ) -> Self {
+        self.properties.push(Property {
+            name: name.into(),
+            ty,
+            disabled_reason: Some(reason.into()),
+            options: Vec::new(),
+        });
+        self
+    }
+
+    /// Resolves properties missing from the eager ones on demand.
+    pub fn property_resolver(mut self, resolver: impl PropertyResolver + 'static) -> Self {
+        self.property_resolver = Some(SharedPropertyResolver::new(resolver));
+        self
+    }
+
+    /// How `prop("...")` names match; [`PropertyLookup::Exact`] by default.
+    pub fn property_lookup(mut self, lookup: PropertyLookup) -> Self {
+        self.property_lookup = lookup;
+        self
+    }
+
+    /// Caps formula size; uncapped by default.
+    pub fn limits(mut self, limits: FormulaLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Adds a function signature.
+    pub fn func(mut self, sig: FunctionSig) -> Self {
+        self.functions.to_mut().push(sig);
+        self
+    }
+
+    /// Adds every builtin function. Without other functions, the shared table is borrowed, as in
+    /// [`Context::with_builtins`].
+    pub fn with_builtins(mut self) -> Self {
+        if self.functions.is_empty() {
+            self.functions = Cow::Borrowed(builtins_functions());
+        } else {
+            self.functions
+                .to_mut()
+                .extend_from_slice(builtins_functions());
+        }
+        self
+    }
+
+    /// Removes the functions called any of `names`.
+    pub fn without(mut self, names: &[&str]) -> Self {
+        if self
+            .functions
+            .iter()
+            .any(|f| names.contains(&f.name.as_str()))
+        {
+            self.functions
+                .to_mut()
+                .retain(|f| !names.contains(&f.name.as_str()));
+        }
+        self
+    }
+
+    pub fn build(self) -> Context {
+        Context {
+            properties: self.properties.into(),
+            functions: self.functions.into(),
+            property_resolver: self.property_resolver,
+            property_lookup: self.property_lookup,
+            limits: self.limits,
+        }
+    }
+}
+
+impl Context {
+    /// Starts a [`ContextBuilder`].
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::new()
+    }
+}
@@ -0,0 +1,493 @@
+//! JSON Schema for the serialized [`Context`] and schema validation with field paths.
+//!
+//! [`context_json_schema`] mirrors the serde representation of [`Context`], [`Property`],
+//! [`FunctionSig`], [`ParamShape`], and [`Ty`]. `cargo run -p analyzer --bin export_context_schema`
+//! writes it to [`CONTEXT_SCHEMA_PATH`]; a test fails when that file is stale, and another checks
+//! that the serialized builtins validate against it.
+//!
+//! [`Context::validate_json`] interprets the schema itself (the subset it uses: `type`, `enum`,
+//! `properties`, `required`, `additionalProperties`, `items`, `minimum`, `oneOf`, and local
+//! `$ref`s), so the published schema and the validator cannot drift apart.
+//!
+//! Context JSON is exchanged between hosts built at different times, so it is read tolerantly:
+//! unknown fields are ignored, missing optional fields take their defaults, and the `version` key
+//! (written as [`CONTEXT_VERSION`]) selects compatibility shims instead of rejecting the input.
+//!
+//! [`Property`]: super::Property
+//! [`FunctionSig`]: super::FunctionSig
+//! [`ParamShape`]: super::ParamShape
+//! [`Ty`]: super::Ty
+
+use core::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value, json};
+
+use super::{Context, FormulaLimits, Functions, Properties, PropertyLookup};
+use crate::prelude::*;
+
+/// Relative to the `analyzer` crate root.
+pub const CONTEXT_SCHEMA_PATH: &str = "schema/context.schema.json";
+
+/// The context JSON format written by this build.
+///
+/// Input without a `version` key predates it and reads as version 1. Input from a newer version
+/// is read as far as this build understands it: fields added since are ignored.
+pub const CONTEXT_VERSION: u32 = 1;
+
+/// One schema violation in context JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextIssue {
+    /// Field path (e.g. `functions[2].params.head[0].type`); empty for the root.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ContextIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Why [`Context::validate_json`] rejected its input.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ContextError {
+    /// The input is not JSON; `line` and `column` are 1-based.
+    #[error("invalid JSON: {message}")]
+    InvalidJson {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// Every violation of [`context_json_schema`], in document order.
+    #[error("{}", join_issues(.0))]
+    Schema(Vec<ContextIssue>),
+    /// Schema-valid JSON that still does not deserialize.
+    #[error("{0}")]
+    Deserialize(String),
+}
+
+impl ContextError {
+    /// The failure as path-qualified issues; non-schema failures are a single root issue.
+    pub fn into_issues(self) -> Vec<ContextIssue> {
+        match self {
+            ContextError::Schema(issues) => issues,
+            other => vec![issue("", other.to_string())],
+        }
+    }
+}
+
+fn join_issues(issues: &[ContextIssue]) -> String {
+    issues
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[derive(Serialize)]
+struct ContextOut<'a> {
+    version: u32,
+    properties: &'a Properties,
+    functions: &'a Functions,
+    #[serde(skip_serializing_if = "is_exact")]
+    property_lookup: PropertyLookup,
+    #[serde(skip_serializing_if = "FormulaLimits::is_unlimited")]
+    limits: FormulaLimits,
+}
+
+#[derive(Deserialize)]
+struct ContextIn {
+    #[serde(default)]
+    version: Option<u32>,
+    #[serde(default)]
+    properties: Properties,
+    #[serde(default)]
+    functions: Functions,
+    #[serde(default)]
+    property_lookup: PropertyLookup,
+    #[serde(default)]
+    limits: FormulaLimits,
+}
+
+impl ContextIn {
+    /// Applies the compatibility shims for `version`. A format change adds an arm here that
+    /// rewrites input from before it.
+    fn upgrade<E: serde::de::Error>(self) -> Result<Context, E> {
+        match self.version {
+            Some(0) => Err(E::custom("context `version` starts at 1")),
+            // Every version so far has one shape, and fields from newer ones are already dropped.
+            None | Some(_) => Ok(Context {
+                properties: self.properties,
+                functions: self.functions,
+                property_resolver: None,
+                property_lookup: self.property_lookup,
+                limits: self.limits,
+            }),
+        }
+    }
+}
+
+impl Serialize for Context {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ContextOut {
+            version: CONTEXT_VERSION,
+            properties: &self.properties,
+            functions: &self.functions,
+            property_lookup: self.property_lookup,
+            limits: self.limits,
+        }
+        .serialize(serializer)
+    }
+}
+
+fn is_exact(lookup: &PropertyLookup) -> bool {
+    *lookup == PropertyLookup::Exact
+}
+
+impl<'de> Deserialize<'de> for Context {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ContextIn::deserialize(deserializer)?.upgrade()
+    }
+}
+
+/// The JSON Schema (draft 2020-12) for serialized [`Context`]s.
+pub fn context_json_schema() -> Value {
+    let param_list = json!({ "type": "array", "items": { "$ref": "#/$defs/ParamSig" } });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "notion-formula analyzer Context",
+        "description": "Properties and functions available to formulas. Unknown fields are ignored.",
+        "type": "object",
+        "properties": {
+            "properties": { "type": "array", "items": { "$ref": "#/$defs/Property" } },
+            "functions": { "type": "array", "items": { "$ref": "#/$defs/FunctionSig" } },
+            "property_lookup": { "type": "string", "enum": ["exact", "lenient"] },
+            "limits": { "$ref": "#/$defs/FormulaLimits" },
+            "version": { "type": "integer", "minimum": 1 },
+        },
+        "$defs": {
+            "Ty": {
+                "title": "a type name or { \"List\" | \"Union\" | \"Generic\": ... }",
+                "oneOf": [
+                    {
+                        "type": "string",
+                        "enum": ["Number", "String", "Boolean", "Date", "Null", "Unknown"],
+                    },
+                    {
+                        "type": "object",
+                        "properties": { "List": { "$ref": "#/$defs/Ty" } },
+                        "required": ["List"],
+                        "additionalProperties": false,
+                    },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "Union": { "type": "array", "items": { "$ref": "#/$defs/Ty" } },
+                        },
+                        "required": ["Union"],
+                        "additionalProperties": false,
+                    },
+                    {
+                        "type": "object",
+                        "properties": { "Generic": { "type": "integer", "minimum": 0 } },
+                        "required": ["Generic"],
+                        "additionalProperties": false,
+                    },
+                ],
+            },
+            "FormulaLimits": {
+                "description": "Caps on formula size; a missing or `null` cap is unlimited.",
+                "type": "object",
+                "properties": {
+                    "max_length": { "type": ["integer", "null"], "minimum": 0 },
+                    "max_depth": { "type": ["integer", "null"], "minimum": 0 },
+                },
+            },
+            "Property": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "type": { "$ref": "#/$defs/Ty" },
+                    "disabled_reason": { "type": ["string", "null"] },
+                    "options": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["name", "type"],
+            },
+            "ParamSig": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "type": { "$ref": "#/$defs/Ty" },
+                    "optional": { "type": "boolean" },
+                },
+                "required": ["name", "type"],
+            },
+            "ParamShape": {
+                "description": "`head`, then `repeat` cycled at least `repeat_min_groups` times (default 1), then `tail`.",
+                "type": "object",
+                "properties": {
+                    "head": param_list,
+                    "repeat": param_list,
+                    "tail": param_list,
+                    "repeat_min_groups": { "type": "integer", "minimum": 0 },
+                },
+            },
+            "GenericParam": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer", "minimum": 0 },
+                    "kind": { "type": "string", "enum": ["Plain", "Variant"] },
+                },
+                "required": ["id", "kind"],
+            },
+            "FunctionSig": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "params": { "$ref": "#/$defs/ParamShape" },
+                    "ret": { "$ref": "#/$defs/Ty" },
+                    "category": {
+                        "type": "string",
+                        "enum": ["General", "Text", "Number", "Date", "People", "List", "Special"],
+                    },
+                    "detail": { "type": "string" },
+                    "generics": { "type": "array", "items": { "$ref": "#/$defs/GenericParam" } },
+                },
+                "required": ["name", "params", "ret", "category"],
+            },
+        },
+    })
+}
+
+impl Context {
+    /// Parse context JSON, reporting every violation of [`context_json_schema`] with its path.
+    ///
+    /// Validation is structural: [`ParamShape::new`](super::ParamShape::new) invariants and builtin
+    /// signature rules are not checked.
+    pub fn validate_json(json: &str) -> Result<Context, ContextError> {
+        let value: Value = serde_json::from_str(json).map_err(|err| ContextError::InvalidJson {
+            message: err.to_string(),
+            line: err.line(),
+            column: err.column(),
+        })?;
+
+        let schema = context_json_schema();
+        let mut validator = Validator {
+            root: &schema,
+            issues: Vec::new(),
+        };
+        validator.check(&schema, &value, "");
+        if !validator.issues.is_empty() {
+            return Err(ContextError::Schema(validator.issues));
+        }
+
+        serde_json::from_value(value).map_err(|err| ContextError::Deserialize(err.to_string()))
+    }
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    issues: Vec<ContextIssue>,
+}
+
+impl Validator<'_> {
+    fn check(&mut self, schema: &Value, value: &Value, path: &str) {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let def = reference
+                .strip_prefix("#/$defs/")
+                .and_then(|name| self.root["$defs"].get(name))
+                .unwrap_or_else(|| panic!("unresolved schema $ref `{reference}`"));
+            return self.check(def, value, path);
+        }
+
+        if let Some(branches) = schema.get("oneOf").and_then(Value::as_array) {
+            match select_branch(branches, value) {
+                Some(branch) => self.check(branch, value, path),
+                None => {
+                    let title = schema["title"].as_str().unwrap_or("one of the variants");
+                    self.push(path, format!("expected {title}, found {}", describe(value)));
+                }
+            }
+            return;
+        }
+
+        if let Some(types) = schema.get("type")
+            && !type_matches(types, value)
+        {
+            self.push(
+                path,
+                format!("expected {}, found {}", type_names(types), kind(value)),
+            );
+            return;
+        }
+
+        if let Some(options) = schema.get("enum").and_then(Value::as_array)
+            && !options.contains(value)
+        {
+            let names: Vec<_> = options
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|s| format!("`{s}`"))
+                .collect();
+            self.push(
+                path,
+                format!(
+                    "expected one of {}, found {}",
+                    names.join(", "),
+                    describe(value)
+                ),
+            );
+            return;
+        }
+
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_i64)
+            && let Some(n) = value.as_i64()
+            && n < minimum
+        {
+            self.push(path, format!("expected integer >= {minimum}, found {n}"));
+        }
+
+        match value {
+            Value::Object(object) => self.check_object(schema, object, path),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        self.check(item_schema, item, &format!("{path}[{i}]"));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_object(&mut self, schema: &Value, object: &Map<String, Value>, path: &str) {
+        let empty = Map::new();
+        let fields = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+
+        for key in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !object.contains_key(key) {
+                self.push(&join(path, key), "missing field".into());
+            }
+        }
+
+        let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+        for (key, field) in object {
+            match fields.get(key) {
+                Some(field_schema) => self.check(field_schema, field, &join(path, key)),
+                None if closed => {
+                    let expected: Vec<_> = fields.keys().map(|k| format!("`{k}`")).collect();
+                    self.push(
+                        &join(path, key),
+                        format!("unknown field (expected one of {})", expected.join(", ")),
+                    );
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn push(&mut self, path: &str, message: String) {
+        self.issues.push(issue(path, message));
+    }
+}
+
+/// The `oneOf` branch to report errors against: the only branch whose `type` fits, or else the
+/// only one whose `required` fields are all present.
+fn select_branch<'s>(branches: &'s [Value], value: &Value) -> Option<&'s Value> {
+    let typed: Vec<_> = branches
+        .iter()
+        .filter(|b| b.get("type").is_none_or(|t| type_matches(t, value)))
+        .collect();
+    if let [only] = typed.as_slice() {
+        return Some(only);
+    }
+
+    let object = value.as_object()?;
+    let mut with_fields = typed.into_iter().filter(|b| {
+        b.get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .all(|key| object.contains_key(key))
+    });
+    match (with_fields.next(), with_fields.next()) {
+        (Some(branch), None) => Some(branch),
+        _ => None,
+    }
+}
+
+fn type_matches(types: &Value, value: &Value) -> bool {
+    match types {
+        Value::String(name) => match name.as_str() {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => false,
+        },
+        Value::Array(names) => names.iter().any(|name| type_matches(name, value)),
+        _ => true,
+    }
+}
+
+fn type_names(types: &Value) -> String {
+    match types {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.as_str().unwrap_or_default().to_string(),
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn issue(path: &str, message: String) -> ContextIssue {
+    ContextIssue {
+        path: path.to_string(),
+        message,
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{s}\""),
+        other => kind(other).to_string(),
+    }
+}
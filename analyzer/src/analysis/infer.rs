@@ -4,10 +4,12 @@
 //! diagnostics.
 
 use crate::ast::{Expr, ExprKind, UnOp};
+use crate::collections::Map;
+use crate::prelude::*;
 use crate::{LitKind, NodeId};
-use std::collections::HashMap;
+use core::fmt;
 
-use super::{normalize_union, Context, FunctionSig, GenericId, GenericParamKind, Ty};
+use super::{Context, FunctionSig, GenericId, GenericParamKind, Ty, TyContext, TyId, TyKind};
 
 /// Identifier for an expression node used as the key in [`TypeMap`].
 pub type ExprId = NodeId;
@@ -15,154 +17,180 @@ pub type ExprId = NodeId;
 /// Map from expression id to its inferred [`Ty`].
 ///
 /// [`infer_expr_with_map`] records types for all visited [`ExprId`]s, including intermediate nodes,
-/// so downstream consumers can look up types for subexpressions.
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// so downstream consumers can look up types for subexpressions. Types are interned in the map's
+/// own [`TyContext`], so repeated types are stored once.
+#[derive(Default, Clone)]
 pub struct TypeMap {
-    inner: HashMap<ExprId, Ty>,
+    tys: TyContext,
+    inner: Map<ExprId, TyId>,
 }
 
 impl TypeMap {
     /// Record the inferred type for `id`.
     pub fn insert(&mut self, id: ExprId, ty: Ty) {
+        let ty = self.tys.intern(&ty);
         self.inner.insert(id, ty);
     }
 
     /// Look up the inferred type for `id`, if it was visited.
     pub fn get(&self, id: ExprId) -> Option<&Ty> {
-        self.inner.get(&id)
+        self.get_id(id).map(|ty| self.tys.ty(ty))
+    }
+
+    /// Like [`TypeMap::get`], but returns the interned handle.
+    pub fn get_id(&self, id: ExprId) -> Option<TyId> {
+        self.inner.get(&id).copied()
+    }
+
+    /// The interner behind the map's [`TyId`]s.
+    pub fn tys(&self) -> &TyContext {
+        &self.tys
+    }
+}
+
+/// Maps compare (and print) the types they record, not their interner state.
+impl PartialEq for TypeMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.len() == other.inner.len()
+            && self.inner.keys().all(|&id| self.get(id) == other.get(id))
     }
 }
 
-pub(crate) type Subst = HashMap<GenericId, Ty>;
-type GenericRegistry = HashMap<GenericId, GenericParamKind>;
+impl Eq for TypeMap {}
 
-fn registry_for(sig: &FunctionSig) -> GenericRegistry {
-    sig.generics.iter().map(|g| (g.id, g.kind)).collect()
+impl fmt::Debug for TypeMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.inner.iter().map(|(id, &ty)| (id, self.tys.ty(ty))))
+            .finish()
+    }
+}
+
+/// Generic bindings for one call of a [`FunctionSig`].
+///
+/// [`Instantiation::unify`] binds the signature's generics against actual argument types, then
+/// [`Instantiation::apply`] substitutes them into a template such as the return type. Unbound
+/// generics apply as [`Ty::Unknown`].
+#[derive(Debug, Clone)]
+pub struct Instantiation<'a> {
+    sig: &'a FunctionSig,
+    subst: Vec<(GenericId, TyId)>,
 }
 
-fn bind_generic(subst: &mut Subst, registry: &GenericRegistry, id: GenericId, actual: &Ty) {
-    let kind = registry
-        .get(&id)
-        .copied()
-        .unwrap_or(GenericParamKind::Plain);
-
-    fn contains_unknown(ty: &Ty) -> bool {
-        match ty {
-            Ty::Unknown => true,
-            Ty::Union(members) => members.iter().any(contains_unknown),
-            _ => false,
+impl<'a> Instantiation<'a> {
+    pub fn new(sig: &'a FunctionSig) -> Self {
+        Self {
+            sig,
+            subst: Vec::new(),
         }
     }
 
-    match kind {
-        GenericParamKind::Plain => {
-            if matches!(actual, Ty::Unknown) {
-                return;
-            }
+    /// The type bound to `id` so far.
+    pub fn binding(&self, id: GenericId) -> Option<TyId> {
+        self.subst
+            .iter()
+            .find_map(|&(bound, ty)| (bound == id).then_some(ty))
+    }
 
-            let to_add = vec![actual.clone()];
-            match subst.get(&id).cloned() {
-                None => {
-                    subst.insert(id, normalize_union(to_add));
+    /// Bind the generics in `expected` (a parameter type of the signature) against `actual`.
+    pub fn unify(&mut self, tys: &mut TyContext, expected: &Ty, actual: TyId) {
+        match expected {
+            Ty::Generic(id) => self.bind(tys, *id, actual),
+            Ty::List(exp_inner) => {
+                if let TyKind::List(act_inner) = *tys.kind(actual) {
+                    self.unify(tys, exp_inner, act_inner);
                 }
-                Some(prev) => {
-                    // Plain generics: permissive accumulation on conflicts.
-                    subst.insert(id, normalize_union(std::iter::once(prev).chain(to_add)));
+            }
+            Ty::Union(branches) => {
+                for branch in branches {
+                    self.unify(tys, branch, actual);
                 }
             }
+            _ => {}
         }
-        GenericParamKind::Variant => {
-            if contains_unknown(actual) {
-                subst.insert(id, Ty::Unknown);
-                return;
-            }
+    }
 
-            // Once a variant generic sees an Unknown, the result stays Unknown.
-            if subst.get(&id).is_some_and(|t| matches!(t, Ty::Unknown)) {
-                return;
+    /// Substitute the bound generics into `template`.
+    pub fn apply(&self, tys: &mut TyContext, template: &Ty) -> TyId {
+        match template {
+            Ty::Generic(id) => self.binding(*id).unwrap_or(TyId::UNKNOWN),
+            Ty::List(inner) => {
+                let inner = self.apply(tys, inner);
+                tys.list(inner)
             }
+            Ty::Union(members) => {
+                let members: Vec<TyId> = members.iter().map(|m| self.apply(tys, m)).collect();
+                tys.union(members)
+            }
+            other => tys.intern(other),
+        }
+    }
 
-            let mut to_add: Vec<Ty> = Vec::new();
-            match actual {
-                Ty::Union(members) => {
-                    to_add.extend(members.iter().cloned());
+    fn bind(&mut self, tys: &mut TyContext, id: GenericId, actual: TyId) {
+        let kind = self
+            .sig
+            .generics
+            .iter()
+            .find(|g| g.id == id)
+            .map_or(GenericParamKind::Plain, |g| g.kind);
+        let prev = self.binding(id);
+
+        let bound = match kind {
+            GenericParamKind::Plain => {
+                if actual == TyId::UNKNOWN {
+                    return;
                 }
-                other => {
-                    to_add.push(other.clone());
+                // Plain generics: permissive accumulation on conflicts.
+                match prev {
+                    None => tys.union([actual]),
+                    Some(prev) => tys.union([prev, actual]),
                 }
             }
-
-            if to_add.is_empty() {
-                return;
-            }
-
-            match subst.get(&id).cloned() {
-                None => {
-                    subst.insert(id, normalize_union(to_add));
-                }
-                Some(prev) => {
+            GenericParamKind::Variant => {
+                if tys.contains_unknown(actual) {
+                    TyId::UNKNOWN
+                } else if prev == Some(TyId::UNKNOWN) {
+                    // Once a variant generic sees an Unknown, the result stays Unknown.
+                    return;
+                } else if matches!(tys.kind(actual), TyKind::Union(members) if members.is_empty()) {
+                    return;
+                } else {
                     // Variant generics: union-accumulate across all bindings.
-                    subst.insert(id, normalize_union(std::iter::once(prev).chain(to_add)));
+                    match prev {
+                        None => tys.union([actual]),
+                        Some(prev) => tys.union([prev, actual]),
+                    }
                 }
             }
-        }
-    }
-}
+        };
 
-pub(crate) fn unify(subst: &mut Subst, registry: &GenericRegistry, expected: &Ty, actual: &Ty) {
-    match expected {
-        Ty::Generic(id) => bind_generic(subst, registry, *id, actual),
-        Ty::List(exp_inner) => {
-            if let Ty::List(act_inner) = actual {
-                unify(subst, registry, exp_inner, act_inner);
-            }
+        match self.subst.iter_mut().find(|(bound, _)| *bound == id) {
+            Some((_, ty)) => *ty = bound,
+            None => self.subst.push((id, bound)),
         }
-        Ty::Union(branches) => {
-            for branch in branches {
-                unify(subst, registry, branch, actual);
-            }
-        }
-        _ => {}
-    }
-}
-
-pub(crate) fn apply(subst: &Subst, ty_template: &Ty) -> Ty {
-    match ty_template {
-        Ty::Generic(id) => subst.get(id).cloned().unwrap_or(Ty::Unknown),
-        Ty::List(inner) => Ty::List(Box::new(apply(subst, inner))),
-        Ty::Union(members) => normalize_union(members.iter().map(|m| apply(subst, m))),
-        other => other.clone(),
     }
 }
 
-fn unify_call_args(sig: &FunctionSig, arg_tys: &[Ty], subst: &mut Subst) {
-    let registry = registry_for(sig);
+fn unify_call_args(inst: &mut Instantiation, tys: &mut TyContext, arg_tys: &[TyId]) {
+    let sig = inst.sig;
 
     if sig.params.repeat.is_empty() {
         let params = sig.params.head.iter().chain(sig.params.tail.iter());
-        for (param, actual) in params.zip(arg_tys.iter()) {
-            unify(subst, &registry, &param.ty, actual);
+        for (param, &actual) in params.zip(arg_tys.iter()) {
+            inst.unify(tys, &param.ty, actual);
         }
         return;
     }
 
-    let head_len = sig.params.head.len();
-    let tail_used = super::resolve_repeat_tail_used(&sig.params, arg_tys.len())
+    let tail_used = sig
+        .params
+        .tail_used(arg_tys.len())
         .unwrap_or(sig.params.tail.len());
     let tail_start = arg_tys.len().saturating_sub(tail_used);
 
-    for (idx, actual) in arg_tys.iter().enumerate() {
-        let expected = if idx < head_len {
-            sig.params.head.get(idx)
-        } else if idx >= tail_start {
-            sig.params.tail.get(idx - tail_start)
-        } else {
-            let r_idx = (idx - head_len) % sig.params.repeat.len();
-            sig.params.repeat.get(r_idx)
-        };
-
-        if let Some(param) = expected {
-            unify(subst, &registry, &param.ty, actual);
+    for (idx, &actual) in arg_tys.iter().enumerate() {
+        if let Some(param) = sig.params.param_at(idx, tail_start) {
+            inst.unify(tys, &param.ty, actual);
         }
     }
 }
@@ -175,86 +203,73 @@ fn unify_call_args(sig: &FunctionSig, arg_tys: &[Ty], subst: &mut Subst) {
 ///   back to [`Ty::Unknown`],
 /// - types are recorded in `map` after inferring each expression node.
 pub fn infer_expr_with_map(expr: &Expr, ctx: &Context, map: &mut TypeMap) -> Ty {
-    infer_expr_inner(expr, ctx, map)
+    let ty = infer_expr_inner(expr, ctx, map);
+    map.tys.ty(ty).clone()
 }
 
-fn infer_expr_inner(expr: &Expr, ctx: &Context, map: &mut TypeMap) -> Ty {
+fn infer_expr_inner(expr: &Expr, ctx: &Context, map: &mut TypeMap) -> TyId {
     let ty = match &expr.kind {
         ExprKind::Lit(lit) => match lit.kind {
-            LitKind::Number => Ty::Number,
-            LitKind::String => Ty::String,
-            LitKind::Bool => Ty::Boolean,
+            LitKind::Number => TyId::NUMBER,
+            LitKind::String => TyId::STRING,
+            LitKind::Bool => TyId::BOOLEAN,
         },
-        ExprKind::Ident(_) => Ty::Unknown,
-        ExprKind::Group { inner } => infer_expr_with_map(inner, ctx, map),
+        ExprKind::Ident(_) => TyId::UNKNOWN,
+        ExprKind::Group { inner } => infer_expr_inner(inner, ctx, map),
         ExprKind::List { items } => {
-            fn contains_unknown(ty: &Ty) -> bool {
-                match ty {
-                    Ty::Unknown => true,
-                    Ty::Union(members) => members.iter().any(contains_unknown),
-                    _ => false,
-                }
+            let mut item_tys = Vec::with_capacity(items.len());
+            for item in items {
+                item_tys.push(infer_expr_inner(item, ctx, map));
             }
 
-            if items.is_empty() {
-                Ty::List(Box::new(Ty::Unknown))
-            } else {
-                let mut item_tys = Vec::with_capacity(items.len());
-                for item in items {
-                    item_tys.push(infer_expr_with_map(item, ctx, map));
-                }
-
-                if item_tys.iter().any(contains_unknown) {
-                    Ty::List(Box::new(Ty::Unknown))
+            let inner =
+                if items.is_empty() || item_tys.iter().any(|&ty| map.tys.contains_unknown(ty)) {
+                    TyId::UNKNOWN
                 } else {
-                    Ty::List(Box::new(normalize_union(item_tys)))
-                }
-            }
+                    map.tys.union(item_tys)
+                };
+            map.tys.list(inner)
         }
         ExprKind::Unary { op, expr } => {
-            let inner_ty = infer_expr_with_map(expr, ctx, map);
+            let inner_ty = infer_expr_inner(expr, ctx, map);
             match op {
-                UnOp::Not(_) => match inner_ty {
-                    Ty::Boolean => Ty::Boolean,
-                    _ => Ty::Unknown,
-                },
-                UnOp::Neg => match inner_ty {
-                    Ty::Number => Ty::Number,
-                    _ => Ty::Unknown,
-                },
+                UnOp::Not(_) if inner_ty == TyId::BOOLEAN => TyId::BOOLEAN,
+                UnOp::Neg if inner_ty == TyId::NUMBER => TyId::NUMBER,
+                _ => TyId::UNKNOWN,
             }
         }
         ExprKind::Binary { op, left, right } => {
-            let left_ty = infer_expr_with_map(left, ctx, map);
-            let right_ty = infer_expr_with_map(right, ctx, map);
+            let left_ty = infer_expr_inner(left, ctx, map);
+            let right_ty = infer_expr_inner(right, ctx, map);
             use crate::ast::BinOpKind::*;
             match op.node {
                 Plus | Minus | Star | Slash | Percent | Caret => {
-                    if left_ty == Ty::Number && right_ty == Ty::Number {
-                        Ty::Number
+                    if left_ty == TyId::NUMBER && right_ty == TyId::NUMBER {
+                        TyId::NUMBER
                     } else {
-                        Ty::Unknown
+                        TyId::UNKNOWN
                     }
                 }
                 AndAnd | OrOr => {
-                    if left_ty == Ty::Boolean && right_ty == Ty::Boolean {
-                        Ty::Boolean
+                    if left_ty == TyId::BOOLEAN && right_ty == TyId::BOOLEAN {
+                        TyId::BOOLEAN
                     } else {
-                        Ty::Unknown
+                        TyId::UNKNOWN
                     }
                 }
                 Lt | Le | Ge | Gt => {
-                    if left_ty != Ty::Unknown && right_ty != Ty::Unknown {
-                        Ty::Boolean
+                    if left_ty != TyId::UNKNOWN && right_ty != TyId::UNKNOWN {
+                        TyId::BOOLEAN
                     } else {
-                        Ty::Unknown
+                        TyId::UNKNOWN
                     }
                 }
                 EqEq | Ne => {
-                    if left_ty == right_ty && left_ty != Ty::Unknown {
-                        Ty::Boolean
+                    // Interned: equal ids are equal types.
+                    if left_ty == right_ty && left_ty != TyId::UNKNOWN {
+                        TyId::BOOLEAN
                     } else {
-                        Ty::Unknown
+                        TyId::UNKNOWN
                     }
                 }
             }
@@ -264,16 +279,16 @@ fn infer_expr_inner(expr: &Expr, ctx: &Context, map: &mut TypeMap) -> Ty {
             then,
             otherwise,
         } => {
-            let _ = infer_expr_with_map(cond, ctx, map);
-            let then_ty = infer_expr_with_map(then, ctx, map);
-            let otherwise_ty = infer_expr_with_map(otherwise, ctx, map);
-            join_types(then_ty, otherwise_ty)
+            let _ = infer_expr_inner(cond, ctx, map);
+            let then_ty = infer_expr_inner(then, ctx, map);
+            let otherwise_ty = infer_expr_inner(otherwise, ctx, map);
+            join_types(&mut map.tys, then_ty, otherwise_ty)
         }
         ExprKind::Call { callee, args } => match callee.text.as_str() {
             "prop" => infer_prop(args, ctx, map),
             name => {
-                let sig = ctx.functions.iter().find(|f| f.name == name);
-                infer_call(name, sig, args, ctx, map)
+                let sig = ctx.function(name);
+                infer_call(sig, args.iter(), ctx, map)
             }
         },
         ExprKind::MemberCall {
@@ -281,86 +296,73 @@ fn infer_expr_inner(expr: &Expr, ctx: &Context, map: &mut TypeMap) -> Ty {
             method,
             args,
         } => {
-            let _ = infer_expr_with_map(receiver, ctx, map);
-            for arg in args {
-                let _ = infer_expr_with_map(arg, ctx, map);
-            }
-
             // Postfix form: `receiver.fn(arg1, ...)` corresponds to `fn(receiver, arg1, ...)`.
-            if !super::postfix_capable_builtin_names().contains(method.text.as_str()) {
-                Ty::Unknown
+            let sig = if super::postfix_capable_builtin_names().contains(method.text.as_str()) {
+                ctx.function(method.text.as_str())
+                    .filter(|sig| super::is_postfix_capable(sig))
             } else {
-                let sig = ctx.functions.iter().find(|f| f.name == method.text);
-                let sig = sig.filter(|sig| super::is_postfix_capable(sig));
-                if sig.is_none() {
-                    Ty::Unknown
-                } else {
-                    let mut all_args: Vec<Expr> = Vec::with_capacity(1 + args.len());
-                    all_args.push((**receiver).clone());
-                    all_args.extend(args.iter().cloned());
-                    infer_call(method.text.as_str(), sig, &all_args, ctx, map)
-                }
-            }
+                None
+            };
+            // Without a signature, `infer_call` still records the receiver and argument types.
+            infer_call(sig, core::iter::once(&**receiver).chain(args), ctx, map)
         }
-        ExprKind::Error => Ty::Unknown,
+        ExprKind::Error => TyId::UNKNOWN,
     };
 
-    map.insert(expr.id, ty.clone());
+    map.inner.insert(expr.id, ty);
     ty
 }
 
-fn infer_prop(args: &[Expr], ctx: &Context, map: &mut TypeMap) -> Ty {
+fn infer_prop(args: &[Expr], ctx: &Context, map: &mut TypeMap) -> TyId {
     for arg in args {
-        let _ = infer_expr_with_map(arg, ctx, map);
+        let _ = infer_expr_inner(arg, ctx, map);
     }
 
-    if args.len() != 1 {
-        return Ty::Unknown;
-    }
-    let arg = &args[0];
+    let [arg] = args else {
+        return TyId::UNKNOWN;
+    };
     let name = match &arg.kind {
         ExprKind::Lit(lit) if lit.kind == LitKind::String => lit.symbol.text.as_str(),
-        _ => return Ty::Unknown,
+        _ => return TyId::UNKNOWN,
     };
-    ctx.lookup(name).unwrap_or(Ty::Unknown)
+    match ctx.property(name) {
+        Some(prop) => map.tys.intern(&prop.ty),
+        None => TyId::UNKNOWN,
+    }
 }
 
-fn infer_call(
-    _name: &str,
+fn infer_call<'e>(
     sig: Option<&FunctionSig>,
-    args: &[Expr],
+    args: impl Iterator<Item = &'e Expr>,
     ctx: &Context,
     map: &mut TypeMap,
-) -> Ty {
+) -> TyId {
     let Some(sig) = sig else {
         for arg in args {
-            let _ = infer_expr_with_map(arg, ctx, map);
+            let _ = infer_expr_inner(arg, ctx, map);
         }
-        return Ty::Unknown;
+        return TyId::UNKNOWN;
     };
 
-    let mut arg_tys = Vec::with_capacity(args.len());
-    for arg in args {
-        arg_tys.push(infer_expr_with_map(arg, ctx, map));
-    }
+    let arg_tys: Vec<TyId> = args.map(|arg| infer_expr_inner(arg, ctx, map)).collect();
 
     // If the signature has a custom resolver, use it instead of standard
     // generic unification.
     if let Some(resolver) = sig.resolver {
+        let arg_tys: Vec<Ty> = arg_tys.iter().map(|&ty| map.tys.ty(ty).clone()).collect();
         let resolved = resolver(sig, &arg_tys);
-        return resolved.ret;
+        return map.tys.intern(&resolved.ret);
     }
 
-    let mut subst = Subst::new();
-    unify_call_args(sig, arg_tys.as_slice(), &mut subst);
-
-    apply(&subst, &sig.ret)
+    let mut inst = Instantiation::new(sig);
+    unify_call_args(&mut inst, &mut map.tys, &arg_tys);
+    inst.apply(&mut map.tys, &sig.ret)
 }
 
-fn join_types(a: Ty, b: Ty) -> Ty {
-    if a == Ty::Unknown || b == Ty::Unknown {
-        Ty::Unknown
+fn join_types(tys: &mut TyContext, a: TyId, b: TyId) -> TyId {
+    if a == TyId::UNKNOWN || b == TyId::UNKNOWN {
+        TyId::UNKNOWN
     } else {
-        normalize_union([a, b])
+        tys.union([a, b])
     }
 }
@@ -0,0 +1,130 @@
+//! Size caps that Notion enforces on formulas, checked before the host saves one.
+
+use crate::ast::{Expr, ExprKind};
+use crate::diagnostics::{Diagnostic, DiagnosticCode, DiagnosticKind};
+use crate::lexer::Span;
+use crate::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Caps on formula size, as [`Context::limits`](super::Context::limits); `None` is uncapped.
+///
+/// Notion does not publish its limits, so none are set by default. Exceeding a cap is an error
+/// naming the current and allowed numbers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FormulaLimits {
+    /// Source length in characters (Unicode scalar values), comments and whitespace included.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_length: Option<usize>,
+    /// Nesting of parenthesized groups, call arguments, and lists: `f(g(1))` is 2 deep, and so
+    /// is `prop("A").map((current))`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_depth: Option<usize>,
+}
+
+impl FormulaLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_length.is_none() && self.max_depth.is_none()
+    }
+}
+
+/// Diagnostics for each cap in `limits` that `text` (parsed as `root`) exceeds.
+pub fn limit_diagnostics(text: &str, root: &Expr, limits: &FormulaLimits) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    if let Some(max) = limits.max_length
+        && let Some((overflow, _)) = text.char_indices().nth(max)
+    {
+        let length = max + text[overflow..].chars().count();
+        diags.push(limit_error(
+            DiagnosticCode::FormulaTooLong,
+            format!("Formula is {length} characters long; Notion allows at most {max}"),
+            Span {
+                start: overflow as u32,
+                end: text.len() as u32,
+            },
+        ));
+    }
+    if let Some(max) = limits.max_depth {
+        let mut depth = Depth {
+            max,
+            deepest: 0,
+            first_over: None,
+        };
+        depth.visit(root, 0);
+        if let Some(span) = depth.first_over {
+            diags.push(limit_error(
+                DiagnosticCode::NestingTooDeep,
+                format!(
+                    "Formula nests {} levels deep; Notion allows at most {max}",
+                    depth.deepest
+                ),
+                span,
+            ));
+        }
+    }
+    diags
+}
+
+fn limit_error(code: DiagnosticCode, message: String, span: Span) -> Diagnostic {
+    Diagnostic {
+        kind: DiagnosticKind::Error,
+        code,
+        message,
+        span,
+        labels: vec![],
+        notes: vec![],
+        actions: vec![],
+    }
+}
+
+struct Depth {
+    max: usize,
+    deepest: usize,
+    /// The first expression (in source order) that opens a level past `max`.
+    first_over: Option<Span>,
+}
+
+impl Depth {
+    /// `level` counts the delimiters enclosing `expr`.
+    fn visit(&mut self, expr: &Expr, level: usize) {
+        let inner = level + 1;
+        match &expr.kind {
+            ExprKind::Ident(_) | ExprKind::Lit(_) | ExprKind::Error => {}
+            ExprKind::Group { inner: child } => {
+                self.open(expr, inner);
+                self.visit(child, inner);
+            }
+            ExprKind::List { items: args } | ExprKind::Call { args, .. } => {
+                self.open(expr, inner);
+                args.iter().for_each(|arg| self.visit(arg, inner));
+            }
+            ExprKind::MemberCall { receiver, args, .. } => {
+                self.visit(receiver, level);
+                self.open(expr, inner);
+                args.iter().for_each(|arg| self.visit(arg, inner));
+            }
+            ExprKind::Unary { expr, .. } => self.visit(expr, level),
+            ExprKind::Binary { left, right, .. } => {
+                self.visit(left, level);
+                self.visit(right, level);
+            }
+            ExprKind::Ternary {
+                cond,
+                then,
+                otherwise,
+            } => {
+                self.visit(cond, level);
+                self.visit(then, level);
+                self.visit(otherwise, level);
+            }
+        }
+    }
+
+    fn open(&mut self, expr: &Expr, level: usize) {
+        self.deepest = self.deepest.max(level);
+        if level > self.max && self.first_over.is_none() {
+            self.first_over = Some(expr.span);
+        }
+    }
+}
@@ -4,28 +4,58 @@
 //! [`FunctionSig`]s plus the special-cased `prop("Name")` form.
 
 use crate::ast::{Expr, ExprKind};
-use crate::diagnostics::{Diagnostic, DiagnosticCode, DiagnosticKind};
-use crate::{LitKind, Span};
+use crate::cancel::{CancellationToken, Cancelled};
+use crate::collections::Set;
+use crate::diagnostics::{CodeAction, Diagnostic, DiagnosticCode, DiagnosticKind};
+use crate::prelude::*;
+use crate::sync::LazyLock;
+use crate::{LitKind, Span, TextEdit};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::fmt;
-use std::sync::LazyLock;
 
 mod builtins;
-pub use builtins::builtins_functions;
+pub use builtins::{builtins_context, builtins_functions};
+mod context_builder;
+pub use context_builder::ContextBuilder;
+#[cfg(feature = "serde")]
+mod context_schema;
+#[cfg(feature = "serde")]
+pub use context_schema::{
+    CONTEXT_SCHEMA_PATH, CONTEXT_VERSION, ContextError, ContextIssue, context_json_schema,
+};
 mod signature;
-pub use signature::{FunctionSig, GenericParam, GenericParamKind, ParamShape, ParamSig, SigResolver};
+pub use signature::{
+    FunctionSig, GenericParam, GenericParamKind, ParamShape, ParamSig, SigResolver,
+    SignatureError,
+};
+mod signature_builder;
+pub use signature_builder::FunctionSigBuilder;
 mod infer;
+mod notion_schema;
+pub use notion_schema::{
+    NotionFormulaSchema, NotionOptionSchema, NotionOptionsSchema, NotionPropertySchema,
+    NotionRollupSchema, notion_property_ty, notion_schema_properties,
+};
+mod name_index;
+pub use name_index::{Functions, NameIndexed, Named, Properties};
+mod limits;
+pub use limits::{FormulaLimits, limit_diagnostics};
 mod param_shape;
-pub use infer::{ExprId, TypeMap, infer_expr_with_map};
+mod property_resolver;
+pub use property_resolver::{PropertyPage, PropertyResolver, SharedPropertyResolver};
+pub use infer::{ExprId, Instantiation, TypeMap, infer_expr_with_map};
+mod ty_context;
+pub use ty_context::{TyContext, TyId, TyKind};
 mod type_hints;
 pub use type_hints::normalize_union;
+mod ty_display;
+pub use ty_display::TyDisplay;
 
-static POSTFIX_CAPABLE_BUILTIN_NAMES: LazyLock<HashSet<String>> = LazyLock::new(|| {
+static POSTFIX_CAPABLE_BUILTIN_NAMES: LazyLock<Set<String>> = LazyLock::new(|| {
     builtins_functions()
-        .into_iter()
-        .filter(is_postfix_capable)
-        .map(|sig| sig.name)
+        .iter()
+        .filter(|sig| is_postfix_capable(sig))
+        .map(|sig| sig.name.clone())
         .collect()
 });
 
@@ -35,7 +65,7 @@ static POSTFIX_CAPABLE_BUILTIN_NAMES: LazyLock<HashSet<String>> = LazyLock::new(
 /// `name(receiver, args...)` when:
 /// - `name` resolves to a builtin [`FunctionSig`], and
 /// - [`is_postfix_capable`] is true for that signature.
-pub fn postfix_capable_builtin_names() -> &'static HashSet<String> {
+pub fn postfix_capable_builtin_names() -> &'static Set<String> {
     &POSTFIX_CAPABLE_BUILTIN_NAMES
 }
 
@@ -65,19 +95,22 @@ pub fn is_postfix_capable(sig: &FunctionSig) -> bool {
 /// Identifier for a generic type parameter in [`Ty::Generic`].
 ///
 /// Currently the UI-facing generic names are derived from this numeric id (e.g. `T0`, `T1`, ...).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-#[serde(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct GenericId(pub u32);
 
 /// Formula type used by inference, validation, and editor tooling.
 ///
 /// - [`Ty::Unknown`] represents “unknown / could not be inferred”.
 /// - [`Ty::Generic`] represents a type parameter (see [`GenericId`]) and is instantiated via generic
-///   unification (see `infer::instantiate_sig`).
+///   unification (see [`Instantiation`]).
 ///
-/// See [`ty_accepts`] for validation acceptance rules.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+/// See [`ty_accepts`] for validation acceptance rules. `Display` renders user-facing names such as
+/// `number[]` and `string | null`; see [`TyDisplay`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "PascalCase"))]
 pub enum Ty {
     Number,
     String,
@@ -90,66 +123,10 @@ pub enum Ty {
     Union(Vec<Ty>),
 }
 
-impl Ty {
-    fn precedence(&self) -> u8 {
-        match self {
-            Ty::Union(_) => 1,
-            Ty::List(_) => 2,
-            Ty::Number
-            | Ty::String
-            | Ty::Boolean
-            | Ty::Date
-            | Ty::Null
-            | Ty::Unknown
-            | Ty::Generic(_) => 3,
-        }
-    }
-
-    fn fmt_with_prec(&self, f: &mut fmt::Formatter<'_>, parent_prec: u8) -> fmt::Result {
-        let my_prec = self.precedence();
-        let needs_parens = my_prec < parent_prec;
-        if needs_parens {
-            f.write_str("(")?;
-        }
-
-        match self {
-            Ty::Number => f.write_str("number")?,
-            Ty::String => f.write_str("string")?,
-            Ty::Boolean => f.write_str("boolean")?,
-            Ty::Date => f.write_str("date")?,
-            Ty::Null => f.write_str("null")?,
-            Ty::Unknown => f.write_str("unknown")?,
-            Ty::Generic(id) => write!(f, "T{}", id.0)?,
-            Ty::List(inner) => {
-                inner.fmt_with_prec(f, my_prec)?;
-                f.write_str("[]")?;
-            }
-            Ty::Union(members) => {
-                for (idx, m) in members.iter().enumerate() {
-                    if idx > 0 {
-                        f.write_str(" | ")?;
-                    }
-                    m.fmt_with_prec(f, my_prec)?;
-                }
-            }
-        };
-
-        if needs_parens {
-            f.write_str(")")?;
-        }
-        Ok(())
-    }
-}
-
-impl fmt::Display for Ty {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.fmt_with_prec(f, 0)
-    }
-}
-
 /// Category bucket for builtin functions (used for editor grouping).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "PascalCase"))]
 pub enum FunctionCategory {
     General,
     Text,
@@ -191,36 +168,81 @@ pub fn ty_accepts(expected: &Ty, actual: &Ty) -> bool {
 }
 
 /// A property available to `prop("Name")` calls and to editor completion.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Property {
     /// Canonical property name as referenced by `prop("...")`.
     pub name: String,
-    #[serde(rename = "type")]
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     /// Declared property type.
     pub ty: Ty,
     /// If set, editor completions may surface this item as disabled and provide this reason.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub disabled_reason: Option<String>,
+    /// Declared option names of a select, status, or multi-select property, in display order;
+    /// completed inside string literals compared with the property. Empty when not known.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub options: Vec<String>,
 }
 
 /// Semantic environment used for validation and editor features.
 ///
 /// - `properties` are supplied externally (e.g. by the WASM layer via JSON) and used by `prop(...)`.
 /// - `functions` are sourced from Rust builtins at the WASM boundary (JS cannot supply them).
+///   [`Context::with_builtins`] borrows the shared builtin table instead of copying it.
+/// - `property_resolver` supplies properties on demand when listing them all upfront is too
+///   costly; eager `properties` win over resolved ones of the same name.
+/// - `property_lookup` selects how `prop("...")` names match property names.
+/// - `limits` caps formula size as Notion does; entry points that read source text check them.
+///
+/// The JSON form is described by [`context_json_schema`]; see [`Context::validate_json`]. It
+/// carries a `version` key ([`CONTEXT_VERSION`]) and tolerates fields it does not know.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Context {
-    pub properties: Vec<Property>,
-    pub functions: Vec<FunctionSig>,
+    pub properties: Properties,
+    pub functions: Functions,
+    /// Not part of the JSON form.
+    pub property_resolver: Option<SharedPropertyResolver>,
+    pub property_lookup: PropertyLookup,
+    pub limits: FormulaLimits,
+}
+
+/// How `prop("...")` names match [`Property::name`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PropertyLookup {
+    /// Names must match exactly.
+    #[default]
+    Exact,
+    /// Without an exact match, names also match when equal after trimming surrounding
+    /// whitespace and ignoring case. Such references type-check against the matched property and
+    /// get a [`DiagnosticCode::InexactPropertyName`] warning with a quick fix to its name.
+    Lenient,
 }
 
 impl Context {
-    /// Look up a property type by name.
+    /// A context with `properties` and the builtin functions.
+    pub fn with_builtins(properties: Vec<Property>) -> Self {
+        Self {
+            properties: properties.into(),
+            functions: builtins_functions().into(),
+            property_resolver: None,
+            property_lookup: PropertyLookup::Exact,
+            limits: FormulaLimits::default(),
+        }
+    }
+
+    /// Look up a property type by name, falling back to the resolver.
     ///
     /// Currently this is used for `prop("Name")` resolution.
     pub fn lookup(&self, name: &str) -> Option<Ty> {
-        self.properties
-            .iter()
-            .find(|p| p.name == name)
-            .map(|p| p.ty.clone())
+        self.property(name).map(|p| p.ty.clone())
+    }
+
+    /// Look up a function signature by name.
+    pub fn function(&self, name: &str) -> Option<&FunctionSig> {
+        self.functions.by_name(name)
     }
 }
 
@@ -235,17 +257,40 @@ impl Context {
 /// - Postfix member calls may be treated as calls when the callee is a postfix-capable builtin (see
 ///   [`is_postfix_capable`]).
 pub fn analyze_expr(expr: &Expr, ctx: &Context) -> (Ty, Vec<Diagnostic>) {
+    let mut map = TypeMap::default();
+    analyze_expr_with_map(expr, ctx, &mut map)
+}
+
+/// Like [`analyze_expr`], but checks `cancel` between inference and validation.
+pub fn analyze_expr_cancellable(
+    expr: &Expr,
+    ctx: &Context,
+    cancel: &CancellationToken,
+) -> Result<(Ty, Vec<Diagnostic>), Cancelled> {
+    cancel.check()?;
     let mut map = TypeMap::default();
     let ty = infer_expr_with_map(expr, ctx, &mut map);
+    cancel.check()?;
 
     let mut diags = Vec::new();
     validate_expr(expr, ctx, &map, &mut diags);
+    cancel.check()?;
 
-    (ty, diags)
+    Ok((ty, diags))
 }
 
-fn lookup_function<'a>(ctx: &'a Context, name: &str) -> Option<&'a FunctionSig> {
-    ctx.functions.iter().find(|f| f.name == name)
+/// Like [`analyze_expr`], but also records every inferred node type in `map`.
+pub fn analyze_expr_with_map(
+    expr: &Expr,
+    ctx: &Context,
+    map: &mut TypeMap,
+) -> (Ty, Vec<Diagnostic>) {
+    let ty = infer_expr_with_map(expr, ctx, map);
+
+    let mut diags = Vec::new();
+    validate_expr(expr, ctx, map, &mut diags);
+
+    (ty, diags)
 }
 
 fn validate_expr(expr: &Expr, ctx: &Context, map: &TypeMap, diags: &mut Vec<Diagnostic>) {
@@ -279,11 +324,12 @@ fn validate_expr(expr: &Expr, ctx: &Context, map: &TypeMap, diags: &mut Vec<Diag
             match callee.text.as_str() {
                 "prop" => validate_prop_call(expr, args, ctx, diags),
                 name => {
-                    let Some(sig) = lookup_function(ctx, name) else {
+                    let Some(sig) = ctx.function(name) else {
                         emit_error(diags, expr.span, format!("unknown function: {}", name));
                         return;
                     };
-                    validate_call(expr.span, name, sig, args, ctx, map, diags);
+                    let args: Vec<&Expr> = args.iter().collect();
+                    validate_call(expr.span, name, sig, &args, ctx, map, diags);
                 }
             }
         }
@@ -297,7 +343,7 @@ fn validate_expr(expr: &Expr, ctx: &Context, map: &TypeMap, diags: &mut Vec<Diag
                 validate_expr(arg, ctx, map, diags);
             }
 
-            let Some(sig) = lookup_function(ctx, method.text.as_str()) else {
+            let Some(sig) = ctx.function(method.text.as_str()) else {
                 return;
             };
 
@@ -312,9 +358,9 @@ fn validate_expr(expr: &Expr, ctx: &Context, map: &TypeMap, diags: &mut Vec<Diag
                 return;
             }
 
-            let mut all_args: Vec<Expr> = Vec::with_capacity(1 + args.len());
-            all_args.push((**receiver).clone());
-            all_args.extend(args.iter().cloned());
+            let mut all_args: Vec<&Expr> = Vec::with_capacity(1 + args.len());
+            all_args.push(receiver);
+            all_args.extend(args);
             validate_call(
                 expr.span,
                 method.text.as_str(),
@@ -343,8 +389,24 @@ fn validate_prop_call(expr: &Expr, args: &[Expr], ctx: &Context, diags: &mut Vec
         }
     };
 
-    if ctx.lookup(name).is_none() {
-        emit_error(diags, arg.span, format!("Unknown property: {}", name));
+    match ctx.property(name) {
+        None => emit_error(diags, arg.span, format!("Unknown property: {}", name)),
+        Some(prop) if prop.name != name => diags.push(Diagnostic {
+            kind: DiagnosticKind::Warning,
+            code: DiagnosticCode::InexactPropertyName,
+            message: format!("Property name `{name}` does not match `{}` exactly", prop.name),
+            span: arg.span,
+            labels: vec![],
+            notes: vec![],
+            actions: vec![CodeAction {
+                title: format!("Replace with `{}`", prop.name),
+                edits: vec![TextEdit {
+                    range: arg.span,
+                    new_text: crate::string_literal(&prop.name),
+                }],
+            }],
+        }),
+        Some(_) => {}
     }
 }
 
@@ -352,7 +414,7 @@ fn validate_call(
     call_span: Span,
     name: &str,
     sig: &FunctionSig,
-    args: &[Expr],
+    args: &[&Expr],
     _ctx: &Context,
     map: &TypeMap,
     diags: &mut Vec<Diagnostic>,
@@ -365,8 +427,8 @@ fn validate_call(
         let Some(param) = param_for_arg_index_with_total(sig, idx, args.len()) else {
             continue;
         };
-        let actual = map.get(arg.id).cloned().unwrap_or(Ty::Unknown);
-        if !ty_accepts(&param.ty, &actual) {
+        let actual = map.get(arg.id).unwrap_or(&Ty::Unknown);
+        if !ty_accepts(&param.ty, actual) {
             if name == "sum" {
                 emit_error(diags, arg.span, "sum() expects number arguments");
             } else {
@@ -374,7 +436,7 @@ fn validate_call(
                     diags,
                     arg.span,
                     format!(
-                        "argument type mismatch: expected {:?}, got {:?}",
+                        "argument type mismatch: expected `{}`, got `{}`",
                         param.ty, actual
                     ),
                 );
@@ -449,7 +511,7 @@ fn validate_arity(
         return false;
     }
 
-    if resolve_repeat_tail_used(&sig.params, arg_len).is_none() {
+    if sig.params.tail_used(arg_len).is_none() {
         emit_error(
             diags,
             call_span,
@@ -466,33 +528,12 @@ fn param_for_arg_index_with_total(
     idx: usize,
     total: usize,
 ) -> Option<&ParamSig> {
-    if sig.params.repeat.is_empty() {
-        if idx < sig.params.head.len() {
-            return sig.params.head.get(idx);
-        }
-        return sig
-            .params
-            .tail
-            .get(idx.saturating_sub(sig.params.head.len()));
-    }
-
-    let head_len = sig.params.head.len();
-    let tail_used = resolve_repeat_tail_used(&sig.params, total)?;
-    let tail_start = total.saturating_sub(tail_used);
-
-    if idx < head_len {
-        return sig.params.head.get(idx);
-    }
-    if idx >= tail_start {
-        return sig.params.tail.get(idx - tail_start);
-    }
-
-    let idx = idx.saturating_sub(head_len);
-    sig.params.repeat.get(idx % sig.params.repeat.len())
-}
-
-pub(crate) fn resolve_repeat_tail_used(params: &ParamShape, total: usize) -> Option<usize> {
-    param_shape::resolve_repeat_tail_used(params, total)
+    let tail_start = if sig.params.repeat.is_empty() {
+        sig.params.head.len()
+    } else {
+        total.saturating_sub(sig.params.tail_used(total)?)
+    };
+    sig.params.param_at(idx, tail_start)
 }
 
 fn emit_error(diags: &mut Vec<Diagnostic>, span: Span, message: impl Into<String>) {
@@ -0,0 +1,181 @@
+//! Name-indexed collections for [`Context`](super::Context).
+//!
+//! [`NameIndexed`] wraps a list of named items and builds a `name -> position` map on the first
+//! lookup. Shared access goes through `Deref`; any mutable access goes through `DerefMut`, which
+//! drops the map, so lookups never see a stale index.
+
+use crate::collections::Map;
+use crate::prelude::*;
+use crate::sync::OnceLock;
+use alloc::borrow::Cow;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{FunctionSig, Property};
+
+/// Items looked up by name.
+pub trait Named {
+    fn name(&self) -> &str;
+}
+
+impl Named for Property {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Named for FunctionSig {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A list of named items with O(1) [`NameIndexed::by_name`] lookups.
+///
+/// Dereferences to the list itself. Equality, `Debug`, and serde see only the list.
+pub struct NameIndexed<C> {
+    items: C,
+    index: OnceLock<Map<String, usize>>,
+}
+
+/// `Context.properties`.
+pub type Properties = NameIndexed<Vec<Property>>;
+/// `Context.functions`; usually borrows the shared builtin table.
+pub type Functions = NameIndexed<Cow<'static, [FunctionSig]>>;
+
+impl<C> NameIndexed<C> {
+    pub fn new(items: C) -> Self {
+        Self {
+            items,
+            index: OnceLock::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.items
+    }
+}
+
+impl<C, T> NameIndexed<C>
+where
+    C: Deref<Target = [T]>,
+    T: Named,
+{
+    /// The first item called `name`, like `iter().find(..)`.
+    pub fn by_name(&self, name: &str) -> Option<&T> {
+        let index = self.index.get_or_init(|| {
+            // Reversed, so the first item with a name is inserted last and wins.
+            let items = self.items.iter().enumerate().rev();
+            items
+                .map(|(pos, item)| (item.name().to_string(), pos))
+                .collect()
+        });
+        index.get(name).map(|&pos| &self.items[pos])
+    }
+}
+
+impl<C> Deref for NameIndexed<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.items
+    }
+}
+
+impl<C> DerefMut for NameIndexed<C> {
+    fn deref_mut(&mut self) -> &mut C {
+        self.index = OnceLock::new();
+        &mut self.items
+    }
+}
+
+impl<C: IntoIterator> IntoIterator for NameIndexed<C> {
+    type Item = C::Item;
+    type IntoIter = C::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, C> IntoIterator for &'a NameIndexed<C>
+where
+    &'a C: IntoIterator,
+{
+    type Item = <&'a C as IntoIterator>::Item;
+    type IntoIter = <&'a C as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<C: FromIterator<T>, T> FromIterator<T> for NameIndexed<C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<C> From<C> for NameIndexed<C> {
+    fn from(items: C) -> Self {
+        Self::new(items)
+    }
+}
+
+impl From<Vec<FunctionSig>> for Functions {
+    fn from(items: Vec<FunctionSig>) -> Self {
+        Self::new(Cow::Owned(items))
+    }
+}
+
+impl From<&'static [FunctionSig]> for Functions {
+    fn from(items: &'static [FunctionSig]) -> Self {
+        Self::new(Cow::Borrowed(items))
+    }
+}
+
+impl<C: Default> Default for NameIndexed<C> {
+    fn default() -> Self {
+        Self::new(C::default())
+    }
+}
+
+impl<C: Clone> Clone for NameIndexed<C> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+            index: self.index.clone(),
+        }
+    }
+}
+
+impl<C: PartialEq> PartialEq for NameIndexed<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<C: Eq> Eq for NameIndexed<C> {}
+
+impl<C: fmt::Debug> fmt::Debug for NameIndexed<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.items.fmt(f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<C: Serialize> Serialize for NameIndexed<C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.items.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, C: Deserialize<'de>> Deserialize<'de> for NameIndexed<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        C::deserialize(deserializer).map(Self::new)
+    }
+}
@@ -0,0 +1,142 @@
+//! Notion API database schemas → [`Property`] lists.
+//!
+//! Deserialize (with the `serde` feature) `database.properties` (property name → schema object) into
+//! `BTreeMap<String, NotionPropertySchema>` and pass it to [`notion_schema_properties`]. Only
+//! `id`, `type`, `rollup.function`, `formula.expression`, and the option names of `select`,
+//! `status`, and `multi_select` are read; other fields are ignored.
+//! The ids resolve the property references in formula expressions (see
+//! `ide::from_notion_expression`).
+//!
+//! Types with no counterpart in [`Ty`] map to `Unknown`, which the analyzer accepts anywhere:
+//! there is no person type (the people builtins take a generic), and formula and relation
+//! results are not part of the schema.
+
+use alloc::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+use super::{Property, Ty};
+use crate::prelude::*;
+
+/// One entry of a Notion API `database.properties` object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct NotionPropertySchema {
+    /// The property id (URL-encoded, e.g. `"%3AUPp"`); `None` if the payload omits it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub id: Option<String>,
+    /// The Notion property type (`"rich_text"`, `"number"`, …).
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub kind: String,
+    /// Set for `rollup` properties.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rollup: Option<NotionRollupSchema>,
+    /// Set for `formula` properties.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub formula: Option<NotionFormulaSchema>,
+    /// Set for `select` properties.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub select: Option<NotionOptionsSchema>,
+    /// Set for `status` properties.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status: Option<NotionOptionsSchema>,
+    /// Set for `multi_select` properties.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub multi_select: Option<NotionOptionsSchema>,
+}
+
+impl NotionPropertySchema {
+    /// Option names of a `select`, `status`, or `multi_select` property, in schema order.
+    pub fn option_names(&self) -> Vec<String> {
+        let options = match self.kind.as_str() {
+            "select" => &self.select,
+            "status" => &self.status,
+            "multi_select" => &self.multi_select,
+            _ => return Vec::new(),
+        };
+        options
+            .iter()
+            .flat_map(|schema| &schema.options)
+            .map(|option| option.name.clone())
+            .collect()
+    }
+}
+
+/// The `rollup` configuration of a rollup property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct NotionRollupSchema {
+    /// Aggregation (`"sum"`, `"show_original"`, …).
+    pub function: String,
+}
+
+/// The `formula` configuration of a formula property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct NotionFormulaSchema {
+    /// The formula source in the API payload form (property references as placeholders).
+    pub expression: String,
+}
+
+/// The `select`, `status`, or `multi_select` configuration of a property.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct NotionOptionsSchema {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub options: Vec<NotionOptionSchema>,
+}
+
+/// One option of a [`NotionOptionsSchema`]; its `id` and `color` are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct NotionOptionSchema {
+    pub name: String,
+}
+
+/// Properties for a Notion database schema, in name order.
+pub fn notion_schema_properties(schema: &BTreeMap<String, NotionPropertySchema>) -> Vec<Property> {
+    schema
+        .iter()
+        .map(|(name, property)| Property {
+            name: name.clone(),
+            ty: notion_property_ty(property),
+            disabled_reason: None,
+            options: property.option_names(),
+        })
+        .collect()
+}
+
+/// The formula type of one Notion property.
+pub fn notion_property_ty(property: &NotionPropertySchema) -> Ty {
+    let list = |item: Ty| Ty::List(Box::new(item));
+    match property.kind.as_str() {
+        "title" | "rich_text" | "url" | "email" | "phone_number" => Ty::String,
+        // Values are option names; see `NotionPropertySchema::option_names`.
+        "select" | "status" => Ty::String,
+        "multi_select" => list(Ty::String),
+        "number" => Ty::Number,
+        "checkbox" => Ty::Boolean,
+        "date" | "created_time" | "last_edited_time" => Ty::Date,
+        "people" | "relation" | "files" => list(Ty::Unknown),
+        "rollup" => property
+            .rollup
+            .as_ref()
+            .map_or(Ty::Unknown, |rollup| rollup_ty(&rollup.function)),
+        // `created_by` / `last_edited_by` (a person), `formula`, `unique_id`, and newer types.
+        _ => Ty::Unknown,
+    }
+}
+
+fn rollup_ty(function: &str) -> Ty {
+    match function {
+        "count" | "count_values" | "unique" | "empty" | "not_empty" | "checked" | "unchecked"
+        | "percent_empty" | "percent_not_empty" | "percent_checked" | "percent_unchecked"
+        | "count_per_group" | "percent_per_group" | "sum" | "average" | "median" | "min"
+        | "max" | "range" => Ty::Number,
+        "earliest_date" | "latest_date" => Ty::Date,
+        // The item type depends on the related database's property.
+        "show_original" | "show_unique" => Ty::List(Box::new(Ty::Unknown)),
+        _ => Ty::Unknown,
+    }
+}
@@ -6,59 +6,66 @@
 
 use super::{ParamShape, ParamSig};
 
-/// Resolve `tail_used` for `total` args.
-///
-/// Returns `None` if `total` cannot fit the repeat shape, or if there is no repeat section.
-/// If more than one split fits, it prefers the largest `tail_used`.
-pub(crate) fn resolve_repeat_tail_used(params: &ParamShape, total: usize) -> Option<usize> {
-    resolve_repeat_tail_used_with_min_groups(params, total, params.repeat_min_groups)
-}
-
-fn resolve_repeat_tail_used_with_min_groups(
-    params: &ParamShape,
-    total: usize,
-    repeat_min_groups: usize,
-) -> Option<usize> {
-    if params.repeat.is_empty() {
-        return Some(params.tail.len());
-    }
+impl ParamShape {
+    /// How many `tail` params a call with `total` args uses.
+    ///
+    /// Returns `None` if `total` cannot fit the repeat shape (with at least
+    /// `repeat_min_groups` groups). Without a repeat section this is the whole tail. If more
+    /// than one split fits, it prefers the largest `tail_used`.
+    pub fn tail_used(&self, total: usize) -> Option<usize> {
+        if self.repeat.is_empty() {
+            return Some(self.tail.len());
+        }
 
-    let head_len = params.head.len();
-    if total < head_len {
-        return None;
-    }
+        let head_len = self.head.len();
+        if total < head_len {
+            return None;
+        }
 
-    let repeat_len = params.repeat.len();
-    let tail_min = required_tail_prefix_len(&params.tail);
-    let min_middle = repeat_len.saturating_mul(repeat_min_groups);
+        let repeat_len = self.repeat.len();
+        let tail_min = self.required_tail_len();
+        let min_middle = repeat_len.saturating_mul(self.repeat_min_groups);
 
-    for tail_used in (tail_min..=params.tail.len()).rev() {
-        if total < head_len + tail_used {
-            continue;
-        }
-        let middle = total - head_len - tail_used;
-        if middle >= min_middle && middle.is_multiple_of(repeat_len) {
-            return Some(tail_used);
+        for tail_used in (tail_min..=self.tail.len()).rev() {
+            if total < head_len + tail_used {
+                continue;
+            }
+            let middle = total - head_len - tail_used;
+            if middle >= min_middle && middle.is_multiple_of(repeat_len) {
+                return Some(tail_used);
+            }
         }
-    }
 
-    None
-}
+        None
+    }
 
-fn required_tail_prefix_len(tail: &[ParamSig]) -> usize {
-    let mut required = 0usize;
-    for (idx, p) in tail.iter().enumerate() {
-        if !p.optional {
-            required = idx + 1;
+    /// The param that argument `idx` binds to, when the tail starts at argument `tail_start`.
+    ///
+    /// Without a repeat section, pass `head.len()` as `tail_start`.
+    pub fn param_at(&self, idx: usize, tail_start: usize) -> Option<&ParamSig> {
+        let head_len = self.head.len();
+        if idx < head_len {
+            self.head.get(idx)
+        } else if idx >= tail_start {
+            self.tail.get(idx - tail_start)
+        } else {
+            let r_idx = (idx - head_len).checked_rem(self.repeat.len())?;
+            self.repeat.get(r_idx)
         }
     }
-    required
+
+    /// Length of the `tail` prefix that ends with its last required param.
+    pub fn required_tail_len(&self) -> usize {
+        self.tail
+            .iter()
+            .rposition(|p| !p.optional)
+            .map_or(0, |idx| idx + 1)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use crate::analysis::Ty;
+    use crate::analysis::{ParamShape, ParamSig, Ty};
 
     fn p(name: &str, optional: bool) -> ParamSig {
         ParamSig {
@@ -69,7 +76,7 @@ mod tests {
     }
 
     #[test]
-    fn resolve_repeat_tail_used_prefers_largest_tail_used_when_ambiguous() {
+    fn tail_used_prefers_largest_tail_used_when_ambiguous() {
         // NOTE: This shape violates `ParamShape::new` invariants (repeat + optional tail).
         // We still test the resolver's deterministic choice rule to prevent future drift.
         let params = ParamShape {
@@ -83,6 +90,27 @@ mod tests {
         // - tail_used=2, middle=2 (1 repeat group)
         // - tail_used=0, middle=4 (2 repeat groups)
         // Prefer the larger tail_used.
-        assert_eq!(resolve_repeat_tail_used(&params, 4), Some(2));
+        assert_eq!(params.tail_used(4), Some(2));
+    }
+
+    #[test]
+    fn param_at_maps_head_repeat_groups_and_tail() {
+        // Like `ifs(condition, value, ..., else)`.
+        let params = ParamShape::new(
+            vec![],
+            vec![p("condition", false), p("value", false)],
+            vec![p("else", false)],
+        );
+        let name = |idx, total| {
+            let tail_start = total - params.tail_used(total).unwrap();
+            params.param_at(idx, tail_start).map(|p| p.name.as_str())
+        };
+
+        assert_eq!(params.required_tail_len(), 1);
+        assert_eq!(params.tail_used(4), None);
+        assert_eq!(name(2, 5), Some("condition"));
+        assert_eq!(name(3, 5), Some("value"));
+        assert_eq!(name(4, 5), Some("else"));
+        assert_eq!(name(5, 5), None);
     }
 }
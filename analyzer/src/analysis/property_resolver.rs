@@ -0,0 +1,149 @@
+//! Properties resolved on demand instead of listed upfront in [`Context::properties`].
+
+use crate::prelude::*;
+use alloc::borrow::Cow;
+use alloc::sync::Arc;
+use core::fmt;
+use core::ops::Deref;
+
+use super::{Context, Property, PropertyLookup};
+
+/// Looks up properties lazily, e.g. from a host database or a remote schema service.
+///
+/// A [`Context`] asks its resolver only for names its eager `properties` do not have, and
+/// analysis asks only for the names a formula reads. Completion lists pages by the prefix being
+/// typed.
+pub trait PropertyResolver: Send + Sync {
+    /// The property called exactly `name`.
+    fn resolve(&self, name: &str) -> Option<Property>;
+
+    /// Properties whose names start with `prefix` (ASCII case-insensitively; `""` matches all),
+    /// skipping the first `offset` matches and returning at most `limit`.
+    ///
+    /// The order must be stable across calls so that offsets page through every match once.
+    fn list(&self, prefix: &str, offset: usize, limit: usize) -> PropertyPage;
+}
+
+/// One page of [`PropertyResolver::list`] results.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertyPage {
+    pub properties: Vec<Property>,
+    /// Offset of the next page, or `None` after the last match.
+    pub next_offset: Option<usize>,
+}
+
+/// An in-memory resolver, mainly for tests and for hosts migrating from eager lists.
+impl PropertyResolver for Vec<Property> {
+    fn resolve(&self, name: &str) -> Option<Property> {
+        self.iter().find(|prop| prop.name == name).cloned()
+    }
+
+    fn list(&self, prefix: &str, offset: usize, limit: usize) -> PropertyPage {
+        let mut matches = self
+            .iter()
+            .filter(|prop| starts_with_ignore_ascii_case(&prop.name, prefix));
+        let properties: Vec<Property> =
+            matches.by_ref().skip(offset).take(limit).cloned().collect();
+        let next_offset = matches.next().map(|_| offset + properties.len());
+        PropertyPage {
+            properties,
+            next_offset,
+        }
+    }
+}
+
+/// A shared [`PropertyResolver`], as stored in [`Context::property_resolver`].
+///
+/// Clones share the resolver, and only clones compare equal.
+#[derive(Clone)]
+pub struct SharedPropertyResolver(Arc<dyn PropertyResolver>);
+
+impl SharedPropertyResolver {
+    pub fn new(resolver: impl PropertyResolver + 'static) -> Self {
+        Self(Arc::new(resolver))
+    }
+}
+
+impl Deref for SharedPropertyResolver {
+    type Target = dyn PropertyResolver;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl PartialEq for SharedPropertyResolver {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedPropertyResolver {}
+
+impl fmt::Debug for SharedPropertyResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedPropertyResolver(..)")
+    }
+}
+
+/// Page size for scanning the resolver under [`PropertyLookup::Lenient`].
+const LENIENT_PAGE: usize = 64;
+
+impl Context {
+    /// The property called `name`: the first in `properties`, else from the resolver.
+    ///
+    /// Under [`PropertyLookup::Lenient`], a name without an exact match falls back to a property
+    /// whose name is equal after trimming and ignoring case; its `name` then differs from `name`.
+    pub fn property(&self, name: &str) -> Option<Cow<'_, Property>> {
+        self.exact_property(name)
+            .or_else(|| match self.property_lookup {
+                PropertyLookup::Exact => None,
+                PropertyLookup::Lenient => self.lenient_property(name),
+            })
+    }
+
+    fn exact_property(&self, name: &str) -> Option<Cow<'_, Property>> {
+        if let Some(prop) = self.properties.by_name(name) {
+            return Some(Cow::Borrowed(prop));
+        }
+        self.property_resolver
+            .as_ref()?
+            .resolve(name)
+            .map(Cow::Owned)
+    }
+
+    /// Resolver names are found through [`PropertyResolver::list`], so only their ASCII case is
+    /// ignored.
+    fn lenient_property(&self, name: &str) -> Option<Cow<'_, Property>> {
+        let key = lenient_key(name);
+        if let Some(prop) = self
+            .properties
+            .iter()
+            .find(|prop| lenient_key(&prop.name) == key)
+        {
+            return Some(Cow::Borrowed(prop));
+        }
+        let resolver = self.property_resolver.as_ref()?;
+        let mut offset = 0;
+        loop {
+            let page = resolver.list(name.trim(), offset, LENIENT_PAGE);
+            if let Some(prop) = page
+                .properties
+                .into_iter()
+                .find(|prop| lenient_key(&prop.name) == key)
+            {
+                return Some(Cow::Owned(prop));
+            }
+            offset = page.next_offset?;
+        }
+    }
+}
+
+fn lenient_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn starts_with_ignore_ascii_case(name: &str, prefix: &str) -> bool {
+    name.len() >= prefix.len()
+        && name.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+}
@@ -4,7 +4,12 @@
 //! invariants required for stable validation and signature help.
 
 use super::{FunctionCategory, GenericId, Ty};
-use std::collections::HashSet;
+use crate::collections::Set;
+use crate::prelude::*;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// How a generic parameter binds during inference.
 ///
@@ -12,24 +17,30 @@ use std::collections::HashSet;
 /// current rules.
 ///
 /// `Variant` is stricter around `Unknown` participation than `Plain`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "PascalCase"))]
 pub enum GenericParamKind {
     Plain,
     Variant,
 }
 
 /// Declaration of a generic parameter used by a [`FunctionSig`].
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GenericParam {
     pub id: GenericId,
     pub kind: GenericParamKind,
 }
 
 /// A single parameter slot in a function signature.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ParamSig {
     pub name: String,
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub ty: Ty,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub optional: bool,
 }
 
@@ -38,15 +49,27 @@ pub struct ParamSig {
 /// This shape is designed to make arity/shape validation and signature-help presentation stable.
 /// By default, repeat shapes assume at least one repeat group (`repeat_min_groups = 1`).
 /// Set `repeat_min_groups = 0` for truly optional variadic args (e.g. `splice(...items)`).
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Deserialization does not go through [`ParamShape::new`], so its invariants are not checked.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ParamShape {
+    #[cfg_attr(feature = "serde", serde(default))]
     pub head: Vec<ParamSig>,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub repeat: Vec<ParamSig>,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub tail: Vec<ParamSig>,
     /// Minimum number of repeat-group cycles required. Defaults to `1`.
+    #[cfg_attr(feature = "serde", serde(default = "default_repeat_min_groups"))]
     pub repeat_min_groups: usize,
 }
 
+#[cfg(feature = "serde")]
+fn default_repeat_min_groups() -> usize {
+    1
+}
+
 impl ParamShape {
     /// Construct a new [`ParamShape`] and enforce determinism invariants.
     ///
@@ -56,37 +79,51 @@ impl ParamShape {
     /// - `repeat` is non-empty and any `tail` param is optional (repeat+optional-tail is rejected),
     /// - `tail` contains a required param after an optional param (optional tail must be suffix-only).
     pub fn new(head: Vec<ParamSig>, repeat: Vec<ParamSig>, tail: Vec<ParamSig>) -> Self {
+        Self::try_new(head, repeat, tail).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Like [`ParamShape::new`], returning the violated invariant instead of panicking.
+    pub fn try_new(
+        head: Vec<ParamSig>,
+        repeat: Vec<ParamSig>,
+        tail: Vec<ParamSig>,
+    ) -> Result<Self, SignatureError> {
         if let Some(param) = repeat.iter().find(|p| p.optional) {
-            panic!(
+            return Err(SignatureError::new(format!(
                 "ParamShape invariant violated: repeat params must not be optional (found: {:?})",
                 param
-            );
+            )));
         }
 
         if !repeat.is_empty()
             && !tail.is_empty()
             && let Some(param) = tail.iter().find(|p| p.optional)
         {
-            panic!(
+            return Err(SignatureError::new(format!(
                 "ParamShape invariant violated: when repeat params exist, tail params must be required for determinism (found optional: {:?})",
                 param
-            );
+            )));
         }
 
         let mut seen_optional = false;
         for p in &tail {
             if seen_optional && !p.optional {
-                panic!(
+                return Err(SignatureError::new(format!(
                     "ParamShape invariant violated: tail params must be suffix-only optional; found required param after optional: {:?}",
                     p
-                );
+                )));
             }
             if p.optional {
                 seen_optional = true;
             }
         }
 
-        Self { head, repeat, tail, repeat_min_groups: 1 }
+        Ok(Self {
+            head,
+            repeat,
+            tail,
+            repeat_min_groups: 1,
+        })
     }
 
     /// Set the minimum number of repeat-group cycles. Default is `1`.
@@ -99,6 +136,26 @@ impl ParamShape {
     }
 }
 
+/// A [`ParamShape`] or [`FunctionSig`] invariant that does not hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureError {
+    pub message: String,
+}
+
+impl SignatureError {
+    fn new(message: String) -> Self {
+        Self { message }
+    }
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl core::error::Error for SignatureError {}
+
 /// Custom type resolution function for builtins whose return type cannot be
 /// expressed by the standard generic unification system.
 ///
@@ -116,16 +173,22 @@ pub type SigResolver = fn(&FunctionSig, &[Ty]) -> FunctionSig;
 /// Builtin signatures also carry:
 /// - `category` for UI grouping
 /// - `detail` for completion/signature help display
+///
+/// `resolver` is not serialized; deserialized signatures always use generic unification.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FunctionSig {
     pub name: String,
     pub params: ParamShape,
     pub ret: Ty,
     pub category: FunctionCategory,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub detail: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub generics: Vec<GenericParam>,
     /// Optional custom type resolver. When set, type inference uses this
     /// instead of the standard generic unification path.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub resolver: Option<SigResolver>,
 }
 
@@ -144,6 +207,18 @@ impl PartialEq for FunctionSig {
 
 impl Eq for FunctionSig {}
 
+/// Hashes the same fields that [`PartialEq`] compares.
+impl Hash for FunctionSig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.params.hash(state);
+        self.ret.hash(state);
+        self.category.hash(state);
+        self.detail.hash(state);
+        self.generics.hash(state);
+    }
+}
+
 impl FunctionSig {
     /// Create a signature without additional validation.
     pub fn new(
@@ -203,42 +278,51 @@ impl FunctionSig {
     }
 
     fn validate_builtin(&self) {
-        let mut declared = HashSet::<GenericId>::new();
+        if let Err(err) = self.check() {
+            panic!("{err}");
+        }
+    }
+
+    /// Checks the invariants builtin signatures are held to: expected types contain no
+    /// [`Ty::Unknown`], and every used generic is declared in `generics`.
+    pub fn check(&self) -> Result<(), SignatureError> {
+        let mut declared = Set::<GenericId>::new();
         for g in &self.generics {
             declared.insert(g.id);
         }
 
         for p in self.display_params() {
-            if let Some(ty) = find_unknown_in_ty(&p.ty) {
-                panic!(
-                    "Builtin FunctionSig `{}`: expected param `{}` type must not contain Ty::Unknown (found: {:?})",
-                    self.name, p.name, ty
-                );
+            if contains_unknown(&p.ty) {
+                return Err(SignatureError::new(format!(
+                    "FunctionSig `{}`: expected param `{}` type must not contain Ty::Unknown (found: `{}`)",
+                    self.name, p.name, p.ty
+                )));
             }
             for used in collect_generics_in_ty(&p.ty) {
                 if !declared.contains(&used) {
-                    panic!(
-                        "Builtin FunctionSig `{}`: param `{}` type uses generic {:?} but it is not declared in `generics`",
-                        self.name, p.name, used
-                    );
+                    return Err(SignatureError::new(format!(
+                        "FunctionSig `{}`: param `{}` type uses generic `{}` but it is not declared in `generics`",
+                        self.name, p.name, Ty::Generic(used)
+                    )));
                 }
             }
         }
 
-        if let Some(ty) = find_unknown_in_ty(&self.ret) {
-            panic!(
-                "Builtin FunctionSig `{}`: expected return type must not contain Ty::Unknown (found: {:?})",
-                self.name, ty
-            );
+        if contains_unknown(&self.ret) {
+            return Err(SignatureError::new(format!(
+                "FunctionSig `{}`: expected return type must not contain Ty::Unknown (found: `{}`)",
+                self.name, self.ret
+            )));
         }
         for used in collect_generics_in_ty(&self.ret) {
             if !declared.contains(&used) {
-                panic!(
-                    "Builtin FunctionSig `{}`: return type uses generic {:?} but it is not declared in `generics`",
-                    self.name, used
-                );
+                return Err(SignatureError::new(format!(
+                    "FunctionSig `{}`: return type uses generic `{}` but it is not declared in `generics`",
+                    self.name, Ty::Generic(used)
+                )));
             }
         }
+        Ok(())
     }
 
     /// Returns a flat parameter list for signatures that are exactly `head` params.
@@ -350,11 +434,11 @@ fn collect_generics_in_ty(ty: &Ty) -> Vec<GenericId> {
     out
 }
 
-fn find_unknown_in_ty(ty: &Ty) -> Option<&Ty> {
+fn contains_unknown(ty: &Ty) -> bool {
     match ty {
-        Ty::Unknown => Some(ty),
-        Ty::List(inner) => find_unknown_in_ty(inner),
-        Ty::Union(members) => members.iter().find_map(find_unknown_in_ty),
-        Ty::Number | Ty::String | Ty::Boolean | Ty::Date | Ty::Null | Ty::Generic(_) => None,
+        Ty::Unknown => true,
+        Ty::List(inner) => contains_unknown(inner),
+        Ty::Union(members) => members.iter().any(contains_unknown),
+        Ty::Number | Ty::String | Ty::Boolean | Ty::Date | Ty::Null | Ty::Generic(_) => false,
     }
 }
@@ -0,0 +1,157 @@
+//! Fluent construction of custom [`FunctionSig`]s.
+
+use crate::prelude::*;
+
+use super::{
+    FunctionCategory, FunctionSig, GenericId, GenericParam, GenericParamKind, ParamShape, ParamSig,
+    SigResolver, SignatureError, Ty,
+};
+
+/// Builds a [`FunctionSig`] and checks it like a builtin.
+///
+/// Parameters are added in display order: [`param`](Self::param)/[`optional`](Self::optional)
+/// fill `head` until the first [`repeat`](Self::repeat) param, and `tail` after it.
+///
+/// ```
+/// use analyzer::semantic::{FunctionCategory, FunctionSig, GenericId, GenericParamKind, Ty};
+///
+/// let t = Ty::Generic(GenericId(0));
+/// let sig = FunctionSig::builder("coalesce")
+///     .category(FunctionCategory::General)
+///     .detail("coalesce(value1, ..., fallback)")
+///     .generic(0, GenericParamKind::Variant)
+///     .repeat("value1", t.clone())
+///     .param("fallback", t.clone())
+///     .returns(t)
+///     .build()
+///     .unwrap();
+/// assert_eq!(sig.params.repeat.len(), 1);
+/// assert_eq!(sig.params.tail.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FunctionSigBuilder {
+    name: String,
+    category: FunctionCategory,
+    detail: String,
+    generics: Vec<GenericParam>,
+    head: Vec<ParamSig>,
+    repeat: Vec<ParamSig>,
+    tail: Vec<ParamSig>,
+    repeat_min_groups: usize,
+    ret: Ty,
+    resolver: Option<SigResolver>,
+}
+
+impl FunctionSigBuilder {
+    /// A `General` function with no parameters and an `Unknown` return type, which
+    /// [`build`](Self::build) rejects until [`returns`](Self::returns) sets one.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            category: FunctionCategory::General,
+            detail: String::new(),
+            generics: Vec::new(),
+            head: Vec::new(),
+            repeat: Vec::new(),
+            tail: Vec::new(),
+            repeat_min_groups: 1,
+            ret: Ty::Unknown,
+            resolver: None,
+        }
+    }
+
+    pub fn category(mut self, category: FunctionCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Completion detail and hover text, e.g. `"clamp(value, min, max)"`.
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+
+    /// Declares `Ty::Generic(GenericId(id))`.
+    pub fn generic(mut self, id: u32, kind: GenericParamKind) -> Self {
+        self.generics.push(GenericParam {
+            id: GenericId(id),
+            kind,
+        });
+        self
+    }
+
+    /// Adds a required param to `head`, or to `tail` once a repeat param exists.
+    pub fn param(self, name: impl Into<String>, ty: Ty) -> Self {
+        self.push(name, ty, false)
+    }
+
+    /// Adds an optional param to `head`, or to `tail` once a repeat param exists.
+    pub fn optional(self, name: impl Into<String>, ty: Ty) -> Self {
+        self.push(name, ty, true)
+    }
+
+    /// Adds a param to the repeating group.
+    pub fn repeat(mut self, name: impl Into<String>, ty: Ty) -> Self {
+        self.repeat.push(ParamSig {
+            name: name.into(),
+            ty,
+            optional: false,
+        });
+        self
+    }
+
+    /// Minimum number of repeat groups; see [`ParamShape::with_repeat_min_groups`].
+    pub fn repeat_min_groups(mut self, min: usize) -> Self {
+        self.repeat_min_groups = min;
+        self
+    }
+
+    pub fn returns(mut self, ret: Ty) -> Self {
+        self.ret = ret;
+        self
+    }
+
+    /// Computes the return type from the argument types instead of generic unification.
+    pub fn resolver(mut self, resolver: SigResolver) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Checks the [`ParamShape`] invariants and [`FunctionSig::check`].
+    pub fn build(self) -> Result<FunctionSig, SignatureError> {
+        let params = ParamShape::try_new(self.head, self.repeat, self.tail)?
+            .with_repeat_min_groups(self.repeat_min_groups);
+        let mut sig = FunctionSig::new(
+            self.category,
+            self.detail,
+            self.name,
+            params,
+            self.ret,
+            self.generics,
+        );
+        sig.resolver = self.resolver;
+        sig.check()?;
+        Ok(sig)
+    }
+
+    fn push(mut self, name: impl Into<String>, ty: Ty, optional: bool) -> Self {
+        let params = if self.repeat.is_empty() {
+            &mut self.head
+        } else {
+            &mut self.tail
+        };
+        params.push(ParamSig {
+            name: name.into(),
+            ty,
+            optional,
+        });
+        self
+    }
+}
+
+impl FunctionSig {
+    /// Starts a [`FunctionSigBuilder`].
+    pub fn builder(name: impl Into<String>) -> FunctionSigBuilder {
+        FunctionSigBuilder::new(name)
+    }
+}
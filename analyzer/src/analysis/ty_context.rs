@@ -0,0 +1,230 @@
+//! Interned types for inference.
+//!
+//! [`TyContext`] hash-conses [`Ty`] values into copyable [`TyId`] handles: every distinct type is
+//! stored once, equal types get equal ids, and building a type that already exists allocates
+//! nothing. Inference works on ids and only materializes a [`Ty`] at API boundaries.
+
+use crate::collections::Map;
+use crate::prelude::*;
+
+use super::{GenericId, Ty};
+
+/// Handle to a type interned in a [`TyContext`].
+///
+/// Ids are only meaningful for the context that created them. Within one context, id equality
+/// is type equality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TyId(u32);
+
+impl TyId {
+    pub const NUMBER: TyId = TyId(0);
+    pub const STRING: TyId = TyId(1);
+    pub const BOOLEAN: TyId = TyId(2);
+    pub const DATE: TyId = TyId(3);
+    pub const NULL: TyId = TyId(4);
+    pub const UNKNOWN: TyId = TyId(5);
+}
+
+/// Shape of an interned type, with children as ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TyKind {
+    Number,
+    String,
+    Boolean,
+    Date,
+    Null,
+    Unknown,
+    Generic(GenericId),
+    List(TyId),
+    Union(Box<[TyId]>),
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    kind: TyKind,
+    ty: Ty,
+    /// Union member order, see [`TyContext::union`].
+    sort_key: (u8, String),
+}
+
+/// Interner for [`Ty`] values.
+#[derive(Debug, Clone)]
+pub struct TyContext {
+    entries: Vec<Entry>,
+    generics: Map<GenericId, TyId>,
+    lists: Map<TyId, TyId>,
+    unions: Map<Box<[TyId]>, TyId>,
+}
+
+impl Default for TyContext {
+    fn default() -> Self {
+        let mut tys = Self {
+            entries: Vec::new(),
+            generics: Map::new(),
+            lists: Map::new(),
+            unions: Map::new(),
+        };
+        // Same order as the `TyId` constants.
+        for (kind, ty) in [
+            (TyKind::Number, Ty::Number),
+            (TyKind::String, Ty::String),
+            (TyKind::Boolean, Ty::Boolean),
+            (TyKind::Date, Ty::Date),
+            (TyKind::Null, Ty::Null),
+            (TyKind::Unknown, Ty::Unknown),
+        ] {
+            tys.push(kind, ty);
+        }
+        tys
+    }
+}
+
+impl TyContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The type behind `id`.
+    pub fn ty(&self, id: TyId) -> &Ty {
+        &self.entries[id.0 as usize].ty
+    }
+
+    pub fn kind(&self, id: TyId) -> &TyKind {
+        &self.entries[id.0 as usize].kind
+    }
+
+    /// Interns `ty` as written; unions are not normalized.
+    pub fn intern(&mut self, ty: &Ty) -> TyId {
+        match ty {
+            Ty::Number => TyId::NUMBER,
+            Ty::String => TyId::STRING,
+            Ty::Boolean => TyId::BOOLEAN,
+            Ty::Date => TyId::DATE,
+            Ty::Null => TyId::NULL,
+            Ty::Unknown => TyId::UNKNOWN,
+            Ty::Generic(id) => self.generic(*id),
+            Ty::List(inner) => {
+                let inner = self.intern(inner);
+                self.list(inner)
+            }
+            Ty::Union(members) => {
+                let members: Vec<TyId> = members.iter().map(|m| self.intern(m)).collect();
+                self.union_of(members)
+            }
+        }
+    }
+
+    pub fn generic(&mut self, id: GenericId) -> TyId {
+        if let Some(&ty) = self.generics.get(&id) {
+            return ty;
+        }
+        let ty = self.push(TyKind::Generic(id), Ty::Generic(id));
+        self.generics.insert(id, ty);
+        ty
+    }
+
+    pub fn list(&mut self, inner: TyId) -> TyId {
+        if let Some(&ty) = self.lists.get(&inner) {
+            return ty;
+        }
+        let ty = Ty::List(Box::new(self.ty(inner).clone()));
+        let ty = self.push(TyKind::List(inner), ty);
+        self.lists.insert(inner, ty);
+        ty
+    }
+
+    /// Builds a normalized union, like [`normalize_union`](super::normalize_union): nested unions
+    /// are flattened, duplicates dropped, members sorted deterministically; one member is
+    /// returned as is, and no members give `Unknown`.
+    pub fn union(&mut self, members: impl IntoIterator<Item = TyId>) -> TyId {
+        let mut members = members.into_iter();
+        let Some(first) = members.next() else {
+            return TyId::UNKNOWN;
+        };
+        let mut flat = Vec::new();
+        match members.next() {
+            None if !matches!(self.kind(first), TyKind::Union(_)) => return first,
+            None => self.push_flattened(&mut flat, first),
+            Some(second) => {
+                for member in [first, second].into_iter().chain(members) {
+                    self.push_flattened(&mut flat, member);
+                }
+            }
+        }
+
+        let mut unique: Vec<TyId> = Vec::with_capacity(flat.len());
+        for member in flat {
+            if !unique.contains(&member) {
+                unique.push(member);
+            }
+        }
+        unique.sort_by(|a, b| {
+            self.entries[a.0 as usize]
+                .sort_key
+                .cmp(&self.entries[b.0 as usize].sort_key)
+        });
+
+        match unique.as_slice() {
+            [] => TyId::UNKNOWN,
+            [only] => *only,
+            _ => self.union_of(unique),
+        }
+    }
+
+    /// `Unknown` itself or a union containing it.
+    pub fn contains_unknown(&self, id: TyId) -> bool {
+        match self.kind(id) {
+            TyKind::Unknown => true,
+            TyKind::Union(members) => members.iter().any(|&m| self.contains_unknown(m)),
+            _ => false,
+        }
+    }
+
+    fn push_flattened(&self, out: &mut Vec<TyId>, id: TyId) {
+        match self.kind(id) {
+            TyKind::Union(members) => {
+                for &member in members {
+                    self.push_flattened(out, member);
+                }
+            }
+            _ => out.push(id),
+        }
+    }
+
+    /// Interns a union with exactly these members.
+    fn union_of(&mut self, members: Vec<TyId>) -> TyId {
+        if let Some(&ty) = self.unions.get(members.as_slice()) {
+            return ty;
+        }
+        let members = members.into_boxed_slice();
+        let ty = Ty::Union(members.iter().map(|&m| self.ty(m).clone()).collect());
+        let ty = self.push(TyKind::Union(members.clone()), ty);
+        self.unions.insert(members, ty);
+        ty
+    }
+
+    fn push(&mut self, kind: TyKind, ty: Ty) -> TyId {
+        let id = TyId(self.entries.len() as u32);
+        let sort_key = self.sort_key(&kind);
+        self.entries.push(Entry { kind, ty, sort_key });
+        id
+    }
+
+    fn sort_key(&self, kind: &TyKind) -> (u8, String) {
+        match kind {
+            TyKind::Null => (0, "null".into()),
+            TyKind::Boolean => (1, "boolean".into()),
+            TyKind::Number => (2, "number".into()),
+            TyKind::String => (3, "string".into()),
+            TyKind::Date => (4, "date".into()),
+            TyKind::List(inner) => {
+                let inner = &self.entries[inner.0 as usize].sort_key.1;
+                (5, format!("list<{inner}>"))
+            }
+            TyKind::Generic(id) => (6, format!("T{}", id.0)),
+            // By the time we sort, unions should already be flattened.
+            TyKind::Union(_) => (7, "union".into()),
+            TyKind::Unknown => (8, "unknown".into()),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! The one user-facing rendering of [`Ty`], shared by diagnostics, completion, signature help,
+//! hover, and the host bindings.
+
+use core::fmt;
+
+use super::Ty;
+
+/// Renders a [`Ty`] the way users write it: `number`, `string[]`, `(number | date)[]`,
+/// `string | null`, and `T0` for generics.
+///
+/// `Ty`'s `Display` is a `TyDisplay` with default options; [`Ty::display`] starts one to adjust.
+///
+/// ```
+/// use analyzer::semantic::Ty;
+///
+/// let ty = Ty::List(Box::new(Ty::Union(vec![Ty::Number, Ty::Null])));
+/// assert_eq!(ty.to_string(), "(number | null)[]");
+/// assert_eq!(Ty::String.display().optional(true).to_string(), "string?");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TyDisplay<'a> {
+    ty: &'a Ty,
+    optional: bool,
+}
+
+impl Ty {
+    /// A configurable [`TyDisplay`] of this type.
+    pub fn display(&self) -> TyDisplay<'_> {
+        TyDisplay {
+            ty: self,
+            optional: false,
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Ty::Union(_) => 1,
+            Ty::List(_) => 2,
+            Ty::Number
+            | Ty::String
+            | Ty::Boolean
+            | Ty::Date
+            | Ty::Null
+            | Ty::Unknown
+            | Ty::Generic(_) => 3,
+        }
+    }
+}
+
+impl TyDisplay<'_> {
+    /// Appends `?`, as for optional parameters; a union is parenthesized first.
+    pub fn optional(mut self, optional: bool) -> Self {
+        self.optional = optional;
+        self
+    }
+}
+
+impl fmt::Display for TyDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.optional {
+            // Binds like `[]`.
+            write_ty(f, self.ty, 2)?;
+            f.write_str("?")
+        } else {
+            write_ty(f, self.ty, 0)
+        }
+    }
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display().fmt(f)
+    }
+}
+
+fn write_ty(f: &mut fmt::Formatter<'_>, ty: &Ty, parent_prec: u8) -> fmt::Result {
+    let my_prec = ty.precedence();
+    let needs_parens = my_prec < parent_prec;
+    if needs_parens {
+        f.write_str("(")?;
+    }
+
+    match ty {
+        Ty::Number => f.write_str("number")?,
+        Ty::String => f.write_str("string")?,
+        Ty::Boolean => f.write_str("boolean")?,
+        Ty::Date => f.write_str("date")?,
+        Ty::Null => f.write_str("null")?,
+        Ty::Unknown => f.write_str("unknown")?,
+        Ty::Generic(id) => write!(f, "T{}", id.0)?,
+        Ty::List(inner) => {
+            write_ty(f, inner, my_prec)?;
+            f.write_str("[]")?;
+        }
+        Ty::Union(members) => {
+            for (idx, m) in members.iter().enumerate() {
+                if idx > 0 {
+                    f.write_str(" | ")?;
+                }
+                write_ty(f, m, my_prec)?;
+            }
+        }
+    };
+
+    if needs_parens {
+        f.write_str(")")?;
+    }
+    Ok(())
+}
@@ -1,6 +1,7 @@
 //! Helpers for deterministic union normalization.
 
 use super::Ty;
+use crate::prelude::*;
 
 /// Normalize a union-like set of types into a deterministic [`Ty`].
 ///
@@ -0,0 +1,16 @@
+use std::fs;
+use std::path::PathBuf;
+
+use analyzer::analysis::{CONTEXT_SCHEMA_PATH, context_json_schema};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let out_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(CONTEXT_SCHEMA_PATH);
+    if let Some(parent) = out_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut json = serde_json::to_string_pretty(&context_json_schema())?;
+    json.push('\n');
+    fs::write(out_path, json)?;
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use std::fs;
+use std::path::PathBuf;
+
+use analyzer::grammar::{
+    GRAMMAR_DIR, HIGHLIGHTJS_FILE, TEXTMATE_FILE, highlightjs_language, textmate_grammar,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(GRAMMAR_DIR);
+    fs::create_dir_all(&dir)?;
+
+    let mut json = serde_json::to_string_pretty(&textmate_grammar())?;
+    json.push('\n');
+    fs::write(dir.join(TEXTMATE_FILE), json)?;
+    fs::write(dir.join(HIGHLIGHTJS_FILE), highlightjs_language())?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Opt-in memoization of [`analyze`](crate::analyze) results.
+//!
+//! Hosts re-validate unchanged formulas whenever anything in the database changes. An
+//! [`AnalysisCache`] remembers the result for each source text together with a fingerprint of the
+//! [`Context`] entries that source actually reads: the properties named in `prop("...")` calls and
+//! the functions it calls. A lookup hits when the source is the same and those entries hash the
+//! same, so edits to unrelated properties or functions keep the cached result.
+
+use core::hash::{Hash, Hasher};
+
+use crate::AnalyzeResult;
+use crate::analysis::Context;
+use crate::ast::{Expr, ExprKind};
+use crate::collections::Map;
+use crate::lexer::LitKind;
+use crate::prelude::*;
+
+/// A bounded, least-recently-used cache of [`AnalyzeResult`]s.
+///
+/// Holds at most one result per source text, and at least one entry whatever the capacity.
+#[derive(Debug, Clone)]
+pub struct AnalysisCache {
+    capacity: usize,
+    entries: Map<u64, Entry>,
+    /// Bumped on every lookup; entries remember the tick of their last use.
+    tick: u64,
+    stats: CacheStats,
+}
+
+/// Hit and miss counts since the cache was created or last [`cleared`](AnalysisCache::clear).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Guards against source hash collisions.
+    source: Box<str>,
+    deps: Deps,
+    context: u64,
+    result: AnalyzeResult,
+    last_used: u64,
+}
+
+/// Names a source looks up in its [`Context`], sorted and deduplicated.
+#[derive(Debug, Clone, Default)]
+struct Deps {
+    properties: Vec<String>,
+    functions: Vec<String>,
+}
+
+impl AnalysisCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Map::new(),
+            tick: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.stats = CacheStats::default();
+    }
+
+    /// Same as [`analyze`](crate::analyze), reusing the stored result when `text` and the
+    /// context entries it reads are unchanged since it was stored.
+    ///
+    /// A miss stores the new result, replacing any older result for `text` and evicting the
+    /// least recently used entry when the cache is full.
+    pub fn analyze(&mut self, text: &str, ctx: &Context) -> &AnalyzeResult {
+        self.tick += 1;
+        let key = fingerprint(text);
+        let hit = self.entries.get(&key).is_some_and(|entry| {
+            &*entry.source == text && entry.context == entry.deps.fingerprint(ctx)
+        });
+
+        if hit {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+            let (expr, result) = crate::analyze_with_expr(text, ctx);
+            let deps = Deps::of(&expr);
+            let entry = Entry {
+                source: text.into(),
+                context: deps.fingerprint(ctx),
+                deps,
+                result,
+                last_used: self.tick,
+            };
+            if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+                self.evict();
+            }
+            self.entries.insert(key, entry);
+        }
+
+        let entry = self
+            .entries
+            .get_mut(&key)
+            .expect("entry was just checked or inserted");
+        entry.last_used = self.tick;
+        &entry.result
+    }
+
+    fn evict(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(&key, _)| key);
+        if let Some(key) = oldest {
+            self.entries.remove(&key);
+        }
+    }
+}
+
+impl Deps {
+    fn of(expr: &Expr) -> Self {
+        let mut deps = Deps::default();
+        deps.collect(expr);
+        for names in [&mut deps.properties, &mut deps.functions] {
+            names.sort_unstable();
+            names.dedup();
+        }
+        deps
+    }
+
+    fn collect(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Ident(_) | ExprKind::Lit(_) | ExprKind::Error => {}
+            ExprKind::Group { inner } => self.collect(inner),
+            ExprKind::List { items } => items.iter().for_each(|item| self.collect(item)),
+            ExprKind::Call { callee, args } => {
+                if callee.text == "prop"
+                    && let [arg] = args.as_slice()
+                    && let ExprKind::Lit(lit) = &arg.kind
+                    && lit.kind == LitKind::String
+                {
+                    self.properties.push(lit.symbol.text.to_string());
+                }
+                self.functions.push(callee.text.to_string());
+                args.iter().for_each(|arg| self.collect(arg));
+            }
+            ExprKind::MemberCall {
+                receiver,
+                method,
+                args,
+            } => {
+                self.functions.push(method.text.to_string());
+                self.collect(receiver);
+                args.iter().for_each(|arg| self.collect(arg));
+            }
+            ExprKind::Unary { expr, .. } => self.collect(expr),
+            ExprKind::Binary { left, right, .. } => {
+                self.collect(left);
+                self.collect(right);
+            }
+            ExprKind::Ternary {
+                cond,
+                then,
+                otherwise,
+            } => {
+                self.collect(cond);
+                self.collect(then);
+                self.collect(otherwise);
+            }
+        }
+    }
+
+    /// Hashes what `ctx` has under each name, including absence.
+    ///
+    /// Property names are hashed too: a lenient match is diagnosed, an exact one is not.
+    fn fingerprint(&self, ctx: &Context) -> u64 {
+        let mut hasher = Fnv::default();
+        ctx.limits.hash(&mut hasher);
+        for name in &self.properties {
+            let prop = ctx.property(name);
+            prop.as_deref()
+                .map(|prop| (&prop.name, &prop.ty))
+                .hash(&mut hasher);
+        }
+        for name in &self.functions {
+            ctx.function(name).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+fn fingerprint(value: impl Hash) -> u64 {
+    let mut hasher = Fnv::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 64-bit FNV-1a; deterministic and available without `std`.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
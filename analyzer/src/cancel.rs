@@ -0,0 +1,50 @@
+//! Cooperative cancellation for long-running analysis queries.
+//!
+//! Editors create a [`CancellationToken`], pass it to a `*_cancellable` entry point, and call
+//! [`CancellationToken::cancel`] (e.g. from another thread or a newer keystroke) when the result is
+//! no longer wanted. Queries check the token at safe points and return [`Cancelled`].
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Shared cancellation flag. Clones observe the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of every query holding this token (or a clone of it).
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns `Err(Cancelled)` once cancellation was requested.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A query stopped early because its [`CancellationToken`] was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl core::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("query cancelled")
+    }
+}
+
+impl core::error::Error for Cancelled {}
@@ -0,0 +1,9 @@
+//! Map and set types for the core.
+//!
+//! With `std` these are the std hash collections. `no_std` builds use the ordered `alloc`
+//! collections instead, which need no hasher and no source of randomness.
+
+#[cfg(not(feature = "std"))]
+pub(crate) use alloc::collections::{BTreeMap as Map, BTreeSet as Set};
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{HashMap as Map, HashSet as Set};
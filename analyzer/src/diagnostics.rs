@@ -1,12 +1,28 @@
 use crate::lexer::Span;
+use crate::prelude::*;
 use crate::source_map::SourceMap;
 use crate::text_edit::TextEdit;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+/// Serializes as its [`as_str`](Self::as_str) name.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DiagnosticCode {
     LexError,
     SemanticError,
     Parse(ParseDiagnostic),
+    /// A tool bug was caught before it could corrupt output (e.g. formatter verification).
+    InternalError,
+    /// A `prop("...")` name that matches a property only after trimming and case folding
+    /// ([`PropertyLookup::Lenient`](crate::semantic::PropertyLookup::Lenient)); a warning.
+    InexactPropertyName,
+    /// Longer than [`FormulaLimits::max_length`](crate::semantic::FormulaLimits::max_length).
+    FormulaTooLong,
+    /// Nested deeper than [`FormulaLimits::max_depth`](crate::semantic::FormulaLimits::max_depth).
+    NestingTooDeep,
+    /// A `prop("...")` reading a formula that (through other formulas) reads this one, reported
+    /// by workspaces holding several formulas.
+    CircularReference,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,8 +36,45 @@ pub enum ParseDiagnostic {
 }
 
 impl DiagnosticCode {
+    /// Every code, e.g. for hosts that list or parse them.
+    pub const ALL: [DiagnosticCode; 13] = [
+        DiagnosticCode::LexError,
+        DiagnosticCode::SemanticError,
+        DiagnosticCode::InternalError,
+        DiagnosticCode::InexactPropertyName,
+        DiagnosticCode::FormulaTooLong,
+        DiagnosticCode::NestingTooDeep,
+        DiagnosticCode::CircularReference,
+        DiagnosticCode::Parse(ParseDiagnostic::UnclosedDelimiter),
+        DiagnosticCode::Parse(ParseDiagnostic::MismatchedDelimiter),
+        DiagnosticCode::Parse(ParseDiagnostic::MissingComma),
+        DiagnosticCode::Parse(ParseDiagnostic::TrailingComma),
+        DiagnosticCode::Parse(ParseDiagnostic::MissingExpr),
+        DiagnosticCode::Parse(ParseDiagnostic::UnexpectedToken),
+    ];
+
+    /// Stable snake_case name for reports and tooling (parse codes use the variant name alone).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DiagnosticCode::LexError => "lex_error",
+            DiagnosticCode::SemanticError => "semantic_error",
+            DiagnosticCode::InternalError => "internal_error",
+            DiagnosticCode::InexactPropertyName => "inexact_property_name",
+            DiagnosticCode::FormulaTooLong => "formula_too_long",
+            DiagnosticCode::NestingTooDeep => "nesting_too_deep",
+            DiagnosticCode::CircularReference => "circular_reference",
+            DiagnosticCode::Parse(ParseDiagnostic::UnclosedDelimiter) => "unclosed_delimiter",
+            DiagnosticCode::Parse(ParseDiagnostic::MismatchedDelimiter) => "mismatched_delimiter",
+            DiagnosticCode::Parse(ParseDiagnostic::MissingComma) => "missing_comma",
+            DiagnosticCode::Parse(ParseDiagnostic::TrailingComma) => "trailing_comma",
+            DiagnosticCode::Parse(ParseDiagnostic::MissingExpr) => "missing_expr",
+            DiagnosticCode::Parse(ParseDiagnostic::UnexpectedToken) => "unexpected_token",
+        }
+    }
+
     pub fn priority(self) -> u8 {
         match self {
+            DiagnosticCode::InternalError => 110,
             DiagnosticCode::Parse(ParseDiagnostic::UnclosedDelimiter) => 100,
             DiagnosticCode::Parse(ParseDiagnostic::MismatchedDelimiter) => 95,
             DiagnosticCode::LexError => 90,
@@ -29,17 +82,34 @@ impl DiagnosticCode {
             DiagnosticCode::Parse(ParseDiagnostic::MissingExpr) => 70,
             DiagnosticCode::Parse(ParseDiagnostic::MissingComma) => 60,
             DiagnosticCode::Parse(ParseDiagnostic::TrailingComma) => 50,
+            DiagnosticCode::CircularReference => 30,
+            DiagnosticCode::FormulaTooLong | DiagnosticCode::NestingTooDeep => 20,
             DiagnosticCode::SemanticError => 10,
+            DiagnosticCode::InexactPropertyName => 5,
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DiagnosticKind {
     Error,
+    /// The formula works, but likely not as written (e.g. [`DiagnosticCode::InexactPropertyName`]).
+    Warning,
+}
+
+impl DiagnosticKind {
+    /// `"error"` or `"warning"`, as printed by [`format_diagnostics`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticKind::Error => "error",
+            DiagnosticKind::Warning => "warning",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Diagnostic {
     pub kind: DiagnosticKind,
     pub code: DiagnosticCode,
@@ -51,17 +121,37 @@ pub struct Diagnostic {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CodeAction {
     pub title: String,
     pub edits: Vec<TextEdit>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Label {
     pub span: Span,
     pub message: Option<String>,
 }
 
+#[cfg(feature = "serde")]
+impl Serialize for DiagnosticCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for DiagnosticCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        DiagnosticCode::ALL
+            .into_iter()
+            .find(|code| code.as_str() == name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown diagnostic code `{name}`")))
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct Diagnostics {
     pub diags: Vec<Diagnostic>,
@@ -105,6 +195,21 @@ impl Diagnostics {
         self.push(diag);
     }
 
+    /// Records a typed error (e.g. a `ParseError`) with extra labels and quick-fix actions.
+    pub fn report(
+        &mut self,
+        error: impl Into<Diagnostic>,
+        labels: Vec<Label>,
+        actions: Vec<CodeAction>,
+    ) {
+        let mut diag = error.into();
+        diag.labels.extend(labels);
+        diag.actions.extend(actions);
+        dedup_labels(&mut diag.labels);
+        dedup_actions(&mut diag.actions);
+        self.push(diag);
+    }
+
     fn push(&mut self, diag: Diagnostic) {
         let Some(existing_idx) = self.diags.iter().position(|d| d.span == diag.span) else {
             self.diags.push(diag);
@@ -133,9 +238,14 @@ impl Diagnostics {
     }
 }
 
-pub fn format_diagnostics(source: &str, mut diags: Vec<Diagnostic>) -> String {
-    use std::cmp::Reverse;
-    use std::fmt::Write;
+pub fn format_diagnostics(source: &str, diags: Vec<Diagnostic>) -> String {
+    format_diagnostics_for("<input>", source, diags)
+}
+
+/// Like [`format_diagnostics`], naming `path` instead of `<input>` in `-->` lines.
+pub fn format_diagnostics_for(path: &str, source: &str, mut diags: Vec<Diagnostic>) -> String {
+    use core::cmp::Reverse;
+    use core::fmt::Write;
 
     diags.sort_by(|a, b| {
         (
@@ -166,11 +276,11 @@ pub fn format_diagnostics(source: &str, mut diags: Vec<Diagnostic>) -> String {
         });
 
         let (line, col) = sm.line_col(d.span.start);
-        let _ = writeln!(&mut out, "error: {}", d.message);
+        let _ = writeln!(&mut out, "{}: {}", d.kind.as_str(), d.message);
         let _ = writeln!(
             &mut out,
-            "  --> <input>:{}:{} [{}..{}]",
-            line, col, d.span.start, d.span.end
+            "  --> {}:{}:{} [{}..{}]",
+            path, line, col, d.span.start, d.span.end
         );
         for label in labels {
             let (line, col) = sm.line_col(label.span.start);
@@ -192,9 +302,9 @@ pub fn format_diagnostics(source: &str, mut diags: Vec<Diagnostic>) -> String {
 }
 
 fn dedup_labels(labels: &mut Vec<Label>) {
-    use std::collections::HashSet;
+    use crate::collections::Set;
 
-    let mut seen = HashSet::new();
+    let mut seen = Set::new();
     labels.retain(|l| {
         let key = (
             l.span.start,
@@ -206,16 +316,16 @@ fn dedup_labels(labels: &mut Vec<Label>) {
 }
 
 fn dedup_notes(notes: &mut Vec<String>) {
-    use std::collections::HashSet;
+    use crate::collections::Set;
 
-    let mut seen = HashSet::new();
+    let mut seen = Set::new();
     notes.retain(|n| seen.insert(n.clone()));
 }
 
 fn dedup_actions(actions: &mut Vec<CodeAction>) {
-    use std::collections::HashSet;
+    use crate::collections::Set;
 
-    let mut seen = HashSet::new();
+    let mut seen = Set::new();
     actions.retain(|action| {
         let key = (
             action.title.clone(),
@@ -0,0 +1,123 @@
+//! Typed lexer failures; [`Diagnostic`]s are built from them at the analysis boundary.
+
+use crate::diagnostics::{CodeAction, Diagnostic, DiagnosticCode, DiagnosticKind};
+use crate::prelude::*;
+use crate::text_edit::TextEdit;
+
+use super::{Lookalike, Span};
+
+/// A lexing failure. The lexer keeps going after each one, so a source can have several.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LexError {
+    /// A char that starts no token.
+    #[error("unexpected char '{found}'")]
+    UnexpectedChar { span: Span, found: char },
+    /// A Unicode stand-in for ASCII, typically from pasted text (e.g. a smart quote); its
+    /// diagnostic carries the replacement as a quick fix.
+    #[error(
+        "unexpected {} {} ({})",
+        .lookalike.description,
+        shown(*.found),
+        hint(.lookalike)
+    )]
+    LookalikeChar {
+        span: Span,
+        found: char,
+        lookalike: Lookalike,
+    },
+    /// The first half of a two-char operator, such as a lone `=` for `==`. The lexer goes on as
+    /// if `expected` was written, and the diagnostic's quick fix inserts the missing char.
+    #[error("unexpected char '{found}' (did you mean '{expected}')")]
+    IncompleteOperator {
+        span: Span,
+        found: char,
+        expected: &'static str,
+    },
+    /// A `/*` without `*/`; the span runs to the end of the source.
+    #[error("unterminated block comment")]
+    UnterminatedBlockComment { span: Span },
+    /// A `"` without a closing quote; the span runs to the end of the source.
+    #[error("unterminated string literal")]
+    UnterminatedString { span: Span },
+    /// An escape other than `\n`, `\t`, `\"`, `\\`; it is kept verbatim in the literal.
+    #[error("invalid escape sequence '{escape}'")]
+    InvalidEscape { span: Span, escape: String },
+}
+
+impl LexError {
+    pub fn span(&self) -> Span {
+        match self {
+            LexError::UnexpectedChar { span, .. }
+            | LexError::LookalikeChar { span, .. }
+            | LexError::IncompleteOperator { span, .. }
+            | LexError::UnterminatedBlockComment { span }
+            | LexError::UnterminatedString { span }
+            | LexError::InvalidEscape { span, .. } => *span,
+        }
+    }
+}
+
+impl From<LexError> for Diagnostic {
+    fn from(error: LexError) -> Self {
+        let actions = match &error {
+            LexError::LookalikeChar {
+                span, lookalike, ..
+            } => vec![CodeAction {
+                title: match lookalike.ascii {
+                    "" => format!("Remove the {}", lookalike.description),
+                    " " => "Replace with a space".into(),
+                    "\n" => "Replace with a newline".into(),
+                    ascii => format!("Replace with '{ascii}'"),
+                },
+                edits: vec![TextEdit {
+                    range: *span,
+                    new_text: lookalike.ascii.into(),
+                }],
+            }],
+            LexError::IncompleteOperator { span, found, .. } => vec![CodeAction {
+                title: format!("Insert `{found}`"),
+                edits: vec![TextEdit {
+                    range: Span {
+                        start: span.end,
+                        end: span.end,
+                    },
+                    new_text: found.to_string(),
+                }],
+            }],
+            _ => vec![],
+        };
+        Diagnostic {
+            kind: DiagnosticKind::Error,
+            code: DiagnosticCode::LexError,
+            message: error.to_string(),
+            span: error.span(),
+            labels: vec![],
+            notes: vec![],
+            actions,
+        }
+    }
+}
+
+/// Visible chars quoted, invisible ones (spaces, zero-width) as code points.
+fn shown(ch: char) -> String {
+    if ch.is_whitespace()
+        || ch.is_control()
+        || matches!(
+            ch,
+            '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}'
+        )
+    {
+        format!("U+{:04X}", u32::from(ch))
+    } else {
+        format!("'{ch}'")
+    }
+}
+
+fn hint(lookalike: &Lookalike) -> String {
+    match lookalike.ascii {
+        "" => "remove it".into(),
+        " " => "did you mean a space".into(),
+        "\n" => "did you mean a newline".into(),
+        ascii => format!("did you mean '{ascii}'"),
+    }
+}
@@ -0,0 +1,198 @@
+//! Editor highlighting grammars derived from the lexer's token tables.
+//!
+//! [`textmate_grammar`] (VS Code, GitHub Linguist, Sublime Text) and [`highlightjs_language`]
+//! (documentation sites) are built from [`TokenKind::FIXED`], [`Keywords::NOTION`], and the
+//! builtin function names. `cargo run -p analyzer --bin export_grammars` writes them under
+//! [`GRAMMAR_DIR`]; a test fails when the files are stale.
+
+use serde_json::{Value, json};
+
+use super::{KeywordKind, Keywords, TokenKind};
+use crate::analysis::builtins_functions;
+use crate::prelude::*;
+
+/// Relative to the `analyzer` crate root.
+pub const GRAMMAR_DIR: &str = "grammars";
+pub const TEXTMATE_FILE: &str = "notion-formula.tmLanguage.json";
+pub const HIGHLIGHTJS_FILE: &str = "notion-formula.highlight.js";
+pub const SCOPE_NAME: &str = "source.notion-formula";
+
+/// Matches the lexer's number literals: `digits ('.' digits)? ([eE] [+-]? digits)?`.
+const NUMBER: &str = r"\b\d+(?:\.\d+)?(?:[eE][+-]?\d+)?";
+/// The escapes the lexer accepts in strings; other `\x` sequences are errors.
+const ESCAPE: &str = r#"\\[nt"\\]"#;
+
+/// How a fixed-text token is highlighted.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Operator,
+    Punctuation,
+    Bracket,
+}
+
+fn class(kind: &TokenKind) -> Class {
+    use TokenKind::*;
+    match kind {
+        Lt | Le | EqEq | Ne | Ge | Gt | AndAnd | OrOr | Bang | Plus | Minus | Star | Slash
+        | Percent | Caret | Question | Colon => Class::Operator,
+        Dot | Comma | Pound => Class::Punctuation,
+        OpenParen | CloseParen | OpenBracket | CloseBracket => Class::Bracket,
+        Not | Literal(_) | Ident(_) | DocComment(..) | Newline | Eof => {
+            unreachable!("{kind:?} has no fixed text")
+        }
+    }
+}
+
+/// A regex alternation of the fixed tokens in `class`, longest first.
+fn fixed_tokens(class_: Class) -> String {
+    let mut texts: Vec<&str> = TokenKind::FIXED
+        .iter()
+        .filter(|kind| class(kind) == class_)
+        .filter_map(TokenKind::to_str)
+        .collect();
+    texts.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    texts
+        .iter()
+        .map(|text| escape(text))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if r"\.^$|?*+()[]{}/".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// `prop` and the builtin function names, sorted.
+fn function_names() -> Vec<&'static str> {
+    let mut names: Vec<&str> = builtins_functions()
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    names.push("prop");
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// The constant and the operator words of [`Keywords::NOTION`].
+fn keyword_words() -> (Vec<&'static str>, Vec<&'static str>) {
+    (
+        Keywords::notion().words(KeywordKind::is_constant).collect(),
+        Keywords::notion()
+            .words(|kind| !kind.is_constant())
+            .collect(),
+    )
+}
+
+fn words(words: &[&str]) -> String {
+    format!(r"\b(?:{})\b", words.join("|"))
+}
+
+/// The TextMate grammar (`scopeName` [`SCOPE_NAME`]).
+pub fn textmate_grammar() -> Value {
+    let scope = |name: &str| format!("{name}.notion-formula");
+    let functions = function_names();
+    let (constants, keywords) = keyword_words();
+    json!({
+        "$schema": "https://raw.githubusercontent.com/martinring/tmlanguage/master/tmlanguage.json",
+        "name": "Notion Formula",
+        "scopeName": SCOPE_NAME,
+        "fileTypes": ["formula"],
+        "patterns": [
+            { "include": "#comments" },
+            { "include": "#strings" },
+            { "include": "#numbers" },
+            { "include": "#words" },
+            { "include": "#symbols" },
+        ],
+        "repository": {
+            "comments": {
+                "patterns": [
+                    { "name": scope("comment.line.double-slash"), "match": "//.*$" },
+                    { "name": scope("comment.block"), "begin": r"/\*", "end": r"\*/" },
+                ],
+            },
+            "strings": {
+                "name": scope("string.quoted.double"),
+                "begin": "\"",
+                "end": "\"",
+                "patterns": [
+                    { "name": scope("constant.character.escape"), "match": ESCAPE },
+                    { "name": scope("invalid.illegal.escape"), "match": r"\\." },
+                ],
+            },
+            "numbers": { "name": scope("constant.numeric"), "match": NUMBER },
+            "words": {
+                "patterns": [
+                    { "name": scope("constant.language.boolean"), "match": words(&constants) },
+                    { "name": scope("keyword.operator.word"), "match": words(&keywords) },
+                    {
+                        "name": scope("support.function.builtin"),
+                        "match": format!(r"{}(?=\s*\()", words(&functions)),
+                    },
+                ],
+            },
+            "symbols": {
+                "patterns": [
+                    { "name": scope("keyword.operator"), "match": fixed_tokens(Class::Operator) },
+                    {
+                        "name": scope("punctuation.separator"),
+                        "match": fixed_tokens(Class::Punctuation),
+                    },
+                    { "name": scope("punctuation.section"), "match": fixed_tokens(Class::Bracket) },
+                ],
+            },
+        },
+    })
+}
+
+/// The highlight.js language module: `hljs.registerLanguage("notion-formula", notionFormula)`.
+pub fn highlightjs_language() -> String {
+    let (constants, keywords) = keyword_words();
+    let definition = json!({
+        "name": "Notion Formula",
+        "aliases": ["notion-formula", "formula"],
+        "keywords": {
+            "keyword": keywords.join(" "),
+            "literal": constants.join(" "),
+            "built_in": function_names().join(" "),
+        },
+        "contains": [
+            { "scope": "comment", "begin": "//", "end": "$" },
+            { "scope": "comment", "begin": r"/\*", "end": r"\*/" },
+            {
+                "scope": "string",
+                "begin": "\"",
+                "end": "\"",
+                "contains": [{ "scope": "char.escape", "match": ESCAPE }],
+            },
+            { "scope": "number", "match": NUMBER, "relevance": 0 },
+            { "scope": "operator", "match": fixed_tokens(Class::Operator), "relevance": 0 },
+            {
+                "scope": "punctuation",
+                "match": format!(
+                    "{}|{}",
+                    fixed_tokens(Class::Punctuation),
+                    fixed_tokens(Class::Bracket)
+                ),
+                "relevance": 0,
+            },
+        ],
+    });
+    let body = serde_json::to_string_pretty(&definition)
+        .expect("definition serializes")
+        .replace('\n', "\n  ");
+    format!(
+        "// Generated by `just gen-grammars` from the analyzer's token tables; do not edit.\n\
+         \n\
+         /** highlight.js language: `hljs.registerLanguage(\"notion-formula\", notionFormula)`. */\n\
+         export default function notionFormula() {{\n  return {body};\n}}\n"
+    )
+}
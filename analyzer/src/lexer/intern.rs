@@ -0,0 +1,163 @@
+//! Interned symbol text.
+//!
+//! [`Atom`] is a shared `str`: clones are a reference-count bump, and every atom built through
+//! [`Atom::new`] on a thread shares one allocation per distinct text. Names like `prop` and
+//! property labels are lexed again on every keystroke, so the interner outlives a single
+//! [`lex`](super::lex) call; it is cleared once it holds [`MAX_INTERNED`] texts, which only
+//! costs sharing with atoms created before the reset.
+//!
+//! The interner is thread-local, so it needs `std`. Without it every atom owns its text.
+
+use alloc::sync::Arc;
+use core::borrow::Borrow;
+use core::fmt;
+use core::ops::Deref;
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+
+#[cfg(feature = "std")]
+use crate::collections::Set;
+use crate::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Interner size that triggers a reset, bounding memory in long editor sessions.
+#[cfg(feature = "std")]
+const MAX_INTERNED: usize = 4096;
+
+#[cfg(feature = "std")]
+thread_local! {
+    static INTERNER: RefCell<Set<Arc<str>>> = RefCell::new(Set::new());
+}
+
+/// Immutable, cheaply cloned text. Derefs to `str` and compares equal to string types.
+/// Serializes as a plain string; deserializing interns it.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "String", from = "String"))]
+pub struct Atom(Arc<str>);
+
+impl Atom {
+    /// Interns `text`, reusing the allocation of an equal atom made earlier on this thread.
+    #[cfg(feature = "std")]
+    pub fn new(text: &str) -> Self {
+        INTERNER.with(|interner| {
+            let mut interner = interner.borrow_mut();
+            if let Some(existing) = interner.get(text) {
+                return Atom(existing.clone());
+            }
+            if interner.len() >= MAX_INTERNED {
+                interner.clear();
+            }
+            let text: Arc<str> = Arc::from(text);
+            interner.insert(text.clone());
+            Atom(text)
+        })
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub fn new(text: &str) -> Self {
+        Atom(Arc::from(text))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether both atoms share one allocation (always true for equal atoms interned together).
+    pub fn ptr_eq(a: &Atom, b: &Atom) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+}
+
+impl Deref for Atom {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Atom {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Atom {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(text: &str) -> Self {
+        Atom::new(text)
+    }
+}
+
+impl From<String> for Atom {
+    fn from(text: String) -> Self {
+        Atom::new(&text)
+    }
+}
+
+impl From<&Atom> for String {
+    fn from(atom: &Atom) -> Self {
+        atom.0.to_string()
+    }
+}
+
+impl From<Atom> for String {
+    fn from(atom: Atom) -> Self {
+        atom.0.to_string()
+    }
+}
+
+impl PartialEq<str> for Atom {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Atom {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Atom {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<Atom> for str {
+    fn eq(&self, other: &Atom) -> bool {
+        self == &*other.0
+    }
+}
+
+impl PartialEq<Atom> for &str {
+    fn eq(&self, other: &Atom) -> bool {
+        *self == &*other.0
+    }
+}
+
+impl PartialEq<Atom> for String {
+    fn eq(&self, other: &Atom) -> bool {
+        **self == *other.0
+    }
+}
+
+impl fmt::Debug for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
@@ -0,0 +1,147 @@
+//! The reserved word table: which identifiers lex as keywords or constants instead of names.
+
+use alloc::borrow::Cow;
+
+use super::{Lit, LitKind, Symbol, TokenKind};
+
+/// What a reserved word lexes as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeywordKind {
+    /// Prefix negation, [`TokenKind::Not`].
+    Not,
+    /// Infix `&&`, [`TokenKind::AndAnd`].
+    And,
+    /// Infix `||`, [`TokenKind::OrOr`].
+    Or,
+    /// A [`LitKind::Bool`] literal; `true`/`false` are the only texts later phases read.
+    Bool,
+}
+
+impl KeywordKind {
+    /// The token for `word`, a reserved word of this kind.
+    pub fn token_kind(self, word: &str) -> TokenKind {
+        match self {
+            KeywordKind::Not => TokenKind::Not,
+            KeywordKind::And => TokenKind::AndAnd,
+            KeywordKind::Or => TokenKind::OrOr,
+            KeywordKind::Bool => TokenKind::Literal(Lit {
+                kind: LitKind::Bool,
+                symbol: Symbol::new(word),
+            }),
+        }
+    }
+
+    /// Literal constants, as opposed to operator keywords.
+    pub fn is_constant(self) -> bool {
+        self == KeywordKind::Bool
+    }
+
+    /// Words that can start an expression (`not`, constants), as opposed to infix ones.
+    pub fn begins_expr(self) -> bool {
+        matches!(self, KeywordKind::Not | KeywordKind::Bool)
+    }
+}
+
+/// A reserved word and what it lexes as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keyword {
+    pub word: Cow<'static, str>,
+    pub kind: KeywordKind,
+}
+
+/// The reserved words of a formula dialect, in display order.
+///
+/// [`lex`](super::lex) and editor features use [`Keywords::NOTION`]; the grammars in
+/// [`grammar`](super::grammar) are generated from it. [`lex_with`](super::lex_with) takes any
+/// table, e.g. one that also spells `&&` and `||` as words:
+///
+/// ```
+/// use analyzer::{KeywordKind, Keywords, TokenKind, lex_with};
+///
+/// let keywords = Keywords::NOTION
+///     .with("and", KeywordKind::And)
+///     .with("or", KeywordKind::Or);
+/// let tokens = lex_with("true and not false", &keywords).tokens;
+/// assert_eq!(tokens[1].kind, TokenKind::AndAnd);
+/// assert_eq!(tokens[2].kind, TokenKind::Not);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keywords {
+    entries: Cow<'static, [Keyword]>,
+}
+
+const fn keyword(word: &'static str, kind: KeywordKind) -> Keyword {
+    Keyword {
+        word: Cow::Borrowed(word),
+        kind,
+    }
+}
+
+impl Keywords {
+    /// Notion's words: `not`, `true`, and `false`.
+    pub const NOTION: Keywords = Keywords {
+        entries: Cow::Borrowed(&[
+            keyword("not", KeywordKind::Not),
+            keyword("true", KeywordKind::Bool),
+            keyword("false", KeywordKind::Bool),
+        ]),
+    };
+
+    /// [`Keywords::NOTION`], borrowed for `'static`.
+    pub fn notion() -> &'static Keywords {
+        static NOTION: Keywords = Keywords::NOTION;
+        &NOTION
+    }
+
+    /// A table without reserved words.
+    pub const fn empty() -> Self {
+        Keywords {
+            entries: Cow::Borrowed(&[]),
+        }
+    }
+
+    /// Adds `word`, replacing its previous kind if it was already reserved.
+    pub fn with(mut self, word: impl Into<Cow<'static, str>>, kind: KeywordKind) -> Self {
+        let word = word.into();
+        let entries = self.entries.to_mut();
+        match entries.iter_mut().find(|entry| entry.word == word) {
+            Some(entry) => entry.kind = kind,
+            None => entries.push(Keyword { word, kind }),
+        }
+        self
+    }
+
+    /// Removes `word`, which then lexes as an identifier.
+    pub fn without(mut self, word: &str) -> Self {
+        if self.get(word).is_some() {
+            self.entries.to_mut().retain(|entry| entry.word != word);
+        }
+        self
+    }
+
+    /// The kind of `word`, or `None` for identifiers. Case-sensitive.
+    pub fn get(&self, word: &str) -> Option<KeywordKind> {
+        self.entries
+            .iter()
+            .find(|entry| entry.word == word)
+            .map(|entry| entry.kind)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Keyword> {
+        self.entries.iter()
+    }
+
+    /// The words whose kind satisfies `filter`.
+    pub fn words(&self, filter: impl Fn(KeywordKind) -> bool) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(move |entry| filter(entry.kind))
+            .map(|entry| entry.word.as_ref())
+    }
+}
+
+impl Default for Keywords {
+    fn default() -> Self {
+        Keywords::NOTION
+    }
+}
@@ -0,0 +1,52 @@
+//! Unicode characters that text pasted from documents and chat apps uses in place of ASCII:
+//! smart quotes, non-breaking spaces, dashes for minus, full-width punctuation.
+
+/// The ASCII a pasted Unicode character stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lookalike {
+    /// The replacement: usually one char, `"!="`/`"<="`/`">="` for `≠`/`≤`/`≥`, and empty for
+    /// zero-width characters.
+    pub ascii: &'static str,
+    /// What the character is, e.g. `"smart quote"`, for messages.
+    pub description: &'static str,
+}
+
+/// Printable ASCII, `!` to `~`, in the order of the full-width block U+FF01..U+FF5E.
+const PRINTABLE_ASCII: &str = concat!(
+    "!\"#$%&'()*+,-./0123456789:;<=>?@",
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`",
+    "abcdefghijklmnopqrstuvwxyz{|}~",
+);
+
+/// The ASCII that `ch` stands in for, if it is a known lookalike.
+///
+/// Full-width letters are not lookalikes: they are valid identifier chars.
+pub fn ascii_lookalike(ch: char) -> Option<Lookalike> {
+    let (ascii, description) = match ch {
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' | '\u{00AB}'
+        | '\u{00BB}' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => {
+            ("\"", "smart quote")
+        }
+        '\u{00A0}' | '\u{202F}' => (" ", "non-breaking space"),
+        '\u{2000}'..='\u{200A}' | '\u{205F}' | '\u{3000}' | '\u{1680}' => (" ", "unicode space"),
+        '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}' => {
+            ("", "zero-width character")
+        }
+        '\u{2028}' | '\u{2029}' | '\u{0085}' => ("\n", "line separator"),
+        '\u{2212}' => ("-", "minus sign"),
+        '\u{2013}' => ("-", "en dash"),
+        '\u{2014}' => ("-", "em dash"),
+        '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{FE63}' => ("-", "unicode hyphen"),
+        '\u{00D7}' | '\u{2217}' => ("*", "multiplication sign"),
+        '\u{00F7}' | '\u{2215}' => ("/", "division sign"),
+        '\u{2260}' => ("!=", "not-equal sign"),
+        '\u{2264}' => ("<=", "less-than-or-equal sign"),
+        '\u{2265}' => (">=", "greater-than-or-equal sign"),
+        '\u{FF01}'..='\u{FF5E}' if !ch.is_alphabetic() => {
+            let index = ch as usize - 0xFF01;
+            (&PRINTABLE_ASCII[index..index + 1], "full-width character")
+        }
+        _ => return None,
+    };
+    Some(Lookalike { ascii, description })
+}
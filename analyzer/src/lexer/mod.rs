@@ -1,16 +1,28 @@
-use crate::diagnostics::{Diagnostic, DiagnosticCode, DiagnosticKind};
-
+use crate::prelude::*;
+use crate::text_edit::TextEdit;
+
+mod error;
+#[cfg(feature = "serde")]
+pub mod grammar;
+mod intern;
+mod keywords;
+mod lookalike;
 mod token;
 
 pub use crate::span::{Span, Spanned};
+pub use error::LexError;
+pub use intern::Atom;
+pub use keywords::{Keyword, KeywordKind, Keywords};
+pub use lookalike::{ascii_lookalike, Lookalike};
 pub use token::{
-    tokens_in_span, CommentKind, Lit, LitKind, NodeId, Symbol, Token, TokenIdx, TokenKind,
-    TokenRange,
+    non_trivia, tokens_in_span, CommentKind, Lit, LitKind, NodeId, Symbol, Token, TokenIdx,
+    TokenKind, TokenRange, BOOL_LITERALS, KEYWORDS,
 };
 
 pub struct LexOutput {
     pub tokens: Vec<Token>,
-    pub diagnostics: Vec<Diagnostic>,
+    /// In source order; each converts into a [`Diagnostic`](crate::Diagnostic).
+    pub errors: Vec<LexError>,
 }
 
 /// Lex the input into tokens.
@@ -19,15 +31,45 @@ pub struct LexOutput {
 ///   Dot is consumed as decimal only when followed by a digit (`3.14` is one token,
 ///   `3.method()` stays as three tokens: `3`, `.`, `method`).
 /// - Strings: double-quoted with escapes: `\n`, `\t`, `\"`, `\\`.
-///   Invalid escapes emit a diagnostic but are kept verbatim.
+///   Invalid escapes report a [`LexError`] but are kept verbatim.
 /// - Identifiers: `_` or Unicode letter (`is_alphabetic`), followed by `_` or
-///   Unicode alphanumeric (`is_alphanumeric`).
+///   Unicode alphanumeric (`is_alphanumeric`). Reserved words ([`Keywords::NOTION`]) lex as
+///   keywords or literals instead.
 pub fn lex(input: &str) -> LexOutput {
-    let mut tokens = Vec::new();
-    let mut diagnostics = Vec::new();
-    let mut iter = input.char_indices().peekable();
+    lex_with(input, &Keywords::NOTION)
+}
+
+/// Like [`lex`], with the reserved words of `keywords`.
+pub fn lex_with(input: &str, keywords: &Keywords) -> LexOutput {
+    lex_from(input, 0, keywords, &mut |_| false)
+}
 
+/// Lexes `input` from byte offset `from`, which must be between tokens.
+///
+/// Stops right after the first token for which `stop` returns `true`; that token is the last one
+/// in the output and no `Eof` is added. Spans are offsets into the whole `input`.
+pub(crate) fn lex_from(
+    input: &str,
+    from: usize,
+    keywords: &Keywords,
+    stop: &mut dyn FnMut(&Token) -> bool,
+) -> LexOutput {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut errors = Vec::new();
+    let mut iter = input[from..]
+        .char_indices()
+        .map(|(i, c)| (i + from, c))
+        .peekable();
+
+    let mut checked = 0;
     while let Some((start, ch)) = iter.next() {
+        if tokens.len() > checked {
+            checked = tokens.len();
+            if stop(&tokens[checked - 1]) {
+                return LexOutput { tokens, errors };
+            }
+        }
+
         // Skip spaces/tabs but keep newlines as trivia tokens.
         if matches!(ch, ' ' | '\t' | '\r') {
             continue;
@@ -68,14 +110,8 @@ pub fn lex(input: &str) -> LexOutput {
                     let (_, _) = iter.next().unwrap();
                     TokenKind::EqEq
                 } else {
-                    diagnostics.push(make_error(
-                        Span {
-                            start: start as u32,
-                            end: (start + 1) as u32,
-                        },
-                        "unexpected char '=' (did you mean '==')".to_string(),
-                    ));
-                    break;
+                    recover_incomplete_operator(&mut tokens, &mut errors, start, TokenKind::EqEq);
+                    continue;
                 }
             }
             '!' => {
@@ -91,14 +127,8 @@ pub fn lex(input: &str) -> LexOutput {
                     let (_, _) = iter.next().unwrap();
                     TokenKind::AndAnd
                 } else {
-                    diagnostics.push(make_error(
-                        Span {
-                            start: start as u32,
-                            end: (start + 1) as u32,
-                        },
-                        "unexpected char '&' (did you mean '&&')".to_string(),
-                    ));
-                    break;
+                    recover_incomplete_operator(&mut tokens, &mut errors, start, TokenKind::AndAnd);
+                    continue;
                 }
             }
             '|' => {
@@ -106,14 +136,8 @@ pub fn lex(input: &str) -> LexOutput {
                     let (_, _) = iter.next().unwrap();
                     TokenKind::OrOr
                 } else {
-                    diagnostics.push(make_error(
-                        Span {
-                            start: start as u32,
-                            end: (start + 1) as u32,
-                        },
-                        "unexpected char '|' (did you mean '||')".to_string(),
-                    ));
-                    break;
+                    recover_incomplete_operator(&mut tokens, &mut errors, start, TokenKind::OrOr);
+                    continue;
                 }
             }
 
@@ -136,9 +160,7 @@ pub fn lex(input: &str) -> LexOutput {
                     tokens.push(Token {
                         kind: TokenKind::DocComment(
                             CommentKind::Line,
-                            Symbol {
-                                text: String::from(&input[start + 2..end]),
-                            },
+                            Symbol::new(&input[start + 2..end]),
                         ),
                         span: Span {
                             start: start as u32,
@@ -164,22 +186,19 @@ pub fn lex(input: &str) -> LexOutput {
                     }
 
                     if !terminated {
-                        diagnostics.push(make_error(
-                            Span {
+                        errors.push(LexError::UnterminatedBlockComment {
+                            span: Span {
                                 start: start as u32,
                                 end: input.len() as u32,
                             },
-                            "unterminated block comment".to_string(),
-                        ));
+                        });
                         break;
                     }
 
                     tokens.push(Token {
                         kind: TokenKind::DocComment(
                             CommentKind::Block,
-                            Symbol {
-                                text: String::from(&input[content_start..end - 2]),
-                            },
+                            Symbol::new(&input[content_start..end - 2]),
                         ),
                         span: Span {
                             start: start as u32,
@@ -241,33 +260,27 @@ pub fn lex(input: &str) -> LexOutput {
                 let end = match end {
                     Some(end) => end,
                     None => {
-                        diagnostics.push(make_error(
-                            Span {
+                        errors.push(LexError::UnterminatedString {
+                            span: Span {
                                 start: start as u32,
                                 end: input.len() as u32,
                             },
-                            "unterminated string literal".to_string(),
-                        ));
+                        });
                         break;
                     }
                 };
 
                 for esc_span in invalid_escape_spans {
-                    diagnostics.push(make_error(
-                        esc_span,
-                        format!(
-                            "invalid escape sequence '{}'",
-                            &input[esc_span.start as usize..esc_span.end as usize]
-                        ),
-                    ));
+                    errors.push(LexError::InvalidEscape {
+                        span: esc_span,
+                        escape: input[esc_span.start as usize..esc_span.end as usize].to_string(),
+                    });
                 }
 
                 tokens.push(Token {
                     kind: TokenKind::Literal(Lit {
                         kind: LitKind::String,
-                        symbol: Symbol {
-                            text: String::from(&input[start..end]),
-                        },
+                        symbol: Symbol::new(&input[start..end]),
                     }),
                     span: Span {
                         start: start as u32,
@@ -302,57 +315,57 @@ pub fn lex(input: &str) -> LexOutput {
                     // Peek two chars ahead: need a digit after the dot.
                     let mut lookahead = iter.clone();
                     lookahead.next(); // consume '.'
-                    if let Some(&(_, c2)) = lookahead.peek() {
-                        if c2.is_ascii_digit() {
-                            iter.next(); // consume '.'
-                            end = dot_i + 1;
-                            while let Some(&(i, c2)) = iter.peek() {
-                                if c2.is_ascii_digit() {
-                                    iter.next();
-                                    end = i + c2.len_utf8();
-                                } else {
-                                    break;
-                                }
+                    if let Some(&(_, c2)) = lookahead.peek()
+                        && c2.is_ascii_digit()
+                    {
+                        iter.next(); // consume '.'
+                        end = dot_i + 1;
+                        while let Some(&(i, c2)) = iter.peek() {
+                            if c2.is_ascii_digit() {
+                                iter.next();
+                                end = i + c2.len_utf8();
+                            } else {
+                                break;
                             }
                         }
                     }
                 }
 
                 // Exponent part: 'e' or 'E', optional '+'/'-', then digits.
-                if let Some(&(_, ec)) = iter.peek() {
-                    if ec == 'e' || ec == 'E' {
-                        let mut lookahead = iter.clone();
-                        lookahead.next(); // consume 'e'/'E'
-
-                        let mut has_digits = false;
-                        if let Some(&(_, sc)) = lookahead.peek() {
-                            if sc == '+' || sc == '-' {
-                                lookahead.next(); // consume sign
-                            }
-                            if let Some(&(_, dc)) = lookahead.peek() {
-                                has_digits = dc.is_ascii_digit();
-                            }
+                if let Some(&(_, ec)) = iter.peek()
+                    && (ec == 'e' || ec == 'E')
+                {
+                    let mut lookahead = iter.clone();
+                    lookahead.next(); // consume 'e'/'E'
+
+                    let mut has_digits = false;
+                    if let Some(&(_, sc)) = lookahead.peek() {
+                        if sc == '+' || sc == '-' {
+                            lookahead.next(); // consume sign
+                        }
+                        if let Some(&(_, dc)) = lookahead.peek() {
+                            has_digits = dc.is_ascii_digit();
                         }
+                    }
 
-                        if has_digits {
-                            // Commit: consume e/E
-                            let (ei, _) = iter.next().unwrap();
-                            end = ei + 1;
-                            // Consume optional sign
-                            if let Some(&(si, sc)) = iter.peek() {
-                                if sc == '+' || sc == '-' {
-                                    iter.next();
-                                    end = si + 1;
-                                }
-                            }
-                            // Consume exponent digits
-                            while let Some(&(i, c2)) = iter.peek() {
-                                if c2.is_ascii_digit() {
-                                    iter.next();
-                                    end = i + c2.len_utf8();
-                                } else {
-                                    break;
-                                }
+                    if has_digits {
+                        // Commit: consume e/E
+                        let (ei, _) = iter.next().unwrap();
+                        end = ei + 1;
+                        // Consume optional sign
+                        if let Some(&(si, sc)) = iter.peek()
+                            && (sc == '+' || sc == '-')
+                        {
+                            iter.next();
+                            end = si + 1;
+                        }
+                        // Consume exponent digits
+                        while let Some(&(i, c2)) = iter.peek() {
+                            if c2.is_ascii_digit() {
+                                iter.next();
+                                end = i + c2.len_utf8();
+                            } else {
+                                break;
                             }
                         }
                     }
@@ -361,9 +374,7 @@ pub fn lex(input: &str) -> LexOutput {
                 tokens.push(Token {
                     kind: TokenKind::Literal(Lit {
                         kind: LitKind::Number,
-                        symbol: Symbol {
-                            text: String::from(&input[start..end]),
-                        },
+                        symbol: Symbol::new(&input[start..end]),
                     }),
                     span: Span {
                         start: start as u32,
@@ -375,12 +386,9 @@ pub fn lex(input: &str) -> LexOutput {
 
             c if is_ident_start(c) => {
                 let mut end = start + c.len_utf8();
-                let mut ident = String::new();
-                ident.push(c);
 
                 while let Some(&(i, ch2)) = iter.peek() {
                     if is_ident_continue(ch2) {
-                        ident.push(ch2);
                         iter.next();
                         end = i + ch2.len_utf8();
                     } else {
@@ -388,14 +396,10 @@ pub fn lex(input: &str) -> LexOutput {
                     }
                 }
 
-                let kind = match ident.as_str() {
-                    // Reserved keywords.
-                    "not" => TokenKind::Not,
-                    "true" | "false" => TokenKind::Literal(Lit {
-                        kind: LitKind::Bool,
-                        symbol: Symbol { text: ident },
-                    }),
-                    _ => TokenKind::Ident(Symbol { text: ident }),
+                let ident = &input[start..end];
+                let kind = match keywords.get(ident) {
+                    Some(keyword) => keyword.token_kind(ident),
+                    None => TokenKind::Ident(Symbol::new(ident)),
                 };
 
                 tokens.push(Token {
@@ -409,13 +413,18 @@ pub fn lex(input: &str) -> LexOutput {
             }
 
             _ => {
-                diagnostics.push(make_error(
-                    Span {
-                        start: start as u32,
-                        end: (start + ch.len_utf8()) as u32,
+                let span = Span {
+                    start: start as u32,
+                    end: (start + ch.len_utf8()) as u32,
+                };
+                errors.push(match ascii_lookalike(ch) {
+                    Some(lookalike) => LexError::LookalikeChar {
+                        span,
+                        found: ch,
+                        lookalike,
                     },
-                    format!("unexpected char '{}'", ch),
-                ));
+                    None => LexError::UnexpectedChar { span, found: ch },
+                });
                 break;
             }
         };
@@ -448,10 +457,113 @@ pub fn lex(input: &str) -> LexOutput {
         },
     });
 
-    LexOutput {
-        tokens,
-        diagnostics,
+    LexOutput { tokens, errors }
+}
+
+/// Bytes the lexer may peek past a token's end (`1` + `e+5`), so tokens ending that close to an edit are re-lexed.
+const MAX_LOOKAHEAD: u32 = 3;
+
+/// Tokens for `new_text`, the result of applying `edit` to the text `old_tokens` came from.
+///
+/// Tokens more than [`MAX_LOOKAHEAD`] bytes before the edit are kept. Lexing restarts in front
+/// of the edit and stops at the first token past it that starts where an old token started;
+/// from there on the text and lexer state match, so the remaining old tokens are shifted and
+/// reused. `old_tokens` must come from a lex without errors. Returns `None` when lexing near
+/// the edit reports an error, so callers lex everything and get every diagnostic: an unexpected
+/// char or unterminated literal stops the lexer, and even a recovered error (an invalid escape,
+/// or the incomplete operator [`recover_incomplete_operator`] stands in for) is not in
+/// `old_tokens`. Reserved words are those of [`Keywords::NOTION`].
+pub(crate) fn relex(new_text: &str, old_tokens: &[Token], edit: &TextEdit) -> Option<Vec<Token>> {
+    let old_len = old_tokens.last()?.span.end;
+    let delta = new_text.len() as i64 - old_len as i64;
+    let shift = |offset: u32| (offset as i64 + delta) as u32;
+    let new_edit_end = edit.range.start + edit.new_text.len() as u32;
+
+    let kept = old_tokens
+        .iter()
+        .take_while(|token| {
+            token.kind != TokenKind::Eof && token.span.end + MAX_LOOKAHEAD <= edit.range.start
+        })
+        .count();
+    let from = old_tokens[kept].span.start.min(edit.range.start);
+
+    let mut resume = None;
+    let relexed = lex_from(new_text, from as usize, &Keywords::NOTION, &mut |token| {
+        if token.span.start < new_edit_end {
+            return false;
+        }
+        let old_start = (token.span.start as i64 - delta) as u32;
+        let idx = old_tokens.partition_point(|old| old.span.start < old_start);
+        let synced = old_tokens
+            .get(idx)
+            .is_some_and(|old| old.span.start == old_start && old.kind != TokenKind::Eof);
+        if synced {
+            resume = Some(idx);
+        }
+        synced
+    });
+    if !relexed.errors.is_empty() {
+        return None;
     }
+
+    let mut tokens = old_tokens[..kept].to_vec();
+    tokens.extend(relexed.tokens);
+    if let Some(idx) = resume
+        && tokens.last().is_some_and(|last| last.kind != TokenKind::Eof)
+    {
+        // The synced token is re-lexed already; reuse everything after it.
+        tokens.extend(old_tokens[idx + 1..].iter().map(|token| Token {
+            kind: token.kind.clone(),
+            span: Span {
+                start: shift(token.span.start),
+                end: shift(token.span.end),
+            },
+        }));
+    }
+    Some(tokens)
+}
+
+/// Quotes `text` as a string literal that [`lex`] reads back as `text`.
+pub fn string_literal(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A lone `=`, `&`, or `|`: reports it and lexes it as the `==`, `&&`, or `||` it most likely
+/// meant, spanning the one char, so the rest of the source still parses and is analyzed.
+fn recover_incomplete_operator(
+    tokens: &mut Vec<Token>,
+    errors: &mut Vec<LexError>,
+    start: usize,
+    kind: TokenKind,
+) {
+    let span = Span {
+        start: start as u32,
+        end: (start + 1) as u32,
+    };
+    let (found, expected) = match kind {
+        TokenKind::EqEq => ('=', "=="),
+        TokenKind::AndAnd => ('&', "&&"),
+        TokenKind::OrOr => ('|', "||"),
+        _ => unreachable!("only two-char operators have a first half"),
+    };
+    errors.push(LexError::IncompleteOperator {
+        span,
+        found,
+        expected,
+    });
+    tokens.push(Token { kind, span });
 }
 
 fn is_ident_start(c: char) -> bool {
@@ -461,15 +573,3 @@ fn is_ident_start(c: char) -> bool {
 fn is_ident_continue(c: char) -> bool {
     c == '_' || c.is_alphanumeric()
 }
-
-fn make_error(span: Span, message: String) -> Diagnostic {
-    Diagnostic {
-        kind: DiagnosticKind::Error,
-        code: DiagnosticCode::LexError,
-        message,
-        span,
-        labels: vec![],
-        notes: vec![],
-        actions: vec![],
-    }
-}
@@ -4,13 +4,28 @@
 //! The lexer also emits a [`TokenKind::Eof`] token with an empty span at end of input.
 
 use crate::Span;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::intern::Atom;
 
 pub type NodeId = u32;
 pub type TokenIdx = u32;
 
+/// Identifier, literal, or comment text. Cloning shares the interned [`Atom`].
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Symbol {
-    pub text: String,
+    pub text: Atom,
+}
+
+impl Symbol {
+    pub fn new(text: &str) -> Self {
+        Symbol {
+            text: Atom::new(text),
+        }
+    }
 }
 
 /// Half-open range of token indices: `[lo, hi)`.
@@ -27,6 +42,7 @@ impl TokenRange {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum LitKind {
     Bool,
     Number,
@@ -34,18 +50,21 @@ pub enum LitKind {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Lit {
     pub kind: LitKind,
     pub symbol: Symbol,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CommentKind {
     Line,
     Block,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TokenKind {
     /* Expression-operator symbols. */
     /// `<`
@@ -119,6 +138,7 @@ pub enum TokenKind {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// A token with its source span.
 ///
 /// `span` is a byte offset range into the original source (`[start, end)`).
@@ -146,7 +166,42 @@ impl Token {
     }
 }
 
+/// The operator words of [`Keywords::NOTION`](super::Keywords::NOTION): `not` lexes as
+/// [`TokenKind::Not`].
+pub const KEYWORDS: &[&str] = &["not"];
+
+/// The [`LitKind::Bool`] words of [`Keywords::NOTION`](super::Keywords::NOTION).
+pub const BOOL_LITERALS: &[&str] = &["true", "false"];
+
 impl TokenKind {
+    /// Every kind with fixed text ([`TokenKind::to_str`]), e.g. for generated grammars.
+    pub const FIXED: &'static [TokenKind] = &[
+        TokenKind::Lt,
+        TokenKind::Le,
+        TokenKind::EqEq,
+        TokenKind::Ne,
+        TokenKind::Ge,
+        TokenKind::Gt,
+        TokenKind::AndAnd,
+        TokenKind::OrOr,
+        TokenKind::Bang,
+        TokenKind::Plus,
+        TokenKind::Minus,
+        TokenKind::Star,
+        TokenKind::Slash,
+        TokenKind::Percent,
+        TokenKind::Caret,
+        TokenKind::Dot,
+        TokenKind::Comma,
+        TokenKind::Colon,
+        TokenKind::Pound,
+        TokenKind::Question,
+        TokenKind::OpenParen,
+        TokenKind::CloseParen,
+        TokenKind::OpenBracket,
+        TokenKind::CloseBracket,
+    ];
+
     pub fn is_trivia(&self) -> bool {
         matches!(self, TokenKind::DocComment(..) | TokenKind::Newline)
     }
@@ -197,6 +252,19 @@ impl TokenKind {
     }
 }
 
+/// The significant tokens of `tokens` with their indices: everything but trivia and `Eof`.
+///
+/// Borrows instead of collecting, so callers can scan forwards or backwards (`next_back`) without
+/// allocating. Indices are relative to `tokens`.
+pub fn non_trivia(
+    tokens: &[Token],
+) -> impl DoubleEndedIterator<Item = (usize, &Token)> + Clone + '_ {
+    tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| !token.is_trivia() && !matches!(token.kind, TokenKind::Eof))
+}
+
 /// Returns the token-index range `[lo, hi)` covered by `span`.
 ///
 /// `span` is a half-open byte range (UTF-8 offsets) into the original source: `[start, end)`.
@@ -1,19 +1,36 @@
 //! Core formula analyzer.
 //!
-//! Pipeline: lex → parse → analyze/format → completion.
+//! Pipeline: lex → parse → analyze. Formatting, completion, and signature help live in the `ide`
+//! crate on top of this one.
 //! All spans are UTF-8 byte offsets into the original source, using `[start, end)`.
-//! UTF-16 conversion for editors happens in `analyzer_wasm`.
-use crate::{lexer::lex, parser::Parser};
+//! [`SourceMap`] converts them to lines, columns, and UTF-16 offsets for editors.
+//!
+//! The `std` feature is on by default. Without it the crate is `no_std` and only needs `alloc`:
+//! maps fall back to ordered `alloc` collections, lazily built tables use a race-to-init cell, and
+//! [`Atom`]s are no longer shared across lexes.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use crate::parser::Parser;
+use crate::prelude::*;
 
 pub mod analysis;
+mod cache;
+mod cancel;
+mod collections;
 mod diagnostics;
 mod lexer;
 mod parser;
+mod prelude;
 mod source_map;
 mod span;
+mod sync;
 mod tests;
 mod text_edit;
+mod timing;
 
+pub use lexer::{Keyword, KeywordKind, Keywords, LexOutput, lex, lex_with, string_literal};
 pub use parser::ParseOutput;
 pub type SyntaxResult = ParseOutput;
 
@@ -25,18 +42,153 @@ pub struct AnalyzeResult {
 }
 
 pub fn analyze_syntax(text: &str) -> SyntaxResult {
+    analyze_syntax_with(text, &Keywords::NOTION)
+}
+
+/// Like [`analyze_syntax`], with the reserved words of `keywords` (see [`lex_with`]).
+pub fn analyze_syntax_with(text: &str, keywords: &Keywords) -> SyntaxResult {
+    let lex_output = lex_with(text, keywords);
+    let token_cursor = parser::TokenCursor::new(text, lex_output.tokens);
+    let mut parser = Parser::new(token_cursor);
+    let mut output = parser.parse();
+    output
+        .diagnostics
+        .extend(lex_output.errors.into_iter().map(Diagnostic::from));
+    output
+}
+
+/// Parses tokens that were already lexed from `text`, e.g. a prefix of a document's tokens.
+///
+/// `tokens` must end with an `Eof` token. Only parse diagnostics are reported.
+pub fn parse_tokens(text: &str, tokens: Vec<lexer::Token>) -> SyntaxResult {
+    let token_cursor = parser::TokenCursor::new(text, tokens);
+    Parser::new(token_cursor).parse()
+}
+
+/// Updates `old`, the syntax of a text, after `edit` turned that text into `new_text`.
+///
+/// Re-lexes only around the edit and, when the edit stays inside one call argument, list item,
+/// or parenthesized expression of an error-free formula, reparses only that expression. The
+/// result equals `analyze_syntax(new_text)`.
+pub fn reparse_syntax(old: SyntaxResult, new_text: &str, edit: &TextEdit) -> SyntaxResult {
+    parser::reparse(old, new_text, edit)
+}
+
+/// Like [`analyze_syntax`], also timing the lex and parse phases with `now`.
+pub fn analyze_syntax_timed(
+    text: &str,
+    now: &mut dyn FnMut() -> f64,
+) -> (SyntaxResult, PhaseTimings) {
+    let mut timer = PhaseTimer::new(now);
+    let output = analyze_syntax_instrumented(text, &mut timer);
+    (output, timer.timings)
+}
+
+/// Like [`analyze_syntax`], reporting the lex and parse phases and the `"tokens"` count to
+/// `instrumentation`.
+pub fn analyze_syntax_instrumented(
+    text: &str,
+    instrumentation: &mut dyn Instrumentation,
+) -> SyntaxResult {
+    let mut clock = PhaseClock::start(instrumentation, text.len());
     let lex_output = lex(text);
+    clock.lap(Phase::Lex);
+    clock.count("tokens", lex_output.tokens.len());
     let token_cursor = parser::TokenCursor::new(text, lex_output.tokens);
     let mut parser = Parser::new(token_cursor);
     let mut output = parser.parse();
-    output.diagnostics.extend(lex_output.diagnostics);
     output
+        .diagnostics
+        .extend(lex_output.errors.into_iter().map(Diagnostic::from));
+    clock.lap(Phase::Parse);
+    output
+}
+
+/// Like [`analyze_syntax`], but stops early once `cancel` is cancelled.
+pub fn analyze_syntax_cancellable(
+    text: &str,
+    cancel: &CancellationToken,
+) -> Result<SyntaxResult, Cancelled> {
+    cancel.check()?;
+    let lex_output = lex(text);
+    cancel.check()?;
+    let token_cursor = parser::TokenCursor::new(text, lex_output.tokens);
+    let mut parser = Parser::new(token_cursor).with_cancellation(cancel.clone());
+    let mut output = parser.parse();
+    cancel.check()?;
+    output
+        .diagnostics
+        .extend(lex_output.errors.into_iter().map(Diagnostic::from));
+    Ok(output)
+}
+
+/// Like [`analyze`], but stops early once `cancel` is cancelled.
+///
+/// The token is checked after lexing, during parsing, and between inference and validation.
+pub fn analyze_cancellable(
+    text: &str,
+    ctx: &analysis::Context,
+    cancel: &CancellationToken,
+) -> Result<AnalyzeResult, Cancelled> {
+    let mut syntax = analyze_syntax_cancellable(text, cancel)?;
+    let (output_type, sema_diags) = analysis::analyze_expr_cancellable(&syntax.expr, ctx, cancel)?;
+    syntax.diagnostics.extend(sema_diags);
+    let limit_diags = analysis::limit_diagnostics(text, &syntax.expr, &ctx.limits);
+    syntax.diagnostics.extend(limit_diags);
+
+    Ok(AnalyzeResult {
+        diagnostics: syntax.diagnostics,
+        tokens: syntax.tokens,
+        output_type,
+    })
 }
 
 pub fn analyze(text: &str, ctx: &analysis::Context) -> AnalyzeResult {
+    analyze_with_expr(text, ctx).1
+}
+
+/// [`analyze`], also returning the parsed expression the result was computed from.
+pub(crate) fn analyze_with_expr(text: &str, ctx: &analysis::Context) -> (ast::Expr, AnalyzeResult) {
     let mut syntax = analyze_syntax(text);
     let (output_type, sema_diags) = analysis::analyze_expr(&syntax.expr, ctx);
     syntax.diagnostics.extend(sema_diags);
+    let limit_diags = analysis::limit_diagnostics(text, &syntax.expr, &ctx.limits);
+    syntax.diagnostics.extend(limit_diags);
+
+    let result = AnalyzeResult {
+        diagnostics: syntax.diagnostics,
+        tokens: syntax.tokens,
+        output_type,
+    };
+    (syntax.expr, result)
+}
+
+/// Like [`analyze`], also timing the lex, parse, and infer phases with `now`.
+pub fn analyze_timed(
+    text: &str,
+    ctx: &analysis::Context,
+    now: &mut dyn FnMut() -> f64,
+) -> (AnalyzeResult, PhaseTimings) {
+    let mut timer = PhaseTimer::new(now);
+    let result = analyze_instrumented(text, ctx, &mut timer);
+    (result, timer.timings)
+}
+
+/// Like [`analyze`], reporting the lex, parse, and infer phases and the `"tokens"` and
+/// `"diagnostics"` counts to `instrumentation`.
+pub fn analyze_instrumented(
+    text: &str,
+    ctx: &analysis::Context,
+    instrumentation: &mut dyn Instrumentation,
+) -> AnalyzeResult {
+    let mut syntax = analyze_syntax_instrumented(text, instrumentation);
+    let mut clock = PhaseClock::start(instrumentation, text.len());
+    let (output_type, sema_diags) = analysis::analyze_expr(&syntax.expr, ctx);
+    syntax.diagnostics.extend(sema_diags);
+    let limit_diags = analysis::limit_diagnostics(text, &syntax.expr, &ctx.limits);
+    syntax.diagnostics.extend(limit_diags);
+    clock.lap(Phase::Infer);
+    clock.count("diagnostics", syntax.diagnostics.len());
 
     AnalyzeResult {
         diagnostics: syntax.diagnostics,
@@ -47,15 +199,21 @@ pub fn analyze(text: &str, ctx: &analysis::Context) -> AnalyzeResult {
 
 pub use analysis as semantic;
 pub use analysis::{ExprId, TypeMap, infer_expr_with_map};
-pub use diagnostics::format_diagnostics;
+pub use cache::{AnalysisCache, CacheStats};
+pub use cancel::{CancellationToken, Cancelled};
+pub use diagnostics::{format_diagnostics, format_diagnostics_for};
 pub use diagnostics::{
     CodeAction, Diagnostic, DiagnosticCode, DiagnosticKind, Diagnostics, ParseDiagnostic,
 };
-pub use lexer::Lit;
+pub use lexer::{LexError, Lit, Lookalike, ascii_lookalike};
+#[cfg(feature = "serde")]
+pub use lexer::grammar;
 pub use lexer::{CommentKind, LitKind, Token, TokenKind};
-pub use lexer::{NodeId, Symbol, TokenIdx, TokenRange, tokens_in_span};
-pub use parser::TokenQuery;
+pub use lexer::{BOOL_LITERALS, KEYWORDS};
+pub use lexer::{Atom, NodeId, Symbol, TokenIdx, TokenRange, non_trivia, tokens_in_span};
+pub use parser::{ParseError, TokenQuery};
 pub use parser::ast;
-pub use source_map::SourceMap;
+pub use source_map::{LineIndex, SourceMap, byte_to_utf16_offset, utf16_offset_to_byte};
 pub use span::{Span, Spanned};
 pub use text_edit::TextEdit;
+pub use timing::{Instrumentation, Phase, PhaseClock, PhaseTimer, PhaseTimings, Stopwatch};
@@ -1,3 +1,4 @@
+use crate::prelude::*;
 use crate::{
     Token, TokenKind,
     lexer::{Lit, NodeId, Span, Spanned, Symbol},
@@ -63,6 +64,28 @@ pub enum BinOpKind {
     Caret,
 }
 
+impl BinOpKind {
+    pub fn as_str(&self) -> &'static str {
+        use BinOpKind::*;
+        match self {
+            Lt => "<",
+            Le => "<=",
+            EqEq => "==",
+            Ne => "!=",
+            Ge => ">=",
+            Gt => ">",
+            AndAnd => "&&",
+            OrOr => "||",
+            Plus => "+",
+            Minus => "-",
+            Star => "*",
+            Slash => "/",
+            Percent => "%",
+            Caret => "^",
+        }
+    }
+}
+
 pub type BinOp = Spanned<BinOpKind>;
 
 impl BinOp {
@@ -0,0 +1,141 @@
+//! Typed parse failures; each becomes one [`Diagnostic`] with a [`ParseDiagnostic`] code.
+//!
+//! Token descriptions (`found`, `expected`) are pre-rendered, e.g. `` `)` `` or
+//! `end of input`. Recovery labels and quick fixes are attached when the diagnostic is emitted.
+
+use crate::diagnostics::{Diagnostic, DiagnosticCode, DiagnosticKind, ParseDiagnostic};
+use crate::prelude::*;
+
+use crate::lexer::Span;
+
+/// A parse failure. The parser recovers from each one, so a source can have several.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseError {
+    /// A token the grammar does not allow here.
+    #[error("expected {expected}, found {found}")]
+    UnexpectedToken {
+        span: Span,
+        expected: String,
+        found: String,
+    },
+    /// Input left over after a complete expression.
+    #[error("unexpected token {found} after expression")]
+    TrailingInput { span: Span, found: String },
+    /// `(` after something that cannot be called.
+    #[error("expected call callee (identifier)")]
+    MissingCallee { span: Span },
+    /// The second `.` in `a..f()`.
+    #[error("unexpected '.' in member call")]
+    ExtraDot { span: Span },
+    /// `a.b` without a call.
+    #[error("expected '(' after member name (member access is not supported yet)")]
+    MemberAccess { span: Span },
+    /// A token that cannot start an expression where one is required.
+    #[error("expected expression, found {found}")]
+    ExpectedExpr { span: Span, found: String },
+    /// A sequence item missing after a separator.
+    #[error("expected expression after {separator}, found {found}")]
+    ExpectedItem {
+        span: Span,
+        separator: String,
+        found: String,
+    },
+    /// A separator with no item before it, as in `f(,a)`.
+    #[error("expected expression before {separator}")]
+    MissingItem { span: Span, separator: String },
+    /// A binary operator with no right operand.
+    #[error("expected expression after {operator}")]
+    MissingOperand { span: Span, operator: String },
+    /// A ternary with no expression after `?` or `:`.
+    #[error("expected expression after '{after}' in ternary expression")]
+    MissingTernaryBranch { span: Span, after: char },
+    #[error("trailing comma is not supported")]
+    TrailingComma { span: Span },
+    /// Two items with no separator between them, as in `f(a b)`.
+    #[error("expected {separator} or {close}, found {found}")]
+    MissingSeparator {
+        span: Span,
+        separator: String,
+        close: String,
+        found: String,
+    },
+    /// A token between items that is neither a separator nor an item.
+    #[error("expected {separator} or {close}, found {found}")]
+    UnexpectedInSequence {
+        span: Span,
+        separator: String,
+        close: String,
+        found: String,
+    },
+    /// The wrong closing delimiter, as in `f(1]`.
+    #[error("expected {expected}, found {found}")]
+    MismatchedDelimiter {
+        span: Span,
+        expected: String,
+        found: String,
+    },
+    /// A missing closing delimiter.
+    #[error("expected {expected}, found {found}")]
+    UnclosedDelimiter {
+        span: Span,
+        expected: String,
+        found: String,
+    },
+}
+
+impl ParseError {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. }
+            | ParseError::TrailingInput { span, .. }
+            | ParseError::MissingCallee { span }
+            | ParseError::ExtraDot { span }
+            | ParseError::MemberAccess { span }
+            | ParseError::ExpectedExpr { span, .. }
+            | ParseError::ExpectedItem { span, .. }
+            | ParseError::MissingItem { span, .. }
+            | ParseError::MissingOperand { span, .. }
+            | ParseError::MissingTernaryBranch { span, .. }
+            | ParseError::TrailingComma { span }
+            | ParseError::MissingSeparator { span, .. }
+            | ParseError::UnexpectedInSequence { span, .. }
+            | ParseError::MismatchedDelimiter { span, .. }
+            | ParseError::UnclosedDelimiter { span, .. } => *span,
+        }
+    }
+
+    /// The diagnostic code, which also sets its dedup priority.
+    pub fn code(&self) -> ParseDiagnostic {
+        match self {
+            ParseError::UnexpectedToken { .. }
+            | ParseError::TrailingInput { .. }
+            | ParseError::MissingCallee { .. }
+            | ParseError::ExtraDot { .. }
+            | ParseError::MemberAccess { .. }
+            | ParseError::UnexpectedInSequence { .. } => ParseDiagnostic::UnexpectedToken,
+            ParseError::ExpectedExpr { .. }
+            | ParseError::ExpectedItem { .. }
+            | ParseError::MissingItem { .. }
+            | ParseError::MissingOperand { .. }
+            | ParseError::MissingTernaryBranch { .. } => ParseDiagnostic::MissingExpr,
+            ParseError::TrailingComma { .. } => ParseDiagnostic::TrailingComma,
+            ParseError::MissingSeparator { .. } => ParseDiagnostic::MissingComma,
+            ParseError::MismatchedDelimiter { .. } => ParseDiagnostic::MismatchedDelimiter,
+            ParseError::UnclosedDelimiter { .. } => ParseDiagnostic::UnclosedDelimiter,
+        }
+    }
+}
+
+impl From<ParseError> for Diagnostic {
+    fn from(error: ParseError) -> Self {
+        Diagnostic {
+            kind: DiagnosticKind::Error,
+            code: DiagnosticCode::Parse(error.code()),
+            message: error.to_string(),
+            span: error.span(),
+            labels: vec![],
+            notes: vec![],
+            actions: vec![],
+        }
+    }
+}
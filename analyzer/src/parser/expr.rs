@@ -3,11 +3,12 @@
 //! Produces an AST plus parse diagnostics. Spans are UTF-8 byte offsets with half-open semantics
 //! `[start, end)`.
 
-use super::{ParseOutput, Parser};
+use super::{ParseError, ParseOutput, Parser};
+use crate::Token;
 use crate::ast::{AssocOp, Expr, ExprKind, NotKind, UnOp};
-use crate::diagnostics::{DiagnosticCode, Label, ParseDiagnostic};
+use crate::diagnostics::Label;
 use crate::lexer::{Lit, LitKind, Span, Symbol, TokenKind};
-use crate::Token;
+use crate::prelude::*;
 
 impl<'a> Parser<'a> {
     /// Parser's entry point
@@ -36,24 +37,22 @@ impl<'a> Parser<'a> {
     /// `1 + 2 * 3` parses as `1 + (2 * 3)`
     /// `1 > 2 || 3 > 4 ? "x" : "y"` parses as `(1 > 2 || 3 > 4) ? "x" : "y"`
     /// ```
+    ///
+    /// The token buffer moves into the output, so the parser is spent afterwards.
     pub fn parse(&mut self) -> ParseOutput {
         let expr = self.parse_expr();
 
         if self.cur().kind != TokenKind::Eof {
             let tok = self.cur();
-            self.diagnostics.emit(
-                DiagnosticCode::Parse(ParseDiagnostic::UnexpectedToken),
-                self.cur().span,
-                format!(
-                    "unexpected token {} after expression",
-                    Self::describe_token(&tok.kind)
-                ),
-            );
+            self.error(ParseError::TrailingInput {
+                span: tok.span,
+                found: Self::describe_token(&tok.kind),
+            });
         }
         ParseOutput {
             expr,
-            diagnostics: std::mem::take(&mut self.diagnostics.diags),
-            tokens: self.token_cursor.tokens.clone(),
+            diagnostics: core::mem::take(&mut self.diagnostics.diags),
+            tokens: core::mem::take(&mut self.token_cursor.tokens),
         }
     }
 
@@ -114,6 +113,9 @@ impl<'a> Parser<'a> {
     /// Parses a prefix-unary-operator expr.
     /// Note: when adding new unary operators, don't forget to adjust [`Token::can_begin_expr()`]
     fn parse_expr_prefix(&mut self) -> Expr {
+        if self.check_cancelled() {
+            return self.mk_expr(self.cur().span, ExprKind::Error);
+        }
         match self.cur().kind {
             // `!expr`
             TokenKind::Bang => self.parse_expr_unary(UnOp::Not(NotKind::Bang)),
@@ -159,14 +161,10 @@ impl<'a> Parser<'a> {
 
             _ => {
                 let tok = self.cur();
-                self.diagnostics.emit(
-                    DiagnosticCode::Parse(ParseDiagnostic::MissingExpr),
-                    tok.span,
-                    format!(
-                        "expected expression, found {}",
-                        Self::describe_token(&tok.kind)
-                    ),
-                );
+                self.error(ParseError::ExpectedExpr {
+                    span: tok.span,
+                    found: Self::describe_token(&tok.kind),
+                });
                 self.error_expr_bump()
             }
         }
@@ -190,9 +188,7 @@ impl<'a> Parser<'a> {
             tok.span,
             ExprKind::Lit(Lit {
                 kind: LitKind::Bool,
-                symbol: Symbol {
-                    text: self.lit_text(tok.span).into(),
-                },
+                symbol: Symbol::new(self.lit_text(tok.span)),
             }),
         )
     }
@@ -203,9 +199,7 @@ impl<'a> Parser<'a> {
             tok.span,
             ExprKind::Lit(Lit {
                 kind: LitKind::Number,
-                symbol: Symbol {
-                    text: self.lit_text(tok.span).into(),
-                },
+                symbol: Symbol::new(self.lit_text(tok.span)),
             }),
         )
     }
@@ -228,7 +222,7 @@ impl<'a> Parser<'a> {
             tok.span,
             ExprKind::Lit(Lit {
                 kind: LitKind::String,
-                symbol: Symbol { text: unescaped },
+                symbol: Symbol::new(&unescaped),
             }),
         )
     }
@@ -324,11 +318,7 @@ impl<'a> Parser<'a> {
                 Ok(e)
             }
             _ => {
-                self.diagnostics.emit(
-                    DiagnosticCode::Parse(ParseDiagnostic::UnexpectedToken),
-                    lparen.span,
-                    "expected call callee (identifier)",
-                );
+                self.error(ParseError::MissingCallee { span: lparen.span });
                 Err(self.error_expr_at(span))
             }
         }
@@ -343,11 +333,7 @@ impl<'a> Parser<'a> {
                 // `a..if(...)`: recover by skipping extra dots.
                 TokenKind::Dot => {
                     let extra = self.bump();
-                    self.diagnostics.emit(
-                        DiagnosticCode::Parse(ParseDiagnostic::UnexpectedToken),
-                        extra.span,
-                        "unexpected '.' in member call",
-                    );
+                    self.error(ParseError::ExtraDot { span: extra.span });
                 }
                 _ => {
                     let span = Span {
@@ -365,11 +351,9 @@ impl<'a> Parser<'a> {
         };
 
         if self.cur().kind != TokenKind::OpenParen {
-            self.diagnostics.emit(
-                DiagnosticCode::Parse(ParseDiagnostic::UnexpectedToken),
-                method_tok.span,
-                "expected '(' after member name (member access is not supported yet)",
-            );
+            self.error(ParseError::MemberAccess {
+                span: method_tok.span,
+            });
             let span = Span {
                 start: receiver.span.start,
                 end: self.last_bumped_end(),
@@ -412,11 +396,7 @@ impl<'a> Parser<'a> {
                 start: insertion,
                 end: insertion,
             };
-            self.diagnostics.emit(
-                DiagnosticCode::Parse(ParseDiagnostic::MissingExpr),
-                span,
-                "expected expression after '?' in ternary expression",
-            );
+            self.error(ParseError::MissingTernaryBranch { span, after: '?' });
             self.error_expr_at(span)
         };
 
@@ -454,11 +434,7 @@ impl<'a> Parser<'a> {
             } else {
                 found.span
             };
-            self.diagnostics.emit(
-                DiagnosticCode::Parse(ParseDiagnostic::MissingExpr),
-                span,
-                "expected expression after ':' in ternary expression",
-            );
+            self.error(ParseError::MissingTernaryBranch { span, after: ':' });
 
             if !matches!(
                 found.kind,
@@ -515,11 +491,10 @@ impl<'a> Parser<'a> {
                 if kind == sep {
                     // `f(,a)` / `[1,,2]`: missing item before the separator.
                     let sep_tok = self.bump();
-                    self.diagnostics.emit(
-                        DiagnosticCode::Parse(ParseDiagnostic::MissingExpr),
-                        sep_tok.span,
-                        format!("expected expression before {}", Self::describe_token(&sep)),
-                    );
+                    self.error(ParseError::MissingItem {
+                        span: sep_tok.span,
+                        separator: Self::describe_token(&sep),
+                    });
                     items.push(self.error_expr_at(sep_tok.span));
                     after_sep = true;
                     continue;
@@ -534,23 +509,19 @@ impl<'a> Parser<'a> {
 
                 // Missing / malformed item.
                 let found = self.cur().clone();
-                let msg = if after_sep {
-                    format!(
-                        "expected expression after {}, found {}",
-                        Self::describe_token(&sep),
-                        Self::describe_token(&found.kind)
-                    )
+                let found_desc = Self::describe_token(&found.kind);
+                self.error(if after_sep {
+                    ParseError::ExpectedItem {
+                        span: found.span,
+                        separator: Self::describe_token(&sep),
+                        found: found_desc,
+                    }
                 } else {
-                    format!(
-                        "expected expression, found {}",
-                        Self::describe_token(&found.kind)
-                    )
-                };
-                self.diagnostics.emit(
-                    DiagnosticCode::Parse(ParseDiagnostic::MissingExpr),
-                    found.span,
-                    msg,
-                );
+                    ParseError::ExpectedExpr {
+                        span: found.span,
+                        found: found_desc,
+                    }
+                });
                 if found.kind != TokenKind::Eof {
                     self.bump();
                 }
@@ -574,10 +545,8 @@ impl<'a> Parser<'a> {
                         .quick_fix_action("Remove trailing comma", sep_tok.span, "")
                         .into_iter()
                         .collect();
-                    self.diagnostics.emit_with_labels_and_actions(
-                        DiagnosticCode::Parse(ParseDiagnostic::TrailingComma),
-                        sep_tok.span,
-                        "trailing comma is not supported",
+                    self.error_with(
+                        ParseError::TrailingComma { span: sep_tok.span },
                         vec![Label {
                             span: sep_tok.span,
                             message: Some("remove this comma".into()),
@@ -607,13 +576,13 @@ impl<'a> Parser<'a> {
                     .quick_fix_action("Insert `,`", insertion, ",")
                     .into_iter()
                     .collect();
-                self.diagnostics.emit_with_labels_and_actions(
-                    DiagnosticCode::Parse(ParseDiagnostic::MissingComma),
-                    found.span,
-                    format!(
-                        "expected {sep_expected} or {close_expected}, found {}",
-                        Self::describe_token(&found.kind)
-                    ),
+                self.error_with(
+                    ParseError::MissingSeparator {
+                        span: found.span,
+                        separator: sep_expected.clone(),
+                        close: close_expected.clone(),
+                        found: Self::describe_token(&found.kind),
+                    },
                     vec![Label {
                         span: insertion,
                         message: Some(format!("insert {sep_expected}")),
@@ -627,14 +596,12 @@ impl<'a> Parser<'a> {
 
             // Unexpected token between items; skip forward to a plausible boundary.
             let found = self.cur().clone();
-            self.diagnostics.emit(
-                DiagnosticCode::Parse(ParseDiagnostic::UnexpectedToken),
-                found.span,
-                format!(
-                    "expected {sep_expected} or {close_expected}, found {}",
-                    Self::describe_token(&found.kind)
-                ),
-            );
+            self.error(ParseError::UnexpectedInSequence {
+                span: found.span,
+                separator: sep_expected.clone(),
+                close: close_expected.clone(),
+                found: Self::describe_token(&found.kind),
+            });
             if found.kind != TokenKind::Eof {
                 self.bump();
             }
@@ -704,7 +671,8 @@ impl<'a> Parser<'a> {
             end: primary_span.start,
         };
 
-        let (code, labels, actions) = if is_mismatched_closing {
+        let found_desc = Self::describe_token(&found.kind);
+        let (error, labels, actions) = if is_mismatched_closing {
             let replacement = close.to_str().unwrap_or_default().to_string();
             let actions = self
                 .quick_fix_action(
@@ -719,7 +687,11 @@ impl<'a> Parser<'a> {
                 .into_iter()
                 .collect::<Vec<_>>();
             (
-                DiagnosticCode::Parse(ParseDiagnostic::MismatchedDelimiter),
+                ParseError::MismatchedDelimiter {
+                    span: primary_span,
+                    expected: expected.to_string(),
+                    found: found_desc,
+                },
                 vec![
                     Label {
                         span: open_span,
@@ -746,7 +718,11 @@ impl<'a> Parser<'a> {
                 .into_iter()
                 .collect::<Vec<_>>();
             (
-                DiagnosticCode::Parse(ParseDiagnostic::UnclosedDelimiter),
+                ParseError::UnclosedDelimiter {
+                    span: primary_span,
+                    expected: expected.to_string(),
+                    found: found_desc,
+                },
                 vec![
                     Label {
                         span: open_span,
@@ -760,16 +736,7 @@ impl<'a> Parser<'a> {
                 actions,
             )
         };
-        self.diagnostics.emit_with_labels_and_actions(
-            code,
-            primary_span,
-            format!(
-                "expected {expected}, found {}",
-                Self::describe_token(&found.kind)
-            ),
-            labels,
-            actions,
-        );
+        self.error_with(error, labels, actions);
 
         self.recover_to_close_delimiter(close)
     }
@@ -868,14 +835,10 @@ impl<'a> Parser<'a> {
     }
 
     fn recover_from_infix(&mut self, op_tok: Token) -> Expr {
-        self.diagnostics.emit(
-            DiagnosticCode::Parse(ParseDiagnostic::MissingExpr),
-            op_tok.span,
-            format!(
-                "expected expression after {}",
-                Self::describe_token(&op_tok.kind)
-            ),
-        );
+        self.error(ParseError::MissingOperand {
+            span: op_tok.span,
+            operator: Self::describe_token(&op_tok.kind),
+        });
         let err_span = if self.cur().kind == TokenKind::Eof {
             Span {
                 start: op_tok.span.end,
@@ -943,16 +906,8 @@ impl DelimDepth {
         match kind {
             TokenKind::OpenParen => self.paren = self.paren.saturating_add(1),
             TokenKind::OpenBracket => self.bracket = self.bracket.saturating_add(1),
-            TokenKind::CloseParen => {
-                if self.paren > 0 {
-                    self.paren -= 1;
-                }
-            }
-            TokenKind::CloseBracket => {
-                if self.bracket > 0 {
-                    self.bracket -= 1;
-                }
-            }
+            TokenKind::CloseParen if self.paren > 0 => self.paren -= 1,
+            TokenKind::CloseBracket if self.bracket > 0 => self.bracket -= 1,
             _ => {}
         }
     }
@@ -0,0 +1,234 @@
+//! Incremental reparsing after a single text edit.
+//!
+//! Tokens are re-lexed around the edit ([`crate::lexer::relex`]). When the old formula had no
+//! diagnostics and the edit stays between the delimiters of one call argument, list item, or
+//! parenthesized expression, only that expression is parsed again and spliced into the old
+//! tree. Everything else falls back to parsing all tokens. The result always equals
+//! `analyze_syntax(new_text)`, node ids included.
+
+use super::ast::{Expr, ExprKind};
+use super::{ParseOutput, Parser, TokenCursor};
+use crate::diagnostics::DiagnosticCode;
+use crate::lexer::{Span, Token, TokenKind, lex, relex};
+use crate::prelude::*;
+use crate::text_edit::TextEdit;
+
+/// Updates `old`, the syntax of some text, for `edit`, which turned that text into `new_text`.
+pub(crate) fn reparse(old: ParseOutput, new_text: &str, edit: &TextEdit) -> ParseOutput {
+    let old_lexed_cleanly = !old
+        .diagnostics
+        .iter()
+        .any(|diag| diag.code == DiagnosticCode::LexError);
+    let tokens = old_lexed_cleanly
+        .then(|| relex(new_text, &old.tokens, edit))
+        .flatten();
+    let Some(tokens) = tokens else {
+        let lexed = lex(new_text);
+        let mut output = parse_all(new_text, lexed.tokens);
+        output
+            .diagnostics
+            .extend(lexed.errors.into_iter().map(Into::into));
+        return output;
+    };
+
+    if old.diagnostics.is_empty()
+        && let Some(expr) = reparse_region(old.expr, &old.tokens, new_text, &tokens, edit)
+    {
+        return ParseOutput {
+            expr,
+            diagnostics: Vec::new(),
+            tokens,
+        };
+    }
+    parse_all(new_text, tokens)
+}
+
+fn parse_all(text: &str, tokens: Vec<Token>) -> ParseOutput {
+    Parser::new(TokenCursor::new(text, tokens)).parse()
+}
+
+/// Reparses the innermost delimited expression containing `edit`, or `None` to parse everything.
+///
+/// `root` must have parsed from `old_tokens` without diagnostics.
+fn reparse_region(
+    mut root: Expr,
+    old_tokens: &[Token],
+    new_text: &str,
+    new_tokens: &[Token],
+    edit: &TextEdit,
+) -> Option<Expr> {
+    let target = innermost_region(&root, old_tokens, edit)?;
+
+    // The delimiters are outside the edit; they must still be the same tokens.
+    let delta = new_text.len() as i64 - old_tokens.last()?.span.end as i64;
+    let open = &old_tokens[target.open];
+    let close = &old_tokens[target.close];
+    let new_open = new_tokens.get(target.open)?;
+    let close_start = (close.span.start as i64 + delta) as u32;
+    let new_close = new_tokens.partition_point(|token| token.span.start < close_start);
+    if new_open.kind != open.kind
+        || new_open.span != open.span
+        || new_tokens.get(new_close)?.kind != close.kind
+        || new_tokens[new_close].span.start != close_start
+    {
+        return None;
+    }
+
+    let mut region = new_tokens[target.open + 1..new_close].to_vec();
+    region.push(Token {
+        kind: TokenKind::Eof,
+        span: Span {
+            start: close_start,
+            end: close_start,
+        },
+    });
+    let parsed = parse_all(new_text, region);
+    if !parsed.diagnostics.is_empty() {
+        return None;
+    }
+
+    let mut replacement = Some(parsed.expr);
+    splice(
+        &mut root,
+        target.id,
+        &mut replacement,
+        edit.range.end,
+        delta,
+    );
+    let mut next_id = 0;
+    renumber(&mut root, &mut next_id);
+    Some(root)
+}
+
+/// A delimited child expression: its id and the token indices of its delimiters.
+struct Region {
+    id: u32,
+    open: usize,
+    close: usize,
+}
+
+fn innermost_region(root: &Expr, tokens: &[Token], edit: &TextEdit) -> Option<Region> {
+    let mut found = None;
+    let mut expr = root;
+    'descend: loop {
+        for (child, delimited) in children(expr) {
+            if delimited
+                && let Some((open, close)) = delimiters(tokens, child.span)
+                && tokens[open].span.end <= edit.range.start
+                && edit.range.end <= tokens[close].span.start
+            {
+                found = Some(Region {
+                    id: child.id,
+                    open,
+                    close,
+                });
+                expr = child;
+                continue 'descend;
+            }
+            if child.span.start <= edit.range.start && edit.range.end <= child.span.end {
+                expr = child;
+                continue 'descend;
+            }
+        }
+        return found;
+    }
+}
+
+/// Children of `expr`, each flagged when it sits alone between delimiters (`(`, `[`, `,` before
+/// and `)`, `]`, `,` after) and is parsed as a standalone expression there.
+fn children(expr: &Expr) -> Vec<(&Expr, bool)> {
+    match &expr.kind {
+        ExprKind::Group { inner } => vec![(inner, true)],
+        ExprKind::List { items } => items.iter().map(|item| (item, true)).collect(),
+        ExprKind::Call { args, .. } => args.iter().map(|arg| (arg, true)).collect(),
+        ExprKind::MemberCall { receiver, args, .. } => core::iter::once((&**receiver, false))
+            .chain(args.iter().map(|arg| (arg, true)))
+            .collect(),
+        ExprKind::Unary { expr, .. } => vec![(expr, false)],
+        ExprKind::Binary { left, right, .. } => vec![(left, false), (right, false)],
+        ExprKind::Ternary {
+            cond,
+            then,
+            otherwise,
+        } => vec![(cond, false), (then, false), (otherwise, false)],
+        ExprKind::Ident(_) | ExprKind::Lit(_) | ExprKind::Error => Vec::new(),
+    }
+}
+
+/// Indices of the non-trivia tokens around `span`, when they are an opening and a closing
+/// delimiter.
+fn delimiters(tokens: &[Token], span: Span) -> Option<(usize, usize)> {
+    let before = tokens.partition_point(|token| token.span.end <= span.start);
+    let open = (0..before).rev().find(|&idx| !tokens[idx].is_trivia())?;
+    let after = tokens.partition_point(|token| token.span.start < span.end);
+    let close = (after..tokens.len()).find(|&idx| !tokens[idx].is_trivia())?;
+    let opens = matches!(
+        tokens[open].kind,
+        TokenKind::OpenParen | TokenKind::OpenBracket | TokenKind::Comma
+    );
+    let closes = matches!(
+        tokens[close].kind,
+        TokenKind::CloseParen | TokenKind::CloseBracket | TokenKind::Comma
+    );
+    (opens && closes).then_some((open, close))
+}
+
+/// Replaces the node `id` with `replacement` and shifts every other span at or after `old_end`.
+fn splice(expr: &mut Expr, id: u32, replacement: &mut Option<Expr>, old_end: u32, delta: i64) {
+    if expr.id == id
+        && let Some(new) = replacement.take()
+    {
+        *expr = new;
+        return;
+    }
+    let shift = |offset: &mut u32| {
+        if *offset >= old_end {
+            *offset = (*offset as i64 + delta) as u32;
+        }
+    };
+    shift(&mut expr.span.start);
+    shift(&mut expr.span.end);
+    if let ExprKind::Binary { op, .. } = &mut expr.kind {
+        shift(&mut op.span.start);
+        shift(&mut op.span.end);
+    }
+    for_each_child_mut(expr, |child| splice(child, id, replacement, old_end, delta));
+}
+
+/// Reassigns ids in the order the parser allocates them: children first, and a call's callee
+/// identifier takes an id before its arguments.
+fn renumber(expr: &mut Expr, next_id: &mut u32) {
+    if matches!(expr.kind, ExprKind::Call { .. }) {
+        *next_id += 1;
+    }
+    for_each_child_mut(expr, |child| renumber(child, next_id));
+    expr.id = *next_id;
+    *next_id += 1;
+}
+
+fn for_each_child_mut(expr: &mut Expr, mut f: impl FnMut(&mut Expr)) {
+    match &mut expr.kind {
+        ExprKind::Group { inner } => f(inner),
+        ExprKind::List { items } => items.iter_mut().for_each(f),
+        ExprKind::Call { args, .. } => args.iter_mut().for_each(f),
+        ExprKind::MemberCall { receiver, args, .. } => {
+            f(receiver);
+            args.iter_mut().for_each(f);
+        }
+        ExprKind::Unary { expr, .. } => f(expr),
+        ExprKind::Binary { left, right, .. } => {
+            f(left);
+            f(right);
+        }
+        ExprKind::Ternary {
+            cond,
+            then,
+            otherwise,
+        } => {
+            f(cond);
+            f(then);
+            f(otherwise);
+        }
+        ExprKind::Ident(_) | ExprKind::Lit(_) | ExprKind::Error => {}
+    }
+}
@@ -7,22 +7,29 @@
 //! Responsibility: build the AST plus parse diagnostics only. Semantic analysis is handled
 //! separately in `analysis`.
 
-use crate::diagnostics::{Diagnostic, DiagnosticCode, Diagnostics, ParseDiagnostic};
+use crate::cancel::CancellationToken;
+use crate::collections::Set;
+use crate::diagnostics::{CodeAction, Diagnostic, Diagnostics, Label};
+use crate::prelude::*;
 use crate::text_edit::TextEdit;
-use std::collections::HashSet;
 
 pub mod ast;
 use crate::lexer::{NodeId, Span, Token, TokenKind};
 use ast::{Expr, ExprKind};
+mod error;
 mod expr;
+mod incremental;
+pub use error::ParseError;
 mod tokenstream;
+pub(crate) use incremental::reparse;
 pub use tokenstream::{TokenCursor, TokenQuery};
 
 pub struct Parser<'a> {
     token_cursor: TokenCursor<'a>,
     next_id: NodeId,
     diagnostics: Diagnostics,
-    quick_fix_seen: HashSet<(u32, u32, String)>,
+    quick_fix_seen: Set<(u32, u32, String)>,
+    cancel: Option<CancellationToken>,
 }
 
 #[derive(Debug)]
@@ -38,10 +45,28 @@ impl<'a> Parser<'a> {
             token_cursor,
             next_id: 0,
             diagnostics: Diagnostics::default(),
-            quick_fix_seen: HashSet::new(),
+            quick_fix_seen: Set::new(),
+            cancel: None,
         }
     }
 
+    /// Check `token` while parsing; once cancelled, the parser skips to EOF.
+    ///
+    /// The partial output is meaningless; callers should check the token after `parse()`.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Safe point: on cancellation, jump to EOF so every parse loop terminates.
+    fn check_cancelled(&mut self) -> bool {
+        let cancelled = self.cancel.as_ref().is_some_and(|c| c.is_cancelled());
+        if cancelled {
+            self.token_cursor.pos = self.token_cursor.tokens.len().saturating_sub(1);
+        }
+        cancelled
+    }
+
     fn alloc_id(&mut self) -> NodeId {
         let id = self.next_id;
         self.next_id += 1;
@@ -136,13 +161,9 @@ impl<'a> Parser<'a> {
         )
     }
 
-    fn is_trivia(kind: TokenKind) -> bool {
-        kind.is_trivia()
-    }
-
     fn next_nontrivia_idx(&self, mut idx: usize) -> usize {
         while idx < self.token_cursor.tokens.len() {
-            if Self::is_trivia(self.token_cursor.tokens[idx].clone().kind) {
+            if self.token_cursor.tokens[idx].is_trivia() {
                 idx += 1;
                 continue;
             }
@@ -167,12 +188,19 @@ impl<'a> Parser<'a> {
     }
 
     fn emit_unexpected(&mut self, expected: &str, found: TokenKind, span: Span) {
-        let found = Self::describe_token(&found);
-        self.diagnostics.emit(
-            DiagnosticCode::Parse(ParseDiagnostic::UnexpectedToken),
+        self.error(ParseError::UnexpectedToken {
             span,
-            format!("expected {expected}, found {found}"),
-        );
+            expected: expected.to_string(),
+            found: Self::describe_token(&found),
+        });
+    }
+
+    fn error(&mut self, error: ParseError) {
+        self.diagnostics.report(error, vec![], vec![]);
+    }
+
+    fn error_with(&mut self, error: ParseError, labels: Vec<Label>, actions: Vec<CodeAction>) {
+        self.diagnostics.report(error, labels, actions);
     }
 
     fn describe_token(kind: &TokenKind) -> String {
@@ -197,7 +225,7 @@ impl<'a> Parser<'a> {
         title: impl Into<String>,
         range: Span,
         new_text: impl Into<String>,
-    ) -> Option<crate::diagnostics::CodeAction> {
+    ) -> Option<CodeAction> {
         let new_text = new_text.into();
         if !self
             .quick_fix_seen
@@ -206,7 +234,7 @@ impl<'a> Parser<'a> {
             return None;
         }
 
-        Some(crate::diagnostics::CodeAction {
+        Some(CodeAction {
             title: title.into(),
             edits: vec![TextEdit { range, new_text }],
         })
@@ -4,6 +4,7 @@
 //! Use [`TokenQuery`] for span-to-range and trivia-aware neighbor scans.
 
 use crate::lexer::{Span, Token, TokenIdx, TokenKind, TokenRange, tokens_in_span};
+use crate::prelude::*;
 
 /// A mutable cursor over a token stream.
 ///
@@ -123,7 +124,7 @@ impl<'a> TokenQuery<'a> {
     /// - The result is `(p + 1)..idx` if `p` exists, otherwise `0..idx`.
     ///
     /// The returned range is half-open and may include newlines and/or comments.
-    pub fn leading_trivia_before(&self, idx: usize) -> std::ops::Range<usize> {
+    pub fn leading_trivia_before(&self, idx: usize) -> core::ops::Range<usize> {
         let idx = idx.min(self.tokens.len());
         let lo = self.prev_nontrivia(idx).map(|i| i + 1).unwrap_or(0);
         lo..idx
@@ -134,7 +135,10 @@ impl<'a> TokenQuery<'a> {
     /// - the first newline token.
     ///
     /// This is used by formatter comment attachment to consider only "same line" trivia.
-    pub fn trailing_trivia_until_newline_or_nontrivia(&self, idx: usize) -> std::ops::Range<usize> {
+    pub fn trailing_trivia_until_newline_or_nontrivia(
+        &self,
+        idx: usize,
+    ) -> core::ops::Range<usize> {
         let mut i = idx.min(self.tokens.len());
         while i < self.tokens.len() {
             let tok = &self.tokens[i];
@@ -1,36 +1,216 @@
 //! Line/column lookup for a source string.
 //!
-//! Input byte offsets are clamped down to a UTF-8 char boundary.
-//! The column is a Rust `char` count (Unicode scalar values), not bytes or UTF-16.
+//! Lines and columns are 1-based. Input byte offsets are clamped down to a UTF-8 char boundary.
+//! [`SourceMap::line_col`] counts columns in Rust `char`s (Unicode scalar values); the `_utf8`
+//! and `_utf16` variants count UTF-8 bytes and UTF-16 code units, as editors do.
 
-pub struct SourceMap<'a> {
-    src: &'a str,
+use alloc::borrow::Cow;
+
+use crate::prelude::*;
+use crate::text_edit::TextEdit;
+
+/// Byte offsets of line starts in a source string.
+///
+/// Owned separately from the text so editors can keep it across edits and patch it with
+/// [`LineIndex::apply_edits`] instead of rescanning the whole document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Always starts with `0`; sorted.
     line_starts: Vec<usize>,
 }
 
-impl<'a> SourceMap<'a> {
-    pub fn new(src: &'a str) -> Self {
+impl LineIndex {
+    pub fn new(src: &str) -> Self {
         let mut line_starts = vec![0];
-        for (i, b) in src.bytes().enumerate() {
-            if b == b'\n' {
-                line_starts.push(i + 1);
+        line_starts.extend(newline_starts(src, 0));
+        Self { line_starts }
+    }
+
+    /// Updates the index for `edits`, given in coordinates of the text this index was built for.
+    ///
+    /// Edits must be valid and non-overlapping for that text (as accepted by `apply_edits`); they
+    /// may come in any order. Only line starts at or after each edit are touched.
+    pub fn apply_edits(&mut self, edits: &[TextEdit]) {
+        let mut edits: Vec<&TextEdit> = edits.iter().collect();
+        edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+        // Back to front, so earlier offsets stay valid for the remaining edits.
+        for edit in edits.into_iter().rev() {
+            self.apply_edit(edit);
+        }
+    }
+
+    fn apply_edit(&mut self, edit: &TextEdit) {
+        let start = edit.range.start as usize;
+        let end = edit.range.end as usize;
+        // Lines starting inside `(start, end]` began at a removed `\n`.
+        let first = self.line_starts.partition_point(|&line| line <= start);
+        let last = self.line_starts.partition_point(|&line| line <= end);
+        let shift = edit.new_text.len() as isize - (end - start) as isize;
+        for line in &mut self.line_starts[last..] {
+            *line = line.wrapping_add_signed(shift);
+        }
+        self.line_starts
+            .splice(first..last, newline_starts(&edit.new_text, start));
+    }
+
+    /// Return `(line, col)`, both 1-based, for a byte offset into `src`.
+    ///
+    /// `src` must be the text this index describes.
+    pub fn line_col(&self, src: &str, byte: u32) -> (usize, usize) {
+        let (line, prefix) = self.line_prefix(src, byte);
+        (line, prefix.chars().count() + 1)
+    }
+
+    /// Like [`line_col`](Self::line_col), with the column in UTF-8 bytes.
+    pub fn line_col_utf8(&self, src: &str, byte: u32) -> (usize, usize) {
+        let (line, prefix) = self.line_prefix(src, byte);
+        (line, prefix.len() + 1)
+    }
+
+    /// Like [`line_col`](Self::line_col), with the column in UTF-16 code units.
+    pub fn line_col_utf16(&self, src: &str, byte: u32) -> (usize, usize) {
+        let (line, prefix) = self.line_prefix(src, byte);
+        (line, prefix.encode_utf16().count() + 1)
+    }
+
+    /// Byte offset of a 1-based `(line, col)` with the column in UTF-8 bytes.
+    ///
+    /// Columns past the end of the line clamp to its end (before the `\n`), lines past the end
+    /// of `src` to `src.len()`, and columns inside a char floor to its start.
+    pub fn offset_utf8(&self, src: &str, line: usize, col: usize) -> u32 {
+        match self.line_text(src, line) {
+            Some((start, text)) => {
+                (start + clamp_to_char_boundary(text, col.saturating_sub(1))) as u32
             }
+            None => src.len() as u32,
         }
-        Self { src, line_starts }
     }
 
-    /// Return `(line, col)`, both 1-based.
-    /// `byte` is a UTF-8 byte offset into `src`.
-    pub fn line_col(&self, byte: u32) -> (usize, usize) {
-        let b = clamp_to_char_boundary(self.src, byte as usize);
+    /// Like [`offset_utf8`](Self::offset_utf8), with the column in UTF-16 code units.
+    pub fn offset_utf16(&self, src: &str, line: usize, col: usize) -> u32 {
+        match self.line_text(src, line) {
+            Some((start, text)) => {
+                start as u32 + utf16_offset_to_byte(text, col.saturating_sub(1) as u32)
+            }
+            None => src.len() as u32,
+        }
+    }
+
+    /// The 1-based line containing `byte` and its text up to `byte`.
+    fn line_prefix<'s>(&self, src: &'s str, byte: u32) -> (usize, &'s str) {
+        let b = clamp_to_char_boundary(src, byte as usize);
         let line_idx = match self.line_starts.binary_search(&b) {
             Ok(i) => i,
             Err(i) => i.saturating_sub(1),
         };
-        let line_start = self.line_starts[line_idx];
-        let col = self.src[line_start..b].chars().count();
-        (line_idx + 1, col + 1)
+        (line_idx + 1, &src[self.line_starts[line_idx]..b])
     }
+
+    /// Start and text (without the `\n`) of a 1-based line; line `0` is line `1`.
+    fn line_text<'s>(&self, src: &'s str, line: usize) -> Option<(usize, &'s str)> {
+        let idx = line.saturating_sub(1);
+        let start = *self.line_starts.get(idx)?;
+        let end = self
+            .line_starts
+            .get(idx + 1)
+            .map_or(src.len(), |&next| next - 1);
+        Some((start, &src[start..end]))
+    }
+}
+
+pub struct SourceMap<'a> {
+    src: &'a str,
+    index: Cow<'a, LineIndex>,
+}
+
+impl<'a> SourceMap<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            index: Cow::Owned(LineIndex::new(src)),
+        }
+    }
+
+    /// A map over a line index that is already built for `src`.
+    pub fn with_index(src: &'a str, index: &'a LineIndex) -> Self {
+        Self {
+            src,
+            index: Cow::Borrowed(index),
+        }
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.src
+    }
+
+    /// Return `(line, col)`, both 1-based.
+    /// `byte` is a UTF-8 byte offset into `src`.
+    pub fn line_col(&self, byte: u32) -> (usize, usize) {
+        self.index.line_col(self.src, byte)
+    }
+
+    /// Return `(line, col)`, both 1-based, with `col` in UTF-8 bytes.
+    pub fn line_col_utf8(&self, byte: u32) -> (usize, usize) {
+        self.index.line_col_utf8(self.src, byte)
+    }
+
+    /// Return `(line, col)`, both 1-based, with `col` in UTF-16 code units.
+    pub fn line_col_utf16(&self, byte: u32) -> (usize, usize) {
+        self.index.line_col_utf16(self.src, byte)
+    }
+
+    /// Byte offset of a 1-based `(line, col)`, `col` in UTF-8 bytes; see
+    /// [`LineIndex::offset_utf8`] for clamping.
+    pub fn offset_utf8(&self, line: usize, col: usize) -> u32 {
+        self.index.offset_utf8(self.src, line, col)
+    }
+
+    /// Byte offset of a 1-based `(line, col)`, `col` in UTF-16 code units.
+    pub fn offset_utf16(&self, line: usize, col: usize) -> u32 {
+        self.index.offset_utf16(self.src, line, col)
+    }
+
+    /// See [`utf16_offset_to_byte`].
+    pub fn utf16_offset_to_byte(&self, utf16: u32) -> u32 {
+        utf16_offset_to_byte(self.src, utf16)
+    }
+
+    /// See [`byte_to_utf16_offset`].
+    pub fn byte_to_utf16_offset(&self, byte: u32) -> u32 {
+        byte_to_utf16_offset(self.src, byte)
+    }
+}
+
+/// Convert a UTF-16 code unit offset into `src` to a UTF-8 byte offset.
+///
+/// Out-of-range values are clamped. An offset inside a char's UTF-16 encoding (for example,
+/// inside a surrogate pair) is floored to the char start.
+pub fn utf16_offset_to_byte(src: &str, utf16: u32) -> u32 {
+    let utf16 = utf16 as usize;
+    let mut units = 0usize;
+    for (byte, ch) in src.char_indices() {
+        units += ch.len_utf16();
+        if units > utf16 {
+            return byte as u32;
+        }
+    }
+    src.len() as u32
+}
+
+/// Convert a UTF-8 byte offset into `src` to a UTF-16 code unit offset.
+///
+/// Out-of-range values are clamped, and offsets inside a char floor to its start.
+pub fn byte_to_utf16_offset(src: &str, byte: u32) -> u32 {
+    let byte = clamp_to_char_boundary(src, byte as usize);
+    src[..byte].encode_utf16().count() as u32
+}
+
+/// Line starts after each `\n` in `text`, which begins at byte `offset`.
+fn newline_starts(text: &str, offset: usize) -> impl Iterator<Item = usize> + '_ {
+    text.bytes()
+        .enumerate()
+        .filter(|&(_, b)| b == b'\n')
+        .map(move |(i, _)| offset + i + 1)
 }
 
 fn clamp_to_char_boundary(source: &str, mut byte: usize) -> usize {
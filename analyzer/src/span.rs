@@ -1,7 +1,11 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// Half-open byte span into the source string: `[start, end)`.
 ///
 /// `start` and `end` must be valid UTF-8 slice boundaries for that same source string.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Span {
     pub start: u32,
     pub end: u32,
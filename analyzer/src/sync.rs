@@ -0,0 +1,57 @@
+//! Lazily initialized cells.
+//!
+//! With `std` these are the std types. `no_std` builds get minimal stand-ins on top of
+//! [`once_cell::race::OnceBox`]: threads racing to initialize a cell may each run the initializer,
+//! and one result wins. Every initializer in the core is pure, so only the wasted work differs.
+
+#[cfg(feature = "std")]
+pub(crate) use std::sync::{LazyLock, OnceLock};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use race::{LazyLock, OnceLock};
+
+#[cfg(not(feature = "std"))]
+mod race {
+    use core::ops::Deref;
+
+    use once_cell::race::OnceBox;
+
+    use crate::prelude::*;
+
+    /// The subset of `std::sync::OnceLock` the core uses.
+    #[derive(Debug, Default, Clone)]
+    pub(crate) struct OnceLock<T>(OnceBox<T>);
+
+    impl<T> OnceLock<T> {
+        pub(crate) const fn new() -> Self {
+            Self(OnceBox::new())
+        }
+
+        pub(crate) fn get_or_init(&self, init: impl FnOnce() -> T) -> &T {
+            self.0.get_or_init(|| Box::new(init()))
+        }
+    }
+
+    /// The subset of `std::sync::LazyLock` the core uses.
+    pub(crate) struct LazyLock<T> {
+        cell: OnceBox<T>,
+        init: fn() -> T,
+    }
+
+    impl<T> LazyLock<T> {
+        pub(crate) const fn new(init: fn() -> T) -> Self {
+            Self {
+                cell: OnceBox::new(),
+                init,
+            }
+        }
+    }
+
+    impl<T> Deref for LazyLock<T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            self.cell.get_or_init(|| Box::new((self.init)()))
+        }
+    }
+}
@@ -1,14 +1,30 @@
 #[cfg(test)]
 mod test_builtins_smoke;
 #[cfg(test)]
+mod test_context_builder;
+#[cfg(test)]
+mod test_context_lookup;
+#[cfg(all(test, feature = "serde"))]
+mod test_context_schema;
+#[cfg(test)]
 mod test_generic_infer;
 #[cfg(test)]
+mod test_limits;
+#[cfg(test)]
 mod test_list_literal;
 #[cfg(test)]
 mod test_normalize_union;
 #[cfg(test)]
+mod test_notion_schema;
+#[cfg(test)]
+mod test_property_resolver;
+#[cfg(test)]
 mod test_semantic;
 #[cfg(test)]
 mod test_semantic_infer_builtins;
 #[cfg(test)]
 mod test_sig_resolver;
+#[cfg(test)]
+mod test_signature_builder;
+#[cfg(test)]
+mod test_ty_context;
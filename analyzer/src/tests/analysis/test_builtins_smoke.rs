@@ -1,6 +1,18 @@
-use crate::semantic::builtins_functions;
+use std::borrow::Cow;
+
+use crate::semantic::{Context, builtins_context, builtins_functions};
 
 #[test]
 fn builtins_functions_smoke() {
     let _ = builtins_functions();
 }
+
+#[test]
+fn builtin_contexts_share_one_table() {
+    assert!(std::ptr::eq(builtins_functions(), builtins_functions()));
+    let ctx = Context::with_builtins(Vec::new());
+    assert!(
+        matches!(*ctx.functions, Cow::Borrowed(functions) if std::ptr::eq(functions, builtins_functions()))
+    );
+    assert_eq!(&ctx, builtins_context());
+}
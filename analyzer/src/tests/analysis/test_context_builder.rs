@@ -0,0 +1,99 @@
+use alloc::borrow::Cow;
+
+use crate::semantic::{
+    Context, FunctionCategory, FunctionSig, ParamShape, Property, Ty, builtins_functions,
+};
+
+fn answer() -> FunctionSig {
+    FunctionSig::new(
+        FunctionCategory::General,
+        "answer()",
+        "answer",
+        ParamShape::new(vec![], vec![], vec![]),
+        Ty::Number,
+        vec![],
+    )
+}
+
+#[test]
+fn builds_properties_in_order() {
+    let ctx = Context::builder()
+        .prop("Age", Ty::Number)
+        .disabled_prop("Owner", Ty::String, "not supported")
+        .prop_with_options("Status", Ty::String, ["Open", "Done"])
+        .build();
+    assert_eq!(
+        ctx.properties.as_slice(),
+        [
+            Property {
+                name: "Age".into(),
+                ty: Ty::Number,
+                disabled_reason: None,
+                options: Vec::new(),
+            },
+            Property {
+                name: "Owner".into(),
+                ty: Ty::String,
+                disabled_reason: Some("not supported".into()),
+                options: Vec::new(),
+            },
+            Property {
+                name: "Status".into(),
+                ty: Ty::String,
+                disabled_reason: None,
+                options: vec!["Open".into(), "Done".into()],
+            },
+        ]
+    );
+    assert!(ctx.functions.is_empty());
+}
+
+#[test]
+fn with_builtins_alone_matches_context_with_builtins() {
+    let ctx = Context::builder()
+        .prop("Age", Ty::Number)
+        .with_builtins()
+        .build();
+    let expected = Context::with_builtins(vec![Property {
+        name: "Age".into(),
+        ty: Ty::Number,
+        disabled_reason: None,
+        options: Vec::new(),
+    }]);
+    assert_eq!(ctx, expected);
+    assert!(matches!(&*ctx.functions, Cow::Borrowed(_)));
+}
+
+#[test]
+fn functions_added_first_shadow_builtins() {
+    let custom_if = FunctionSig {
+        name: "if".into(),
+        ..answer()
+    };
+    let ctx = Context::builder()
+        .func(custom_if.clone())
+        .func(answer())
+        .with_builtins()
+        .build();
+    assert_eq!(ctx.functions.len(), builtins_functions().len() + 2);
+    assert_eq!(ctx.function("if"), Some(&custom_if));
+    assert_eq!(ctx.function("answer"), Some(&answer()));
+}
+
+#[test]
+fn without_removes_functions_by_name() {
+    let ctx = Context::builder()
+        .with_builtins()
+        .func(answer())
+        .without(&["if", "answer", "no_such_function"])
+        .build();
+    assert!(ctx.function("if").is_none());
+    assert!(ctx.function("answer").is_none());
+    assert!(ctx.function("ifs").is_some());
+
+    let untouched = Context::builder()
+        .with_builtins()
+        .without(&["nope"])
+        .build();
+    assert!(matches!(&*untouched.functions, Cow::Borrowed(_)));
+}
@@ -0,0 +1,106 @@
+use crate::semantic::{Context, Property, PropertyLookup, Ty};
+use crate::{DiagnosticCode, DiagnosticKind, Span};
+
+fn prop(name: &str, ty: Ty) -> Property {
+    Property {
+        name: name.into(),
+        ty,
+        disabled_reason: None,
+        options: Vec::new(),
+    }
+}
+
+#[test]
+fn lookups_use_the_first_entry_for_a_name() {
+    let ctx = Context::with_builtins(vec![
+        prop("Price", Ty::Number),
+        prop("Title", Ty::String),
+        prop("Price", Ty::Boolean),
+    ]);
+    assert_eq!(ctx.lookup("Price"), Some(Ty::Number));
+    assert_eq!(ctx.lookup("Title"), Some(Ty::String));
+    assert_eq!(ctx.lookup("Missing"), None);
+    assert_eq!(ctx.function("if").map(|sig| sig.name.as_str()), Some("if"));
+    assert!(ctx.function("prop").is_none());
+}
+
+#[test]
+fn mutating_a_context_refreshes_its_index() {
+    let mut ctx = Context::with_builtins(vec![prop("A", Ty::Number)]);
+    assert_eq!(ctx.lookup("A"), Some(Ty::Number));
+
+    ctx.properties.push(prop("B", Ty::String));
+    assert_eq!(ctx.lookup("B"), Some(Ty::String));
+
+    ctx.properties[0].name = "C".into();
+    assert_eq!(ctx.lookup("A"), None);
+    assert_eq!(ctx.lookup("C"), Some(Ty::Number));
+
+    ctx.functions.to_mut().retain(|sig| sig.name != "if");
+    assert!(ctx.function("if").is_none());
+    assert!(ctx.function("ifs").is_some());
+}
+
+#[test]
+fn lenient_lookup_ignores_case_and_surrounding_whitespace() {
+    let mut ctx = Context::with_builtins(vec![
+        prop("Status", Ty::String),
+        prop("status", Ty::Number),
+        prop("Due Date", Ty::Date),
+    ]);
+    assert_eq!(ctx.lookup(" due date "), None);
+
+    ctx.property_lookup = PropertyLookup::Lenient;
+    assert_eq!(ctx.lookup("status"), Some(Ty::Number));
+    assert_eq!(ctx.lookup("STATUS"), Some(Ty::String));
+    let due = ctx.property(" due date ").unwrap();
+    assert_eq!(due.name, "Due Date");
+    assert_eq!(ctx.lookup("duedate"), None);
+}
+
+#[test]
+fn lenient_lookup_pages_through_the_resolver() {
+    let remote: Vec<Property> = (0..100)
+        .map(|i| prop(&format!("Item {i}"), Ty::Number))
+        .chain([prop("ITEM", Ty::Date)])
+        .collect();
+    let ctx = Context::builder()
+        .property_resolver(remote)
+        .property_lookup(PropertyLookup::Lenient)
+        .build();
+    assert_eq!(ctx.property("item ").unwrap().name, "ITEM");
+    assert_eq!(ctx.property("item 99").unwrap().name, "Item 99");
+    assert!(ctx.property("item 100").is_none());
+}
+
+#[test]
+fn inexact_property_names_warn_with_a_quick_fix() {
+    let ctx = Context::builder()
+        .prop("Say \"hi\"", Ty::String)
+        .property_lookup(PropertyLookup::Lenient)
+        .with_builtins()
+        .build();
+    let source = r#"prop(" say ""hi"" ")"#.replace("\"\"", "\\\"");
+
+    let out = crate::analyze(&source, &ctx);
+    assert_eq!(out.output_type, Ty::String);
+    let [diag] = out.diagnostics.as_slice() else {
+        panic!("{:?}", out.diagnostics);
+    };
+    assert_eq!(diag.kind, DiagnosticKind::Warning);
+    assert_eq!(diag.code, DiagnosticCode::InexactPropertyName);
+    assert_eq!(
+        diag.message,
+        "Property name ` say \"hi\" ` does not match `Say \"hi\"` exactly"
+    );
+    assert_eq!(diag.span, Span { start: 5, end: 19 });
+    assert_eq!(diag.actions[0].title, "Replace with `Say \"hi\"`");
+
+    let [edit] = diag.actions[0].edits.as_slice() else {
+        panic!("{:?}", diag.actions);
+    };
+    assert_eq!(edit.range, diag.span);
+    let fixed = format!("{}{})", &source[..5], edit.new_text);
+    assert_eq!(fixed, r#"prop("Say \"hi\"")"#);
+    assert!(crate::analyze(&fixed, &ctx).diagnostics.is_empty());
+}
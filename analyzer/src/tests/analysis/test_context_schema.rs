@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+
+use crate::semantic::{
+    CONTEXT_SCHEMA_PATH, CONTEXT_VERSION, Context, ContextError, FormulaLimits, Property,
+    PropertyLookup, Ty, context_json_schema,
+};
+
+fn issues(json: &str) -> Vec<(String, String)> {
+    Context::validate_json(json)
+        .expect_err("expected schema violations")
+        .into_issues()
+        .into_iter()
+        .map(|i| (i.path, i.message))
+        .collect()
+}
+
+#[test]
+fn published_schema_is_up_to_date() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(CONTEXT_SCHEMA_PATH);
+    let on_disk = std::fs::read_to_string(&path).expect("read context schema");
+    let expected = serde_json::to_string_pretty(&context_json_schema()).unwrap() + "\n";
+    assert!(
+        on_disk == expected,
+        "{} is stale; run `just gen-schema`",
+        path.display()
+    );
+}
+
+#[test]
+fn serialized_builtins_round_trip_through_validate_json() {
+    let ctx = Context::with_builtins(vec![Property {
+        name: "Tags".into(),
+        ty: Ty::List(Box::new(Ty::String)),
+        disabled_reason: Some("archived".into()),
+        options: Vec::new(),
+    }]);
+    let json = serde_json::to_string(&ctx).unwrap();
+
+    let parsed = Context::validate_json(&json).expect("serialized context validates");
+    assert_eq!(parsed, ctx);
+}
+
+#[test]
+fn optional_fields_default() {
+    let ctx = Context::validate_json(
+        r#"{
+            "properties": [{ "name": "Price", "type": "Number" }],
+            "functions": [{
+                "name": "double",
+                "params": { "head": [{ "name": "x", "type": "Number" }] },
+                "ret": "Number",
+                "category": "Number"
+            }]
+        }"#,
+    )
+    .expect("valid context");
+
+    assert_eq!(ctx.properties[0].disabled_reason, None);
+    let sig = &ctx.functions[0];
+    assert_eq!(sig.detail, "");
+    assert!(sig.generics.is_empty());
+    assert_eq!(sig.params.repeat_min_groups, 1);
+    assert!(!sig.params.head[0].optional);
+    assert!(sig.params.tail.is_empty());
+
+    assert_eq!(
+        Context::validate_json("{}"),
+        Ok(Context {
+            properties: vec![].into(),
+            functions: Vec::new().into(),
+            property_resolver: None,
+            property_lookup: Default::default(),
+            limits: Default::default(),
+        })
+    );
+}
+
+#[test]
+fn reports_violations_with_paths() {
+    assert_eq!(
+        issues(
+            r#"{
+                "properties": [
+                    { "name": "Ok", "type": { "List": { "Union": ["Date", "Text"] } } },
+                    { "type": { "Lst": "Number" } },
+                    "Title"
+                ],
+                "functions": [{
+                    "name": "f",
+                    "params": { "head": [{ "name": "x", "type": { "Generic": -1 } }] },
+                    "ret": "Number",
+                    "category": "Math",
+                    "generics": [{ "id": 0, "kind": "Plain" }]
+                }],
+                "version": 0
+            }"#
+        ),
+        [
+            (
+                "functions[0].category".into(),
+                "expected one of `General`, `Text`, `Number`, `Date`, `People`, `List`, `Special`, found \"Math\"".into()
+            ),
+            (
+                "functions[0].params.head[0].type.Generic".into(),
+                "expected integer >= 0, found -1".into()
+            ),
+            (
+                "properties[0].type.List.Union[1]".into(),
+                "expected one of `Number`, `String`, `Boolean`, `Date`, `Null`, `Unknown`, found \"Text\"".into()
+            ),
+            ("properties[1].name".into(), "missing field".into()),
+            (
+                "properties[1].type".into(),
+                "expected a type name or { \"List\" | \"Union\" | \"Generic\": ... }, found object".into()
+            ),
+            ("properties[2]".into(), "expected object, found string".into()),
+            ("version".into(), "expected integer >= 1, found 0".into()),
+        ]
+    );
+}
+
+#[test]
+fn reports_invalid_json_and_non_object_root() {
+    let [(path, message)] = issues("{").try_into().unwrap();
+    assert_eq!(path, "");
+    assert!(message.starts_with("invalid JSON: "), "{message}");
+
+    assert_eq!(
+        issues("[]"),
+        [("".into(), "expected object, found array".into())]
+    );
+}
+
+#[test]
+fn invalid_json_keeps_its_position() {
+    let Err(ContextError::InvalidJson { line, column, .. }) =
+        Context::validate_json("{\n  \"properties\": [,]\n}")
+    else {
+        panic!("expected invalid JSON");
+    };
+    assert_eq!((line, column), (2, 18));
+
+    let err = Context::validate_json(r#"{ "version": "1" }"#).unwrap_err();
+    assert!(matches!(&err, ContextError::Schema(issues) if issues.len() == 1));
+    assert_eq!(err.to_string(), "version: expected integer, found string");
+}
+
+#[test]
+fn serialized_context_carries_the_version() {
+    let ctx = Context::with_builtins(vec![]);
+    let value = serde_json::to_value(&ctx).unwrap();
+    assert_eq!(value["version"], CONTEXT_VERSION);
+}
+
+#[test]
+fn unknown_fields_and_newer_versions_are_tolerated() {
+    let json = r#"{
+        "version": 7,
+        "properties": [{ "name": "Price", "type": "Number", "format": "dollar" }],
+        "functions": [{
+            "name": "double",
+            "params": { "head": [{ "name": "x", "type": "Number", "doc": "any number" }] },
+            "ret": "Number",
+            "category": "Number",
+            "deprecated": true
+        }],
+        "views": []
+    }"#;
+
+    let ctx = Context::validate_json(json).expect("tolerant context");
+    assert_eq!(ctx.lookup("Price"), Some(Ty::Number));
+    assert_eq!(ctx.functions[0].params.head[0].name, "x");
+    assert_eq!(serde_json::from_str::<Context>(json).unwrap(), ctx);
+
+    let without_version: Context = serde_json::from_str(r#"{ "properties": [] }"#).unwrap();
+    assert!(without_version.properties.is_empty());
+    let err = serde_json::from_str::<Context>(r#"{ "version": 0 }"#).unwrap_err();
+    assert!(err.to_string().contains("starts at 1"), "{err}");
+}
+
+#[test]
+fn property_lookup_round_trips_and_defaults_to_exact() {
+    let ctx = Context::validate_json(r#"{ "property_lookup": "lenient" }"#).expect("valid context");
+    assert_eq!(ctx.property_lookup, PropertyLookup::Lenient);
+    let value = serde_json::to_value(&ctx).unwrap();
+    assert_eq!(value["property_lookup"], "lenient");
+
+    let exact = serde_json::to_value(Context::with_builtins(vec![])).unwrap();
+    assert!(exact.get("property_lookup").is_none());
+
+    let err = Context::validate_json(r#"{ "property_lookup": "fuzzy" }"#).unwrap_err();
+    assert!(err.to_string().starts_with("property_lookup: "), "{err}");
+}
+
+#[test]
+fn limits_round_trip_and_default_to_unlimited() {
+    let ctx = Context::validate_json(r#"{ "limits": { "max_length": 1000, "max_depth": null } }"#)
+        .expect("valid context");
+    assert_eq!(
+        ctx.limits,
+        FormulaLimits {
+            max_length: Some(1000),
+            max_depth: None,
+        }
+    );
+    let value = serde_json::to_value(&ctx).unwrap();
+    assert_eq!(value["limits"]["max_length"], 1000);
+
+    let unlimited = serde_json::to_value(Context::with_builtins(vec![])).unwrap();
+    assert!(unlimited.get("limits").is_none());
+
+    assert_eq!(
+        issues(r#"{ "limits": { "max_depth": -1 } }"#),
+        [(
+            "limits.max_depth".to_string(),
+            "expected integer >= 0, found -1".to_string()
+        )]
+    );
+}
@@ -1,7 +1,7 @@
 use crate::analyze_syntax;
 use crate::semantic::{
-    infer_expr_with_map, Context, FunctionCategory, FunctionSig, GenericId, GenericParam,
-    GenericParamKind, ParamShape, ParamSig, Ty, TypeMap,
+    Context, FunctionCategory, FunctionSig, GenericId, GenericParam, GenericParamKind, ParamShape,
+    ParamSig, Ty, TypeMap, infer_expr_with_map,
 };
 
 fn p(name: &str, ty: Ty) -> ParamSig {
@@ -28,7 +28,7 @@ fn infer(source: &str, ctx: &Context) -> (Ty, TypeMap, crate::ast::Expr) {
 fn generic_if_plain_unifies_then_else_into_union() {
     let t = GenericId(0);
     let ctx = Context {
-        properties: vec![],
+        properties: vec![].into(),
         functions: vec![FunctionSig::new(
             FunctionCategory::General,
             "if(condition, then, else)",
@@ -47,7 +47,11 @@ fn generic_if_plain_unifies_then_else_into_union() {
                 id: t,
                 kind: GenericParamKind::Plain,
             }],
-        )],
+        )]
+        .into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
     };
 
     let (ty, map, root) = infer("if(true, 1, \"x\")", &ctx);
@@ -59,7 +63,7 @@ fn generic_if_plain_unifies_then_else_into_union() {
 fn generic_list_unifies_inner_type() {
     let t = GenericId(0);
     let ctx = Context {
-        properties: vec![],
+        properties: vec![].into(),
         functions: vec![
             FunctionSig::new(
                 FunctionCategory::List,
@@ -88,7 +92,11 @@ fn generic_list_unifies_inner_type() {
                     kind: GenericParamKind::Plain,
                 }],
             ),
-        ],
+        ]
+        .into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
     };
 
     let (ty, _, _) = infer("first(split(\"a\", \",\"))", &ctx);
@@ -99,7 +107,7 @@ fn generic_list_unifies_inner_type() {
 fn variant_generic_accumulates_union_when_no_unknown() {
     let t = GenericId(0);
     let ctx = Context {
-        properties: vec![],
+        properties: vec![].into(),
         functions: vec![FunctionSig::new(
             FunctionCategory::General,
             "ifs(condition1, value1, ..., else)",
@@ -114,7 +122,11 @@ fn variant_generic_accumulates_union_when_no_unknown() {
                 id: t,
                 kind: GenericParamKind::Variant,
             }],
-        )],
+        )]
+        .into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
     };
 
     let (ty, _, _) = infer("ifs(true, 1, false, 2, \"a\")", &ctx);
@@ -125,7 +137,7 @@ fn variant_generic_accumulates_union_when_no_unknown() {
 fn variant_generic_propagates_unknown() {
     let t = GenericId(0);
     let ctx = Context {
-        properties: vec![],
+        properties: vec![].into(),
         functions: vec![FunctionSig::new(
             FunctionCategory::General,
             "ifs(condition1, value1, ..., else)",
@@ -140,7 +152,11 @@ fn variant_generic_propagates_unknown() {
                 id: t,
                 kind: GenericParamKind::Variant,
             }],
-        )],
+        )]
+        .into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
     };
 
     let (ty, _, _) = infer("ifs(true, 1, false, x, \"a\")", &ctx);
@@ -0,0 +1,98 @@
+use crate::semantic::{Context, FormulaLimits};
+use crate::{DiagnosticCode, DiagnosticKind, Span, analyze, analyze_syntax};
+
+fn limited(max_length: Option<usize>, max_depth: Option<usize>) -> Context {
+    Context::builder()
+        .with_builtins()
+        .limits(FormulaLimits {
+            max_length,
+            max_depth,
+        })
+        .build()
+}
+
+fn limit_diags(source: &str, ctx: &Context) -> Vec<(DiagnosticCode, String, Span)> {
+    analyze(source, ctx)
+        .diagnostics
+        .into_iter()
+        .filter(|diag| {
+            matches!(
+                diag.code,
+                DiagnosticCode::FormulaTooLong | DiagnosticCode::NestingTooDeep
+            )
+        })
+        .map(|diag| {
+            assert_eq!(diag.kind, DiagnosticKind::Error);
+            (diag.code, diag.message, diag.span)
+        })
+        .collect()
+}
+
+#[test]
+fn no_limits_by_default() {
+    let ctx = Context::with_builtins(Vec::new());
+    assert_eq!(ctx.limits, FormulaLimits::default());
+    assert!(analyze("abs(abs(abs(1)))", &ctx).diagnostics.is_empty());
+}
+
+#[test]
+fn length_counts_characters_and_points_past_the_cap() {
+    let ctx = limited(Some(5), None);
+    assert_eq!(limit_diags("\"éé\" + 1", &ctx).len(), 1);
+    assert_eq!(limit_diags("1 + 2", &ctx), []);
+
+    // `é` is two bytes: the sixth character starts at byte 7.
+    assert_eq!(
+        limit_diags("\"éé\" + \"x\"", &ctx),
+        [(
+            DiagnosticCode::FormulaTooLong,
+            "Formula is 10 characters long; Notion allows at most 5".into(),
+            Span { start: 7, end: 12 },
+        )]
+    );
+}
+
+#[test]
+fn depth_counts_groups_calls_and_lists() {
+    let ctx = limited(None, Some(2));
+    assert_eq!(limit_diags("abs(abs(1)) + [1, (2)].sum()", &ctx), []);
+
+    let source = "abs(abs([1]).sum()) + abs(abs(abs(1)))";
+    assert_eq!(
+        limit_diags(source, &ctx),
+        [(
+            DiagnosticCode::NestingTooDeep,
+            "Formula nests 3 levels deep; Notion allows at most 2".into(),
+            Span { start: 8, end: 11 },
+        )]
+    );
+    assert_eq!(&source[8..11], "[1]");
+}
+
+#[test]
+fn method_receivers_do_not_nest() {
+    let ctx = limited(None, Some(1));
+    assert_eq!(limit_diags("[1, 2].sum().abs().round()", &ctx), []);
+    assert_eq!(limit_diags("[1].map(current + 1)", &ctx), []);
+}
+
+#[test]
+fn limit_diagnostics_match_the_public_helper() {
+    let source = "abs(abs(1))";
+    let limits = FormulaLimits {
+        max_length: Some(3),
+        max_depth: Some(1),
+    };
+    let expr = analyze_syntax(source).expr;
+    let helper = crate::semantic::limit_diagnostics(source, &expr, &limits);
+    let codes: Vec<_> = helper.iter().map(|diag| diag.code).collect();
+    assert_eq!(
+        codes,
+        [
+            DiagnosticCode::FormulaTooLong,
+            DiagnosticCode::NestingTooDeep
+        ]
+    );
+    let ctx = limited(limits.max_length, limits.max_depth);
+    assert_eq!(analyze(source, &ctx).diagnostics, helper);
+}
@@ -10,8 +10,11 @@ fn infer_ok(source: &str) -> Ty {
     );
 
     let ctx = Context {
-        properties: vec![],
-        functions: vec![],
+        properties: vec![].into(),
+        functions: Vec::new().into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
     };
 
     let (ty, diags) = semantic::analyze_expr(&output.expr, &ctx);
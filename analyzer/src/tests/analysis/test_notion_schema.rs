@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use crate::semantic::{
+    NotionOptionSchema, NotionOptionsSchema, NotionPropertySchema, NotionRollupSchema, Ty,
+    notion_property_ty, notion_schema_properties,
+};
+
+fn schema(kind: &str) -> NotionPropertySchema {
+    NotionPropertySchema {
+        id: None,
+        kind: kind.to_string(),
+        rollup: None,
+        formula: None,
+        select: None,
+        status: None,
+        multi_select: None,
+    }
+}
+
+fn rollup(function: &str) -> NotionPropertySchema {
+    NotionPropertySchema {
+        id: None,
+        kind: "rollup".to_string(),
+        rollup: Some(NotionRollupSchema {
+            function: function.to_string(),
+        }),
+        formula: None,
+        select: None,
+        status: None,
+        multi_select: None,
+    }
+}
+
+#[test]
+fn property_types_map_to_formula_types() {
+    let list = |item: Ty| Ty::List(Box::new(item));
+    let cases = [
+        ("title", Ty::String),
+        ("rich_text", Ty::String),
+        ("url", Ty::String),
+        ("select", Ty::String),
+        ("status", Ty::String),
+        ("multi_select", list(Ty::String)),
+        ("number", Ty::Number),
+        ("checkbox", Ty::Boolean),
+        ("date", Ty::Date),
+        ("last_edited_time", Ty::Date),
+        ("people", list(Ty::Unknown)),
+        ("relation", list(Ty::Unknown)),
+        ("created_by", Ty::Unknown),
+        ("formula", Ty::Unknown),
+        ("rollup", Ty::Unknown),
+        ("something_new", Ty::Unknown),
+    ];
+    for (kind, expected) in cases {
+        assert_eq!(notion_property_ty(&schema(kind)), expected, "{kind}");
+    }
+}
+
+#[test]
+fn rollups_map_by_function() {
+    assert_eq!(notion_property_ty(&rollup("sum")), Ty::Number);
+    assert_eq!(notion_property_ty(&rollup("percent_checked")), Ty::Number);
+    assert_eq!(notion_property_ty(&rollup("latest_date")), Ty::Date);
+    assert_eq!(
+        notion_property_ty(&rollup("show_original")),
+        Ty::List(Box::new(Ty::Unknown))
+    );
+    assert_eq!(notion_property_ty(&rollup("date_range")), Ty::Unknown);
+}
+
+#[test]
+fn schema_properties_are_named_by_key_in_order() {
+    let schema = BTreeMap::from([
+        ("Tags".to_string(), schema("multi_select")),
+        ("Price".to_string(), schema("number")),
+    ]);
+    let properties = notion_schema_properties(&schema);
+    let names: Vec<_> = properties.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Price", "Tags"]);
+    assert_eq!(properties[0].ty, Ty::Number);
+    assert!(properties.iter().all(|p| p.disabled_reason.is_none()));
+}
+
+#[test]
+fn option_properties_carry_their_option_names() {
+    let options = |names: &[&str]| {
+        Some(NotionOptionsSchema {
+            options: names
+                .iter()
+                .map(|name| NotionOptionSchema {
+                    name: name.to_string(),
+                })
+                .collect(),
+        })
+    };
+    let schema = BTreeMap::from([
+        (
+            "Status".to_string(),
+            NotionPropertySchema {
+                status: options(&["Not started", "Done"]),
+                ..schema("status")
+            },
+        ),
+        (
+            "Tags".to_string(),
+            NotionPropertySchema {
+                multi_select: options(&["Urgent"]),
+                ..schema("multi_select")
+            },
+        ),
+        // Options of another kind's configuration are ignored.
+        (
+            "Title".to_string(),
+            NotionPropertySchema {
+                select: options(&["Stray"]),
+                ..schema("title")
+            },
+        ),
+    ]);
+    let properties = notion_schema_properties(&schema);
+    let options: Vec<_> = properties.iter().map(|p| p.options.clone()).collect();
+    assert_eq!(
+        options,
+        [vec!["Not started", "Done"], vec!["Urgent"], vec![]]
+    );
+}
@@ -0,0 +1,98 @@
+use std::sync::{Arc, Mutex};
+
+use crate::semantic::{Context, Property, PropertyPage, PropertyResolver, Ty};
+
+fn prop(name: &str, ty: Ty) -> Property {
+    Property {
+        name: name.into(),
+        ty,
+        disabled_reason: None,
+        options: Vec::new(),
+    }
+}
+
+/// Records every name it is asked to resolve.
+struct Recording {
+    props: Vec<Property>,
+    resolved: Arc<Mutex<Vec<String>>>,
+}
+
+impl PropertyResolver for Recording {
+    fn resolve(&self, name: &str) -> Option<Property> {
+        self.resolved.lock().unwrap().push(name.into());
+        self.props.resolve(name)
+    }
+
+    fn list(&self, prefix: &str, offset: usize, limit: usize) -> PropertyPage {
+        self.props.list(prefix, offset, limit)
+    }
+}
+
+#[test]
+fn analysis_resolves_only_names_missing_from_the_eager_properties() {
+    let resolved = Arc::default();
+    let ctx = Context::builder()
+        .prop("Local", Ty::Number)
+        .property_resolver(Recording {
+            props: vec![prop("Remote", Ty::Date), prop("Local", Ty::Boolean)],
+            resolved: Arc::clone(&resolved),
+        })
+        .with_builtins()
+        .build();
+
+    let out = crate::analyze(r#"prop("Local") + 1"#, &ctx);
+    assert!(out.diagnostics.is_empty(), "{:?}", out.diagnostics);
+    assert_eq!(ctx.lookup("Local"), Some(Ty::Number));
+    assert!(resolved.lock().unwrap().is_empty());
+
+    let out = crate::analyze(r#"dateAdd(prop("Remote"), 1, "days")"#, &ctx);
+    assert!(out.diagnostics.is_empty(), "{:?}", out.diagnostics);
+    assert_eq!(out.output_type, Ty::Date);
+
+    let out = crate::analyze(r#"prop("Missing")"#, &ctx);
+    let messages: Vec<&str> = out.diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(messages, ["Unknown property: Missing"]);
+
+    let mut names = resolved.lock().unwrap().clone();
+    names.dedup();
+    assert!(
+        names
+            .iter()
+            .all(|name| name == "Remote" || name == "Missing"),
+        "{names:?}"
+    );
+}
+
+#[test]
+fn vec_resolver_pages_through_prefix_matches() {
+    let props = vec![
+        prop("Alpha", Ty::Number),
+        prop("beta", Ty::Number),
+        prop("Also", Ty::Number),
+        prop("alt", Ty::Number),
+    ];
+    let names = |page: &PropertyPage| -> Vec<String> {
+        page.properties.iter().map(|p| p.name.clone()).collect()
+    };
+
+    let first = props.list("al", 0, 2);
+    assert_eq!(names(&first), ["Alpha", "Also"]);
+    assert_eq!(first.next_offset, Some(2));
+    let second = props.list("al", 2, 2);
+    assert_eq!(names(&second), ["alt"]);
+    assert_eq!(second.next_offset, None);
+    assert_eq!(props.list("", 0, 10).properties.len(), 4);
+}
+
+#[test]
+fn contexts_with_resolvers_compare_by_identity() {
+    let a = Context::builder()
+        .property_resolver(vec![prop("A", Ty::Number)])
+        .build();
+    assert_eq!(a.clone(), a);
+    let b = Context::builder()
+        .property_resolver(vec![prop("A", Ty::Number)])
+        .build();
+    assert_ne!(a, b);
+    assert_eq!(b.property("A").map(|p| p.ty.clone()), Some(Ty::Number));
+}
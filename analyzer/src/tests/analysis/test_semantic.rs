@@ -1,7 +1,7 @@
 use crate::semantic::{
     self, Context, FunctionCategory, FunctionSig, GenericId, ParamShape, ParamSig, Property, Ty,
 };
-use crate::{analyze_syntax, Span};
+use crate::{Span, analyze_syntax};
 
 fn p(name: &str, ty: Ty) -> ParamSig {
     ParamSig {
@@ -37,10 +37,7 @@ fn assert_single_diag(diags: Vec<crate::Diagnostic>, message: &str, span: Span)
 }
 
 fn ctx_with_builtins() -> Context {
-    Context {
-        properties: vec![],
-        functions: semantic::builtins_functions(),
-    }
+    Context::with_builtins(vec![])
 }
 
 #[test]
@@ -50,6 +47,7 @@ fn test_prop_ok() {
         name: "Title".into(),
         ty: Ty::String,
         disabled_reason: None,
+        options: Vec::new(),
     });
     let diags = run_semantic("prop(\"Title\")", ctx);
     assert!(diags.is_empty(), "unexpected diagnostics: {:?}", diags);
@@ -95,6 +93,7 @@ fn test_if_ok() {
         name: "Done".into(),
         ty: Ty::Boolean,
         disabled_reason: None,
+        options: Vec::new(),
     });
     let diags = run_semantic("if(prop(\"Done\"), 1, 2)", ctx);
     assert!(diags.is_empty(), "unexpected diagnostics: {:?}", diags);
@@ -106,7 +105,7 @@ fn test_if_cond_not_bool() {
     let diags = run_semantic("if(1, 1, 2)", ctx);
     assert_single_diag(
         diags,
-        "argument type mismatch: expected Boolean, got Number",
+        "argument type mismatch: expected `boolean`, got `number`",
         Span { start: 3, end: 4 },
     );
 }
@@ -176,6 +175,7 @@ fn test_sum_accepts_number_list_property() {
         name: "Nums".into(),
         ty: Ty::List(Box::new(Ty::Number)),
         disabled_reason: None,
+        options: Vec::new(),
     });
     let diags = run_semantic("sum(prop(\"Nums\"))", ctx);
     assert!(diags.is_empty(), "unexpected diagnostics: {:?}", diags);
@@ -211,17 +211,21 @@ fn validate_call_does_not_wildcard_inferred_actual_generic() {
             name: "x".into(),
             ty: Ty::Generic(GenericId(0)),
             disabled_reason: None,
-        }],
-        functions: vec![sig],
+            options: Vec::new(),
+        }]
+        .into(),
+        functions: vec![sig].into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
     };
 
     let (_, diags) = semantic::analyze_expr(&output.expr, &ctx);
 
     assert_eq!(diags.len(), 1, "unexpected diagnostics: {:?}", diags);
     assert!(diags[0].message.contains("argument type mismatch"));
-    assert!(diags[0].message.contains("expected"));
-    assert!(diags[0].message.contains("Number"));
-    assert!(diags[0].message.contains("Generic"));
+    assert!(diags[0].message.contains("expected `number`"));
+    assert!(diags[0].message.contains("got `T0`"));
     assert_eq!(diags[0].span, arg_span);
 }
 
@@ -243,3 +247,16 @@ fn required_min_args_repeat_group_counts_all_non_optional_in_head_and_tail() {
 
     assert_eq!(sig.required_min_args(), 3);
 }
+
+#[test]
+fn test_long_postfix_chain_infers_each_node_once() {
+    // Each `.add(1)` used to clone and re-infer its whole receiver chain, doubling per link.
+    let source = format!("1{}", ".add(1)".repeat(40));
+    let output = analyze_syntax(&source);
+    assert!(output.diagnostics.is_empty(), "{:?}", output.diagnostics);
+
+    let ctx = ctx_with_builtins();
+    let (ty, diags) = semantic::analyze_expr(&output.expr, &ctx);
+    assert!(diags.is_empty(), "unexpected diagnostics: {:?}", diags);
+    assert_eq!(ty, Ty::Number);
+}
@@ -1,5 +1,5 @@
-use crate::semantic::{self, builtins_functions, Context, Ty};
-use crate::{analyze_syntax, Span};
+use crate::semantic::{self, Context, Ty};
+use crate::{Span, analyze_syntax};
 
 fn infer_ok(source: &str, ctx: &Context) -> Ty {
     let output = analyze_syntax(source);
@@ -31,10 +31,7 @@ fn assert_single_diag(source: &str, ctx: &Context, message: &str, span: Span) {
 }
 
 fn builtins_ctx() -> Context {
-    Context {
-        properties: vec![],
-        functions: builtins_functions(),
-    }
+    Context::with_builtins(vec![])
 }
 
 #[test]
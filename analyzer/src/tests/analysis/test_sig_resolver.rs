@@ -3,8 +3,8 @@
 //! - `flat()` uses a custom resolver for depth-sensitive return types.
 //! - `padStart`, `padEnd`, `formatNumber`, `splice` are new builtins added alongside the resolver.
 
-use crate::semantic::{self, builtins_functions, Context, Ty};
-use crate::{analyze_syntax, Span};
+use crate::semantic::{self, Context, Ty};
+use crate::{Span, analyze_syntax};
 
 fn infer_ok(source: &str, ctx: &Context) -> Ty {
     let output = analyze_syntax(source);
@@ -46,10 +46,7 @@ fn assert_single_diag(source: &str, ctx: &Context, message: &str, span: Span) {
 }
 
 fn builtins_ctx() -> Context {
-    Context {
-        properties: vec![],
-        functions: builtins_functions(),
-    }
+    Context::with_builtins(vec![])
 }
 
 // ---------------------------------------------------------------------------
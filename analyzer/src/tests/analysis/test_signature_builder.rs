@@ -0,0 +1,84 @@
+use crate::analyze;
+use crate::semantic::{
+    Context, FunctionCategory, FunctionSig, GenericId, GenericParamKind, Ty, builtins_functions,
+};
+
+fn builtin(name: &str) -> &'static FunctionSig {
+    builtins_functions()
+        .iter()
+        .find(|sig| sig.name == name)
+        .unwrap()
+}
+
+#[test]
+fn rebuilds_builtin_signatures() {
+    let t = Ty::Generic(GenericId(0));
+    let ifs = FunctionSig::builder("ifs")
+        .detail(builtin("ifs").detail.clone())
+        .generic(0, GenericParamKind::Variant)
+        .repeat("condition1", Ty::Boolean)
+        .repeat("value1", t.clone())
+        .param("else", t.clone())
+        .returns(t.clone())
+        .build()
+        .unwrap();
+    assert_eq!(&ifs, builtin("ifs"));
+
+    let empty = FunctionSig::builder("empty")
+        .detail(builtin("empty").detail.clone())
+        .generic(0, GenericParamKind::Plain)
+        .optional("value", t)
+        .returns(Ty::Boolean)
+        .build()
+        .unwrap();
+    assert_eq!(&empty, builtin("empty"));
+}
+
+#[test]
+fn custom_functions_take_part_in_analysis() {
+    let clamp = FunctionSig::builder("clamp")
+        .category(FunctionCategory::Number)
+        .param("value", Ty::Number)
+        .param("min", Ty::Number)
+        .param("max", Ty::Number)
+        .returns(Ty::Number)
+        .build()
+        .unwrap();
+    let ctx = Context::builder().func(clamp).with_builtins().build();
+
+    let ok = analyze("clamp(5, 0, 10)", &ctx);
+    assert!(ok.diagnostics.is_empty(), "{:?}", ok.diagnostics);
+    assert_eq!(ok.output_type, Ty::Number);
+    assert!(!analyze(r#"clamp("5", 0)"#, &ctx).diagnostics.is_empty());
+}
+
+#[test]
+fn rejects_what_builtins_reject() {
+    let missing_return = FunctionSig::builder("f").build().unwrap_err();
+    assert!(
+        missing_return.message.contains("return type"),
+        "{missing_return}"
+    );
+
+    let undeclared = FunctionSig::builder("f")
+        .param("x", Ty::Generic(GenericId(0)))
+        .returns(Ty::Number)
+        .build()
+        .unwrap_err();
+    assert!(undeclared.message.contains("not declared"), "{undeclared}");
+
+    let unknown_param = FunctionSig::builder("f")
+        .param("x", Ty::List(Box::new(Ty::Unknown)))
+        .returns(Ty::Number)
+        .build()
+        .unwrap_err();
+    assert!(unknown_param.message.contains("`x`"), "{unknown_param}");
+
+    let optional_tail = FunctionSig::builder("f")
+        .repeat("x", Ty::Number)
+        .optional("y", Ty::Number)
+        .returns(Ty::Number)
+        .build()
+        .unwrap_err();
+    assert!(optional_tail.message.contains("tail"), "{optional_tail}");
+}
@@ -0,0 +1,85 @@
+use crate::analyze_syntax;
+use crate::semantic::{
+    Context, GenericId, Property, Ty, TyContext, TyId, TypeMap, builtins_functions,
+    infer_expr_with_map, normalize_union,
+};
+
+#[test]
+fn interning_is_structural() {
+    let mut tys = TyContext::new();
+    let list = Ty::List(Box::new(Ty::Union(vec![Ty::Number, Ty::String])));
+    let a = tys.intern(&list);
+    let b = tys.intern(&list.clone());
+    assert_eq!(a, b);
+    assert_eq!(tys.ty(a), &list);
+    assert_ne!(a, tys.intern(&Ty::List(Box::new(Ty::Number))));
+    assert_eq!(tys.intern(&Ty::Number), TyId::NUMBER);
+    assert_eq!(tys.intern(&Ty::Unknown), TyId::UNKNOWN);
+}
+
+#[test]
+fn union_matches_normalize_union() {
+    let cases = [
+        vec![Ty::String, Ty::Number],
+        vec![Ty::Number, Ty::String, Ty::Number],
+        vec![
+            Ty::Union(vec![Ty::String, Ty::Date]),
+            Ty::Null,
+            Ty::Union(vec![Ty::Date]),
+        ],
+        vec![Ty::Generic(GenericId(1)), Ty::List(Box::new(Ty::Boolean))],
+        vec![Ty::Union(vec![Ty::Number])],
+        vec![Ty::Union(vec![])],
+        vec![],
+    ];
+    for members in cases {
+        let mut tys = TyContext::new();
+        let ids: Vec<TyId> = members.iter().map(|ty| tys.intern(ty)).collect();
+        let id = tys.union(ids);
+        assert_eq!(tys.ty(id), &normalize_union(members.clone()), "{members:?}");
+    }
+}
+
+#[test]
+fn union_reuses_existing_ids() {
+    let mut tys = TyContext::new();
+    let a = tys.union([TyId::STRING, TyId::NUMBER]);
+    let b = tys.union([TyId::NUMBER, TyId::STRING, TyId::NUMBER]);
+    assert_eq!(a, b);
+    assert_eq!(tys.union([a]), a);
+    assert_eq!(tys.union([TyId::DATE]), TyId::DATE);
+    assert!(!tys.contains_unknown(a));
+    let with_unknown = tys.union([a, TyId::UNKNOWN]);
+    assert!(tys.contains_unknown(with_unknown));
+}
+
+#[test]
+fn type_map_equality_ignores_interning_order() {
+    let ctx = Context {
+        properties: vec![Property {
+            name: "Tags".into(),
+            ty: Ty::List(Box::new(Ty::String)),
+            disabled_reason: None,
+            options: Vec::new(),
+        }]
+        .into(),
+        functions: builtins_functions().into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
+    };
+    let expr = analyze_syntax(r#"if(true, prop("Tags"), [1, "a"])"#).expr;
+
+    let mut a = TypeMap::default();
+    let ty = infer_expr_with_map(&expr, &ctx, &mut a);
+
+    // Same types, interned in a different order.
+    let mut b = TypeMap::default();
+    b.insert(expr.id, Ty::Boolean);
+    let _ = infer_expr_with_map(&expr, &ctx, &mut b);
+
+    assert_eq!(a, b);
+    assert_eq!(a.get(expr.id), Some(&ty));
+    let id = a.get_id(expr.id).unwrap();
+    assert_eq!(a.tys().ty(id), &ty);
+}
@@ -1,4 +1,10 @@
+#[cfg(all(test, feature = "serde"))]
+mod test_grammar;
+#[cfg(test)]
+mod test_keywords;
 #[cfg(test)]
 mod test_lexer;
 #[cfg(test)]
+mod test_non_trivia;
+#[cfg(test)]
 mod test_tokens_in_span;
@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use crate::grammar::{
+    GRAMMAR_DIR, HIGHLIGHTJS_FILE, TEXTMATE_FILE, highlightjs_language, textmate_grammar,
+};
+use crate::lexer::{BOOL_LITERALS, KEYWORDS, LitKind, TokenKind, lex};
+
+fn single_token(text: &str) -> TokenKind {
+    let output = lex(text);
+    assert!(output.errors.is_empty(), "`{text}` does not lex");
+    match output.tokens.as_slice() {
+        [token, eof] if eof.kind == TokenKind::Eof => token.kind.clone(),
+        tokens => panic!("`{text}` lexes as {} tokens", tokens.len() - 1),
+    }
+}
+
+#[test]
+fn token_tables_match_the_lexer() {
+    for kind in TokenKind::FIXED {
+        let text = kind.to_str().expect("fixed tokens have text");
+        assert_eq!(&single_token(text), kind);
+    }
+    for word in KEYWORDS {
+        assert_eq!(single_token(word), TokenKind::Not);
+    }
+    for word in BOOL_LITERALS {
+        assert!(
+            matches!(single_token(word), TokenKind::Literal(lit) if lit.kind == LitKind::Bool),
+            "`{word}` is not a boolean literal"
+        );
+    }
+}
+
+#[test]
+fn published_grammars_are_up_to_date() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(GRAMMAR_DIR);
+    let textmate = serde_json::to_string_pretty(&textmate_grammar()).unwrap() + "\n";
+    for (file, expected) in [
+        (TEXTMATE_FILE, textmate),
+        (HIGHLIGHTJS_FILE, highlightjs_language()),
+    ] {
+        let path = dir.join(file);
+        let on_disk = std::fs::read_to_string(&path).expect("read grammar");
+        assert!(
+            on_disk == expected,
+            "{} is stale; run `just gen-grammars`",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn grammars_name_builtins_and_literals() {
+    let textmate = textmate_grammar().to_string();
+    let highlightjs = highlightjs_language();
+    for word in ["if", "formatDate", "prop", "true", "not"] {
+        assert!(textmate.contains(word), "TextMate grammar lacks `{word}`");
+        assert!(
+            highlightjs.contains(word),
+            "highlight.js language lacks `{word}`"
+        );
+    }
+}
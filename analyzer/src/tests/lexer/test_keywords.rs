@@ -0,0 +1,89 @@
+use crate::ast::ExprKind;
+use crate::lexer::{
+    BOOL_LITERALS, KEYWORDS, KeywordKind, Keywords, Lit, LitKind, Symbol, TokenKind, lex, lex_with,
+};
+
+fn kinds(input: &str, keywords: &Keywords) -> Vec<TokenKind> {
+    lex_with(input, keywords)
+        .tokens
+        .into_iter()
+        .map(|t| t.kind)
+        .collect()
+}
+
+fn ident(name: &str) -> TokenKind {
+    TokenKind::Ident(Symbol::new(name))
+}
+
+#[test]
+fn notion_table_matches_the_default_lexer_and_word_lists() {
+    let input = "not true false nothing True";
+    assert_eq!(
+        kinds(input, &Keywords::NOTION),
+        kinds(input, &Keywords::default())
+    );
+    assert_eq!(
+        kinds(input, &Keywords::NOTION),
+        lex(input)
+            .tokens
+            .into_iter()
+            .map(|t| t.kind)
+            .collect::<Vec<_>>()
+    );
+
+    let notion = Keywords::notion();
+    assert_eq!(
+        notion.words(|kind| !kind.is_constant()).collect::<Vec<_>>(),
+        KEYWORDS
+    );
+    assert_eq!(
+        notion.words(KeywordKind::is_constant).collect::<Vec<_>>(),
+        BOOL_LITERALS
+    );
+}
+
+#[test]
+fn custom_tables_add_replace_and_remove_words() {
+    let keywords = Keywords::NOTION
+        .with("and", KeywordKind::And)
+        .with("or", KeywordKind::Or)
+        .with("yes", KeywordKind::Bool)
+        .without("not");
+    assert_eq!(
+        kinds("yes and not or x", &keywords),
+        [
+            TokenKind::Literal(Lit {
+                kind: LitKind::Bool,
+                symbol: Symbol::new("yes"),
+            }),
+            TokenKind::AndAnd,
+            ident("not"),
+            TokenKind::OrOr,
+            ident("x"),
+            TokenKind::Eof,
+        ]
+    );
+
+    let replaced = Keywords::NOTION.with("not", KeywordKind::And);
+    assert_eq!(replaced.get("not"), Some(KeywordKind::And));
+    assert_eq!(replaced.iter().count(), 3);
+    assert_eq!(
+        kinds("true", &Keywords::empty()),
+        [ident("true"), TokenKind::Eof]
+    );
+}
+
+#[test]
+fn word_operators_parse_like_their_symbols() {
+    let keywords = Keywords::NOTION
+        .with("and", KeywordKind::And)
+        .with("or", KeywordKind::Or);
+    let words = crate::analyze_syntax_with("a and not b or c", &keywords);
+    let symbols = crate::analyze_syntax("a && not b || c");
+    assert!(words.diagnostics.is_empty(), "{:?}", words.diagnostics);
+    assert!(matches!(words.expr.kind, ExprKind::Binary { .. }));
+    assert_eq!(
+        format!("{:?}", words.expr.kind).replace(char::is_numeric, ""),
+        format!("{:?}", symbols.expr.kind).replace(char::is_numeric, "")
+    );
+}
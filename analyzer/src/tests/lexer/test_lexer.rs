@@ -1,5 +1,5 @@
 use crate::lexer::lex;
-use crate::lexer::{CommentKind, Lit, LitKind, Span, Symbol, Token, TokenKind};
+use crate::lexer::{Atom, CommentKind, LexError, Lit, LitKind, Span, Symbol, Token, TokenKind};
 
 fn tokens(input: &str) -> Vec<Token> {
     lex(input).tokens
@@ -17,54 +17,36 @@ fn spans(input: &str) -> Vec<(u32, u32)> {
 }
 
 fn ident(name: &str) -> TokenKind {
-    TokenKind::Ident(Symbol {
-        text: name.to_string(),
-    })
+    TokenKind::Ident(Symbol::new(name))
 }
 
 fn number(text: &str) -> TokenKind {
     TokenKind::Literal(Lit {
         kind: LitKind::Number,
-        symbol: Symbol {
-            text: text.to_string(),
-        },
+        symbol: Symbol::new(text),
     })
 }
 
 fn string_lit(text: &str) -> TokenKind {
     TokenKind::Literal(Lit {
         kind: LitKind::String,
-        symbol: Symbol {
-            text: text.to_string(),
-        },
+        symbol: Symbol::new(text),
     })
 }
 
 fn bool_lit(text: &str) -> TokenKind {
     TokenKind::Literal(Lit {
         kind: LitKind::Bool,
-        symbol: Symbol {
-            text: text.to_string(),
-        },
+        symbol: Symbol::new(text),
     })
 }
 
 fn line_comment(text: &str) -> TokenKind {
-    TokenKind::DocComment(
-        CommentKind::Line,
-        Symbol {
-            text: text.to_string(),
-        },
-    )
+    TokenKind::DocComment(CommentKind::Line, Symbol::new(text))
 }
 
 fn block_comment(text: &str) -> TokenKind {
-    TokenKind::DocComment(
-        CommentKind::Block,
-        Symbol {
-            text: text.to_string(),
-        },
-    )
+    TokenKind::DocComment(CommentKind::Block, Symbol::new(text))
 }
 
 #[test]
@@ -192,32 +174,105 @@ fn test_whitespace_skipping_spans() {
 #[test]
 fn test_lex_error_single_equals() {
     let output = lex("=");
-    assert_eq!(output.diagnostics.len(), 1);
-    assert!(output.diagnostics[0].message.contains("did you mean '=='"));
+    assert_eq!(output.errors.len(), 1);
+    assert!(output.errors[0].to_string().contains("did you mean '=='"));
+    assert!(matches!(
+        output.errors[0],
+        LexError::IncompleteOperator { expected: "==", .. }
+    ));
+}
+
+#[test]
+fn test_lex_single_operators_recover_as_double() {
+    let output = lex("a = b & c | d");
+    assert_eq!(output.errors.len(), 3);
+    assert_eq!(
+        output.errors[0],
+        LexError::IncompleteOperator {
+            span: Span { start: 2, end: 3 },
+            found: '=',
+            expected: "==",
+        }
+    );
+    assert_eq!(
+        kinds("a = b & c | d"),
+        vec![
+            ident("a"),
+            TokenKind::EqEq,
+            ident("b"),
+            TokenKind::AndAnd,
+            ident("c"),
+            TokenKind::OrOr,
+            ident("d"),
+            TokenKind::Eof
+        ]
+    );
+    assert_eq!(spans("a = b")[1], (2, 3));
 }
 
 #[test]
 fn test_lex_error_single_and() {
     let output = lex("&");
-    assert_eq!(output.diagnostics.len(), 1);
-    assert!(output.diagnostics[0].message.contains("did you mean '&&'"));
+    assert_eq!(output.errors.len(), 1);
+    assert!(output.errors[0].to_string().contains("did you mean '&&'"));
 }
 
 #[test]
 fn test_lex_error_single_or() {
     let output = lex("|");
-    assert_eq!(output.diagnostics.len(), 1);
-    assert!(output.diagnostics[0].message.contains("did you mean '||'"));
+    assert_eq!(output.errors.len(), 1);
+    assert!(output.errors[0].to_string().contains("did you mean '||'"));
 }
 
 #[test]
 fn test_lex_error_unknown_char() {
     let output = lex("@");
-    assert_eq!(output.diagnostics.len(), 1);
-    assert!(output.diagnostics[0].message.contains("unexpected char"));
-    assert!(output.diagnostics[0].message.contains("@"));
-    assert_eq!(output.diagnostics[0].span.start, 0);
-    assert_eq!(output.diagnostics[0].span.end, 1);
+    assert_eq!(output.errors.len(), 1);
+    assert!(output.errors[0].to_string().contains("unexpected char"));
+    assert!(output.errors[0].to_string().contains("@"));
+    assert_eq!(
+        output.errors[0],
+        LexError::UnexpectedChar {
+            span: Span { start: 0, end: 1 },
+            found: '@',
+        }
+    );
+}
+
+#[test]
+fn test_lex_error_lookalike_char() {
+    let output = lex("1 \u{2212} 2");
+    assert_eq!(
+        output.errors,
+        vec![LexError::LookalikeChar {
+            span: Span { start: 2, end: 5 },
+            found: '\u{2212}',
+            lookalike: crate::Lookalike {
+                ascii: "-",
+                description: "minus sign",
+            },
+        }]
+    );
+    assert_eq!(
+        output.errors[0].to_string(),
+        "unexpected minus sign '\u{2212}' (did you mean '-')"
+    );
+
+    let message = |source: &str| lex(source).errors[0].to_string();
+    assert_eq!(
+        message("1\u{00A0}+ 2"),
+        "unexpected non-breaking space U+00A0 (did you mean a space)"
+    );
+    assert_eq!(
+        message("1\u{200B}"),
+        "unexpected zero-width character U+200B (remove it)"
+    );
+    assert_eq!(
+        message("sum\u{FF08}1)"),
+        "unexpected full-width character '\u{FF08}' (did you mean '(')"
+    );
+    // Full-width letters are identifier chars, not lookalikes.
+    assert!(lex("\u{FF41}").errors.is_empty());
 }
 
 #[test]
@@ -231,10 +286,8 @@ fn test_empty_input_eof_span() {
 #[test]
 fn test_unterminated_string_error() {
     let output = lex("\"abc");
-    assert_eq!(output.diagnostics.len(), 1);
-    assert!(output.diagnostics[0]
-        .message
-        .contains("unterminated string"));
+    assert_eq!(output.errors.len(), 1);
+    assert!(output.errors[0].to_string().contains("unterminated string"));
 }
 
 #[test]
@@ -263,10 +316,8 @@ fn test_unterminated_string_recovers_partial_tokens() {
     let input = r#"if(prop("Number") > 10, prop("Text"), "Needs review)"#;
     let output = lex(input);
     assert!(!output.tokens.is_empty());
-    assert_eq!(output.diagnostics.len(), 1);
-    assert!(output.diagnostics[0]
-        .message
-        .contains("unterminated string"));
+    assert_eq!(output.errors.len(), 1);
+    assert!(output.errors[0].to_string().contains("unterminated string"));
 
     let has_prop = output
         .tokens
@@ -286,6 +337,28 @@ fn test_unterminated_string_recovers_partial_tokens() {
     assert!(has_number);
 }
 
+#[test]
+fn test_repeated_names_share_text() {
+    let first = tokens(r#"prop("Price") + prop("Price")"#);
+    let second = tokens("prop");
+    let symbols: Vec<&Atom> = first
+        .iter()
+        .chain(&second)
+        .filter_map(|tok| match &tok.kind {
+            TokenKind::Ident(sym) => Some(&sym.text),
+            TokenKind::Literal(lit) => Some(&lit.symbol.text),
+            _ => None,
+        })
+        .collect();
+    let [prop_a, price_a, prop_b, price_b, prop_c] = symbols[..] else {
+        panic!("unexpected tokens: {symbols:?}");
+    };
+    assert!(Atom::ptr_eq(prop_a, prop_b));
+    assert!(Atom::ptr_eq(prop_a, prop_c));
+    assert!(Atom::ptr_eq(price_a, price_b));
+    assert_eq!(*price_a, r#""Price""#);
+}
+
 // ---------------------------------------------------------------------------
 // Decimal and scientific notation numbers
 // ---------------------------------------------------------------------------
@@ -396,7 +469,7 @@ fn test_string_escape_sequences() {
     // The lexer stores the raw text including quotes and escape sequences.
     let input = r#""hello\nworld""#;
     let output = lex(input);
-    assert!(output.diagnostics.is_empty());
+    assert!(output.errors.is_empty());
     assert_eq!(output.tokens[0].kind, string_lit(r#""hello\nworld""#));
 }
 
@@ -405,7 +478,7 @@ fn test_string_escaped_quote() {
     // `"say \"hi\""` -- escaped quotes inside a string.
     let input = r#""say \"hi\"""#;
     let output = lex(input);
-    assert!(output.diagnostics.is_empty());
+    assert!(output.errors.is_empty());
     assert_eq!(output.tokens[0].kind, string_lit(r#""say \"hi\"""#));
     // Verify span covers entire string including the escaped quotes.
     assert_eq!(output.tokens[0].span.start, 0);
@@ -416,7 +489,7 @@ fn test_string_escaped_quote() {
 fn test_string_escaped_backslash() {
     let input = r#""a\\b""#;
     let output = lex(input);
-    assert!(output.diagnostics.is_empty());
+    assert!(output.errors.is_empty());
     assert_eq!(output.tokens[0].kind, string_lit(r#""a\\b""#));
 }
 
@@ -424,7 +497,7 @@ fn test_string_escaped_backslash() {
 fn test_string_escaped_tab() {
     let input = r#""a\tb""#;
     let output = lex(input);
-    assert!(output.diagnostics.is_empty());
+    assert!(output.errors.is_empty());
     assert_eq!(output.tokens[0].kind, string_lit(r#""a\tb""#));
 }
 
@@ -432,9 +505,9 @@ fn test_string_escaped_tab() {
 fn test_string_invalid_escape_emits_diagnostic() {
     let input = r#""a\xb""#;
     let output = lex(input);
-    assert_eq!(output.diagnostics.len(), 1);
-    assert!(output.diagnostics[0].message.contains("invalid escape"));
-    assert!(output.diagnostics[0].message.contains(r"\x"));
+    assert_eq!(output.errors.len(), 1);
+    assert!(output.errors[0].to_string().contains("invalid escape"));
+    assert!(output.errors[0].to_string().contains(r"\x"));
     // The string is still produced (with raw text).
     assert_eq!(output.tokens[0].kind, string_lit(r#""a\xb""#));
 }
@@ -443,10 +516,8 @@ fn test_string_invalid_escape_emits_diagnostic() {
 fn test_string_unterminated_with_trailing_backslash() {
     let input = r#""abc\"#;
     let output = lex(input);
-    assert_eq!(output.diagnostics.len(), 1);
-    assert!(output.diagnostics[0]
-        .message
-        .contains("unterminated string"));
+    assert_eq!(output.errors.len(), 1);
+    assert!(output.errors[0].to_string().contains("unterminated string"));
 }
 
 // ---------------------------------------------------------------------------
@@ -469,9 +540,9 @@ fn test_identifier_cjk_accepted() {
 fn test_identifier_emoji_rejected() {
     let input = "😀";
     let output = lex(input);
-    assert_eq!(output.diagnostics.len(), 1);
-    assert!(output.diagnostics[0].message.contains("unexpected char"));
-    assert!(output.diagnostics[0].message.contains("😀"));
+    assert_eq!(output.errors.len(), 1);
+    assert!(output.errors[0].to_string().contains("unexpected char"));
+    assert!(output.errors[0].to_string().contains("😀"));
 }
 
 #[test]
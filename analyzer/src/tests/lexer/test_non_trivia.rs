@@ -0,0 +1,23 @@
+use crate::lexer::{TokenKind, lex, non_trivia};
+
+#[test]
+fn non_trivia_skips_comments_newlines_and_eof() {
+    let tokens = lex("1 /* a */\n+ 2").tokens;
+    let kinds: Vec<(usize, &TokenKind)> = non_trivia(&tokens)
+        .map(|(idx, token)| (idx, &token.kind))
+        .collect();
+    assert_eq!(kinds.len(), 3);
+    assert_eq!(kinds[1].1, &TokenKind::Plus);
+    for (idx, kind) in kinds {
+        assert_eq!(&tokens[idx].kind, kind);
+    }
+}
+
+#[test]
+fn non_trivia_scans_backwards() {
+    let tokens = lex("a(\n)").tokens;
+    let (idx, last) = non_trivia(&tokens).next_back().unwrap();
+    assert_eq!(last.kind, TokenKind::CloseParen);
+    assert_eq!(idx, tokens.len() - 2);
+    assert!(non_trivia(&tokens[..0]).next().is_none());
+}
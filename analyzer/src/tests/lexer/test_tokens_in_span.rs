@@ -8,7 +8,7 @@ fn test_tokens_in_span() {
     // Exact boundary matches.
     let src = "(a+b)";
     let out = lex(src);
-    assert!(out.diagnostics.is_empty());
+    assert!(out.errors.is_empty());
     let tokens = out.tokens;
 
     let r = tokens_in_span(&tokens, span(1, 4)); // "a+b"
@@ -44,7 +44,7 @@ fn test_tokens_in_span() {
     // Trivia inside the queried span is included (intersection-based).
     let src = "a/*c*/+b";
     let out = lex(src);
-    assert!(out.diagnostics.is_empty());
+    assert!(out.errors.is_empty());
     let tokens = out.tokens;
 
     let r = tokens_in_span(&tokens, span(0, 8)); // whole expression
@@ -64,7 +64,7 @@ fn test_tokens_in_span() {
     // Span covering only trivia.
     let src = "a/*c*/b";
     let out = lex(src);
-    assert!(out.diagnostics.is_empty());
+    assert!(out.errors.is_empty());
     let tokens = out.tokens;
     let r = tokens_in_span(&tokens, span(1, 6));
     assert_eq!((r.lo, r.hi), (1, 2));
@@ -76,7 +76,7 @@ fn test_tokens_in_span() {
     // Span covering newline + comment + token.
     let src = "a\n/*c*/b";
     let out = lex(src);
-    assert!(out.diagnostics.is_empty());
+    assert!(out.errors.is_empty());
     let tokens = out.tokens;
     let r = tokens_in_span(&tokens, span(1, 8));
     assert_eq!((r.lo, r.hi), (1, 4));
@@ -90,7 +90,7 @@ fn test_tokens_in_span() {
     // Empty span: always empty result.
     let src = "(a+b)";
     let out = lex(src);
-    assert!(out.diagnostics.is_empty());
+    assert!(out.errors.is_empty());
     let tokens = out.tokens;
 
     let r = tokens_in_span(&tokens, span(2, 2));
@@ -8,3 +8,13 @@ mod analysis;
 mod lexer;
 #[cfg(test)]
 mod parser;
+#[cfg(test)]
+mod test_cache;
+#[cfg(test)]
+mod test_cancellation;
+#[cfg(all(test, feature = "serde"))]
+mod test_serde;
+#[cfg(test)]
+mod test_timing;
+#[cfg(test)]
+mod test_source_map;
@@ -3,6 +3,8 @@ mod test_ast_regression;
 #[cfg(test)]
 mod test_errors;
 #[cfg(test)]
+mod test_incremental;
+#[cfg(test)]
 mod test_invariants;
 #[cfg(test)]
 mod test_list_literal;
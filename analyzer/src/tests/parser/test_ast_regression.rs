@@ -17,25 +17,19 @@ fn test_analyze_single_line() {
             id: 6,
             span: Span { start: 0, end: 23 },
             kind: ExprKind::Call {
-                callee: Symbol {
-                    text: "if".to_string()
-                },
+                callee: Symbol::new("if"),
                 args: vec![
                     Expr {
                         id: 3,
                         span: Span { start: 3, end: 16 },
                         kind: ExprKind::Call {
-                            callee: Symbol {
-                                text: "prop".to_string()
-                            },
+                            callee: Symbol::new("prop"),
                             args: vec![Expr {
                                 id: 2,
                                 span: Span { start: 8, end: 15 },
                                 kind: ExprKind::Lit(Lit {
                                     kind: LitKind::String,
-                                    symbol: Symbol {
-                                        text: "Title".to_string(),
-                                    },
+                                    symbol: Symbol::new("Title"),
                                 }),
                             }],
                         },
@@ -45,9 +39,7 @@ fn test_analyze_single_line() {
                         span: Span { start: 18, end: 19 },
                         kind: ExprKind::Lit(Lit {
                             kind: LitKind::Number,
-                            symbol: Symbol {
-                                text: "1".to_string(),
-                            },
+                            symbol: Symbol::new("1"),
                         }),
                     },
                     Expr {
@@ -55,9 +47,7 @@ fn test_analyze_single_line() {
                         span: Span { start: 21, end: 22 },
                         kind: ExprKind::Lit(Lit {
                             kind: LitKind::Number,
-                            symbol: Symbol {
-                                text: "0".to_string(),
-                            },
+                            symbol: Symbol::new("0"),
                         }),
                     },
                 ],
@@ -85,25 +75,19 @@ fn test_analyze_multiple_lines() {
             id: 6,
             span: Span { start: 0, end: 37 },
             kind: ExprKind::Call {
-                callee: Symbol {
-                    text: "if".to_string()
-                },
+                callee: Symbol::new("if"),
                 args: vec![
                     Expr {
                         id: 3,
                         span: Span { start: 8, end: 21 },
                         kind: ExprKind::Call {
-                            callee: Symbol {
-                                text: "prop".to_string()
-                            },
+                            callee: Symbol::new("prop"),
                             args: vec![Expr {
                                 id: 2,
                                 span: Span { start: 13, end: 20 },
                                 kind: ExprKind::Lit(Lit {
                                     kind: LitKind::String,
-                                    symbol: Symbol {
-                                        text: "Title".to_string()
-                                    }
+                                    symbol: Symbol::new("Title")
                                 }),
                             }],
                         },
@@ -113,9 +97,7 @@ fn test_analyze_multiple_lines() {
                         span: Span { start: 27, end: 28 },
                         kind: ExprKind::Lit(Lit {
                             kind: LitKind::Number,
-                            symbol: Symbol {
-                                text: "1".to_string()
-                            },
+                            symbol: Symbol::new("1"),
                         }),
                     },
                     Expr {
@@ -123,9 +105,7 @@ fn test_analyze_multiple_lines() {
                         span: Span { start: 34, end: 35 },
                         kind: ExprKind::Lit(Lit {
                             kind: LitKind::Number,
-                            symbol: Symbol {
-                                text: "0".to_string()
-                            },
+                            symbol: Symbol::new("0"),
                         }),
                     }
                 ]
@@ -154,9 +134,7 @@ fn test_precedence() {
                     span: Span { start: 0, end: 1 },
                     kind: ExprKind::Lit(Lit {
                         kind: LitKind::Number,
-                        symbol: Symbol {
-                            text: "1".to_string()
-                        }
+                        symbol: Symbol::new("1")
                     })
                 }),
                 right: Box::new(Expr {
@@ -172,9 +150,7 @@ fn test_precedence() {
                             span: Span { start: 4, end: 5 },
                             kind: ExprKind::Lit(Lit {
                                 kind: LitKind::Number,
-                                symbol: Symbol {
-                                    text: "2".to_string()
-                                }
+                                symbol: Symbol::new("2")
                             })
                         }),
                         right: Box::new(Expr {
@@ -182,9 +158,7 @@ fn test_precedence() {
                             span: Span { start: 8, end: 9 },
                             kind: ExprKind::Lit(Lit {
                                 kind: LitKind::Number,
-                                symbol: Symbol {
-                                    text: "3".to_string()
-                                }
+                                symbol: Symbol::new("3")
                             })
                         }),
                     }
@@ -1,6 +1,7 @@
 use crate::analyze_syntax;
 use crate::ast::ExprKind;
-use crate::diagnostics::DiagnosticKind;
+use crate::diagnostics::{Diagnostic, DiagnosticCode, DiagnosticKind, ParseDiagnostic};
+use crate::{ParseError, Span, TextEdit};
 
 #[test]
 fn test_trailing_tokens_error() {
@@ -182,6 +183,50 @@ fn diagnostics_mismatched_delimiter_suggests_replacement() {
     );
 }
 
+#[test]
+fn diagnostics_lookalike_char_offers_its_ascii() {
+    let result = analyze_syntax("prop(\u{201C}Title\u{201D})");
+    let diag = result
+        .diagnostics
+        .iter()
+        .find(|diag| diag.code == DiagnosticCode::LexError)
+        .unwrap();
+    assert_eq!(
+        diag.message,
+        "unexpected smart quote '\u{201C}' (did you mean '\"')"
+    );
+    assert_eq!(diag.actions.len(), 1);
+    assert_eq!(diag.actions[0].title, "Replace with '\"'");
+    assert_eq!(
+        diag.actions[0].edits,
+        vec![TextEdit {
+            range: Span { start: 5, end: 8 },
+            new_text: "\"".into(),
+        }]
+    );
+}
+
+#[test]
+fn diagnostics_single_operator_offers_the_missing_char() {
+    let result = analyze_syntax("1 = 2 & true");
+    assert_eq!(result.diagnostics.len(), 2, "{:?}", result.diagnostics);
+    let diag = &result.diagnostics[0];
+    assert_eq!(diag.code, DiagnosticCode::LexError);
+    assert_eq!(diag.message, "unexpected char '=' (did you mean '==')");
+    assert_eq!(diag.actions.len(), 1);
+    assert_eq!(diag.actions[0].title, "Insert `=`");
+    assert_eq!(
+        diag.actions[0].edits,
+        vec![TextEdit {
+            range: Span { start: 3, end: 3 },
+            new_text: "=".into(),
+        }]
+    );
+    assert_eq!(result.diagnostics[1].actions[0].title, "Insert `&`");
+    // The rest of the formula still parses.
+    assert!(matches!(result.expr.kind, ExprKind::Binary { .. }));
+}
+
 #[test]
 fn diagnostics_lex_error_has_no_actions() {
     let result = analyze_syntax("1 @");
@@ -196,3 +241,21 @@ fn diagnostics_lex_error_has_no_actions() {
         result.diagnostics
     );
 }
+
+#[test]
+fn parse_error_converts_into_its_diagnostic() {
+    let error = ParseError::MissingOperand {
+        span: Span { start: 2, end: 3 },
+        operator: "`+`".into(),
+    };
+    let diagnostic = Diagnostic::from(error.clone());
+    assert_eq!(
+        diagnostic.code,
+        DiagnosticCode::Parse(ParseDiagnostic::MissingExpr)
+    );
+    assert_eq!(diagnostic.message, "expected expression after `+`");
+    assert_eq!(diagnostic.span, error.span());
+
+    let result = analyze_syntax("1 +");
+    assert_eq!(result.diagnostics[0].message, diagnostic.message);
+}
@@ -0,0 +1,137 @@
+use crate::ast::ExprKind;
+use crate::lexer::{Span, Symbol, Token, TokenKind, relex};
+use crate::{SyntaxResult, TextEdit, analyze_syntax, reparse_syntax};
+
+/// Replaces the first `old` in `text` (after `skip` bytes) with `new`.
+fn edit(text: &str, skip: usize, old: &str, new: &str) -> (String, TextEdit) {
+    let start = skip + text[skip..].find(old).expect("edit target");
+    let end = start + old.len();
+    let new_text = format!("{}{new}{}", &text[..start], &text[end..]);
+    let edit = TextEdit {
+        range: Span {
+            start: start as u32,
+            end: end as u32,
+        },
+        new_text: new.to_string(),
+    };
+    (new_text, edit)
+}
+
+fn kinds(tokens: &[Token]) -> Vec<(TokenKind, Span)> {
+    tokens
+        .iter()
+        .map(|tok| (tok.kind.clone(), tok.span))
+        .collect()
+}
+
+fn assert_same(actual: &SyntaxResult, expected: &SyntaxResult) {
+    assert_eq!(kinds(&actual.tokens), kinds(&expected.tokens));
+    assert_eq!(actual.expr, expected.expr);
+    assert_eq!(actual.diagnostics, expected.diagnostics);
+}
+
+#[test]
+fn test_reparse_matches_full_parse() {
+    let cases: &[(&str, usize, &str, &str)] = &[
+        // Inside one argument.
+        ("f(1, 2 + 3, x)", 0, "2", "20 * 4"),
+        ("f(1, 2 + 3, x)", 0, "x", "g(y, [1, 2])"),
+        ("if(a > 1, \"yes\", \"no\")", 0, "\"yes\"", "\"y\" + \"es\""),
+        ("a.b(c, d).e(f)", 0, "f", "f + 1"),
+        ("[1, [2, 3], 4]", 0, "3", "3, 5"),
+        ("(1 + 2) * 3", 0, "2", "2 / 7"),
+        ("f(\n  1, // one\n  2\n)", 0, "2", "2 + 2"),
+        ("f(1.5, 2)", 0, "1.5", "1.5e+3"),
+        (
+            "prop(\"A\") + f(prop(\"B\"))",
+            12,
+            "prop(\"B\")",
+            "prop(\"C\") - 1",
+        ),
+        // Edits that change delimiters, break the tree, or touch the top level.
+        ("f(1, 2)", 0, ", 2", ""),
+        ("f(1, 2)", 0, "2", ""),
+        ("f(1, 2)", 0, "2", "2)"),
+        ("f(1, 2)", 0, "f", "g"),
+        ("1 + 2", 0, "2", "3"),
+        ("f(1, 2)", 0, "2", "\"unterminated"),
+        ("f(\"a\", 2)", 0, "\"a\", 2", "\"a, 2"),
+        ("f(1, 2)", 0, "2", "/* comment */ 2"),
+        ("f(1, /* c */ 2)", 0, "/* c */", "/* c "),
+        ("f(1 +, 2)", 0, "1 +", "1 + 4"),
+        ("f(a, b)", 0, "b", "b ? c : d"),
+        ("x.y()", 0, "()", "(1)"),
+        ("", 0, "", "f(1)"),
+    ];
+
+    for &(text, skip, old, new) in cases {
+        let (new_text, text_edit) = edit(text, skip, old, new);
+        let actual = reparse_syntax(analyze_syntax(text), &new_text, &text_edit);
+        let expected = analyze_syntax(&new_text);
+        assert_same(&actual, &expected);
+    }
+}
+
+#[test]
+fn test_reparse_sequence_of_keystrokes() {
+    let target = r#"if(prop("Price") > 10, [prop("Price"), 2.5e3], sum(1, 2) / 3)"#;
+    let mut text = String::new();
+    let mut syntax = analyze_syntax(&text);
+    for ch in target.chars() {
+        let offset = text.len() as u32;
+        text.push(ch);
+        let text_edit = TextEdit {
+            range: Span {
+                start: offset,
+                end: offset,
+            },
+            new_text: ch.to_string(),
+        };
+        syntax = reparse_syntax(syntax, &text, &text_edit);
+        assert_same(&syntax, &analyze_syntax(&text));
+    }
+
+    // Now edit inside the finished formula, one argument at a time.
+    for (old, new) in [
+        ("10", "100"),
+        ("2.5e3", "2.5e3 + 1"),
+        ("sum(1, 2)", "sum(4)"),
+    ] {
+        let (new_text, text_edit) = edit(&text, 0, old, new);
+        syntax = reparse_syntax(syntax, &new_text, &text_edit);
+        text = new_text;
+        assert_same(&syntax, &analyze_syntax(&text));
+    }
+}
+
+#[test]
+fn test_reparse_reuses_nodes_outside_the_edited_argument() {
+    let text = "f(1, 2, 3)";
+    let mut old = analyze_syntax(text);
+    // Mark the first argument; only a reparse confined to the second argument keeps the mark.
+    let ExprKind::Call { args, .. } = &mut old.expr.kind else {
+        panic!("expected call");
+    };
+    args[0].kind = ExprKind::Ident(Symbol::new("marked"));
+
+    let (new_text, text_edit) = edit(text, 0, "2", "20");
+    let reparsed = reparse_syntax(old, &new_text, &text_edit);
+    let ExprKind::Call { args, .. } = &reparsed.expr.kind else {
+        panic!("expected call");
+    };
+    assert_eq!(args[0].kind, ExprKind::Ident(Symbol::new("marked")));
+    assert_eq!(args[1].span, Span { start: 5, end: 7 });
+    assert_eq!(args[2].span, Span { start: 9, end: 10 });
+}
+
+#[test]
+fn test_relex_stops_after_the_edit() {
+    let text = "f(1, 2) + g(3)";
+    let old = analyze_syntax(text);
+    let (new_text, text_edit) = edit(text, 0, "2", "22");
+    let tokens = relex(&new_text, &old.tokens, &text_edit).expect("clean relex");
+    assert_eq!(kinds(&tokens), kinds(&analyze_syntax(&new_text).tokens));
+
+    let (new_text, text_edit) = edit(text, 0, ")", "\"");
+    assert!(relex(&new_text, &old.tokens, &text_edit).is_none());
+}
@@ -92,9 +92,9 @@ fn test_span_and_tokenrange_invariants() {
     for input in cases {
         let output = lex(input);
         assert!(
-            output.diagnostics.is_empty(),
+            output.errors.is_empty(),
             "expected no lex errors for {input:?}, got {:?}",
-            output.diagnostics
+            output.errors
         );
         let tokens = output.tokens;
         let token_cursor = TokenCursor::new(input, tokens.clone());
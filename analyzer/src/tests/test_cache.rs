@@ -0,0 +1,155 @@
+use crate::semantic::{Context, FormulaLimits, Property, PropertyLookup, Ty};
+use crate::{AnalysisCache, CacheStats, analyze};
+
+fn property(name: &str, ty: Ty) -> Property {
+    Property {
+        name: name.into(),
+        ty,
+        disabled_reason: None,
+        options: Vec::new(),
+    }
+}
+
+fn ctx(properties: Vec<Property>) -> Context {
+    Context::with_builtins(properties)
+}
+
+#[test]
+fn results_match_uncached_analysis() {
+    let ctx = ctx(vec![property("Title", Ty::String)]);
+    let mut cache = AnalysisCache::new(8);
+    for source in [
+        r#"prop("Title").length()"#,
+        r#"prop("Missing") + 1"#,
+        "if(true, 1,",
+    ] {
+        let plain = analyze(source, &ctx);
+        for _ in 0..2 {
+            let cached = cache.analyze(source, &ctx);
+            assert_eq!(cached.diagnostics, plain.diagnostics);
+            assert_eq!(cached.tokens.len(), plain.tokens.len());
+            assert_eq!(cached.output_type, plain.output_type);
+        }
+    }
+    assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 3 });
+}
+
+#[test]
+fn unrelated_context_changes_keep_the_entry() {
+    let source = r#"prop("Title").length()"#;
+    let mut cache = AnalysisCache::new(8);
+    cache.analyze(source, &ctx(vec![property("Title", Ty::String)]));
+
+    let changed = ctx(vec![
+        property("Due", Ty::Date),
+        property("Title", Ty::String),
+    ]);
+    cache.analyze(source, &changed);
+    assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+}
+
+#[test]
+fn referenced_property_changes_invalidate_the_entry() {
+    let source = r#"prop("Title")"#;
+    let mut cache = AnalysisCache::new(8);
+    assert_eq!(
+        cache
+            .analyze(source, &ctx(vec![property("Title", Ty::String)]))
+            .output_type,
+        Ty::String
+    );
+    assert_eq!(
+        cache
+            .analyze(source, &ctx(vec![property("Title", Ty::Number)]))
+            .output_type,
+        Ty::Number
+    );
+    assert_eq!(
+        cache.analyze(source, &ctx(Vec::new())).output_type,
+        Ty::Unknown
+    );
+    assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 3 });
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn referenced_function_changes_invalidate_the_entry() {
+    let source = "abs(1)";
+    let mut cache = AnalysisCache::new(8);
+    let with_builtins = ctx(Vec::new());
+    assert!(cache.analyze(source, &with_builtins).diagnostics.is_empty());
+
+    let without_functions = Context {
+        properties: Vec::new().into(),
+        functions: Vec::new().into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
+    };
+    assert!(
+        !cache
+            .analyze(source, &without_functions)
+            .diagnostics
+            .is_empty()
+    );
+    assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+}
+
+#[test]
+fn evicts_the_least_recently_used_entry() {
+    let ctx = ctx(Vec::new());
+    let mut cache = AnalysisCache::new(2);
+    cache.analyze("1", &ctx);
+    cache.analyze("2", &ctx);
+    cache.analyze("1", &ctx);
+    cache.analyze("3", &ctx);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 3 });
+
+    cache.analyze("1", &ctx);
+    cache.analyze("2", &ctx);
+    assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 4 });
+}
+
+#[test]
+fn zero_capacity_still_holds_the_latest_result() {
+    let ctx = ctx(Vec::new());
+    let mut cache = AnalysisCache::new(0);
+    assert_eq!(cache.capacity(), 1);
+    cache.analyze("1", &ctx);
+    cache.analyze("2", &ctx);
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.stats(), CacheStats::default());
+}
+
+#[test]
+fn lenient_matches_are_told_apart_from_exact_ones() {
+    let source = r#"prop("title")"#;
+    let mut cache = AnalysisCache::new(8);
+    let mut exact = ctx(vec![property("title", Ty::String)]);
+    exact.property_lookup = PropertyLookup::Lenient;
+    let mut lenient = ctx(vec![property("Title", Ty::String)]);
+    lenient.property_lookup = PropertyLookup::Lenient;
+
+    assert!(cache.analyze(source, &exact).diagnostics.is_empty());
+    assert_eq!(cache.analyze(source, &lenient).diagnostics.len(), 1);
+    assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+}
+
+#[test]
+fn changing_limits_invalidates() {
+    let source = "abs(abs(1))";
+    let mut cache = AnalysisCache::new(8);
+    let mut ctx = ctx(vec![]);
+    assert!(cache.analyze(source, &ctx).diagnostics.is_empty());
+
+    ctx.limits = FormulaLimits {
+        max_length: None,
+        max_depth: Some(1),
+    };
+    assert_eq!(cache.analyze(source, &ctx).diagnostics.len(), 1);
+    assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+}
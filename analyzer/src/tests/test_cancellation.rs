@@ -0,0 +1,71 @@
+use crate::ast::ExprKind;
+use crate::lexer::lex;
+use crate::parser::{Parser, TokenCursor};
+use crate::semantic::{Context, Ty};
+use crate::{
+    CancellationToken, Cancelled, analyze, analyze_cancellable, analyze_syntax,
+    analyze_syntax_cancellable,
+};
+
+fn empty_ctx() -> Context {
+    Context {
+        properties: Vec::new().into(),
+        functions: Vec::new().into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
+    }
+}
+
+#[test]
+fn cancellable_entry_points_match_plain_ones_when_not_cancelled() {
+    let source = "if(true, 1, 2) + [1, 2].length()";
+    let ctx = empty_ctx();
+    let cancel = CancellationToken::new();
+
+    let syntax = analyze_syntax_cancellable(source, &cancel).unwrap();
+    let plain = analyze_syntax(source);
+    assert_eq!(syntax.expr, plain.expr);
+    assert_eq!(syntax.diagnostics, plain.diagnostics);
+
+    let out = analyze_cancellable(source, &ctx, &cancel).unwrap();
+    let plain = analyze(source, &ctx);
+    assert_eq!(out.diagnostics, plain.diagnostics);
+    assert_eq!(out.output_type, plain.output_type);
+}
+
+#[test]
+fn cancelled_token_stops_queries() {
+    let cancel = CancellationToken::new();
+    cancel.clone().cancel();
+
+    assert!(cancel.is_cancelled());
+    assert_eq!(
+        analyze_syntax_cancellable("1 + 2", &cancel).err(),
+        Some(Cancelled)
+    );
+    assert_eq!(
+        analyze_cancellable("1 + 2", &empty_ctx(), &cancel).err(),
+        Some(Cancelled)
+    );
+}
+
+#[test]
+fn cancelled_parser_skips_to_eof() {
+    let source = "f(1, [2, (3 + 4)], g(5";
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+
+    let tokens = lex(source).tokens;
+    let out = Parser::new(TokenCursor::new(source, tokens))
+        .with_cancellation(cancel)
+        .parse();
+
+    assert!(matches!(out.expr.kind, ExprKind::Error));
+}
+
+#[test]
+fn analyze_cancellable_reports_types() {
+    let out = analyze_cancellable("1 > 2", &empty_ctx(), &CancellationToken::new()).unwrap();
+    assert_eq!(out.output_type, Ty::Boolean);
+}
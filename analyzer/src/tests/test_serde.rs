@@ -0,0 +1,61 @@
+use serde_json::json;
+
+use crate::semantic::Context;
+use crate::{Diagnostic, DiagnosticCode, Span, Token, analyze, lex};
+
+#[test]
+fn diagnostic_serializes_with_its_code_name() {
+    let result = analyze("(1 + 2", &Context::builder().build());
+    let diagnostic = &result.diagnostics[0];
+    let value = serde_json::to_value(diagnostic).unwrap();
+
+    assert_eq!(value["code"], "unclosed_delimiter");
+    assert_eq!(value["kind"], "Error");
+    assert_eq!(value["span"], json!({ "start": 6, "end": 6 }));
+    assert_eq!(value["actions"][0]["edits"][0]["new_text"], ")");
+
+    let back: Diagnostic = serde_json::from_value(value).unwrap();
+    assert_eq!(&back, diagnostic);
+}
+
+#[test]
+fn every_diagnostic_code_round_trips() {
+    for code in DiagnosticCode::ALL {
+        let value = serde_json::to_value(code).unwrap();
+        assert_eq!(value, code.as_str());
+        assert_eq!(
+            serde_json::from_value::<DiagnosticCode>(value).unwrap(),
+            code
+        );
+    }
+    assert!(serde_json::from_value::<DiagnosticCode>(json!("nope")).is_err());
+}
+
+#[test]
+fn tokens_serialize_symbols_as_strings() {
+    let tokens = lex(r#"prop("A") // c"#).tokens;
+    let value = serde_json::to_value(&tokens).unwrap();
+
+    assert_eq!(
+        value[0],
+        json!({ "kind": { "Ident": "prop" }, "span": { "start": 0, "end": 4 } })
+    );
+    assert_eq!(
+        value[2]["kind"],
+        json!({ "Literal": { "kind": "String", "symbol": "\"A\"" } })
+    );
+    assert_eq!(value[4]["kind"], json!({ "DocComment": ["Line", " c"] }));
+    assert_eq!(value[5]["kind"], "Eof");
+
+    let back: Vec<Token> = serde_json::from_value(value).unwrap();
+    assert_eq!(back.len(), tokens.len());
+    assert!(
+        back.iter()
+            .zip(&tokens)
+            .all(|(a, b)| a.kind == b.kind && a.span == b.span)
+    );
+    assert_eq!(
+        serde_json::from_value::<Span>(json!({ "start": 1, "end": 2 })).unwrap(),
+        Span { start: 1, end: 2 }
+    );
+}
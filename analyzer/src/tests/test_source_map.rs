@@ -0,0 +1,109 @@
+use crate::{LineIndex, SourceMap, Span, TextEdit, byte_to_utf16_offset, utf16_offset_to_byte};
+
+fn edit(start: u32, end: u32, new_text: &str) -> TextEdit {
+    TextEdit {
+        range: Span { start, end },
+        new_text: new_text.to_string(),
+    }
+}
+
+/// Applies non-overlapping edits back to front.
+fn apply(source: &str, edits: &[TextEdit]) -> String {
+    let mut edits = edits.to_vec();
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+    let mut out = source.to_string();
+    for edit in edits.iter().rev() {
+        out.replace_range(
+            edit.range.start as usize..edit.range.end as usize,
+            &edit.new_text,
+        );
+    }
+    out
+}
+
+#[test]
+fn source_map_line_col_is_one_based_char_columns() {
+    let source = "1 +\n\"é\" +\n\n2";
+    let sm = SourceMap::new(source);
+
+    assert_eq!(sm.line_col(0), (1, 1));
+    assert_eq!(sm.line_col(3), (1, 4));
+    assert_eq!(sm.line_col(4), (2, 1));
+    // After the two-byte `é`.
+    assert_eq!(sm.line_col(7), (2, 3));
+    // Inside `é`: clamped down to its start.
+    assert_eq!(sm.line_col(6), (2, 2));
+    assert_eq!(sm.line_col(11), (3, 1));
+    assert_eq!(sm.line_col(99), (4, 2));
+}
+
+#[test]
+fn line_index_patched_by_edits_matches_rebuilt_index() {
+    let source = "if(\n  a,\n  b\n)\n";
+    let cases: &[&[TextEdit]] = &[
+        &[edit(0, 0, "\n")],
+        &[edit(3, 4, "")],
+        &[edit(3, 9, "x\ny\nz")],
+        &[edit(15, 15, "\n\n")],
+        &[edit(2, 14, "")],
+        &[edit(0, 15, "1")],
+        &[edit(9, 9, "\n"), edit(0, 1, ""), edit(13, 14, "\n)")],
+        &[edit(4, 4, "a\n"), edit(4, 4, "\nb"), edit(4, 8, "")],
+    ];
+
+    for edits in cases {
+        let mut index = LineIndex::new(source);
+        index.apply_edits(edits);
+        let new_source = apply(source, edits);
+        assert_eq!(index, LineIndex::new(&new_source), "{new_source:?}");
+    }
+}
+
+#[test]
+fn source_map_columns_in_utf8_and_utf16_units() {
+    // `é` is 2 bytes and 1 UTF-16 unit; `😀` is 4 bytes and 2 UTF-16 units.
+    let source = "\"é😀\" +\nx";
+    let sm = SourceMap::new(source);
+
+    assert_eq!(sm.line_col_utf8(7), (1, 8));
+    assert_eq!(sm.line_col_utf16(7), (1, 5));
+    assert_eq!(sm.line_col(7), (1, 4));
+    // Inside `😀`: floored to its start.
+    assert_eq!(sm.line_col_utf16(5), (1, 3));
+    assert_eq!(sm.line_col_utf16(11), (2, 1));
+    assert_eq!(sm.line_col_utf16(99), (2, 2));
+}
+
+#[test]
+fn source_map_offsets_round_trip_and_clamp() {
+    let source = "\"é😀\" +\nx";
+    let sm = SourceMap::new(source);
+
+    for byte in [0, 1, 3, 7, 8, 10, 11, 12] {
+        let (line, col) = sm.line_col_utf8(byte);
+        assert_eq!(sm.offset_utf8(line, col), byte, "utf8 {byte}");
+        let (line, col) = sm.line_col_utf16(byte);
+        assert_eq!(sm.offset_utf16(line, col), byte, "utf16 {byte}");
+    }
+
+    // Inside `😀`: floored to its start.
+    assert_eq!(sm.offset_utf8(1, 6), 3);
+    assert_eq!(sm.offset_utf16(1, 4), 3);
+    // Past the end of a line: its end, before the `\n`.
+    assert_eq!(sm.offset_utf16(1, 99), 10);
+    // Past the last line: the end of the source.
+    assert_eq!(sm.offset_utf8(9, 1), 12);
+}
+
+#[test]
+fn utf16_offsets_convert_whole_source() {
+    let source = "a😀b";
+    assert_eq!(utf16_offset_to_byte(source, 1), 1);
+    assert_eq!(utf16_offset_to_byte(source, 2), 1);
+    assert_eq!(utf16_offset_to_byte(source, 3), 5);
+    assert_eq!(utf16_offset_to_byte(source, 99), 6);
+    assert_eq!(byte_to_utf16_offset(source, 3), 1);
+    assert_eq!(byte_to_utf16_offset(source, 5), 3);
+    assert_eq!(byte_to_utf16_offset(source, 99), 4);
+    assert_eq!(SourceMap::new(source).utf16_offset_to_byte(3), 5);
+}
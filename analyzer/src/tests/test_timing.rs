@@ -0,0 +1,86 @@
+use crate::semantic::Context;
+use crate::{
+    Instrumentation, Phase, analyze, analyze_instrumented, analyze_syntax, analyze_syntax_timed,
+    analyze_timed,
+};
+
+/// A clock that advances by one unit per reading.
+fn ticking() -> impl FnMut() -> f64 {
+    let mut t = 0.0;
+    move || {
+        t += 1.0;
+        t
+    }
+}
+
+#[test]
+fn timed_entry_points_match_plain_ones() {
+    let source = "if(true, 1, 2) + [1, 2].length() +";
+    let ctx = Context::with_builtins(Vec::new());
+
+    let (syntax, _) = analyze_syntax_timed(source, &mut ticking());
+    let plain = analyze_syntax(source);
+    assert_eq!(syntax.expr, plain.expr);
+    assert_eq!(syntax.diagnostics, plain.diagnostics);
+
+    let (out, _) = analyze_timed(source, &ctx, &mut ticking());
+    let plain = analyze(source, &ctx);
+    assert_eq!(out.diagnostics, plain.diagnostics);
+    assert_eq!(out.output_type, plain.output_type);
+}
+
+#[test]
+fn each_phase_is_measured_between_clock_readings() {
+    let ctx = Context {
+        properties: Vec::new().into(),
+        functions: Vec::new().into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
+    };
+    let (_, timings) = analyze_timed("1 + 2", &ctx, &mut ticking());
+    assert_eq!(
+        (timings.lex, timings.parse, timings.infer, timings.complete),
+        (1.0, 1.0, 1.0, 0.0)
+    );
+}
+
+/// Records every callback, with a clock that advances by one unit per reading.
+#[derive(Default)]
+struct Recording {
+    t: f64,
+    events: Vec<(&'static str, f64, usize)>,
+    counters: Vec<(&'static str, usize)>,
+}
+
+impl Instrumentation for Recording {
+    fn now(&mut self) -> f64 {
+        self.t += 1.0;
+        self.t
+    }
+
+    fn phase(&mut self, phase: Phase, duration: f64, input_len: usize) {
+        self.events.push((phase.as_str(), duration, input_len));
+    }
+
+    fn counter(&mut self, name: &'static str, value: usize) {
+        self.counters.push((name, value));
+    }
+}
+
+#[test]
+fn instrumentation_sees_each_phase_with_input_size_and_counters() {
+    let source = "1 +";
+    let ctx = Context::with_builtins(Vec::new());
+    let mut recording = Recording::default();
+    let out = analyze_instrumented(source, &ctx, &mut recording);
+    assert_eq!(out.diagnostics, analyze(source, &ctx).diagnostics);
+    assert_eq!(
+        recording.events,
+        [("lex", 1.0, 3), ("parse", 1.0, 3), ("infer", 1.0, 3)]
+    );
+    assert_eq!(
+        recording.counters,
+        [("tokens", out.tokens.len()), ("diagnostics", 1)]
+    );
+}
@@ -1,7 +1,11 @@
 use crate::lexer::Span;
+use crate::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// A single text edit in byte offsets.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TextEdit {
     pub range: Span,
     pub new_text: String,
@@ -0,0 +1,143 @@
+//! Per-phase timings for profiling slow inputs.
+//!
+//! The core has no clock of its own (`std::time::Instant` panics on `wasm32-unknown-unknown`), so
+//! `*_timed` entry points take a `now` callback and report differences of its readings. The
+//! `*_instrumented` entry points report each phase to an [`Instrumentation`] instead, which the
+//! `*_timed` ones use through [`PhaseTimer`].
+
+/// Time spent in each pipeline phase, in the unit of the caller's clock.
+///
+/// Phases an entry point does not run stay `0.0`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhaseTimings {
+    pub lex: f64,
+    pub parse: f64,
+    pub infer: f64,
+    pub complete: f64,
+}
+
+/// Measures consecutive phases with a caller-supplied clock.
+pub struct Stopwatch<'a> {
+    now: &'a mut dyn FnMut() -> f64,
+    last: f64,
+}
+
+impl<'a> Stopwatch<'a> {
+    pub fn start(now: &'a mut dyn FnMut() -> f64) -> Self {
+        let last = now();
+        Self { now, last }
+    }
+
+    /// Time since the previous lap (or the start).
+    pub fn lap(&mut self) -> f64 {
+        let now = (self.now)();
+        let elapsed = now - self.last;
+        self.last = now;
+        elapsed
+    }
+}
+
+/// A pipeline phase reported to [`Instrumentation::phase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    Lex,
+    Parse,
+    Infer,
+    /// Completion and signature help, after parsing.
+    Complete,
+    Format,
+}
+
+impl Phase {
+    /// Stable snake_case name for metrics.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::Lex => "lex",
+            Phase::Parse => "parse",
+            Phase::Infer => "infer",
+            Phase::Complete => "complete",
+            Phase::Format => "format",
+        }
+    }
+}
+
+/// Callbacks through which the `*_instrumented` entry points report their phases, e.g. to a
+/// production host's telemetry.
+///
+/// Like the `*_timed` entry points, the core reads the host's clock through [`now`](Self::now).
+pub trait Instrumentation {
+    /// The current time; durations are differences of its readings.
+    fn now(&mut self) -> f64;
+
+    /// `phase` ran for `duration` on an input of `input_len` bytes.
+    fn phase(&mut self, phase: Phase, duration: f64, input_len: usize);
+
+    /// A count produced by the last phase, e.g. `"tokens"` after [`Phase::Lex`].
+    fn counter(&mut self, name: &'static str, value: usize) {
+        let _ = (name, value);
+    }
+}
+
+/// Reports consecutive phases of one input to an [`Instrumentation`].
+pub struct PhaseClock<'a> {
+    instrumentation: &'a mut dyn Instrumentation,
+    input_len: usize,
+    last: f64,
+}
+
+impl<'a> PhaseClock<'a> {
+    pub fn start(instrumentation: &'a mut dyn Instrumentation, input_len: usize) -> Self {
+        let last = instrumentation.now();
+        Self {
+            instrumentation,
+            input_len,
+            last,
+        }
+    }
+
+    /// Reports the time since the previous lap (or the start) as `phase`.
+    pub fn lap(&mut self, phase: Phase) {
+        let now = self.instrumentation.now();
+        self.instrumentation
+            .phase(phase, now - self.last, self.input_len);
+        self.last = now;
+    }
+
+    pub fn count(&mut self, name: &'static str, value: usize) {
+        self.instrumentation.counter(name, value);
+    }
+}
+
+/// Collects [`PhaseTimings`]; the `*_timed` entry points are built on it.
+///
+/// [`Phase::Format`] has no field and is dropped.
+pub struct PhaseTimer<'a> {
+    now: &'a mut dyn FnMut() -> f64,
+    pub timings: PhaseTimings,
+}
+
+impl<'a> PhaseTimer<'a> {
+    pub fn new(now: &'a mut dyn FnMut() -> f64) -> Self {
+        Self {
+            now,
+            timings: PhaseTimings::default(),
+        }
+    }
+}
+
+impl Instrumentation for PhaseTimer<'_> {
+    fn now(&mut self) -> f64 {
+        (self.now)()
+    }
+
+    fn phase(&mut self, phase: Phase, duration: f64, _input_len: usize) {
+        let slot = match phase {
+            Phase::Lex => &mut self.timings.lex,
+            Phase::Parse => &mut self.timings.parse,
+            Phase::Infer => &mut self.timings.infer,
+            Phase::Complete => &mut self.timings.complete,
+            Phase::Format => return,
+        };
+        *slot += duration;
+    }
+}
@@ -32,12 +32,12 @@ fn builtins_follow_builtin_functions_doc() {
 
     let builtins = builtins_functions();
     let mut code_by_name = HashMap::new();
-    for sig in &builtins {
+    for sig in builtins {
         let prev = code_by_name.insert(sig.name.clone(), sig);
         assert!(prev.is_none(), "duplicate builtin function `{}`", sig.name);
     }
 
-    for sig in &builtins {
+    for sig in builtins {
         let spec = doc_by_name
             .get(&sig.name)
             .unwrap_or_else(|| panic!("builtin `{}` is not defined in docs", sig.name));
@@ -2,7 +2,7 @@ mod common;
 
 use std::path::Path;
 
-use analyzer::semantic::{Context, Property, Ty, builtins_functions};
+use analyzer::semantic::{Context, Property, Ty};
 use analyzer::{analyze, analyze_syntax, format_diagnostics};
 use common::golden::run_golden_dir;
 
@@ -20,14 +20,12 @@ fn diagnostics_golden() {
                 .unwrap_or(false);
 
             let diagnostics = if is_semantic {
-                let ctx = Context {
-                    properties: vec![Property {
-                        name: "Title".into(),
-                        ty: Ty::String,
-                        disabled_reason: None,
-                    }],
-                    functions: builtins_functions(),
-                };
+                let ctx = Context::with_builtins(vec![Property {
+                    name: "Title".into(),
+                    ty: Ty::String,
+                    disabled_reason: None,
+                    options: Vec::new(),
+                }]);
                 analyze(source, &ctx).diagnostics
             } else {
                 analyze_syntax(source).diagnostics
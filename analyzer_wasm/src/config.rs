@@ -0,0 +1,446 @@
+//! Schema validation for `AnalyzerConfig`.
+//!
+//! Deserialization only says "invalid"; this walks the raw JSON value and reports every problem
+//! with the field path it was found at (e.g. `properties[2].type.List`).
+
+use serde_json::{Map, Value};
+
+use crate::dto::v1::ConfigIssue;
+
+const CONFIG_FIELDS: &[&str] = &[
+    "properties",
+    "preferred_limit",
+    "position_encoding",
+    "debug_timings",
+    "property_lookup",
+    "limits",
+];
+const LIMIT_FIELDS: &[&str] = &["max_length", "max_depth"];
+const TYPE_NAMES: &[&str] = &["Number", "String", "Boolean", "Date", "List", "Unknown"];
+const POSITION_ENCODINGS: &[&str] = &["utf-8", "utf-16"];
+const PROPERTY_LOOKUPS: &[&str] = &["exact", "lenient"];
+
+/// Every schema violation in `config`. Empty when the config is valid.
+pub(crate) fn validate(config: &Value) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let Some(object) = config.as_object() else {
+        push(
+            &mut issues,
+            "",
+            format!("expected object, found {}", kind(config)),
+        );
+        return issues;
+    };
+
+    for key in object.keys() {
+        if !CONFIG_FIELDS.contains(&key.as_str()) {
+            push(
+                &mut issues,
+                key,
+                format!("unknown field (expected one of {})", quoted(CONFIG_FIELDS)),
+            );
+        }
+    }
+
+    if let Some(properties) = object.get("properties") {
+        validate_properties(properties, &mut issues);
+    }
+    if let Some(limit) = object.get("preferred_limit") {
+        validate_count(limit, "preferred_limit", &mut issues);
+    }
+    if let Some(encoding) = object.get("position_encoding") {
+        let known = encoding
+            .as_str()
+            .is_some_and(|s| POSITION_ENCODINGS.contains(&s));
+        if !encoding.is_null() && !known {
+            push(
+                &mut issues,
+                "position_encoding",
+                format!(
+                    "expected one of {} or null, found {}",
+                    quoted(POSITION_ENCODINGS),
+                    describe(encoding)
+                ),
+            );
+        }
+    }
+
+    if let Some(flag) = object.get("debug_timings")
+        && !flag.is_null()
+        && !flag.is_boolean()
+    {
+        push(
+            &mut issues,
+            "debug_timings",
+            format!("expected boolean or null, found {}", kind(flag)),
+        );
+    }
+
+    if let Some(lookup) = object.get("property_lookup") {
+        let known = lookup
+            .as_str()
+            .is_some_and(|s| PROPERTY_LOOKUPS.contains(&s));
+        if !lookup.is_null() && !known {
+            push(
+                &mut issues,
+                "property_lookup",
+                format!(
+                    "expected one of {} or null, found {}",
+                    quoted(PROPERTY_LOOKUPS),
+                    describe(lookup)
+                ),
+            );
+        }
+    }
+
+    if let Some(limits) = object.get("limits") {
+        validate_limits(limits, &mut issues);
+    }
+
+    issues
+}
+
+fn validate_count(value: &Value, path: &str, issues: &mut Vec<ConfigIssue>) {
+    let is_count = value.as_f64().is_some_and(|n| n >= 0.0 && n.fract() == 0.0);
+    if !value.is_null() && !is_count {
+        push(
+            issues,
+            path,
+            format!(
+                "expected non-negative integer or null, found {}",
+                kind(value)
+            ),
+        );
+    }
+}
+
+fn validate_limits(limits: &Value, issues: &mut Vec<ConfigIssue>) {
+    if limits.is_null() {
+        return;
+    }
+    let Some(object) = limits.as_object() else {
+        push(
+            issues,
+            "limits",
+            format!("expected object or null, found {}", kind(limits)),
+        );
+        return;
+    };
+    for (key, value) in object {
+        let path = format!("limits.{key}");
+        if LIMIT_FIELDS.contains(&key.as_str()) {
+            validate_count(value, &path, issues);
+        } else {
+            push(
+                issues,
+                &path,
+                format!("unknown field (expected one of {})", quoted(LIMIT_FIELDS)),
+            );
+        }
+    }
+}
+
+fn validate_properties(properties: &Value, issues: &mut Vec<ConfigIssue>) {
+    let Some(items) = properties.as_array() else {
+        push(
+            issues,
+            "properties",
+            format!("expected array, found {}", kind(properties)),
+        );
+        return;
+    };
+
+    for (i, item) in items.iter().enumerate() {
+        let path = format!("properties[{i}]");
+        let Some(property) = item.as_object() else {
+            push(
+                issues,
+                &path,
+                format!("expected object, found {}", kind(item)),
+            );
+            continue;
+        };
+        match property.get("name") {
+            Some(Value::String(_)) => {}
+            Some(other) => push(
+                issues,
+                &format!("{path}.name"),
+                format!("expected string, found {}", kind(other)),
+            ),
+            None => push(issues, &format!("{path}.name"), "missing field".into()),
+        }
+        match property.get("type") {
+            Some(ty) => validate_ty(ty, &format!("{path}.type"), issues),
+            None => push(issues, &format!("{path}.type"), "missing field".into()),
+        }
+        if let Some(options) = property.get("options") {
+            validate_options(options, &format!("{path}.options"), issues);
+        }
+    }
+}
+
+fn validate_options(options: &Value, path: &str, issues: &mut Vec<ConfigIssue>) {
+    if options.is_null() {
+        return;
+    }
+    let Some(items) = options.as_array() else {
+        push(
+            issues,
+            path,
+            format!("expected array of strings or null, found {}", kind(options)),
+        );
+        return;
+    };
+    for (i, item) in items.iter().enumerate() {
+        if !item.is_string() {
+            push(
+                issues,
+                &format!("{path}[{i}]"),
+                format!("expected string, found {}", kind(item)),
+            );
+        }
+    }
+}
+
+/// `Ty` is externally tagged: `"Number"` or `{ "List": <Ty> }`.
+fn validate_ty(ty: &Value, path: &str, issues: &mut Vec<ConfigIssue>) {
+    match ty {
+        Value::String(name) if name == "List" => push(
+            issues,
+            path,
+            "`List` needs an element type: { \"List\": <type> }".into(),
+        ),
+        Value::String(name) if TYPE_NAMES.contains(&name.as_str()) => {}
+        Value::String(name) => push(issues, path, unknown_type(name)),
+        Value::Object(map) => validate_list_ty(map, path, issues),
+        other => push(
+            issues,
+            path,
+            format!(
+                "expected type name or {{ \"List\": <type> }}, found {}",
+                kind(other)
+            ),
+        ),
+    }
+}
+
+fn validate_list_ty(map: &Map<String, Value>, path: &str, issues: &mut Vec<ConfigIssue>) {
+    let mut entries = map.iter();
+    match (entries.next(), entries.next()) {
+        (Some((tag, inner)), None) if tag == "List" => {
+            validate_ty(inner, &format!("{path}.List"), issues)
+        }
+        (Some((tag, _)), None) if TYPE_NAMES.contains(&tag.as_str()) => push(
+            issues,
+            path,
+            format!("`{tag}` takes no element type; use \"{tag}\""),
+        ),
+        (Some((tag, _)), None) => push(issues, path, unknown_type(tag)),
+        _ => push(
+            issues,
+            path,
+            format!(
+                "expected {{ \"List\": <type> }} with exactly one key, found {} keys",
+                map.len()
+            ),
+        ),
+    }
+}
+
+fn unknown_type(name: &str) -> String {
+    format!(
+        "unknown type `{name}` (expected one of {})",
+        TYPE_NAMES.join(", ")
+    )
+}
+
+fn push(issues: &mut Vec<ConfigIssue>, path: &str, message: String) {
+    issues.push(ConfigIssue {
+        path: path.to_string(),
+        message,
+    });
+}
+
+fn quoted(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|n| format!("`{n}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{s}\""),
+        other => kind(other).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::validate;
+
+    fn issues(config: serde_json::Value) -> Vec<(String, String)> {
+        validate(&config)
+            .into_iter()
+            .map(|i| (i.path, i.message))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_valid_configs() {
+        assert!(issues(json!({})).is_empty());
+        assert!(
+            issues(json!({
+                "properties": [
+                    { "name": "Title", "type": "String" },
+                    { "name": "Tags", "type": { "List": { "List": "Date" } } },
+                ],
+                "preferred_limit": 0,
+                "position_encoding": "utf-8",
+                "debug_timings": true,
+                "property_lookup": "lenient",
+                "limits": { "max_length": 1000, "max_depth": null },
+            }))
+            .is_empty()
+        );
+        assert!(issues(json!({ "preferred_limit": null, "position_encoding": null })).is_empty());
+    }
+
+    #[test]
+    fn reports_non_object_root() {
+        assert_eq!(
+            issues(json!("{")),
+            [("".into(), "expected object, found string".into())]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_fields_and_scalar_types() {
+        assert_eq!(
+            issues(json!({
+                "functions": [],
+                "preferred_limit": -1,
+                "position_encoding": "utf-32",
+                "debug_timings": 1,
+                "property_lookup": "fuzzy",
+                "limits": { "max_length": 1.5, "depth": 3 },
+            })),
+            [
+                (
+                    "functions".into(),
+                    "unknown field (expected one of `properties`, `preferred_limit`, `position_encoding`, `debug_timings`, `property_lookup`, `limits`)"
+                        .into()
+                ),
+                (
+                    "preferred_limit".into(),
+                    "expected non-negative integer or null, found number".into()
+                ),
+                (
+                    "position_encoding".into(),
+                    "expected one of `utf-8`, `utf-16` or null, found \"utf-32\"".into()
+                ),
+                (
+                    "debug_timings".into(),
+                    "expected boolean or null, found number".into()
+                ),
+                (
+                    "property_lookup".into(),
+                    "expected one of `exact`, `lenient` or null, found \"fuzzy\"".into()
+                ),
+                (
+                    "limits.depth".into(),
+                    "unknown field (expected one of `max_length`, `max_depth`)".into()
+                ),
+                (
+                    "limits.max_length".into(),
+                    "expected non-negative integer or null, found number".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_property_paths_and_unknown_type_names() {
+        assert_eq!(
+            issues(json!({
+                "properties": [
+                    { "name": "Ok", "type": "Number" },
+                    { "type": "Text" },
+                    { "name": 1, "type": { "List": "Checkbox" } },
+                    { "name": "L", "type": "List" },
+                    "Title",
+                ],
+            })),
+            [
+                ("properties[1].name".into(), "missing field".into()),
+                (
+                    "properties[1].type".into(),
+                    "unknown type `Text` (expected one of Number, String, Boolean, Date, List, Unknown)"
+                        .into()
+                ),
+                (
+                    "properties[2].name".into(),
+                    "expected string, found number".into()
+                ),
+                (
+                    "properties[2].type.List".into(),
+                    "unknown type `Checkbox` (expected one of Number, String, Boolean, Date, List, Unknown)"
+                        .into()
+                ),
+                (
+                    "properties[3].type".into(),
+                    "`List` needs an element type: { \"List\": <type> }".into()
+                ),
+                (
+                    "properties[4]".into(),
+                    "expected object, found string".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_non_string_options() {
+        assert_eq!(
+            issues(json!({
+                "properties": [
+                    { "name": "Status", "type": "String", "options": ["Done", null] },
+                    { "name": "Tags", "type": { "List": "String" }, "options": "Urgent" },
+                    { "name": "Stage", "type": "String", "options": null },
+                ],
+            })),
+            [
+                (
+                    "properties[0].options[1]".into(),
+                    "expected string, found null".into()
+                ),
+                (
+                    "properties[1].options".into(),
+                    "expected array of strings or null, found string".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_non_array_properties() {
+        assert_eq!(
+            issues(json!({ "properties": {} })),
+            [("properties".into(), "expected array, found object".into())]
+        );
+    }
+}
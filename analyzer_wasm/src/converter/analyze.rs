@@ -1,46 +1,133 @@
+use analyzer::SourceMap;
+use analyzer::semantic::Ty;
+use ide::PositionEncoding;
+
 use crate::converter::Converter;
-use crate::converter::shared::{diagnostic_view, token_view};
-use crate::dto::v1::AnalyzeResult;
+use crate::converter::shared::{
+    code_action, diagnostic_view, span_dto, text_edit_view, token_view,
+};
+use crate::dto::v1::{
+    AnalyzeResult, BatchAnalyzeItem, CodeAction, Diagnostic, PropertyReference, TextEdit,
+};
 
 impl Converter {
-    pub fn analyze_output(source: &str, output: analyzer::AnalyzeResult) -> AnalyzeResult {
-        let source_map = analyzer::SourceMap::new(source);
-
-        let diagnostics = output
-            .diagnostics
-            .iter()
-            .map(|d| diagnostic_view(source, &source_map, d))
-            .collect();
+    pub fn analyze_output(
+        source: &str,
+        enc: PositionEncoding,
+        output: analyzer::AnalyzeResult,
+    ) -> AnalyzeResult {
+        Self::analysis_view(
+            &SourceMap::new(source),
+            enc,
+            &output.diagnostics,
+            &output.tokens,
+            &output.output_type,
+        )
+    }
 
-        let tokens = output
-            .tokens
+    /// Same as [`Converter::analyze_output`], from borrowed (e.g. cached) results.
+    pub fn analysis_view(
+        sm: &SourceMap<'_>,
+        enc: PositionEncoding,
+        diagnostics: &[analyzer::Diagnostic],
+        tokens: &[analyzer::Token],
+        output_type: &Ty,
+    ) -> AnalyzeResult {
+        let source = sm.source();
+        let tokens = tokens
             .iter()
             .filter(|t| !t.is_trivia())
-            .map(|t| token_view(source, t))
+            .map(|t| token_view(source, enc, t))
             .collect();
 
         AnalyzeResult {
-            diagnostics,
+            diagnostics: Self::diagnostics_view(sm, enc, diagnostics),
             tokens,
+            output_type: output_type.to_string(),
+            timings: None,
+        }
+    }
+
+    /// Diagnostics and output type for one `analyze_batch` entry.
+    pub fn batch_item(
+        id: String,
+        source: &str,
+        enc: PositionEncoding,
+        output: analyzer::AnalyzeResult,
+    ) -> BatchAnalyzeItem {
+        BatchAnalyzeItem {
+            id,
+            diagnostics: Self::diagnostics_view(&SourceMap::new(source), enc, &output.diagnostics),
             output_type: output.output_type.to_string(),
         }
     }
+
+    pub fn code_actions_view(
+        source: &str,
+        enc: PositionEncoding,
+        actions: &[analyzer::CodeAction],
+    ) -> Vec<CodeAction> {
+        actions
+            .iter()
+            .map(|action| code_action(source, enc, action))
+            .collect()
+    }
+
+    pub fn text_edits_view(
+        source: &str,
+        enc: PositionEncoding,
+        edits: &[analyzer::TextEdit],
+    ) -> Vec<TextEdit> {
+        edits
+            .iter()
+            .map(|edit| text_edit_view(source, enc, edit))
+            .collect()
+    }
+
+    pub fn property_references_view(
+        source: &str,
+        enc: PositionEncoding,
+        references: &[ide::PropertyReference],
+    ) -> Vec<PropertyReference> {
+        references
+            .iter()
+            .map(|reference| PropertyReference {
+                name: reference.name.clone(),
+                spans: reference
+                    .spans
+                    .iter()
+                    .map(|&span| span_dto(source, enc, span))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// `sm` supplies the source text and its line index (for `line`/`col`).
+    pub fn diagnostics_view(
+        sm: &SourceMap<'_>,
+        enc: PositionEncoding,
+        diagnostics: &[analyzer::Diagnostic],
+    ) -> Vec<Diagnostic> {
+        diagnostics
+            .iter()
+            .map(|d| diagnostic_view(sm.source(), enc, sm, d))
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use ide::PositionEncoding;
+
     use crate::converter::Converter;
 
     #[test]
     fn diagnostics_include_line_col_for_multiline_source() {
         let source = "1 +\n2 *";
-        let ctx = analyzer::semantic::Context {
-            properties: Vec::new(),
-            functions: analyzer::semantic::builtins_functions(),
-        };
-        let output = analyzer::analyze(source, &ctx);
+        let ctx = analyzer::semantic::builtins_context();
+        let output = analyzer::analyze(source, ctx);
 
-        let result = Converter::analyze_output(source, output);
+        let result = Converter::analyze_output(source, PositionEncoding::Utf16, output);
         let diag = result
             .diagnostics
             .first()
@@ -51,4 +138,39 @@ mod tests {
         assert_eq!(diag.line, 2);
         assert_eq!(diag.col, 3);
     }
+
+    #[test]
+    fn batch_item_keeps_id_diagnostics_and_output_type() {
+        let source = "\"😀\" + ";
+        let ctx = analyzer::semantic::builtins_context();
+        let output = analyzer::analyze(source, ctx);
+        let full = Converter::analyze_output(source, PositionEncoding::Utf16, output.clone());
+
+        let item = Converter::batch_item("f1".into(), source, PositionEncoding::Utf16, output);
+
+        assert_eq!(item.id, "f1");
+        assert_eq!(item.output_type, full.output_type);
+        assert!(!item.diagnostics.is_empty());
+        assert_eq!(item.diagnostics.len(), full.diagnostics.len());
+        for (got, want) in item.diagnostics.iter().zip(&full.diagnostics) {
+            assert_eq!(got.message, want.message);
+            assert_eq!(
+                (got.span.start, got.span.end),
+                (want.span.start, want.span.end)
+            );
+        }
+    }
+
+    #[test]
+    fn property_references_use_the_position_encoding() {
+        let source = "\"😀\" + prop(\"A\") + prop(\"A\")";
+        let references = ide::referenced_properties(source);
+
+        let out = Converter::property_references_view(source, PositionEncoding::Utf16, &references);
+
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].name, "A");
+        let spans: Vec<_> = out[0].spans.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(spans, [(12, 15), (24, 27)]);
+    }
 }
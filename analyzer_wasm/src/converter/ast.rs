@@ -0,0 +1,195 @@
+use analyzer::ast::{Expr, ExprKind};
+use analyzer::{Lit, LitKind, SourceMap};
+use ide::PositionEncoding;
+
+use crate::converter::Converter;
+use crate::converter::shared::span_dto;
+use crate::dto::v1::{AstNode, AstResult, Span};
+
+impl Converter {
+    pub fn ast_view(
+        source: &str,
+        enc: PositionEncoding,
+        output: &analyzer::ParseOutput,
+    ) -> AstResult {
+        AstResult {
+            root: ast_node(source, enc, &output.expr),
+            diagnostics: Self::diagnostics_view(&SourceMap::new(source), enc, &output.diagnostics),
+        }
+    }
+}
+
+fn ast_node(source: &str, enc: PositionEncoding, expr: &Expr) -> AstNode {
+    let span = span_dto(source, enc, expr.span);
+    let node = |e: &Expr| Box::new(ast_node(source, enc, e));
+    let nodes = |es: &[Expr]| es.iter().map(|e| ast_node(source, enc, e)).collect();
+
+    match &expr.kind {
+        ExprKind::Ident(sym) => AstNode::Ident {
+            span,
+            name: sym.text.to_string(),
+        },
+        ExprKind::Group { inner } => AstNode::Group {
+            span,
+            inner: node(inner),
+        },
+        ExprKind::List { items } => AstNode::List {
+            span,
+            items: nodes(items),
+        },
+        ExprKind::Call { callee, args } => AstNode::Call {
+            span,
+            callee: callee.text.to_string(),
+            args: nodes(args),
+        },
+        ExprKind::MemberCall {
+            receiver,
+            method,
+            args,
+        } => AstNode::MemberCall {
+            span,
+            receiver: node(receiver),
+            method: method.text.to_string(),
+            args: nodes(args),
+        },
+        ExprKind::Lit(lit) => lit_node(span, lit),
+        ExprKind::Unary { op, expr } => AstNode::Unary {
+            span,
+            op: op.as_str().to_string(),
+            expr: node(expr),
+        },
+        ExprKind::Binary { op, left, right } => AstNode::Binary {
+            span,
+            op: op.node.as_str().to_string(),
+            left: node(left),
+            right: node(right),
+        },
+        ExprKind::Ternary {
+            cond,
+            then,
+            otherwise,
+        } => AstNode::Ternary {
+            span,
+            cond: node(cond),
+            then: node(then),
+            otherwise: node(otherwise),
+        },
+        ExprKind::Error => AstNode::Error { span },
+    }
+}
+
+fn lit_node(span: Span, lit: &Lit) -> AstNode {
+    let text = &lit.symbol.text;
+    match lit.kind {
+        LitKind::Number => match text.parse() {
+            Ok(value) => AstNode::Number { span, value },
+            // The lexer only produces parseable numbers; keep the tree total anyway.
+            Err(_) => AstNode::Error { span },
+        },
+        LitKind::String => AstNode::String {
+            span,
+            value: text.to_string(),
+        },
+        LitKind::Bool => AstNode::Boolean {
+            span,
+            value: text == "true",
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ide::PositionEncoding;
+
+    use crate::converter::Converter;
+    use crate::dto::v1::{AstNode, AstResult, Span};
+
+    fn view(source: &str) -> AstResult {
+        let output = analyzer::analyze_syntax(source);
+        Converter::ast_view(source, PositionEncoding::Utf16, &output)
+    }
+
+    #[test]
+    fn builds_tree_with_operators_and_literal_values() {
+        let out = view("\"😀\" + 1.5 > 2 ? true : x");
+        assert!(out.diagnostics.is_empty());
+
+        let AstNode::Ternary {
+            span,
+            cond,
+            then,
+            otherwise,
+        } = out.root
+        else {
+            panic!("expected a ternary root");
+        };
+        assert_eq!(span, Span { start: 0, end: 25 });
+        assert_eq!(
+            *then,
+            AstNode::Boolean {
+                span: Span { start: 17, end: 21 },
+                value: true,
+            }
+        );
+        assert_eq!(
+            *otherwise,
+            AstNode::Ident {
+                span: Span { start: 24, end: 25 },
+                name: "x".into(),
+            }
+        );
+
+        let AstNode::Binary { op, left, .. } = *cond else {
+            panic!("expected `>`");
+        };
+        assert_eq!(op, ">");
+        let AstNode::Binary {
+            op, left, right, ..
+        } = *left
+        else {
+            panic!("expected `+`");
+        };
+        assert_eq!(op, "+");
+        assert_eq!(
+            *left,
+            AstNode::String {
+                span: Span { start: 0, end: 4 },
+                value: "😀".into(),
+            }
+        );
+        assert_eq!(
+            *right,
+            AstNode::Number {
+                span: Span { start: 7, end: 10 },
+                value: 1.5,
+            }
+        );
+    }
+
+    #[test]
+    fn member_calls_keep_receiver_and_args() {
+        let out = view("[1].map(not x)");
+        let AstNode::MemberCall {
+            receiver,
+            method,
+            args,
+            ..
+        } = out.root
+        else {
+            panic!("expected a member call");
+        };
+        assert_eq!(method, "map");
+        assert!(matches!(*receiver, AstNode::List { ref items, .. } if items.len() == 1));
+        assert!(matches!(&args[..], [AstNode::Unary { op, .. }] if op == "not"));
+    }
+
+    #[test]
+    fn syntax_errors_keep_error_nodes_and_diagnostics() {
+        let out = view("1 +");
+        assert!(!out.diagnostics.is_empty());
+        assert!(matches!(
+            out.root,
+            AstNode::Binary { ref right, .. } if matches!(**right, AstNode::Error { .. })
+        ));
+    }
+}
@@ -0,0 +1,76 @@
+use analyzer::semantic::{self, FunctionSig, ParamSig};
+
+use crate::converter::Converter;
+use crate::dto::v1::{BuiltinFunction, FunctionCategory, ParamInfo, ParamShapeInfo};
+
+impl Converter {
+    pub fn builtin_catalog_view(functions: &[FunctionSig]) -> Vec<BuiltinFunction> {
+        functions
+            .iter()
+            .map(|sig| BuiltinFunction {
+                name: sig.name.clone(),
+                category: function_category_view(sig.category),
+                params: ParamShapeInfo {
+                    head: params_view(&sig.params.head),
+                    repeat: params_view(&sig.params.repeat),
+                    tail: params_view(&sig.params.tail),
+                    repeat_min_groups: sig.params.repeat_min_groups,
+                },
+                ret: sig.ret.to_string(),
+                docs: sig.detail.clone(),
+                postfix: semantic::is_postfix_capable(sig),
+            })
+            .collect()
+    }
+}
+
+fn params_view(params: &[ParamSig]) -> Vec<ParamInfo> {
+    params
+        .iter()
+        .map(|p| ParamInfo {
+            name: p.name.clone(),
+            ty: p.ty.to_string(),
+            optional: p.optional,
+        })
+        .collect()
+}
+
+fn function_category_view(category: semantic::FunctionCategory) -> FunctionCategory {
+    use semantic::FunctionCategory::*;
+    match category {
+        General => FunctionCategory::General,
+        Text => FunctionCategory::Text,
+        Number => FunctionCategory::Number,
+        Date => FunctionCategory::Date,
+        People => FunctionCategory::People,
+        List => FunctionCategory::List,
+        Special => FunctionCategory::Special,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use analyzer::semantic::builtins_functions;
+
+    use crate::converter::Converter;
+    use crate::dto::v1::FunctionCategory;
+
+    #[test]
+    fn catalog_covers_every_builtin_with_rendered_types() {
+        let functions = builtins_functions();
+        let catalog = Converter::builtin_catalog_view(functions);
+        assert_eq!(catalog.len(), functions.len());
+
+        let round = catalog
+            .iter()
+            .find(|f| f.name == "round")
+            .expect("expected round in catalog");
+        assert_eq!(round.category, FunctionCategory::Number);
+        assert_eq!(round.ret, "number");
+        if cfg!(feature = "docs") {
+            assert_eq!(round.docs, "round(value, places?)");
+        }
+        assert!(round.params.head[1].optional);
+        assert!(round.postfix);
+    }
+}
@@ -0,0 +1,232 @@
+use analyzer::SourceMap;
+use ide::PositionEncoding;
+
+use crate::converter::Converter;
+use crate::converter::shared::span_dto;
+use crate::dto::v1::{
+    CmAction, CmChange, CmCompletion, CmCompletionResult, CmDecoration, CmDiagnostic, CmSeverity,
+    CodeMirrorResult, CompletionItem, CompletionItemKind, CompletionResult, Diagnostic,
+    DiagnosticKind, TextEdit,
+};
+
+/// `Diagnostic.source` shown by `@codemirror/lint`.
+const LINT_SOURCE: &str = "notion-formula";
+
+impl Converter {
+    /// CodeMirror 6 payloads built from the regular DTOs, so positions are already in `enc`.
+    pub fn codemirror_view(
+        source: &str,
+        enc: PositionEncoding,
+        diagnostics: &[analyzer::Diagnostic],
+        help: &ide::HelpResult,
+        tokens: &[ide::SemanticToken],
+    ) -> CodeMirrorResult {
+        let completion = Self::help_output_view(source, enc, help).completion;
+        CodeMirrorResult {
+            diagnostics: Self::diagnostics_view(&SourceMap::new(source), enc, diagnostics)
+                .into_iter()
+                .map(cm_diagnostic)
+                .collect(),
+            completion: cm_completion_result(completion),
+            decorations: tokens
+                .iter()
+                .map(|token| {
+                    let span = span_dto(source, enc, token.span);
+                    CmDecoration {
+                        from: span.start,
+                        to: span.end,
+                        class: decoration_class(token),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn cm_diagnostic(diag: Diagnostic) -> CmDiagnostic {
+    CmDiagnostic {
+        from: diag.span.start,
+        to: diag.span.end,
+        severity: match diag.kind {
+            DiagnosticKind::Error => CmSeverity::Error,
+            DiagnosticKind::Warning => CmSeverity::Warning,
+        },
+        message: diag.message,
+        source: LINT_SOURCE.to_string(),
+        actions: diag
+            .actions
+            .into_iter()
+            .map(|action| CmAction {
+                name: action.title,
+                changes: action.edits.into_iter().map(cm_change).collect(),
+            })
+            .collect(),
+    }
+}
+
+fn cm_completion_result(completion: CompletionResult) -> Option<CmCompletionResult> {
+    let preferred = completion.preferred_indices;
+    let options: Vec<CmCompletion> = completion
+        .items
+        .into_iter()
+        .enumerate()
+        .filter(|(_, item)| !item.is_disabled)
+        .map(|(i, item)| {
+            let rank = preferred.iter().position(|&p| p == i);
+            cm_completion(item, rank, preferred.len())
+        })
+        .collect();
+
+    (!options.is_empty()).then_some(CmCompletionResult {
+        from: completion.replace.start,
+        to: completion.replace.end,
+        options,
+    })
+}
+
+fn cm_completion(item: CompletionItem, rank: Option<usize>, preferred: usize) -> CmCompletion {
+    let changes = item
+        .primary_edit
+        .into_iter()
+        .chain(item.additional_edits)
+        .map(cm_change)
+        .collect();
+
+    CmCompletion {
+        label: item.label,
+        detail: item.detail,
+        kind: completion_type(item.kind).to_string(),
+        section: match rank {
+            Some(_) => "Recommended",
+            None => section_label(item.kind),
+        }
+        .to_string(),
+        boost: rank.map_or(0, |r| (preferred - r) as i32),
+        changes,
+        selection: item.cursor,
+    }
+}
+
+fn cm_change(edit: TextEdit) -> CmChange {
+    CmChange {
+        from: edit.range.start,
+        to: edit.range.end,
+        insert: edit.new_text,
+    }
+}
+
+fn completion_type(kind: CompletionItemKind) -> &'static str {
+    match kind {
+        CompletionItemKind::FunctionGeneral
+        | CompletionItemKind::FunctionText
+        | CompletionItemKind::FunctionNumber
+        | CompletionItemKind::FunctionDate
+        | CompletionItemKind::FunctionPeople
+        | CompletionItemKind::FunctionList
+        | CompletionItemKind::FunctionSpecial => "function",
+        CompletionItemKind::Keyword | CompletionItemKind::Operator => "keyword",
+        CompletionItemKind::Constant => "constant",
+        CompletionItemKind::Property => "property",
+        CompletionItemKind::Variable => "variable",
+        CompletionItemKind::Snippet => "text",
+    }
+}
+
+fn section_label(kind: CompletionItemKind) -> &'static str {
+    match kind {
+        CompletionItemKind::FunctionGeneral => "General Functions",
+        CompletionItemKind::FunctionText => "Text Functions",
+        CompletionItemKind::FunctionNumber => "Number Functions",
+        CompletionItemKind::FunctionDate => "Date Functions",
+        CompletionItemKind::FunctionPeople => "People Functions",
+        CompletionItemKind::FunctionList => "List Functions",
+        CompletionItemKind::FunctionSpecial => "Special Functions",
+        CompletionItemKind::Keyword => "Keywords",
+        CompletionItemKind::Constant => "Constants",
+        CompletionItemKind::Property => "Properties",
+        CompletionItemKind::Variable => "Variables",
+        CompletionItemKind::Operator => "Operators",
+        CompletionItemKind::Snippet => "Snippets",
+    }
+}
+
+fn decoration_class(token: &ide::SemanticToken) -> String {
+    let mut class = format!("nf-{}", ide::SEMANTIC_TOKEN_TYPES[token.kind as usize]);
+    for (bit, modifier) in ide::SEMANTIC_TOKEN_MODIFIERS.iter().enumerate() {
+        if token.modifiers & (1 << bit) != 0 {
+            class.push_str(" nf-");
+            class.push_str(modifier);
+        }
+    }
+    class
+}
+
+#[cfg(test)]
+mod tests {
+    use analyzer::semantic::builtins_context;
+    use ide::PositionEncoding;
+
+    use crate::converter::Converter;
+    use crate::dto::v1::{CmSeverity, CodeMirrorResult};
+
+    fn view(source: &str, cursor: usize) -> CodeMirrorResult {
+        let ctx = builtins_context();
+        let analyzed = analyzer::analyze(source, ctx);
+        let help = ide::help(source, cursor, ctx, ide::CompletionConfig::default());
+        let tokens = ide::semantic_tokens(source, ctx);
+        Converter::codemirror_view(
+            source,
+            PositionEncoding::Utf16,
+            &analyzed.diagnostics,
+            &help,
+            &tokens,
+        )
+    }
+
+    #[test]
+    fn diagnostics_use_lint_shape_in_utf16() {
+        let out = view("\"😀\" +", 0);
+        let diag = out.diagnostics.first().expect("expected a diagnostic");
+        assert_eq!(diag.severity, CmSeverity::Error);
+        assert_eq!(diag.source, "notion-formula");
+        assert!(diag.from >= 4, "emoji counts as two UTF-16 units");
+    }
+
+    #[test]
+    fn completion_options_carry_changes_and_sections() {
+        let out = view("su", 2);
+        let completion = out.completion.expect("expected completion");
+        assert_eq!((completion.from, completion.to), (0, 2));
+
+        let sum = completion
+            .options
+            .iter()
+            .find(|o| o.label == "sum()")
+            .expect("expected sum()");
+        assert_eq!(sum.kind, "function");
+        assert!(!sum.changes.is_empty());
+        assert!(sum.selection.is_some());
+
+        for option in &completion.options {
+            assert_eq!(option.section == "Recommended", option.boost > 0);
+        }
+    }
+
+    #[test]
+    fn decorations_classify_tokens() {
+        let out = view("sum(1, 2)", 0);
+        let classes: Vec<_> = out
+            .decorations
+            .iter()
+            .map(|d| (d.from, d.to, d.class.as_str()))
+            .collect();
+        assert_eq!(
+            classes,
+            [
+                (0, 3, "nf-function nf-defaultLibrary"),
+                (4, 5, "nf-number"),
+                (7, 8, "nf-number"),
+            ]
+        );
+    }
+}
@@ -1,30 +1,41 @@
+use ide::PositionEncoding;
+
 use crate::converter::Converter;
 use crate::converter::shared::span_dto;
 use crate::dto::v1::{
-    CompletionItem, CompletionItemKind, CompletionResult as CompletionResultDto, DisplaySegment,
-    HelpResult as HelpResultDto, SignatureHelp, SignatureItem, TextEdit,
+    CompletionItem, CompletionItemKind, CompletionReplace, CompletionResult as CompletionResultDto,
+    DisplaySegment, HelpResult as HelpResultDto, MatchStrength, SignatureHelp, SignatureItem,
+    TextEdit,
 };
 
 impl Converter {
-    pub fn help_output_view(source: &str, output: &ide::HelpResult) -> HelpResultDto {
-        let replace = span_dto(source, output.completion.replace);
-        let signature_help = output.signature_help.as_ref().map(|sig| SignatureHelp {
-            signatures: sig
-                .signatures
-                .iter()
-                .map(|s| SignatureItem {
-                    segments: s.segments.iter().map(display_segment_view).collect(),
-                })
-                .collect(),
-            active_signature: sig.active_signature,
-            active_parameter: sig.active_parameter,
-        });
+    pub fn completion_replace_view(
+        source: &str,
+        enc: PositionEncoding,
+        output: &ide::CompletionReplace,
+    ) -> CompletionReplace {
+        CompletionReplace {
+            replace: span_dto(source, enc, output.replace),
+            query: output.query.clone(),
+        }
+    }
+
+    pub fn help_output_view(
+        source: &str,
+        enc: PositionEncoding,
+        output: &ide::HelpResult,
+    ) -> HelpResultDto {
+        let replace = span_dto(source, enc, output.completion.replace);
+        let signature_help = output
+            .signature_help
+            .as_ref()
+            .map(Self::signature_help_view);
 
         let items = output
             .completion
             .items
             .iter()
-            .map(|item| completion_item_view(source, &output.completion, item))
+            .map(|item| completion_item_view(source, enc, item))
             .collect();
 
         HelpResultDto {
@@ -34,17 +45,33 @@ impl Converter {
                 preferred_indices: output.completion.preferred_indices.clone(),
             },
             signature_help,
+            timings: None,
+        }
+    }
+
+    /// Signature segments carry no positions, so this needs no encoding.
+    pub fn signature_help_view(sig: &ide::SignatureHelp) -> SignatureHelp {
+        SignatureHelp {
+            signatures: sig
+                .signatures
+                .iter()
+                .map(|s| SignatureItem {
+                    segments: s.segments.iter().map(display_segment_view).collect(),
+                })
+                .collect(),
+            active_signature: sig.active_signature,
+            active_parameter: sig.active_parameter,
         }
     }
 }
 
 fn completion_item_view(
     source: &str,
-    completion: &ide::CompletionResult,
+    enc: PositionEncoding,
     item: &ide::CompletionItem,
 ) -> CompletionItem {
     let primary_edit_view = item.primary_edit.as_ref().map(|edit| TextEdit {
-        range: span_dto(source, edit.range),
+        range: span_dto(source, enc, edit.range),
         new_text: edit.new_text.clone(),
     });
 
@@ -52,72 +79,29 @@ fn completion_item_view(
         .additional_edits
         .iter()
         .map(|edit| TextEdit {
-            range: span_dto(source, edit.range),
+            range: span_dto(source, enc, edit.range),
             new_text: edit.new_text.clone(),
         })
         .collect::<Vec<_>>();
 
-    let cursor_utf16 = item.primary_edit.as_ref().map(|primary_edit| {
-        let mut edits = Vec::with_capacity(1 + item.additional_edits.len());
-        edits.push(primary_edit.clone());
-        edits.extend(item.additional_edits.iter().cloned());
-        edits.sort_by(|a, b| {
-            a.range
-                .start
-                .cmp(&b.range.start)
-                .then(a.range.end.cmp(&b.range.end))
-        });
-
-        // The analyzer's `cursor` is intended to be a position in the updated document after
-        // applying the primary edit, but additional edits may shift that position.
-        let mut cursor_byte: i64 = i64::from(item.cursor.unwrap_or_else(|| {
-            completion
-                .replace
-                .start
-                .saturating_add(primary_edit.new_text.len() as u32)
-        }));
-
-        let primary_start = primary_edit.range.start;
-        for edit in &item.additional_edits {
-            if edit.range.end <= primary_start {
-                let start_u32 = edit.range.start;
-                let end_u32 = edit.range.end;
-                let start = start_u32 as usize;
-                let end = end_u32 as usize;
-
-                // Match edit validity checks: skipped edits must not shift the cursor.
-                if start_u32 > end_u32 || end > source.len() {
-                    continue;
-                }
-                if !source.is_char_boundary(start) || !source.is_char_boundary(end) {
-                    continue;
-                }
-
-                let replaced_len = end_u32.saturating_sub(start_u32) as i64;
-                let inserted_len = edit.new_text.len() as i64;
-                cursor_byte = cursor_byte.saturating_add(inserted_len.saturating_sub(replaced_len));
-            }
-        }
-
-        let (updated, _) = ide::apply_text_edits_bytes_with_cursor(source, &edits, 0);
-        let cursor_byte = usize::min(usize::try_from(cursor_byte).unwrap_or(0), updated.len());
-        Converter::utf8_to_16_offset(&updated, cursor_byte)
-    });
+    // Invalid or overlapping edits leave the cursor unset rather than guessing a position.
+    let cursor = ide::completion_cursor(source, item, enc);
 
     CompletionItem {
         label: item.label.clone(),
         kind: completion_kind_view(item.kind),
         insert_text: item.insert_text.clone(),
         primary_edit: primary_edit_view,
-        cursor: cursor_utf16,
+        cursor,
         additional_edits,
         detail: item.detail.clone(),
         is_disabled: item.is_disabled,
         disabled_reason: item.disabled_reason.clone(),
+        match_strength: item.match_strength.map(match_strength_view),
     }
 }
 
-fn display_segment_view(seg: &ide::DisplaySegment) -> DisplaySegment {
+pub(crate) fn display_segment_view(seg: &ide::DisplaySegment) -> DisplaySegment {
     use ide::DisplaySegment as S;
     match seg {
         S::Name { text } => DisplaySegment::Name { text: text.clone() },
@@ -138,6 +122,15 @@ fn display_segment_view(seg: &ide::DisplaySegment) -> DisplaySegment {
     }
 }
 
+fn match_strength_view(strength: ide::MatchStrength) -> MatchStrength {
+    match strength {
+        ide::MatchStrength::Fuzzy => MatchStrength::Fuzzy,
+        ide::MatchStrength::Contains => MatchStrength::Contains,
+        ide::MatchStrength::Prefix => MatchStrength::Prefix,
+        ide::MatchStrength::Exact => MatchStrength::Exact,
+    }
+}
+
 fn completion_kind_view(kind: ide::CompletionKind) -> CompletionItemKind {
     use ide::CompletionKind::*;
     match kind {
@@ -148,8 +141,11 @@ fn completion_kind_view(kind: ide::CompletionKind) -> CompletionItemKind {
         FunctionPeople => CompletionItemKind::FunctionPeople,
         FunctionList => CompletionItemKind::FunctionList,
         FunctionSpecial => CompletionItemKind::FunctionSpecial,
-        Builtin => CompletionItemKind::Builtin,
+        Keyword => CompletionItemKind::Keyword,
+        Constant => CompletionItemKind::Constant,
         Property => CompletionItemKind::Property,
+        Variable => CompletionItemKind::Variable,
         Operator => CompletionItemKind::Operator,
+        Snippet => CompletionItemKind::Snippet,
     }
 }
@@ -0,0 +1,34 @@
+use ide::PositionEncoding;
+
+use crate::converter::Converter;
+use crate::converter::completion::display_segment_view;
+use crate::converter::shared::span_dto;
+use crate::dto::v1::{HoverResult, SignatureItem, TypeAtResult};
+
+impl Converter {
+    pub fn hover_output_view(
+        source: &str,
+        enc: PositionEncoding,
+        output: &ide::HoverResult,
+    ) -> HoverResult {
+        HoverResult {
+            range: span_dto(source, enc, output.range),
+            ty: output.ty.to_string(),
+            signature: output.signature.as_ref().map(|sig| SignatureItem {
+                segments: sig.segments.iter().map(display_segment_view).collect(),
+            }),
+            docs: output.docs.clone(),
+        }
+    }
+
+    pub fn type_at_output_view(
+        source: &str,
+        enc: PositionEncoding,
+        output: &ide::TypeAtResult,
+    ) -> TypeAtResult {
+        TypeAtResult {
+            range: span_dto(source, enc, output.range),
+            ty: output.ty.to_string(),
+        }
+    }
+}
@@ -1,11 +1,16 @@
 //! Conversion utilities for the WASM/JS boundary.
 //!
 //! This module is intentionally stateless and centralizes:
-//! - UTF-16 ↔ byte offset bridging for editor-facing positions.
+//! - Host position encoding (UTF-16 or UTF-8) ↔ byte offset bridging for editor-facing positions.
 //! - DTO conversion (internal analyzer types → `dto::v1::*`).
 
 mod analyze;
+mod ast;
+mod catalog;
+mod codemirror;
 mod completion;
+mod hover;
+mod monaco;
 mod shared;
 
 pub struct Converter;
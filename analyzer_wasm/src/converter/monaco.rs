@@ -0,0 +1,263 @@
+//! Monaco payloads. Monaco positions are always 1-based lines and UTF-16 columns, so this works
+//! from byte spans directly instead of the encoding-dependent DTOs.
+
+use analyzer::{SourceMap, Span as ByteSpan};
+
+use crate::converter::Converter;
+use crate::converter::completion::display_segment_view;
+use crate::dto::v1::{
+    DisplaySegment, MonacoCompletionItem, MonacoMarker, MonacoParameterInformation, MonacoRange,
+    MonacoResult, MonacoSignatureHelp, MonacoSignatureInformation, MonacoTextEdit,
+};
+
+/// `MarkerSeverity.Error`.
+const MARKER_SEVERITY_ERROR: u32 = 8;
+/// `MarkerSeverity.Warning`.
+const MARKER_SEVERITY_WARNING: u32 = 4;
+/// `CompletionItemInsertTextRule.InsertAsSnippet`.
+const INSERT_AS_SNIPPET: u32 = 4;
+/// `IMarkerData.source`.
+const MARKER_SOURCE: &str = "notion-formula";
+
+impl Converter {
+    pub fn monaco_view(
+        source: &str,
+        diagnostics: &[analyzer::Diagnostic],
+        help: &ide::HelpResult,
+    ) -> MonacoResult {
+        let lines = SourceMap::new(source);
+
+        let markers = diagnostics
+            .iter()
+            .map(|diag| {
+                let range = range(&lines, diag.span);
+                MonacoMarker {
+                    start_line_number: range.start_line_number,
+                    start_column: range.start_column,
+                    end_line_number: range.end_line_number,
+                    end_column: range.end_column,
+                    severity: match diag.kind {
+                        analyzer::DiagnosticKind::Error => MARKER_SEVERITY_ERROR,
+                        analyzer::DiagnosticKind::Warning => MARKER_SEVERITY_WARNING,
+                    },
+                    message: diag.message.clone(),
+                    source: MARKER_SOURCE.to_string(),
+                }
+            })
+            .collect();
+
+        let completion = &help.completion;
+        let suggestions = completion
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| !item.is_disabled)
+            .map(|(i, item)| {
+                let sort_text = match completion.preferred_indices.iter().position(|&p| p == i) {
+                    Some(rank) => format!("0{rank:04}"),
+                    None => format!("1{i:04}"),
+                };
+                completion_item(&lines, completion.replace, item, sort_text)
+            })
+            .collect();
+
+        MonacoResult {
+            markers,
+            suggestions,
+            signature_help: help.signature_help.as_ref().map(signature_help),
+        }
+    }
+}
+
+fn completion_item(
+    lines: &SourceMap<'_>,
+    replace: ByteSpan,
+    item: &ide::CompletionItem,
+    sort_text: String,
+) -> MonacoCompletionItem {
+    let (range, text) = match &item.primary_edit {
+        Some(edit) => (edit.range, edit.new_text.as_str()),
+        None => (replace, item.insert_text.as_str()),
+    };
+
+    // Tab stops (the cursor, snippet placeholders) are in the document after the primary edit.
+    let (insert_text, insert_text_rules) = match item.snippet_text() {
+        Some(snippet) => (snippet, INSERT_AS_SNIPPET),
+        None => (text.to_string(), 0),
+    };
+
+    MonacoCompletionItem {
+        label: item.label.clone(),
+        kind: completion_kind(item.kind),
+        detail: item.detail.clone(),
+        insert_text,
+        insert_text_rules,
+        range: self::range(lines, range),
+        additional_text_edits: item
+            .additional_edits
+            .iter()
+            .map(|edit| MonacoTextEdit {
+                range: self::range(lines, edit.range),
+                text: edit.new_text.clone(),
+            })
+            .collect(),
+        sort_text,
+    }
+}
+
+fn signature_help(sig: &ide::SignatureHelp) -> MonacoSignatureHelp {
+    let mut active_parameter = None;
+    let signatures = sig
+        .signatures
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let mut label = String::new();
+            let mut parameters = Vec::new();
+            for seg in item.segments.iter().map(display_segment_view) {
+                let start = utf16_len(&label);
+                let param_index = match &seg {
+                    DisplaySegment::Param { param_index, .. } => Some(*param_index),
+                    _ => None,
+                };
+                label.push_str(&segment_text(seg));
+                if let Some(param_index) = param_index {
+                    let is_active = i == sig.active_signature
+                        && param_index.is_some_and(|p| p as usize == sig.active_parameter);
+                    if is_active && active_parameter.is_none() {
+                        active_parameter = Some(parameters.len());
+                    }
+                    parameters.push(MonacoParameterInformation {
+                        label: (start, utf16_len(&label)),
+                    });
+                }
+            }
+            MonacoSignatureInformation { label, parameters }
+        })
+        .collect();
+
+    MonacoSignatureHelp {
+        signatures,
+        active_signature: sig.active_signature as u32,
+        // Out of range highlights no parameter.
+        active_parameter: active_parameter.map_or(u32::MAX, |p| p as u32),
+    }
+}
+
+/// Segment text as the example UI renders it (`name: type` for parameters).
+fn segment_text(seg: DisplaySegment) -> String {
+    match seg {
+        DisplaySegment::Name { text }
+        | DisplaySegment::Punct { text }
+        | DisplaySegment::Separator { text }
+        | DisplaySegment::Arrow { text }
+        | DisplaySegment::ReturnType { text } => text,
+        DisplaySegment::Ellipsis => "...".to_string(),
+        DisplaySegment::Param { name, ty, .. } => format!("{name}: {ty}"),
+    }
+}
+
+fn completion_kind(kind: ide::CompletionKind) -> u32 {
+    use ide::CompletionKind::*;
+    // `languages.CompletionItemKind` values.
+    match kind {
+        FunctionGeneral | FunctionText | FunctionNumber | FunctionDate | FunctionPeople
+        | FunctionList | FunctionSpecial => 1,
+        Variable => 4,
+        Property => 9,
+        Operator => 11,
+        Constant => 14,
+        Keyword => 17,
+        Snippet => 27,
+    }
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+/// Byte span → Monaco range.
+fn range(lines: &SourceMap<'_>, span: ByteSpan) -> MonacoRange {
+    let (start_line, start_column) = lines.line_col_utf16(span.start);
+    let (end_line, end_column) = lines.line_col_utf16(span.end);
+    MonacoRange {
+        start_line_number: start_line as u32,
+        start_column: start_column as u32,
+        end_line_number: end_line as u32,
+        end_column: end_column as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use analyzer::semantic::builtins_context;
+    use analyzer::{SourceMap, Span};
+
+    use super::range;
+    use crate::converter::Converter;
+    use crate::dto::v1::{MonacoRange, MonacoResult};
+
+    fn view(source: &str, cursor: usize) -> MonacoResult {
+        let ctx = builtins_context();
+        let analyzed = analyzer::analyze(source, ctx);
+        let help = ide::help(source, cursor, ctx, ide::CompletionConfig::default());
+        Converter::monaco_view(source, &analyzed.diagnostics, &help)
+    }
+
+    #[test]
+    fn ranges_use_utf16_columns() {
+        let lines = SourceMap::new("\"😀\"\n  1");
+        assert_eq!(
+            range(&lines, Span { start: 5, end: 7 }),
+            MonacoRange {
+                start_line_number: 1,
+                start_column: 4,
+                end_line_number: 2,
+                end_column: 1,
+            }
+        );
+        assert_eq!(
+            range(&lines, Span { start: 9, end: 10 }),
+            MonacoRange {
+                start_line_number: 2,
+                start_column: 3,
+                end_line_number: 2,
+                end_column: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn markers_carry_monaco_positions() {
+        let out = view("1 +\n2 *", 0);
+        let marker = out.markers.first().expect("expected a marker");
+        assert_eq!(marker.severity, 8);
+        assert_eq!((marker.start_line_number, marker.start_column), (2, 3));
+    }
+
+    #[test]
+    fn function_completion_is_a_snippet_with_cursor_in_parens() {
+        let out = view("su", 2);
+        let sum = out
+            .suggestions
+            .iter()
+            .find(|s| s.label == "sum()")
+            .expect("expected sum()");
+        assert_eq!(sum.kind, 1);
+        assert_eq!(sum.insert_text, "sum($0)");
+        assert_eq!(sum.insert_text_rules, 4);
+        assert_eq!((sum.range.start_column, sum.range.end_column), (1, 3));
+    }
+
+    #[test]
+    fn signature_help_labels_parameters_by_offset() {
+        let out = view("if(true, ", 9);
+        let help = out.signature_help.expect("expected signature help");
+        let sig = &help.signatures[help.active_signature as usize];
+        let param = &sig.parameters[help.active_parameter as usize];
+        let label: Vec<u16> = sig.label.encode_utf16().collect();
+        let text = String::from_utf16(&label[param.label.0 as usize..param.label.1 as usize])
+            .expect("utf16");
+        assert!(text.starts_with("then"), "{text} in {}", sig.label);
+    }
+}
@@ -4,6 +4,8 @@ use analyzer::{
     TokenKind,
 };
 
+use ide::PositionEncoding;
+
 use crate::dto::v1::{
     CodeAction, Diagnostic, DiagnosticKind, Span as Utf16Span, TextEdit as Utf16TextEdit, Token,
 };
@@ -11,6 +13,7 @@ use crate::span::byte_span_to_utf16_span;
 
 pub(crate) fn diagnostic_view(
     source: &str,
+    enc: PositionEncoding,
     sm: &SourceMap<'_>,
     diag: &ByteDiagnostic,
 ) -> Diagnostic {
@@ -19,18 +22,18 @@ pub(crate) fn diagnostic_view(
     Diagnostic {
         kind: diagnostic_kind_view(&diag.kind),
         message: diag.message.clone(),
-        span: span_dto(source, diag.span),
+        span: span_dto(source, enc, diag.span),
         line,
         col,
         actions: diag
             .actions
             .iter()
-            .map(|action| code_action(source, action))
+            .map(|action| code_action(source, enc, action))
             .collect(),
     }
 }
 
-pub(crate) fn token_view(source: &str, token: &ByteToken) -> Token {
+pub(crate) fn token_view(source: &str, enc: PositionEncoding, token: &ByteToken) -> Token {
     let start = token.span.start as usize;
     let end = token.span.end as usize;
     let text = source.get(start..end).unwrap_or("").to_string();
@@ -38,31 +41,50 @@ pub(crate) fn token_view(source: &str, token: &ByteToken) -> Token {
     Token {
         kind: token_kind_string(&token.kind).to_string(),
         text,
-        span: span_dto(source, token.span),
+        span: span_dto(source, enc, token.span),
     }
 }
 
-fn code_action(source: &str, action: &ByteCodeAction) -> CodeAction {
+pub(crate) fn code_action(
+    source: &str,
+    enc: PositionEncoding,
+    action: &ByteCodeAction,
+) -> CodeAction {
     CodeAction {
         title: action.title.clone(),
         edits: action
             .edits
             .iter()
-            .map(|edit| Utf16TextEdit {
-                range: span_dto(source, edit.range),
-                new_text: edit.new_text.clone(),
-            })
+            .map(|edit| text_edit_view(source, enc, edit))
             .collect(),
     }
 }
 
-pub(crate) fn span_dto(source: &str, span: ByteSpan) -> Utf16Span {
-    byte_span_to_utf16_span(source, span)
+pub(crate) fn text_edit_view(
+    source: &str,
+    enc: PositionEncoding,
+    edit: &analyzer::TextEdit,
+) -> Utf16TextEdit {
+    Utf16TextEdit {
+        range: span_dto(source, enc, edit.range),
+        new_text: edit.new_text.clone(),
+    }
+}
+
+pub(crate) fn span_dto(source: &str, enc: PositionEncoding, span: ByteSpan) -> Utf16Span {
+    match enc {
+        PositionEncoding::Utf16 => byte_span_to_utf16_span(source, span),
+        PositionEncoding::Utf8 => {
+            let ByteSpan { start, end } = enc.span_from_byte(source, span);
+            Utf16Span { start, end }
+        }
+    }
 }
 
 fn diagnostic_kind_view(kind: &ByteDiagnosticKind) -> DiagnosticKind {
     match kind {
         ByteDiagnosticKind::Error => DiagnosticKind::Error,
+        ByteDiagnosticKind::Warning => DiagnosticKind::Warning,
     }
 }
 
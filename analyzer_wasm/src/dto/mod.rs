@@ -1,4 +1,6 @@
 //! JS-facing DTO types for `analyzer_wasm`.
 //!
-//! Spans and offsets use UTF-16 code units and are half-open `[start, end)`.
+//! Spans and offsets use the configured position encoding (UTF-16 code units by default) and
+//! are half-open `[start, end)`.
+pub mod typescript;
 pub mod v1;
@@ -0,0 +1,152 @@
+//! TypeScript declarations for the DTOs, generated with `ts-rs`.
+//!
+//! `cargo run -p analyzer_wasm --bin export_ts` writes [`declarations`] to
+//! `examples/vite/src/analyzer/generated/wasm_dto.ts`; a test fails when that file is stale.
+
+use ts_rs::TS;
+
+use crate::dto::v1::{
+    AnalyzeResult, AnalyzerConfig, ApplyResult, AstNode, AstResult, BatchAnalyzeItem,
+    BuiltinFunction, ClosingDelim, CmAction, CmChange, CmCompletion, CmCompletionResult,
+    CmDecoration, CmDiagnostic, CmSeverity, CodeAction, CodeMirrorResult, CompletionConfig,
+    CompletionItem, CompletionItemKind, CompletionReplace, CompletionResult, ConfigIssue,
+    ConfigValidationResult, Diagnostic, DiagnosticKind, DisplaySegment, ErrorCode, FormatConfig,
+    FormulaLimits, FormulaSource, FunctionCategory, HelpResult, HoverResult, InternalError, KindBoost, MatchMode,
+    MatchStrength, MonacoCompletionItem, MonacoMarker, MonacoParameterInformation, MonacoRange,
+    MonacoResult, MonacoSignatureHelp, MonacoSignatureInformation, MonacoTextEdit, ParamInfo,
+    ParamShapeInfo, PositionEncoding, Property, PropertyLookup, PropertyReference, RankingConfig,
+    SemanticTokensLegend, SignatureHelp, SignatureItem, Span, TextEdit, Timings, Token, Ty,
+    TypeAtResult,
+};
+
+/// Relative to the `analyzer_wasm` crate root.
+pub const OUTPUT_PATH: &str = "../examples/vite/src/analyzer/generated/wasm_dto.ts";
+
+/// The full generated TypeScript module (one exported type per DTO).
+pub fn declarations() -> String {
+    let mut out = String::new();
+    out.push_str("/* eslint-disable */\n");
+    out.push_str("/* prettier-ignore */\n");
+    out.push_str("// AUTO-GENERATED: `cargo run -p analyzer_wasm --bin export_ts`\n\n");
+
+    for decl in [
+        Ty::decl(),
+        Property::decl(),
+        PositionEncoding::decl(),
+        PropertyLookup::decl(),
+        FormulaLimits::decl(),
+        AnalyzerConfig::decl(),
+        ConfigIssue::decl(),
+        ConfigValidationResult::decl(),
+        Span::decl(),
+        TextEdit::decl(),
+        CodeAction::decl(),
+        PropertyReference::decl(),
+        DiagnosticKind::decl(),
+        Diagnostic::decl(),
+        Token::decl(),
+        AnalyzeResult::decl(),
+        Timings::decl(),
+        FormulaSource::decl(),
+        BatchAnalyzeItem::decl(),
+        ApplyResult::decl(),
+        DisplaySegment::decl(),
+        SignatureItem::decl(),
+        SignatureHelp::decl(),
+        CompletionItemKind::decl(),
+        MatchMode::decl(),
+        MatchStrength::decl(),
+        KindBoost::decl(),
+        RankingConfig::decl(),
+        CompletionConfig::decl(),
+        ClosingDelim::decl(),
+        FormatConfig::decl(),
+        CompletionItem::decl(),
+        CompletionResult::decl(),
+        CompletionReplace::decl(),
+        HelpResult::decl(),
+        HoverResult::decl(),
+        TypeAtResult::decl(),
+        AstNode::decl(),
+        AstResult::decl(),
+        SemanticTokensLegend::decl(),
+        CmSeverity::decl(),
+        CmChange::decl(),
+        CmAction::decl(),
+        CmDiagnostic::decl(),
+        CmCompletion::decl(),
+        CmCompletionResult::decl(),
+        CmDecoration::decl(),
+        CodeMirrorResult::decl(),
+        MonacoRange::decl(),
+        MonacoMarker::decl(),
+        MonacoTextEdit::decl(),
+        MonacoCompletionItem::decl(),
+        MonacoParameterInformation::decl(),
+        MonacoSignatureInformation::decl(),
+        MonacoSignatureHelp::decl(),
+        MonacoResult::decl(),
+        ErrorCode::decl(),
+        InternalError::decl(),
+        FunctionCategory::decl(),
+        ParamInfo::decl(),
+        ParamShapeInfo::decl(),
+        BuiltinFunction::decl(),
+    ] {
+        let decl = export_decl(decl);
+        out.push_str(&decl);
+        if !decl.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+fn export_decl(mut decl: String) -> String {
+    let trimmed = decl.trim_start();
+    if trimmed.starts_with("export ") {
+        return decl;
+    }
+
+    if trimmed.starts_with("type ")
+        || trimmed.starts_with("interface ")
+        || trimmed.starts_with("enum ")
+        || trimmed.starts_with("declare ")
+    {
+        decl.insert_str(decl.len() - trimmed.len(), "export ");
+    }
+
+    decl
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{OUTPUT_PATH, declarations};
+
+    #[test]
+    fn generated_file_is_up_to_date() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(OUTPUT_PATH);
+        let on_disk = std::fs::read_to_string(&path).expect("read generated wasm_dto.ts");
+        assert!(
+            on_disk == declarations(),
+            "{} is stale; run `just gen-ts`",
+            path.display()
+        );
+    }
+
+    #[test]
+    fn every_declaration_is_exported() {
+        let out = declarations();
+        let unexported: Vec<_> = out.lines().filter(|l| l.starts_with("type ")).collect();
+        assert!(
+            unexported.is_empty(),
+            "unexported declarations: {unexported:?}"
+        );
+        assert!(out.contains("export type HelpResult = "));
+        assert!(out.contains("export type ErrorCode = "));
+    }
+}
@@ -1,7 +1,8 @@
 //! WASM DTOs (v1).
 //!
 //! JS-facing types returned by `analyzer_wasm`.
-//! Spans and offsets use UTF-16 code units and are half-open `[start, end)`.
+//! Spans and offsets use the configured position encoding (UTF-16 code units by default) and
+//! are half-open `[start, end)`.
 
 use analyzer::analysis::Ty as AnalyzerTy;
 use serde::{Deserialize, Serialize};
@@ -15,6 +16,8 @@ pub enum Ty {
     Boolean,
     Date,
     List(Box<Ty>),
+    /// Accepted anywhere (e.g. Notion people, relations, and formula results).
+    Unknown,
 }
 
 impl From<Ty> for AnalyzerTy {
@@ -25,6 +28,24 @@ impl From<Ty> for AnalyzerTy {
             Ty::Boolean => AnalyzerTy::Boolean,
             Ty::Date => AnalyzerTy::Date,
             Ty::List(ty) => AnalyzerTy::List(Box::new((*ty).into())),
+            Ty::Unknown => AnalyzerTy::Unknown,
+        }
+    }
+}
+
+impl From<AnalyzerTy> for Ty {
+    /// Types with no DTO form (`Null`, generics, unions) become `Unknown`.
+    fn from(ty: AnalyzerTy) -> Ty {
+        match ty {
+            AnalyzerTy::Number => Ty::Number,
+            AnalyzerTy::String => Ty::String,
+            AnalyzerTy::Boolean => Ty::Boolean,
+            AnalyzerTy::Date => Ty::Date,
+            AnalyzerTy::List(ty) => Ty::List(Box::new((*ty).into())),
+            AnalyzerTy::Null
+            | AnalyzerTy::Unknown
+            | AnalyzerTy::Generic(_)
+            | AnalyzerTy::Union(_) => Ty::Unknown,
         }
     }
 }
@@ -37,6 +58,32 @@ pub struct Property {
     #[serde(rename = "type")]
     /// Declared property type.
     pub ty: Ty,
+    /// Option names of a select, status, or multi-select property, completed inside string
+    /// literals compared with it.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub options: Option<Vec<String>>,
+}
+
+/// Unit for every offset and span exchanged with the analyzer (LSP `PositionEncodingKind` names).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub enum PositionEncoding {
+    /// UTF-8 bytes.
+    #[serde(rename = "utf-8")]
+    Utf8,
+    /// UTF-16 code units (JS string indices).
+    #[default]
+    #[serde(rename = "utf-16")]
+    Utf16,
+}
+
+impl From<PositionEncoding> for ide::PositionEncoding {
+    fn from(encoding: PositionEncoding) -> ide::PositionEncoding {
+        match encoding {
+            PositionEncoding::Utf8 => ide::PositionEncoding::Utf8,
+            PositionEncoding::Utf16 => ide::PositionEncoding::Utf16,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
@@ -45,19 +92,196 @@ pub struct AnalyzerConfig {
     #[serde(default)]
     pub properties: Vec<Property>,
     pub preferred_limit: Option<usize>,
+    /// Defaults to `"utf-16"`.
+    #[serde(default)]
+    pub position_encoding: Option<PositionEncoding>,
+    /// Attach per-phase [`Timings`] to `analyze` and `help` results. Defaults to `false`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub debug_timings: Option<bool>,
+    /// How `prop("...")` names match property names. Defaults to `"exact"`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub property_lookup: Option<PropertyLookup>,
+    /// Caps on formula size; uncapped by default.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub limits: Option<FormulaLimits>,
+}
+
+/// Exceeding a cap reports a `formula_too_long` or `nesting_too_deep` error. Lengths count
+/// Unicode scalar values; depth counts nested parentheses, call arguments, and lists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+pub struct FormulaLimits {
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub max_length: Option<usize>,
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub max_depth: Option<usize>,
+}
+
+impl From<FormulaLimits> for analyzer::semantic::FormulaLimits {
+    fn from(limits: FormulaLimits) -> analyzer::semantic::FormulaLimits {
+        analyzer::semantic::FormulaLimits {
+            max_length: limits.max_length,
+            max_depth: limits.max_depth,
+        }
+    }
+}
+
+/// `"lenient"` also matches names that differ only in case or surrounding whitespace, with an
+/// `inexact_property_name` warning and a quick fix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyLookup {
+    Exact,
+    Lenient,
+}
+
+impl From<PropertyLookup> for analyzer::semantic::PropertyLookup {
+    fn from(lookup: PropertyLookup) -> analyzer::semantic::PropertyLookup {
+        match lookup {
+            PropertyLookup::Exact => analyzer::semantic::PropertyLookup::Exact,
+            PropertyLookup::Lenient => analyzer::semantic::PropertyLookup::Lenient,
+        }
+    }
+}
+
+/// Time spent per pipeline phase, in microseconds (`AnalyzerConfig.debug_timings`).
+///
+/// Phases a call does not run are `0`; inference done for completion counts toward `complete`.
+#[derive(Serialize, TS, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Timings {
+    pub lex: f64,
+    pub parse: f64,
+    pub infer: f64,
+    pub complete: f64,
+}
+
+impl From<analyzer::PhaseTimings> for Timings {
+    fn from(timings: analyzer::PhaseTimings) -> Timings {
+        Timings {
+            lex: timings.lex,
+            parse: timings.parse,
+            infer: timings.infer,
+            complete: timings.complete,
+        }
+    }
+}
+
+/// A panic recorded by the hook installed with `init`, returned from `take_internal_error`.
+#[derive(Serialize, TS, Clone, Debug, PartialEq, Eq)]
+pub struct InternalError {
+    /// Panic message with its Rust source location.
+    pub message: String,
+    /// JS stack at the panic, truncated; one frame per line.
+    pub backtrace: String,
+}
+
+/// One schema violation found in an `AnalyzerConfig`.
+#[derive(Serialize, TS, Clone, Debug, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Field path, e.g. `properties[2].type.List`; empty for the config value itself.
+    pub path: String,
+    pub message: String,
+}
+
+/// Result of the `validate_config` WASM export.
+#[derive(Serialize, TS)]
+pub struct ConfigValidationResult {
+    /// Empty when the config is valid.
+    pub issues: Vec<ConfigIssue>,
+}
+
+/// Stable `code` property of errors thrown by WASM exports (`error.name` is `"AnalyzerError"`).
+///
+/// `error.message` is a human-readable category; match on `code` instead.
+#[derive(Serialize, TS, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// `AnalyzerConfig` has the wrong shape, unknown fields, or invalid values.
+    InvalidConfig,
+    /// The per-call `CompletionConfig` is invalid.
+    InvalidCompletionConfig,
+    /// The per-call `FormatConfig` is invalid.
+    InvalidFormatConfig,
+    /// `rename` was given an empty name.
+    InvalidRename,
+    /// The `edits` argument is not a `TextEdit[]`.
+    InvalidEdits,
+    /// The `formulas` argument of `analyze_batch` is not a `FormulaSource[]`.
+    InvalidBatch,
+    /// The argument of `properties_from_notion_schema` is not a Notion `database.properties`
+    /// object.
+    InvalidNotionSchema,
+    /// A Notion `formula.expression` references a property the schema cannot resolve.
+    InvalidNotionExpression,
+    InvalidEditRange,
+    EditNotOnCharBoundary,
+    OverlappingEdits,
+    UnsortedEdits,
+    InvalidCursor,
+    InvalidRange,
+    /// The source has syntax errors, so it cannot be formatted.
+    FormatError,
+    /// A result could not be converted to a JS value.
+    SerializeError,
+    /// An export panicked. Never thrown by the module itself (a panic traps); JS wrappers report
+    /// it with the [`InternalError`] from `take_internal_error`.
+    InternalError,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidConfig => "invalid_config",
+            ErrorCode::InvalidCompletionConfig => "invalid_completion_config",
+            ErrorCode::InvalidFormatConfig => "invalid_format_config",
+            ErrorCode::InvalidRename => "invalid_rename",
+            ErrorCode::InvalidEdits => "invalid_edits",
+            ErrorCode::InvalidBatch => "invalid_batch",
+            ErrorCode::InvalidNotionSchema => "invalid_notion_schema",
+            ErrorCode::InvalidNotionExpression => "invalid_notion_expression",
+            ErrorCode::InvalidEditRange => "invalid_edit_range",
+            ErrorCode::EditNotOnCharBoundary => "edit_not_on_char_boundary",
+            ErrorCode::OverlappingEdits => "overlapping_edits",
+            ErrorCode::UnsortedEdits => "unsorted_edits",
+            ErrorCode::InvalidCursor => "invalid_cursor",
+            ErrorCode::InvalidRange => "invalid_range",
+            ErrorCode::FormatError => "format_error",
+            ErrorCode::SerializeError => "serialize_error",
+            ErrorCode::InternalError => "internal_error",
+        }
+    }
 }
 
-/// A span in UTF-16 code units (half-open `[start, end)`).
+impl From<ide::EditError> for ErrorCode {
+    fn from(err: ide::EditError) -> ErrorCode {
+        match err {
+            ide::EditError::FormatError => ErrorCode::FormatError,
+            ide::EditError::InvalidCursor => ErrorCode::InvalidCursor,
+            ide::EditError::InvalidRange => ErrorCode::InvalidRange,
+            ide::EditError::InvalidEditRange { .. } => ErrorCode::InvalidEditRange,
+            ide::EditError::EditNotOnCharBoundary { .. } => ErrorCode::EditNotOnCharBoundary,
+            ide::EditError::OverlappingEdits { .. } => ErrorCode::OverlappingEdits,
+            ide::EditError::UnsortedEdits { .. } => ErrorCode::UnsortedEdits,
+        }
+    }
+}
+
+/// A span in the configured position encoding (half-open `[start, end)`).
 #[derive(Serialize, Deserialize, TS, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Span {
-    /// Start offset in UTF-16 code units.
+    /// Start offset.
     pub start: u32,
-    /// End offset in UTF-16 code units (exclusive).
+    /// End offset (exclusive).
     pub end: u32,
 }
 
 /// Completion item kind.
-#[derive(Serialize, TS, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, TS, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CompletionItemKind {
     FunctionGeneral,
     FunctionText,
@@ -66,9 +290,211 @@ pub enum CompletionItemKind {
     FunctionPeople,
     FunctionList,
     FunctionSpecial,
-    Builtin,
+    Keyword,
+    Constant,
     Property,
+    Variable,
     Operator,
+    Snippet,
+}
+
+impl From<CompletionItemKind> for ide::CompletionKind {
+    fn from(kind: CompletionItemKind) -> ide::CompletionKind {
+        match kind {
+            CompletionItemKind::FunctionGeneral => ide::CompletionKind::FunctionGeneral,
+            CompletionItemKind::FunctionText => ide::CompletionKind::FunctionText,
+            CompletionItemKind::FunctionNumber => ide::CompletionKind::FunctionNumber,
+            CompletionItemKind::FunctionDate => ide::CompletionKind::FunctionDate,
+            CompletionItemKind::FunctionPeople => ide::CompletionKind::FunctionPeople,
+            CompletionItemKind::FunctionList => ide::CompletionKind::FunctionList,
+            CompletionItemKind::FunctionSpecial => ide::CompletionKind::FunctionSpecial,
+            CompletionItemKind::Keyword => ide::CompletionKind::Keyword,
+            CompletionItemKind::Constant => ide::CompletionKind::Constant,
+            CompletionItemKind::Property => ide::CompletionKind::Property,
+            CompletionItemKind::Variable => ide::CompletionKind::Variable,
+            CompletionItemKind::Operator => ide::CompletionKind::Operator,
+            CompletionItemKind::Snippet => ide::CompletionKind::Snippet,
+        }
+    }
+}
+
+/// Per-call completion options for `help`. Omitted fields use the analyzer defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+pub struct CompletionConfig {
+    /// Overrides `AnalyzerConfig.preferred_limit`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub preferred_limit: Option<usize>,
+    /// Snippet mode: insert `name()` with the cursor inside the parens. Defaults to `true`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub call_parens: Option<bool>,
+    /// Group items toward the expected argument type inside calls. Defaults to `true`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub type_ranking: Option<bool>,
+    /// Item kinds to drop from the results.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub exclude_kinds: Option<Vec<CompletionItemKind>>,
+    /// How the query matches labels. Defaults to `fuzzy`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub match_mode: Option<MatchMode>,
+    /// Weights behind the item order and `preferred_indices`; omitted fields keep the defaults.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub ranking: Option<RankingConfig>,
+    /// Skip ranking: items stay in catalog order and `preferred_indices` is empty. Defaults to
+    /// `false`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub raw: Option<bool>,
+}
+
+/// Query matching for completion labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    Fuzzy,
+    Prefix,
+    Contains,
+}
+
+impl From<MatchMode> for ide::MatchMode {
+    fn from(mode: MatchMode) -> ide::MatchMode {
+        match mode {
+            MatchMode::Fuzzy => ide::MatchMode::Fuzzy,
+            MatchMode::Prefix => ide::MatchMode::Prefix,
+            MatchMode::Contains => ide::MatchMode::Contains,
+        }
+    }
+}
+
+/// How well a query matches a label, weakest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStrength {
+    Fuzzy,
+    Contains,
+    Prefix,
+    Exact,
+}
+
+impl From<MatchStrength> for ide::MatchStrength {
+    fn from(strength: MatchStrength) -> ide::MatchStrength {
+        match strength {
+            MatchStrength::Fuzzy => ide::MatchStrength::Fuzzy,
+            MatchStrength::Contains => ide::MatchStrength::Contains,
+            MatchStrength::Prefix => ide::MatchStrength::Prefix,
+            MatchStrength::Exact => ide::MatchStrength::Exact,
+        }
+    }
+}
+
+/// Score added to every item of `kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+pub struct KindBoost {
+    pub kind: CompletionItemKind,
+    pub boost: i32,
+}
+
+/// Overrides for the completion ranking heuristics.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+pub struct RankingConfig {
+    /// Weakest match that can be preferred. Defaults to `fuzzy`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub preferred_min_match: Option<MatchStrength>,
+    /// Per-kind scores; higher kinds sort and are preferred first. Defaults to none.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub kind_boosts: Option<Vec<KindBoost>>,
+    /// Type score of an item the expected argument type accepts. Defaults to `2`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub type_match_bonus: Option<i32>,
+    /// Type score of an item with an unknown result type. Defaults to `0`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub type_unknown_bonus: Option<i32>,
+    /// Type score of an item the expected argument type rejects. Defaults to `-1`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub type_mismatch_bonus: Option<i32>,
+}
+
+impl From<RankingConfig> for ide::RankingConfig {
+    fn from(overrides: RankingConfig) -> ide::RankingConfig {
+        let defaults = ide::RankingConfig::default();
+        ide::RankingConfig {
+            preferred_min_match: overrides
+                .preferred_min_match
+                .map_or(defaults.preferred_min_match, Into::into),
+            kind_boosts: overrides
+                .kind_boosts
+                .unwrap_or_default()
+                .into_iter()
+                .map(|boost| (boost.kind.into(), boost.boost))
+                .collect(),
+            type_match_bonus: overrides
+                .type_match_bonus
+                .unwrap_or(defaults.type_match_bonus),
+            type_unknown_bonus: overrides
+                .type_unknown_bonus
+                .unwrap_or(defaults.type_unknown_bonus),
+            type_mismatch_bonus: overrides
+                .type_mismatch_bonus
+                .unwrap_or(defaults.type_mismatch_bonus),
+        }
+    }
+}
+
+/// Placement of `)` / `]` when a call or list is broken one item per line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ClosingDelim {
+    OwnLine,
+    SameLine,
+}
+
+#[cfg(feature = "format")]
+impl From<ClosingDelim> for ide::ClosingDelim {
+    fn from(delim: ClosingDelim) -> ide::ClosingDelim {
+        match delim {
+            ClosingDelim::OwnLine => ide::ClosingDelim::OwnLine,
+            ClosingDelim::SameLine => ide::ClosingDelim::SameLine,
+        }
+    }
+}
+
+/// Per-call layout options for `format`. Omitted fields use the formatter defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+pub struct FormatConfig {
+    /// Spaces per indentation level. Defaults to `2`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub indent_width: Option<usize>,
+    /// Preferred maximum line width (UTF-8 bytes) before breaking. Defaults to `80`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub max_width: Option<usize>,
+    /// Wrap own-line `//` comments wider than `max_width`. Defaults to `false`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub wrap_comments: Option<bool>,
+    /// Re-join broken calls and lists that fit on one line. Defaults to `false`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub collapse_fitting: Option<bool>,
+    /// Defaults to `"own_line"`.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub closing_delim: Option<ClosingDelim>,
 }
 
 /// Diagnostic severity/kind.
@@ -76,12 +502,14 @@ pub enum CompletionItemKind {
 pub enum DiagnosticKind {
     #[serde(rename = "error")]
     Error,
+    #[serde(rename = "warning")]
+    Warning,
 }
 
-/// A text edit in UTF-16 coordinates.
+/// A text edit in host coordinates (see `AnalyzerConfig.position_encoding`).
 #[derive(Serialize, Deserialize, TS)]
 pub struct TextEdit {
-    /// Replace range in the original document (UTF-16, half-open).
+    /// Replace range in the original document (half-open).
     pub range: Span,
     /// Inserted verbatim.
     pub new_text: String,
@@ -91,16 +519,25 @@ pub struct TextEdit {
 #[derive(Serialize, TS)]
 pub struct CodeAction {
     pub title: String,
-    /// Edits are in original-document coordinates (UTF-16).
+    /// Edits are in original-document coordinates.
     pub edits: Vec<TextEdit>,
 }
 
+/// A property read by a formula, returned from `referenced_properties`.
+#[derive(Serialize, TS)]
+pub struct PropertyReference {
+    /// Property name with escapes resolved.
+    pub name: String,
+    /// Spans of every `prop("...")` literal naming it (quotes included), in source order.
+    pub spans: Vec<Span>,
+}
+
 /// A diagnostic message tied to a source span.
 #[derive(Serialize, TS)]
 pub struct Diagnostic {
     pub kind: DiagnosticKind,
     pub message: String,
-    /// Location in the source text (UTF-16 span).
+    /// Location in the source text.
     pub span: Span,
     /// 1-based line number derived from source byte offsets.
     pub line: usize,
@@ -115,7 +552,7 @@ pub struct Diagnostic {
 pub struct Token {
     pub kind: String,
     pub text: String,
-    /// Location in the source text (UTF-16 span).
+    /// Location in the source text.
     pub span: Span,
 }
 
@@ -128,13 +565,35 @@ pub struct AnalyzeResult {
     ///
     /// Never nullable. Unknown/failed inference is represented as `"unknown"`.
     pub output_type: String,
+    /// Present only when `AnalyzerConfig.debug_timings` is set.
+    #[ts(optional)]
+    pub timings: Option<Timings>,
+}
+
+/// One formula in an `analyze_batch` call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(deny_unknown_fields)]
+pub struct FormulaSource {
+    /// Caller-chosen key (e.g. the formula property id), echoed back in the result.
+    pub id: String,
+    pub source: String,
+}
+
+/// Per-formula entry returned from the `analyze_batch` WASM export, in input order.
+///
+/// Same `diagnostics`/`output_type` as [`AnalyzeResult`], without the tokens.
+#[derive(Serialize, TS)]
+pub struct BatchAnalyzeItem {
+    pub id: String,
+    pub diagnostics: Vec<Diagnostic>,
+    pub output_type: String,
 }
 
 /// Result payload for `format` and `apply_edits`.
 #[derive(Serialize, TS)]
 pub struct ApplyResult {
     pub source: String,
-    /// Cursor position in the updated document (UTF-16).
+    /// Cursor position in the updated document.
     pub cursor: u32,
 }
 
@@ -184,29 +643,353 @@ pub struct CompletionItem {
     pub label: String,
     pub kind: CompletionItemKind,
     pub insert_text: String,
-    /// Primary edit to apply in the original document (UTF-16), if available.
+    /// Primary edit to apply in the original document, if available.
     pub primary_edit: Option<TextEdit>,
-    /// Cursor position in the updated document after applying edits (UTF-16).
+    /// Cursor position in the updated document after applying edits.
     pub cursor: Option<u32>,
-    /// Additional edits to apply in the original document (UTF-16).
+    /// Additional edits to apply in the original document.
     pub additional_edits: Vec<TextEdit>,
     pub detail: Option<String>,
     pub is_disabled: bool,
     pub disabled_reason: Option<String>,
+    /// How well the typed query matches the label; `null` without a query or a match.
+    pub match_strength: Option<MatchStrength>,
 }
 
 /// Completion payload returned from `help`
 #[derive(Serialize, TS)]
 pub struct CompletionResult {
     pub items: Vec<CompletionItem>,
-    /// Replace range in the original document (UTF-16).
+    /// Replace range in the original document.
     pub replace: Span,
     pub preferred_indices: Vec<usize>,
 }
 
+/// Payload returned from the `completion_replace_span` WASM export.
+#[derive(Serialize, TS)]
+pub struct CompletionReplace {
+    /// Range a completion would replace (the word being typed, or empty at the cursor).
+    pub replace: Span,
+    /// The word being typed, lowercased with `_` removed; `null` when empty or not ASCII.
+    pub query: Option<String>,
+}
+
 /// Help result payload returned from the `help` WASM export.
 #[derive(Serialize, TS)]
 pub struct HelpResult {
     pub completion: CompletionResult,
     pub signature_help: Option<SignatureHelp>,
+    /// Present only when `AnalyzerConfig.debug_timings` is set.
+    #[ts(optional)]
+    pub timings: Option<Timings>,
+}
+
+/// Payload returned from the `type_at` WASM export.
+#[derive(Serialize, TS)]
+pub struct TypeAtResult {
+    /// Range of the smallest expression enclosing the cursor.
+    pub range: Span,
+    /// Inferred type rendered for UI (`"unknown"` when inference fails).
+    pub ty: String,
+}
+
+/// A parse tree node returned from the `ast_json` WASM export, tagged by `kind`.
+///
+/// Every node carries the `span` it covers in the original document.
+#[derive(Serialize, TS, Clone, Debug, PartialEq)]
+#[serde(tag = "kind")]
+pub enum AstNode {
+    Ident {
+        span: Span,
+        name: String,
+    },
+    /// A parenthesized expression.
+    Group {
+        span: Span,
+        inner: Box<AstNode>,
+    },
+    List {
+        span: Span,
+        items: Vec<AstNode>,
+    },
+    Call {
+        span: Span,
+        callee: String,
+        args: Vec<AstNode>,
+    },
+    /// `receiver.method(args)`.
+    MemberCall {
+        span: Span,
+        receiver: Box<AstNode>,
+        method: String,
+        args: Vec<AstNode>,
+    },
+    Number {
+        span: Span,
+        value: f64,
+    },
+    /// String literal; `value` has its escapes resolved.
+    String {
+        span: Span,
+        value: String,
+    },
+    Boolean {
+        span: Span,
+        value: bool,
+    },
+    Unary {
+        span: Span,
+        /// Operator as written (`"-"`, `"!"`, `"not"`).
+        op: String,
+        expr: Box<AstNode>,
+    },
+    Binary {
+        span: Span,
+        /// Operator as written (e.g. `"+"`, `"&&"`).
+        op: String,
+        left: Box<AstNode>,
+        right: Box<AstNode>,
+    },
+    Ternary {
+        span: Span,
+        cond: Box<AstNode>,
+        then: Box<AstNode>,
+        otherwise: Box<AstNode>,
+    },
+    /// Placeholder where the parser recovered from a syntax error.
+    Error {
+        span: Span,
+    },
+}
+
+/// Payload returned from the `ast_json` WASM export.
+#[derive(Serialize, TS)]
+pub struct AstResult {
+    pub root: AstNode,
+    /// Lex and parse diagnostics only (no type checking).
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// CodeMirror 6 `Diagnostic.severity`.
+#[derive(Serialize, TS, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CmSeverity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+/// A CodeMirror `ChangeSpec` (`{ from, to, insert }`), in original-document coordinates.
+#[derive(Serialize, TS, Clone, Debug, PartialEq, Eq)]
+pub struct CmChange {
+    pub from: u32,
+    pub to: u32,
+    pub insert: String,
+}
+
+/// A lint action; dispatch `changes` when it is picked.
+#[derive(Serialize, TS)]
+pub struct CmAction {
+    pub name: String,
+    pub changes: Vec<CmChange>,
+}
+
+/// Shaped like `@codemirror/lint`'s `Diagnostic` (actions carry changes instead of callbacks).
+#[derive(Serialize, TS)]
+pub struct CmDiagnostic {
+    pub from: u32,
+    pub to: u32,
+    pub severity: CmSeverity,
+    pub message: String,
+    pub source: String,
+    pub actions: Vec<CmAction>,
+}
+
+/// Shaped like `@codemirror/autocomplete`'s `Completion`.
+///
+/// Disabled items are left out. Applying is `dispatch({ changes, selection: { anchor: selection } })`.
+#[derive(Serialize, TS)]
+pub struct CmCompletion {
+    pub label: String,
+    pub detail: Option<String>,
+    /// CodeMirror icon type: `function`, `property`, `constant`, or `keyword`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Group header (`Recommended` for the preferred items).
+    pub section: String,
+    /// Positive for preferred items, in preference order.
+    pub boost: i32,
+    pub changes: Vec<CmChange>,
+    /// Cursor after applying `changes`, when known.
+    pub selection: Option<u32>,
+}
+
+/// Shaped like `@codemirror/autocomplete`'s `CompletionResult`.
+#[derive(Serialize, TS)]
+pub struct CmCompletionResult {
+    pub from: u32,
+    pub to: u32,
+    pub options: Vec<CmCompletion>,
+}
+
+/// A mark decoration range; `class` is `nf-<token type>` plus `nf-<modifier>` per modifier.
+#[derive(Serialize, TS, Clone, Debug, PartialEq, Eq)]
+pub struct CmDecoration {
+    pub from: u32,
+    pub to: u32,
+    pub class: String,
+}
+
+/// Payload returned from the `codemirror` WASM export.
+#[derive(Serialize, TS)]
+pub struct CodeMirrorResult {
+    pub diagnostics: Vec<CmDiagnostic>,
+    /// `null` when there are no enabled items.
+    pub completion: Option<CmCompletionResult>,
+    pub decorations: Vec<CmDecoration>,
+}
+
+/// Monaco `IRange`: 1-based lines, 1-based UTF-16 columns, end exclusive.
+#[derive(Serialize, TS, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MonacoRange {
+    pub start_line_number: u32,
+    pub start_column: u32,
+    pub end_line_number: u32,
+    pub end_column: u32,
+}
+
+/// Shaped like Monaco's `IMarkerData` (pass to `editor.setModelMarkers`).
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct MonacoMarker {
+    pub start_line_number: u32,
+    pub start_column: u32,
+    pub end_line_number: u32,
+    pub end_column: u32,
+    /// `MarkerSeverity` value (`8` = error).
+    pub severity: u32,
+    pub message: String,
+    pub source: String,
+}
+
+/// Monaco `ISingleEditOperation` (`{ range, text }`).
+#[derive(Serialize, TS, Clone, Debug, PartialEq, Eq)]
+pub struct MonacoTextEdit {
+    pub range: MonacoRange,
+    pub text: String,
+}
+
+/// Shaped like Monaco's `languages.CompletionItem`. Disabled items are left out.
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct MonacoCompletionItem {
+    pub label: String,
+    /// `languages.CompletionItemKind` value (`Function`, `Property`, `Constant`, `Operator`).
+    pub kind: u32,
+    pub detail: Option<String>,
+    /// A snippet with `$0` at the cursor when `insert_text_rules` is `4` (`InsertAsSnippet`).
+    pub insert_text: String,
+    pub insert_text_rules: u32,
+    pub range: MonacoRange,
+    pub additional_text_edits: Vec<MonacoTextEdit>,
+    /// Keeps the analyzer's order (preferred items first).
+    pub sort_text: String,
+}
+
+/// Monaco `ParameterInformation`; `label` is `[start, end)` UTF-16 offsets into the signature label.
+#[derive(Serialize, TS)]
+pub struct MonacoParameterInformation {
+    pub label: (u32, u32),
+}
+
+/// Monaco `SignatureInformation`.
+#[derive(Serialize, TS)]
+pub struct MonacoSignatureInformation {
+    pub label: String,
+    pub parameters: Vec<MonacoParameterInformation>,
+}
+
+/// Shaped like Monaco's `languages.SignatureHelp`.
+#[derive(Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct MonacoSignatureHelp {
+    pub signatures: Vec<MonacoSignatureInformation>,
+    pub active_signature: u32,
+    pub active_parameter: u32,
+}
+
+/// Payload returned from the `monaco` WASM export.
+#[derive(Serialize, TS)]
+pub struct MonacoResult {
+    pub markers: Vec<MonacoMarker>,
+    pub suggestions: Vec<MonacoCompletionItem>,
+    pub signature_help: Option<MonacoSignatureHelp>,
+}
+
+/// Legend for `semantic_tokens` data, returned from `semantic_token_legend`.
+///
+/// Token type `i` in the data is `token_types[i]`; modifier bit `i` is `token_modifiers[i]`.
+#[derive(Serialize, TS)]
+pub struct SemanticTokensLegend {
+    pub token_types: Vec<String>,
+    pub token_modifiers: Vec<String>,
+}
+
+/// Hover payload returned from the `hover` WASM export.
+#[derive(Serialize, TS)]
+pub struct HoverResult {
+    /// Hovered range in the original document.
+    pub range: Span,
+    /// Inferred type rendered for UI (`"unknown"` when inference fails).
+    pub ty: String,
+    /// Signature rendered for the call site, when hovering a known function name.
+    pub signature: Option<SignatureItem>,
+    /// Function documentation, when hovering a known function name.
+    pub docs: Option<String>,
+}
+
+/// Builtin function category (UI grouping).
+#[derive(Serialize, TS, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FunctionCategory {
+    General,
+    Text,
+    Number,
+    Date,
+    People,
+    List,
+    Special,
+}
+
+/// A single parameter slot of a builtin signature.
+#[derive(Serialize, TS)]
+pub struct ParamInfo {
+    pub name: String,
+    /// Expected type rendered for UI (generics render as `T0`, `T1`, ...).
+    pub ty: String,
+    pub optional: bool,
+}
+
+/// Parameter shape: `head`, then `repeat` cycled at least `repeat_min_groups` times, then `tail`.
+#[derive(Serialize, TS)]
+pub struct ParamShapeInfo {
+    pub head: Vec<ParamInfo>,
+    pub repeat: Vec<ParamInfo>,
+    pub tail: Vec<ParamInfo>,
+    pub repeat_min_groups: usize,
+}
+
+/// One entry of the catalog returned from `builtin_catalog`.
+#[derive(Serialize, TS)]
+pub struct BuiltinFunction {
+    pub name: String,
+    pub category: FunctionCategory,
+    pub params: ParamShapeInfo,
+    /// Declared return type rendered for UI.
+    pub ret: String,
+    /// Function documentation.
+    pub docs: String,
+    /// Whether `receiver.name(...)` postfix-call sugar is supported.
+    pub postfix: bool,
 }
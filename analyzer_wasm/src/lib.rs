@@ -1,23 +1,41 @@
 //! WASM entry points for the analyzer.
 //!
-//! Core code uses UTF-8 byte offsets. The JS boundary uses UTF-16 code unit offsets.
+//! Core code uses UTF-8 byte offsets. The JS boundary uses the configured position encoding
+//! (`AnalyzerConfig.position_encoding`), UTF-16 code units by default.
 //! Spans are half-open `[start, end)`.
+mod config;
 mod converter;
 pub mod dto;
 mod offsets;
+mod panic;
+mod session;
 mod span;
 
-use analyzer::analysis::{Context, Property as AnalyzerProperty, builtins_functions};
-use ide::CompletionConfig;
+use std::collections::BTreeMap;
+
+use analyzer::{Instrumentation, Phase};
+use analyzer::analysis::{
+    Context, NotionPropertySchema, Property as AnalyzerProperty, builtins_functions,
+    notion_schema_properties,
+};
+use ide::PositionEncoding;
 use js_sys::Error as JsError;
-use js_sys::Object;
+use js_sys::Reflect;
 use serde::Serialize;
-use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::*;
 
 use crate::converter::Converter;
-use crate::dto::v1::{AnalyzeResult, AnalyzerConfig, ApplyResult, TextEdit as Utf16TextEdit};
-use crate::offsets::{utf16_to_8_cursor, utf16_to_8_text_edits};
+#[cfg(feature = "format")]
+use crate::dto::v1::FormatConfig;
+use crate::dto::v1::{
+    AnalyzeResult, AnalyzerConfig, ApplyResult, CompletionConfig, ConfigIssue,
+    ConfigValidationResult, ErrorCode, FormulaSource, Property, SemanticTokensLegend, Span,
+    TextEdit as HostTextEdit, Timings,
+};
+use crate::offsets::{from_byte_offset, to_byte_cursor, to_byte_offset, to_byte_text_edits};
+
+pub use crate::panic::{init, take_internal_error};
+pub use crate::session::Session;
 
 const DEFAULT_PREFERRED_LIMIT: usize = 5;
 
@@ -25,6 +43,8 @@ const DEFAULT_PREFERRED_LIMIT: usize = 5;
 pub struct Analyzer {
     context: Context,
     preferred_limit: usize,
+    encoding: PositionEncoding,
+    debug_timings: bool,
 }
 
 #[wasm_bindgen]
@@ -33,39 +53,80 @@ impl Analyzer {
     ///
     /// @param config: [`AnalyzerConfig`]
     /// @returns [`Analyzer`]
-    /// @throws [`String`] if the config is invalid
+    /// @throws `AnalyzerError` with code `invalid_config` if the config is invalid; the message
+    /// lists every schema issue (see `validate_config`)
     #[wasm_bindgen(constructor)]
-    pub fn new(config: JsValue) -> Result<Self, String> {
-        validate_config_keys(&config)?;
-        let input: AnalyzerConfig = from_value(config, "Invalid analyzer config")?;
-        Ok(Self {
-            context: Context {
-                properties: input
-                    .properties
-                    .into_iter()
-                    .map(|p| AnalyzerProperty {
-                        name: p.name,
-                        ty: p.ty.into(),
-                        disabled_reason: None,
-                    })
-                    .collect(),
-                functions: builtins_functions(),
-            },
-            preferred_limit: input.preferred_limit.unwrap_or(DEFAULT_PREFERRED_LIMIT),
+    pub fn new(config: JsValue) -> Result<Self, JsValue> {
+        parse_config(config)
+    }
+
+    /// Check a config against the `AnalyzerConfig` schema without constructing an analyzer.
+    ///
+    /// @returns [`ConfigValidationResult`]; `issues` is empty when `new(config)` would succeed
+    pub fn validate_config(config: JsValue) -> Result<JsValue, JsValue> {
+        to_value(&ConfigValidationResult {
+            issues: config_issues(&config),
         })
     }
 
+    /// Open a stateful [`Session`] on `source` that shares this analyzer's config.
+    ///
+    /// @returns [`Session`]
+    pub fn session(&self, source: String) -> Session {
+        Session::new(self, source)
+    }
+
+    /// @returns [`AnalyzeResult`]; with `timings` when `AnalyzerConfig.debug_timings` is set
     pub fn analyze(&self, source: String) -> Result<JsValue, JsValue> {
-        let result = analyzer::analyze(&source, &self.context);
-        let out: AnalyzeResult = Converter::analyze_output(&source, result);
+        if !self.debug_timings {
+            let result = analyzer::analyze(&source, &self.context);
+            let out: AnalyzeResult = Converter::analyze_output(&source, self.encoding, result);
+            return to_value(&out);
+        }
+        let mut recorder = TimingsRecorder::default();
+        let result = analyzer::analyze_instrumented(&source, &self.context, &mut recorder);
+        let mut out = Converter::analyze_output(&source, self.encoding, result);
+        out.timings = Some(recorder.timings);
         to_value(&out)
     }
 
-    pub fn format(&self, source: String, cursor_utf16: u32) -> Result<JsValue, JsValue> {
-        let cursor_byte = utf16_to_8_cursor(&source, cursor_utf16).map_err(operation_err)? as u32;
-        let output = ide::format(&source, cursor_byte).map_err(operation_err)?;
+    /// Analyze many formulas against this analyzer's context in one call (e.g. every formula
+    /// property of a database view at load time).
+    ///
+    /// @param formulas: [`FormulaSource`]`[]`
+    /// @returns [`BatchAnalyzeItem`]`[]`, in input order
+    /// @throws `AnalyzerError` with code `invalid_batch` if `formulas` is not a `FormulaSource[]`
+    pub fn analyze_batch(&self, formulas: JsValue) -> Result<JsValue, JsValue> {
+        let formulas: Vec<FormulaSource> = serde_wasm_bindgen::from_value(formulas)
+            .map_err(|_| js_error(ErrorCode::InvalidBatch, "Invalid batch"))?;
+        let items: Vec<_> = formulas
+            .into_iter()
+            .map(|f| {
+                let result = analyzer::analyze(&f.source, &self.context);
+                Converter::batch_item(f.id, &f.source, self.encoding, result)
+            })
+            .collect();
+        to_value(&items)
+    }
+
+    /// Format `source` and rebase `cursor`.
+    ///
+    /// @param config: optional [`FormatConfig`]; omitted fields use the formatter defaults
+    /// @returns [`ApplyResult`]
+    /// @throws `AnalyzerError` with code `invalid_format_config`, `format_error`, or
+    /// `invalid_cursor`
+    #[cfg(feature = "format")]
+    pub fn format(
+        &self,
+        source: String,
+        cursor: u32,
+        config: Option<JsValue>,
+    ) -> Result<JsValue, JsValue> {
+        let config = format_config(config)?;
+        let cursor = to_byte_cursor(&source, self.encoding, cursor).map_err(operation_err)?;
+        let output = ide::format_with_config(&source, cursor, config).map_err(operation_err)?;
         to_value(&ApplyResult {
-            cursor: Converter::utf8_to_16_offset(&output.source, output.cursor as usize),
+            cursor: from_byte_offset(&output.source, self.encoding, output.cursor),
             source: output.source,
         })
     }
@@ -74,67 +135,514 @@ impl Analyzer {
         &self,
         source: String,
         edits: JsValue,
-        cursor_utf16: u32,
+        cursor: u32,
     ) -> Result<JsValue, JsValue> {
-        let text_edits: Vec<Utf16TextEdit> = serde_wasm_bindgen::from_value(edits)
-            .map_err(|_| JsValue::from(JsError::new("Invalid edits")))?;
-        let text_edits = utf16_to_8_text_edits(&source, text_edits).map_err(operation_err)?;
-        let cursor = utf16_to_8_cursor(&source, cursor_utf16).map_err(operation_err)? as u32;
+        let text_edits: Vec<HostTextEdit> = serde_wasm_bindgen::from_value(edits)
+            .map_err(|_| js_error(ErrorCode::InvalidEdits, "Invalid edits"))?;
+        let text_edits =
+            to_byte_text_edits(&source, self.encoding, text_edits).map_err(operation_err)?;
+        let cursor = to_byte_cursor(&source, self.encoding, cursor).map_err(operation_err)?;
 
         let result = ide::apply_edits(&source, text_edits, cursor).map_err(operation_err)?;
 
         to_value(&ApplyResult {
-            cursor: Converter::utf8_to_16_offset(&result.source, result.cursor as usize),
+            cursor: from_byte_offset(&result.source, self.encoding, result.cursor),
             source: result.source,
         })
     }
 
-    pub fn help(&self, source: String, cursor_utf16: u32) -> Result<JsValue, JsValue> {
-        let cursor = Converter::utf16_to_8_offset(&source, cursor_utf16 as usize);
+    /// Strict variant of `apply_edits` wrapping `ide::apply_text_edits_bytes_with_cursor`.
+    ///
+    /// Edits must already be sorted by `(start, end)` and non-overlapping; unsorted input is
+    /// rejected (`Unsorted edits`) instead of reordered.
+    ///
+    /// @returns [`ApplyResult`]
+    pub fn apply_text_edits(
+        &self,
+        source: String,
+        edits: JsValue,
+        cursor: u32,
+    ) -> Result<JsValue, JsValue> {
+        let text_edits: Vec<HostTextEdit> = serde_wasm_bindgen::from_value(edits)
+            .map_err(|_| js_error(ErrorCode::InvalidEdits, "Invalid edits"))?;
+        let text_edits =
+            to_byte_text_edits(&source, self.encoding, text_edits).map_err(operation_err)?;
+        let cursor = to_byte_cursor(&source, self.encoding, cursor).map_err(operation_err)?;
+
+        let (source, cursor) =
+            ide::apply_text_edits_bytes_with_cursor(&source, &text_edits, cursor)
+                .map_err(operation_err)?;
+
+        to_value(&ApplyResult {
+            cursor: from_byte_offset(&source, self.encoding, cursor),
+            source,
+        })
+    }
+
+    /// Completion items and signature help at a cursor.
+    ///
+    /// @param config: optional [`CompletionConfig`] overriding the analyzer defaults
+    /// @returns [`HelpResult`]; with `timings` when `AnalyzerConfig.debug_timings` is set
+    /// @throws `AnalyzerError` with code `invalid_completion_config` if `config` is invalid
+    pub fn help(
+        &self,
+        source: String,
+        cursor: u32,
+        config: Option<JsValue>,
+    ) -> Result<JsValue, JsValue> {
+        let config = completion_config(self.preferred_limit, config)?;
+        let cursor = to_byte_offset(&source, self.encoding, cursor);
+
+        if !self.debug_timings {
+            let output = ide::help(&source, cursor, &self.context, config);
+            return to_value(&Converter::help_output_view(
+                &source,
+                self.encoding,
+                &output,
+            ));
+        }
+        let mut recorder = TimingsRecorder::default();
+        let output =
+            ide::help_instrumented(&source, cursor, &self.context, config, &mut recorder);
+        let mut out = Converter::help_output_view(&source, self.encoding, &output);
+        out.timings = Some(recorder.timings);
+        to_value(&out)
+    }
+
+    /// Signature help at a cursor without computing completion, for `(` / `,` triggers.
+    ///
+    /// @returns [`SignatureHelp`], or `null` when the cursor is not inside a known call
+    pub fn signature_help(&self, source: String, cursor: u32) -> Result<JsValue, JsValue> {
+        let cursor = to_byte_offset(&source, self.encoding, cursor);
+        match ide::signature_help(&source, cursor, &self.context) {
+            Some(sig) => to_value(&Converter::signature_help_view(&sig)),
+            None => Ok(JsValue::NULL),
+        }
+    }
+
+    /// The completion replace range and query at a cursor, from the lexer alone, for checking
+    /// on each keystroke whether an open completion popup still applies. Unlike `help`, a
+    /// complete name right before the cursor (such as `sum`) is still replaced.
+    ///
+    /// @returns [`CompletionReplace`]
+    pub fn completion_replace_span(&self, source: String, cursor: u32) -> Result<JsValue, JsValue> {
+        let cursor = to_byte_offset(&source, self.encoding, cursor);
+        let output = ide::completion_replace_span(&source, cursor);
+        to_value(&Converter::completion_replace_view(
+            &source,
+            self.encoding,
+            &output,
+        ))
+    }
+
+    /// Inferred type and range of the smallest expression enclosing a cursor.
+    ///
+    /// @returns [`TypeAtResult`], or `null` when the cursor is not on an expression
+    pub fn type_at(&self, source: String, cursor: u32) -> Result<JsValue, JsValue> {
+        let cursor = to_byte_offset(&source, self.encoding, cursor);
+        match ide::type_at(&source, cursor, &self.context) {
+            Some(output) => to_value(&Converter::type_at_output_view(
+                &source,
+                self.encoding,
+                &output,
+            )),
+            None => Ok(JsValue::NULL),
+        }
+    }
+
+    /// The parse tree of `source` (node kinds, spans, literal values) with its syntax
+    /// diagnostics, for tree views and documentation tooling.
+    ///
+    /// @returns [`AstResult`]
+    pub fn ast_json(&self, source: String) -> Result<JsValue, JsValue> {
+        let output = analyzer::analyze_syntax(&source);
+        to_value(&Converter::ast_view(&source, self.encoding, &output))
+    }
+
+    /// Distinct properties read by `prop("...")` calls, in order of first reference, for host
+    /// dependency graphs (e.g. detecting formula cycles).
+    ///
+    /// @returns [`PropertyReference`]`[]`
+    pub fn referenced_properties(&self, source: String) -> Result<JsValue, JsValue> {
+        let references = ide::referenced_properties(&source);
+        to_value(&Converter::property_references_view(
+            &source,
+            self.encoding,
+            &references,
+        ))
+    }
+
+    /// Edits renaming the property referenced at `cursor` (every `prop("...")` naming it).
+    ///
+    /// @returns [`TextEdit`]`[]` (sorted), or `null` when the cursor is not on a property
+    /// reference
+    /// @throws `AnalyzerError` with code `invalid_rename` if `new_name` is empty
+    pub fn rename(
+        &self,
+        source: String,
+        cursor: u32,
+        new_name: String,
+    ) -> Result<JsValue, JsValue> {
+        if new_name.is_empty() {
+            return Err(js_error(ErrorCode::InvalidRename, "Invalid rename"));
+        }
+        let cursor = to_byte_offset(&source, self.encoding, cursor) as u32;
+        match ide::rename(&source, cursor, &new_name) {
+            Some(edits) => to_value(&Converter::text_edits_view(&source, self.encoding, &edits)),
+            None => Ok(JsValue::NULL),
+        }
+    }
+
+    /// Quick fixes from the diagnostics overlapping `range` (a cursor when empty).
+    ///
+    /// @param range: [`Span`]
+    /// @returns [`CodeAction`]`[]`
+    /// @throws `AnalyzerError` with code `invalid_range` if `range` is not a `Span` or is reversed
+    pub fn code_actions(&self, source: String, range: JsValue) -> Result<JsValue, JsValue> {
+        let range: Span = serde_wasm_bindgen::from_value(range)
+            .ok()
+            .filter(|range: &Span| range.start <= range.end)
+            .ok_or_else(|| operation_err(ide::EditError::InvalidRange))?;
+        let range = analyzer::Span {
+            start: to_byte_offset(&source, self.encoding, range.start) as u32,
+            end: to_byte_offset(&source, self.encoding, range.end) as u32,
+        };
+        let actions = ide::code_actions(&source, range, &self.context);
+        to_value(&Converter::code_actions_view(
+            &source,
+            self.encoding,
+            &actions,
+        ))
+    }
+
+    /// Semantic tokens as LSP `SemanticTokens.data` (delta-encoded; columns in the configured
+    /// position encoding).
+    ///
+    /// Decode token types/modifiers with `Analyzer.semantic_token_legend()`.
+    pub fn semantic_tokens(&self, source: String) -> Vec<u32> {
+        let tokens = ide::semantic_tokens(&source, &self.context);
+        ide::encode_semantic_tokens_lsp(&source, &tokens, self.encoding)
+    }
 
-        let output = ide::help(
+    /// Diagnostics, completion at `cursor`, and token decorations shaped for CodeMirror 6
+    /// (`@codemirror/lint`, `@codemirror/autocomplete`, mark decorations).
+    ///
+    /// @param config: optional [`CompletionConfig`], as for `help`
+    /// @returns [`CodeMirrorResult`]
+    pub fn codemirror(
+        &self,
+        source: String,
+        cursor: u32,
+        config: Option<JsValue>,
+    ) -> Result<JsValue, JsValue> {
+        let config = completion_config(self.preferred_limit, config)?;
+        let cursor = to_byte_offset(&source, self.encoding, cursor);
+
+        let analyzed = analyzer::analyze(&source, &self.context);
+        let help = ide::help(&source, cursor, &self.context, config);
+        let tokens = ide::semantic_tokens(&source, &self.context);
+        to_value(&Converter::codemirror_view(
             &source,
-            cursor,
-            &self.context,
-            CompletionConfig {
-                preferred_limit: self.preferred_limit,
-            },
-        );
-        to_value(&Converter::help_output_view(&source, &output))
+            self.encoding,
+            &analyzed.diagnostics,
+            &help,
+            &tokens,
+        ))
+    }
+
+    /// Markers, completion items, and signature help at `cursor` shaped for Monaco (1-based
+    /// lines, UTF-16 columns regardless of the position encoding).
+    ///
+    /// @param config: optional [`CompletionConfig`], as for `help`
+    /// @returns [`MonacoResult`]
+    pub fn monaco(
+        &self,
+        source: String,
+        cursor: u32,
+        config: Option<JsValue>,
+    ) -> Result<JsValue, JsValue> {
+        let config = completion_config(self.preferred_limit, config)?;
+        let cursor = to_byte_offset(&source, self.encoding, cursor);
+
+        let analyzed = analyzer::analyze(&source, &self.context);
+        let help = ide::help(&source, cursor, &self.context, config);
+        to_value(&Converter::monaco_view(
+            &source,
+            &analyzed.diagnostics,
+            &help,
+        ))
+    }
+
+    /// @returns [`SemanticTokensLegend`]
+    pub fn semantic_token_legend() -> Result<JsValue, JsValue> {
+        to_value(&SemanticTokensLegend {
+            token_types: ide::SEMANTIC_TOKEN_TYPES
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            token_modifiers: ide::SEMANTIC_TOKEN_MODIFIERS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        })
+    }
+
+    /// Every builtin function with its category, parameter shape, return type, docs, and
+    /// postfix capability, for rendering a function reference.
+    ///
+    /// @returns [`BuiltinFunction`]`[]`
+    pub fn builtin_catalog() -> Result<JsValue, JsValue> {
+        to_value(&Converter::builtin_catalog_view(builtins_functions()))
+    }
+
+    /// Map a Notion API `database.properties` object (name → property schema) to
+    /// `AnalyzerConfig.properties`, in name order.
+    ///
+    /// @returns [`Property`]`[]`
+    /// @throws `AnalyzerError` with code `invalid_notion_schema` if a property has no `type`
+    pub fn properties_from_notion_schema(properties: JsValue) -> Result<JsValue, JsValue> {
+        let schema = notion_schema(properties)?;
+        let properties: Vec<Property> = notion_schema_properties(&schema)
+            .into_iter()
+            .map(|p| Property {
+                name: p.name,
+                ty: p.ty.into(),
+                options: (!p.options.is_empty()).then_some(p.options),
+            })
+            .collect();
+        to_value(&properties)
+    }
+
+    /// Formula source for a Notion API `formula.expression`: property-id placeholders become
+    /// `prop("Name")`, with names from `properties` (the database's `properties` object).
+    ///
+    /// @throws `AnalyzerError` with code `invalid_notion_schema` or `invalid_notion_expression`
+    /// (unknown property id, unterminated placeholder)
+    pub fn from_notion_expression(
+        expression: String,
+        properties: JsValue,
+    ) -> Result<String, JsValue> {
+        let schema = notion_schema(properties)?;
+        ide::from_notion_expression(&expression, &schema).map_err(invalid_notion_expression)
+    }
+
+    /// The Notion API `formula.expression` for `source`: each `prop("Name")` becomes the
+    /// property's id placeholder.
+    ///
+    /// @throws `AnalyzerError` with code `invalid_notion_schema` or `invalid_notion_expression`
+    /// (a property missing from `properties` or without an `id`)
+    pub fn to_notion_expression(source: String, properties: JsValue) -> Result<String, JsValue> {
+        let schema = notion_schema(properties)?;
+        ide::to_notion_expression(&source, &schema).map_err(invalid_notion_expression)
+    }
+
+    /// Hover info at a cursor.
+    ///
+    /// @returns [`HoverResult`], or `null` when the cursor is not on an expression
+    pub fn hover(&self, source: String, cursor: u32) -> Result<JsValue, JsValue> {
+        let cursor = to_byte_offset(&source, self.encoding, cursor);
+        match ide::hover(&source, cursor, &self.context) {
+            Some(output) => to_value(&Converter::hover_output_view(
+                &source,
+                self.encoding,
+                &output,
+            )),
+            None => Ok(JsValue::NULL),
+        }
     }
 }
 
-fn to_value<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
-    serde_wasm_bindgen::to_value(value).map_err(|_| JsValue::from(JsError::new("Serialize error")))
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
 }
 
-fn from_value<T: serde::de::DeserializeOwned>(
-    value: JsValue,
-    err: &'static str,
-) -> Result<T, String> {
-    serde_wasm_bindgen::from_value(value).map_err(|_| err.to_string())
+/// Clock for `debug_timings`: `performance.now()` in microseconds.
+fn now_micros() -> f64 {
+    performance_now() * 1000.0
 }
 
-fn operation_err(err: ide::IdeError) -> JsValue {
-    JsValue::from(JsError::new(err.message()))
+/// Collects the [`Timings`] attached under `debug_timings`.
+#[derive(Default)]
+struct TimingsRecorder {
+    timings: Timings,
 }
 
-fn validate_config_keys(config: &JsValue) -> Result<(), String> {
-    if !config.is_object() {
-        return Err("Invalid analyzer config".to_string());
+impl Instrumentation for TimingsRecorder {
+    fn now(&mut self) -> f64 {
+        now_micros()
     }
 
-    let object = config.unchecked_ref::<Object>();
-    let keys = Object::keys(object);
-    for i in 0..keys.length() {
-        let Some(key) = keys.get(i).as_string() else {
-            return Err("Invalid analyzer config".to_string());
+    fn phase(&mut self, phase: Phase, duration: f64, _input_len: usize) {
+        let slot = match phase {
+            Phase::Lex => &mut self.timings.lex,
+            Phase::Parse => &mut self.timings.parse,
+            Phase::Infer => &mut self.timings.infer,
+            Phase::Complete => &mut self.timings.complete,
+            Phase::Format => return,
         };
-        match key.as_str() {
-            "properties" | "preferred_limit" => {}
-            _ => return Err("Invalid analyzer config".to_string()),
+        *slot += duration;
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value)
+        .map_err(|_| js_error(ErrorCode::SerializeError, "Serialize error"))
+}
+
+/// Build the `AnalyzerError` thrown by every export: a JS `Error` with a stable `code`.
+fn js_error(code: ErrorCode, message: &str) -> JsValue {
+    let err = JsError::new(message);
+    err.set_name("AnalyzerError");
+    // Setting a property on a fresh `Error` object cannot fail.
+    let _ = Reflect::set(
+        &err,
+        &JsValue::from_str("code"),
+        &JsValue::from_str(code.as_str()),
+    );
+    err.into()
+}
+
+fn notion_schema(properties: JsValue) -> Result<BTreeMap<String, NotionPropertySchema>, JsValue> {
+    serde_wasm_bindgen::from_value(properties)
+        .map_err(|_| js_error(ErrorCode::InvalidNotionSchema, "Invalid Notion schema"))
+}
+
+/// `Invalid Notion expression: <detail>`
+fn invalid_notion_expression(err: ide::NotionExpressionError) -> JsValue {
+    js_error(
+        ErrorCode::InvalidNotionExpression,
+        &format!("Invalid Notion expression: {err}"),
+    )
+}
+
+fn invalid_config() -> JsValue {
+    js_error(ErrorCode::InvalidConfig, "Invalid analyzer config")
+}
+
+/// `Invalid analyzer config: <path>: <message>; ...`
+fn invalid_config_with(issues: &[ConfigIssue]) -> JsValue {
+    let details = issues
+        .iter()
+        .map(|issue| match issue.path.as_str() {
+            "" => issue.message.clone(),
+            path => format!("{path}: {}", issue.message),
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+    js_error(
+        ErrorCode::InvalidConfig,
+        &format!("Invalid analyzer config: {details}"),
+    )
+}
+
+fn operation_err(err: ide::EditError) -> JsValue {
+    js_error(err.into(), err.message())
+}
+
+/// Per-call format options over the formatter defaults.
+#[cfg(feature = "format")]
+fn format_config(overrides: Option<JsValue>) -> Result<ide::FormatConfig, JsValue> {
+    let overrides: FormatConfig = match overrides {
+        Some(value) if !value.is_undefined() && !value.is_null() => {
+            serde_wasm_bindgen::from_value(value)
+                .map_err(|_| js_error(ErrorCode::InvalidFormatConfig, "Invalid format config"))?
         }
+        _ => FormatConfig::default(),
+    };
+
+    let defaults = ide::FormatConfig::default();
+    Ok(ide::FormatConfig {
+        indent_width: overrides.indent_width.unwrap_or(defaults.indent_width),
+        max_width: overrides.max_width.unwrap_or(defaults.max_width),
+        wrap_comments: overrides.wrap_comments.unwrap_or(defaults.wrap_comments),
+        collapse_fitting: overrides
+            .collapse_fitting
+            .unwrap_or(defaults.collapse_fitting),
+        closing_delim: overrides
+            .closing_delim
+            .map_or(defaults.closing_delim, Into::into),
+    })
+}
+
+/// Merge per-call completion options over the analyzer's `preferred_limit`.
+fn completion_config(
+    preferred_limit: usize,
+    overrides: Option<JsValue>,
+) -> Result<ide::CompletionConfig, JsValue> {
+    let overrides: CompletionConfig = match overrides {
+        Some(value) if !value.is_undefined() && !value.is_null() => {
+            serde_wasm_bindgen::from_value(value).map_err(|_| {
+                js_error(
+                    ErrorCode::InvalidCompletionConfig,
+                    "Invalid completion config",
+                )
+            })?
+        }
+        _ => CompletionConfig::default(),
+    };
+
+    let defaults = ide::CompletionConfig::default();
+    Ok(ide::CompletionConfig {
+        preferred_limit: overrides.preferred_limit.unwrap_or(preferred_limit),
+        call_parens: overrides.call_parens.unwrap_or(defaults.call_parens),
+        type_ranking: overrides.type_ranking.unwrap_or(defaults.type_ranking),
+        exclude_kinds: overrides
+            .exclude_kinds
+            .unwrap_or_default()
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        match_mode: overrides
+            .match_mode
+            .map_or(defaults.match_mode, Into::into),
+        ranking: overrides.ranking.map_or(defaults.ranking, Into::into),
+        raw: overrides.raw.unwrap_or(defaults.raw),
+        snippets: defaults.snippets,
+    })
+}
+
+fn parse_config(config: JsValue) -> Result<Analyzer, JsValue> {
+    let issues = config_issues(&config);
+    if !issues.is_empty() {
+        return Err(invalid_config_with(&issues));
     }
+    let input: AnalyzerConfig =
+        serde_wasm_bindgen::from_value(config).map_err(|_| invalid_config())?;
+    let mut context = Context::with_builtins(
+        input
+            .properties
+            .into_iter()
+            .map(|p| AnalyzerProperty {
+                name: p.name,
+                ty: p.ty.into(),
+                disabled_reason: None,
+                options: p.options.unwrap_or_default(),
+            })
+            .collect(),
+    );
+    if let Some(lookup) = input.property_lookup {
+        context.property_lookup = lookup.into();
+    }
+    if let Some(limits) = input.limits {
+        context.limits = limits.into();
+    }
+    Ok(Analyzer {
+        context,
+        preferred_limit: input.preferred_limit.unwrap_or(DEFAULT_PREFERRED_LIMIT),
+        encoding: input.position_encoding.unwrap_or_default().into(),
+        debug_timings: input.debug_timings.unwrap_or(false),
+    })
+}
 
-    Ok(())
+fn config_issues(config: &JsValue) -> Vec<ConfigIssue> {
+    match serde_wasm_bindgen::from_value::<serde_json::Value>(config.clone()) {
+        Ok(value) => config::validate(&value),
+        // Functions, symbols, bigints, ...: not representable as JSON at all.
+        Err(_) => vec![ConfigIssue {
+            path: String::new(),
+            message: "expected a JSON-compatible object".into(),
+        }],
+    }
 }
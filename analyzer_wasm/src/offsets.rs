@@ -1,12 +1,13 @@
-//! Convert between UTF-16 offsets (editors) and UTF-8 byte offsets (Rust).
+//! Convert between host offsets (UTF-16 by default, or UTF-8) and UTF-8 byte offsets (Rust).
 //!
 //! Inputs are clamped and floored to valid boundaries, so these helpers never panic.
+//! The conversion logic itself lives in `ide` (shared with non-WASM hosts).
 
 use analyzer::{Span as ByteSpan, TextEdit as ByteTextEdit};
-use ide::IdeError;
+use ide::{EditError, PositionEncoding, TextEditUtf16};
 
 use crate::converter::Converter;
-use crate::dto::v1::{Span as Utf16Span, TextEdit as Utf16TextEdit};
+use crate::dto::v1::TextEdit as Utf16TextEdit;
 
 impl Converter {
     /// Convert a UTF-16 code unit offset into a UTF-8 byte offset.
@@ -14,28 +15,8 @@ impl Converter {
     /// Out-of-range values are clamped. If the offset lands inside a scalar's UTF-16 encoding (for
     /// example, inside a surrogate pair), it is floored to the scalar start.
     pub fn utf16_to_8_offset(source: &str, utf16: usize) -> usize {
-        let utf16_len = source.encode_utf16().count();
-        let utf16 = utf16.min(utf16_len);
-
-        if utf16 == 0 {
-            return 0;
-        }
-
-        let mut u16_count = 0usize;
-        for (byte_idx, ch) in source.char_indices() {
-            if u16_count >= utf16 {
-                return byte_idx;
-            }
-
-            let next = u16_count.saturating_add(ch.len_utf16());
-            // If `utf16` falls inside this scalar's UTF-16 encoding, floor to the scalar start.
-            if next > utf16 {
-                return byte_idx;
-            }
-            u16_count = next;
-        }
-
-        source.len()
+        let utf16 = u32::try_from(utf16).unwrap_or(u32::MAX);
+        ide::utf16_to_byte_offset(source, utf16) as usize
     }
 
     /// Convert a UTF-8 byte offset into a UTF-16 code unit offset.
@@ -43,89 +24,87 @@ impl Converter {
     /// If `byte` is not a UTF-8 char boundary, it is floored to the previous boundary.
     /// If `byte` is past the end, this returns the UTF-16 length of `source`.
     pub fn utf8_to_16_offset(source: &str, byte: usize) -> u32 {
-        if byte == 0 {
-            return 0;
-        }
-
-        let mut u16_count = 0u32;
-        for (byte_idx, ch) in source.char_indices() {
-            if byte <= byte_idx {
-                return u16_count;
-            }
+        let byte = u32::try_from(byte).unwrap_or(u32::MAX);
+        ide::byte_to_utf16_offset(source, byte)
+    }
+}
 
-            let ch_end = byte_idx.saturating_add(ch.len_utf8());
-            if byte < ch_end {
-                // `byte` falls inside this scalar's UTF-8 encoding => floor to this scalar start.
-                return u16_count;
-            }
+pub fn utf16_to_8_cursor(source: &str, cursor_utf16: u32) -> Result<usize, EditError> {
+    ide::utf16_cursor_to_byte(source, cursor_utf16).map(|cursor| cursor as usize)
+}
 
-            u16_count = u16_count.saturating_add(ch.len_utf16() as u32);
-        }
+pub fn utf16_to_8_text_edits(
+    source: &str,
+    text_edits: Vec<Utf16TextEdit>,
+) -> Result<Vec<ByteTextEdit>, EditError> {
+    let text_edits = text_edits
+        .into_iter()
+        .map(|edit| TextEditUtf16 {
+            range: ByteSpan {
+                start: edit.range.start,
+                end: edit.range.end,
+            },
+            new_text: edit.new_text,
+        })
+        .collect();
+    ide::text_edits_from_utf16(source, text_edits)
+}
 
-        source.encode_utf16().count() as u32
+/// Convert a host offset (in `enc` units) to a byte offset, clamping and flooring.
+pub fn to_byte_offset(source: &str, enc: PositionEncoding, offset: u32) -> usize {
+    match enc {
+        PositionEncoding::Utf16 => Converter::utf16_to_8_offset(source, offset as usize),
+        PositionEncoding::Utf8 => enc.to_byte(source, offset) as usize,
     }
 }
 
-pub fn utf16_to_8_cursor(source: &str, cursor_utf16: u32) -> Result<usize, IdeError> {
-    let utf16_len = source.encode_utf16().count();
-    let cursor_utf16 = cursor_utf16 as usize;
-    if cursor_utf16 > utf16_len {
-        return Err(IdeError::InvalidCursor);
+/// Convert a byte offset to a host offset (in `enc` units).
+pub fn from_byte_offset(source: &str, enc: PositionEncoding, byte: u32) -> u32 {
+    match enc {
+        PositionEncoding::Utf16 => Converter::utf8_to_16_offset(source, byte as usize),
+        PositionEncoding::Utf8 => enc.from_byte(source, byte),
     }
+}
 
-    let cursor_utf8 = Converter::utf16_to_8_offset(source, cursor_utf16);
-    if !source.is_char_boundary(cursor_utf8) {
-        return Err(IdeError::InvalidCursor);
+/// Like [`utf16_to_8_cursor`], for any encoding.
+pub fn to_byte_cursor(source: &str, enc: PositionEncoding, cursor: u32) -> Result<u32, EditError> {
+    match enc {
+        PositionEncoding::Utf16 => utf16_to_8_cursor(source, cursor).map(|c| c as u32),
+        PositionEncoding::Utf8 => enc.cursor_to_byte(source, cursor),
     }
-
-    Ok(cursor_utf8)
 }
 
-pub fn utf16_to_8_text_edits(
+/// Like [`utf16_to_8_text_edits`], for any encoding. UTF-8 edits are validated by `apply_edits`.
+pub fn to_byte_text_edits(
     source: &str,
+    enc: PositionEncoding,
     text_edits: Vec<Utf16TextEdit>,
-) -> Result<Vec<ByteTextEdit>, IdeError> {
-    let utf16_len = source.encode_utf16().count();
-
-    let mut utf8_text_edits = Vec::with_capacity(text_edits.len());
-    for edit in text_edits {
-        let Utf16Span { start, end } = edit.range;
-        let start_utf16 = start as usize;
-        let end_utf16 = end as usize;
-
-        if end_utf16 < start_utf16 || end_utf16 > utf16_len {
-            return Err(IdeError::InvalidEditRange);
-        }
-
-        let start_utf8 = Converter::utf16_to_8_offset(source, start_utf16);
-        let end_utf8 = Converter::utf16_to_8_offset(source, end_utf16);
-
-        if end_utf8 < start_utf8 {
-            return Err(IdeError::InvalidEditRange);
-        }
-        if !source.is_char_boundary(start_utf8) || !source.is_char_boundary(end_utf8) {
-            return Err(IdeError::InvalidEditRange);
-        }
-
-        utf8_text_edits.push(ByteTextEdit {
-            range: ByteSpan {
-                start: start_utf8 as u32,
-                end: end_utf8 as u32,
-            },
-            new_text: edit.new_text,
-        });
+) -> Result<Vec<ByteTextEdit>, EditError> {
+    match enc {
+        PositionEncoding::Utf16 => utf16_to_8_text_edits(source, text_edits),
+        PositionEncoding::Utf8 => Ok(text_edits
+            .into_iter()
+            .map(|edit| ByteTextEdit {
+                range: ByteSpan {
+                    start: edit.range.start,
+                    end: edit.range.end,
+                },
+                new_text: edit.new_text,
+            })
+            .collect()),
     }
-
-    Ok(utf8_text_edits)
 }
 
 #[cfg(test)]
 mod tests {
-    use ide::IdeError;
+    use ide::EditError;
 
     use crate::converter::Converter;
     use crate::dto::v1::{Span as Utf16Span, TextEdit as Utf16TextEdit};
-    use crate::offsets::{utf16_to_8_cursor, utf16_to_8_text_edits};
+    use crate::offsets::{
+        from_byte_offset, to_byte_cursor, to_byte_offset, utf16_to_8_cursor, utf16_to_8_text_edits,
+    };
+    use ide::PositionEncoding;
 
     #[test]
     fn utf16_to_8_offset_floors_inside_scalar_encoding() {
@@ -154,7 +133,7 @@ mod tests {
     fn utf16_to_8_cursor_rejects_out_of_bounds() {
         let source = "abc";
         let err = utf16_to_8_cursor(source, 4).expect_err("expected out-of-bounds cursor");
-        assert_eq!(err, IdeError::InvalidCursor);
+        assert_eq!(err, EditError::InvalidCursor);
     }
 
     #[test]
@@ -167,6 +146,44 @@ mod tests {
 
         let err =
             utf16_to_8_text_edits(source, text_edits).expect_err("expected invalid edit range");
-        assert_eq!(err, IdeError::InvalidEditRange);
+        assert_eq!(err, EditError::InvalidEditRange { index: 0 });
+    }
+
+    #[test]
+    fn utf16_to_8_text_edits_rejects_offset_inside_surrogate_pair() {
+        let source = "a😀b";
+        let text_edits = vec![
+            Utf16TextEdit {
+                range: Utf16Span { start: 0, end: 1 },
+                new_text: "x".to_string(),
+            },
+            Utf16TextEdit {
+                range: Utf16Span { start: 2, end: 3 },
+                new_text: "y".to_string(),
+            },
+        ];
+
+        let err =
+            utf16_to_8_text_edits(source, text_edits).expect_err("expected mid-surrogate offset");
+        assert_eq!(
+            err,
+            EditError::EditNotOnCharBoundary {
+                index: 1,
+                offset: 2
+            }
+        );
+    }
+
+    #[test]
+    fn utf8_encoding_uses_byte_offsets_and_floors_inside_chars() {
+        let source = "😀a";
+        assert_eq!(to_byte_offset(source, PositionEncoding::Utf8, 5), 5);
+        assert_eq!(to_byte_offset(source, PositionEncoding::Utf8, 2), 0);
+        assert_eq!(from_byte_offset(source, PositionEncoding::Utf8, 4), 4);
+        assert_eq!(to_byte_offset(source, PositionEncoding::Utf16, 2), 4);
+
+        let err = to_byte_cursor(source, PositionEncoding::Utf8, 6)
+            .expect_err("expected out-of-bounds cursor");
+        assert_eq!(err, EditError::InvalidCursor);
     }
 }
@@ -0,0 +1,112 @@
+//! Panic reporting for the WASM module.
+//!
+//! `wasm32-unknown-unknown` aborts on panic: the export traps with a bare
+//! `RuntimeError: unreachable` and nothing can be returned to JS. The hook installed by [`init`]
+//! logs the panic to `console.error` and records it as an [`InternalError`], which JS collects
+//! with [`take_internal_error`] after catching the trap.
+
+use std::panic::PanicHookInfo;
+use std::sync::{Mutex, Once};
+
+use js_sys::Reflect;
+use wasm_bindgen::prelude::*;
+
+use crate::dto::v1::InternalError;
+use crate::to_value;
+
+/// Stack frames kept in [`InternalError::backtrace`].
+const MAX_BACKTRACE_LINES: usize = 16;
+
+static INSTALL: Once = Once::new();
+static LAST_PANIC: Mutex<Option<InternalError>> = Mutex::new(None);
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn console_error(message: &str);
+}
+
+/// Install the panic hook. Call once after instantiating the module; repeated calls are no-ops.
+#[wasm_bindgen]
+pub fn init() {
+    INSTALL.call_once(|| std::panic::set_hook(Box::new(hook)));
+}
+
+/// The panic recorded since the last call, if any.
+///
+/// @returns [`InternalError`], or `null` when no export has panicked
+#[wasm_bindgen]
+pub fn take_internal_error() -> Result<JsValue, JsValue> {
+    match LAST_PANIC.lock().ok().and_then(|mut last| last.take()) {
+        Some(error) => to_value(&error),
+        None => Ok(JsValue::NULL),
+    }
+}
+
+fn hook(info: &PanicHookInfo<'_>) {
+    let error = internal_error(&info.to_string(), &js_stack());
+    console_error(&format!(
+        "{}\n\nStack:\n\n{}",
+        error.message, error.backtrace
+    ));
+    if let Ok(mut last) = LAST_PANIC.lock() {
+        *last = Some(error);
+    }
+}
+
+/// `new Error().stack`; the Rust frames show up there as wasm function names.
+fn js_stack() -> String {
+    Reflect::get(&js_sys::Error::new(""), &JsValue::from_str("stack"))
+        .ok()
+        .and_then(|stack| stack.as_string())
+        .unwrap_or_default()
+}
+
+fn internal_error(message: &str, stack: &str) -> InternalError {
+    let mut frames = stack
+        .lines()
+        .map(str::trim)
+        // The `Error` header line carries no frame.
+        .filter(|line| !line.is_empty() && *line != "Error");
+    let mut backtrace = frames
+        .by_ref()
+        .take(MAX_BACKTRACE_LINES)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let omitted = frames.count();
+    if omitted > 0 {
+        backtrace.push_str(&format!("\n... {omitted} more frames"));
+    }
+
+    InternalError {
+        message: message.to_string(),
+        backtrace,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_BACKTRACE_LINES, internal_error};
+
+    #[test]
+    fn backtrace_drops_header_and_truncates_frames() {
+        let stack = std::iter::once("Error".to_string())
+            .chain((0..MAX_BACKTRACE_LINES + 3).map(|i| format!("    at frame{i} (wasm)")))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let error = internal_error("panicked at src/lib.rs:1:1:\nboom", &stack);
+
+        assert_eq!(error.message, "panicked at src/lib.rs:1:1:\nboom");
+        let lines: Vec<_> = error.backtrace.lines().collect();
+        assert_eq!(lines.len(), MAX_BACKTRACE_LINES + 1);
+        assert_eq!(lines[0], "at frame0 (wasm)");
+        assert_eq!(lines[MAX_BACKTRACE_LINES], "... 3 more frames");
+    }
+
+    #[test]
+    fn short_backtrace_is_kept_whole() {
+        let error = internal_error("boom", "Error\n    at a\n    at b\n");
+        assert_eq!(error.backtrace, "at a\nat b");
+    }
+}
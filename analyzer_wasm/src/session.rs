@@ -0,0 +1,151 @@
+//! Stateful analysis session for large or frequently edited formulas.
+//!
+//! `Session` wraps `ide::AnalysisHost`: the document is parsed and type-checked once per text
+//! change, and every query (`analyze`, `diagnostics`, `help`, `hover`, ...) reuses the cached
+//! results, including the line index behind diagnostic `line`/`col`, which edits patch in place. Offsets use the analyzer's configured position encoding.
+
+use analyzer::SourceMap;
+use ide::{AnalysisHost, PositionEncoding};
+use wasm_bindgen::prelude::*;
+
+use crate::converter::Converter;
+use crate::dto::v1::{ErrorCode, TextEdit as HostTextEdit};
+use crate::offsets::{to_byte_offset, to_byte_text_edits};
+use crate::{Analyzer, completion_config, js_error, operation_err, to_value};
+
+#[wasm_bindgen]
+pub struct Session {
+    host: AnalysisHost,
+    preferred_limit: usize,
+    encoding: PositionEncoding,
+}
+
+impl Session {
+    pub(crate) fn new(analyzer: &Analyzer, source: String) -> Self {
+        let mut host = AnalysisHost::new(analyzer.context.clone());
+        host.set_text(source);
+        Self {
+            host,
+            preferred_limit: analyzer.preferred_limit,
+            encoding: analyzer.encoding,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Session {
+    /// Current document text.
+    pub fn text(&self) -> String {
+        self.host.text().to_string()
+    }
+
+    /// Replace the whole document.
+    pub fn set_text(&mut self, source: String) {
+        self.host.set_text(source);
+    }
+
+    /// Apply edits (in current-document coordinates) to the document.
+    ///
+    /// Follows `Analyzer.apply_edits` validation; on error the document is unchanged.
+    pub fn update_text(&mut self, edits: JsValue) -> Result<(), JsValue> {
+        let text_edits: Vec<HostTextEdit> = serde_wasm_bindgen::from_value(edits)
+            .map_err(|_| js_error(ErrorCode::InvalidEdits, "Invalid edits"))?;
+        let text_edits = to_byte_text_edits(self.host.text(), self.encoding, text_edits)
+            .map_err(operation_err)?;
+        self.host.apply_change(text_edits).map_err(operation_err)
+    }
+
+    /// Diagnostics the document would have after `edits` (in current-document coordinates),
+    /// without applying them; positions refer to the edited text.
+    ///
+    /// Follows `update_text` validation.
+    ///
+    /// @returns [`Diagnostic`]`[]`
+    pub fn preview(&self, edits: JsValue) -> Result<JsValue, JsValue> {
+        let text_edits: Vec<HostTextEdit> = serde_wasm_bindgen::from_value(edits)
+            .map_err(|_| js_error(ErrorCode::InvalidEdits, "Invalid edits"))?;
+        let text_edits = to_byte_text_edits(self.host.text(), self.encoding, text_edits)
+            .map_err(operation_err)?;
+        let edited = ide::apply_edits(self.host.text(), text_edits.clone(), 0)
+            .map_err(operation_err)?
+            .source;
+        let diagnostics = self.host.preview(text_edits).map_err(operation_err)?;
+        to_value(&Converter::diagnostics_view(
+            &SourceMap::new(&edited),
+            self.encoding,
+            &diagnostics,
+        ))
+    }
+
+    /// Same as `Analyzer.analyze` on the current document.
+    ///
+    /// @returns [`AnalyzeResult`]
+    pub fn analyze(&self) -> Result<JsValue, JsValue> {
+        let host = &self.host;
+        to_value(&Converter::analysis_view(
+            &host.source_map(),
+            self.encoding,
+            host.diagnostics(),
+            host.tokens(),
+            host.output_type(),
+        ))
+    }
+
+    /// Syntax and semantic diagnostics for the current document.
+    ///
+    /// @returns [`Diagnostic`]`[]`
+    pub fn diagnostics(&self) -> Result<JsValue, JsValue> {
+        let host = &self.host;
+        to_value(&Converter::diagnostics_view(
+            &host.source_map(),
+            self.encoding,
+            host.diagnostics(),
+        ))
+    }
+
+    /// Completion and signature help (same as `Analyzer.help`).
+    ///
+    /// @param config: optional [`CompletionConfig`] overriding the analyzer defaults
+    /// @returns [`HelpResult`]
+    pub fn help(&self, cursor: u32, config: Option<JsValue>) -> Result<JsValue, JsValue> {
+        let config = completion_config(self.preferred_limit, config)?;
+        let source = self.host.text();
+        let cursor = to_byte_offset(source, self.encoding, cursor);
+        let output = self.host.help(cursor, config);
+        to_value(&Converter::help_output_view(source, self.encoding, &output))
+    }
+
+    /// @returns [`HoverResult`], or `null` when the cursor is not on an expression
+    pub fn hover(&self, cursor: u32) -> Result<JsValue, JsValue> {
+        let source = self.host.text();
+        let cursor = to_byte_offset(source, self.encoding, cursor);
+        match self.host.hover(cursor) {
+            Some(output) => to_value(&Converter::hover_output_view(
+                source,
+                self.encoding,
+                &output,
+            )),
+            None => Ok(JsValue::NULL),
+        }
+    }
+
+    /// @returns [`TypeAtResult`], or `null` when the cursor is not on an expression
+    pub fn type_at(&self, cursor: u32) -> Result<JsValue, JsValue> {
+        let source = self.host.text();
+        let cursor = to_byte_offset(source, self.encoding, cursor);
+        match self.host.type_at(cursor) {
+            Some(output) => to_value(&Converter::type_at_output_view(
+                source,
+                self.encoding,
+                &output,
+            )),
+            None => Ok(JsValue::NULL),
+        }
+    }
+
+    /// Same as `Analyzer.semantic_tokens` on the current document.
+    pub fn semantic_tokens(&self) -> Vec<u32> {
+        let tokens = self.host.semantic_tokens();
+        ide::encode_semantic_tokens_lsp(self.host.text(), &tokens, self.encoding)
+    }
+}
@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 use wasm_bindgen::JsValue;
 use wasm_bindgen_test::wasm_bindgen_test;
 
-use analyzer_wasm::dto::v1::AnalyzerConfig;
+use analyzer_wasm::dto::v1::{
+    AnalyzerConfig, CompletionConfig, CompletionItemKind, PositionEncoding,
+};
 
 #[derive(Deserialize)]
 struct AnalyzeResult {
@@ -56,18 +58,48 @@ struct TextEdit {
 
 #[derive(Deserialize)]
 struct CompletionResult {
+    items: Vec<CompletionItem>,
     preferred_indices: Vec<usize>,
 }
 
+#[derive(Deserialize)]
+struct CompletionItem {
+    label: String,
+    kind: String,
+    insert_text: String,
+}
+
 #[derive(Deserialize)]
 struct HelpResult {
     completion: CompletionResult,
 }
 
+#[derive(Deserialize)]
+struct HoverResult {
+    range: Span,
+    ty: String,
+    signature: Option<SignatureItem>,
+    docs: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SignatureItem {
+    segments: Vec<DisplaySegment>,
+}
+
+#[derive(Deserialize)]
+struct DisplaySegment {
+    kind: String,
+}
+
 fn analyzer(preferred_limit: Option<usize>) -> analyzer_wasm::Analyzer {
     let config = AnalyzerConfig {
         properties: Vec::new(),
         preferred_limit,
+        position_encoding: None,
+        debug_timings: None,
+        property_lookup: None,
+        limits: None,
     };
     let config = serde_wasm_bindgen::to_value(&config).expect("expected analyzer config JsValue");
     analyzer_wasm::Analyzer::new(config).expect("expected Analyzer::new Ok")
@@ -82,11 +114,18 @@ fn analyze_value(source: &str) -> AnalyzeResult {
 
 fn format_value(source: &str, cursor_utf16: u32) -> ApplyResult {
     let value = analyzer(None)
-        .format(source.to_string(), cursor_utf16)
+        .format(source.to_string(), cursor_utf16, None)
         .expect("expected format() Ok");
     serde_wasm_bindgen::from_value(value).expect("expected ApplyResult")
 }
 
+/// JSON as a plain JS object (`to_value` turns maps into `Map`s).
+fn js_object(value: serde_json::Value) -> JsValue {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .expect("expected JS object")
+}
+
 fn edit(start: u32, end: u32, new_text: &str) -> TextEdit {
     TextEdit {
         range: Span { start, end },
@@ -144,6 +183,12 @@ fn error_message(err: JsValue) -> Option<String> {
         .and_then(|v| v.as_string())
 }
 
+fn error_code(err: &JsValue) -> Option<String> {
+    Reflect::get(err, &JsValue::from_str("code"))
+        .ok()
+        .and_then(|v| v.as_string())
+}
+
 #[wasm_bindgen_test]
 fn analyze_ascii_spans_and_output_type() {
     let source = "1+2";
@@ -256,6 +301,37 @@ fn analyze_diagnostics_include_line_col_multiline() {
     assert_eq!(diag.col, 3);
 }
 
+#[wasm_bindgen_test]
+fn analyze_batch_returns_items_in_input_order() {
+    let formulas = js_object(serde_json::json!([
+        { "id": "a", "source": "1 + 2" },
+        { "id": "b", "source": "\"😀\" +" },
+    ]));
+    let out = analyzer(None)
+        .analyze_batch(formulas)
+        .expect("expected analyze_batch() Ok");
+    let out: serde_json::Value = serde_wasm_bindgen::from_value(out).expect("BatchAnalyzeItem[]");
+
+    assert_eq!(out[0]["id"], "a");
+    assert_eq!(out[0]["output_type"], "number");
+    assert_eq!(out[0]["diagnostics"], serde_json::json!([]));
+    assert_eq!(out[1]["id"], "b");
+    let diags = out[1]["diagnostics"].as_array().expect("diagnostics");
+    assert!(!diags.is_empty());
+    // UTF-16 spans: the emoji is two code units.
+    assert!(diags[0]["span"]["start"].as_u64().expect("start") >= 5);
+    assert!(out[1].get("tokens").is_none());
+}
+
+#[wasm_bindgen_test]
+fn analyze_batch_rejects_invalid_input() {
+    let formulas = js_object(serde_json::json!([{ "id": 1, "source": "1" }]));
+    let err = analyzer(None)
+        .analyze_batch(formulas)
+        .expect_err("expected analyze_batch() Err");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_batch"));
+}
+
 #[wasm_bindgen_test]
 fn format_success_returns_source_and_cursor() {
     let source = "1+2";
@@ -273,11 +349,28 @@ fn format_rebases_mid_document_cursor_through_full_replace_edit() {
     assert_eq!(out.cursor, 0);
 }
 
+#[wasm_bindgen_test]
+fn format_applies_per_call_config() {
+    let source = "if(\n  true,\n  1,\n  2\n)";
+    let config = js_object(serde_json::json!({ "indent_width": 4, "closing_delim": "same_line" }));
+    let out = analyzer(None)
+        .format(source.to_string(), 0, Some(config))
+        .expect("expected format() Ok");
+    let out: ApplyResult = serde_wasm_bindgen::from_value(out).expect("expected ApplyResult");
+    assert_eq!(out.source, "if(\n    true,\n    1,\n    2)\n");
+
+    let config = js_object(serde_json::json!({ "quote_style": "single" }));
+    let err = analyzer(None)
+        .format(source.to_string(), 0, Some(config))
+        .expect_err("expected format() Err on unknown config field");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_format_config"));
+}
+
 #[wasm_bindgen_test]
 fn format_parse_error_returns_err() {
     let source = "1 +";
     let err = analyzer(None)
-        .format(source.to_string(), 0)
+        .format(source.to_string(), 0, None)
         .expect_err("expected format() Err");
     assert_eq!(error_message(err).as_deref(), Some("Format error"));
 }
@@ -286,7 +379,7 @@ fn format_parse_error_returns_err() {
 fn format_lex_error_returns_err() {
     let source = "1 @";
     let err = analyzer(None)
-        .format(source.to_string(), 0)
+        .format(source.to_string(), 0, None)
         .expect_err("expected format() Err");
     assert_eq!(error_message(err).as_deref(), Some("Format error"));
 }
@@ -309,9 +402,38 @@ fn apply_edits_overlapping_returns_err() {
     let err = analyzer(None)
         .apply_edits(source.to_string(), edits, 0)
         .expect_err("expected overlapping edits Err");
+    assert_eq!(error_code(&err).as_deref(), Some("overlapping_edits"));
     assert_eq!(error_message(err).as_deref(), Some("Overlapping edits"));
 }
 
+#[wasm_bindgen_test]
+fn apply_text_edits_applies_sorted_edits_and_rebases_cursor() {
+    let source = "😀ab";
+    let edits: JsValue = serde_wasm_bindgen::to_value(&vec![edit(0, 2, "x"), edit(3, 4, "YZ")])
+        .expect("edits to JsValue");
+
+    let out = analyzer(None)
+        .apply_text_edits(source.to_string(), edits, 4)
+        .expect("expected apply_text_edits() Ok");
+    let out: ApplyResult = serde_wasm_bindgen::from_value(out).expect("ApplyResult");
+
+    assert_eq!(out.source, "xaYZ");
+    assert_eq!(out.cursor, 4);
+}
+
+#[wasm_bindgen_test]
+fn apply_text_edits_rejects_unsorted_edits() {
+    let source = "abcd";
+    let edits: JsValue = serde_wasm_bindgen::to_value(&vec![edit(2, 3, "X"), edit(0, 1, "Y")])
+        .expect("edits to JsValue");
+
+    let err = analyzer(None)
+        .apply_text_edits(source.to_string(), edits, 0)
+        .expect_err("expected unsorted edits Err");
+    assert_eq!(error_code(&err).as_deref(), Some("unsorted_edits"));
+    assert_eq!(error_message(err).as_deref(), Some("Unsorted edits"));
+}
+
 #[wasm_bindgen_test]
 fn apply_edits_invalid_range_returns_err() {
     let source = "abcd";
@@ -339,12 +461,69 @@ fn apply_edits_emoji_utf16_conversion_is_correct() {
     assert_eq!(out.cursor, 2);
 }
 
+fn utf8_analyzer() -> analyzer_wasm::Analyzer {
+    let config = AnalyzerConfig {
+        properties: Vec::new(),
+        preferred_limit: None,
+        position_encoding: Some(PositionEncoding::Utf8),
+        debug_timings: None,
+        property_lookup: None,
+        limits: None,
+    };
+    let config = serde_wasm_bindgen::to_value(&config).expect("expected analyzer config JsValue");
+    analyzer_wasm::Analyzer::new(config).expect("expected Analyzer::new Ok")
+}
+
+#[wasm_bindgen_test]
+fn utf8_position_encoding_uses_byte_offsets() {
+    let out = utf8_analyzer()
+        .analyze("😀+1".to_string())
+        .expect("expected analyze() Ok");
+    let out: AnalyzeResult = serde_wasm_bindgen::from_value(out).expect("AnalyzeResult");
+    let ident = &out.tokens[0];
+    assert_eq!((ident.span.start, ident.span.end), (0, 4));
+
+    let edits: JsValue =
+        serde_wasm_bindgen::to_value(&vec![edit(4, 5, "Z")]).expect("edits to JsValue");
+    let out = utf8_analyzer()
+        .apply_edits("😀a".to_string(), edits, 4)
+        .expect("expected apply_edits() Ok");
+    let out: ApplyResult = serde_wasm_bindgen::from_value(out).expect("ApplyResult");
+    assert_eq!(out.source, "😀Z");
+    assert_eq!(out.cursor, 4);
+}
+
+#[wasm_bindgen_test]
+fn analyzer_new_rejects_unknown_position_encoding() {
+    let config = js_sys::Object::new();
+    Reflect::set(
+        &config,
+        &JsValue::from_str("position_encoding"),
+        &JsValue::from_str("utf-32"),
+    )
+    .expect("set position_encoding");
+    let err = analyzer_wasm::Analyzer::new(config.into())
+        .err()
+        .expect("expected Analyzer::new Err on unknown encoding");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_config"));
+    assert_eq!(
+        error_message(err).as_deref(),
+        Some(
+            "Invalid analyzer config: position_encoding: expected one of `utf-8`, `utf-16` or null, found \"utf-32\""
+        )
+    );
+}
+
 #[wasm_bindgen_test]
 fn analyzer_new_rejects_non_object_config() {
     let err = analyzer_wasm::Analyzer::new(JsValue::from_str("{"))
         .err()
         .expect("expected Analyzer::new Err on invalid config");
-    assert_eq!(err, "Invalid analyzer config");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_config"));
+    assert_eq!(
+        error_message(err).as_deref(),
+        Some("Invalid analyzer config: expected object, found string")
+    );
 }
 
 #[wasm_bindgen_test]
@@ -359,7 +538,13 @@ fn analyzer_new_rejects_unknown_fields() {
     let err = analyzer_wasm::Analyzer::new(config.into())
         .err()
         .expect("expected Analyzer::new Err on unknown fields");
-    assert_eq!(err, "Invalid analyzer config");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_config"));
+    assert_eq!(
+        error_message(err).as_deref(),
+        Some(
+            "Invalid analyzer config: functions: unknown field (expected one of `properties`, `preferred_limit`, `position_encoding`)"
+        )
+    );
 }
 
 #[wasm_bindgen_test]
@@ -374,7 +559,89 @@ fn analyzer_new_rejects_invalid_properties_structure() {
     let err = analyzer_wasm::Analyzer::new(config.into())
         .err()
         .expect("expected Analyzer::new Err on invalid properties");
-    assert_eq!(err, "Invalid analyzer config");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_config"));
+    assert_eq!(
+        error_message(err).as_deref(),
+        Some("Invalid analyzer config: properties: expected array, found object")
+    );
+}
+
+#[wasm_bindgen_test]
+fn analyzer_new_reports_unknown_property_type_path() {
+    let config = js_object(serde_json::json!({
+        "properties": [{ "name": "Title", "type": "String" }, { "name": "Tags", "type": { "List": "Text" } }],
+    }));
+    let err = analyzer_wasm::Analyzer::new(config)
+        .err()
+        .expect("expected Analyzer::new Err on unknown type name");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_config"));
+    assert_eq!(
+        error_message(err).as_deref(),
+        Some(
+            "Invalid analyzer config: properties[1].type.List: unknown type `Text` (expected one of Number, String, Boolean, Date, List, Unknown)"
+        )
+    );
+}
+
+#[wasm_bindgen_test]
+fn lenient_property_lookup_warns_with_a_quick_fix() {
+    let config = js_object(serde_json::json!({
+        "properties": [{ "name": "Status", "type": "String" }],
+        "property_lookup": "lenient",
+    }));
+    let analyzer = analyzer_wasm::Analyzer::new(config).expect("expected Analyzer::new Ok");
+    let value = analyzer
+        .analyze(r#"prop("status")"#.to_string())
+        .expect("expected analyze() Ok");
+    let out: AnalyzeResult = serde_wasm_bindgen::from_value(value).expect("AnalyzeResult");
+    let [diag] = out.diagnostics.as_slice() else {
+        panic!("expected one diagnostic");
+    };
+    assert_eq!(diag.kind, "warning");
+    assert_eq!(diag.actions[0].title, "Replace with `Status`");
+}
+
+#[wasm_bindgen_test]
+fn formula_limits_report_current_and_allowed_numbers() {
+    let config = js_object(serde_json::json!({
+        "limits": { "max_length": 8, "max_depth": 1 },
+    }));
+    let analyzer = analyzer_wasm::Analyzer::new(config).expect("expected Analyzer::new Ok");
+    let value = analyzer
+        .analyze("sum(abs(1), 2)".to_string())
+        .expect("expected analyze() Ok");
+    let out: AnalyzeResult = serde_wasm_bindgen::from_value(value).expect("AnalyzeResult");
+    let messages: Vec<&str> = out.diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "Formula is 14 characters long; Notion allows at most 8",
+            "Formula nests 2 levels deep; Notion allows at most 1",
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+fn validate_config_lists_issues_without_throwing() {
+    let config = js_object(serde_json::json!({
+        "properties": [{ "type": "Number" }],
+        "preferred_limit": "5",
+    }));
+    let out = analyzer_wasm::Analyzer::validate_config(config).expect("validate_config");
+    let out: serde_json::Value =
+        serde_wasm_bindgen::from_value(out).expect("ConfigValidationResult");
+    assert_eq!(
+        out,
+        serde_json::json!({ "issues": [
+            { "path": "properties[0].name", "message": "missing field" },
+            { "path": "preferred_limit", "message": "expected non-negative integer or null, found string" },
+        ] })
+    );
+
+    let ok = analyzer_wasm::Analyzer::validate_config(js_sys::Object::new().into())
+        .expect("validate_config");
+    let ok: serde_json::Value = serde_wasm_bindgen::from_value(ok).expect("ConfigValidationResult");
+    assert_eq!(ok, serde_json::json!({ "issues": [] }));
 }
 
 #[wasm_bindgen_test]
@@ -382,12 +649,12 @@ fn analyzer_config_nullable_preferred_limit_defaults_to_five() {
     let source = "if(";
 
     let out_null = analyzer(None)
-        .help(source.to_string(), 3)
+        .help(source.to_string(), 3, None)
         .expect("expected help() Ok");
     let out_null: HelpResult = serde_wasm_bindgen::from_value(out_null).expect("HelpResult");
 
     let out_five = analyzer(Some(5))
-        .help(source.to_string(), 3)
+        .help(source.to_string(), 3, None)
         .expect("expected help() Ok");
     let out_five: HelpResult = serde_wasm_bindgen::from_value(out_five).expect("HelpResult");
 
@@ -400,9 +667,503 @@ fn analyzer_config_nullable_preferred_limit_defaults_to_five() {
 #[wasm_bindgen_test]
 fn analyzer_config_zero_preferred_limit_disables_preferred_indices() {
     let out = analyzer(Some(0))
-        .help("if(".to_string(), 3)
+        .help("if(".to_string(), 3, None)
         .expect("expected help() Ok");
     let out: HelpResult = serde_wasm_bindgen::from_value(out).expect("HelpResult");
 
     assert!(out.completion.preferred_indices.is_empty());
 }
+
+#[wasm_bindgen_test]
+fn hover_returns_utf16_range_type_and_signature() {
+    let source = "\"😀\" + if(true, 1, 2)";
+    let out = analyzer(None)
+        .hover(source.to_string(), 8)
+        .expect("expected hover() Ok");
+    let out: HoverResult = serde_wasm_bindgen::from_value(out).expect("HoverResult");
+
+    assert_eq!((out.range.start, out.range.end), (7, 9));
+    assert_eq!(out.ty, "number");
+    let signature = out.signature.expect("expected signature");
+    assert_eq!(signature.segments[0].kind, "Name");
+    assert!(out.docs.is_some());
+}
+
+#[wasm_bindgen_test]
+fn hover_off_expression_returns_null() {
+    let out = analyzer(None)
+        .hover("1 +   2".to_string(), 4)
+        .expect("expected hover() Ok");
+    assert!(out.is_null());
+}
+
+#[wasm_bindgen_test]
+fn ast_json_returns_tagged_nodes_with_utf16_spans() {
+    let out = analyzer(None)
+        .ast_json("\"😀\" + 1".to_string())
+        .expect("expected ast_json() Ok");
+    let out: serde_json::Value = serde_wasm_bindgen::from_value(out).expect("AstResult");
+
+    assert_eq!(out["diagnostics"], serde_json::json!([]));
+    let root = &out["root"];
+    assert_eq!(root["kind"], "Binary");
+    assert_eq!(root["op"], "+");
+    assert_eq!(root["left"]["value"], "😀");
+    assert_eq!(
+        root["right"]["span"],
+        serde_json::json!({ "start": 7, "end": 8 })
+    );
+}
+
+#[wasm_bindgen_test]
+fn referenced_properties_dedupes_names_with_utf16_spans() {
+    let out = analyzer(None)
+        .referenced_properties("\"😀\" + prop(\"A\") + prop(\"B\") + prop(\"A\")".to_string())
+        .expect("expected referenced_properties() Ok");
+    let out: serde_json::Value = serde_wasm_bindgen::from_value(out).expect("PropertyReference[]");
+
+    assert_eq!(
+        out,
+        serde_json::json!([
+            { "name": "A", "spans": [{ "start": 12, "end": 15 }, { "start": 36, "end": 39 }] },
+            { "name": "B", "spans": [{ "start": 24, "end": 27 }] },
+        ])
+    );
+}
+
+#[wasm_bindgen_test]
+fn signature_help_matches_help_and_is_null_outside_calls() {
+    let a = analyzer(None);
+    let source = "\"😀\" + if(true, ";
+
+    let help: serde_json::Value =
+        serde_wasm_bindgen::from_value(a.help(source.to_string(), 15, None).expect("help() Ok"))
+            .expect("HelpResult");
+    let out: serde_json::Value = serde_wasm_bindgen::from_value(
+        a.signature_help(source.to_string(), 15)
+            .expect("expected signature_help() Ok"),
+    )
+    .expect("SignatureHelp");
+    assert_eq!(out["active_parameter"], 1);
+    assert_eq!(out, help["signature_help"]);
+
+    let out = a
+        .signature_help(source.to_string(), 0)
+        .expect("expected signature_help() Ok");
+    assert!(out.is_null());
+}
+
+#[wasm_bindgen_test]
+fn debug_timings_attach_phase_timings_to_analyze_and_help() {
+    let plain: serde_json::Value =
+        serde_wasm_bindgen::from_value(analyzer(None).analyze("1 + 2".to_string()).expect("Ok"))
+            .expect("AnalyzeResult");
+    assert!(plain.get("timings").is_none_or(|t| t.is_null()));
+
+    let config = js_object(serde_json::json!({ "debug_timings": true }));
+    let a = analyzer_wasm::Analyzer::new(config).expect("expected Analyzer::new Ok");
+
+    let out: serde_json::Value =
+        serde_wasm_bindgen::from_value(a.analyze("1 + 2".to_string()).expect("Ok"))
+            .expect("AnalyzeResult");
+    let timings = &out["timings"];
+    for phase in ["lex", "parse", "infer", "complete"] {
+        assert!(timings[phase].as_f64().is_some_and(|t| t >= 0.0), "{phase}");
+    }
+    assert_eq!(timings["complete"], 0.0);
+
+    let out: serde_json::Value =
+        serde_wasm_bindgen::from_value(a.help("su".to_string(), 2, None).expect("Ok"))
+            .expect("HelpResult");
+    assert!(out["timings"]["complete"].as_f64().is_some());
+    assert_eq!(out["timings"]["infer"], 0.0);
+}
+
+#[derive(Deserialize)]
+struct SemanticTokensLegend {
+    token_types: Vec<String>,
+    token_modifiers: Vec<String>,
+}
+
+#[wasm_bindgen_test]
+fn semantic_tokens_are_lsp_encoded_with_utf16_columns() {
+    let legend = analyzer_wasm::Analyzer::semantic_token_legend().expect("expected legend");
+    let legend: SemanticTokensLegend =
+        serde_wasm_bindgen::from_value(legend).expect("SemanticTokensLegend");
+    let ty = |name: &str| {
+        legend
+            .token_types
+            .iter()
+            .position(|t| t == name)
+            .expect("token type in legend") as u32
+    };
+    assert_eq!(legend.token_modifiers, vec!["defaultLibrary".to_string()]);
+
+    let data = analyzer(None).semantic_tokens("\"😀\" + abs(1)".to_string());
+    #[rustfmt::skip]
+    let expected = vec![
+        0, 0, 4, ty("string"), 0,
+        0, 5, 1, ty("operator"), 0,
+        0, 2, 3, ty("function"), 1,
+        0, 4, 1, ty("number"), 0,
+    ];
+    assert_eq!(data, expected);
+}
+
+#[derive(Deserialize)]
+struct TypeAtResult {
+    range: Span,
+    ty: String,
+}
+
+#[wasm_bindgen_test]
+fn type_at_returns_utf16_range_and_type() {
+    let out = analyzer(None)
+        .type_at("\"😀\" + \"a\"".to_string(), 8)
+        .expect("expected type_at() Ok");
+    let out: TypeAtResult = serde_wasm_bindgen::from_value(out).expect("TypeAtResult");
+
+    assert_eq!((out.range.start, out.range.end), (7, 10));
+    assert_eq!(out.ty, "string");
+}
+
+#[derive(Deserialize)]
+struct CompletionReplace {
+    replace: Span,
+    query: Option<String>,
+}
+
+#[wasm_bindgen_test]
+fn completion_replace_span_returns_utf16_range_and_query() {
+    let out = analyzer(None)
+        .completion_replace_span("\"😀\" + form".to_string(), 11)
+        .expect("expected completion_replace_span() Ok");
+    let out: CompletionReplace = serde_wasm_bindgen::from_value(out).expect("CompletionReplace");
+
+    assert_eq!((out.replace.start, out.replace.end), (7, 11));
+    assert_eq!(out.query.as_deref(), Some("form"));
+}
+
+#[wasm_bindgen_test]
+fn session_queries_reflect_incremental_edits() {
+    let mut session = analyzer(None).session("1 + ".to_string());
+    let diagnostics = session.diagnostics().expect("expected diagnostics() Ok");
+    let diagnostics: Vec<Diagnostic> =
+        serde_wasm_bindgen::from_value(diagnostics).expect("Vec<Diagnostic>");
+    assert!(!diagnostics.is_empty());
+
+    let edits: JsValue =
+        serde_wasm_bindgen::to_value(&vec![edit(4, 4, "\"😀\"")]).expect("edits to JsValue");
+    session
+        .update_text(edits)
+        .expect("expected update_text() Ok");
+    assert_eq!(session.text(), "1 + \"😀\"");
+
+    let out = session.analyze().expect("expected analyze() Ok");
+    let out: AnalyzeResult = serde_wasm_bindgen::from_value(out).expect("AnalyzeResult");
+    let string = out
+        .tokens
+        .iter()
+        .find(|t| t.kind == "String")
+        .expect("string token");
+    assert_eq!((string.span.start, string.span.end), (4, 8));
+
+    let out = session.type_at(5).expect("expected type_at() Ok");
+    let out: TypeAtResult = serde_wasm_bindgen::from_value(out).expect("TypeAtResult");
+    assert_eq!((out.range.start, out.range.end), (4, 8));
+}
+
+#[wasm_bindgen_test]
+fn session_preview_reports_diagnostics_without_applying_edits() {
+    let session = analyzer(None).session("\"😀\" + 1".to_string());
+    let edits: JsValue =
+        serde_wasm_bindgen::to_value(&vec![edit(6, 8, "")]).expect("edits to JsValue");
+    let out = session.preview(edits).expect("expected preview() Ok");
+    let diagnostics: Vec<Diagnostic> =
+        serde_wasm_bindgen::from_value(out).expect("Vec<Diagnostic>");
+
+    assert_eq!(diagnostics[0].message, "expected expression after `+`");
+    assert_eq!((diagnostics[0].span.start, diagnostics[0].span.end), (5, 6));
+    assert_eq!(diagnostics[0].col, 5);
+    assert_eq!(session.text(), "\"😀\" + 1");
+    let current = session.diagnostics().expect("expected diagnostics() Ok");
+    let current: Vec<Diagnostic> =
+        serde_wasm_bindgen::from_value(current).expect("Vec<Diagnostic>");
+    assert!(current.is_empty());
+}
+
+#[wasm_bindgen_test]
+fn session_update_text_rejects_invalid_edits_and_keeps_text() {
+    let mut session = analyzer(None).session("😀a".to_string());
+    let edits: JsValue =
+        serde_wasm_bindgen::to_value(&vec![edit(1, 2, "")]).expect("edits to JsValue");
+    let err = session
+        .update_text(edits)
+        .expect_err("expected mid-surrogate edit Err");
+    assert_eq!(
+        error_message(err).as_deref(),
+        Some("Edit not on char boundary")
+    );
+    assert_eq!(session.text(), "😀a");
+}
+
+#[derive(Deserialize)]
+struct ParamInfo {
+    name: String,
+    ty: String,
+}
+
+#[derive(Deserialize)]
+struct ParamShapeInfo {
+    head: Vec<ParamInfo>,
+}
+
+#[derive(Deserialize)]
+struct BuiltinFunction {
+    name: String,
+    category: String,
+    params: ParamShapeInfo,
+    ret: String,
+    postfix: bool,
+}
+
+#[wasm_bindgen_test]
+fn properties_from_notion_schema_maps_types_in_name_order() {
+    let schema = js_object(serde_json::json!({
+        "Tags": {
+            "id": "t",
+            "type": "multi_select",
+            "multi_select": { "options": [{ "id": "u", "name": "Urgent", "color": "red" }] },
+        },
+        "Due": { "id": "d", "type": "date", "date": {} },
+        "Owner": { "id": "o", "type": "people", "people": {} },
+        "Total": { "id": "s", "type": "rollup", "rollup": { "function": "sum" } },
+    }));
+    let out = analyzer_wasm::Analyzer::properties_from_notion_schema(schema)
+        .expect("expected properties_from_notion_schema() Ok");
+    let out: serde_json::Value = serde_wasm_bindgen::from_value(out).expect("Property[]");
+
+    assert_eq!(
+        out,
+        serde_json::json!([
+            { "name": "Due", "type": "Date", "options": null },
+            { "name": "Owner", "type": { "List": "Unknown" }, "options": null },
+            { "name": "Tags", "type": { "List": "String" }, "options": ["Urgent"] },
+            { "name": "Total", "type": "Number", "options": null },
+        ])
+    );
+
+    let err = analyzer_wasm::Analyzer::properties_from_notion_schema(js_object(
+        serde_json::json!({ "Name": { "id": "n" } }),
+    ))
+    .expect_err("expected properties_from_notion_schema() Err without `type`");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_notion_schema"));
+}
+
+#[wasm_bindgen_test]
+fn notion_expressions_convert_through_property_ids() {
+    let schema = || {
+        js_object(serde_json::json!({
+            "Price": { "id": "%3AUPp", "type": "number" },
+        }))
+    };
+    let source = analyzer_wasm::Analyzer::from_notion_expression(
+        "{{notion:block_property:%3AUPp:0000:1b2c}} * 2".into(),
+        schema(),
+    )
+    .expect("expected from_notion_expression() Ok");
+    assert_eq!(source, r#"prop("Price") * 2"#);
+
+    let expression = analyzer_wasm::Analyzer::to_notion_expression(source, schema())
+        .expect("expected to_notion_expression() Ok");
+    assert_eq!(expression, "{{notion:block_property:%3AUPp}} * 2");
+
+    let err = analyzer_wasm::Analyzer::to_notion_expression(r#"prop("Tax")"#.into(), schema())
+        .expect_err("expected to_notion_expression() Err on unknown property");
+    assert_eq!(
+        error_code(&err).as_deref(),
+        Some("invalid_notion_expression")
+    );
+}
+
+#[wasm_bindgen_test]
+fn builtin_catalog_lists_signatures_and_postfix_capability() {
+    let catalog =
+        analyzer_wasm::Analyzer::builtin_catalog().expect("expected builtin_catalog() Ok");
+    let catalog: Vec<BuiltinFunction> =
+        serde_wasm_bindgen::from_value(catalog).expect("Vec<BuiltinFunction>");
+    let find = |name: &str| {
+        catalog
+            .iter()
+            .find(|f| f.name == name)
+            .expect("builtin in catalog")
+    };
+
+    let contains = find("contains");
+    assert_eq!(contains.category, "Text");
+    assert_eq!(contains.ret, "boolean");
+    let params: Vec<(&str, &str)> = contains
+        .params
+        .head
+        .iter()
+        .map(|p| (p.name.as_str(), p.ty.as_str()))
+        .collect();
+    assert_eq!(params, vec![("text", "string"), ("search", "string")]);
+    assert!(contains.postfix);
+
+    assert!(!find("abs").postfix);
+}
+
+#[wasm_bindgen_test]
+fn help_applies_per_call_completion_config() {
+    let config = CompletionConfig {
+        call_parens: Some(false),
+        exclude_kinds: Some(vec![CompletionItemKind::Property]),
+        ..CompletionConfig::default()
+    };
+    let config = serde_wasm_bindgen::to_value(&config).expect("config to JsValue");
+
+    let out = analyzer(None)
+        .help("ab".to_string(), 2, Some(config))
+        .expect("expected help() Ok");
+    let out: HelpResult = serde_wasm_bindgen::from_value(out).expect("HelpResult");
+
+    let abs = out
+        .completion
+        .items
+        .iter()
+        .find(|item| item.label == "abs()")
+        .expect("abs() item");
+    assert_eq!(abs.insert_text, "abs");
+    assert!(
+        out.completion
+            .items
+            .iter()
+            .all(|item| item.kind != "Property")
+    );
+}
+
+#[wasm_bindgen_test]
+fn help_rejects_invalid_completion_config() {
+    let config = js_sys::Object::new();
+    Reflect::set(&config, &JsValue::from_str("snippets"), &JsValue::TRUE).expect("set snippets");
+
+    let err = analyzer(None)
+        .help("ab".to_string(), 2, Some(config.into()))
+        .expect_err("expected invalid config Err");
+    assert_eq!(
+        error_code(&err).as_deref(),
+        Some("invalid_completion_config")
+    );
+    assert_eq!(
+        error_message(err).as_deref(),
+        Some("Invalid completion config")
+    );
+}
+
+#[wasm_bindgen_test]
+fn errors_are_named_analyzer_errors_with_codes() {
+    let err = analyzer(None)
+        .format("1 +".to_string(), 0, None)
+        .expect_err("expected format Err");
+    let name = Reflect::get(&err, &JsValue::from_str("name"))
+        .ok()
+        .and_then(|v| v.as_string());
+    assert_eq!(name.as_deref(), Some("AnalyzerError"));
+    assert_eq!(error_code(&err).as_deref(), Some("format_error"));
+
+    let err = analyzer(None)
+        .apply_edits("abc".to_string(), JsValue::from_str("nope"), 0)
+        .expect_err("expected invalid edits Err");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_edits"));
+}
+
+#[wasm_bindgen_test]
+fn init_is_idempotent_and_no_internal_error_without_panic() {
+    analyzer_wasm::init();
+    analyzer_wasm::init();
+    analyze_value("1 + 2");
+    let last = analyzer_wasm::take_internal_error().expect("expected take_internal_error() Ok");
+    assert!(last.is_null());
+}
+
+#[wasm_bindgen_test]
+fn codemirror_returns_lint_completion_and_decorations() {
+    let out = analyzer(None)
+        .codemirror("sum(1, ".to_string(), 7, None)
+        .expect("expected codemirror() Ok");
+    let out: serde_json::Value = serde_wasm_bindgen::from_value(out).expect("CodeMirrorResult");
+
+    assert_eq!(out["diagnostics"][0]["severity"], "error");
+    assert!(
+        out["completion"]["options"]
+            .as_array()
+            .is_some_and(|o| !o.is_empty())
+    );
+    assert_eq!(
+        out["decorations"][0]["class"],
+        "nf-function nf-defaultLibrary"
+    );
+}
+
+#[wasm_bindgen_test]
+fn monaco_returns_markers_suggestions_and_signature_help() {
+    let out = analyzer(None)
+        .monaco("\"😀\" + sum(".to_string(), 11, None)
+        .expect("expected monaco() Ok");
+    let out: serde_json::Value = serde_wasm_bindgen::from_value(out).expect("MonacoResult");
+
+    assert_eq!(out["markers"][0]["severity"], 8);
+    assert_eq!(out["markers"][0]["startLineNumber"], 1);
+    assert!(out["suggestions"].as_array().is_some_and(|s| !s.is_empty()));
+    assert!(out["suggestions"][0]["sortText"].is_string());
+    assert!(
+        out["signature_help"]["signatures"][0]["label"]
+            .as_str()
+            .is_some_and(|l| l.starts_with("sum("))
+    );
+}
+
+#[wasm_bindgen_test]
+fn rename_edits_every_property_reference() {
+    let source = "\"😀\" + prop(\"A\") + prop(\"A\")";
+    let out = analyzer(None)
+        .rename(source.to_string(), 12, "B".to_string())
+        .expect("expected rename() Ok");
+    let edits: Vec<TextEdit> = serde_wasm_bindgen::from_value(out).expect("TextEdit[]");
+    assert_eq!(edits.len(), 2);
+    assert_eq!((edits[0].range.start, edits[0].range.end), (12, 15));
+    assert_eq!(edits[0].new_text, "\"B\"");
+
+    let out = analyzer(None)
+        .rename(source.to_string(), 0, "B".to_string())
+        .expect("expected rename() Ok");
+    assert!(out.is_null());
+
+    let err = analyzer(None)
+        .rename(source.to_string(), 12, String::new())
+        .expect_err("expected rename() Err on empty name");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_rename"));
+}
+
+#[wasm_bindgen_test]
+fn code_actions_return_quick_fixes_in_range() {
+    let range = serde_wasm_bindgen::to_value(&Span { start: 4, end: 4 }).expect("range");
+    let out = analyzer(None)
+        .code_actions("f(1 2)".to_string(), range)
+        .expect("expected code_actions() Ok");
+    let actions: serde_json::Value = serde_wasm_bindgen::from_value(out).expect("CodeAction[]");
+    assert!(
+        actions
+            .as_array()
+            .expect("array")
+            .iter()
+            .any(|action| action["title"] == "Insert `,`")
+    );
+
+    let reversed = serde_wasm_bindgen::to_value(&Span { start: 3, end: 1 }).expect("range");
+    let err = analyzer(None)
+        .code_actions("f(1 2)".to_string(), reversed)
+        .expect_err("expected code_actions() Err on reversed range");
+    assert_eq!(error_code(&err).as_deref(), Some("invalid_range"));
+}
@@ -0,0 +1,21 @@
+//! Criterion benchmarks for every (fixture, phase) case in `notion-formula-bench`.
+//!
+//! `cargo bench --manifest-path bench/criterion/Cargo.toml [-- <fixture/phase filter>]`
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use notion_formula_bench::{Case, PHASES, fixtures};
+
+fn phases(c: &mut Criterion) {
+    let fixtures = fixtures();
+    for phase in PHASES {
+        let mut group = c.benchmark_group(phase.name());
+        for fixture in &fixtures {
+            let case = Case::new(fixture, phase);
+            group.bench_function(fixture.name, |b| b.iter(|| case.run()));
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, phases);
+criterion_main!(benches);
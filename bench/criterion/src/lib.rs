@@ -0,0 +1 @@
+//! Empty; the benchmarks live in `benches/`.
@@ -0,0 +1,260 @@
+//! Benchmark fixtures, phases, and latency budgets for the IDE hot path.
+//!
+//! Every (fixture, phase) pair is a [`Case`]. The criterion benches in `criterion/` time the
+//! cases; [`check_budgets`] times them with a plain median and compares against [`budget`], so a
+//! slowdown fails `cargo test --release -p notion-formula-bench` instead of reaching users.
+
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use analyzer::semantic::{Context, Property, Ty};
+use analyzer::{Token, TypeMap};
+use ide::CompletionConfig;
+
+/// A formula plus the cursor used by the `complete` and `signature` phases.
+pub struct Fixture {
+    pub name: &'static str,
+    pub source: String,
+    /// Byte offset inside a call argument list.
+    pub cursor: usize,
+}
+
+/// Measured work, in pipeline order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// `analyzer::lex`.
+    Lex,
+    /// `analyzer::parse_tokens` on pre-lexed tokens (includes cloning them).
+    Parse,
+    /// Type inference on a pre-parsed AST.
+    Infer,
+    /// `ide::help` from source: lex, parse, infer, and completion.
+    Complete,
+    /// `ide::signature_help` from source.
+    Signature,
+}
+
+pub const PHASES: [Phase; 5] = [
+    Phase::Lex,
+    Phase::Parse,
+    Phase::Infer,
+    Phase::Complete,
+    Phase::Signature,
+];
+
+impl Phase {
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Lex => "lex",
+            Phase::Parse => "parse",
+            Phase::Infer => "infer",
+            Phase::Complete => "complete",
+            Phase::Signature => "signature",
+        }
+    }
+}
+
+/// Properties referenced by the fixtures.
+pub static CONTEXT: LazyLock<Context> = LazyLock::new(|| {
+    let property = |name: &str, ty: Ty| Property {
+        name: name.to_string(),
+        ty,
+        disabled_reason: None,
+        options: Vec::new(),
+    };
+    Context::with_builtins(vec![
+        property("Title", Ty::String),
+        property("Price", Ty::Number),
+        property("Done", Ty::Boolean),
+        property("Due", Ty::Date),
+        property("Done Count", Ty::Number),
+        property("Total Count", Ty::Number),
+        property("Tags", Ty::List(Box::new(Ty::String))),
+        property("Owner", Ty::String),
+    ])
+});
+
+/// Call depth of the `nested_calls` fixture.
+const NESTING: usize = 64;
+/// Operand count of the `long_chain` fixture.
+const CHAIN_LEN: usize = 256;
+/// Unclosed calls in the `unclosed` fixture.
+const UNCLOSED: usize = 64;
+
+pub fn fixtures() -> Vec<Fixture> {
+    let small = r#"if(prop("Price") > 10, "high", "low")"#.to_string();
+    let small_cursor = small.find(r#""high""#).unwrap();
+
+    let medium = include_str!("fixtures/medium.formula").to_string();
+    let medium_cursor = medium.find(r#""🗓 ""#).unwrap();
+
+    // if(true, if(true, ... 1, 0), 0)
+    let nested = format!(
+        "{}prop(\"Price\"){}",
+        "if(true, ".repeat(NESTING),
+        ", 0)".repeat(NESTING)
+    );
+    let nested_cursor = nested.find("prop").unwrap();
+
+    // sum(prop("Price") + 1 * 2 - 3 / 4 + ...)
+    let chain = format!(
+        "sum({})",
+        (0..CHAIN_LEN)
+            .map(|i| match i % 4 {
+                0 => "prop(\"Price\")".to_string(),
+                _ => i.to_string(),
+            })
+            .zip(["+", "*", "-", "/"].into_iter().cycle())
+            .map(|(operand, op)| format!("{operand} {op} "))
+            .collect::<String>()
+            + "0"
+    );
+    let chain_cursor = "sum(".len();
+
+    // sum(1, sum(1, sum(1, ... with no closing parens
+    let unclosed = "sum(1, ".repeat(UNCLOSED);
+    let unclosed_cursor = unclosed.len();
+
+    vec![
+        Fixture {
+            name: "small",
+            source: small,
+            cursor: small_cursor,
+        },
+        Fixture {
+            name: "medium",
+            source: medium,
+            cursor: medium_cursor,
+        },
+        Fixture {
+            name: "nested_calls",
+            source: nested,
+            cursor: nested_cursor,
+        },
+        Fixture {
+            name: "long_chain",
+            source: chain,
+            cursor: chain_cursor,
+        },
+        Fixture {
+            name: "unclosed",
+            source: unclosed,
+            cursor: unclosed_cursor,
+        },
+    ]
+}
+
+/// Upper bound for the median run of `phase` on `fixture`, in release builds.
+///
+/// Budgets sit well above typical timings so that machine noise does not fail them; a breach
+/// means a real regression (e.g. accidentally quadratic work), not a slow CI runner.
+pub fn budget(fixture: &str, phase: Phase) -> Duration {
+    let micros = match (fixture, phase) {
+        ("small", Phase::Lex | Phase::Parse | Phase::Infer) => 50,
+        ("small", Phase::Complete | Phase::Signature) => 500,
+        ("medium", Phase::Lex | Phase::Parse | Phase::Infer) => 500,
+        ("medium", Phase::Complete | Phase::Signature) => 2_000,
+        (_, Phase::Lex | Phase::Parse | Phase::Infer) => 2_000,
+        (_, Phase::Complete | Phase::Signature) => 5_000,
+    };
+    Duration::from_micros(micros)
+}
+
+/// One (fixture, phase) pair with its inputs prepared up front.
+pub struct Case<'a> {
+    pub fixture: &'a Fixture,
+    pub phase: Phase,
+    tokens: Vec<Token>,
+    syntax: analyzer::SyntaxResult,
+}
+
+impl<'a> Case<'a> {
+    pub fn new(fixture: &'a Fixture, phase: Phase) -> Self {
+        let syntax = analyzer::analyze_syntax(&fixture.source);
+        Self {
+            fixture,
+            phase,
+            tokens: syntax.tokens.clone(),
+            syntax,
+        }
+    }
+
+    /// `fixture/phase`.
+    pub fn id(&self) -> String {
+        format!("{}/{}", self.fixture.name, self.phase.name())
+    }
+
+    /// Runs the phase once; the result is returned so it is not optimized away.
+    pub fn run(&self) -> usize {
+        let source = self.fixture.source.as_str();
+        match self.phase {
+            Phase::Lex => analyzer::lex(source).tokens.len(),
+            Phase::Parse => analyzer::parse_tokens(source, self.tokens.clone())
+                .diagnostics
+                .len(),
+            Phase::Infer => {
+                let mut types = TypeMap::default();
+                analyzer::semantic::analyze_expr_with_map(&self.syntax.expr, &CONTEXT, &mut types)
+                    .1
+                    .len()
+            }
+            Phase::Complete => ide::help(
+                source,
+                self.fixture.cursor,
+                &CONTEXT,
+                CompletionConfig::default(),
+            )
+            .completion
+            .items
+            .len(),
+            Phase::Signature => ide::signature_help(source, self.fixture.cursor, &CONTEXT)
+                .map_or(0, |help| help.signatures.len()),
+        }
+    }
+
+    /// Median of `samples` timed runs, after a few warm-up runs.
+    pub fn median(&self, samples: usize) -> Duration {
+        for _ in 0..3 {
+            std::hint::black_box(self.run());
+        }
+        let mut times: Vec<Duration> = (0..samples.max(1))
+            .map(|_| {
+                let start = Instant::now();
+                std::hint::black_box(self.run());
+                start.elapsed()
+            })
+            .collect();
+        times.sort();
+        times[times.len() / 2]
+    }
+}
+
+/// One measured case.
+pub struct Measurement {
+    pub id: String,
+    pub median: Duration,
+    pub budget: Duration,
+}
+
+impl Measurement {
+    pub fn within_budget(&self) -> bool {
+        self.median <= self.budget
+    }
+}
+
+/// Measures every (fixture, phase) pair against its [`budget`].
+pub fn check_budgets(samples: usize) -> Vec<Measurement> {
+    let fixtures = fixtures();
+    let mut out = Vec::new();
+    for fixture in &fixtures {
+        for phase in PHASES {
+            let case = Case::new(fixture, phase);
+            out.push(Measurement {
+                id: case.id(),
+                median: case.median(samples),
+                budget: budget(fixture.name, phase),
+            });
+        }
+    }
+    out
+}
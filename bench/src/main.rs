@@ -0,0 +1,33 @@
+//! Prints the median time of every benchmark case next to its budget.
+//!
+//! Usage: `cargo run --release -p notion-formula-bench [SAMPLES]`. Exits `1` if a case is over
+//! budget.
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let samples = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(51);
+    if cfg!(debug_assertions) {
+        eprintln!("warning: budgets apply to release builds (`--release`)");
+    }
+
+    let measurements = notion_formula_bench::check_budgets(samples);
+    let width = measurements.iter().map(|m| m.id.len()).max().unwrap_or(0);
+    let mut over = 0;
+    for m in &measurements {
+        let mark = if m.within_budget() { "ok" } else { "OVER" };
+        println!(
+            "{:width$}  {:>10.1?}  budget {:>8?}  {mark}",
+            m.id, m.median, m.budget
+        );
+        over += usize::from(!m.within_budget());
+    }
+    if over > 0 {
+        eprintln!("{over} of {} cases over budget", measurements.len());
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,40 @@
+use notion_formula_bench::{CONTEXT, Case, PHASES, Phase, check_budgets, fixtures};
+
+#[test]
+fn fixtures_exercise_the_intended_paths() {
+    for fixture in fixtures() {
+        let diagnostics = analyzer::analyze(&fixture.source, &CONTEXT).diagnostics;
+        if fixture.name == "unclosed" {
+            assert!(!diagnostics.is_empty());
+        } else {
+            assert!(diagnostics.is_empty(), "{}: {diagnostics:?}", fixture.name);
+        }
+        // The cursor sits in an argument list, so signature help and typed completion run.
+        assert!(
+            ide::signature_help(&fixture.source, fixture.cursor, &CONTEXT).is_some(),
+            "{}: no signature help at the cursor",
+            fixture.name
+        );
+        for phase in PHASES {
+            Case::new(&fixture, phase).run();
+        }
+    }
+
+    let medium = fixtures().into_iter().find(|f| f.name == "medium").unwrap();
+    assert!((1000..1500).contains(&medium.source.len()));
+    assert!(Case::new(&medium, Phase::Complete).run() > 0);
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    ignore = "budgets apply to release builds: cargo test --release -p notion-formula-bench"
+)]
+fn phases_stay_within_latency_budgets() {
+    let over: Vec<String> = check_budgets(51)
+        .into_iter()
+        .filter(|m| !m.within_budget())
+        .map(|m| format!("{}: {:?} > {:?}", m.id, m.median, m.budget))
+        .collect();
+    assert!(over.is_empty(), "over budget:\n{}", over.join("\n"));
+}
@@ -0,0 +1,112 @@
+//! Minimal argument parsing: long flags, `--name value` / `--name=value` options, positionals.
+
+/// Parsed command arguments.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub flags: Vec<String>,
+    pub options: Vec<(String, String)>,
+    pub positionals: Vec<String>,
+}
+
+impl Args {
+    /// Parses `args`; `flags` and `options` list the accepted names (without `--`).
+    ///
+    /// `-` is a positional (stdin); everything after `--` is positional.
+    pub fn parse(args: &[String], flags: &[&str], options: &[&str]) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--" {
+                parsed.positionals.extend(iter.by_ref().cloned());
+                break;
+            }
+            let Some(name) = arg.strip_prefix("--") else {
+                if arg.starts_with('-') && arg != "-" && arg != "-h" {
+                    return Err(format!("unknown option `{arg}`"));
+                }
+                if arg == "-h" {
+                    parsed.flags.push("help".into());
+                } else {
+                    parsed.positionals.push(arg.clone());
+                }
+                continue;
+            };
+            let (name, inline) = match name.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (name, None),
+            };
+            if options.contains(&name) {
+                let value = match inline {
+                    Some(value) => value,
+                    None => iter
+                        .next()
+                        .cloned()
+                        .ok_or_else(|| format!("`--{name}` needs a value"))?,
+                };
+                parsed.options.push((name.to_string(), value));
+            } else if flags.contains(&name) && inline.is_none() {
+                parsed.flags.push(name.to_string());
+            } else {
+                return Err(format!("unknown option `--{name}`"));
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    /// The last value given for option `name`.
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Args;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_flags_options_and_positionals() {
+        let args = Args::parse(
+            &strings(&[
+                "a.formula",
+                "--context",
+                "c.json",
+                "-",
+                "--help",
+                "--context=d.json",
+            ]),
+            &["help"],
+            &["context"],
+        )
+        .unwrap();
+        assert!(args.flag("help"));
+        assert_eq!(args.option("context"), Some("d.json"));
+        assert_eq!(args.positionals, ["a.formula", "-"]);
+
+        let args = Args::parse(&strings(&["--", "--context"]), &[], &["context"]).unwrap();
+        assert_eq!(args.positionals, ["--context"]);
+    }
+
+    #[test]
+    fn rejects_unknown_options_and_missing_values() {
+        let parse = |args: &[&str]| Args::parse(&strings(args), &["help"], &["context"]);
+        assert_eq!(parse(&["--nope"]).unwrap_err(), "unknown option `--nope`");
+        assert_eq!(parse(&["-x"]).unwrap_err(), "unknown option `-x`");
+        assert_eq!(parse(&["--help=1"]).unwrap_err(), "unknown option `--help`");
+        assert_eq!(
+            parse(&["--context"]).unwrap_err(),
+            "`--context` needs a value"
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! `notion-formula check`: print diagnostics; exit 1 if there are any.
+
+use std::io::{Read, Write};
+
+use crate::args::Args;
+use crate::{EXIT_FAILURE, EXIT_OK, context, input};
+
+const USAGE: &str = "\
+Usage: notion-formula check [OPTIONS] [FILE]...
+
+Prints diagnostics for each formula and exits 1 if there are any.
+Reads stdin when no FILE is given or FILE is `-`.
+
+Options:
+  --context <PATH>  JSON file: { \"properties\": [{ \"name\": ..., \"type\": ... }] }
+  -h, --help        Print this help
+";
+
+pub fn run(
+    args: &[String],
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> Result<i32, String> {
+    let args = Args::parse(args, &["help"], &["context"])?;
+    if args.flag("help") {
+        let _ = write!(stdout, "{USAGE}");
+        return Ok(EXIT_OK);
+    }
+    let ctx = context::load(args.option("context"))?;
+    let inputs = input::read_all(&args.positionals, stdin)?;
+
+    let mut errors = 0;
+    let mut failed_files = 0;
+    for input in &inputs {
+        let diagnostics = analyzer::analyze(&input.source, &ctx).diagnostics;
+        if diagnostics.is_empty() {
+            continue;
+        }
+        errors += diagnostics.len();
+        failed_files += 1;
+        let rendered = analyzer::format_diagnostics_for(input.name(), &input.source, diagnostics);
+        let _ = write!(stdout, "{rendered}");
+    }
+
+    if errors == 0 {
+        return Ok(EXIT_OK);
+    }
+    let _ = writeln!(
+        stderr,
+        "{} in {}",
+        plural(errors, "error"),
+        plural(failed_files, "file")
+    );
+    Ok(EXIT_FAILURE)
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
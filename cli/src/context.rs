@@ -0,0 +1,45 @@
+//! `--context` files: a serialized [`Context`], in the JSON form `Context` itself reads and writes.
+//!
+//! ```json
+//! { "version": 1, "properties": [{ "name": "Price", "type": "Number" }] }
+//! ```
+//!
+//! A file without `functions` gets the builtins. Without `--context`, no properties are defined.
+
+use analyzer::semantic::{Context, ContextError, builtins_functions};
+
+/// Loads the context at `path`, or the builtins-only context for `None`.
+pub fn load(path: Option<&str>) -> Result<Context, String> {
+    let Some(path) = path else {
+        return Ok(Context::with_builtins(Vec::new()));
+    };
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("cannot read context `{path}`: {err}"))?;
+    parse(&text).map_err(|err| format!("invalid context `{path}`: {err}"))
+}
+
+fn parse(text: &str) -> Result<Context, ContextError> {
+    let mut ctx = Context::validate_json(text)?;
+    if ctx.functions.is_empty() {
+        ctx.functions = builtins_functions().into();
+    }
+    Ok(ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use analyzer::semantic::Ty;
+
+    use super::parse;
+
+    #[test]
+    fn parses_properties_and_fills_in_builtins() {
+        let ctx = parse(r#"{ "properties": [{ "name": "Price", "type": "Number" }] }"#).unwrap();
+        assert_eq!(ctx.properties[0].name, "Price");
+        assert_eq!(ctx.properties[0].ty, Ty::Number);
+        assert!(ctx.functions.by_name("if").is_some());
+
+        let err = parse(r#"{ "properties": 1 }"#).unwrap_err();
+        assert!(err.to_string().starts_with("properties: "), "{err}");
+    }
+}
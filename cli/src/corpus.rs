@@ -0,0 +1,141 @@
+//! Formula corpora for `lint`: many formulas keyed by id.
+//!
+//! - JSON: an object mapping id → source, read in id order.
+//! - CSV (`.csv` files): a header row with `id` and `source` columns (others are ignored), then
+//!   one formula per row in file order. Fields may be quoted; `""` escapes a quote.
+
+use std::collections::BTreeMap;
+
+/// One formula of a corpus.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub id: String,
+    pub source: String,
+}
+
+/// Parses a corpus named `name`; CSV when it ends in `.csv`, JSON otherwise.
+pub fn parse(name: &str, text: &str) -> Result<Vec<Entry>, String> {
+    let entries = if name.to_ascii_lowercase().ends_with(".csv") {
+        parse_csv(text)
+    } else {
+        parse_json(text)
+    };
+    entries.map_err(|err| format!("invalid corpus `{name}`: {err}"))
+}
+
+fn parse_json(text: &str) -> Result<Vec<Entry>, String> {
+    let map: BTreeMap<String, String> = serde_json::from_str(text)
+        .map_err(|err| format!("expected an object of id → formula source: {err}"))?;
+    Ok(map
+        .into_iter()
+        .map(|(id, source)| Entry { id, source })
+        .collect())
+}
+
+fn parse_csv(text: &str) -> Result<Vec<Entry>, String> {
+    let mut rows = csv_records(text)?.into_iter();
+    let header = rows.next().ok_or("missing header row")?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|field| field.trim() == name)
+            .ok_or_else(|| format!("header has no `{name}` column"))
+    };
+    let (id, source) = (column("id")?, column("source")?);
+
+    rows.enumerate()
+        // A trailing newline leaves one empty record.
+        .filter(|(_, row)| !(row.len() == 1 && row[0].is_empty()))
+        .map(|(i, mut row)| {
+            if row.len() <= id.max(source) {
+                return Err(format!("row {} has {} fields", i + 2, row.len()));
+            }
+            Ok(Entry {
+                source: std::mem::take(&mut row[source]),
+                id: std::mem::take(&mut row[id]),
+            })
+        })
+        .collect()
+}
+
+/// Splits RFC 4180-style CSV into records of fields.
+fn csv_records(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    let mut in_quotes = false;
+    while let Some(c) = chars.next() {
+        match (in_quotes, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => in_quotes = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => in_quotes = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("unterminated quoted field".into());
+    }
+    record.push(field);
+    records.push(record);
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, parse};
+
+    fn entry(id: &str, source: &str) -> Entry {
+        Entry {
+            id: id.into(),
+            source: source.into(),
+        }
+    }
+
+    #[test]
+    fn json_corpus_is_read_in_id_order() {
+        let entries = parse("c.json", r#"{ "b": "1 +", "a": "2" }"#).unwrap();
+        assert_eq!(entries, [entry("a", "2"), entry("b", "1 +")]);
+        assert!(
+            parse("c.json", "[]")
+                .unwrap_err()
+                .starts_with("invalid corpus `c.json`")
+        );
+    }
+
+    #[test]
+    fn csv_corpus_handles_quotes_and_column_order() {
+        let text = "source,name,id\r\n\"if(true, \"\"a\"\", \"\"b\"\")\",x,f1\n\"1 +\n2\",y,f2\n";
+        let entries = parse("c.CSV", text).unwrap();
+        assert_eq!(
+            entries,
+            [entry("f1", "if(true, \"a\", \"b\")"), entry("f2", "1 +\n2")]
+        );
+
+        assert!(
+            parse("c.csv", "id\n1")
+                .unwrap_err()
+                .contains("no `source` column")
+        );
+        assert!(
+            parse("c.csv", "id,source\n1")
+                .unwrap_err()
+                .contains("row 2 has 1 fields")
+        );
+        assert!(
+            parse("c.csv", "id,source\n1,\"x")
+                .unwrap_err()
+                .contains("unterminated")
+        );
+    }
+}
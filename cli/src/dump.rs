@@ -0,0 +1,160 @@
+//! `notion-formula dump`: print tokens, the AST, or the AST with inferred types.
+
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+
+use analyzer::ast::{Expr, ExprKind};
+use analyzer::semantic::Context;
+use analyzer::{LitKind, Token, TokenKind, TypeMap};
+
+use crate::args::Args;
+use crate::{EXIT_OK, context, input};
+
+const USAGE: &str = "\
+Usage: notion-formula dump (--tokens | --ast | --types) [OPTIONS] [FILE]
+
+Prints compiler internals for one formula, for debugging and issue reports.
+Reads stdin when FILE is missing or `-`. Spans are UTF-8 byte offsets.
+
+Options:
+  --tokens          Lexer output, one token per line (trivia included)
+  --ast             Parse tree, one node per line with its span
+  --types           Parse tree with each node's inferred type
+  --context <PATH>  JSON file: { \"properties\": [{ \"name\": ..., \"type\": ... }] }
+  -h, --help        Print this help
+";
+
+const MODES: [&str; 3] = ["tokens", "ast", "types"];
+
+pub fn run(
+    args: &[String],
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    _stderr: &mut dyn Write,
+) -> Result<i32, String> {
+    let args = Args::parse(args, &["help", "tokens", "ast", "types"], &["context"])?;
+    if args.flag("help") {
+        let _ = write!(stdout, "{USAGE}");
+        return Ok(EXIT_OK);
+    }
+    let mode = match MODES
+        .iter()
+        .filter(|mode| args.flag(mode))
+        .collect::<Vec<_>>()[..]
+    {
+        [mode] => *mode,
+        _ => return Err("`dump` takes exactly one of `--tokens`, `--ast`, `--types`".into()),
+    };
+    if args.positionals.len() > 1 {
+        return Err("`dump` takes one file".into());
+    }
+    let ctx = context::load(args.option("context"))?;
+    let source = input::read_all(&args.positionals, stdin)?.remove(0).source;
+
+    let syntax = analyzer::analyze_syntax(&source);
+    let out = match mode {
+        "tokens" => tokens(&source, &syntax.tokens),
+        "ast" => tree(&syntax.expr, None),
+        _ => types(&syntax.expr, &ctx),
+    };
+    let _ = write!(stdout, "{out}");
+    Ok(EXIT_OK)
+}
+
+/// `start..end Kind "text"` per token.
+fn tokens(source: &str, tokens: &[Token]) -> String {
+    let mut out = String::new();
+    for token in tokens {
+        let text = &source[token.span.start as usize..token.span.end as usize];
+        let _ = writeln!(
+            out,
+            "{}..{} {} {text:?}",
+            token.span.start,
+            token.span.end,
+            token_kind(&token.kind)
+        );
+    }
+    out
+}
+
+fn token_kind(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::Literal(lit) => format!("Literal({})", lit_kind(lit.kind)),
+        TokenKind::Ident(_) => "Ident".into(),
+        TokenKind::DocComment(kind, _) => format!("Comment({kind:?})"),
+        other => format!("{other:?}"),
+    }
+}
+
+fn lit_kind(kind: LitKind) -> &'static str {
+    match kind {
+        LitKind::Number => "Number",
+        LitKind::String => "String",
+        LitKind::Bool => "Boolean",
+    }
+}
+
+fn types(expr: &Expr, ctx: &Context) -> String {
+    let mut map = TypeMap::default();
+    analyzer::semantic::analyze_expr_with_map(expr, ctx, &mut map);
+    tree(expr, Some(&map))
+}
+
+/// One node per line, children indented: `Kind detail @start..end[: type]`.
+pub fn tree(expr: &Expr, types: Option<&TypeMap>) -> String {
+    let mut out = String::new();
+    write_node(&mut out, expr, types, 0);
+    out
+}
+
+fn write_node(out: &mut String, expr: &Expr, types: Option<&TypeMap>, depth: usize) {
+    let (label, children): (String, Vec<&Expr>) = match &expr.kind {
+        ExprKind::Ident(sym) => (format!("Ident {}", sym.text), vec![]),
+        ExprKind::Group { inner } => ("Group".into(), vec![inner]),
+        ExprKind::List { items } => ("List".into(), items.iter().collect()),
+        ExprKind::Call { callee, args } => (format!("Call {}", callee.text), args.iter().collect()),
+        ExprKind::MemberCall {
+            receiver,
+            method,
+            args,
+        } => (
+            format!("MemberCall .{}", method.text),
+            std::iter::once(&**receiver).chain(args).collect(),
+        ),
+        ExprKind::Lit(lit) => match lit.kind {
+            LitKind::String => (format!("String {:?}", lit.symbol.text), vec![]),
+            kind => (format!("{} {}", lit_kind(kind), lit.symbol.text), vec![]),
+        },
+        ExprKind::Unary { op, expr } => (format!("Unary {}", op.as_str()), vec![expr]),
+        ExprKind::Binary { op, left, right } => {
+            (format!("Binary {}", op.node.as_str()), vec![left, right])
+        }
+        ExprKind::Ternary {
+            cond,
+            then,
+            otherwise,
+        } => ("Ternary".into(), vec![cond, then, otherwise]),
+        ExprKind::Error => ("Error".into(), vec![]),
+    };
+
+    let _ = write!(
+        out,
+        "{:indent$}{label} @{}..{}",
+        "",
+        expr.span.start,
+        expr.span.end,
+        indent = depth * 2
+    );
+    if let Some(types) = types {
+        match types.get(expr.id) {
+            Some(ty) => {
+                let _ = write!(out, ": {ty}");
+            }
+            None => out.push_str(": ?"),
+        }
+    }
+    out.push('\n');
+    for child in children {
+        write_node(out, child, types, depth + 1);
+    }
+}
@@ -0,0 +1,195 @@
+//! `notion-formula eval`: type-check and evaluate one formula against one row.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use analyzer::semantic::Context;
+use evaluator::json::value_from_json;
+use evaluator::{EvalError, Value};
+use serde_json::Value as Json;
+
+use crate::args::Args;
+use crate::{EXIT_FAILURE, EXIT_OK, context};
+
+const USAGE: &str = "\
+Usage: notion-formula eval [OPTIONS] [EXPR]
+
+Type-checks EXPR, evaluates it against one row, and prints the value and its type.
+Reads EXPR from stdin when it is missing or `-`.
+
+Options:
+  --context <PATH>  JSON file: { \"properties\": [{ \"name\": ..., \"type\": ... }] }
+  --row <PATH>      JSON object of property name -> value (missing properties are empty)
+  -h, --help        Print this help
+";
+
+/// Name of the expression in diagnostics.
+const EXPR_NAME: &str = "<expr>";
+
+pub fn run(
+    args: &[String],
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> Result<i32, String> {
+    let args = Args::parse(args, &["help"], &["context", "row"])?;
+    if args.flag("help") {
+        let _ = write!(stdout, "{USAGE}");
+        return Ok(EXIT_OK);
+    }
+    let source = match args.positionals.as_slice() {
+        [] => read_stdin(stdin)?,
+        [expr] if expr == "-" => read_stdin(stdin)?,
+        [expr] => expr.clone(),
+        _ => return Err("`eval` takes one expression".into()),
+    };
+    let ctx = context::load(args.option("context"))?;
+    let row = match args.option("row") {
+        Some(path) => load_row(path, &ctx)?,
+        None => HashMap::new(),
+    };
+
+    let analysis = analyzer::analyze(&source, &ctx);
+    if !analysis.diagnostics.is_empty() {
+        let rendered = analyzer::format_diagnostics_for(EXPR_NAME, &source, analysis.diagnostics);
+        let _ = write!(stderr, "{rendered}");
+        return Ok(EXIT_FAILURE);
+    }
+
+    match evaluate(&source, &ctx, row) {
+        Ok(value) => {
+            let _ = writeln!(stdout, "value: {}", display(value.as_ref()));
+            let _ = writeln!(stdout, "type: {}", analysis.output_type);
+            Ok(EXIT_OK)
+        }
+        Err(message) => {
+            let _ = writeln!(stderr, "error: evaluation failed: {message}");
+            Ok(EXIT_FAILURE)
+        }
+    }
+}
+
+fn read_stdin(stdin: &mut dyn Read) -> Result<String, String> {
+    let mut source = String::new();
+    stdin
+        .read_to_string(&mut source)
+        .map_err(|err| format!("cannot read stdin: {err}"))?;
+    Ok(source)
+}
+
+/// Evaluates `source` for a single row; `None` is an empty value.
+pub fn evaluate(
+    source: &str,
+    ctx: &Context,
+    row: HashMap<String, Option<Value>>,
+) -> Result<Option<Value>, String> {
+    let expr = analyzer::analyze_syntax(source).expr;
+    evaluator::evaluate_row(&expr, ctx.properties.to_vec(), &row).map_err(|error| match error {
+        EvalError::InvalidArgument | EvalError::UnknownFunction => {
+            "not supported by the evaluator yet (it covers literals, lists, `prop(...)`, and \
+             `+ - * /`)"
+                .into()
+        }
+        error => error.to_string(),
+    })
+}
+
+/// Loads `--row`, converting each value to its property's type.
+pub fn load_row(path: &str, ctx: &Context) -> Result<HashMap<String, Option<Value>>, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|err| format!("cannot read row `{path}`: {err}"))?;
+    parse_row(&text, ctx).map_err(|err| format!("invalid row `{path}`: {err}"))
+}
+
+fn parse_row(text: &str, ctx: &Context) -> Result<HashMap<String, Option<Value>>, String> {
+    let object: serde_json::Map<String, Json> = serde_json::from_str(text)
+        .map_err(|err| format!("expected an object of property name -> value: {err}"))?;
+    object
+        .into_iter()
+        .map(|(name, json)| {
+            let ty = ctx
+                .lookup(&name)
+                .ok_or_else(|| format!("`{name}` is not a property of the context"))?;
+            let value = value_from_json(&json, &ty).map_err(|err| format!("`{name}`: {err}"))?;
+            Ok((name, value))
+        })
+        .collect()
+}
+
+/// Formula-literal rendering: quoted strings, `[a, b]` lists, dates as epoch ms.
+pub fn display(value: Option<&Value>) -> String {
+    let Some(value) = value else {
+        return "empty".into();
+    };
+    match value {
+        Value::Number(n) => n.to_string(),
+        Value::Text(s) => Json::String(s.clone()).to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Date(ms) => format!("date({ms})"),
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(|item| display(Some(item))).collect();
+            format!("[{}]", items.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use analyzer::semantic::{Context, Property, Ty};
+    use evaluator::Value;
+
+    use super::parse_row;
+
+    fn ctx(properties: &[(&str, Ty)]) -> Context {
+        Context {
+            properties: properties
+                .iter()
+                .map(|(name, ty)| Property {
+                    name: name.to_string(),
+                    ty: ty.clone(),
+                    disabled_reason: None,
+                    options: Vec::new(),
+                })
+                .collect(),
+            functions: Vec::new().into(),
+            property_resolver: None,
+            property_lookup: Default::default(),
+            limits: Default::default(),
+        }
+    }
+
+    #[test]
+    fn row_values_follow_property_types() {
+        let ctx = ctx(&[
+            ("Price", Ty::Number),
+            ("Tags", Ty::List(Box::new(Ty::String))),
+            ("Due", Ty::Date),
+            ("Note", Ty::String),
+        ]);
+        let row = parse_row(
+            r#"{ "Price": 2.5, "Tags": ["a"], "Due": 1700000000000, "Note": null }"#,
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(row["Price"], Some(Value::Number(2.5)));
+        assert_eq!(
+            row["Tags"],
+            Some(Value::List(vec![Value::Text("a".into())]))
+        );
+        assert_eq!(row["Due"], Some(Value::Date(1_700_000_000_000)));
+        assert_eq!(row["Note"], None);
+    }
+
+    #[test]
+    fn row_rejects_unknown_properties_and_wrong_types() {
+        let ctx = ctx(&[("Price", Ty::Number)]);
+        assert_eq!(
+            parse_row(r#"{ "Price": "2" }"#, &ctx).unwrap_err(),
+            "`Price`: expected number, found \"2\""
+        );
+        assert_eq!(
+            parse_row(r#"{ "Tax": 1 }"#, &ctx).unwrap_err(),
+            "`Tax` is not a property of the context"
+        );
+    }
+}
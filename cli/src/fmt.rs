@@ -0,0 +1,149 @@
+//! `notion-formula fmt`: format formulas to stdout, in place (`--write`), or verify (`--check`).
+
+use std::io::{Read, Write};
+
+use ide::{ClosingDelim, FormatConfig};
+
+use crate::args::Args;
+use crate::input::{self, Input};
+use crate::{EXIT_FAILURE, EXIT_OK};
+
+const USAGE: &str = "\
+Usage: notion-formula fmt [OPTIONS] [FILE]...
+
+Formats each formula and prints the result.
+Reads stdin when no FILE is given or FILE is `-`.
+
+Options:
+  --write                   Rewrite FILEs in place
+  --check                   Print files that are not formatted; exit 1 if there are any
+  --indent-width <N>        Spaces per indentation level [default: 2]
+  --max-width <N>           Preferred maximum line width [default: 80]
+  --wrap-comments           Wrap own-line `//` comments longer than the max width
+  --collapse-fitting        Re-join broken calls and lists that fit on one line
+  --closing-delim <WHERE>   `own-line` or `same-line` [default: own-line]
+  -h, --help                Print this help
+";
+
+const FLAGS: &[&str] = &[
+    "help",
+    "write",
+    "check",
+    "wrap-comments",
+    "collapse-fitting",
+];
+const OPTIONS: &[&str] = &["indent-width", "max-width", "closing-delim"];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Print,
+    Write,
+    Check,
+}
+
+pub fn run(
+    args: &[String],
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> Result<i32, String> {
+    let args = Args::parse(args, FLAGS, OPTIONS)?;
+    if args.flag("help") {
+        let _ = write!(stdout, "{USAGE}");
+        return Ok(EXIT_OK);
+    }
+    let mode = match (args.flag("write"), args.flag("check")) {
+        (true, true) => return Err("`--write` and `--check` cannot be used together".into()),
+        (true, false) => Mode::Write,
+        (false, true) => Mode::Check,
+        (false, false) => Mode::Print,
+    };
+    let config = format_config(&args)?;
+    let inputs = input::read_all(&args.positionals, stdin)?;
+    if mode == Mode::Write && inputs.iter().any(|input| input.path.is_none()) {
+        return Err("`--write` needs file paths, not stdin".into());
+    }
+
+    let mut failed = false;
+    let mut unformatted = 0;
+    for input in &inputs {
+        let formatted = match format(input, config) {
+            Ok(formatted) => formatted,
+            Err(message) => {
+                let _ = writeln!(stderr, "error: {message}");
+                failed = true;
+                continue;
+            }
+        };
+        match mode {
+            Mode::Print => {
+                let _ = write!(stdout, "{formatted}");
+            }
+            Mode::Check => {
+                if formatted != input.source {
+                    unformatted += 1;
+                    let _ = writeln!(stdout, "{}", input.name());
+                }
+            }
+            Mode::Write => {
+                if formatted != input.source {
+                    let path = input.name();
+                    std::fs::write(path, &formatted)
+                        .map_err(|err| format!("cannot write `{path}`: {err}"))?;
+                }
+            }
+        }
+    }
+
+    if unformatted > 0 {
+        let noun = if unformatted == 1 { "file" } else { "files" };
+        let _ = writeln!(stderr, "{unformatted} {noun} not formatted");
+    }
+    Ok(if failed || unformatted > 0 {
+        EXIT_FAILURE
+    } else {
+        EXIT_OK
+    })
+}
+
+fn format(input: &Input, config: FormatConfig) -> Result<String, String> {
+    let name = input.name();
+    let result = ide::format_with_config(&input.source, 0, config)
+        .map_err(|_| format!("cannot format `{name}`: it has syntax errors"))?;
+    // The formatter returns the source unchanged, with an internal diagnostic, rather than
+    // output that would change the formula's meaning.
+    if let Some(diagnostic) = result.diagnostics.first() {
+        return Err(format!("cannot format `{name}`: {}", diagnostic.message));
+    }
+    Ok(result.source)
+}
+
+fn format_config(args: &Args) -> Result<FormatConfig, String> {
+    let mut config = FormatConfig::default();
+    if let Some(width) = args.option("indent-width") {
+        config.indent_width = count("indent-width", width)?;
+    }
+    if let Some(width) = args.option("max-width") {
+        config.max_width = count("max-width", width)?;
+    }
+    config.wrap_comments = args.flag("wrap-comments");
+    config.collapse_fitting = args.flag("collapse-fitting");
+    if let Some(placement) = args.option("closing-delim") {
+        config.closing_delim = match placement {
+            "own-line" => ClosingDelim::OwnLine,
+            "same-line" => ClosingDelim::SameLine,
+            other => {
+                return Err(format!(
+                    "`--closing-delim` must be `own-line` or `same-line`, found `{other}`"
+                ));
+            }
+        };
+    }
+    Ok(config)
+}
+
+fn count(option: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("`--{option}` must be a non-negative integer, found `{value}`"))
+}
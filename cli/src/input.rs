@@ -0,0 +1,51 @@
+//! Reading formula sources from files or stdin.
+
+use std::io::Read;
+
+/// Display name for stdin in diagnostics.
+pub const STDIN_NAME: &str = "<stdin>";
+
+/// One formula source.
+pub struct Input {
+    /// `None` for stdin.
+    pub path: Option<String>,
+    pub source: String,
+}
+
+impl Input {
+    pub fn name(&self) -> &str {
+        self.path.as_deref().unwrap_or(STDIN_NAME)
+    }
+}
+
+/// Reads every path (`-` is stdin), or stdin alone when `paths` is empty.
+pub fn read_all(paths: &[String], stdin: &mut dyn Read) -> Result<Vec<Input>, String> {
+    if paths.is_empty() {
+        return Ok(vec![read_stdin(stdin)?]);
+    }
+    if paths.iter().filter(|path| *path == "-").count() > 1 {
+        return Err("stdin (`-`) can only be read once".into());
+    }
+    paths
+        .iter()
+        .map(|path| {
+            if path == "-" {
+                return read_stdin(stdin);
+            }
+            let source = std::fs::read_to_string(path)
+                .map_err(|err| format!("cannot read `{path}`: {err}"))?;
+            Ok(Input {
+                path: Some(path.clone()),
+                source,
+            })
+        })
+        .collect()
+}
+
+fn read_stdin(stdin: &mut dyn Read) -> Result<Input, String> {
+    let mut source = String::new();
+    stdin
+        .read_to_string(&mut source)
+        .map_err(|err| format!("cannot read stdin: {err}"))?;
+    Ok(Input { path: None, source })
+}
@@ -0,0 +1,81 @@
+//! `notion-formula` command line tool.
+//!
+//! Commands:
+//! - `check`: print diagnostics for formula files (or stdin).
+//! - `dump`: print tokens, the AST, or the AST with inferred types.
+//! - `eval`: type-check and evaluate one formula against one row of property values.
+//! - `fmt`: format formula files (or stdin) to stdout, in place, or as a check.
+//! - `lint`: analyze a corpus of formulas and print a JSON report grouped by diagnostic code.
+//! - `minimize`: shrink an input that crashes a fuzz target to a minimal reproducer.
+//! - `repl`: evaluate formulas interactively, with session variables.
+//!
+//! Exit codes: [`EXIT_OK`], [`EXIT_FAILURE`] when a command finds problems, [`EXIT_USAGE`] for
+//! bad arguments or unreadable input.
+
+mod args;
+mod check;
+mod context;
+mod corpus;
+mod dump;
+mod eval;
+mod fmt;
+mod input;
+mod lint;
+mod minimize;
+mod repl;
+
+use std::io::{Read, Write};
+
+/// Success.
+pub const EXIT_OK: i32 = 0;
+/// The command ran and found problems (e.g. diagnostics).
+pub const EXIT_FAILURE: i32 = 1;
+/// Bad arguments, or a file/context that could not be read.
+pub const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "\
+Usage: notion-formula <COMMAND> [OPTIONS] [FILE]...
+
+Commands:
+  check    Print diagnostics; exits 1 if there are any
+  dump     Print tokens, the AST, or inferred types (`--tokens | --ast | --types`)
+  eval     Type-check and evaluate a formula against one row
+  fmt      Format formulas (`--write` in place, `--check` to verify)
+  lint     Analyze a JSON/CSV corpus of formulas; prints a JSON report
+  minimize Shrink an input that crashes a fuzz target (`--target`) to a minimal reproducer
+  repl     Evaluate formulas interactively (`let x = ...`, `:type`, `:ast`)
+
+Reads stdin when no FILE is given or FILE is `-`.
+Run `notion-formula <COMMAND> --help` for command options.
+";
+
+/// Runs the tool with `args` (without the program name), returning the process exit code.
+pub fn run(
+    args: &[String],
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> i32 {
+    let Some((command, rest)) = args.split_first() else {
+        let _ = write!(stderr, "{USAGE}");
+        return EXIT_USAGE;
+    };
+    let result = match command.as_str() {
+        "check" => check::run(rest, stdin, stdout, stderr),
+        "dump" => dump::run(rest, stdin, stdout, stderr),
+        "eval" => eval::run(rest, stdin, stdout, stderr),
+        "fmt" => fmt::run(rest, stdin, stdout, stderr),
+        "lint" => lint::run(rest, stdin, stdout, stderr),
+        "minimize" => minimize::run(rest, stdin, stdout, stderr),
+        "repl" => repl::run(rest, stdin, stdout, stderr),
+        "-h" | "--help" | "help" => {
+            let _ = write!(stdout, "{USAGE}");
+            Ok(EXIT_OK)
+        }
+        other => Err(format!("unknown command `{other}`\n\n{USAGE}")),
+    };
+    result.unwrap_or_else(|message| {
+        let _ = writeln!(stderr, "error: {}", message.trim_end());
+        EXIT_USAGE
+    })
+}
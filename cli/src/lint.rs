@@ -0,0 +1,147 @@
+//! `notion-formula lint`: analyze every formula of a corpus and print a JSON report.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+use analyzer::SourceMap;
+use serde::Serialize;
+
+use crate::args::Args;
+use crate::{EXIT_FAILURE, EXIT_OK, context, corpus, input};
+
+const USAGE: &str = "\
+Usage: notion-formula lint [OPTIONS] [CORPUS]
+
+Analyzes every formula in CORPUS and prints a JSON report with diagnostics grouped by code.
+Exits 1 if any formula has diagnostics.
+
+CORPUS is a JSON object of id -> formula source, or a `.csv` file with `id` and `source`
+columns. Reads JSON from stdin when CORPUS is missing or `-`.
+
+Options:
+  --context <PATH>  JSON file: { \"properties\": [{ \"name\": ..., \"type\": ... }] }
+  -h, --help        Print this help
+";
+
+#[derive(Debug, Serialize)]
+struct Report {
+    formulas: usize,
+    formulas_with_errors: usize,
+    diagnostics: usize,
+    /// Most frequent code first.
+    by_code: Vec<CodeSummary>,
+    /// Formulas with diagnostics, in corpus order.
+    results: Vec<FormulaResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct CodeSummary {
+    code: &'static str,
+    count: usize,
+    /// Ids of the formulas with this code, in corpus order.
+    formulas: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FormulaResult {
+    id: String,
+    diagnostics: Vec<DiagnosticEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiagnosticEntry {
+    code: &'static str,
+    message: String,
+    /// UTF-8 byte offsets, `[start, end)`.
+    start: u32,
+    end: u32,
+    /// 1-based.
+    line: usize,
+    column: usize,
+}
+
+pub fn run(
+    args: &[String],
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> Result<i32, String> {
+    let args = Args::parse(args, &["help"], &["context"])?;
+    if args.flag("help") {
+        let _ = write!(stdout, "{USAGE}");
+        return Ok(EXIT_OK);
+    }
+    if args.positionals.len() > 1 {
+        return Err("`lint` takes one corpus file".into());
+    }
+    let ctx = context::load(args.option("context"))?;
+    let input = input::read_all(&args.positionals, stdin)?.remove(0);
+    let entries = corpus::parse(input.name(), &input.source)?;
+
+    let report = lint(entries, &ctx);
+    let json = serde_json::to_string_pretty(&report).expect("report serializes");
+    let _ = writeln!(stdout, "{json}");
+    if report.diagnostics == 0 {
+        return Ok(EXIT_OK);
+    }
+    let _ = writeln!(
+        stderr,
+        "{} diagnostics in {} of {} formulas",
+        report.diagnostics, report.formulas_with_errors, report.formulas
+    );
+    Ok(EXIT_FAILURE)
+}
+
+fn lint(entries: Vec<corpus::Entry>, ctx: &analyzer::semantic::Context) -> Report {
+    let formulas = entries.len();
+    let mut results = Vec::new();
+    let mut by_code: BTreeMap<&'static str, CodeSummary> = BTreeMap::new();
+    for entry in entries {
+        let mut diagnostics = analyzer::analyze(&entry.source, ctx).diagnostics;
+        if diagnostics.is_empty() {
+            continue;
+        }
+        diagnostics.sort_by_key(|diag| (diag.span.start, diag.span.end));
+        let sm = SourceMap::new(&entry.source);
+        let diagnostics: Vec<DiagnosticEntry> = diagnostics
+            .into_iter()
+            .map(|diag| {
+                let (line, column) = sm.line_col(diag.span.start);
+                DiagnosticEntry {
+                    code: diag.code.as_str(),
+                    message: diag.message,
+                    start: diag.span.start,
+                    end: diag.span.end,
+                    line,
+                    column,
+                }
+            })
+            .collect();
+
+        for diag in &diagnostics {
+            let summary = by_code.entry(diag.code).or_insert_with(|| CodeSummary {
+                code: diag.code,
+                count: 0,
+                formulas: Vec::new(),
+            });
+            summary.count += 1;
+            if summary.formulas.last() != Some(&entry.id) {
+                summary.formulas.push(entry.id.clone());
+            }
+        }
+        results.push(FormulaResult {
+            id: entry.id,
+            diagnostics,
+        });
+    }
+
+    let mut by_code: Vec<CodeSummary> = by_code.into_values().collect();
+    by_code.sort_by(|a, b| b.count.cmp(&a.count).then(a.code.cmp(b.code)));
+    Report {
+        formulas,
+        formulas_with_errors: results.len(),
+        diagnostics: results.iter().map(|result| result.diagnostics.len()).sum(),
+        by_code,
+        results,
+    }
+}
@@ -0,0 +1,10 @@
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let code = notion_formula_cli::run(
+        &args,
+        &mut std::io::stdin().lock(),
+        &mut std::io::stdout().lock(),
+        &mut std::io::stderr().lock(),
+    );
+    std::process::exit(code);
+}
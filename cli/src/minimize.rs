@@ -0,0 +1,84 @@
+//! `notion-formula minimize`: shrink an input that crashes a fuzz target to a minimal reproducer.
+
+use std::io::{Read, Write};
+use std::panic;
+
+use notion_formula_fuzz::{TARGETS, minimize_crash, target};
+
+use crate::args::Args;
+use crate::{EXIT_FAILURE, EXIT_OK};
+
+const USAGE: &str = "\
+Usage: notion-formula minimize [OPTIONS] [FILE]
+
+Shrinks an input on which a fuzz target panics (including a broken target invariant) to a
+minimal input panicking with the same message, up to numbers, and prints it.
+Reads stdin when FILE is missing or `-`. Exits 1 if the input does not panic.
+
+Options:
+  --target <NAME>   Fuzz target: analyze, help, format, apply_edits [default: analyze]
+  --output <PATH>   Write the reproducer to PATH instead of stdout
+  -h, --help        Print this help
+";
+
+pub fn run(
+    args: &[String],
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> Result<i32, String> {
+    let args = Args::parse(args, &["help"], &["target", "output"])?;
+    if args.flag("help") {
+        let _ = write!(stdout, "{USAGE}");
+        return Ok(EXIT_OK);
+    }
+    let name = args.option("target").unwrap_or("analyze");
+    let Some(target) = target(name) else {
+        let names: Vec<&str> = TARGETS.iter().map(|target| target.name).collect();
+        return Err(format!(
+            "unknown target `{name}` (expected one of: {})",
+            names.join(", ")
+        ));
+    };
+    let data = match args.positionals.as_slice() {
+        [] => read_stdin(stdin)?,
+        [path] if path == "-" => read_stdin(stdin)?,
+        [path] => std::fs::read(path).map_err(|err| format!("cannot read `{path}`: {err}"))?,
+        _ => return Err("expected at most one FILE".into()),
+    };
+
+    // Every candidate that still fails panics; keep the default hook from printing each one.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let minimized = minimize_crash(target, &data);
+    panic::set_hook(hook);
+
+    let Some(minimized) = minimized else {
+        let _ = writeln!(stderr, "{name}: the input does not panic");
+        return Ok(EXIT_FAILURE);
+    };
+    match args.option("output") {
+        Some(path) => std::fs::write(path, &minimized.data)
+            .map_err(|err| format!("cannot write `{path}`: {err}"))?,
+        None => {
+            let _ = stdout.write_all(&minimized.data);
+        }
+    }
+    let _ = writeln!(stderr, "{name}: panicked: {}", minimized.message);
+    let _ = writeln!(
+        stderr,
+        "minimized {} to {} bytes in {} runs",
+        data.len(),
+        minimized.data.len(),
+        minimized.runs
+    );
+    Ok(EXIT_OK)
+}
+
+fn read_stdin(stdin: &mut dyn Read) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    stdin
+        .read_to_end(&mut data)
+        .map_err(|err| format!("cannot read stdin: {err}"))?;
+    Ok(data)
+}
@@ -0,0 +1,326 @@
+//! `notion-formula repl`: evaluate formulas line by line, with session variables.
+//!
+//! `let x = expr` evaluates `expr` and binds `x` for later lines: the value is stored as a
+//! session property, and bare `x` identifiers are rewritten to `prop("x")` before analysis
+//! ([`Rewrite`]). Diagnostics are mapped back to the typed line.
+//!
+//! Input is plain stdin lines, so editing is the terminal's own (no history; `rlwrap` adds it).
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, IsTerminal, Read, Write};
+
+use analyzer::semantic::{Context, Property, Ty};
+use analyzer::{Diagnostic, Span, TokenKind};
+use evaluator::Value;
+
+use crate::args::Args;
+use crate::{EXIT_OK, context, dump, eval};
+
+const USAGE: &str = "\
+Usage: notion-formula repl [OPTIONS]
+
+Reads formulas line by line, printing each value and its type.
+Prompts go to stderr, so piped output holds only results.
+
+Options:
+  --context <PATH>  JSON file: { \"properties\": [{ \"name\": ..., \"type\": ... }] }
+  --row <PATH>      JSON object of property name -> value (missing properties are empty)
+  --color <WHEN>    Color diagnostics: `auto` (default; when stdout is a terminal), `always`, `never`
+  -h, --help        Print this help
+";
+
+const HELP: &str = "\
+  <expr>           Evaluate and print `value : type`
+  let <name> = <expr>
+                   Evaluate and bind <name> for later lines
+  :type <expr>     Print the inferred type
+  :ast <expr>      Print the parse tree
+  :vars            List session variables
+  :help            Show this help
+  :quit            Exit (or end of input)
+";
+
+const PROMPT: &str = "> ";
+
+pub fn run(
+    args: &[String],
+    stdin: &mut dyn Read,
+    stdout: &mut dyn Write,
+    stderr: &mut dyn Write,
+) -> Result<i32, String> {
+    let args = Args::parse(args, &["help"], &["context", "row", "color"])?;
+    if args.flag("help") {
+        let _ = write!(stdout, "{USAGE}");
+        return Ok(EXIT_OK);
+    }
+    if !args.positionals.is_empty() {
+        return Err("`repl` takes no files".into());
+    }
+    let color = match args.option("color").unwrap_or("auto") {
+        "auto" => std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        "always" => true,
+        "never" => false,
+        other => {
+            return Err(format!(
+                "`--color` must be auto, always, or never, not `{other}`"
+            ));
+        }
+    };
+    let ctx = context::load(args.option("context"))?;
+    let row = match args.option("row") {
+        Some(path) => eval::load_row(path, &ctx)?,
+        None => HashMap::new(),
+    };
+
+    let mut session = Session {
+        ctx,
+        row,
+        variables: Vec::new(),
+        color,
+    };
+    let _ = write!(stderr, "Type :help for commands.\n{PROMPT}");
+    let _ = stderr.flush();
+    for line in BufReader::new(stdin).lines() {
+        let line = line.map_err(|err| format!("cannot read stdin: {err}"))?;
+        if !session.line(line.trim(), stdout, stderr) {
+            break;
+        }
+        let _ = write!(stderr, "{PROMPT}");
+        let _ = stderr.flush();
+    }
+    let _ = writeln!(stderr);
+    Ok(EXIT_OK)
+}
+
+struct Session {
+    /// Context properties plus one property per session variable.
+    ctx: Context,
+    /// `--row` values plus the session variables' values.
+    row: HashMap<String, Option<Value>>,
+    /// Variable names in definition order.
+    variables: Vec<String>,
+    color: bool,
+}
+
+impl Session {
+    /// Handles one input line; `false` ends the session.
+    fn line(&mut self, line: &str, stdout: &mut dyn Write, stderr: &mut dyn Write) -> bool {
+        let (command, rest) = match line.split_once(char::is_whitespace) {
+            Some((command, rest)) => (command, rest.trim()),
+            None => (line, ""),
+        };
+        let result = match command {
+            "" => Ok(String::new()),
+            ":quit" | ":q" => return false,
+            ":help" => Ok(HELP.to_string()),
+            ":vars" => Ok(self.vars()),
+            ":type" => self.analyze(rest).map(|ty| format!("{ty}\n")),
+            ":ast" => {
+                let rewrite = Rewrite::new(rest, &self.variables);
+                Ok(dump::tree(
+                    &analyzer::analyze_syntax(&rewrite.source).expr,
+                    None,
+                ))
+            }
+            "let" => self.define(rest),
+            command if command.starts_with(':') => {
+                Err(self.error(&format!("unknown command `{command}` (see :help)")))
+            }
+            _ => self
+                .evaluate(line)
+                .map(|(value, ty)| format!("{value} : {ty}\n")),
+        };
+        match result {
+            Ok(out) => {
+                let _ = write!(stdout, "{out}");
+            }
+            Err(message) => {
+                let _ = write!(stderr, "{message}");
+            }
+        }
+        true
+    }
+
+    /// `let <name> = <expr>`.
+    fn define(&mut self, rest: &str) -> Result<String, String> {
+        let Some((name, expr)) = rest.split_once('=') else {
+            return Err(self.error("expected `let <name> = <expr>`"));
+        };
+        let name = name.trim();
+        let tokens = analyzer::analyze_syntax(name).tokens;
+        if !matches!(tokens.as_slice(), [ident, _eof] if matches!(ident.kind, TokenKind::Ident(_)))
+        {
+            return Err(self.error(&format!("`{name}` is not a variable name")));
+        }
+        if !self.variables.iter().any(|v| v == name) && self.ctx.lookup(name).is_some() {
+            return Err(self.error(&format!("`{name}` is a context property")));
+        }
+
+        let source = Rewrite::new(expr.trim(), &self.variables);
+        let (ty, value) = self.run(&source)?;
+        let property = Property {
+            name: name.to_string(),
+            ty: ty.clone(),
+            disabled_reason: None,
+            options: Vec::new(),
+        };
+        match self.ctx.properties.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = property,
+            None => {
+                self.ctx.properties.push(property);
+                self.variables.push(name.to_string());
+            }
+        }
+        self.row.insert(name.to_string(), value);
+        Ok(format!("{name} : {ty}\n"))
+    }
+
+    fn evaluate(&self, line: &str) -> Result<(String, Ty), String> {
+        let (ty, value) = self.run(&Rewrite::new(line, &self.variables))?;
+        Ok((eval::display(value.as_ref()), ty))
+    }
+
+    /// Type-checks `line`, returning its type.
+    fn analyze(&self, line: &str) -> Result<Ty, String> {
+        let rewrite = Rewrite::new(line, &self.variables);
+        let analysis = analyzer::analyze(&rewrite.source, &self.ctx);
+        self.check(&rewrite, analysis.diagnostics)?;
+        Ok(analysis.output_type)
+    }
+
+    /// Type-checks and evaluates a rewritten line.
+    fn run(&self, rewrite: &Rewrite) -> Result<(Ty, Option<Value>), String> {
+        let analysis = analyzer::analyze(&rewrite.source, &self.ctx);
+        self.check(rewrite, analysis.diagnostics)?;
+        let value = eval::evaluate(&rewrite.source, &self.ctx, self.row.clone())
+            .map_err(|message| self.error(&format!("evaluation failed: {message}")))?;
+        Ok((analysis.output_type, value))
+    }
+
+    fn check(&self, rewrite: &Rewrite, mut diagnostics: Vec<Diagnostic>) -> Result<(), String> {
+        if diagnostics.is_empty() {
+            return Ok(());
+        }
+        diagnostics.sort_by_key(|d| (d.span.start, d.span.end));
+        let mut out = String::new();
+        for diagnostic in diagnostics {
+            let span = rewrite.original(diagnostic.span);
+            out.push_str(&self.error(&diagnostic.message));
+            out.push_str(&self.snippet(&rewrite.input, span));
+        }
+        Err(out)
+    }
+
+    fn vars(&self) -> String {
+        self.variables
+            .iter()
+            .map(|name| {
+                let ty = self.ctx.lookup(name).unwrap_or(Ty::Unknown);
+                let value = eval::display(self.row[name].as_ref());
+                format!("{name} = {value} : {ty}\n")
+            })
+            .collect()
+    }
+
+    /// `error: <message>` line.
+    fn error(&self, message: &str) -> String {
+        format!("{}: {message}\n", self.paint(RED_BOLD, "error"))
+    }
+
+    /// The input line with `^` under `span` (at least one caret).
+    fn snippet(&self, input: &str, span: Span) -> String {
+        let start = input[..span.start as usize].chars().count();
+        let width = input[span.start as usize..span.end as usize]
+            .chars()
+            .count()
+            .max(1);
+        let gutter = self.paint(BLUE, "|");
+        let carets = self.paint(RED_BOLD, &"^".repeat(width));
+        format!(
+            "  {gutter} {input}\n  {gutter} {}{carets}\n",
+            " ".repeat(start)
+        )
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{style}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+const RED_BOLD: &str = "1;31";
+const BLUE: &str = "34";
+
+/// A line with bare session-variable identifiers rewritten to `prop("name")`.
+struct Rewrite {
+    input: String,
+    source: String,
+    /// `(input span, source span)` per rewritten identifier, in order.
+    edits: Vec<(Span, Span)>,
+}
+
+impl Rewrite {
+    fn new(input: &str, variables: &[String]) -> Self {
+        let tokens = analyzer::analyze_syntax(input).tokens;
+        let significant: Vec<_> = tokens.iter().filter(|t| !t.is_trivia()).collect();
+        let mut source = String::with_capacity(input.len());
+        let mut edits = Vec::new();
+        let mut pos = 0;
+        for (i, token) in significant.iter().enumerate() {
+            let TokenKind::Ident(symbol) = &token.kind else {
+                continue;
+            };
+            // Method names (`x.name`) and calls (`name(...)`) are not variables.
+            let after_dot = i > 0 && significant[i - 1].kind == TokenKind::Dot;
+            let before_call = significant
+                .get(i + 1)
+                .is_some_and(|next| next.kind == TokenKind::OpenParen);
+            if after_dot || before_call || !variables.iter().any(|v| *v == symbol.text) {
+                continue;
+            }
+            source.push_str(&input[pos..token.span.start as usize]);
+            let start = source.len() as u32;
+            source.push_str(&format!("prop({})", analyzer::string_literal(&symbol.text)));
+            edits.push((
+                token.span,
+                Span {
+                    start,
+                    end: source.len() as u32,
+                },
+            ));
+            pos = token.span.end as usize;
+        }
+        source.push_str(&input[pos..]);
+        Rewrite {
+            input: input.to_string(),
+            source,
+            edits,
+        }
+    }
+
+    /// Maps a span in `source` back to `input`.
+    fn original(&self, span: Span) -> Span {
+        Span {
+            start: self.offset(span.start, false),
+            end: self.offset(span.end, true),
+        }
+    }
+
+    /// Offsets inside a rewritten identifier snap to its start (or its end for span ends).
+    fn offset(&self, offset: u32, is_end: bool) -> u32 {
+        let mut mapped = offset as i64;
+        for (input, source) in &self.edits {
+            if offset < source.start || (is_end && offset == source.start) {
+                break;
+            }
+            if offset < source.end {
+                return if is_end { input.end } else { input.start };
+            }
+            mapped = offset as i64 - source.end as i64 + input.end as i64;
+        }
+        mapped as u32
+    }
+}
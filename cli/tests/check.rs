@@ -0,0 +1,113 @@
+mod common;
+
+use analyzer::semantic::{Context, PropertyLookup, Ty};
+use common::{run, temp_dir};
+use notion_formula_cli::{EXIT_FAILURE, EXIT_OK, EXIT_USAGE};
+
+#[test]
+fn clean_stdin_exits_zero_without_output() {
+    let out = run(&["check"], "if(true, 1, 2)");
+    assert_eq!(
+        (out.code, out.stdout.as_str(), out.stderr.as_str()),
+        (EXIT_OK, "", "")
+    );
+}
+
+#[test]
+fn diagnostics_are_rendered_with_the_file_name() {
+    let dir = temp_dir(
+        "render",
+        &[
+            ("ok.formula", "1 + 2"),
+            ("bad.formula", "1 +\n  prop(\"Price\")"),
+        ],
+    );
+    let (ok, bad) = (dir.join("ok.formula"), dir.join("bad.formula"));
+    let out = run(&["check", ok.to_str().unwrap(), bad.to_str().unwrap()], "");
+
+    assert_eq!(out.code, EXIT_FAILURE);
+    let bad = bad.to_str().unwrap();
+    assert_eq!(
+        out.stdout,
+        format!("error: Unknown property: Price\n  --> {bad}:2:8 [11..18]\n")
+    );
+    assert_eq!(out.stderr, "1 error in 1 file\n");
+}
+
+#[test]
+fn context_file_defines_properties() {
+    let dir = temp_dir(
+        "context",
+        &[(
+            "ctx.json",
+            r#"{ "properties": [{ "name": "Price", "type": "Number" }] }"#,
+        )],
+    );
+    let ctx = dir.join("ctx.json");
+    let out = run(
+        &["check", "--context", ctx.to_str().unwrap()],
+        "prop(\"Price\") * 2",
+    );
+    assert_eq!(out.code, EXIT_OK, "{}", out.stdout);
+
+    let out = run(&["check", "-"], "prop(\"Price\") * 2");
+    assert_eq!(out.code, EXIT_FAILURE);
+    assert!(out.stdout.contains("--> <stdin>:1:6"), "{}", out.stdout);
+}
+
+#[test]
+fn context_file_may_be_a_serialized_context() {
+    let ctx = Context::builder()
+        .prop("Price", Ty::Number)
+        .property_lookup(PropertyLookup::Lenient)
+        .with_builtins()
+        .build();
+    let json = serde_json::to_string(&ctx).unwrap();
+    assert!(json.starts_with(r#"{"version":1,"properties":["#), "{json}");
+    let dir = temp_dir("serialized-context", &[("ctx.json", &json)]);
+    let ctx = dir.join("ctx.json");
+    let out = run(
+        &["check", "--context", ctx.to_str().unwrap()],
+        "prop(\"price\") * 2",
+    );
+    assert!(
+        out.stdout
+            .starts_with("warning: Property name `price` does not match `Price` exactly"),
+        "{}{}",
+        out.stdout,
+        out.stderr
+    );
+}
+
+#[test]
+fn usage_errors_exit_two() {
+    let out = run(&["check", "--context"], "");
+    assert_eq!(out.code, EXIT_USAGE);
+    assert_eq!(out.stderr, "error: `--context` needs a value\n");
+
+    let out = run(&["check", "missing.formula"], "");
+    assert_eq!(out.code, EXIT_USAGE);
+    assert!(
+        out.stderr
+            .starts_with("error: cannot read `missing.formula`"),
+        "{}",
+        out.stderr
+    );
+
+    let dir = temp_dir("bad-context", &[("ctx.json", "{ \"properties\": 1 }")]);
+    let ctx = dir.join("ctx.json");
+    let out = run(&["check", "--context", ctx.to_str().unwrap()], "1");
+    assert_eq!(out.code, EXIT_USAGE);
+    assert!(
+        out.stderr.contains("invalid context") && out.stderr.contains("properties: "),
+        "{}",
+        out.stderr
+    );
+
+    let out = run(&["compile"], "");
+    assert_eq!(out.code, EXIT_USAGE);
+    assert!(out.stderr.starts_with("error: unknown command `compile`"));
+
+    assert_eq!(run(&[], "").code, EXIT_USAGE);
+    assert_eq!(run(&["check", "--help"], "").code, EXIT_OK);
+}
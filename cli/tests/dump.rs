@@ -0,0 +1,76 @@
+mod common;
+
+use common::{run, temp_dir};
+use notion_formula_cli::{EXIT_OK, EXIT_USAGE};
+
+#[test]
+fn tokens_include_trivia_and_eof() {
+    let out = run(&["dump", "--tokens"], "1 + x // c");
+    assert_eq!(out.code, EXIT_OK);
+    assert_eq!(
+        out.stdout,
+        "0..1 Literal(Number) \"1\"\n\
+         2..3 Plus \"+\"\n\
+         4..5 Ident \"x\"\n\
+         6..10 Comment(Line) \"// c\"\n\
+         10..10 Eof \"\"\n"
+    );
+}
+
+#[test]
+fn ast_is_an_indented_span_tree() {
+    let out = run(&["dump", "--ast"], "if(true, \"a\", -[1].at(0))");
+    assert_eq!(
+        out.stdout,
+        "Call if @0..25\n\
+         \x20 Boolean true @3..7\n\
+         \x20 String \"a\" @9..12\n\
+         \x20 Unary - @14..24\n\
+         \x20   MemberCall .at @15..24\n\
+         \x20     List @15..18\n\
+         \x20       Number 1 @16..17\n\
+         \x20     Number 0 @22..23\n"
+    );
+}
+
+#[test]
+fn types_use_the_context() {
+    let dir = temp_dir(
+        "dump-types",
+        &[(
+            "ctx.json",
+            r#"{ "properties": [{ "name": "Price", "type": "Number" }] }"#,
+        )],
+    );
+    let out = run(
+        &[
+            "dump",
+            "--types",
+            "--context",
+            dir.join("ctx.json").to_str().unwrap(),
+        ],
+        "prop(\"Price\") > 1 ? \"big\" : \"small\"",
+    );
+    assert_eq!(
+        out.stdout,
+        "Ternary @0..35: string\n\
+         \x20 Binary > @0..17: boolean\n\
+         \x20   Call prop @0..13: number\n\
+         \x20     String \"Price\" @5..12: string\n\
+         \x20   Number 1 @16..17: number\n\
+         \x20 String \"big\" @20..25: string\n\
+         \x20 String \"small\" @28..35: string\n"
+    );
+}
+
+#[test]
+fn exactly_one_mode_is_required() {
+    for args in [&["dump"][..], &["dump", "--ast", "--types"]] {
+        let out = run(args, "1");
+        assert_eq!(out.code, EXIT_USAGE);
+        assert_eq!(
+            out.stderr,
+            "error: `dump` takes exactly one of `--tokens`, `--ast`, `--types`\n"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::{run, temp_dir};
+use notion_formula_cli::{EXIT_FAILURE, EXIT_OK, EXIT_USAGE};
+
+#[test]
+fn prints_value_and_type() {
+    let out = run(&["eval", "1 + 2 * 3"], "");
+    assert_eq!(
+        (out.code, out.stdout.as_str()),
+        (EXIT_OK, "value: 7\ntype: number\n")
+    );
+
+    let out = run(&["eval", "\"ab\""], "");
+    assert_eq!(out.stdout, "value: \"ab\"\ntype: string\n");
+
+    let out = run(&["eval"], "[1, 2]");
+    assert_eq!(out.stdout, "value: [1, 2]\ntype: number[]\n");
+}
+
+#[test]
+fn properties_come_from_the_row() {
+    let dir = temp_dir(
+        "eval-row",
+        &[
+            (
+                "ctx.json",
+                r#"{ "properties": [
+                    { "name": "Price", "type": "Number" },
+                    { "name": "Qty", "type": "Number" }
+                ] }"#,
+            ),
+            ("row.json", r#"{ "Price": 2.5, "Qty": 4 }"#),
+            ("empty.json", r#"{ "Price": null }"#),
+        ],
+    );
+    let ctx = dir.join("ctx.json");
+    let ctx = ctx.to_str().unwrap();
+    let expr = "prop(\"Price\") * prop(\"Qty\")";
+
+    let row = dir.join("row.json");
+    let out = run(
+        &[
+            "eval",
+            expr,
+            "--context",
+            ctx,
+            "--row",
+            row.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert_eq!(
+        (out.code, out.stdout.as_str()),
+        (EXIT_OK, "value: 10\ntype: number\n")
+    );
+
+    let empty = dir.join("empty.json");
+    let out = run(
+        &[
+            "eval",
+            expr,
+            "--context",
+            ctx,
+            "--row",
+            empty.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert_eq!(out.stdout, "value: empty\ntype: number\n");
+}
+
+#[test]
+fn diagnostics_and_runtime_errors_exit_one() {
+    let out = run(&["eval", "prop(\"Price\")"], "");
+    assert_eq!(out.code, EXIT_FAILURE);
+    assert_eq!(out.stdout, "");
+    assert!(out.stderr.contains("--> <expr>:1:6"), "{}", out.stderr);
+
+    let out = run(&["eval", "1 / 0"], "");
+    assert_eq!(out.code, EXIT_FAILURE);
+    assert_eq!(out.stderr, "error: evaluation failed: division by zero\n");
+
+    let out = run(&["eval", "if(true, 1, 2)"], "");
+    assert_eq!(out.code, EXIT_FAILURE);
+    assert!(
+        out.stderr.contains("not supported by the evaluator yet"),
+        "{}",
+        out.stderr
+    );
+}
+
+#[test]
+fn invalid_rows_exit_two() {
+    let dir = temp_dir(
+        "eval-bad-row",
+        &[
+            (
+                "ctx.json",
+                r#"{ "properties": [{ "name": "Price", "type": "Number" }] }"#,
+            ),
+            ("row.json", r#"{ "Price": "2" }"#),
+        ],
+    );
+    let out = run(
+        &[
+            "eval",
+            "prop(\"Price\")",
+            "--context",
+            dir.join("ctx.json").to_str().unwrap(),
+            "--row",
+            dir.join("row.json").to_str().unwrap(),
+        ],
+        "",
+    );
+    assert_eq!(out.code, EXIT_USAGE);
+    assert!(
+        out.stderr.contains("`Price`: expected number, found \"2\""),
+        "{}",
+        out.stderr
+    );
+
+    assert_eq!(
+        run(&["eval", "1", "2"], "").stderr,
+        "error: `eval` takes one expression\n"
+    );
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::{run, temp_dir};
+use notion_formula_cli::{EXIT_FAILURE, EXIT_OK, EXIT_USAGE};
+
+#[test]
+fn stdin_is_formatted_to_stdout() {
+    let out = run(&["fmt"], "1+2");
+    assert_eq!((out.code, out.stdout.as_str()), (EXIT_OK, "1 + 2\n"));
+}
+
+#[test]
+fn format_options_map_to_format_config() {
+    let source = "if(true, \"aaaaaaaaaa\", \"bbbbbbbbbb\")";
+    let out = run(&["fmt", "--max-width", "20", "--indent-width=4"], source);
+    assert_eq!(
+        out.stdout,
+        "if(\n    true,\n    \"aaaaaaaaaa\",\n    \"bbbbbbbbbb\"\n)\n"
+    );
+
+    let out = run(
+        &["fmt", "--max-width", "20", "--closing-delim", "same-line"],
+        source,
+    );
+    assert_eq!(
+        out.stdout,
+        "if(\n  true,\n  \"aaaaaaaaaa\",\n  \"bbbbbbbbbb\")\n"
+    );
+
+    let broken = "if(\n  true,\n  1,\n  2\n)\n";
+    assert_eq!(run(&["fmt"], broken).stdout, broken);
+    assert_eq!(
+        run(&["fmt", "--collapse-fitting"], broken).stdout,
+        "if(true, 1, 2)\n"
+    );
+}
+
+#[test]
+fn check_lists_unformatted_files_without_writing() {
+    let dir = temp_dir(
+        "fmt-check",
+        &[("ok.formula", "1 + 2\n"), ("bad.formula", "1+2")],
+    );
+    let (ok, bad) = (dir.join("ok.formula"), dir.join("bad.formula"));
+    let out = run(
+        &[
+            "fmt",
+            "--check",
+            ok.to_str().unwrap(),
+            bad.to_str().unwrap(),
+        ],
+        "",
+    );
+
+    assert_eq!(out.code, EXIT_FAILURE);
+    assert_eq!(out.stdout, format!("{}\n", bad.to_str().unwrap()));
+    assert_eq!(out.stderr, "1 file not formatted\n");
+    assert_eq!(std::fs::read_to_string(&bad).unwrap(), "1+2");
+
+    let out = run(&["fmt", "--check", ok.to_str().unwrap()], "");
+    assert_eq!((out.code, out.stdout.as_str()), (EXIT_OK, ""));
+}
+
+#[test]
+fn write_rewrites_files_in_place() {
+    let dir = temp_dir(
+        "fmt-write",
+        &[("a.formula", "1+2"), ("b.formula", "3 * 4\n")],
+    );
+    let (a, b) = (dir.join("a.formula"), dir.join("b.formula"));
+    let out = run(
+        &["fmt", "--write", a.to_str().unwrap(), b.to_str().unwrap()],
+        "",
+    );
+
+    assert_eq!((out.code, out.stdout.as_str()), (EXIT_OK, ""));
+    assert_eq!(std::fs::read_to_string(&a).unwrap(), "1 + 2\n");
+    assert_eq!(std::fs::read_to_string(&b).unwrap(), "3 * 4\n");
+}
+
+#[test]
+fn syntax_errors_fail_but_other_files_are_still_formatted() {
+    let dir = temp_dir(
+        "fmt-syntax",
+        &[("broken.formula", "1 +"), ("ok.formula", "1+2")],
+    );
+    let (broken, ok) = (dir.join("broken.formula"), dir.join("ok.formula"));
+    let out = run(
+        &[
+            "fmt",
+            "--write",
+            broken.to_str().unwrap(),
+            ok.to_str().unwrap(),
+        ],
+        "",
+    );
+
+    assert_eq!(out.code, EXIT_FAILURE);
+    assert_eq!(
+        out.stderr,
+        format!(
+            "error: cannot format `{}`: it has syntax errors\n",
+            broken.to_str().unwrap()
+        )
+    );
+    assert_eq!(std::fs::read_to_string(&ok).unwrap(), "1 + 2\n");
+}
+
+#[test]
+fn invalid_fmt_arguments_exit_two() {
+    let out = run(&["fmt", "--write", "--check", "a.formula"], "");
+    assert_eq!(out.code, EXIT_USAGE);
+    assert_eq!(
+        out.stderr,
+        "error: `--write` and `--check` cannot be used together\n"
+    );
+
+    let out = run(&["fmt", "--write"], "1");
+    assert_eq!(out.stderr, "error: `--write` needs file paths, not stdin\n");
+
+    let out = run(&["fmt", "--indent-width", "two"], "1");
+    assert_eq!(out.code, EXIT_USAGE);
+    assert!(
+        out.stderr
+            .contains("`--indent-width` must be a non-negative integer")
+    );
+
+    let out = run(&["fmt", "--closing-delim", "nowhere"], "1");
+    assert_eq!(out.code, EXIT_USAGE);
+}
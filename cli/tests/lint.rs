@@ -0,0 +1,107 @@
+mod common;
+
+use common::{run, temp_dir};
+use notion_formula_cli::{EXIT_FAILURE, EXIT_OK, EXIT_USAGE};
+use serde_json::{Value, json};
+
+fn report(stdout: &str) -> Value {
+    serde_json::from_str(stdout).unwrap()
+}
+
+#[test]
+fn clean_corpus_reports_no_diagnostics() {
+    let out = run(&["lint"], r#"{ "a": "1 + 2", "b": "if(true, 1, 2)" }"#);
+    assert_eq!(out.code, EXIT_OK);
+    assert_eq!(
+        report(&out.stdout),
+        json!({ "formulas": 2, "formulas_with_errors": 0, "diagnostics": 0, "by_code": [], "results": [] })
+    );
+}
+
+#[test]
+fn diagnostics_are_grouped_by_code() {
+    let dir = temp_dir(
+        "lint-json",
+        &[
+            (
+                "corpus.json",
+                r#"{ "total": "prop(\"Price\") + prop(\"Tax\")", "broken": "1 +", "ok": "1" }"#,
+            ),
+            (
+                "ctx.json",
+                r#"{ "properties": [{ "name": "Price", "type": "Number" }] }"#,
+            ),
+        ],
+    );
+    let out = run(
+        &[
+            "lint",
+            dir.join("corpus.json").to_str().unwrap(),
+            "--context",
+            dir.join("ctx.json").to_str().unwrap(),
+        ],
+        "",
+    );
+
+    assert_eq!(out.code, EXIT_FAILURE);
+    assert_eq!(out.stderr, "2 diagnostics in 2 of 3 formulas\n");
+    assert_eq!(
+        report(&out.stdout),
+        json!({
+            "formulas": 3,
+            "formulas_with_errors": 2,
+            "diagnostics": 2,
+            "by_code": [
+                { "code": "missing_expr", "count": 1, "formulas": ["broken"] },
+                { "code": "semantic_error", "count": 1, "formulas": ["total"] },
+            ],
+            "results": [
+                {
+                    "id": "broken",
+                    "diagnostics": [{
+                        "code": "missing_expr",
+                        "message": "expected expression after `+`",
+                        "start": 2, "end": 3, "line": 1, "column": 3,
+                    }],
+                },
+                {
+                    "id": "total",
+                    "diagnostics": [{
+                        "code": "semantic_error",
+                        "message": "Unknown property: Tax",
+                        "start": 21, "end": 26, "line": 1, "column": 22,
+                    }],
+                },
+            ],
+        })
+    );
+}
+
+#[test]
+fn csv_corpus_keeps_row_order() {
+    let dir = temp_dir(
+        "lint-csv",
+        &[(
+            "corpus.csv",
+            "id,source\nz,\"prop(\"\"A\"\")\"\na,\"prop(\"\"B\"\")\"\n",
+        )],
+    );
+    let out = run(&["lint", dir.join("corpus.csv").to_str().unwrap()], "");
+    let report = report(&out.stdout);
+    assert_eq!(report["by_code"][0]["count"], 2);
+    assert_eq!(report["by_code"][0]["formulas"], json!(["z", "a"]));
+}
+
+#[test]
+fn invalid_corpus_exits_two() {
+    let out = run(&["lint"], "[]");
+    assert_eq!(out.code, EXIT_USAGE);
+    assert!(
+        out.stderr.starts_with("error: invalid corpus `<stdin>`"),
+        "{}",
+        out.stderr
+    );
+
+    let out = run(&["lint", "a.json", "b.json"], "");
+    assert_eq!(out.stderr, "error: `lint` takes one corpus file\n");
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{run, temp_dir};
+use notion_formula_cli::{EXIT_FAILURE, EXIT_OK, EXIT_USAGE};
+
+#[test]
+fn input_without_a_panic_exits_one() {
+    let out = run(&["minimize", "--target", "help"], "if(true, 1, 2)");
+    assert_eq!(
+        (out.code, out.stdout.as_str(), out.stderr.as_str()),
+        (EXIT_FAILURE, "", "help: the input does not panic\n")
+    );
+
+    let dir = temp_dir("minimize", &[("input.formula", "1 +")]);
+    let path = dir.join("input.formula");
+    let out = run(&["minimize", path.to_str().unwrap()], "");
+    assert_eq!(
+        (out.code, out.stderr.as_str()),
+        (EXIT_FAILURE, "analyze: the input does not panic\n")
+    );
+}
+
+#[test]
+fn bad_targets_and_files_are_usage_errors() {
+    let out = run(&["minimize", "--target", "nope"], "");
+    assert_eq!(out.code, EXIT_USAGE);
+    assert_eq!(
+        out.stderr,
+        "error: unknown target `nope` (expected one of: analyze, help, format, apply_edits)\n"
+    );
+
+    let out = run(&["minimize", "a", "b"], "");
+    assert_eq!(
+        (out.code, out.stderr.as_str()),
+        (EXIT_USAGE, "error: expected at most one FILE\n")
+    );
+
+    let out = run(&["minimize", "missing.formula"], "");
+    assert_eq!(out.code, EXIT_USAGE);
+    assert!(
+        out.stderr
+            .starts_with("error: cannot read `missing.formula`")
+    );
+
+    let out = run(&["minimize", "--help"], "");
+    assert_eq!(out.code, EXIT_OK);
+    assert!(out.stdout.starts_with("Usage: notion-formula minimize"));
+}
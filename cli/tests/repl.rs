@@ -0,0 +1,106 @@
+mod common;
+
+use common::{run, temp_dir};
+use notion_formula_cli::{EXIT_OK, EXIT_USAGE};
+
+#[test]
+fn evaluates_each_line() {
+    let out = run(&["repl"], "1 + 2\n\n[1, 2]\n");
+    assert_eq!(
+        (out.code, out.stdout.as_str()),
+        (EXIT_OK, "3 : number\n[1, 2] : number[]\n")
+    );
+}
+
+#[test]
+fn let_binds_session_variables() {
+    let out = run(
+        &["repl"],
+        "let x = 2 * 3\nx + 1\nlet x = x * 2\nx / 4\n:vars\n",
+    );
+    assert_eq!(
+        out.stdout,
+        "x : number\n7 : number\nx : number\n3 : number\nx = 12 : number\n"
+    );
+
+    let out = run(&["repl"], "let 1 = 2\nlet y\n");
+    assert_eq!(out.stdout, "");
+    assert!(out.stderr.contains("error: `1` is not a variable name"));
+    assert!(out.stderr.contains("error: expected `let <name> = <expr>`"));
+}
+
+#[test]
+fn non_ascii_variable_names_round_trip() {
+    let out = run(&["repl"], "let \u{345}x = 2\n\u{345}x + 1\nlet é = 3\né * 2\n");
+    assert!(!out.stderr.contains("error"), "{}", out.stderr);
+    assert_eq!(
+        out.stdout,
+        "\u{345}x : number\n3 : number\né : number\n6 : number\n"
+    );
+}
+
+#[test]
+fn variables_cannot_shadow_context_properties() {
+    let dir = temp_dir(
+        "repl-context",
+        &[
+            (
+                "ctx.json",
+                r#"{ "properties": [{ "name": "Price", "type": "Number" }] }"#,
+            ),
+            ("row.json", r#"{ "Price": 4 }"#),
+        ],
+    );
+    let ctx = dir.join("ctx.json");
+    let row = dir.join("row.json");
+    let out = run(
+        &[
+            "repl",
+            "--context",
+            ctx.to_str().unwrap(),
+            "--row",
+            row.to_str().unwrap(),
+        ],
+        "prop(\"Price\") / 2\nlet Price = 1\n",
+    );
+    assert_eq!(out.stdout, "2 : number\n");
+    assert!(out.stderr.contains("error: `Price` is a context property"));
+}
+
+#[test]
+fn type_and_ast_commands() {
+    let out = run(&["repl"], "let n = 1\n:type [n]\n:ast n + 2\n");
+    assert_eq!(
+        out.stdout,
+        "n : number\nnumber[]\nBinary + @0..13\n  Call prop @0..9\n    String \"n\" @5..8\n  Number 2 @12..13\n"
+    );
+}
+
+#[test]
+fn diagnostics_point_at_the_typed_line() {
+    let out = run(
+        &["repl"],
+        "let abc = 1\nabc + prop(\"Nope\")\n:nope\n:quit\n1\n",
+    );
+    assert_eq!(out.stdout, "abc : number\n");
+    // Carets sit under the typed line, not the rewritten `prop("abc") + prop("Nope")`.
+    assert!(
+        out.stderr.contains(
+            "error: Unknown property: Nope\n  | abc + prop(\"Nope\")\n  |            ^^^^^^\n"
+        ),
+        "{}",
+        out.stderr
+    );
+    assert!(out.stderr.contains("error: unknown command `:nope`"));
+    assert!(!out.stderr.contains("\x1b["));
+
+    let out = run(&["repl", "--color", "always"], "1 +\n");
+    assert!(out.stderr.contains("\x1b[1;31merror\x1b[0m"));
+}
+
+#[test]
+fn rejects_bad_arguments() {
+    assert_eq!(run(&["repl", "f.formula"], "").code, EXIT_USAGE);
+    let out = run(&["repl", "--color", "sometimes"], "");
+    assert_eq!(out.code, EXIT_USAGE);
+}
@@ -0,0 +1,129 @@
+//! Epoch milliseconds to local calendar fields, proleptic Gregorian.
+
+use crate::core::config::{TimeZone, WeekStart};
+
+pub(crate) const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// An instant read as local time in a timezone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct LocalDateTime {
+    pub(crate) year: i64,
+    /// 1-12.
+    pub(crate) month: u32,
+    /// Day of the month, 1-31.
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    pub(crate) millis: u32,
+    /// Days since 1970-01-01 in local time.
+    days: i64,
+}
+
+impl LocalDateTime {
+    pub(crate) fn new(instant: i64, timezone: &TimeZone) -> Self {
+        let local = instant.saturating_add(timezone.offset_millis_at(instant));
+        let days = local.div_euclid(MILLIS_PER_DAY);
+        let time = local.rem_euclid(MILLIS_PER_DAY) as u32;
+        let (year, month, day) = civil_from_days(days);
+        Self {
+            year,
+            month,
+            day,
+            hour: time / 3_600_000,
+            minute: time / 60_000 % 60,
+            second: time / 1000 % 60,
+            millis: time % 1000,
+            days,
+        }
+    }
+
+    /// 0 (Sunday) to 6 (Saturday).
+    pub(crate) fn weekday(&self) -> u32 {
+        weekday(self.days)
+    }
+
+    /// Day of the week counted from `week_start`: 1 to 7.
+    pub(crate) fn day_of_week(&self, week_start: WeekStart) -> u32 {
+        match week_start {
+            WeekStart::Monday => (self.weekday() + 6) % 7 + 1,
+            WeekStart::Sunday => self.weekday() + 1,
+        }
+    }
+
+    /// Day of the year, 0-based.
+    pub(crate) fn ordinal(&self) -> u32 {
+        (self.days - days_from_civil(self.year, 1, 1)) as u32
+    }
+
+    /// Week of the year: the ISO 8601 week for Monday starts (weeks spilling into the
+    /// neighbouring year count there), else the week holding January 1st is week 1.
+    pub(crate) fn week(&self, week_start: WeekStart) -> u32 {
+        match week_start {
+            WeekStart::Monday => {
+                let week = (self.ordinal() + 11 - self.day_of_week(WeekStart::Monday)) / 7;
+                if week == 0 {
+                    iso_weeks_in_year(self.year - 1)
+                } else if week > iso_weeks_in_year(self.year) {
+                    1
+                } else {
+                    week
+                }
+            }
+            WeekStart::Sunday => {
+                let jan1 = weekday(days_from_civil(self.year, 1, 1));
+                (self.ordinal() + jan1) / 7 + 1
+            }
+        }
+    }
+
+    /// The instant of this day's local midnight.
+    pub(crate) fn start_of_day(&self, timezone: &TimeZone) -> i64 {
+        timezone.instant_of_local(self.days * MILLIS_PER_DAY)
+    }
+}
+
+fn weekday(days: i64) -> u32 {
+    // 1970-01-01 was a Thursday.
+    (days + 4).rem_euclid(7) as u32
+}
+
+/// 53 when the year starts on a Thursday, or on a Wednesday in a leap year; else 52.
+fn iso_weeks_in_year(year: i64) -> u32 {
+    let jan1 = weekday(days_from_civil(year, 1, 1));
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    if jan1 == 4 || (leap && jan1 == 3) {
+        53
+    } else {
+        52
+    }
+}
+
+/// Days since 1970-01-01 of a calendar date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((i64::from(month) + 9) % 12) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The calendar date `days` after 1970-01-01: `(year, month, day)`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,95 @@
+//! Date builtins that depend on the user's timezone and week start: `now`, `today`, the date
+//! parts, and `formatDate`.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use analyzer::analysis::{FunctionCategory, FunctionSig, FunctionSigBuilder, Ty};
+
+use crate::core::config::{EvalConfig, WeekStart};
+use crate::core::errors::EvalError;
+use crate::core::functions::FunctionDef;
+use crate::core::types::Value;
+
+use super::civil::LocalDateTime;
+use super::format_date::format_date;
+
+pub(super) fn functions(config: EvalConfig) -> Vec<FunctionDef> {
+    let config = Arc::new(config);
+    let shared = || Arc::clone(&config);
+    let mut defs = vec![
+        FunctionDef::new(sig("now()", "now").returns(Ty::Date).build().unwrap()).with_impl({
+            let config = shared();
+            move |_| Ok(Some(Value::Date(now(&config))))
+        }),
+        FunctionDef::new(sig("today()", "today").returns(Ty::Date).build().unwrap()).with_impl({
+            let config = shared();
+            move |_| {
+                let local = LocalDateTime::new(now(&config), &config.timezone);
+                Ok(Some(Value::Date(local.start_of_day(&config.timezone))))
+            }
+        }),
+        FunctionDef::new(
+            sig("formatDate(date, format)", "formatDate")
+                .param("date", Ty::Date)
+                .param("format", Ty::String)
+                .returns(Ty::String)
+                .build()
+                .unwrap(),
+        )
+        .with_impl({
+            let config = shared();
+            move |args| match args {
+                [Some(Value::Date(instant)), Some(Value::Text(format))] => {
+                    Ok(Some(Value::Text(format_date(*instant, format, &config))))
+                }
+                [None, _] | [_, None] => Ok(None),
+                _ => Err(EvalError::TypeMismatch),
+            }
+        }),
+    ];
+    let parts: [(&str, DatePart); 7] = [
+        ("minute", |local, _| local.minute.into()),
+        ("hour", |local, _| local.hour.into()),
+        ("day", |local, week_start| {
+            local.day_of_week(week_start).into()
+        }),
+        ("date", |local, _| local.day.into()),
+        ("week", |local, week_start| local.week(week_start).into()),
+        ("month", |local, _| local.month.into()),
+        ("year", |local, _| local.year as f64),
+    ];
+    defs.extend(parts.into_iter().map(|(name, part)| {
+        let detail = format!("{name}(date)");
+        let sig = sig(&detail, name)
+            .param("date", Ty::Date)
+            .returns(Ty::Number);
+        let config = shared();
+        FunctionDef::new(sig.build().unwrap()).with_impl(move |args| match args {
+            [Some(Value::Date(instant))] => {
+                let local = LocalDateTime::new(*instant, &config.timezone);
+                Ok(Some(Value::Number(part(&local, config.week_start))))
+            }
+            [None] => Ok(None),
+            _ => Err(EvalError::TypeMismatch),
+        })
+    }));
+    defs
+}
+
+/// One number read off a local date, e.g. its hour.
+type DatePart = fn(&LocalDateTime, WeekStart) -> f64;
+
+fn sig(detail: &str, name: &str) -> FunctionSigBuilder {
+    FunctionSig::builder(name)
+        .category(FunctionCategory::Date)
+        .detail(detail)
+}
+
+fn now(config: &EvalConfig) -> i64 {
+    config.now.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as i64)
+    })
+}
@@ -0,0 +1,131 @@
+//! `formatDate(date, format)`: Moment.js-style format tokens, as Notion takes them.
+
+use crate::core::config::{EvalConfig, WeekStart, format_offset};
+
+use super::civil::LocalDateTime;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Longest first, so `MMMM` is not read as two `MM`s.
+const TOKENS: [&str; 34] = [
+    "YYYY", "MMMM", "dddd", "SSS", "MMM", "ddd", "Do", "YY", "MM", "DD", "dd", "HH", "hh", "mm",
+    "ss", "ww", "WW", "ZZ", "Q", "M", "D", "d", "E", "H", "h", "m", "s", "A", "a", "w", "W", "Z",
+    "X", "x",
+];
+
+/// `instant` in `config`'s timezone, written with `format`.
+///
+/// Text in `[brackets]` is copied without the brackets; characters that start no token are
+/// copied as is. `d` is the weekday from 0 (Sunday), `E` from 1 (the configured week start);
+/// `w`/`ww` follow the configured week start, `W`/`WW` are ISO 8601 weeks.
+pub(crate) fn format_date(instant: i64, format: &str, config: &EvalConfig) -> String {
+    let local = LocalDateTime::new(instant, &config.timezone);
+    let mut out = String::with_capacity(format.len() + 8);
+    let mut rest = format;
+    while let Some(c) = rest.chars().next() {
+        if c == '['
+            && let Some(end) = rest.find(']')
+        {
+            out.push_str(&rest[1..end]);
+            rest = &rest[end + 1..];
+            continue;
+        }
+        match TOKENS.iter().find(|token| rest.starts_with(**token)) {
+            Some(token) => {
+                write_token(&mut out, token, &local, instant, config);
+                rest = &rest[token.len()..];
+            }
+            None => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    out
+}
+
+fn write_token(
+    out: &mut String,
+    token: &str,
+    local: &LocalDateTime,
+    instant: i64,
+    config: &EvalConfig,
+) {
+    let month = MONTHS[local.month as usize - 1];
+    let weekday = WEEKDAYS[local.weekday() as usize];
+    let hour12 = (local.hour + 11) % 12 + 1;
+    let offset = format_offset(config.timezone.offset_minutes_at(instant));
+    let text = match token {
+        "YYYY" => format!("{:04}", local.year),
+        "YY" => format!("{:02}", local.year.rem_euclid(100)),
+        "Q" => ((local.month - 1) / 3 + 1).to_string(),
+        "MMMM" => month.to_string(),
+        "MMM" => month[..3].to_string(),
+        "MM" => format!("{:02}", local.month),
+        "M" => local.month.to_string(),
+        "DD" => format!("{:02}", local.day),
+        "D" => local.day.to_string(),
+        "Do" => ordinal(local.day),
+        "dddd" => weekday.to_string(),
+        "ddd" => weekday[..3].to_string(),
+        "dd" => weekday[..2].to_string(),
+        "d" => local.weekday().to_string(),
+        "E" => local.day_of_week(config.week_start).to_string(),
+        "HH" => format!("{:02}", local.hour),
+        "H" => local.hour.to_string(),
+        "hh" => format!("{hour12:02}"),
+        "h" => hour12.to_string(),
+        "mm" => format!("{:02}", local.minute),
+        "m" => local.minute.to_string(),
+        "ss" => format!("{:02}", local.second),
+        "s" => local.second.to_string(),
+        "SSS" => format!("{:03}", local.millis),
+        "A" => if local.hour < 12 { "AM" } else { "PM" }.to_string(),
+        "a" => if local.hour < 12 { "am" } else { "pm" }.to_string(),
+        "ww" => format!("{:02}", local.week(config.week_start)),
+        "w" => local.week(config.week_start).to_string(),
+        "WW" => format!("{:02}", local.week(WeekStart::Monday)),
+        "W" => local.week(WeekStart::Monday).to_string(),
+        "Z" => offset,
+        "ZZ" => offset.replace(':', ""),
+        "X" => instant.div_euclid(1000).to_string(),
+        "x" => instant.to_string(),
+        _ => unreachable!("every token is formatted"),
+    };
+    out.push_str(&text);
+}
+
+/// `1st`, `2nd`, `3rd`, `4th`, ..., `11th`, ..., `21st`.
+fn ordinal(day: u32) -> String {
+    let suffix = match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{day}{suffix}")
+}
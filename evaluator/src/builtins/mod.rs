@@ -0,0 +1,20 @@
+//! Builtin functions the evaluator implements itself, bound to an [`EvalConfig`].
+//!
+//! They sit behind the host functions: a registered function of the same name wins, as in
+//! [`FunctionRegistry::context`].
+
+mod civil;
+mod date;
+mod format_date;
+
+use crate::core::config::EvalConfig;
+use crate::core::functions::FunctionRegistry;
+
+/// The implemented builtins, reading dates as `config` says.
+pub(crate) fn builtin_functions(config: EvalConfig) -> FunctionRegistry {
+    let mut registry = FunctionRegistry::new();
+    for def in date::functions(config) {
+        registry.register(def);
+    }
+    registry
+}
@@ -0,0 +1,179 @@
+//! Locale settings for the date builtins.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// How date builtins read instants: in which timezone, with which first weekday, and at which
+/// instant `now()` is.
+///
+/// The default is UTC with Monday-first weeks (ISO 8601), Notion's own default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvalConfig {
+    /// The user's timezone: `today()`, `formatDate`, and the date parts (`hour`, `date`,
+    /// `week`, ...) read dates as local time here.
+    pub timezone: TimeZone,
+    /// First day of the week, for `week()` and `day()`.
+    pub week_start: WeekStart,
+    /// The instant `now()` and `today()` see, in epoch milliseconds; the system clock when
+    /// `None`. Fixing it makes a batch, or a test, reproducible.
+    pub now: Option<i64>,
+}
+
+/// The UTC offset of each instant: fixed, or resolved by the host.
+///
+/// There is no timezone database. A named zone (e.g. `Europe/Paris`) whose offset changes with
+/// daylight saving time goes through [`TimeZone::from_fn`], which asks the host for the offset in
+/// effect at every instant read.
+#[derive(Clone, Default)]
+pub struct TimeZone(Offsets);
+
+#[derive(Clone)]
+enum Offsets {
+    Fixed(i32),
+    Resolved(Arc<OffsetFn>),
+}
+
+/// Minutes east of UTC in effect at an instant in epoch milliseconds.
+type OffsetFn = dyn Fn(i64) -> i32 + Send + Sync;
+
+impl Default for Offsets {
+    fn default() -> Self {
+        Self::Fixed(0)
+    }
+}
+
+impl TimeZone {
+    pub const UTC: Self = Self(Offsets::Fixed(0));
+
+    /// Largest accepted offset either way: 18 hours, as in ISO 8601 and ECMAScript.
+    pub const MAX_OFFSET_MINUTES: i32 = 18 * 60;
+
+    /// `UTC + offset_minutes` at every instant; `None` beyond [`Self::MAX_OFFSET_MINUTES`].
+    pub fn from_offset_minutes(offset_minutes: i32) -> Option<Self> {
+        (offset_minutes.abs() <= Self::MAX_OFFSET_MINUTES)
+            .then_some(Self(Offsets::Fixed(offset_minutes)))
+    }
+
+    /// A zone whose offset is `offset_at(instant)` minutes: the instant is in epoch milliseconds,
+    /// and results beyond [`Self::MAX_OFFSET_MINUTES`] are clamped to it.
+    ///
+    /// Clones share the function, and only clones compare equal.
+    pub fn from_fn(offset_at: impl Fn(i64) -> i32 + Send + Sync + 'static) -> Self {
+        Self(Offsets::Resolved(Arc::new(offset_at)))
+    }
+
+    /// The offset of a zone made by [`Self::from_offset_minutes`] (or parsed).
+    pub fn fixed_offset_minutes(&self) -> Option<i32> {
+        match self.0 {
+            Offsets::Fixed(minutes) => Some(minutes),
+            Offsets::Resolved(_) => None,
+        }
+    }
+
+    /// Minutes east of UTC in effect at `instant` (epoch milliseconds).
+    pub fn offset_minutes_at(&self, instant: i64) -> i32 {
+        match &self.0 {
+            Offsets::Fixed(minutes) => *minutes,
+            Offsets::Resolved(offset_at) => {
+                offset_at(instant).clamp(-Self::MAX_OFFSET_MINUTES, Self::MAX_OFFSET_MINUTES)
+            }
+        }
+    }
+
+    /// Milliseconds to add to the UTC instant `instant` for local time.
+    pub(crate) fn offset_millis_at(&self, instant: i64) -> i64 {
+        i64::from(self.offset_minutes_at(instant)) * 60_000
+    }
+
+    /// The instant at which the local wall clock reads `local` (as epoch milliseconds). Within
+    /// the hour a transition skips or repeats, either neighbouring offset may be used.
+    pub(crate) fn instant_of_local(&self, local: i64) -> i64 {
+        let guess = local - self.offset_millis_at(local);
+        local - self.offset_millis_at(guess)
+    }
+}
+
+/// Offsets compare by value; resolved zones by identity.
+impl PartialEq for TimeZone {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Offsets::Fixed(a), Offsets::Fixed(b)) => a == b,
+            (Offsets::Resolved(a), Offsets::Resolved(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for TimeZone {}
+
+impl fmt::Debug for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Offsets::Fixed(minutes) => write!(f, "TimeZone({})", format_offset(minutes)),
+            Offsets::Resolved(_) => f.write_str("TimeZone(<resolved>)"),
+        }
+    }
+}
+
+/// `+05:30`, `-08:00`; UTC is `+00:00`.
+pub(crate) fn format_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+    format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Parses `UTC`, `Z`, `+05:30`, `+0530`, or `+05` (and the `-` forms).
+impl FromStr for TimeZone {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || format!("invalid timezone `{text}` (expected UTC or an offset like +05:30)");
+        if text.eq_ignore_ascii_case("utc") || text == "Z" {
+            return Ok(Self::UTC);
+        }
+        let (sign, rest) = match text.as_bytes().first() {
+            Some(b'+') => (1, &text[1..]),
+            Some(b'-') => (-1, &text[1..]),
+            _ => return Err(invalid()),
+        };
+        let digits = rest.replacen(':', "", usize::from(rest.len() == 5));
+        if !matches!(digits.len(), 2 | 4) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let hours: i32 = digits[..2].parse().map_err(|_| invalid())?;
+        let minutes: i32 = match digits.get(2..) {
+            Some("") | None => 0,
+            Some(minutes) => minutes.parse().map_err(|_| invalid())?,
+        };
+        if minutes >= 60 {
+            return Err(invalid());
+        }
+        Self::from_offset_minutes(sign * (hours * 60 + minutes)).ok_or_else(invalid)
+    }
+}
+
+/// The first day of the week.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WeekStart {
+    /// ISO 8601: weeks start on Monday, and week 1 holds the year's first Thursday.
+    #[default]
+    Monday,
+    /// Weeks start on Sunday, and week 1 holds January 1st (US convention).
+    Sunday,
+}
+
+impl FromStr for WeekStart {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_ascii_lowercase().as_str() {
+            "monday" => Ok(Self::Monday),
+            "sunday" => Ok(Self::Sunday),
+            _ => Err(format!(
+                "invalid week start `{text}` (expected monday or sunday)"
+            )),
+        }
+    }
+}
@@ -1,10 +1,18 @@
-use analyzer::analysis::{Property, Ty};
+use analyzer::analysis::{FunctionSig, Property, Ty};
 use std::collections::HashMap;
 
+use super::config::EvalConfig;
+use super::functions::{FunctionDef, FunctionRegistry};
+use crate::builtins::builtin_functions;
+
 #[derive(Clone, Debug)]
 pub struct EvalContext {
     pub properties: Vec<Property>,
     prop_index: HashMap<String, usize>,
+    functions: FunctionRegistry,
+    config: EvalConfig,
+    /// The implemented builtins, bound to `config`; host functions shadow them.
+    builtins: FunctionRegistry,
 }
 
 impl EvalContext {
@@ -17,9 +25,57 @@ impl EvalContext {
         Self {
             properties,
             prop_index,
+            functions: FunctionRegistry::default(),
+            config: EvalConfig::default(),
+            builtins: builtin_functions(EvalConfig::default()),
+        }
+    }
+
+    /// Makes the implemented functions of `functions` callable.
+    pub fn with_functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    pub fn functions(&self) -> &FunctionRegistry {
+        &self.functions
+    }
+
+    /// Reads dates in `config`'s timezone and week start.
+    pub fn with_config(mut self, config: EvalConfig) -> Self {
+        self.builtins = builtin_functions(config.clone());
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &EvalConfig {
+        &self.config
+    }
+
+    /// Index of the callable function `name`: host functions first, then builtins.
+    pub(crate) fn function_position(&self, name: &str) -> Option<usize> {
+        self.functions.position(name).or_else(|| {
+            let builtin = self.builtins.position(name)?;
+            Some(self.functions.iter().count() + builtin)
+        })
+    }
+
+    pub(crate) fn function_at(&self, idx: usize) -> &FunctionDef {
+        let hosts = self.functions.iter().count();
+        if idx < hosts {
+            self.functions.at(idx)
+        } else {
+            self.builtins.at(idx - hosts)
         }
     }
 
+    /// Signatures of every callable function, host functions first.
+    pub(crate) fn signatures(&self) -> Vec<FunctionSig> {
+        let mut signatures = self.functions.signatures();
+        signatures.extend(self.builtins.signatures());
+        signatures
+    }
+
     pub fn property(&self, name: &str) -> Option<&Property> {
         self.prop_index
             .get(name)
@@ -15,6 +15,19 @@ pub enum EvalError {
     PropertyDisabled,
 }
 
+impl std::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            EvalError::TypeMismatch => "type mismatch",
+            EvalError::DivideByZero => "division by zero",
+            EvalError::UnknownFunction => "unknown function",
+            EvalError::InvalidArgument => "invalid argument",
+            EvalError::CycleDetected => "cycle detected",
+            EvalError::PropertyDisabled => "property is disabled",
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SimpleEvalError {
     Provider(ProviderError),
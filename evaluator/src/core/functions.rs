@@ -0,0 +1,105 @@
+//! Host-defined functions, declared once for both type checking and evaluation.
+
+use std::fmt;
+use std::sync::Arc;
+
+use analyzer::analysis::{Context, FunctionSig, Property};
+
+use super::errors::EvalError;
+use super::types::Value;
+
+/// Runtime body of a host function: one row's argument values in, its value out.
+///
+/// `None` is an empty value, both for arguments and for the result.
+pub type FunctionImpl =
+    Arc<dyn Fn(&[Option<Value>]) -> Result<Option<Value>, EvalError> + Send + Sync>;
+
+/// A function signature with an optional runtime implementation.
+///
+/// Without an implementation the function still type-checks, but evaluating a call to it fails
+/// with [`EvalError::UnknownFunction`].
+#[derive(Clone)]
+pub struct FunctionDef {
+    pub sig: FunctionSig,
+    pub implementation: Option<FunctionImpl>,
+}
+
+impl FunctionDef {
+    pub fn new(sig: FunctionSig) -> Self {
+        Self {
+            sig,
+            implementation: None,
+        }
+    }
+
+    pub fn with_impl(
+        mut self,
+        implementation: impl Fn(&[Option<Value>]) -> Result<Option<Value>, EvalError>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        self.implementation = Some(Arc::new(implementation));
+        self
+    }
+}
+
+impl fmt::Debug for FunctionDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FunctionDef")
+            .field("sig", &self.sig)
+            .field("implementation", &self.implementation.is_some())
+            .finish()
+    }
+}
+
+/// The host functions of a workspace, feeding both the analyzer [`Context`] and the
+/// [`EvalContext`](super::context::EvalContext).
+///
+/// Lookups return the first definition with a name, as [`Context::function`] does.
+#[derive(Clone, Debug, Default)]
+pub struct FunctionRegistry {
+    defs: Vec<FunctionDef>,
+}
+
+impl FunctionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, def: FunctionDef) -> &mut Self {
+        self.defs.push(def);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&FunctionDef> {
+        self.defs.iter().find(|def| def.sig.name == name)
+    }
+
+    pub(crate) fn position(&self, name: &str) -> Option<usize> {
+        self.defs.iter().position(|def| def.sig.name == name)
+    }
+
+    pub(crate) fn at(&self, idx: usize) -> &FunctionDef {
+        &self.defs[idx]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FunctionDef> {
+        self.defs.iter()
+    }
+
+    pub fn signatures(&self) -> Vec<FunctionSig> {
+        self.defs.iter().map(|def| def.sig.clone()).collect()
+    }
+
+    /// An analyzer context with `properties`, these functions, and then the builtins (when the
+    /// analyzer is built with them), so host functions shadow builtins of the same name.
+    pub fn context(&self, properties: Vec<Property>) -> Context {
+        let builder = self.defs.iter().fold(Context::builder(), |builder, def| {
+            builder.func(def.sig.clone())
+        });
+        let mut ctx = builder.with_builtins().build();
+        ctx.properties = properties.into();
+        ctx
+    }
+}
@@ -1,4 +1,6 @@
+pub mod config;
 pub mod context;
 pub mod errors;
+pub mod functions;
 pub mod provider;
 pub mod types;
@@ -1,3 +1,5 @@
+use analyzer::analysis::Property;
+
 use crate::core::types::Value;
 
 #[repr(u8)]
@@ -29,6 +31,10 @@ pub(crate) struct BinaryPlan {
 pub(crate) enum ExecNode {
     LiteralF64(f64),
     LiteralAny(Value),
+    /// Column fetched from the provider; `slot` indexes `ExecPlan.props`.
+    Prop {
+        slot: usize,
+    },
     CastToF64 {
         input: Box<ExecNode>,
     },
@@ -37,9 +43,16 @@ pub(crate) enum ExecNode {
         left: Box<ExecNode>,
         right: Box<ExecNode>,
     },
+    /// Function call; `func` is an `EvalContext::function_position` index.
+    Call {
+        func: usize,
+        args: Vec<ExecNode>,
+    },
 }
 
 #[derive(Clone, Debug)]
 pub(crate) struct ExecPlan {
     pub root: ExecNode,
+    /// Distinct properties read by the plan, fetched once per batch before execution.
+    pub props: Vec<Property>,
 }
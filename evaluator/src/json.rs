@@ -0,0 +1,68 @@
+//! JSON ↔ [`Value`] conversion for hosts exchanging row values as JSON (feature `json`).
+//!
+//! Dates are epoch milliseconds; `null` is an empty value.
+
+use analyzer::analysis::Ty;
+use serde_json::Value as Json;
+
+use crate::core::types::Value;
+
+/// Converts a JSON value to a [`Value`] of type `ty`; `null` is empty.
+///
+/// Untyped properties (`Unknown`, generics) take the JSON value's natural type. The error names
+/// the expected type and the JSON found.
+pub fn value_from_json(json: &Json, ty: &Ty) -> Result<Option<Value>, String> {
+    if json.is_null() {
+        return Ok(None);
+    }
+    let value = match (ty, json) {
+        (Ty::Number, Json::Number(n)) => n.as_f64().map(Value::Number),
+        (Ty::String, Json::String(s)) => Some(Value::Text(s.clone())),
+        (Ty::Boolean, Json::Bool(b)) => Some(Value::Bool(*b)),
+        (Ty::Date, Json::Number(n)) => n.as_i64().map(Value::Date),
+        (Ty::List(item), Json::Array(items)) => Some(Value::List(
+            items
+                .iter()
+                .map(|json| {
+                    value_from_json(json, item)?.ok_or_else(|| "lists cannot hold null".into())
+                })
+                .collect::<Result<_, String>>()?,
+        )),
+        (Ty::Union(members), _) => members
+            .iter()
+            .find_map(|member| value_from_json(json, member).ok().flatten()),
+        (Ty::Unknown | Ty::Generic(_) | Ty::Null, _) => value_from_json(json, &json_ty(json)?)?,
+        _ => None,
+    };
+    value
+        .map(Some)
+        .ok_or_else(|| format!("expected {ty}, found {json}"))
+}
+
+/// JSON for a value; empty is `null` (and so are non-finite numbers).
+pub fn value_to_json(value: Option<&Value>) -> Json {
+    let Some(value) = value else {
+        return Json::Null;
+    };
+    match value {
+        Value::Number(n) => serde_json::Number::from_f64(*n).map_or(Json::Null, Json::Number),
+        Value::Text(s) => Json::String(s.clone()),
+        Value::Bool(b) => Json::Bool(*b),
+        Value::Date(ms) => Json::from(*ms),
+        Value::List(items) => Json::Array(items.iter().map(|v| value_to_json(Some(v))).collect()),
+    }
+}
+
+/// The type a JSON value naturally has, for untyped properties.
+fn json_ty(json: &Json) -> Result<Ty, String> {
+    Ok(match json {
+        Json::Number(_) => Ty::Number,
+        Json::String(_) => Ty::String,
+        Json::Bool(_) => Ty::Boolean,
+        Json::Array(items) => Ty::List(Box::new(match items.first() {
+            Some(first) => json_ty(first)?,
+            None => Ty::Unknown,
+        })),
+        Json::Null | Json::Object(_) => return Err(format!("unsupported value {json}")),
+    })
+}
@@ -1,13 +1,20 @@
+mod builtins;
 pub mod core;
 mod ir;
+#[cfg(feature = "json")]
+pub mod json;
 mod kernels;
 mod planner;
+mod row;
 mod runtime;
 
+pub use core::config::{EvalConfig, TimeZone, WeekStart};
 pub use core::context::EvalContext;
 pub use core::errors::{EvalError, ProviderError, SimpleEvalError};
+pub use core::functions::{FunctionDef, FunctionImpl, FunctionRegistry};
 pub use core::provider::Provider;
 pub use core::types::{Column, ColumnBlock, EvalBlock, Mask, NullMap, RowBatch, RowId, Value};
+pub use row::{evaluate_row, evaluate_row_with};
 pub use runtime::evaluator::Evaluator;
 
 #[cfg(test)]
@@ -13,7 +13,9 @@ pub(crate) fn lower_lit(lit: &analyzer::Lit) -> Result<ExecNode, PlanError> {
             .parse::<f64>()
             .map(ExecNode::LiteralF64)
             .map_err(|_| PlanError::InvalidArgument),
-        LitKind::String => Ok(ExecNode::LiteralAny(Value::Text(lit.symbol.text.clone()))),
+        LitKind::String => Ok(ExecNode::LiteralAny(Value::Text(
+            lit.symbol.text.to_string(),
+        ))),
         LitKind::Bool => match lit.symbol.text.as_str() {
             "true" => Ok(ExecNode::LiteralAny(Value::Bool(true))),
             "false" => Ok(ExecNode::LiteralAny(Value::Bool(false))),
@@ -32,7 +34,7 @@ pub(crate) fn lower_const_value(expr: &analyzer::ast::Expr) -> Result<Value, Pla
                 .parse::<f64>()
                 .map(Value::Number)
                 .map_err(|_| PlanError::InvalidArgument),
-            LitKind::String => Ok(Value::Text(lit.symbol.text.clone())),
+            LitKind::String => Ok(Value::Text(lit.symbol.text.to_string())),
             LitKind::Bool => match lit.symbol.text.as_str() {
                 "true" => Ok(Value::Bool(true)),
                 "false" => Ok(Value::Bool(false)),
@@ -1,4 +1,5 @@
 mod lower_lit;
+#[allow(clippy::module_inception)]
 mod planner;
 mod selectors;
 
@@ -11,6 +12,8 @@ pub(crate) enum PlanError {
     TypeMismatch,
     InvalidArgument,
     MissingTypeMapEntry,
+    PropertyDisabled,
+    UnknownFunction,
 }
 
 impl From<PlanError> for EvalError {
@@ -18,6 +21,8 @@ impl From<PlanError> for EvalError {
         match error {
             PlanError::TypeMismatch => Self::TypeMismatch,
             PlanError::InvalidArgument | PlanError::MissingTypeMapEntry => Self::InvalidArgument,
+            PlanError::PropertyDisabled => Self::PropertyDisabled,
+            PlanError::UnknownFunction => Self::UnknownFunction,
         }
     }
 }
@@ -1,4 +1,5 @@
-use analyzer::analysis::{Context as SemaContext, Ty, TypeMap, infer_expr_with_map};
+use analyzer::LitKind;
+use analyzer::analysis::{Context as SemaContext, Property, Ty, TypeMap, infer_expr_with_map};
 use analyzer::ast::{BinOpKind, Expr, ExprKind};
 
 use crate::core::context::EvalContext;
@@ -14,23 +15,48 @@ pub(crate) struct Planner;
 impl Planner {
     pub(crate) fn build(&self, expr: &Expr, ctx: &EvalContext) -> Result<ExecPlan, PlanError> {
         let sema_ctx = SemaContext {
-            properties: ctx.properties.clone(),
-            functions: vec![],
+            properties: ctx.properties.clone().into(),
+            functions: ctx.signatures().into(),
+            property_resolver: None,
+            property_lookup: Default::default(),
+            limits: Default::default(),
         };
         let mut map = TypeMap::default();
         let _ = infer_expr_with_map(expr, &sema_ctx, &mut map);
-        let root = self.lower(expr, &map)?;
-        Ok(ExecPlan { root })
+        let mut props = Vec::new();
+        let root = self.lower(expr, &map, ctx, &mut props)?;
+        Ok(ExecPlan { root, props })
     }
 
-    fn lower(&self, expr: &Expr, map: &TypeMap) -> Result<ExecNode, PlanError> {
+    fn lower(
+        &self,
+        expr: &Expr,
+        map: &TypeMap,
+        ctx: &EvalContext,
+        props: &mut Vec<Property>,
+    ) -> Result<ExecNode, PlanError> {
         match &expr.kind {
-            ExprKind::Group { inner } => self.lower(inner, map),
+            ExprKind::Group { inner } => self.lower(inner, map, ctx, props),
             ExprKind::Lit(lit) => lower_lit(lit),
             ExprKind::List { .. } => lower_const_value(expr).map(ExecNode::LiteralAny),
+            ExprKind::Call { callee, args } if callee.text == "prop" => {
+                lower_prop(args, ctx, props)
+            }
+            ExprKind::Call { callee, args } => {
+                self.lower_call(&callee.text, args.iter(), map, ctx, props)
+            }
+            // `receiver.name(args)` is `name(receiver, args)`.
+            ExprKind::MemberCall {
+                receiver,
+                method,
+                args,
+            } => {
+                let args = std::iter::once(&**receiver).chain(args);
+                self.lower_call(&method.text, args, map, ctx, props)
+            }
             ExprKind::Binary { op, left, right } if is_arithmetic_op(op.node) => {
-                let left_node = self.lower(left, map)?;
-                let right_node = self.lower(right, map)?;
+                let left_node = self.lower(left, map, ctx, props)?;
+                let right_node = self.lower(right, map, ctx, props)?;
                 let left_ty = inferred_ty_for_expr(map, left)?;
                 let right_ty = inferred_ty_for_expr(map, right)?;
                 let plan = select_binary_plan(op.node, &left_ty, &right_ty)?;
@@ -44,6 +70,56 @@ impl Planner {
             _ => Err(PlanError::InvalidArgument),
         }
     }
+
+    fn lower_call<'e>(
+        &self,
+        name: &str,
+        args: impl Iterator<Item = &'e Expr>,
+        map: &TypeMap,
+        ctx: &EvalContext,
+        props: &mut Vec<Property>,
+    ) -> Result<ExecNode, PlanError> {
+        let func = ctx
+            .function_position(name)
+            .filter(|&func| ctx.function_at(func).implementation.is_some())
+            .ok_or(PlanError::UnknownFunction)?;
+        let args = args
+            .map(|arg| self.lower(arg, map, ctx, props))
+            .collect::<Result<_, _>>()?;
+        Ok(ExecNode::Call { func, args })
+    }
+}
+
+/// `prop("Name")`: a slot in `props`, shared by repeated reads of the same property.
+fn lower_prop(
+    args: &[Expr],
+    ctx: &EvalContext,
+    props: &mut Vec<Property>,
+) -> Result<ExecNode, PlanError> {
+    let [arg] = args else {
+        return Err(PlanError::InvalidArgument);
+    };
+    let ExprKind::Lit(lit) = &arg.kind else {
+        return Err(PlanError::InvalidArgument);
+    };
+    if lit.kind != LitKind::String {
+        return Err(PlanError::InvalidArgument);
+    }
+    let property = ctx
+        .property(&lit.symbol.text)
+        .ok_or(PlanError::InvalidArgument)?;
+    if property.disabled_reason.is_some() {
+        return Err(PlanError::PropertyDisabled);
+    }
+
+    let slot = match props.iter().position(|p| p.name == property.name) {
+        Some(slot) => slot,
+        None => {
+            props.push(property.clone());
+            props.len() - 1
+        }
+    };
+    Ok(ExecNode::Prop { slot })
 }
 
 fn inferred_ty_for_expr(map: &TypeMap, expr: &Expr) -> Result<Ty, PlanError> {
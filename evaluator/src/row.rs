@@ -0,0 +1,84 @@
+//! One-row evaluation for hosts without an async runtime or a columnar property store.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::task::{Context, Poll, Waker};
+
+use analyzer::analysis::Property;
+use analyzer::ast::Expr;
+
+use crate::core::context::EvalContext;
+use crate::core::errors::{EvalError, ProviderError};
+use crate::core::provider::Provider;
+use crate::core::types::{Column, ColumnBlock, Mask, RowBatch, Value};
+use crate::runtime::evaluator::Evaluator;
+
+/// Evaluates `expr` for a single row; `Ok(None)` is an empty result.
+///
+/// `row` maps property names to values; missing names and `None` are empty.
+pub fn evaluate_row(
+    expr: &Expr,
+    properties: Vec<Property>,
+    row: &HashMap<String, Option<Value>>,
+) -> Result<Option<Value>, EvalError> {
+    evaluate_row_with(expr, &EvalContext::new(properties), row)
+}
+
+/// Like [`evaluate_row`], with an [`EvalContext`] that may also carry host functions.
+pub fn evaluate_row_with(
+    expr: &Expr,
+    ctx: &EvalContext,
+    row: &HashMap<String, Option<Value>>,
+) -> Result<Option<Value>, EvalError> {
+    let provider = RowProvider { row };
+    let evaluator = Evaluator::new(ctx, &provider);
+    let rows = [0];
+    let batch = RowBatch {
+        rows: &rows,
+        batch_id: 0,
+    };
+
+    let block = block_on(evaluator.eval(expr, batch)).expect("row provider does not fail");
+    if let Some((_, error)) = block.errors.into_iter().next() {
+        return Err(error);
+    }
+    if block.values.nulls[0] {
+        return Ok(None);
+    }
+    Ok(Some(match block.values.column {
+        Column::F64(values) => Value::Number(values[0]),
+        Column::Any(mut values) => values.swap_remove(0),
+    }))
+}
+
+/// Serves `prop(...)` from the row's values.
+struct RowProvider<'r> {
+    row: &'r HashMap<String, Option<Value>>,
+}
+
+impl Provider for RowProvider<'_> {
+    async fn get_prop<'a>(
+        &'a self,
+        prop: &'a Property,
+        batch: RowBatch<'a>,
+        _mask: Option<&'a Mask>,
+    ) -> Result<ColumnBlock, ProviderError> {
+        let value = self.row.get(&prop.name).cloned().flatten();
+        let len = batch.rows.len();
+        Ok(ColumnBlock {
+            nulls: vec![value.is_none(); len],
+            column: Column::Any(vec![value.unwrap_or(Value::Number(0.0)); len]),
+        })
+    }
+}
+
+/// Polls `future` to completion; `RowProvider` never suspends.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut future = std::pin::pin!(future);
+    loop {
+        if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+            return value;
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::core::functions::FunctionImpl;
+use crate::core::types::{Column, ColumnBlock, EvalBlock, Mask, Value};
+
+/// Calls `implementation` once per active row whose arguments all succeeded.
+///
+/// Argument errors are kept; rows with a failed argument fail without calling it.
+pub(crate) fn call_function(
+    implementation: &FunctionImpl,
+    args: &[EvalBlock],
+    len: usize,
+    mask: &Mask,
+) -> EvalBlock {
+    let mut rows = vec![Value::Number(0.0); len];
+    let mut nulls = vec![true; len];
+    let mut ok = vec![false; len];
+    let mut errors: Vec<_> = args.iter().flat_map(|arg| arg.errors.clone()).collect();
+
+    let mut row_args = Vec::with_capacity(args.len());
+    for (idx, active) in mask.iter().copied().enumerate() {
+        if !active || args.iter().any(|arg| !arg.ok[idx]) {
+            continue;
+        }
+        row_args.clear();
+        row_args.extend(args.iter().map(|arg| value_at(&arg.values, idx)));
+        match implementation(&row_args) {
+            Ok(value) => {
+                ok[idx] = true;
+                if let Some(value) = value {
+                    rows[idx] = value;
+                    nulls[idx] = false;
+                }
+            }
+            Err(error) => errors.push((idx, error)),
+        }
+    }
+
+    EvalBlock {
+        values: ColumnBlock {
+            column: Column::Any(rows),
+            nulls,
+        },
+        ok,
+        errors,
+    }
+}
+
+fn value_at(block: &ColumnBlock, idx: usize) -> Option<Value> {
+    if block.nulls[idx] {
+        return None;
+    }
+    Some(match &block.column {
+        Column::F64(values) => Value::Number(values[idx]),
+        Column::Any(values) => values[idx].clone(),
+    })
+}
@@ -7,14 +7,15 @@ use crate::core::types::{ColumnBlock, EvalBlock, Mask, RowBatch};
 use crate::ir::nodes::ExecNode;
 use crate::planner::Planner;
 
+use super::call::call_function;
 use super::cast::cast_block_to_f64;
 use super::dispatch::dispatch_binary;
-use super::literals::{literal_any, literal_f64};
+use super::literals::{literal_any, literal_f64, prop_column};
 
 #[derive(Debug)]
 pub struct Evaluator<'a, P: Provider> {
     ctx: &'a EvalContext,
-    _provider: &'a P,
+    provider: &'a P,
     planner: Planner,
 }
 
@@ -22,7 +23,7 @@ impl<'a, P: Provider> Evaluator<'a, P> {
     pub fn new(ctx: &'a EvalContext, provider: &'a P) -> Self {
         Self {
             ctx,
-            _provider: provider,
+            provider,
             planner: Planner,
         }
     }
@@ -47,7 +48,16 @@ impl<'a, P: Provider> Evaluator<'a, P> {
             Err(error) => return Ok(EvalBlock::fail_mask(&mask, error.into())),
         };
 
-        Ok(self.eval_node(&plan.root, batch.rows.len(), &mask))
+        let mut columns = Vec::with_capacity(plan.props.len());
+        for prop in &plan.props {
+            let column = self.provider.get_prop(prop, batch, Some(&mask)).await?;
+            if column.len() != batch.rows.len() || column.nulls.len() != batch.rows.len() {
+                return Err(ProviderError::BackendError);
+            }
+            columns.push(column);
+        }
+
+        Ok(self.eval_node(&plan.root, batch.rows.len(), &mask, &columns))
     }
 
     pub async fn eval_simple_fail_batch(
@@ -79,19 +89,35 @@ impl<'a, P: Provider> Evaluator<'a, P> {
         Ok(out.values)
     }
 
-    fn eval_node(&self, node: &ExecNode, len: usize, mask: &Mask) -> EvalBlock {
+    fn eval_node(
+        &self,
+        node: &ExecNode,
+        len: usize,
+        mask: &Mask,
+        columns: &[ColumnBlock],
+    ) -> EvalBlock {
         match node {
             ExecNode::LiteralF64(value) => literal_f64(*value, len, mask),
             ExecNode::LiteralAny(value) => literal_any(value.clone(), len, mask),
+            ExecNode::Prop { slot } => prop_column(&columns[*slot], mask),
             ExecNode::CastToF64 { input } => {
-                let input = self.eval_node(input, len, mask);
+                let input = self.eval_node(input, len, mask, columns);
                 cast_block_to_f64(input, mask)
             }
             ExecNode::Binary { key, left, right } => {
-                let left = self.eval_node(left, len, mask);
-                let right = self.eval_node(right, len, mask);
+                let left = self.eval_node(left, len, mask, columns);
+                let right = self.eval_node(right, len, mask, columns);
                 dispatch_binary(*key, left, right, mask)
             }
+            ExecNode::Call { func, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval_node(arg, len, mask, columns))
+                    .collect::<Vec<_>>();
+                let implementation = self.ctx.function_at(*func).implementation.as_ref();
+                let implementation = implementation.expect("planner only lowers implemented calls");
+                call_function(implementation, &args, len, mask)
+            }
         }
     }
 }
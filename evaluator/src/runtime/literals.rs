@@ -22,6 +22,25 @@ pub(crate) fn literal_f64(value: f64, len: usize, mask: &Mask) -> EvalBlock {
     }
 }
 
+/// A provider column: active rows are ok (null rows stay null), inactive rows are not.
+pub(crate) fn prop_column(column: &ColumnBlock, mask: &Mask) -> EvalBlock {
+    let mut nulls = column.nulls.clone();
+    for (idx, active) in mask.iter().copied().enumerate() {
+        if !active {
+            nulls[idx] = true;
+        }
+    }
+
+    EvalBlock {
+        values: ColumnBlock {
+            column: column.column.clone(),
+            nulls,
+        },
+        ok: mask.clone(),
+        errors: Vec::new(),
+    }
+}
+
 pub(crate) fn literal_any(value: Value, len: usize, mask: &Mask) -> EvalBlock {
     let mut rows = vec![Value::Number(0.0); len];
     let mut nulls = vec![true; len];
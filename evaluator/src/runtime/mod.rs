@@ -1,3 +1,4 @@
+pub(crate) mod call;
 pub(crate) mod cast;
 pub(crate) mod dispatch;
 pub mod evaluator;
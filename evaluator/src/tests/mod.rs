@@ -1,6 +1,7 @@
 use analyzer::analysis::Property;
 use analyzer::ast::Expr;
 use core::future::Future;
+use std::collections::HashMap;
 use std::task::{Context, Poll, Waker};
 
 use crate::core::context::EvalContext;
@@ -14,13 +15,13 @@ use crate::runtime::evaluator::Evaluator;
 struct DummyProvider;
 
 impl Provider for DummyProvider {
-    fn get_prop<'a>(
+    async fn get_prop<'a>(
         &'a self,
         _prop: &'a Property,
         _batch: RowBatch<'a>,
         _mask: Option<&'a Mask>,
-    ) -> impl Future<Output = Result<ColumnBlock, ProviderError>> + 'a {
-        async move { Err(ProviderError::NotFound) }
+    ) -> Result<ColumnBlock, ProviderError> {
+        Err(ProviderError::NotFound)
     }
 }
 
@@ -249,8 +250,8 @@ fn mask_disables_rows_for_binary_arith() {
         batch_id: 1,
     };
 
-    let out = block_on(evaluator.eval_with_mask(&expr, batch, vec![true, false, true, false]))
-        .unwrap();
+    let out =
+        block_on(evaluator.eval_with_mask(&expr, batch, vec![true, false, true, false])).unwrap();
 
     assert_eq!(out.ok, vec![true, false, true, false]);
     assert!(out.errors.is_empty());
@@ -260,3 +261,372 @@ fn mask_disables_rows_for_binary_arith() {
         Column::Any(_) => panic!("expected f64 column"),
     }
 }
+
+/// Serves `prop(...)` from fixed per-property columns and counts fetches.
+struct ColumnsProvider {
+    columns: Vec<(&'static str, ColumnBlock)>,
+    fetches: std::cell::Cell<usize>,
+}
+
+impl Provider for ColumnsProvider {
+    async fn get_prop<'a>(
+        &'a self,
+        prop: &'a Property,
+        _batch: RowBatch<'a>,
+        _mask: Option<&'a Mask>,
+    ) -> Result<ColumnBlock, ProviderError> {
+        self.fetches.set(self.fetches.get() + 1);
+        self.columns
+            .iter()
+            .find(|(name, _)| *name == prop.name)
+            .map(|(_, column)| column.clone())
+            .ok_or(ProviderError::NotFound)
+    }
+}
+
+fn number_property(name: &str) -> Property {
+    Property {
+        name: name.to_string(),
+        ty: analyzer::analysis::Ty::Number,
+        disabled_reason: None,
+        options: Vec::new(),
+    }
+}
+
+#[test]
+fn prop_reads_provider_columns_once_per_batch() {
+    let ctx = EvalContext::new(vec![number_property("Price")]);
+    let provider = ColumnsProvider {
+        columns: vec![(
+            "Price",
+            ColumnBlock {
+                column: Column::Any(vec![Value::Number(2.0), Value::Number(0.0)]),
+                nulls: vec![false, true],
+            },
+        )],
+        fetches: Default::default(),
+    };
+    let evaluator = Evaluator::new(&ctx, &provider);
+    let expr = parse_expr("prop(\"Price\") * prop(\"Price\") + 1");
+    let rows = [1_u64, 2];
+    let batch = RowBatch {
+        rows: &rows,
+        batch_id: 1,
+    };
+
+    let out = block_on(evaluator.eval(&expr, batch)).unwrap();
+
+    assert_eq!(provider.fetches.get(), 1);
+    assert_eq!(out.ok, vec![true, true]);
+    assert!(out.errors.is_empty());
+    assert_eq!(out.values.nulls, vec![false, true]);
+    match out.values.column {
+        Column::F64(values) => assert_eq!(values[0], 5.0),
+        Column::Any(_) => panic!("expected f64 column"),
+    }
+}
+
+#[test]
+fn prop_errors_surface_per_row_or_per_batch() {
+    let mut disabled = number_property("Secret");
+    disabled.disabled_reason = Some("hidden".to_string());
+    let ctx = EvalContext::new(vec![number_property("Price"), disabled]);
+    let provider = ColumnsProvider {
+        columns: vec![],
+        fetches: Default::default(),
+    };
+    let evaluator = Evaluator::new(&ctx, &provider);
+    let rows = [1_u64];
+    let batch = RowBatch {
+        rows: &rows,
+        batch_id: 1,
+    };
+
+    let out = block_on(evaluator.eval(&parse_expr("prop(\"Secret\")"), batch)).unwrap();
+    assert_eq!(out.errors, vec![(0, EvalError::PropertyDisabled)]);
+
+    let out = block_on(evaluator.eval(&parse_expr("prop(\"Missing\")"), batch)).unwrap();
+    assert_eq!(out.errors, vec![(0, EvalError::InvalidArgument)]);
+
+    let out = block_on(evaluator.eval(&parse_expr("prop(\"Price\")"), batch));
+    assert_eq!(out, Err(ProviderError::NotFound));
+    assert_eq!(provider.fetches.get(), 1);
+}
+
+#[test]
+fn evaluate_row_reads_one_row_of_values() {
+    let row = HashMap::from([
+        ("Price".to_string(), Some(Value::Number(2.5))),
+        ("Tax".to_string(), None),
+    ]);
+    let properties = || vec![number_property("Price"), number_property("Tax")];
+
+    let value = crate::evaluate_row(&parse_expr("prop(\"Price\") * 2"), properties(), &row);
+    assert_eq!(value, Ok(Some(Value::Number(5.0))));
+
+    let value = crate::evaluate_row(&parse_expr("prop(\"Tax\")"), properties(), &row);
+    assert_eq!(value, Ok(None));
+
+    let value = crate::evaluate_row(&parse_expr("prop(\"Price\") / 0"), properties(), &row);
+    assert_eq!(value, Err(EvalError::DivideByZero));
+}
+
+fn clamp_registry() -> crate::FunctionRegistry {
+    use analyzer::analysis::{FunctionSig, Ty};
+
+    let clamp = FunctionSig::builder("clamp")
+        .param("value", Ty::Number)
+        .param("min", Ty::Number)
+        .param("max", Ty::Number)
+        .returns(Ty::Number)
+        .build()
+        .unwrap();
+    let shout = FunctionSig::builder("shout")
+        .param("text", Ty::String)
+        .returns(Ty::String)
+        .build()
+        .unwrap();
+    let mut registry = crate::FunctionRegistry::new();
+    registry
+        .register(crate::FunctionDef::new(clamp).with_impl(|args| match args {
+            [
+                Some(Value::Number(value)),
+                Some(Value::Number(min)),
+                Some(Value::Number(max)),
+            ] => {
+                if min > max {
+                    return Err(EvalError::InvalidArgument);
+                }
+                Ok(Some(Value::Number(value.clamp(*min, *max))))
+            }
+            [None, _, _] => Ok(None),
+            _ => Err(EvalError::TypeMismatch),
+        }))
+        .register(crate::FunctionDef::new(shout));
+    registry
+}
+
+#[test]
+fn registered_functions_type_check_and_evaluate() {
+    let properties = || vec![number_property("Price"), number_property("Tax")];
+    let registry = clamp_registry();
+    let sema = registry.context(properties());
+    let analysis = analyzer::analyze(r#"clamp(prop("Price"), 0, 5) + 1"#, &sema);
+    assert!(
+        analysis.diagnostics.is_empty(),
+        "{:?}",
+        analysis.diagnostics
+    );
+    assert_eq!(analysis.output_type, analyzer::analysis::Ty::Number);
+    assert!(!analyzer::analyze("clamp(1)", &sema).diagnostics.is_empty());
+
+    let ctx = EvalContext::new(properties()).with_functions(registry);
+    let row = HashMap::from([("Price".to_string(), Some(Value::Number(12.0)))]);
+    let eval = |source: &str| crate::evaluate_row_with(&parse_expr(source), &ctx, &row);
+    assert_eq!(
+        eval(r#"clamp(prop("Price"), 0, 5) + 1"#),
+        Ok(Some(Value::Number(6.0)))
+    );
+    assert_eq!(eval(r#"clamp(prop("Tax"), 0, 5)"#), Ok(None));
+    assert_eq!(eval("clamp(1, 5, 0)"), Err(EvalError::InvalidArgument));
+    assert_eq!(eval("clamp(1 / 0, 0, 5)"), Err(EvalError::DivideByZero));
+}
+
+#[test]
+fn declared_only_or_unregistered_functions_do_not_evaluate() {
+    let registry = clamp_registry();
+    assert!(registry.get("shout").unwrap().implementation.is_none());
+    assert!(registry.context(Vec::new()).function("shout").is_some());
+
+    let ctx = EvalContext::new(Vec::new()).with_functions(registry);
+    let row = HashMap::new();
+    for source in [r#"shout("a")"#, "missing(1)"] {
+        assert_eq!(
+            crate::evaluate_row_with(&parse_expr(source), &ctx, &row),
+            Err(EvalError::UnknownFunction)
+        );
+    }
+}
+
+/// 2024-12-31T23:30:00Z, a Tuesday: already 2025 east of UTC.
+const NEW_YEARS_EVE: i64 = 1_735_687_800_000;
+
+fn date_eval(config: &crate::EvalConfig, source: &str) -> Result<Option<Value>, EvalError> {
+    let properties = vec![Property {
+        name: "Due".to_string(),
+        ty: analyzer::analysis::Ty::Date,
+        disabled_reason: None,
+        options: Vec::new(),
+    }];
+    let ctx = EvalContext::new(properties).with_config(config.clone());
+    let row = HashMap::from([("Due".to_string(), Some(Value::Date(NEW_YEARS_EVE)))]);
+    crate::evaluate_row_with(&parse_expr(source), &ctx, &row)
+}
+
+#[test]
+fn date_builtins_read_dates_in_the_configured_timezone() {
+    use crate::{EvalConfig, TimeZone, WeekStart};
+
+    let number = |n: f64| Ok(Some(Value::Number(n)));
+    let utc = EvalConfig::default();
+    let paris = EvalConfig {
+        timezone: "+01:00".parse().unwrap(),
+        ..EvalConfig::default()
+    };
+    for (config, year, month, date, hour, day) in [
+        (&utc, 2024.0, 12.0, 31.0, 23.0, 2.0),
+        (&paris, 2025.0, 1.0, 1.0, 0.0, 3.0),
+    ] {
+        assert_eq!(date_eval(config, r#"year(prop("Due"))"#), number(year));
+        assert_eq!(date_eval(config, r#"month(prop("Due"))"#), number(month));
+        assert_eq!(date_eval(config, r#"date(prop("Due"))"#), number(date));
+        assert_eq!(date_eval(config, r#"hour(prop("Due"))"#), number(hour));
+        assert_eq!(date_eval(config, r#"prop("Due").day()"#), number(day));
+        assert_eq!(
+            date_eval(config, r#"minute(prop("Due")) + 1"#),
+            number(31.0)
+        );
+    }
+
+    // ISO weeks by default: Tuesday 2024-12-31 is in 2025's first week.
+    assert_eq!(date_eval(&utc, r#"week(prop("Due"))"#), number(1.0));
+    let sunday = EvalConfig {
+        week_start: WeekStart::Sunday,
+        ..utc
+    };
+    assert_eq!(date_eval(&sunday, r#"week(prop("Due"))"#), number(53.0));
+    assert_eq!(date_eval(&sunday, r#"day(prop("Due"))"#), number(3.0));
+
+    let tz = |text: &str| text.parse::<TimeZone>();
+    assert_eq!(tz("UTC"), Ok(TimeZone::UTC));
+    assert_eq!(
+        tz("-0530").map(|tz| tz.fixed_offset_minutes()),
+        Ok(Some(-330))
+    );
+    assert_eq!(tz("+09").map(|tz| tz.offset_minutes_at(0)), Ok(540));
+    assert!(tz("+19:00").is_err() && tz("+05:60").is_err() && tz("CET").is_err());
+}
+
+#[test]
+fn now_today_and_format_date_follow_the_config() {
+    use crate::EvalConfig;
+
+    let at = |timezone: &str| EvalConfig {
+        timezone: timezone.parse().unwrap(),
+        now: Some(NEW_YEARS_EVE),
+        ..EvalConfig::default()
+    };
+    let date = |ms: i64| Ok(Some(Value::Date(ms)));
+    let text = |text: &str| Ok(Some(Value::Text(text.into())));
+
+    assert_eq!(date_eval(&at("+01:00"), "now()"), date(NEW_YEARS_EVE));
+    // Local midnight: 2025-01-01T00:00+01:00, and 2024-12-31T00:00-05:00.
+    assert_eq!(date_eval(&at("+01:00"), "today()"), date(1_735_686_000_000));
+    assert_eq!(date_eval(&at("-05:00"), "today()"), date(1_735_621_200_000));
+
+    assert_eq!(
+        date_eval(&at("+05:30"), r#"formatDate(now(), "YYYY-MM-DD HH:mm Z")"#),
+        text("2025-01-01 05:00 +05:30")
+    );
+    assert_eq!(
+        date_eval(
+            &EvalConfig::default(),
+            r#"prop("Due").formatDate("dddd, MMMM Do [at] h:mm A, [week] W")"#
+        ),
+        text("Tuesday, December 31st at 11:30 PM, week 1")
+    );
+    assert_eq!(
+        date_eval(
+            &EvalConfig::default(),
+            r#"formatDate(prop("Missing"), "YYYY")"#
+        ),
+        Err(EvalError::InvalidArgument)
+    );
+    assert_eq!(
+        date_eval(&EvalConfig::default(), "year(1)"),
+        Err(EvalError::TypeMismatch)
+    );
+}
+
+#[test]
+fn resolved_timezones_follow_daylight_saving_time() {
+    use crate::{EvalConfig, TimeZone};
+
+    // Europe/Paris in 2026: UTC+2 from 2026-03-29T01:00Z to 2026-10-25T01:00Z, else UTC+1.
+    let paris = TimeZone::from_fn(|instant| {
+        if (1_774_746_000_000..1_792_890_000_000).contains(&instant) {
+            120
+        } else {
+            60
+        }
+    });
+    assert_eq!(paris, paris.clone());
+    assert_ne!(paris, TimeZone::from_fn(|_| 60));
+    assert_eq!(paris.fixed_offset_minutes(), None);
+
+    let at = |now: i64| EvalConfig {
+        timezone: paris.clone(),
+        now: Some(now),
+        ..EvalConfig::default()
+    };
+    let date = |ms: i64| Ok(Some(Value::Date(ms)));
+    let text = |text: &str| Ok(Some(Value::Text(text.into())));
+
+    // Half an hour either side of the switch, an hour of local time apart.
+    let format = r#"formatDate(now(), "YYYY-MM-DD HH:mm Z")"#;
+    assert_eq!(
+        date_eval(&at(1_774_744_200_000), format),
+        text("2026-03-29 01:30 +01:00")
+    );
+    assert_eq!(
+        date_eval(&at(1_774_747_800_000), format),
+        text("2026-03-29 03:30 +02:00")
+    );
+    assert_eq!(
+        date_eval(&at(1_774_747_800_000), "hour(now())"),
+        Ok(Some(Value::Number(3.0)))
+    );
+
+    // Midnight before the switch is at UTC+1, midnight after it at UTC+2.
+    assert_eq!(
+        date_eval(&at(1_774_785_600_000), "today()"),
+        date(1_774_738_800_000)
+    );
+    assert_eq!(
+        date_eval(&at(1_774_872_000_000), "today()"),
+        date(1_774_821_600_000)
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_values_follow_property_types() {
+    use crate::json::{value_from_json, value_to_json};
+    use analyzer::analysis::Ty;
+    use serde_json::json;
+
+    let tags = Ty::List(Box::new(Ty::String));
+    assert_eq!(
+        value_from_json(&json!(["a"]), &tags),
+        Ok(Some(Value::List(vec![Value::Text("a".into())])))
+    );
+    assert_eq!(
+        value_from_json(&json!(1_700_000_000_000_i64), &Ty::Date),
+        Ok(Some(Value::Date(1_700_000_000_000)))
+    );
+    assert_eq!(value_from_json(&json!(null), &Ty::Number), Ok(None));
+    assert_eq!(
+        value_from_json(&json!(true), &Ty::Unknown),
+        Ok(Some(Value::Bool(true)))
+    );
+    assert_eq!(
+        value_from_json(&json!("2"), &Ty::Number),
+        Err("expected number, found \"2\"".into())
+    );
+
+    assert_eq!(value_to_json(None), json!(null));
+    assert_eq!(
+        value_to_json(Some(&Value::List(vec![Value::Number(1.0), Value::Date(5)]))),
+        json!([1.0, 5])
+    );
+}
@@ -2,7 +2,7 @@
 /* prettier-ignore */
 // AUTO-GENERATED: `cargo run -p analyzer_wasm --bin export_ts`
 
-export type Ty = "Number" | "String" | "Boolean" | "Date" | { "List": Ty };
+export type Ty = "Number" | "String" | "Boolean" | "Date" | { "List": Ty } | "Unknown";
 
 export type Property = { 
 /**
@@ -12,23 +12,62 @@ name: string,
 /**
  * Declared property type.
  */
-type: Ty, };
+type: Ty, 
+/**
+ * Option names of a select, status, or multi-select property, completed inside string
+ * literals compared with it.
+ */
+options?: Array<string> | null, };
+
+export type PositionEncoding = "utf-8" | "utf-16";
+
+export type PropertyLookup = "exact" | "lenient";
+
+export type FormulaLimits = { max_length?: number | null, max_depth?: number | null, };
+
+export type AnalyzerConfig = { properties: Array<Property>, preferred_limit: number | null, 
+/**
+ * Defaults to `"utf-16"`.
+ */
+position_encoding: PositionEncoding | null, 
+/**
+ * Attach per-phase [`Timings`] to `analyze` and `help` results. Defaults to `false`.
+ */
+debug_timings?: boolean | null, 
+/**
+ * How `prop("...")` names match property names. Defaults to `"exact"`.
+ */
+property_lookup?: PropertyLookup | null, 
+/**
+ * Caps on formula size; uncapped by default.
+ */
+limits?: FormulaLimits | null, };
+
+export type ConfigIssue = { 
+/**
+ * Field path, e.g. `properties[2].type.List`; empty for the config value itself.
+ */
+path: string, message: string, };
 
-export type AnalyzerConfig = { properties: Array<Property>, preferred_limit: number | null, };
+export type ConfigValidationResult = { 
+/**
+ * Empty when the config is valid.
+ */
+issues: Array<ConfigIssue>, };
 
 export type Span = { 
 /**
- * Start offset in UTF-16 code units.
+ * Start offset.
  */
 start: number, 
 /**
- * End offset in UTF-16 code units (exclusive).
+ * End offset (exclusive).
  */
 end: number, };
 
 export type TextEdit = { 
 /**
- * Replace range in the original document (UTF-16, half-open).
+ * Replace range in the original document (half-open).
  */
 range: Span, 
 /**
@@ -38,15 +77,25 @@ new_text: string, };
 
 export type CodeAction = { title: string, 
 /**
- * Edits are in original-document coordinates (UTF-16).
+ * Edits are in original-document coordinates.
  */
 edits: Array<TextEdit>, };
 
-export type DiagnosticKind = "error";
+export type PropertyReference = { 
+/**
+ * Property name with escapes resolved.
+ */
+name: string, 
+/**
+ * Spans of every `prop("...")` literal naming it (quotes included), in source order.
+ */
+spans: Array<Span>, };
+
+export type DiagnosticKind = "error" | "warning";
 
 export type Diagnostic = { kind: DiagnosticKind, message: string, 
 /**
- * Location in the source text (UTF-16 span).
+ * Location in the source text.
  */
 span: Span, 
 /**
@@ -64,7 +113,7 @@ actions: Array<CodeAction>, };
 
 export type Token = { kind: string, text: string, 
 /**
- * Location in the source text (UTF-16 span).
+ * Location in the source text.
  */
 span: Span, };
 
@@ -74,11 +123,25 @@ export type AnalyzeResult = { diagnostics: Array<Diagnostic>, tokens: Array<Toke
  *
  * Never nullable. Unknown/failed inference is represented as `"unknown"`.
  */
-output_type: string, };
+output_type: string, 
+/**
+ * Present only when `AnalyzerConfig.debug_timings` is set.
+ */
+timings?: Timings, };
+
+export type Timings = { lex: number, parse: number, infer: number, complete: number, };
+
+export type FormulaSource = { 
+/**
+ * Caller-chosen key (e.g. the formula property id), echoed back in the result.
+ */
+id: string, source: string, };
+
+export type BatchAnalyzeItem = { id: string, diagnostics: Array<Diagnostic>, output_type: string, };
 
 export type ApplyResult = { source: string, 
 /**
- * Cursor position in the updated document (UTF-16).
+ * Cursor position in the updated document.
  */
 cursor: number, };
 
@@ -88,27 +151,278 @@ export type SignatureItem = { segments: Array<DisplaySegment>, };
 
 export type SignatureHelp = { signatures: Array<SignatureItem>, active_signature: number, active_parameter: number, };
 
-export type CompletionItemKind = "FunctionGeneral" | "FunctionText" | "FunctionNumber" | "FunctionDate" | "FunctionPeople" | "FunctionList" | "FunctionSpecial" | "Builtin" | "Property" | "Operator";
+export type CompletionItemKind = "FunctionGeneral" | "FunctionText" | "FunctionNumber" | "FunctionDate" | "FunctionPeople" | "FunctionList" | "FunctionSpecial" | "Keyword" | "Constant" | "Property" | "Variable" | "Operator" | "Snippet";
+
+export type MatchMode = "fuzzy" | "prefix" | "contains";
+
+export type MatchStrength = "fuzzy" | "contains" | "prefix" | "exact";
+
+export type KindBoost = { kind: CompletionItemKind, boost: number, };
+
+export type RankingConfig = { 
+/**
+ * Weakest match that can be preferred. Defaults to `fuzzy`.
+ */
+preferred_min_match?: MatchStrength | null, 
+/**
+ * Per-kind scores; higher kinds sort and are preferred first. Defaults to none.
+ */
+kind_boosts?: Array<KindBoost> | null, 
+/**
+ * Type score of an item the expected argument type accepts. Defaults to `2`.
+ */
+type_match_bonus?: number | null, 
+/**
+ * Type score of an item with an unknown result type. Defaults to `0`.
+ */
+type_unknown_bonus?: number | null, 
+/**
+ * Type score of an item the expected argument type rejects. Defaults to `-1`.
+ */
+type_mismatch_bonus?: number | null, };
+
+export type CompletionConfig = { 
+/**
+ * Overrides `AnalyzerConfig.preferred_limit`.
+ */
+preferred_limit?: number | null, 
+/**
+ * Snippet mode: insert `name()` with the cursor inside the parens. Defaults to `true`.
+ */
+call_parens?: boolean | null, 
+/**
+ * Group items toward the expected argument type inside calls. Defaults to `true`.
+ */
+type_ranking?: boolean | null, 
+/**
+ * Item kinds to drop from the results.
+ */
+exclude_kinds?: Array<CompletionItemKind> | null, 
+/**
+ * How the query matches labels. Defaults to `fuzzy`.
+ */
+match_mode?: MatchMode | null, 
+/**
+ * Weights behind the item order and `preferred_indices`; omitted fields keep the defaults.
+ */
+ranking?: RankingConfig | null, 
+/**
+ * Skip ranking: items stay in catalog order and `preferred_indices` is empty. Defaults to
+ * `false`.
+ */
+raw?: boolean | null, };
+
+export type ClosingDelim = "own_line" | "same_line";
+
+export type FormatConfig = { 
+/**
+ * Spaces per indentation level. Defaults to `2`.
+ */
+indent_width?: number | null, 
+/**
+ * Preferred maximum line width (UTF-8 bytes) before breaking. Defaults to `80`.
+ */
+max_width?: number | null, 
+/**
+ * Wrap own-line `//` comments wider than `max_width`. Defaults to `false`.
+ */
+wrap_comments?: boolean | null, 
+/**
+ * Re-join broken calls and lists that fit on one line. Defaults to `false`.
+ */
+collapse_fitting?: boolean | null, 
+/**
+ * Defaults to `"own_line"`.
+ */
+closing_delim?: ClosingDelim | null, };
 
 export type CompletionItem = { label: string, kind: CompletionItemKind, insert_text: string, 
 /**
- * Primary edit to apply in the original document (UTF-16), if available.
+ * Primary edit to apply in the original document, if available.
  */
 primary_edit: TextEdit | null, 
 /**
- * Cursor position in the updated document after applying edits (UTF-16).
+ * Cursor position in the updated document after applying edits.
  */
 cursor: number | null, 
 /**
- * Additional edits to apply in the original document (UTF-16).
+ * Additional edits to apply in the original document.
+ */
+additional_edits: Array<TextEdit>, detail: string | null, is_disabled: boolean, disabled_reason: string | null, 
+/**
+ * How well the typed query matches the label; `null` without a query or a match.
  */
-additional_edits: Array<TextEdit>, detail: string | null, is_disabled: boolean, disabled_reason: string | null, };
+match_strength: MatchStrength | null, };
 
 export type CompletionResult = { items: Array<CompletionItem>, 
 /**
- * Replace range in the original document (UTF-16).
+ * Replace range in the original document.
  */
 replace: Span, preferred_indices: Array<number>, };
 
-export type HelpResult = { completion: CompletionResult, signature_help: SignatureHelp | null, };
+export type CompletionReplace = { 
+/**
+ * Range a completion would replace (the word being typed, or empty at the cursor).
+ */
+replace: Span, 
+/**
+ * The word being typed, lowercased with `_` removed; `null` when empty or not ASCII.
+ */
+query: string | null, };
+
+export type HelpResult = { completion: CompletionResult, signature_help: SignatureHelp | null, 
+/**
+ * Present only when `AnalyzerConfig.debug_timings` is set.
+ */
+timings?: Timings, };
+
+export type HoverResult = { 
+/**
+ * Hovered range in the original document.
+ */
+range: Span, 
+/**
+ * Inferred type rendered for UI (`"unknown"` when inference fails).
+ */
+ty: string, 
+/**
+ * Signature rendered for the call site, when hovering a known function name.
+ */
+signature: SignatureItem | null, 
+/**
+ * Function documentation, when hovering a known function name.
+ */
+docs: string | null, };
+
+export type TypeAtResult = { 
+/**
+ * Range of the smallest expression enclosing the cursor.
+ */
+range: Span, 
+/**
+ * Inferred type rendered for UI (`"unknown"` when inference fails).
+ */
+ty: string, };
+
+export type AstNode = { "kind": "Ident", span: Span, name: string, } | { "kind": "Group", span: Span, inner: AstNode, } | { "kind": "List", span: Span, items: Array<AstNode>, } | { "kind": "Call", span: Span, callee: string, args: Array<AstNode>, } | { "kind": "MemberCall", span: Span, receiver: AstNode, method: string, args: Array<AstNode>, } | { "kind": "Number", span: Span, value: number, } | { "kind": "String", span: Span, value: string, } | { "kind": "Boolean", span: Span, value: boolean, } | { "kind": "Unary", span: Span, 
+/**
+ * Operator as written (`"-"`, `"!"`, `"not"`).
+ */
+op: string, expr: AstNode, } | { "kind": "Binary", span: Span, 
+/**
+ * Operator as written (e.g. `"+"`, `"&&"`).
+ */
+op: string, left: AstNode, right: AstNode, } | { "kind": "Ternary", span: Span, cond: AstNode, then: AstNode, otherwise: AstNode, } | { "kind": "Error", span: Span, };
+
+export type AstResult = { root: AstNode, 
+/**
+ * Lex and parse diagnostics only (no type checking).
+ */
+diagnostics: Array<Diagnostic>, };
+
+export type SemanticTokensLegend = { token_types: Array<string>, token_modifiers: Array<string>, };
+
+export type CmSeverity = "error" | "warning" | "info" | "hint";
+
+export type CmChange = { from: number, to: number, insert: string, };
+
+export type CmAction = { name: string, changes: Array<CmChange>, };
+
+export type CmDiagnostic = { from: number, to: number, severity: CmSeverity, message: string, source: string, actions: Array<CmAction>, };
+
+export type CmCompletion = { label: string, detail: string | null, 
+/**
+ * CodeMirror icon type: `function`, `property`, `constant`, or `keyword`.
+ */
+type: string, 
+/**
+ * Group header (`Recommended` for the preferred items).
+ */
+section: string, 
+/**
+ * Positive for preferred items, in preference order.
+ */
+boost: number, changes: Array<CmChange>, 
+/**
+ * Cursor after applying `changes`, when known.
+ */
+selection: number | null, };
+
+export type CmCompletionResult = { from: number, to: number, options: Array<CmCompletion>, };
+
+export type CmDecoration = { from: number, to: number, class: string, };
+
+export type CodeMirrorResult = { diagnostics: Array<CmDiagnostic>, 
+/**
+ * `null` when there are no enabled items.
+ */
+completion: CmCompletionResult | null, decorations: Array<CmDecoration>, };
+
+export type MonacoRange = { startLineNumber: number, startColumn: number, endLineNumber: number, endColumn: number, };
+
+export type MonacoMarker = { startLineNumber: number, startColumn: number, endLineNumber: number, endColumn: number, 
+/**
+ * `MarkerSeverity` value (`8` = error).
+ */
+severity: number, message: string, source: string, };
+
+export type MonacoTextEdit = { range: MonacoRange, text: string, };
+
+export type MonacoCompletionItem = { label: string, 
+/**
+ * `languages.CompletionItemKind` value (`Function`, `Property`, `Constant`, `Operator`).
+ */
+kind: number, detail: string | null, 
+/**
+ * A snippet with `$0` at the cursor when `insert_text_rules` is `4` (`InsertAsSnippet`).
+ */
+insertText: string, insertTextRules: number, range: MonacoRange, additionalTextEdits: Array<MonacoTextEdit>, 
+/**
+ * Keeps the analyzer's order (preferred items first).
+ */
+sortText: string, };
+
+export type MonacoParameterInformation = { label: [number, number], };
+
+export type MonacoSignatureInformation = { label: string, parameters: Array<MonacoParameterInformation>, };
+
+export type MonacoSignatureHelp = { signatures: Array<MonacoSignatureInformation>, activeSignature: number, activeParameter: number, };
+
+export type MonacoResult = { markers: Array<MonacoMarker>, suggestions: Array<MonacoCompletionItem>, signature_help: MonacoSignatureHelp | null, };
+
+export type ErrorCode = "invalid_config" | "invalid_completion_config" | "invalid_format_config" | "invalid_rename" | "invalid_edits" | "invalid_batch" | "invalid_notion_schema" | "invalid_notion_expression" | "invalid_edit_range" | "edit_not_on_char_boundary" | "overlapping_edits" | "unsorted_edits" | "invalid_cursor" | "invalid_range" | "format_error" | "serialize_error" | "internal_error";
+
+export type InternalError = { 
+/**
+ * Panic message with its Rust source location.
+ */
+message: string, 
+/**
+ * JS stack at the panic, truncated; one frame per line.
+ */
+backtrace: string, };
+
+export type FunctionCategory = "General" | "Text" | "Number" | "Date" | "People" | "List" | "Special";
+
+export type ParamInfo = { name: string, 
+/**
+ * Expected type rendered for UI (generics render as `T0`, `T1`, ...).
+ */
+ty: string, optional: boolean, };
+
+export type ParamShapeInfo = { head: Array<ParamInfo>, repeat: Array<ParamInfo>, tail: Array<ParamInfo>, repeat_min_groups: number, };
+
+export type BuiltinFunction = { name: string, category: FunctionCategory, params: ParamShapeInfo, 
+/**
+ * Declared return type rendered for UI.
+ */
+ret: string, 
+/**
+ * Function documentation.
+ */
+docs: string, 
+/**
+ * Whether `receiver.name(...)` postfix-call sugar is supported.
+ */
+postfix: boolean, };
 
@@ -3,16 +3,52 @@ import type {
   AnalyzeResult,
   AnalyzerConfig,
   ApplyResult,
+  AstResult,
+  BatchAnalyzeItem,
+  BuiltinFunction,
+  CodeAction,
+  CodeMirrorResult,
+  CompletionConfig,
   CompletionItem as CompletionItemDto,
+  ConfigValidationResult,
+  ErrorCode,
+  FormatConfig,
+  FormulaSource,
   HelpResult,
+  HoverResult,
+  InternalError,
+  MonacoResult,
+  Property,
+  PropertyReference,
+  SemanticTokensLegend,
   SignatureHelp as SignatureHelpDto,
+  Span,
   TextEdit,
+  TypeAtResult,
 } from "./generated/wasm_dto";
 
 export type { AnalyzerConfig, Span } from "./generated/wasm_dto";
 export type CompletionItem = CompletionItemDto;
 export type SignatureHelp = SignatureHelpDto;
 
+/**
+ * Error thrown by every WASM export; match on `code`, not `message`.
+ * `internal` is set for `internal_error` (the module panicked).
+ */
+export type AnalyzerError = Error & {
+  name: "AnalyzerError";
+  code: ErrorCode;
+  internal?: InternalError;
+};
+
+export function isAnalyzerError(e: unknown): e is AnalyzerError {
+  return (
+    e instanceof Error &&
+    e.name === "AnalyzerError" &&
+    typeof (e as { code?: unknown }).code === "string"
+  );
+}
+
 export type CompletionState = {
   items: CompletionItem[];
   signatureHelp: SignatureHelp | null;
@@ -44,12 +80,33 @@ export async function initWasm(config: AnalyzerConfig): Promise<void> {
     } else {
       await init();
     }
+    wasm.init();
     analyzer = new wasm.Analyzer(config);
   })();
 
   return initPromise;
 }
 
+/**
+ * Run a WASM call, turning a panic trap into an `AnalyzerError` with code `internal_error`.
+ * The module state after a panic is undefined; callers should re-create the analyzer.
+ */
+function callWasm<T>(f: () => T): T {
+  try {
+    return f();
+  } catch (e) {
+    if (!(e instanceof WebAssembly.RuntimeError)) {
+      throw e;
+    }
+    const internal = (wasm.take_internal_error() as InternalError | null) ?? undefined;
+    const err = new Error(internal?.message ?? e.message) as AnalyzerError;
+    err.name = "AnalyzerError";
+    err.code = "internal_error";
+    err.internal = internal;
+    throw err;
+  }
+}
+
 function getAnalyzer(): wasm.Analyzer {
   if (analyzer) {
     return analyzer;
@@ -58,19 +115,117 @@ function getAnalyzer(): wasm.Analyzer {
 }
 
 export function analyze(source: string): AnalyzeResult {
-  return getAnalyzer().analyze(source) as AnalyzeResult;
+  return callWasm(() => getAnalyzer().analyze(source) as AnalyzeResult);
 }
 
-export function format(source: string, cursorUtf16: number): ApplyResult {
-  return getAnalyzer().format(source, cursorUtf16) as ApplyResult;
+export function analyze_batch(formulas: FormulaSource[]): BatchAnalyzeItem[] {
+  return callWasm(() => getAnalyzer().analyze_batch(formulas) as BatchAnalyzeItem[]);
+}
+
+export function format(
+  source: string,
+  cursorUtf16: number,
+  config?: FormatConfig,
+): ApplyResult {
+  return callWasm(() => getAnalyzer().format(source, cursorUtf16, config) as ApplyResult);
 }
 
 export function apply_edits(source: string, edits: TextEdit[], cursorUtf16: number): ApplyResult {
-  return getAnalyzer().apply_edits(source, edits, cursorUtf16) as ApplyResult;
+  return callWasm(() => getAnalyzer().apply_edits(source, edits, cursorUtf16) as ApplyResult);
+}
+
+export function apply_text_edits(source: string, edits: TextEdit[], cursor: number): ApplyResult {
+  return callWasm(() => getAnalyzer().apply_text_edits(source, edits, cursor) as ApplyResult);
+}
+
+export function help(source: string, cursor: number, config?: CompletionConfig): HelpResult {
+  return callWasm(() => getAnalyzer().help(source, cursor, config) as HelpResult);
+}
+
+/** Signature help only (no completion), for `(` / `,` triggers. */
+export function signature_help(source: string, cursor: number): SignatureHelp | null {
+  return callWasm(
+    () => (getAnalyzer().signature_help(source, cursor) as SignatureHelp | null) ?? null,
+  );
+}
+
+/** CodeMirror-6-shaped lint diagnostics, completion, and decorations in one call. */
+export function codemirror(
+  source: string,
+  cursor: number,
+  config?: CompletionConfig,
+): CodeMirrorResult {
+  return callWasm(() => getAnalyzer().codemirror(source, cursor, config) as CodeMirrorResult);
+}
+
+/** Monaco-shaped markers, completion items, and signature help in one call. */
+export function monaco(source: string, cursor: number, config?: CompletionConfig): MonacoResult {
+  return callWasm(() => getAnalyzer().monaco(source, cursor, config) as MonacoResult);
+}
+
+/** Edits renaming the property at `cursor`, or `null` when the cursor is not on one. */
+export function rename(source: string, cursor: number, newName: string): TextEdit[] | null {
+  return callWasm(
+    () => (getAnalyzer().rename(source, cursor, newName) as TextEdit[] | null) ?? null,
+  );
+}
+
+export function code_actions(source: string, range: Span): CodeAction[] {
+  return callWasm(() => getAnalyzer().code_actions(source, range) as CodeAction[]);
+}
+
+export function hover(source: string, cursorUtf16: number): HoverResult | null {
+  return callWasm(() => (getAnalyzer().hover(source, cursorUtf16) as HoverResult | null) ?? null);
+}
+
+export function type_at(source: string, cursorUtf16: number): TypeAtResult | null {
+  return callWasm(
+    () => (getAnalyzer().type_at(source, cursorUtf16) as TypeAtResult | null) ?? null,
+  );
+}
+
+export function ast_json(source: string): AstResult {
+  return callWasm(() => getAnalyzer().ast_json(source) as AstResult);
+}
+
+export function referenced_properties(source: string): PropertyReference[] {
+  return callWasm(() => getAnalyzer().referenced_properties(source) as PropertyReference[]);
+}
+
+export function semantic_tokens(source: string): Uint32Array {
+  return callWasm(() => getAnalyzer().semantic_tokens(source));
+}
+
+export function builtin_catalog(): BuiltinFunction[] {
+  return callWasm(() => wasm.Analyzer.builtin_catalog() as BuiltinFunction[]);
+}
+
+/** Maps a Notion API `database.properties` object to `AnalyzerConfig.properties`. */
+export function properties_from_notion_schema(properties: unknown): Property[] {
+  return callWasm(() => wasm.Analyzer.properties_from_notion_schema(properties) as Property[]);
+}
+
+/** Formula source for a Notion API `formula.expression` (`properties`: the database schema). */
+export function from_notion_expression(expression: string, properties: unknown): string {
+  return callWasm(() => wasm.Analyzer.from_notion_expression(expression, properties));
+}
+
+/** The Notion API `formula.expression` for `source` (`properties`: the database schema). */
+export function to_notion_expression(source: string, properties: unknown): string {
+  return callWasm(() => wasm.Analyzer.to_notion_expression(source, properties));
+}
+
+/** Schema issues (with field paths) that would make `new Analyzer(config)` throw. */
+export function validate_config(config: unknown): ConfigValidationResult {
+  return callWasm(() => wasm.Analyzer.validate_config(config) as ConfigValidationResult);
+}
+
+export function open_session(source: string): wasm.Session {
+  return callWasm(() => getAnalyzer().session(source));
 }
 
-export function help(source: string, cursor: number): HelpResult {
-  return getAnalyzer().help(source, cursor) as HelpResult;
+export function semantic_token_legend(): SemanticTokensLegend {
+  return callWasm(() => wasm.Analyzer.semantic_token_legend() as SemanticTokensLegend);
 }
 
 export function buildCompletionState(source: string, cursor: number): CompletionState {
@@ -12,4 +12,5 @@ export const PROPERTY_SCHEMA: Property[] = [
 export const ANALYZER_CONFIG: AnalyzerConfig = {
   properties: PROPERTY_SCHEMA,
   preferred_limit: null,
+  position_encoding: null,
 };
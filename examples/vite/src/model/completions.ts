@@ -19,9 +19,12 @@ function kindLabel(kind: CompletionItem["kind"]): string {
   if (kind === "FunctionPeople") return "People Functions";
   if (kind === "FunctionList") return "List Functions";
   if (kind === "FunctionSpecial") return "Special Functions";
-  if (kind === "Builtin") return "Built-ins";
+  if (kind === "Keyword") return "Keywords";
+  if (kind === "Constant") return "Constants";
   if (kind === "Property") return "Properties";
+  if (kind === "Variable") return "Variables";
   if (kind === "Operator") return "Operators";
+  if (kind === "Snippet") return "Snippets";
   return String(kind);
 }
 
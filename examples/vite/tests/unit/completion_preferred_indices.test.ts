@@ -79,6 +79,7 @@ describe("recommended completions", () => {
       detail: null,
       is_disabled: false,
       disabled_reason: null,
+      match_strength: null,
     },
     {
       label: "generalFn",
@@ -90,6 +91,7 @@ describe("recommended completions", () => {
       detail: null,
       is_disabled: false,
       disabled_reason: null,
+      match_strength: null,
     },
   ];
 
@@ -21,6 +21,7 @@ function makeItem(overrides: Partial<CompletionItem>): CompletionItem {
     detail: null,
     is_disabled: false,
     disabled_reason: null,
+    match_strength: null,
     ...overrides,
   };
 }
@@ -30,7 +31,7 @@ describe("completion row planning", () => {
     const items: CompletionItem[] = [
       makeItem({ label: "a", kind: "FunctionGeneral" }),
       makeItem({ label: "b", kind: "FunctionText", is_disabled: true }),
-      makeItem({ label: "c", kind: "Builtin" }),
+      makeItem({ label: "c", kind: "Constant" }),
     ];
 
     const rows = buildCompletionRows(items, [1, 0, 1, 2]);
@@ -61,8 +62,8 @@ describe("completion row planning", () => {
       makeItem({ label: "textFn", kind: "FunctionText" }),
       makeItem({ label: "genFn", kind: "FunctionGeneral" }),
       makeItem({ label: "textFn2", kind: "FunctionText" }),
-      makeItem({ label: "not", kind: "Builtin" }),
-      makeItem({ label: "true", kind: "Builtin" }),
+      makeItem({ label: "not", kind: "Keyword" }),
+      makeItem({ label: "true", kind: "Constant" }),
       makeItem({ label: "+", kind: "Operator" }),
     ];
 
@@ -75,7 +76,8 @@ describe("completion row planning", () => {
       "Text Functions",
       "General Functions",
       "Text Functions",
-      "Built-ins",
+      "Keywords",
+      "Constants",
       "Operators",
     ]);
   });
@@ -84,7 +86,7 @@ describe("completion row planning", () => {
     const items: CompletionItem[] = [
       makeItem({ label: "genFn", kind: "FunctionGeneral" }),
       makeItem({ label: "textFn", kind: "FunctionText" }),
-      makeItem({ label: "not", kind: "Builtin" }),
+      makeItem({ label: "not", kind: "Keyword" }),
     ];
     const rows = buildCompletionRows(items, []);
     const itemRowIndices = rows
@@ -23,6 +23,7 @@ describe("applyCompletionItem", () => {
     detail: null,
     is_disabled: false,
     disabled_reason: null,
+    match_strength: null,
   };
 
   it("uses explicit cursor when provided", () => {
@@ -0,0 +1,68 @@
+//! One crate with a stable API over the formula engine.
+//!
+//! The workspace crates (`analyzer`, `ide`, `evaluator`) reorganize their modules as the engine
+//! grows. This crate re-exports a curated set of their items under fixed paths and follows
+//! semver: an item listed here only moves or changes with a major version bump.
+//!
+//! - [`analysis`]: parsing, diagnostics, types, and the semantic [`Context`](analysis::Context)
+//! - [`completion`]: completion, signature help, and hover (`completion` feature)
+//! - [`format`]: the formatter (`format` feature)
+//! - [`eval`]: row evaluation and host functions (`eval` feature)
+//!
+//! All offsets are UTF-8 byte offsets into the source.
+
+/// Parsing, diagnostics, type inference, and the semantic context.
+pub mod analysis {
+    pub use analyzer::semantic::{
+        Context, ContextBuilder, FunctionCategory, FunctionSig, FunctionSigBuilder, GenericId,
+        GenericParamKind, Property, SignatureError, Ty, builtins_functions,
+    };
+    pub use analyzer::{
+        AnalysisCache, AnalyzeResult, CacheStats, CancellationToken, Cancelled, CodeAction,
+        Diagnostic, DiagnosticCode, DiagnosticKind, LineIndex, SourceMap, Span, TextEdit, analyze,
+        analyze_cancellable, byte_to_utf16_offset, format_diagnostics, utf16_offset_to_byte,
+    };
+}
+
+/// Completion, signature help, and hover at a cursor.
+#[cfg(feature = "completion")]
+pub mod completion {
+    pub use ide::{
+        CompletionConfig, CompletionItem, CompletionKind, CompletionResult, DisplaySegment,
+        HelpResult, HoverResult, SignatureHelp, SignatureItem, help, hover, signature_help,
+    };
+}
+
+/// Deterministic formatting.
+#[cfg(feature = "format")]
+pub mod format {
+    pub use ide::{
+        ApplyResult, EditError, FormatConfig, format, format_minified, format_range,
+        format_with_config,
+    };
+}
+
+/// Row evaluation and host-defined functions.
+#[cfg(feature = "eval")]
+pub mod eval {
+    use std::collections::HashMap;
+
+    pub use evaluator::{
+        EvalConfig, EvalContext, EvalError, FunctionDef, FunctionImpl, FunctionRegistry, TimeZone,
+        Value, WeekStart,
+    };
+
+    /// Evaluates `source` for one row. `row` maps property names to values; missing names and
+    /// `None` are empty, and `Ok(None)` is an empty result.
+    ///
+    /// Run [`analyze`](crate::analysis::analyze) first: sources with diagnostics fail with an
+    /// [`EvalError`] at best.
+    pub fn evaluate(
+        source: &str,
+        ctx: &EvalContext,
+        row: &HashMap<String, Option<Value>>,
+    ) -> Result<Option<Value>, EvalError> {
+        let expr = analyzer::analyze_syntax(source).expr;
+        evaluator::evaluate_row_with(&expr, ctx, row)
+    }
+}
@@ -0,0 +1,83 @@
+//! Exercises every module through the facade paths only.
+
+use std::collections::HashMap;
+
+use notion_formula_rs::analysis::{self, Context, FunctionSig, Ty};
+use notion_formula_rs::completion::{self, CompletionConfig};
+use notion_formula_rs::eval::{self, EvalContext, EvalError, FunctionDef, FunctionRegistry, Value};
+use notion_formula_rs::format;
+
+fn ctx() -> Context {
+    Context::builder()
+        .prop("Price", Ty::Number)
+        .with_builtins()
+        .build()
+}
+
+#[test]
+fn analysis() {
+    let result = analysis::analyze(r#"prop("Price") * 2"#, &ctx());
+    assert!(result.diagnostics.is_empty());
+    assert_eq!(result.output_type, Ty::Number);
+
+    let result = analysis::analyze(r#"prop("Missing")"#, &ctx());
+    assert_eq!(result.diagnostics.len(), 1);
+
+    let mut cache = analysis::AnalysisCache::new(4);
+    assert_eq!(cache.analyze("1", &ctx()).output_type, Ty::Number);
+}
+
+#[test]
+fn completion() {
+    let source = "Pri";
+    let help = completion::help(source, source.len(), &ctx(), CompletionConfig::default());
+    assert!(
+        help.completion
+            .items
+            .iter()
+            .any(|item| item.label == "Price")
+    );
+
+    let source = "if(";
+    let signature = completion::signature_help(source, source.len(), &ctx()).unwrap();
+    assert_eq!(signature.active_parameter, 0);
+}
+
+#[test]
+fn formatting() {
+    let result = format::format("1+2", 0).unwrap();
+    assert_eq!(result.source, "1 + 2\n");
+    assert!(matches!(
+        format::format("1 +", 0),
+        Err(format::EditError::FormatError)
+    ));
+}
+
+#[test]
+fn evaluation_with_host_functions() {
+    let double = FunctionSig::builder("double")
+        .param("value", Ty::Number)
+        .returns(Ty::Number)
+        .build()
+        .unwrap();
+    let mut registry = FunctionRegistry::new();
+    registry.register(FunctionDef::new(double).with_impl(|args| match args {
+        [Some(Value::Number(value))] => Ok(Some(Value::Number(value * 2.0))),
+        _ => Err(EvalError::TypeMismatch),
+    }));
+
+    let properties = ctx().properties.to_vec();
+    let source = r#"double(prop("Price")) + 1"#;
+    assert!(
+        analysis::analyze(source, &registry.context(properties.clone()))
+            .diagnostics
+            .is_empty()
+    );
+
+    let row = HashMap::from([("Price".to_string(), Some(Value::Number(4.0)))]);
+    let ctx = EvalContext::new(properties).with_functions(registry);
+    assert_eq!(
+        eval::evaluate(source, &ctx, &row),
+        Ok(Some(Value::Number(9.0)))
+    );
+}
@@ -0,0 +1,45 @@
+/* C ABI for notion-formula. Keep in sync with ffi/src/lib.rs (see ffi/cbindgen.toml). */
+
+#ifndef NOTION_FORMULA_H
+#define NOTION_FORMULA_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Every request is a NUL-terminated UTF-8 JSON object. Every response is a NUL-terminated JSON
+ * object, {"ok": ...} or {"error": {"code", "message"}}, owned by the caller and released with
+ * nf_string_free. Spans and cursors are UTF-8 byte offsets.
+ */
+
+/**
+ * Type-checks a formula: `{ "source", "properties"? }`.
+ */
+char *nf_analyze(const char *request);
+
+/**
+ * Completion and signature help at a cursor: `{ "source", "cursor", "properties"? }`.
+ */
+char *nf_complete(const char *request);
+
+/**
+ * Formats a formula: `{ "source", "cursor"?, "indent_width"?, "max_width"? }`.
+ */
+char *nf_format(const char *request);
+
+/**
+ * Evaluates a formula against one row: `{ "source", "properties"?, "row"? }`.
+ */
+char *nf_evaluate(const char *request);
+
+/**
+ * Releases a response returned by an `nf_*` function. Null is ignored.
+ */
+void nf_string_free(char *response);
+
+#ifdef __cplusplus
+}  /* extern "C" */
+#endif
+
+#endif  /* NOTION_FORMULA_H */
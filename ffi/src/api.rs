@@ -0,0 +1,334 @@
+//! Request handlers behind the `nf_*` entry points: JSON request text in, `ok` payload out.
+
+use std::collections::HashMap;
+
+use analyzer::Span;
+use analyzer::semantic::{Context, Property};
+use evaluator::json::{value_from_json, value_to_json};
+#[cfg(feature = "ide")]
+use ide::{CompletionConfig, CompletionItem, SignatureHelp};
+#[cfg(feature = "format")]
+use ide::{EditError, FormatConfig};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+
+/// The `error` payload of a response.
+#[derive(Debug, Serialize)]
+pub(crate) struct Error {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new("invalid_request", message)
+    }
+
+    /// For entry points whose Cargo `feature` was off in this build.
+    #[cfg(not(feature = "format"))]
+    pub fn unsupported(feature: &str) -> Self {
+        Self::new(
+            "unsupported",
+            format!("this build has no `{feature}` feature"),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AnalyzeRequest {
+    source: String,
+    #[serde(default)]
+    properties: Vec<Property>,
+}
+
+#[cfg(feature = "ide")]
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CompleteRequest {
+    source: String,
+    cursor: u32,
+    #[serde(default)]
+    properties: Vec<Property>,
+}
+
+#[cfg(feature = "format")]
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FormatRequest {
+    source: String,
+    #[serde(default)]
+    cursor: u32,
+    indent_width: Option<usize>,
+    max_width: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EvaluateRequest {
+    source: String,
+    #[serde(default)]
+    properties: Vec<Property>,
+    /// Property name -> value; missing properties are empty.
+    #[serde(default)]
+    row: serde_json::Map<String, Json>,
+}
+
+#[derive(Debug, Serialize)]
+struct AnalyzeResponse {
+    output_type: String,
+    diagnostics: Vec<DiagnosticEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiagnosticEntry {
+    code: &'static str,
+    message: String,
+    span: SpanEntry,
+}
+
+/// UTF-8 byte offsets, `[start, end)`.
+#[derive(Debug, Serialize)]
+struct SpanEntry {
+    start: u32,
+    end: u32,
+}
+
+#[cfg(feature = "ide")]
+#[derive(Debug, Serialize)]
+struct CompleteResponse {
+    replace: SpanEntry,
+    items: Vec<CompletionEntry>,
+    /// Indices into `items` for the default selection.
+    preferred_indices: Vec<usize>,
+    signature_help: Option<SignatureEntry>,
+}
+
+#[cfg(feature = "ide")]
+#[derive(Debug, Serialize)]
+struct CompletionEntry {
+    label: String,
+    kind: &'static str,
+    insert_text: String,
+    /// Replaces `replace` when set.
+    primary_edit: Option<EditEntry>,
+    /// Cursor in the document after applying the edits.
+    cursor: Option<u32>,
+    additional_edits: Vec<EditEntry>,
+    detail: Option<String>,
+    is_disabled: bool,
+    disabled_reason: Option<String>,
+}
+
+#[cfg(feature = "ide")]
+#[derive(Debug, Serialize)]
+struct EditEntry {
+    span: SpanEntry,
+    new_text: String,
+}
+
+#[cfg(feature = "ide")]
+#[derive(Debug, Serialize)]
+struct SignatureEntry {
+    signatures: Vec<Vec<ide::DisplaySegment>>,
+    active_signature: usize,
+    active_parameter: usize,
+}
+
+#[cfg(feature = "format")]
+#[derive(Debug, Serialize)]
+struct FormatResponse {
+    source: String,
+    cursor: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct EvaluateResponse {
+    /// `null` for an empty result.
+    value: Json,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+pub(crate) fn analyze(request: &str) -> Result<Json, Error> {
+    let request: AnalyzeRequest = parse(request)?;
+    let output = analyzer::analyze(&request.source, &context(request.properties));
+    respond(AnalyzeResponse {
+        output_type: output.output_type.to_string(),
+        diagnostics: output
+            .diagnostics
+            .into_iter()
+            .map(|diagnostic| DiagnosticEntry {
+                code: diagnostic.code.as_str(),
+                message: diagnostic.message,
+                span: span(diagnostic.span),
+            })
+            .collect(),
+    })
+}
+
+#[cfg(feature = "ide")]
+pub(crate) fn complete(request: &str) -> Result<Json, Error> {
+    let request: CompleteRequest = parse(request)?;
+    let cursor = request.cursor as usize;
+    if !request.source.is_char_boundary(cursor) {
+        return Err(Error::new(
+            "invalid_cursor",
+            format!("cursor {cursor} is not a char boundary of the source"),
+        ));
+    }
+    let help = ide::help(
+        &request.source,
+        cursor,
+        &context(request.properties),
+        CompletionConfig::default(),
+    );
+    respond(CompleteResponse {
+        replace: span(help.completion.replace),
+        items: help.completion.items.into_iter().map(completion).collect(),
+        preferred_indices: help.completion.preferred_indices,
+        signature_help: help.signature_help.map(signature),
+    })
+}
+
+#[cfg(feature = "format")]
+pub(crate) fn format(request: &str) -> Result<Json, Error> {
+    let request: FormatRequest = parse(request)?;
+    let defaults = FormatConfig::default();
+    let config = FormatConfig {
+        indent_width: request.indent_width.unwrap_or(defaults.indent_width),
+        max_width: request.max_width.unwrap_or(defaults.max_width),
+        ..defaults
+    };
+    let result =
+        ide::format_with_config(&request.source, request.cursor, config).map_err(|error| {
+            match error {
+                EditError::FormatError => {
+                    Error::new("format_error", "the source has syntax errors")
+                }
+                EditError::InvalidCursor => Error::new("invalid_cursor", error.to_string()),
+                error => Error::new("internal_error", error.to_string()),
+            }
+        })?;
+    respond(FormatResponse {
+        source: result.source,
+        cursor: result.cursor,
+    })
+}
+
+pub(crate) fn evaluate(request: &str) -> Result<Json, Error> {
+    let request: EvaluateRequest = parse(request)?;
+    let ctx = context(request.properties);
+    let row = request
+        .row
+        .into_iter()
+        .map(|(name, json)| {
+            let ty = ctx.lookup(&name).ok_or_else(|| {
+                Error::invalid_request(format!("row: `{name}` is not a property"))
+            })?;
+            let value = value_from_json(&json, &ty)
+                .map_err(|err| Error::invalid_request(format!("row: `{name}`: {err}")))?;
+            Ok((name, value))
+        })
+        .collect::<Result<HashMap<_, _>, Error>>()?;
+
+    let analysis = analyzer::analyze(&request.source, &ctx);
+    if !analysis.diagnostics.is_empty() {
+        return Err(Error::new(
+            "invalid_formula",
+            analyzer::format_diagnostics(&request.source, analysis.diagnostics),
+        ));
+    }
+    let expr = analyzer::analyze_syntax(&request.source).expr;
+    let value = evaluator::evaluate_row(&expr, ctx.properties.into_inner(), &row)
+        .map_err(|error| Error::new("evaluation_error", error.to_string()))?;
+    respond(EvaluateResponse {
+        value: value_to_json(value.as_ref()),
+        ty: analysis.output_type.to_string(),
+    })
+}
+
+fn parse<T: DeserializeOwned>(request: &str) -> Result<T, Error> {
+    serde_json::from_str(request).map_err(|err| Error::invalid_request(err.to_string()))
+}
+
+fn respond(response: impl Serialize) -> Result<Json, Error> {
+    Ok(serde_json::to_value(response).expect("responses serialize to JSON"))
+}
+
+/// Request properties with the builtin functions.
+fn context(properties: Vec<Property>) -> Context {
+    Context::with_builtins(properties)
+}
+
+fn span(span: Span) -> SpanEntry {
+    SpanEntry {
+        start: span.start,
+        end: span.end,
+    }
+}
+
+#[cfg(feature = "ide")]
+fn edit(edit: analyzer::TextEdit) -> EditEntry {
+    EditEntry {
+        span: span(edit.range),
+        new_text: edit.new_text,
+    }
+}
+
+#[cfg(feature = "ide")]
+fn completion(item: CompletionItem) -> CompletionEntry {
+    CompletionEntry {
+        label: item.label,
+        kind: completion_kind(item.kind),
+        insert_text: item.insert_text,
+        primary_edit: item.primary_edit.map(edit),
+        cursor: item.cursor,
+        additional_edits: item.additional_edits.into_iter().map(edit).collect(),
+        detail: item.detail,
+        is_disabled: item.is_disabled,
+        disabled_reason: item.disabled_reason,
+    }
+}
+
+/// Same names as the WASM `CompletionItemKind`.
+#[cfg(feature = "ide")]
+fn completion_kind(kind: ide::CompletionKind) -> &'static str {
+    use ide::CompletionKind as K;
+    match kind {
+        K::FunctionGeneral => "FunctionGeneral",
+        K::FunctionText => "FunctionText",
+        K::FunctionNumber => "FunctionNumber",
+        K::FunctionDate => "FunctionDate",
+        K::FunctionPeople => "FunctionPeople",
+        K::FunctionList => "FunctionList",
+        K::FunctionSpecial => "FunctionSpecial",
+        K::Keyword => "Keyword",
+        K::Constant => "Constant",
+        K::Property => "Property",
+        K::Variable => "Variable",
+        K::Operator => "Operator",
+        K::Snippet => "Snippet",
+    }
+}
+
+#[cfg(feature = "ide")]
+fn signature(help: SignatureHelp) -> SignatureEntry {
+    SignatureEntry {
+        signatures: help
+            .signatures
+            .into_iter()
+            .map(|item| item.segments)
+            .collect(),
+        active_signature: help.active_signature,
+        active_parameter: help.active_parameter,
+    }
+}
@@ -0,0 +1,123 @@
+//! C ABI for the formula engine, for hosts that embed it without WASM (e.g. Swift and Kotlin apps).
+//!
+//! Every `nf_*` entry point takes a NUL-terminated UTF-8 JSON request and returns a newly
+//! allocated NUL-terminated JSON response: `{"ok": ...}` or `{"error": {"code", "message"}}`.
+//! Release responses with [`nf_string_free`]. Spans and cursors are UTF-8 byte offsets.
+//! `include/notion_formula.h` declares the functions; see the README for the payloads.
+
+mod api;
+
+use std::ffi::{CStr, CString, c_char};
+use std::panic::catch_unwind;
+
+use serde_json::{Value as Json, json};
+
+/// Type-checks a formula: `{ "source", "properties"? }`.
+///
+/// # Safety
+///
+/// `request` must be null or a NUL-terminated string valid for reads.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nf_analyze(request: *const c_char) -> *mut c_char {
+    unsafe { call(request, Entry::Analyze) }
+}
+
+/// Completion and signature help at a cursor: `{ "source", "cursor", "properties"? }`.
+///
+/// # Safety
+///
+/// `request` must be null or a NUL-terminated string valid for reads.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nf_complete(request: *const c_char) -> *mut c_char {
+    unsafe { call(request, Entry::Complete) }
+}
+
+/// Formats a formula: `{ "source", "cursor"?, "indent_width"?, "max_width"? }`.
+///
+/// # Safety
+///
+/// `request` must be null or a NUL-terminated string valid for reads.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nf_format(request: *const c_char) -> *mut c_char {
+    unsafe { call(request, Entry::Format) }
+}
+
+/// Evaluates a formula against one row: `{ "source", "properties"?, "row"? }`.
+///
+/// # Safety
+///
+/// `request` must be null or a NUL-terminated string valid for reads.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nf_evaluate(request: *const c_char) -> *mut c_char {
+    unsafe { call(request, Entry::Evaluate) }
+}
+
+/// Releases a response returned by an `nf_*` function. Null is ignored.
+///
+/// # Safety
+///
+/// `response` must be null or a pointer returned by this library that was not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nf_string_free(response: *mut c_char) {
+    if !response.is_null() {
+        drop(unsafe { CString::from_raw(response) });
+    }
+}
+
+/// An `nf_*` entry point, for Rust hosts that wrap this crate (e.g. the Node addon).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    Analyze,
+    Complete,
+    Format,
+    Evaluate,
+}
+
+/// The JSON response the `nf_*` function for `entry` returns for `request`.
+///
+/// Panics become `internal_error`.
+pub fn respond(entry: Entry, request: &str) -> String {
+    let handler = match entry {
+        Entry::Analyze => api::analyze,
+        #[cfg(feature = "ide")]
+        Entry::Complete => api::complete,
+        #[cfg(not(feature = "ide"))]
+        Entry::Complete => |_: &str| Err(api::Error::unsupported("ide")),
+        #[cfg(feature = "format")]
+        Entry::Format => api::format,
+        #[cfg(not(feature = "format"))]
+        Entry::Format => |_: &str| Err(api::Error::unsupported("format")),
+        Entry::Evaluate => api::evaluate,
+    };
+    let result = catch_unwind(|| handler(request))
+        .unwrap_or_else(|_| Err(api::Error::new("internal_error", "the engine panicked")));
+    envelope(result)
+}
+
+fn envelope(result: Result<Json, api::Error>) -> String {
+    match result {
+        Ok(ok) => json!({ "ok": ok }),
+        Err(error) => json!({ "error": error }),
+    }
+    .to_string()
+}
+
+unsafe fn call(request: *const c_char, entry: Entry) -> *mut c_char {
+    let response = match unsafe { request_text(request) } {
+        Ok(request) => respond(entry, request),
+        Err(error) => envelope(Err(error)),
+    };
+    // JSON escapes U+0000, so the text has no interior NUL.
+    CString::new(response)
+        .expect("JSON has no NUL bytes")
+        .into_raw()
+}
+
+unsafe fn request_text<'a>(request: *const c_char) -> Result<&'a str, api::Error> {
+    if request.is_null() {
+        return Err(api::Error::new("invalid_request", "request is null"));
+    }
+    unsafe { CStr::from_ptr(request) }
+        .to_str()
+        .map_err(|err| api::Error::new("invalid_request", format!("request is not UTF-8: {err}")))
+}
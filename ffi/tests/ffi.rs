@@ -0,0 +1,171 @@
+use std::ffi::{CStr, CString, c_char};
+
+use notion_formula::{nf_analyze, nf_complete, nf_evaluate, nf_format, nf_string_free};
+use serde_json::{Value, json};
+
+type Entry = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+
+fn call(entry: Entry, request: Value) -> Value {
+    let request = CString::new(request.to_string()).unwrap();
+    call_raw(entry, request.as_ptr())
+}
+
+fn call_raw(entry: Entry, request: *const c_char) -> Value {
+    unsafe {
+        let response = entry(request);
+        let value = serde_json::from_str(CStr::from_ptr(response).to_str().unwrap()).unwrap();
+        nf_string_free(response);
+        value
+    }
+}
+
+fn error_code(response: &Value) -> &str {
+    response["error"]["code"]
+        .as_str()
+        .unwrap_or_else(|| panic!("{response}"))
+}
+
+fn properties() -> Value {
+    json!([
+        { "name": "Price", "type": "Number" },
+        { "name": "Tags", "type": { "List": "String" } },
+    ])
+}
+
+#[test]
+fn analyze_reports_type_and_diagnostics() {
+    let response = call(
+        nf_analyze,
+        json!({ "source": "prop(\"Price\") * 2", "properties": properties() }),
+    );
+    assert_eq!(
+        response,
+        json!({ "ok": { "output_type": "number", "diagnostics": [] } })
+    );
+
+    let response = call(nf_analyze, json!({ "source": "é + prop(\"Tax\")" }));
+    let diagnostics = response["ok"]["diagnostics"].as_array().unwrap();
+    assert!(!diagnostics.is_empty());
+    let tax = diagnostics
+        .iter()
+        .find(|d| d["message"].as_str().unwrap().contains("Tax"))
+        .unwrap();
+    // UTF-8 byte offsets: `é` is two bytes.
+    assert_eq!(tax["span"], json!({ "start": 10, "end": 15 }));
+}
+
+#[test]
+fn complete_returns_items_and_signature_help() {
+    let response = call(
+        nf_complete,
+        json!({ "source": "if(", "cursor": 3, "properties": properties() }),
+    );
+    let ok = &response["ok"];
+    assert_eq!(ok["replace"], json!({ "start": 3, "end": 3 }));
+    assert!(
+        ok["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|item| item["label"] == "Price" && item["kind"] == "Property")
+    );
+    assert_eq!(ok["signature_help"]["active_parameter"], 0);
+
+    let response = call(nf_complete, json!({ "source": "é", "cursor": 1 }));
+    assert_eq!(error_code(&response), "invalid_cursor");
+}
+
+#[test]
+fn format_returns_source_and_cursor() {
+    let response = call(nf_format, json!({ "source": "1+2" }));
+    assert_eq!(
+        response,
+        json!({ "ok": { "source": "1 + 2\n", "cursor": 0 } })
+    );
+
+    let response = call(nf_format, json!({ "source": "1 +" }));
+    assert_eq!(error_code(&response), "format_error");
+}
+
+#[test]
+fn evaluate_reads_the_row() {
+    let response = call(
+        nf_evaluate,
+        json!({
+            "source": "prop(\"Price\") * 2",
+            "properties": properties(),
+            "row": { "Price": 2.5 },
+        }),
+    );
+    assert_eq!(
+        response,
+        json!({ "ok": { "value": 5.0, "type": "number" } })
+    );
+
+    let response = call(
+        nf_evaluate,
+        json!({ "source": "prop(\"Price\")", "properties": properties() }),
+    );
+    assert_eq!(response["ok"]["value"], Value::Null);
+
+    let response = call(
+        nf_evaluate,
+        json!({ "source": "prop(\"Price\") / 0", "properties": properties(), "row": { "Price": 1 } }),
+    );
+    assert_eq!(
+        response,
+        json!({ "error": { "code": "evaluation_error", "message": "division by zero" } })
+    );
+
+    let response = call(nf_evaluate, json!({ "source": "prop(\"Tax\")" }));
+    assert_eq!(error_code(&response), "invalid_formula");
+
+    let response = call(
+        nf_evaluate,
+        json!({ "source": "1", "properties": properties(), "row": { "Price": "2" } }),
+    );
+    assert_eq!(
+        response["error"]["message"],
+        "row: `Price`: expected number, found \"2\""
+    );
+}
+
+#[test]
+fn malformed_requests_are_errors() {
+    assert_eq!(
+        error_code(&call_raw(nf_analyze, std::ptr::null())),
+        "invalid_request"
+    );
+
+    let not_utf8 = CString::new(vec![0xff_u8]).unwrap();
+    assert_eq!(
+        error_code(&call_raw(nf_analyze, not_utf8.as_ptr())),
+        "invalid_request"
+    );
+
+    assert_eq!(error_code(&call(nf_analyze, json!([]))), "invalid_request");
+    assert_eq!(
+        error_code(&call(nf_format, json!({ "source": "1", "indent": 2 }))),
+        "invalid_request"
+    );
+
+    unsafe { nf_string_free(std::ptr::null_mut()) };
+}
+
+#[test]
+fn header_declares_every_export() {
+    let header = include_str!("../include/notion_formula.h");
+    let lib = include_str!("../src/lib.rs");
+    let exports: Vec<&str> = lib
+        .lines()
+        .filter_map(|line| line.strip_prefix("pub unsafe extern \"C\" fn "))
+        .map(|rest| &rest[..rest.find('(').unwrap()])
+        .collect();
+    assert_eq!(exports.len(), 5);
+    for name in exports {
+        assert!(
+            header.contains(&format!("{name}(")),
+            "`{name}` is missing from include/notion_formula.h"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! Runs fuzz inputs through a target on stable Rust and reports the ones that panic.
+//!
+//! Usage: `replay <TARGET> <PATH>...`, where each `PATH` is an input file or a corpus
+//! directory. Exits `1` if any input panics, `2` on bad arguments or unreadable paths.
+
+use std::path::Path;
+use std::process::ExitCode;
+use std::{env, fs};
+
+use notion_formula_fuzz::{TARGETS, corpus_files, run_catching, target};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some((name, paths)) = args.split_first().filter(|(_, paths)| !paths.is_empty()) else {
+        return usage();
+    };
+    let Some(target) = target(name) else {
+        eprintln!("error: unknown target `{name}`");
+        return usage();
+    };
+
+    let mut inputs = 0;
+    let mut failures = Vec::new();
+    for path in paths {
+        let files = match corpus_files(Path::new(path)) {
+            Ok(files) => files,
+            Err(err) => {
+                eprintln!("error: {path}: {err}");
+                return ExitCode::from(2);
+            }
+        };
+        for file in files {
+            let data = match fs::read(&file) {
+                Ok(data) => data,
+                Err(err) => {
+                    eprintln!("error: {}: {err}", file.display());
+                    return ExitCode::from(2);
+                }
+            };
+            inputs += 1;
+            if let Err(message) = run_catching(target, &data) {
+                failures.push((file, message));
+            }
+        }
+    }
+
+    for (file, message) in &failures {
+        println!("FAIL {}: {message}", file.display());
+    }
+    eprintln!(
+        "{}: {} of {inputs} inputs panicked",
+        target.name,
+        failures.len()
+    );
+    if failures.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn usage() -> ExitCode {
+    let names: Vec<&str> = TARGETS.iter().map(|target| target.name).collect();
+    eprintln!("usage: replay <TARGET> <PATH>...");
+    eprintln!("targets: {}", names.join(", "));
+    ExitCode::from(2)
+}
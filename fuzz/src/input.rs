@@ -0,0 +1,81 @@
+//! Decoding fuzzer bytes into structured inputs.
+//!
+//! Reads never fail: past the end of the data they yield zeros / empty text, so every byte string
+//! is a valid input for every target.
+
+use analyzer::{Span, TextEdit};
+
+pub(crate) struct Input<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Input<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub(crate) fn u8(&mut self) -> u8 {
+        let (&first, rest) = self.data.split_first().unwrap_or((&0, &[]));
+        self.data = rest;
+        first
+    }
+
+    /// Little-endian.
+    pub(crate) fn u16(&mut self) -> u16 {
+        u16::from_le_bytes([self.u8(), self.u8()])
+    }
+
+    /// The next `len` bytes (or fewer at the end) as lossy UTF-8.
+    pub(crate) fn text(&mut self, len: usize) -> String {
+        let (head, rest) = self.data.split_at(len.min(self.data.len()));
+        self.data = rest;
+        String::from_utf8_lossy(head).into_owned()
+    }
+
+    /// All remaining bytes as lossy UTF-8.
+    pub(crate) fn rest(self) -> String {
+        String::from_utf8_lossy(self.data).into_owned()
+    }
+
+    /// Up to four edits, decoded as `count, (start: u16, len: u16, text_len: u8, text)*`.
+    pub(crate) fn edits(&mut self) -> Vec<RawEdit> {
+        let count = self.u8() % 5;
+        (0..count)
+            .map(|_| {
+                let start = self.u16();
+                let len = self.u16();
+                let text_len = usize::from(self.u8() % 16);
+                RawEdit {
+                    start,
+                    len,
+                    new_text: self.text(text_len),
+                }
+            })
+            .collect()
+    }
+}
+
+/// An edit decoded before the source it applies to.
+pub(crate) struct RawEdit {
+    start: u16,
+    len: u16,
+    new_text: String,
+}
+
+impl RawEdit {
+    /// Wraps the range into `0..=source_len`.
+    ///
+    /// The result may still overlap other edits or split a char; rejecting those is part of what
+    /// is fuzzed.
+    pub(crate) fn resolve(self, source_len: usize) -> TextEdit {
+        let start = usize::from(self.start) % (source_len + 1);
+        let end = start + usize::from(self.len) % (source_len - start + 1);
+        TextEdit {
+            range: Span {
+                start: start as u32,
+                end: end as u32,
+            },
+            new_text: self.new_text,
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Fuzz targets for the formula toolchain.
+//!
+//! Nearly every input to this crate is untrusted, half-typed editor text, so each public entry
+//! point must handle arbitrary bytes without panicking. Each target decodes raw fuzzer bytes into
+//! a call (see [`input`] for the byte layouts) and checks a few cheap invariants on the result.
+//!
+//! The same functions back the libFuzzer binaries in `libfuzzer/` and the `replay` binary,
+//! which runs a corpus directory on stable Rust. [`minimize_crash`] shrinks a crashing input to a
+//! minimal reproducer with them.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::{fs, io};
+
+use analyzer::semantic::{Context, Property, Ty};
+use ide::{AnalysisHost, ClosingDelim, CompletionConfig, FormatConfig};
+
+mod input;
+mod minimize;
+
+use input::Input;
+pub use minimize::{Minimized, minimize, minimize_crash};
+
+/// A named fuzz target.
+pub struct Target {
+    pub name: &'static str,
+    pub run: fn(&[u8]),
+}
+
+pub const TARGETS: &[Target] = &[
+    Target {
+        name: "analyze",
+        run: analyze,
+    },
+    Target {
+        name: "help",
+        run: help,
+    },
+    Target {
+        name: "format",
+        run: format,
+    },
+    Target {
+        name: "apply_edits",
+        run: apply_edits,
+    },
+];
+
+pub fn target(name: &str) -> Option<&'static Target> {
+    TARGETS.iter().find(|target| target.name == name)
+}
+
+/// Builtins plus a few properties, one of them disabled, so property paths are reachable.
+static CONTEXT: LazyLock<Context> = LazyLock::new(|| {
+    let property = |name: &str, ty: Ty| Property {
+        name: name.to_string(),
+        ty,
+        disabled_reason: None,
+        options: Vec::new(),
+    };
+    Context::with_builtins(vec![
+        property("Title", Ty::String),
+        property("Price", Ty::Number),
+        property("Due", Ty::Date),
+        property("Tags", Ty::List(Box::new(Ty::String))),
+        Property {
+            disabled_reason: Some("fuzz".to_string()),
+            ..property("Done", Ty::Boolean)
+        },
+    ])
+});
+
+/// `analyze` and diagnostic rendering on the whole input as source.
+pub fn analyze(data: &[u8]) {
+    let source = String::from_utf8_lossy(data);
+    let result = analyzer::analyze(&source, &CONTEXT);
+    for diagnostic in &result.diagnostics {
+        assert_span_in(&source, diagnostic.span);
+    }
+    analyzer::format_diagnostics(&source, result.diagnostics);
+}
+
+/// `ide::help` at an arbitrary byte cursor: `cursor: u16, source`.
+pub fn help(data: &[u8]) {
+    let mut input = Input::new(data);
+    let cursor = input.u16();
+    let source = input.rest();
+    let cursor = usize::from(cursor) % (source.len() + 1);
+
+    let result = ide::help(&source, cursor, &CONTEXT, CompletionConfig::default());
+    let completion = &result.completion;
+    assert_span_in(&source, completion.replace);
+    for item in &completion.items {
+        if let Some(edit) = &item.primary_edit {
+            assert_span_in(&source, edit.range);
+        }
+    }
+    for &index in &completion.preferred_indices {
+        assert!(
+            index < completion.items.len(),
+            "preferred index {index} out of range"
+        );
+    }
+}
+
+/// `ide::format_with_config`: `flags: u8, max_width: u8, cursor: u16, source`.
+///
+/// `flags` bits: `wrap_comments`, `collapse_fitting`, `ClosingDelim::SameLine`, indent of 4.
+pub fn format(data: &[u8]) {
+    let mut input = Input::new(data);
+    let flags = input.u8();
+    let config = FormatConfig {
+        wrap_comments: flags & 1 != 0,
+        collapse_fitting: flags & 2 != 0,
+        closing_delim: if flags & 4 != 0 {
+            ClosingDelim::SameLine
+        } else {
+            ClosingDelim::OwnLine
+        },
+        indent_width: if flags & 8 != 0 { 4 } else { 2 },
+        max_width: usize::from(input.u8()),
+    };
+    let cursor = input.u16();
+    let source = input.rest();
+    let cursor = u32::from(cursor) % (source.len() as u32 + 1);
+
+    if let Ok(result) = ide::format_with_config(&source, cursor, config) {
+        assert!(
+            result.source.is_char_boundary(result.cursor as usize),
+            "formatted cursor {} is not a char boundary",
+            result.cursor
+        );
+    }
+}
+
+/// `ide::apply_edits`, and the same edits through `AnalysisHost::apply_change`, whose incremental
+/// results must match a fresh analysis: `edits, cursor: u16, source` (see [`Input::edits`]).
+pub fn apply_edits(data: &[u8]) {
+    let mut input = Input::new(data);
+    let edits = input.edits();
+    let cursor = input.u16();
+    let source = input.rest();
+    let edits: Vec<_> = edits
+        .into_iter()
+        .map(|edit| edit.resolve(source.len()))
+        .collect();
+    let cursor = floor_char_boundary(&source, usize::from(cursor));
+
+    let applied = ide::apply_edits(&source, edits.clone(), cursor);
+
+    let mut host = AnalysisHost::new(CONTEXT.clone());
+    host.set_text(source.as_str());
+    host.syntax();
+    host.line_index();
+    let changed = host.apply_change(edits);
+    assert_eq!(
+        changed.is_ok(),
+        applied.is_ok(),
+        "{changed:?} vs {applied:?}"
+    );
+
+    let Ok(applied) = applied else {
+        assert_eq!(
+            host.text(),
+            source,
+            "a rejected change modified the document"
+        );
+        return;
+    };
+    assert!(
+        applied.source.is_char_boundary(applied.cursor as usize),
+        "rebased cursor {} is not a char boundary",
+        applied.cursor
+    );
+    assert_eq!(host.text(), applied.source);
+    let fresh = analyzer::analyze_syntax(&applied.source);
+    assert_eq!(
+        host.syntax().expr,
+        fresh.expr,
+        "incremental reparse differs"
+    );
+    assert_eq!(host.syntax().diagnostics, fresh.diagnostics);
+    assert_eq!(
+        host.line_index(),
+        &analyzer::LineIndex::new(&applied.source)
+    );
+}
+
+/// `offset` wrapped into the source and moved down to a char boundary.
+fn floor_char_boundary(source: &str, offset: usize) -> u32 {
+    let mut offset = offset % (source.len() + 1);
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset as u32
+}
+
+fn assert_span_in(source: &str, span: analyzer::Span) {
+    assert!(
+        span.start <= span.end && span.end as usize <= source.len(),
+        "span {span:?} outside source of length {}",
+        source.len()
+    );
+}
+
+/// Files to replay for `path`: the file itself, or the files directly inside a directory,
+/// sorted by name.
+pub fn corpus_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Runs `target` on `data`, returning the panic message if it panics.
+pub fn run_catching(target: &Target, data: &[u8]) -> Result<(), String> {
+    panic::catch_unwind(AssertUnwindSafe(|| (target.run)(data))).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "non-string panic payload".to_string())
+    })
+}
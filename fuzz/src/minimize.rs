@@ -0,0 +1,94 @@
+//! Delta debugging: shrinking a crashing input to a minimal reproducer.
+//!
+//! Fuzzer crashes are usually long byte strings of which a handful matter. [`minimize`] is
+//! Zeller's ddmin over bytes; [`minimize_crash`] drives it with a target's own assertions, so
+//! invariant violations shrink the same way as panics.
+
+use crate::{Target, run_catching};
+
+/// Result of [`minimize_crash`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Minimized {
+    /// The smallest input found that fails like the original.
+    pub data: Vec<u8>,
+    /// The panic message on `data`.
+    pub message: String,
+    /// How many times the target ran, the original input included.
+    pub runs: usize,
+}
+
+/// Shrinks `data`, on which `target` panics, to an input on which it panics the same way.
+///
+/// "The same way" is the same panic message up to numbers, which shrinking shifts (spans,
+/// lengths, offsets); a different panic found along the way is not taken. The result is
+/// 1-minimal: removing any single byte loses the panic. `None` if `data` does not panic.
+pub fn minimize_crash(target: &Target, data: &[u8]) -> Option<Minimized> {
+    let expected = failure_signature(&run_catching(target, data).err()?);
+    let mut runs = 1;
+    let data = minimize(data, |candidate| {
+        runs += 1;
+        run_catching(target, candidate)
+            .is_err_and(|message| failure_signature(&message) == expected)
+    });
+    let message = run_catching(target, &data).expect_err("the minimized input still fails");
+    Some(Minimized {
+        data,
+        message,
+        runs: runs + 1,
+    })
+}
+
+/// A smallest subsequence of `data` for which `fails` holds, assuming it holds for `data`.
+///
+/// ddmin: tries each of `n` chunks alone, then the input without each chunk, starting at `n = 2`
+/// and doubling `n` while nothing is removed. Returns once single bytes cannot be removed.
+pub fn minimize(data: &[u8], mut fails: impl FnMut(&[u8]) -> bool) -> Vec<u8> {
+    let mut data = data.to_vec();
+    let mut chunks = 2;
+    while data.len() >= 2 {
+        let size = data.len().div_ceil(chunks);
+        let starts = (0..data.len()).step_by(size);
+        let subset = starts.clone().find_map(|start| {
+            let candidate = &data[start..(start + size).min(data.len())];
+            fails(candidate).then(|| candidate.to_vec())
+        });
+        if let Some(subset) = subset {
+            data = subset;
+            chunks = 2;
+            continue;
+        }
+        let complement = starts.filter(|_| chunks > 2).find_map(|start| {
+            let mut candidate = data[..start].to_vec();
+            candidate.extend_from_slice(&data[(start + size).min(data.len())..]);
+            fails(&candidate).then_some(candidate)
+        });
+        if let Some(complement) = complement {
+            data = complement;
+            chunks = (chunks - 1).max(2);
+            continue;
+        }
+        if chunks >= data.len() {
+            break;
+        }
+        chunks = (chunks * 2).min(data.len());
+    }
+    if data.len() == 1 && fails(&[]) {
+        data.clear();
+    }
+    data
+}
+
+/// `message` with every run of digits replaced by `#`.
+fn failure_signature(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut in_number = false;
+    for c in message.chars() {
+        if !c.is_ascii_digit() {
+            out.push(c);
+        } else if !in_number {
+            out.push('#');
+        }
+        in_number = c.is_ascii_digit();
+    }
+    out
+}
@@ -0,0 +1,42 @@
+//! Replays `corpus/<target>/` through each target. Crash reproducers belong in the corpus too,
+//! so fixed crashes stay fixed.
+
+use std::fs;
+use std::path::Path;
+
+use notion_formula_fuzz::{TARGETS, corpus_files, run_catching};
+
+#[test]
+fn corpus_replays_without_panics() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    let mut failures = Vec::new();
+    for target in TARGETS {
+        let files = corpus_files(&corpus.join(target.name)).unwrap();
+        assert!(!files.is_empty(), "no corpus for `{}`", target.name);
+        for file in files {
+            let data = fs::read(&file).unwrap();
+            if let Err(message) = run_catching(target, &data) {
+                failures.push(format!("{}: {message}", file.display()));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn targets_accept_arbitrary_bytes() {
+    let inputs: [&[u8]; 5] = [
+        b"",
+        b"\xff",
+        b"\xff\xff\xff\xff\xff\xff\xff",
+        b"\x04\xff\xff\xff\xff\x0f\xff\xff\xff\xff\x0fif(",
+        "\u{0}\u{1}😀(\"".as_bytes(),
+    ];
+    for target in TARGETS {
+        for data in inputs {
+            if let Err(message) = run_catching(target, data) {
+                panic!("{} panicked on {data:?}: {message}", target.name);
+            }
+        }
+    }
+}
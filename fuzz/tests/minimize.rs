@@ -0,0 +1,46 @@
+//! Shrinking crashing inputs with `minimize` and `minimize_crash`.
+
+use notion_formula_fuzz::{Minimized, Target, minimize, minimize_crash, target};
+
+/// Panics on `!` (with its offset), else on `?`.
+fn picky(data: &[u8]) {
+    if let Some(pos) = data.iter().position(|&byte| byte == b'!') {
+        panic!("bang at {pos}");
+    }
+    assert!(!data.contains(&b'?'), "question");
+}
+
+const PICKY: Target = Target {
+    name: "picky",
+    run: picky,
+};
+
+#[test]
+fn minimize_keeps_only_the_bytes_that_matter() {
+    let fails = |data: &[u8]| data.contains(&b'x') && data.contains(&b'y');
+    assert_eq!(minimize(b"aaxbbbbbbbbycc", fails), b"xy");
+    assert_eq!(minimize(b"yx", fails), b"yx");
+    assert_eq!(minimize(b"anything", |_| true), b"");
+}
+
+#[test]
+fn minimize_crash_keeps_the_same_panic_up_to_numbers() {
+    let minimized = minimize_crash(&PICKY, b"ab?cdefgh!ij").unwrap();
+    assert_eq!(
+        minimized,
+        Minimized {
+            data: b"!".to_vec(),
+            message: "bang at 0".to_string(),
+            runs: minimized.runs,
+        }
+    );
+    assert!(minimized.runs > 2);
+
+    assert_eq!(minimize_crash(&PICKY, b"ab?").unwrap().data, b"?");
+}
+
+#[test]
+fn minimize_crash_ignores_inputs_that_do_not_panic() {
+    assert_eq!(minimize_crash(&PICKY, b"fine"), None);
+    assert_eq!(minimize_crash(target("analyze").unwrap(), b"1 + 1"), None);
+}
@@ -0,0 +1,16 @@
+//! Code actions: the quick fixes attached to diagnostics.
+//!
+//! Coordinates are UTF-8 byte offsets.
+
+use analyzer::{CodeAction, Diagnostic, Span};
+
+/// Actions of every diagnostic overlapping `range`, in diagnostic order.
+///
+/// An empty `range` (a cursor) matches diagnostics it touches.
+pub(crate) fn code_actions(diagnostics: &[Diagnostic], range: Span) -> Vec<CodeAction> {
+    diagnostics
+        .iter()
+        .filter(|diag| diag.span.start <= range.end && range.start <= diag.span.end)
+        .flat_map(|diag| diag.actions.iter().cloned())
+        .collect()
+}
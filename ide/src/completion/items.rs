@@ -1,123 +1,243 @@
-//! Builds the raw completion item list for a position.
-//! Items are not ranked here (ranking happens in `ranking`).
-
-use crate::completion::{CompletionData, CompletionItem, CompletionKind};
-use analyzer::semantic;
-
-/// Completion items at an expression start.
-pub(crate) fn expr_start_items(ctx: &semantic::Context) -> Vec<CompletionItem> {
-    let mut items = Vec::new();
-    items.extend(prop_variable_items(ctx));
-    items.extend(builtin_expr_start_items());
-    items.extend(ctx.functions.iter().map(|func| {
-        CompletionItem::new(
-            format!("{}()", func.name),
-            CompletionKind::from(func.category),
-        )
-        .with_insert_text(format!("{}()", func.name))
-        .with_detail(func.detail.clone())
-        .with_data(CompletionData::Function {
-            name: func.name.clone(),
-        })
-    }));
+//! Builds the raw completion candidate list for a position.
+//! Candidates are not ranked here (ranking happens in `ranking`).
+
+use std::borrow::Cow;
+
+use crate::completion::{
+    CompletionData, CompletionItem, CompletionKind, RESOLVED_PROPERTY_LIMIT, Snippet,
+    SnippetRegistry,
+};
+use analyzer::{Keyword, Keywords, semantic};
+
+/// A completion item that is not built yet: it borrows from the [`semantic::Context`] and
+/// only allocates labels, insert texts and details in [`Candidate::into_item`].
+///
+/// Properties from the context's resolver are owned.
+#[derive(Debug, Clone)]
+pub(crate) enum Candidate<'a> {
+    Property(Cow<'a, semantic::Property>),
+    Function(&'a semantic::FunctionSig),
+    /// `insert_dot` is false in member-access position, where the `.` already exists.
+    PostfixMethod {
+        func: &'a semantic::FunctionSig,
+        insert_dot: bool,
+    },
+    /// A reserved word of [`Keywords::notion`].
+    Builtin(&'static Keyword),
+    Operator(&'static str),
+    /// An option of `property`, inserted between the quotes of a string literal.
+    OptionValue {
+        property: Cow<'a, str>,
+        value: Cow<'a, str>,
+    },
+    /// Owned, since the registry lives in the per-request config rather than the context.
+    Snippet(Box<Snippet>),
+}
+
+impl<'a> Candidate<'a> {
+    pub(crate) fn kind(&self) -> CompletionKind {
+        match self {
+            Candidate::Property(_) => CompletionKind::Property,
+            Candidate::Function(func) | Candidate::PostfixMethod { func, .. } => {
+                CompletionKind::from(func.category)
+            }
+            Candidate::Builtin(keyword) => builtin_kind(keyword),
+            Candidate::Operator(_) => CompletionKind::Operator,
+            Candidate::OptionValue { .. } => CompletionKind::Constant,
+            Candidate::Snippet(_) => CompletionKind::Snippet,
+        }
+    }
+
+    pub(crate) fn is_disabled(&self) -> bool {
+        matches!(self, Candidate::Property(prop) if prop.disabled_reason.is_some())
+    }
+
+    /// The item label without a function's `()` suffix; postfix methods keep the leading `.`.
+    pub(crate) fn label_stem(&self) -> Cow<'a, str> {
+        match *self {
+            Candidate::Property(Cow::Borrowed(prop)) => Cow::Borrowed(&prop.name),
+            Candidate::Property(Cow::Owned(ref prop)) => Cow::Owned(prop.name.clone()),
+            Candidate::Function(func) => Cow::Borrowed(&func.name),
+            Candidate::PostfixMethod { func, .. } => Cow::Owned(format!(".{}", func.name)),
+            Candidate::Builtin(keyword) => Cow::Borrowed(&keyword.word),
+            Candidate::Operator(op) => Cow::Borrowed(op),
+            Candidate::OptionValue { ref value, .. } => value.clone(),
+            Candidate::Snippet(ref snippet) => Cow::Owned(snippet.name.clone()),
+        }
+    }
+
+    /// Builds the full item. `call_parens: false` drops the `()` from function insert texts.
+    pub(crate) fn into_item(self, call_parens: bool) -> CompletionItem {
+        let parens = if call_parens { "()" } else { "" };
+        match self {
+            Candidate::Property(prop) => {
+                let item = CompletionItem::new(prop.name.clone(), CompletionKind::Property)
+                    .with_insert_text(format!(r#"prop("{}")"#, prop.name))
+                    .with_data(CompletionData::PropExpr {
+                        property_name: prop.name.clone(),
+                    });
+                match &prop.disabled_reason {
+                    Some(reason) => item.disabled(reason.clone()),
+                    None => item,
+                }
+            }
+            Candidate::Function(func) => {
+                CompletionItem::new(format!("{}()", func.name), self.kind())
+                    .with_insert_text(format!("{}{parens}", func.name))
+                    .with_detail(func.detail.clone())
+                    .with_data(CompletionData::Function {
+                        name: func.name.clone(),
+                    })
+            }
+            Candidate::PostfixMethod { func, insert_dot } => {
+                let dot = if insert_dot { "." } else { "" };
+                CompletionItem::new(format!(".{}()", func.name), self.kind())
+                    .with_insert_text(format!("{dot}{}{parens}", func.name))
+                    .with_detail(postfix_detail(func))
+                    .with_data(CompletionData::PostfixMethod {
+                        name: func.name.clone(),
+                    })
+            }
+            Candidate::Builtin(keyword) => {
+                CompletionItem::new(keyword.word.as_ref(), builtin_kind(keyword))
+                    .with_insert_text(format!("{} ", keyword.word))
+            }
+            Candidate::Operator(op) => CompletionItem::new(op, CompletionKind::Operator),
+            Candidate::OptionValue { property, value } => {
+                let literal = analyzer::string_literal(&value);
+                let content = &literal[1..literal.len() - 1];
+                CompletionItem::new(value.as_ref(), CompletionKind::Constant)
+                    .with_insert_text(content)
+                    .with_detail(format!("{property} option"))
+                    .with_data(CompletionData::OptionValue {
+                        property_name: property.into_owned(),
+                    })
+            }
+            Candidate::Snippet(snippet) => {
+                let Snippet {
+                    name,
+                    description,
+                    body,
+                    placeholders,
+                } = *snippet;
+                let mut item = CompletionItem::new(name.clone(), CompletionKind::Snippet)
+                    .with_insert_text(body)
+                    .with_detail(description)
+                    .with_data(CompletionData::Snippet { name });
+                item.placeholders = placeholders;
+                item
+            }
+        }
+    }
+}
+
+/// Completion candidates at an expression start, where `prefix` is the identifier being typed.
+pub(crate) fn expr_start_items<'a>(ctx: &'a semantic::Context, prefix: &str) -> Vec<Candidate<'a>> {
+    let mut items = prop_variable_items(ctx, prefix);
+    items.extend(
+        Keywords::notion()
+            .iter()
+            .filter(|keyword| keyword.kind.begins_expr())
+            .map(Candidate::Builtin),
+    );
+    items.extend(ctx.functions.iter().map(Candidate::Function));
     items
 }
 
-/// Completion items after an atom (e.g. after `ident`, a literal, or `)`).
-pub(crate) fn after_atom_items(ctx: &semantic::Context) -> Vec<CompletionItem> {
-    const OPS: [&str; 10] = ["==", "!=", ">=", ">", "<=", "<", "+", "-", "*", "/"];
+/// The registry's snippets, offered where an expression starts.
+pub(crate) fn snippet_items<'a>(snippets: &SnippetRegistry) -> Vec<Candidate<'a>> {
+    snippets
+        .iter()
+        .map(|snippet| Candidate::Snippet(Box::new(snippet.clone())))
+        .collect()
+}
 
-    let mut items: Vec<CompletionItem> = OPS
-        .into_iter()
-        .map(|op| CompletionItem::new(op, CompletionKind::Operator))
-        .collect();
+/// Completion candidates after an atom (e.g. after `ident`, a literal, or `)`).
+pub(crate) fn after_atom_items(ctx: &semantic::Context) -> Vec<Candidate<'_>> {
+    const OPS: [&str; 10] = ["==", "!=", ">=", ">", "<=", "<", "+", "-", "*", "/"];
 
+    let mut items: Vec<Candidate> = OPS.into_iter().map(Candidate::Operator).collect();
+    items.extend(
+        Keywords::notion()
+            .iter()
+            .filter(|keyword| !keyword.kind.begins_expr())
+            .map(Candidate::Builtin),
+    );
     items.extend(postfix_method_items(ctx, true, &semantic::Ty::Unknown));
-
     items
 }
 
-/// Completion items right after a `.` (member-access context).
-pub(crate) fn after_dot_items(
-    ctx: &semantic::Context,
+/// Completion candidates right after a `.` (member-access context).
+pub(crate) fn after_dot_items<'a>(
+    ctx: &'a semantic::Context,
     receiver_ty: &semantic::Ty,
-) -> Vec<CompletionItem> {
+) -> Vec<Candidate<'a>> {
     // In a member-access context, the `.` already exists in the source.
     postfix_method_items(ctx, false, receiver_ty)
 }
 
-fn needs_trailing_space(name: &str) -> bool {
-    matches!(name, "not" | "true" | "false")
+fn builtin_kind(keyword: &Keyword) -> CompletionKind {
+    if keyword.kind.is_constant() {
+        CompletionKind::Constant
+    } else {
+        CompletionKind::Keyword
+    }
 }
 
-fn builtin_expr_start_items() -> Vec<CompletionItem> {
-    ["not", "true", "false"]
-        .into_iter()
-        .map(|name| {
-            let insert_text = if needs_trailing_space(name) {
-                format!("{name} ")
-            } else {
-                name.to_string()
-            };
-            CompletionItem::new(name, CompletionKind::Builtin).with_insert_text(insert_text)
-        })
-        .collect()
+fn display_param_name(param: &semantic::ParamSig) -> String {
+    if param.optional {
+        format!("{}?", param.name)
+    } else {
+        param.name.clone()
+    }
 }
 
-fn postfix_method_items(
-    ctx: &semantic::Context,
-    insert_dot: bool,
-    receiver_ty: &semantic::Ty,
-) -> Vec<CompletionItem> {
-    fn display_param_name(param: &semantic::ParamSig) -> String {
-        if param.optional {
-            format!("{}?", param.name)
-        } else {
-            param.name.clone()
+fn postfix_detail(sig: &semantic::FunctionSig) -> String {
+    let mut receiver_param = None::<String>;
+    let mut call_params = Vec::<String>::new();
+
+    if let Some(first) = sig.params.head.first() {
+        receiver_param = Some(display_param_name(first));
+        for param in sig.params.head.iter().skip(1) {
+            call_params.push(display_param_name(param));
+        }
+    } else if let Some(first) = sig.params.repeat.first() {
+        receiver_param = Some(display_param_name(first));
+        for param in sig.params.repeat.iter().skip(1) {
+            call_params.push(display_param_name(param));
         }
     }
 
-    fn postfix_detail(sig: &semantic::FunctionSig) -> String {
-        let mut receiver_param = None::<String>;
-        let mut call_params = Vec::<String>::new();
+    if receiver_param.is_none() {
+        return sig.detail.clone();
+    }
 
-        if let Some(first) = sig.params.head.first() {
-            receiver_param = Some(display_param_name(first));
-            for param in sig.params.head.iter().skip(1) {
-                call_params.push(display_param_name(param));
-            }
-        } else if let Some(first) = sig.params.repeat.first() {
-            receiver_param = Some(display_param_name(first));
-            for param in sig.params.repeat.iter().skip(1) {
+    if !sig.params.repeat.is_empty() {
+        if !sig.params.head.is_empty() {
+            for param in &sig.params.repeat {
                 call_params.push(display_param_name(param));
             }
         }
+        call_params.push("...".to_string());
+    }
 
-        if receiver_param.is_none() {
-            return sig.detail.clone();
-        }
-
-        if !sig.params.repeat.is_empty() {
-            if !sig.params.head.is_empty() {
-                for param in &sig.params.repeat {
-                    call_params.push(display_param_name(param));
-                }
-            }
-            call_params.push("...".to_string());
-        }
-
-        for param in &sig.params.tail {
-            call_params.push(display_param_name(param));
-        }
-
-        let receiver_param = receiver_param.unwrap_or_default();
-        format!(
-            "({receiver_param}).{}({})",
-            sig.name,
-            call_params.join(", ")
-        )
+    for param in &sig.params.tail {
+        call_params.push(display_param_name(param));
     }
 
+    let receiver_param = receiver_param.unwrap_or_default();
+    format!(
+        "({receiver_param}).{}({})",
+        sig.name,
+        call_params.join(", ")
+    )
+}
+
+fn postfix_method_items<'a>(
+    ctx: &'a semantic::Context,
+    insert_dot: bool,
+    receiver_ty: &semantic::Ty,
+) -> Vec<Candidate<'a>> {
     fn postfix_first_param(sig: &semantic::FunctionSig) -> Option<&semantic::ParamSig> {
         if let Some(first) = sig.params.head.first() {
             return Some(first);
@@ -146,42 +266,30 @@ fn postfix_method_items(
         .iter()
         .filter(|func| postfix_capable.contains(func.name.as_str()))
         .filter(|func| receiver_matches_postfix_first_param(func, receiver_ty))
-        .map(|func| {
-            let label = format!(".{}()", func.name);
-            let insert_text = if insert_dot {
-                format!(".{}()", func.name)
-            } else {
-                format!("{}()", func.name)
-            };
-            CompletionItem::new(label, CompletionKind::from(func.category))
-                .with_insert_text(insert_text)
-                .with_detail(postfix_detail(func))
-                .with_data(CompletionData::PostfixMethod {
-                    name: func.name.clone(),
-                })
-        })
+        .map(|func| Candidate::PostfixMethod { func, insert_dot })
         .collect()
 }
 
-fn prop_variable_items(ctx: &semantic::Context) -> Vec<CompletionItem> {
-    if ctx.properties.is_empty() {
-        return Vec::new();
-    }
-    let mut enabled = Vec::new();
-    let mut disabled = Vec::new();
-    for prop in &ctx.properties {
-        let insert_text = format!(r#"prop("{}")"#, prop.name);
-        let item = CompletionItem::new(prop.name.clone(), CompletionKind::Property)
-            .with_insert_text(insert_text)
-            .with_data(CompletionData::PropExpr {
-                property_name: prop.name.clone(),
-            });
-        if let Some(reason) = &prop.disabled_reason {
-            disabled.push(item.disabled(reason.clone()));
-        } else {
-            enabled.push(item);
-        }
-    }
-    enabled.extend(disabled);
+/// The context's properties, then up to [`RESOLVED_PROPERTY_LIMIT`] resolved ones starting with
+/// `prefix` that the eager list lacks.
+fn prop_variable_items<'a>(ctx: &'a semantic::Context, prefix: &str) -> Vec<Candidate<'a>> {
+    let resolved = ctx
+        .property_resolver
+        .as_ref()
+        .map(|resolver| resolver.list(prefix, 0, RESOLVED_PROPERTY_LIMIT).properties)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|prop| ctx.properties.by_name(&prop.name).is_none());
+    // Enabled properties first, then disabled ones.
+    let (enabled, disabled): (Vec<_>, Vec<_>) = ctx
+        .properties
+        .iter()
+        .map(Cow::Borrowed)
+        .chain(resolved.map(Cow::Owned))
+        .partition(|prop| prop.disabled_reason.is_none());
     enabled
+        .into_iter()
+        .chain(disabled)
+        .map(Candidate::Property)
+        .collect()
 }
@@ -3,47 +3,146 @@
 //! Spans are half-open ranges `[start, end)`.
 
 pub use crate::TextEdit;
-use crate::signature::SignatureHelp;
-use analyzer::Span;
 use analyzer::semantic;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 mod items;
 mod matchers;
+mod option_values;
 mod ranking;
+mod snippets;
 
-pub(crate) use items::{after_atom_items, after_dot_items, expr_start_items};
+pub(crate) use items::{Candidate, after_atom_items, after_dot_items, expr_start_items};
+pub(crate) use option_values::OptionValueSlot;
+pub(crate) use items::snippet_items;
+pub(crate) use snippets::browse as browse_snippets;
+pub use snippets::{Snippet, SnippetError, SnippetPlaceholder, SnippetRegistry};
 pub(crate) use ranking::{
-    apply_type_ranking, attach_primary_edits, preferred_indices, rank_by_query,
+    apply_type_ranking, attach_match_strengths, attach_primary_edits, preferred_indices,
+    rank_by_query,
 };
 
 /// Default for `CompletionConfig.preferred_limit`.
 pub const DEFAULT_PREFERRED_LIMIT: usize = 5;
 
-/// Configuration knobs for `complete`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Most properties one completion request asks a
+/// [`PropertyResolver`](semantic::PropertyResolver) for.
+pub const RESOLVED_PROPERTY_LIMIT: usize = 50;
+
+/// Configuration knobs for [`help`](crate::help).
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CompletionConfig {
-    /// Max length of `CompletionOutput.preferred_indices` (0 disables it).
+    /// Max length of `CompletionResult.preferred_indices` (0 disables it).
     pub preferred_limit: usize,
+    /// Snippet mode: function items insert `name()` with the cursor inside the parens.
+    /// When `false`, they insert the bare `name`.
+    pub call_parens: bool,
+    /// Group items toward the expected argument type inside calls.
+    pub type_ranking: bool,
+    /// Item kinds to drop from the results (e.g. to hide operators or a function category).
+    pub exclude_kinds: Vec<CompletionKind>,
+    /// How the typed query matches item labels.
+    pub match_mode: MatchMode,
+    /// Weights behind the item order and `preferred_indices`.
+    pub ranking: RankingConfig,
+    /// Skip query ranking and type grouping, for hosts that rank items themselves.
+    ///
+    /// Items come in catalog order (properties, keywords and constants, then functions in
+    /// context order), methods after a `.` are kept even when they don't match, and
+    /// `preferred_indices` is empty. `CompletionItem::match_strength` is still set.
+    pub raw: bool,
+    /// Formula templates offered where an expression starts (`CompletionKind::Snippet`).
+    pub snippets: SnippetRegistry,
 }
 
-impl Default for CompletionConfig {
+/// How a completion query matches labels, after normalization (lowercased, `_` removed).
+///
+/// Matches rank before non-matches; after a `.`, non-matching methods are dropped. Only
+/// matching items become preferred.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    /// The query's chars appear in the label in order (`sm` matches `sum`).
+    #[default]
+    Fuzzy,
+    /// The label starts with the query.
+    Prefix,
+    /// The label contains the query as a substring.
+    Contains,
+}
+
+/// How well a query matches a label, weakest first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MatchStrength {
+    /// A subsequence match that is not a substring.
+    #[default]
+    Fuzzy,
+    /// A substring match after the start of the label.
+    Contains,
+    /// The label starts with the query.
+    Prefix,
+    /// The label equals the query.
+    Exact,
+}
+
+/// Tunable heuristics for ordering items and picking `preferred_indices`.
+///
+/// The defaults are the built-in policy. To always preselect properties before functions, boost
+/// `CompletionKind::Property` above every function kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankingConfig {
+    /// Weakest match that can be preferred.
+    pub preferred_min_match: MatchStrength,
+    /// Score added per kind; kinds not listed get 0.
+    ///
+    /// Among items with the same match strength, higher-scoring kinds sort first, and preferred
+    /// items are picked from the highest-scoring kinds first. Inside calls, it is added to the
+    /// type score when grouping by expected type.
+    pub kind_boosts: Vec<(CompletionKind, i32)>,
+    /// Type score of an item the expected argument type accepts.
+    pub type_match_bonus: i32,
+    /// Type score of an item whose result type is unknown.
+    pub type_unknown_bonus: i32,
+    /// Type score of an item the expected argument type rejects.
+    pub type_mismatch_bonus: i32,
+}
+
+impl RankingConfig {
+    /// The first boost listed for `kind`, or 0.
+    pub fn kind_boost(&self, kind: CompletionKind) -> i32 {
+        self.kind_boosts
+            .iter()
+            .find(|(boosted, _)| *boosted == kind)
+            .map_or(0, |&(_, boost)| boost)
+    }
+}
+
+impl Default for RankingConfig {
     fn default() -> Self {
         Self {
-            preferred_limit: DEFAULT_PREFERRED_LIMIT,
+            preferred_min_match: MatchStrength::Fuzzy,
+            kind_boosts: Vec::new(),
+            type_match_bonus: 2,
+            type_unknown_bonus: 0,
+            type_mismatch_bonus: -1,
         }
     }
 }
 
-/// Result of a completion query at a byte cursor.
-///
-/// `replace` and all edit ranges are UTF-8 byte spans, half-open `[start, end)`.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CompletionOutput {
-    pub items: Vec<CompletionItem>,
-    pub replace: Span,
-    pub signature_help: Option<SignatureHelp>,
-    /// Indices into `items` for the UI default selection.
-    pub preferred_indices: Vec<usize>,
+impl Default for CompletionConfig {
+    fn default() -> Self {
+        Self {
+            preferred_limit: DEFAULT_PREFERRED_LIMIT,
+            call_parens: true,
+            type_ranking: true,
+            exclude_kinds: Vec::new(),
+            match_mode: MatchMode::default(),
+            ranking: RankingConfig::default(),
+            raw: false,
+            snippets: SnippetRegistry::default(),
+        }
+    }
 }
 
 /// One completion candidate for an editor UI.
@@ -54,6 +153,7 @@ pub struct CompletionOutput {
 /// Use [`CompletionItem::new`] to construct with sensible defaults, then chain
 /// builder methods (`.with_detail()`, `.with_data()`, etc.) to customise.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CompletionItem {
     pub label: String,
     pub kind: CompletionKind,
@@ -64,7 +164,16 @@ pub struct CompletionItem {
     pub detail: Option<String>,
     pub is_disabled: bool,
     pub disabled_reason: Option<String>,
+    /// How well the typed query matches the label (without `()` or a leading `.`), under
+    /// `CompletionConfig::match_mode`. `None` without a query or when the label doesn't match.
+    pub match_strength: Option<MatchStrength>,
     pub data: Option<CompletionData>,
+    /// Tab stops of a snippet, in the document after the primary edit (like `cursor`).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub placeholders: Vec<SnippetPlaceholder>,
 }
 
 impl CompletionItem {
@@ -84,7 +193,9 @@ impl CompletionItem {
             detail: None,
             is_disabled: false,
             disabled_reason: None,
+            match_strength: None,
             data: None,
+            placeholders: Vec::new(),
         }
     }
 
@@ -108,10 +219,69 @@ impl CompletionItem {
         self.disabled_reason = Some(reason.into());
         self
     }
+
+    /// The primary edit's text in LSP / TextMate snippet syntax: `${1:default}` per placeholder
+    /// (linked occurrences share the number) and `$0` at `cursor` when it falls inside the
+    /// text. `None` when there is neither, or no primary edit.
+    pub fn snippet_text(&self) -> Option<String> {
+        let edit = self.primary_edit.as_ref()?;
+        let (start, text) = (edit.range.start, edit.new_text.as_str());
+        let offset = |at: u32| {
+            at.checked_sub(start)
+                .map(|offset| offset as usize)
+                .filter(|&offset| offset <= text.len() && text.is_char_boundary(offset))
+        };
+        // (start, end, tab stop) in text offsets; tab stop 0 is the cursor.
+        let mut stops: Vec<(usize, usize, usize)> = Vec::new();
+        for (idx, placeholder) in self.placeholders.iter().enumerate() {
+            for range in &placeholder.ranges {
+                if let (Some(from), Some(to)) = (offset(range.start), offset(range.end)) {
+                    stops.push((from, to, idx + 1));
+                }
+            }
+        }
+        if let Some(cursor) = self.cursor.and_then(offset).filter(|&at| at < text.len()) {
+            stops.push((cursor, cursor, 0));
+        }
+        if stops.is_empty() {
+            return None;
+        }
+        stops.sort_unstable();
+
+        let mut out = String::with_capacity(text.len() + 8 * stops.len());
+        let mut last = 0;
+        for (from, to, stop) in stops {
+            if from < last {
+                continue;
+            }
+            out.push_str(&escape_snippet(&text[last..from]));
+            if stop == 0 {
+                out.push_str("$0");
+            } else {
+                out.push_str(&format!("${{{stop}:{}}}", escape_snippet(&text[from..to])));
+            }
+            last = to;
+        }
+        out.push_str(&escape_snippet(&text[last..]));
+        Some(out)
+    }
+}
+
+/// Escapes `$`, `}`, and `\` for snippet syntax.
+fn escape_snippet(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '$' | '}' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
 }
 
 /// High-level bucket for UI grouping.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CompletionKind {
     FunctionGeneral,
     FunctionText,
@@ -120,9 +290,16 @@ pub enum CompletionKind {
     FunctionPeople,
     FunctionList,
     FunctionSpecial,
-    Builtin,
+    /// A keyword operator: `not`.
+    Keyword,
+    /// A literal constant: `true`, `false`, or a property's option inside a string literal.
+    Constant,
     Property,
+    /// A name bound inside the formula (`let`/`lets`). Not produced until binders are modeled.
+    Variable,
     Operator,
+    /// A formula template from `CompletionConfig::snippets`.
+    Snippet,
 }
 
 impl CompletionKind {
@@ -156,28 +333,14 @@ impl From<semantic::FunctionCategory> for CompletionKind {
 
 /// Extra metadata used for cursor placement and type ranking.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CompletionData {
     Function { name: String },
     PropExpr { property_name: String },
     PostfixMethod { name: String },
+    /// An option of the property `property_name`, completed inside a string literal.
+    OptionValue { property_name: String },
+    /// The snippet `name` of the registry.
+    Snippet { name: String },
 }
 
-/// Computes completion items and signature help at a cursor position.
-///
-/// `cursor` is a UTF-8 byte offset into `text`.
-#[allow(dead_code)]
-pub fn complete(
-    text: &str,
-    cursor: usize,
-    ctx: &semantic::Context,
-    config: CompletionConfig,
-) -> CompletionOutput {
-    let help = crate::help(text, cursor, ctx, config);
-
-    CompletionOutput {
-        items: help.completion.items,
-        replace: help.completion.replace,
-        signature_help: help.signature_help,
-        preferred_indices: help.completion.preferred_indices,
-    }
-}
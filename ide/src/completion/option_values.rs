@@ -0,0 +1,123 @@
+//! Option values of select, status, and multi-select properties, completed inside the string
+//! literal they are compared with: `prop("Status") == "|"`, `prop("Tags").includes("|")`.
+
+use std::borrow::Cow;
+
+use analyzer::ast::{BinOpKind, Expr, ExprKind};
+use analyzer::{LitKind, Span, semantic};
+
+use crate::completion::Candidate;
+use crate::hover::children;
+
+/// A string literal around the cursor whose value is one of a property's options.
+pub(crate) struct OptionValueSlot<'a> {
+    pub(crate) property: Cow<'a, semantic::Property>,
+    /// The literal's content, between the quotes.
+    pub(crate) content: Span,
+}
+
+impl<'a> OptionValueSlot<'a> {
+    /// The slot strictly inside a string literal (between its quotes) at `cursor`, if the literal
+    /// is compared (`==`, `!=`) with, or passed to `contains`/`includes` on, a property that
+    /// declares options.
+    pub(crate) fn find(root: &Expr, cursor: u32, ctx: &'a semantic::Context) -> Option<Self> {
+        let around = |expr: &Expr| expr.span.start < cursor && cursor < expr.span.end;
+        let mut current = root;
+        while !matches!(current.kind, ExprKind::Lit(_)) {
+            if let Some((subject, literal)) = compared_literal(current)
+                && is_string_literal(literal)
+                && around(literal)
+            {
+                let property = ctx.property(prop_name(ungroup(subject))?)?;
+                if property.options.is_empty() {
+                    return None;
+                }
+                let content = Span {
+                    start: literal.span.start + 1,
+                    end: literal.span.end - 1,
+                };
+                return Some(Self { property, content });
+            }
+            current = children(current).into_iter().find(|child| around(child))?;
+        }
+        None
+    }
+
+    pub(crate) fn candidates(&self) -> Vec<Candidate<'a>> {
+        match &self.property {
+            Cow::Borrowed(property) => property
+                .options
+                .iter()
+                .map(|value| Candidate::OptionValue {
+                    property: Cow::Borrowed(&property.name),
+                    value: Cow::Borrowed(value),
+                })
+                .collect(),
+            Cow::Owned(property) => property
+                .options
+                .iter()
+                .map(|value| Candidate::OptionValue {
+                    property: Cow::Owned(property.name.clone()),
+                    value: Cow::Owned(value.clone()),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// `(subject, literal)` for `subject == literal` (either way round), `subject != literal`,
+/// `contains(subject, literal)`, and `subject.includes(literal)`.
+fn compared_literal(expr: &Expr) -> Option<(&Expr, &Expr)> {
+    match &expr.kind {
+        ExprKind::Binary { op, left, right }
+            if matches!(op.node, BinOpKind::EqEq | BinOpKind::Ne) =>
+        {
+            if is_string_literal(ungroup(right)) {
+                Some((left, ungroup(right)))
+            } else {
+                Some((right, ungroup(left)))
+            }
+        }
+        ExprKind::Call { callee, args } if is_search(&callee.text) => match args.as_slice() {
+            [subject, literal] => Some((subject, ungroup(literal))),
+            _ => None,
+        },
+        ExprKind::MemberCall {
+            receiver,
+            method,
+            args,
+        } if is_search(&method.text) => match args.as_slice() {
+            [literal] => Some((receiver, ungroup(literal))),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_search(name: &str) -> bool {
+    matches!(name, "contains" | "includes")
+}
+
+fn is_string_literal(expr: &Expr) -> bool {
+    matches!(&expr.kind, ExprKind::Lit(lit) if lit.kind == LitKind::String)
+}
+
+fn prop_name(expr: &Expr) -> Option<&str> {
+    match &expr.kind {
+        ExprKind::Call { callee, args } if callee.text == "prop" => match args.as_slice() {
+            [arg] => match &arg.kind {
+                ExprKind::Lit(lit) if lit.kind == LitKind::String => Some(lit.symbol.text.as_str()),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn ungroup(mut expr: &Expr) -> &Expr {
+    while let ExprKind::Group { inner } = &expr.kind {
+        expr = inner;
+    }
+    expr
+}
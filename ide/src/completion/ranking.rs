@@ -1,11 +1,16 @@
-//! Ranks and post-processes completion items.
+//! Ranks completion candidates and post-processes the built items.
 //! Ranking uses an ASCII-ish normalized query (lowercased; `_` removed).
 //! Spans/cursors are UTF-8 byte offsets; ranges are half-open `[start, end)`.
 
-use std::cmp::Ordering;
+use std::borrow::Cow;
+use std::cmp::{Ordering, Reverse};
 
+use crate::completion::items::Candidate;
 use crate::completion::matchers::{FuzzyScore, fuzzy_score, fuzzy_score_cmp, normalize_for_match};
-use crate::completion::{CompletionData, CompletionItem, CompletionKind, TextEdit};
+use crate::completion::{
+    CompletionData, CompletionItem, CompletionKind, MatchMode, MatchStrength, RankingConfig,
+    TextEdit,
+};
 use crate::context::PositionKind;
 use analyzer::Span;
 use analyzer::semantic;
@@ -19,9 +24,10 @@ fn kind_priority(kind: CompletionKind) -> u8 {
         CompletionKind::FunctionPeople => 4,
         CompletionKind::FunctionList => 5,
         CompletionKind::FunctionSpecial => 6,
-        CompletionKind::Builtin => 7,
-        CompletionKind::Property => 8,
+        CompletionKind::Keyword | CompletionKind::Constant => 7,
+        CompletionKind::Property | CompletionKind::Variable => 8,
         CompletionKind::Operator => 9,
+        CompletionKind::Snippet => 10,
     }
 }
 
@@ -42,16 +48,35 @@ impl MatchClass {
             MatchClass::None => 3,
         }
     }
+
+    fn strength(self) -> Option<MatchStrength> {
+        match self {
+            MatchClass::Exact => Some(MatchStrength::Exact),
+            MatchClass::Contains { pos: 0 } => Some(MatchStrength::Prefix),
+            MatchClass::Contains { .. } => Some(MatchStrength::Contains),
+            MatchClass::Fuzzy(_) => Some(MatchStrength::Fuzzy),
+            MatchClass::None => None,
+        }
+    }
 }
 
-fn match_class_for_norm_label(query_norm: &str, label_norm: &str) -> MatchClass {
+fn match_class_for_norm_label(query_norm: &str, label_norm: &str, mode: MatchMode) -> MatchClass {
     if label_norm == query_norm {
         return MatchClass::Exact;
     }
+    if mode == MatchMode::Prefix {
+        return if label_norm.starts_with(query_norm) {
+            MatchClass::Contains { pos: 0 }
+        } else {
+            MatchClass::None
+        };
+    }
     if let Some(pos) = label_norm.find(query_norm) {
         return MatchClass::Contains { pos };
     }
-    if let Some(score) = fuzzy_score(query_norm, label_norm) {
+    if mode == MatchMode::Fuzzy
+        && let Some(score) = fuzzy_score(query_norm, label_norm)
+    {
         return MatchClass::Fuzzy(score);
     }
     MatchClass::None
@@ -64,17 +89,19 @@ enum RankMode {
 }
 
 #[derive(Debug)]
-struct RankedItem {
+struct RankedItem<'a> {
     original_idx: usize,
     label_norm_len: usize,
     class: MatchClass,
-    item: CompletionItem,
+    boost: i32,
+    item: Candidate<'a>,
 }
 
 fn cmp_ranked_items(a: &RankedItem, b: &RankedItem) -> Ordering {
     a.class
         .rank()
         .cmp(&b.class.rank())
+        .then_with(|| b.boost.cmp(&a.boost))
         .then_with(|| match (a.class, b.class) {
             (MatchClass::Exact, MatchClass::Exact) => a
                 .label_norm_len
@@ -85,57 +112,69 @@ fn cmp_ranked_items(a: &RankedItem, b: &RankedItem) -> Ordering {
                 .then_with(|| a.label_norm_len.cmp(&b.label_norm_len))
                 .then_with(|| a.original_idx.cmp(&b.original_idx)),
             (MatchClass::Fuzzy(sa), MatchClass::Fuzzy(sb)) => fuzzy_score_cmp(sa, sb)
-                .then_with(|| kind_priority(a.item.kind).cmp(&kind_priority(b.item.kind)))
+                .then_with(|| kind_priority(a.item.kind()).cmp(&kind_priority(b.item.kind())))
                 .then_with(|| a.original_idx.cmp(&b.original_idx)),
             (MatchClass::None, MatchClass::None) => a.original_idx.cmp(&b.original_idx),
             _ => a.original_idx.cmp(&b.original_idx),
         })
 }
 
-fn label_for_match(item: &CompletionItem, mode: RankMode) -> &str {
-    let base = match mode {
-        RankMode::Normal => item.label.as_str(),
-        RankMode::Postfix => item.label.trim_start_matches('.'),
-    };
-    if item.kind.is_function() {
-        return base.strip_suffix("()").unwrap_or(base);
+/// The label without `()`; in postfix mode also without the leading `.`.
+fn label_for_match<'a>(item: &Candidate<'a>, mode: RankMode) -> Cow<'a, str> {
+    match (mode, item) {
+        (RankMode::Postfix, Candidate::PostfixMethod { func, .. }) => Cow::Borrowed(&func.name),
+        _ => item.label_stem(),
     }
-    base
 }
 
-fn apply_query_ranking(query_norm: &str, items: &mut Vec<CompletionItem>, mode: RankMode) {
+fn apply_query_ranking(
+    query_norm: &str,
+    items: &mut Vec<Candidate>,
+    mode: RankMode,
+    match_mode: MatchMode,
+    ranking: &RankingConfig,
+) {
     let mut ranked: Vec<RankedItem> = items
         .drain(..)
         .enumerate()
-        .map(|(idx, item)| {
-            let label = label_for_match(&item, mode);
-            let label_norm = normalize_for_match(label);
-            let label_norm_len = label_norm.chars().count();
-
-            let class = match mode {
-                RankMode::Normal
-                    if !(item.kind.is_function() || item.kind == CompletionKind::Property) =>
-                {
-                    MatchClass::None
-                }
-                _ => match_class_for_norm_label(query_norm, &label_norm),
-            };
-
-            RankedItem {
+        .filter_map(|(idx, item)| {
+            let kind = item.kind();
+            let boost = ranking.kind_boost(kind);
+            let scored = kind.is_function()
+                || matches!(kind, CompletionKind::Property | CompletionKind::Snippet)
+                || matches!(item, Candidate::OptionValue { .. });
+            if mode == RankMode::Normal && !scored {
+                // Unscored items keep their place after all matches.
+                return Some(RankedItem {
+                    original_idx: idx,
+                    label_norm_len: 0,
+                    class: MatchClass::None,
+                    boost,
+                    item,
+                });
+            }
+
+            let label_norm = normalize_for_match(&label_for_match(&item, mode));
+            let class = match_class_for_norm_label(query_norm, &label_norm, match_mode);
+            if mode == RankMode::Postfix && class == MatchClass::None {
+                return None;
+            }
+            Some(RankedItem {
                 original_idx: idx,
-                label_norm_len,
+                label_norm_len: label_norm.chars().count(),
                 class,
+                boost,
                 item,
-            }
+            })
         })
-        .filter(|r| mode != RankMode::Postfix || r.class != MatchClass::None)
         .collect();
 
     ranked.sort_by(cmp_ranked_items);
-    *items = ranked.into_iter().map(|r| r.item).collect();
+    items.extend(ranked.into_iter().map(|r| r.item));
 }
 
-/// Fills in `primary_edit` and `cursor` for each item based on the replace span.
+/// Fills in `primary_edit` and `cursor` for each item based on the replace span, and moves
+/// snippet placeholders into document coordinates.
 pub(crate) fn attach_primary_edits(output_replace: Span, items: &mut [CompletionItem]) {
     for item in items {
         if item.is_disabled {
@@ -171,19 +210,33 @@ pub(crate) fn attach_primary_edits(output_replace: Span, items: &mut [Completion
                     .start
                     .saturating_add((idx as u32).saturating_add(1))
             }),
+            // After the formula; the placeholders are the tab stops before it.
+            Some(CompletionData::Snippet { .. }) => Some(
+                output_replace
+                    .start
+                    .saturating_add(item.insert_text.len() as u32),
+            ),
             _ => None,
         };
+        // Placeholder ranges come relative to the insert text.
+        for range in item.placeholders.iter_mut().flat_map(|p| &mut p.ranges) {
+            range.start = range.start.saturating_add(output_replace.start);
+            range.end = range.end.saturating_add(output_replace.start);
+        }
     }
 }
 
-/// Sorts and filters items by a query string.
+/// Sorts and filters candidates by a query string.
 ///
 /// In `AfterDot` position, items that don't match the query are removed entirely.
 /// In other positions, items are sorted by match quality but kept.
+/// Items matching equally well sort by kind boost.
 pub(crate) fn rank_by_query(
     query: &str,
-    items: &mut Vec<CompletionItem>,
+    items: &mut Vec<Candidate>,
     position_kind: PositionKind,
+    match_mode: MatchMode,
+    ranking: &RankingConfig,
 ) {
     let query_norm = normalize_for_match(query);
     let mode = if matches!(position_kind, PositionKind::AfterDot) {
@@ -191,48 +244,65 @@ pub(crate) fn rank_by_query(
     } else {
         RankMode::Normal
     };
-    apply_query_ranking(&query_norm, items, mode);
+    apply_query_ranking(&query_norm, items, mode, match_mode, ranking);
+}
+
+/// Sets each item's `match_strength` against `query`.
+pub(crate) fn attach_match_strengths(items: &mut [CompletionItem], query: &str, mode: MatchMode) {
+    let query_norm = normalize_for_match(query);
+    for item in items {
+        let label = item.label.strip_prefix('.').unwrap_or(&item.label);
+        let label = label.strip_suffix("()").unwrap_or(label);
+        item.match_strength =
+            match_class_for_norm_label(&query_norm, &normalize_for_match(label), mode).strength();
+    }
+}
+
+/// How well `query` matches `label` under `mode`, both normalized first.
+pub(crate) fn match_strength(query: &str, label: &str, mode: MatchMode) -> Option<MatchStrength> {
+    let (query, label) = (normalize_for_match(query), normalize_for_match(label));
+    match_class_for_norm_label(&query, &label, mode).strength()
 }
 
 /// Picks "smart" item indices that match the query, up to `preferred_limit`.
+///
+/// Only function, property, and option value items whose `match_strength` is at least
+/// `ranking.preferred_min_match` qualify. Higher kind boosts are picked first; otherwise items
+/// keep their list order.
 pub(crate) fn preferred_indices(
     items: &[CompletionItem],
-    query: &str,
     preferred_limit: usize,
+    ranking: &RankingConfig,
 ) -> Vec<usize> {
     if preferred_limit == 0 {
         return Vec::new();
     }
 
-    let query_norm = normalize_for_match(query);
-    let mut out = Vec::with_capacity(preferred_limit);
-    for (idx, item) in items.iter().enumerate() {
-        if out.len() >= preferred_limit {
-            break;
-        }
-        if item.is_disabled {
-            continue;
-        }
-        let label = if item.kind.is_function() {
-            item.label.strip_suffix("()").unwrap_or(&item.label)
-        } else {
-            &item.label
-        };
-        if (item.kind == CompletionKind::Property || item.kind.is_function())
-            && match_class_for_norm_label(&query_norm, &normalize_for_match(label))
-                != MatchClass::None
-        {
-            out.push(idx);
-        }
-    }
+    let mut out: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            !item.is_disabled
+                && (item.kind == CompletionKind::Property
+                    || item.kind.is_function()
+                    || matches!(item.data, Some(CompletionData::OptionValue { .. })))
+                && item
+                    .match_strength
+                    .is_some_and(|strength| strength >= ranking.preferred_min_match)
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+    out.sort_by_key(|&idx| Reverse(ranking.kind_boost(items[idx].kind)));
+    out.truncate(preferred_limit);
     out
 }
 
-/// Groups items by `CompletionKind` and reorders groups toward `expected_ty`.
+/// Groups candidates by `CompletionKind` and reorders groups toward `expected_ty`.
 pub(crate) fn apply_type_ranking(
-    items: &mut Vec<CompletionItem>,
+    items: &mut Vec<Candidate>,
     expected_ty: Option<semantic::Ty>,
     ctx: &semantic::Context,
+    ranking: &RankingConfig,
 ) {
     let expected_ty = match expected_ty {
         Some(expected_ty) => expected_ty,
@@ -251,16 +321,18 @@ pub(crate) fn apply_type_ranking(
             CompletionKind::FunctionPeople => 4,
             CompletionKind::FunctionList => 5,
             CompletionKind::FunctionSpecial => 6,
-            CompletionKind::Builtin => 7,
-            CompletionKind::Property => 8,
+            // Keywords and constants share one group, as do properties and variables.
+            CompletionKind::Keyword | CompletionKind::Constant => 7,
+            CompletionKind::Property | CompletionKind::Variable => 8,
             CompletionKind::Operator => 9,
+            CompletionKind::Snippet => 10,
         }
     }
 
     fn kind_section_priority(kind: CompletionKind) -> u8 {
         match kind {
-            CompletionKind::Builtin => 0,
-            CompletionKind::Property => 1,
+            CompletionKind::Keyword | CompletionKind::Constant => 0,
+            CompletionKind::Property | CompletionKind::Variable => 1,
             CompletionKind::FunctionGeneral => 2,
             CompletionKind::FunctionText => 3,
             CompletionKind::FunctionNumber => 4,
@@ -269,23 +341,25 @@ pub(crate) fn apply_type_ranking(
             CompletionKind::FunctionList => 7,
             CompletionKind::FunctionSpecial => 8,
             CompletionKind::Operator => 9,
+            CompletionKind::Snippet => 10,
         }
     }
 
     #[derive(Debug)]
-    struct ScoredItem {
+    struct ScoredItem<'a> {
         original_idx: usize,
         score: i32,
-        item: CompletionItem,
+        item: Candidate<'a>,
     }
 
-    let mut buckets: [Vec<ScoredItem>; 10] = std::array::from_fn(|_| Vec::new());
-    let mut best_score: [i32; 10] = [i32::MIN; 10];
+    let mut buckets: [Vec<ScoredItem>; 11] = std::array::from_fn(|_| Vec::new());
+    let mut best_score: [i32; 11] = [i32::MIN; 11];
 
     for (idx, item) in items.drain(..).enumerate() {
         let actual = item_result_ty(&item, ctx);
-        let score = type_match_score(expected_ty.clone(), actual);
-        let bucket = kind_index(item.kind);
+        let score = type_match_score(expected_ty.clone(), actual, ranking)
+            .saturating_add(ranking.kind_boost(item.kind()));
+        let bucket = kind_index(item.kind());
         best_score[bucket] = best_score[bucket].max(score);
         buckets[bucket].push(ScoredItem {
             original_idx: idx,
@@ -296,17 +370,19 @@ pub(crate) fn apply_type_ranking(
 
     for bucket in buckets.iter_mut() {
         bucket.sort_by(|a, b| {
-            let a_key = (a.item.is_disabled, -a.score, a.original_idx as i32);
-            let b_key = (b.item.is_disabled, -b.score, b.original_idx as i32);
+            let a_key = (a.item.is_disabled(), Reverse(a.score), a.original_idx);
+            let b_key = (b.item.is_disabled(), Reverse(b.score), b.original_idx);
             a_key.cmp(&b_key)
         });
     }
 
-    let mut order: Vec<usize> = (0..10).filter(|&i| !buckets[i].is_empty()).collect();
+    let mut order: Vec<usize> = (0..buckets.len())
+        .filter(|&i| !buckets[i].is_empty())
+        .collect();
     order.sort_by(|&a, &b| {
-        (-best_score[a]).cmp(&(-best_score[b])).then_with(|| {
-            let a_kind = buckets[a][0].item.kind;
-            let b_kind = buckets[b][0].item.kind;
+        best_score[b].cmp(&best_score[a]).then_with(|| {
+            let a_kind = buckets[a][0].item.kind();
+            let b_kind = buckets[b][0].item.kind();
             kind_section_priority(a_kind).cmp(&kind_section_priority(b_kind))
         })
     });
@@ -316,36 +392,28 @@ pub(crate) fn apply_type_ranking(
     }
 }
 
-fn item_result_ty(item: &CompletionItem, ctx: &semantic::Context) -> Option<semantic::Ty> {
-    if let Some(data) = &item.data {
-        return match data {
-            CompletionData::Function { name } => ctx
-                .functions
-                .iter()
-                .find(|func| func.name == *name)
-                .map(|func| func.ret.clone()),
-            CompletionData::PropExpr { property_name } => ctx.lookup(property_name),
-            CompletionData::PostfixMethod { .. } => None,
-        };
-    }
-
-    match item.kind {
-        CompletionKind::Builtin => match item.label.as_str() {
-            "true" | "false" | "not" => Some(semantic::Ty::Boolean),
-            _ => None,
-        },
-        _ => None,
+fn item_result_ty(item: &Candidate, ctx: &semantic::Context) -> Option<semantic::Ty> {
+    match item {
+        Candidate::Function(func) => ctx.function(&func.name).map(|func| func.ret.clone()),
+        Candidate::Property(prop) => Some(prop.ty.clone()),
+        Candidate::PostfixMethod { .. } | Candidate::Operator(_) | Candidate::Snippet(_) => None,
+        Candidate::Builtin(keyword) => keyword.kind.begins_expr().then_some(semantic::Ty::Boolean),
+        Candidate::OptionValue { .. } => Some(semantic::Ty::String),
     }
 }
 
-fn type_match_score(expected: semantic::Ty, actual: Option<semantic::Ty>) -> i32 {
+fn type_match_score(
+    expected: semantic::Ty,
+    actual: Option<semantic::Ty>,
+    ranking: &RankingConfig,
+) -> i32 {
     if matches!(expected, semantic::Ty::Unknown) {
         return 1;
     }
     match actual {
-        Some(semantic::Ty::Unknown) => 0,
-        Some(actual_ty) if semantic::ty_accepts(&expected, &actual_ty) => 2,
-        Some(_) => -1,
-        None => 0,
+        Some(semantic::Ty::Unknown) => ranking.type_unknown_bonus,
+        Some(actual_ty) if semantic::ty_accepts(&expected, &actual_ty) => ranking.type_match_bonus,
+        Some(_) => ranking.type_mismatch_bonus,
+        None => ranking.type_unknown_bonus,
     }
 }
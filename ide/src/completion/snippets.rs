@@ -0,0 +1,201 @@
+//! Formula templates ("snippets") that hosts register for completion and browsing.
+//!
+//! A template marks each part the user fills in as `${Default}`. The default text is inserted,
+//! and every occurrence of the same placeholder becomes one linked tab stop.
+
+use std::cmp::Reverse;
+
+use analyzer::Span;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::completion::MatchMode;
+use crate::completion::ranking::match_strength;
+
+/// A named formula template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// Completion label, e.g. `progressBar`.
+    pub name: String,
+    /// One line on what the formula computes, shown as the item detail.
+    pub description: String,
+    /// The formula with each placeholder at its default text.
+    pub body: String,
+    /// Tab stops in order of first occurrence, as ranges of `body`.
+    pub placeholders: Vec<SnippetPlaceholder>,
+}
+
+/// One tab stop of a snippet: its default text and every range where it occurs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SnippetPlaceholder {
+    pub default: String,
+    /// Sorted; all hold `default` and are edited together.
+    pub ranges: Vec<Span>,
+}
+
+/// A template that [`Snippet::new`] rejects.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SnippetError {
+    #[error("unterminated placeholder at byte {offset}")]
+    UnterminatedPlaceholder { offset: u32 },
+    #[error("empty placeholder at byte {offset}")]
+    EmptyPlaceholder { offset: u32 },
+}
+
+impl Snippet {
+    /// Parses `template`, where `${Default}` marks a placeholder (no nesting; a `$` not followed
+    /// by `{` is literal).
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        template: &str,
+    ) -> Result<Self, SnippetError> {
+        let mut body = String::with_capacity(template.len());
+        let mut placeholders: Vec<SnippetPlaceholder> = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find("${") {
+            let offset = (template.len() - rest.len() + open) as u32;
+            body.push_str(&rest[..open]);
+            let after = &rest[open + 2..];
+            let close = after
+                .find('}')
+                .ok_or(SnippetError::UnterminatedPlaceholder { offset })?;
+            let default = &after[..close];
+            if default.is_empty() {
+                return Err(SnippetError::EmptyPlaceholder { offset });
+            }
+            let range = Span {
+                start: body.len() as u32,
+                end: (body.len() + default.len()) as u32,
+            };
+            body.push_str(default);
+            match placeholders.iter_mut().find(|p| p.default == default) {
+                Some(placeholder) => placeholder.ranges.push(range),
+                None => placeholders.push(SnippetPlaceholder {
+                    default: default.to_string(),
+                    ranges: vec![range],
+                }),
+            }
+            rest = &after[close + 1..];
+        }
+        body.push_str(rest);
+        Ok(Self {
+            name: name.into(),
+            description: description.into(),
+            body,
+            placeholders,
+        })
+    }
+}
+
+/// The snippets offered by completion, set through
+/// [`CompletionConfig::snippets`](crate::CompletionConfig::snippets). Empty by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnippetRegistry {
+    snippets: Vec<Snippet>,
+}
+
+impl SnippetRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Common Notion formulas: a progress bar, a due-date countdown, and name initials.
+    ///
+    /// Their placeholders are property names.
+    pub fn common() -> Self {
+        const COMMON: [(&str, &str, &str); 3] = [
+            (
+                "progressBar",
+                "Ten-dot progress bar and percentage of a 0-1 number",
+                concat!(
+                    r#"repeat("●", round(prop("${Progress}") * 10)) + "#,
+                    r#"repeat("○", 10 - round(prop("${Progress}") * 10)) + " " + "#,
+                    r#"format(round(prop("${Progress}") * 100)) + "%""#,
+                ),
+            ),
+            (
+                "dueCountdown",
+                "Days left until a date, empty when it is not set",
+                concat!(
+                    r#"if(empty(prop("${Due}")), "", "#,
+                    r#"format(dateBetween(prop("${Due}"), today(), "days")) + " days left")"#,
+                ),
+            ),
+            (
+                "initials",
+                "Upper-case initials of the first and last word of a name",
+                concat!(
+                    r#"upper(substring(prop("${Name}"), 0, 1) + "#,
+                    r#"substring(last(split(prop("${Name}"), " ")), 0, 1))"#,
+                ),
+            ),
+        ];
+        let mut registry = Self::new();
+        for (name, description, template) in COMMON {
+            registry.insert(Snippet::new(name, description, template).expect("valid template"));
+        }
+        registry
+    }
+
+    /// Adds `snippet`, replacing the one with the same name in place.
+    pub fn insert(&mut self, snippet: Snippet) -> &mut Self {
+        match self.snippets.iter_mut().find(|s| s.name == snippet.name) {
+            Some(existing) => *existing = snippet,
+            None => self.snippets.push(snippet),
+        }
+        self
+    }
+
+    /// Like [`Self::insert`], by value.
+    pub fn with(mut self, snippet: Snippet) -> Self {
+        self.insert(snippet);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Snippet> {
+        self.snippets.iter().find(|snippet| snippet.name == name)
+    }
+
+    /// In insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &Snippet> {
+        self.snippets.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.snippets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snippets.is_empty()
+    }
+}
+
+/// Snippets for `query`: all of them, in registry order, when it is empty.
+///
+/// Otherwise, names matching under `mode`, best first, then snippets whose description contains
+/// the query (case-insensitively).
+pub(crate) fn browse<'r>(
+    registry: &'r SnippetRegistry,
+    query: &str,
+    mode: MatchMode,
+) -> Vec<&'r Snippet> {
+    if query.is_empty() {
+        return registry.iter().collect();
+    }
+    let query_lower = query.to_lowercase();
+    let mut found: Vec<(Option<_>, &Snippet)> = registry
+        .iter()
+        .filter_map(|snippet| match match_strength(query, &snippet.name, mode) {
+            Some(strength) => Some((Some(strength), snippet)),
+            None => snippet
+                .description
+                .to_lowercase()
+                .contains(&query_lower)
+                .then_some((None, snippet)),
+        })
+        .collect();
+    found.sort_by_key(|&(strength, _)| Reverse(strength));
+    found.into_iter().map(|(_, snippet)| snippet).collect()
+}
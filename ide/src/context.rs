@@ -1,37 +1,73 @@
-//! Cursor-context detection helpers shared by completion and signature help.
+//! Cursor-context detection shared by completion and signature help.
 //! All coordinates are UTF-8 byte offsets into the original source text.
+//!
+//! [`detect_cursor_context`] is public for editor heuristics such as deciding whether a typed
+//! character should open the completion popup:
+//!
+//! ```
+//! use analyzer::semantic::Context;
+//! use ide::context::{PositionKind, detect_cursor_context};
+//!
+//! let source = r#"prop("Title").lo"#;
+//! let tokens = analyzer::analyze_syntax(source).tokens;
+//! let ctx = Context::with_builtins(Vec::new());
+//! let cursor = detect_cursor_context(source, &tokens, source.len() as u32, &ctx);
+//! assert_eq!(cursor.position_kind, PositionKind::AfterDot);
+//! assert_eq!(cursor.query.as_deref(), Some("lo"));
+//! ```
 
 use analyzer::semantic;
-use analyzer::{LitKind, Span, Token, TokenKind};
+use analyzer::{LitKind, Span, Token, TokenKind, non_trivia};
 
 /// Coarse completion position derived from nearby non-trivia tokens.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum PositionKind {
+pub enum PositionKind {
+    /// An expression can start here: at the start of the formula, after any token but an
+    /// identifier, literal, or `)`, strictly inside an identifier, or right after an identifier
+    /// that a longer property, function, or keyword name starts with (`su|` for `sum`).
     NeedExpr,
+    /// Right after a complete operand (an identifier, a literal, or `)`), where an operator or a
+    /// `.method()` call can follow.
     AfterAtom,
+    /// After `receiver.`, optionally with part of the method name typed.
     AfterDot,
+    /// Nothing can be completed, e.g. inside a string literal.
     None,
 }
 
-/// Call-site information derived from tokens for the cursor position.
+/// The innermost call whose `(` is before the cursor.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct CallContext {
-    pub(crate) callee: String,
-    pub(crate) lparen_idx: usize,
-    pub(crate) arg_index: usize,
+pub struct CallContext {
+    /// Name of the called function, as written.
+    pub callee: String,
+    /// Index of the call's `(` in the token slice passed to [`detect_cursor_context`].
+    pub lparen_idx: usize,
+    /// Zero-based index of the argument the cursor is in, counting top-level commas.
+    pub arg_index: usize,
 }
 
-/// Full cursor context used by IDE help orchestration.
+/// What the cursor is positioned at, as used for completion and signature help.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct CursorContext {
-    pub(crate) call_ctx: Option<CallContext>,
-    pub(crate) position_kind: PositionKind,
-    pub(crate) replace: Span,
-    pub(crate) query: Option<String>,
+pub struct CursorContext {
+    /// The enclosing call, if any; set even when `position_kind` is `None`.
+    pub call_ctx: Option<CallContext>,
+    pub position_kind: PositionKind,
+    /// The span a completion replaces: the identifier being typed for `NeedExpr` and
+    /// `AfterDot`, or an empty span at the cursor.
+    pub replace: Span,
+    /// The typed text in `replace`, lowercased with `_` removed. `None` when `replace` is
+    /// empty or contains anything but ASCII letters, digits, and `_`.
+    pub query: Option<String>,
 }
 
 /// Detects call/position/replace/query context for the current cursor.
-pub(crate) fn detect_cursor_context(
+///
+/// `tokens` are the lexer tokens of `text` (e.g. `analyzer::analyze_syntax(text).tokens`), and
+/// `cursor` is a UTF-8 byte offset. `semantic_ctx` decides whether the identifier right before
+/// the cursor is still being typed: a case-insensitive prefix of a longer known name gives
+/// `NeedExpr`, anything else `AfterAtom`. Only tokens are inspected, so formulas with syntax
+/// errors work too.
+pub fn detect_cursor_context(
     text: &str,
     tokens: &[Token],
     cursor: u32,
@@ -64,6 +100,21 @@ pub(crate) fn detect_cursor_context(
     }
 }
 
+/// The replace span from tokens alone: the identifier the cursor is inside or right after, or an
+/// empty span at the cursor (always inside string literals).
+///
+/// Same as [`CursorContext::replace`] except after a complete name such as `sum`, which this
+/// still replaces because it cannot look names up.
+pub(crate) fn replace_span_without_context(tokens: &[Token], cursor: u32) -> Span {
+    if cursor_strictly_inside_string_literal(tokens, cursor) {
+        return Span {
+            start: cursor,
+            end: cursor,
+        };
+    }
+    replace_span_for_expr_start(tokens, cursor)
+}
+
 /// Classifies the cursor position using token neighbors.
 pub(crate) fn detect_position_kind(
     tokens: &[Token],
@@ -179,13 +230,10 @@ fn has_extending_completion_prefix(prefix: &str, ctx: &semantic::Context) -> boo
 
     let prefix_lower = prefix.to_ascii_lowercase();
 
-    if "true".starts_with(&prefix_lower) && prefix_lower != "true" {
-        return true;
-    }
-    if "false".starts_with(&prefix_lower) && prefix_lower != "false" {
-        return true;
-    }
-    if "not".starts_with(&prefix_lower) && prefix_lower != "not" {
+    if analyzer::Keywords::notion()
+        .words(|_| true)
+        .any(|word| word.starts_with(&prefix_lower) && prefix_lower != word)
+    {
         return true;
     }
 
@@ -201,6 +249,16 @@ fn has_extending_completion_prefix(prefix: &str, ctx: &semantic::Context) -> boo
         return true;
     }
 
+    // Listed matches all start with `prefix`; one that is longer extends it.
+    if ctx.property_resolver.as_ref().is_some_and(|resolver| {
+        let page = resolver.list(prefix, 0, 2);
+        page.properties
+            .iter()
+            .any(|prop| prop.name.len() > prefix.len())
+    }) {
+        return true;
+    }
+
     false
 }
 
@@ -232,32 +290,14 @@ fn prev_non_trivia_impl(
         return Some((idx, token));
     }
 
-    let mut prev = None;
-    for (idx, token) in tokens.iter().enumerate() {
-        if token.is_trivia() || matches!(token.kind, TokenKind::Eof) {
-            continue;
-        }
-        if token.span.end <= cursor {
-            prev = Some((idx, token));
-        } else {
-            break;
-        }
-    }
-    prev
+    non_trivia(tokens)
+        .take_while(|(_, token)| token.span.end <= cursor)
+        .last()
 }
 
 /// Finds the previous non-trivia token before `idx` (token index, not bytes).
 pub(crate) fn prev_non_trivia_before(tokens: &[Token], idx: usize) -> Option<(usize, &Token)> {
-    let mut i = idx;
-    while i > 0 {
-        i -= 1;
-        let token = &tokens[i];
-        if token.is_trivia() || matches!(token.kind, TokenKind::Eof) {
-            continue;
-        }
-        return Some((i, token));
-    }
-    None
+    non_trivia(&tokens[..idx]).next_back()
 }
 
 fn token_containing_cursor(tokens: &[Token], cursor: u32) -> Option<(usize, &Token)> {
@@ -311,10 +351,7 @@ pub(crate) fn replace_span_for_expr_start(tokens: &[Token], cursor: u32) -> Span
 /// Finds the innermost call whose `(` starts before `cursor`.
 pub(crate) fn detect_call_context(tokens: &[Token], cursor: u32) -> Option<CallContext> {
     let mut stack = Vec::new();
-    for (idx, token) in tokens.iter().enumerate() {
-        if token.is_trivia() || matches!(token.kind, TokenKind::Eof) {
-            continue;
-        }
+    for (idx, token) in non_trivia(tokens) {
         if token.span.start >= cursor {
             break;
         }
@@ -335,37 +372,22 @@ pub(crate) fn detect_call_context(tokens: &[Token], cursor: u32) -> Option<CallC
     let mut arg_index = 0usize;
     let mut paren_depth = 0i32;
     let mut bracket_depth = 0i32;
-    for token in tokens.iter().skip(lparen_idx + 1) {
-        if token.is_trivia() || matches!(token.kind, TokenKind::Eof) {
-            continue;
-        }
+    for (_, token) in non_trivia(&tokens[lparen_idx + 1..]) {
         if token.span.start >= cursor {
             break;
         }
         match token.kind {
             TokenKind::OpenParen => paren_depth += 1,
             TokenKind::OpenBracket => bracket_depth += 1,
-            TokenKind::CloseParen => {
-                if paren_depth > 0 {
-                    paren_depth -= 1;
-                }
-            }
-            TokenKind::CloseBracket => {
-                if bracket_depth > 0 {
-                    bracket_depth -= 1;
-                }
-            }
-            TokenKind::Comma => {
-                if paren_depth == 0 && bracket_depth == 0 {
-                    arg_index += 1;
-                }
-            }
+            TokenKind::CloseParen if paren_depth > 0 => paren_depth -= 1,
+            TokenKind::CloseBracket if bracket_depth > 0 => bracket_depth -= 1,
+            TokenKind::Comma if paren_depth == 0 && bracket_depth == 0 => arg_index += 1,
             _ => {}
         }
     }
 
     Some(CallContext {
-        callee: symbol.text.clone(),
+        callee: symbol.text.to_string(),
         lparen_idx,
         arg_index,
     })
@@ -380,9 +402,7 @@ pub(crate) fn expected_call_arg_ty(
 ) -> Option<semantic::Ty> {
     let call_ctx = call_ctx?;
     let ty = ctx
-        .functions
-        .iter()
-        .find(|func| func.name == call_ctx.callee)
+        .function(&call_ctx.callee)
         .and_then(|func| func.param_for_arg_index(call_ctx.arg_index))
         .map(|param| param.ty.clone())?;
 
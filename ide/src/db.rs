@@ -0,0 +1,149 @@
+//! Revision-keyed inputs and memoized queries backing [`AnalysisHost`](crate::AnalysisHost).
+//!
+//! Every input change bumps a global [`Revision`] and stamps the input with it. A [`Memo`]
+//! remembers the revision it was computed at; after inputs change, the host calls
+//! [`Memo::invalidate`] with the latest revision of the inputs that query reads, and the memo is
+//! recomputed on the next read. A memo also records when its value last *changed*: a
+//! recomputation equal to the previous value keeps the old `changed_at` (backdating), so callers
+//! can tell that e.g. diagnostics are unchanged after an edit.
+
+use std::cell::OnceCell;
+
+/// A point in the edit history; increases with every input change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Revision(u64);
+
+impl Revision {
+    pub(crate) fn next(self) -> Revision {
+        Revision(self.0 + 1)
+    }
+}
+
+/// An input value with the revision it was last set at.
+#[derive(Debug)]
+pub(crate) struct Input<T> {
+    value: T,
+    changed_at: Revision,
+}
+
+impl<T: PartialEq> Input<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self {
+            value,
+            changed_at: Revision::default(),
+        }
+    }
+
+    pub(crate) fn get(&self) -> &T {
+        &self.value
+    }
+
+    pub(crate) fn changed_at(&self) -> Revision {
+        self.changed_at
+    }
+
+    /// Stores `value` at `revision`. Setting an equal value is a no-op and returns `false`.
+    pub(crate) fn set(&mut self, value: T, revision: Revision) -> bool {
+        if value == self.value {
+            return false;
+        }
+        self.value = value;
+        self.changed_at = revision;
+        true
+    }
+}
+
+#[derive(Debug)]
+struct Memoized<T> {
+    value: T,
+    computed_at: Revision,
+    changed_at: Revision,
+}
+
+/// Cached result of a derived query.
+#[derive(Debug)]
+pub(crate) struct Memo<T> {
+    current: OnceCell<Memoized<T>>,
+    /// The last invalidated value, compared against the recomputation for backdating.
+    previous: Option<Memoized<T>>,
+}
+
+impl<T> Default for Memo<T> {
+    fn default() -> Self {
+        Self {
+            current: OnceCell::new(),
+            previous: None,
+        }
+    }
+}
+
+impl<T> Memo<T> {
+    /// Drops the value if it was computed before `inputs_changed_at`.
+    pub(crate) fn invalidate(&mut self, inputs_changed_at: Revision) {
+        if self
+            .current
+            .get()
+            .is_some_and(|memo| memo.computed_at < inputs_changed_at)
+        {
+            self.previous = self.current.take();
+        }
+    }
+
+    /// Whether a value is memoized.
+    pub(crate) fn is_computed(&self) -> bool {
+        self.current.get().is_some()
+    }
+
+    /// Takes the current value out, e.g. to update it incrementally.
+    pub(crate) fn take(&mut self) -> Option<T> {
+        self.current.take().map(|memo| memo.value)
+    }
+
+    /// Stores a value computed outside the memo at `revision`.
+    pub(crate) fn set(&mut self, value: T, revision: Revision) {
+        self.previous = None;
+        self.current = OnceCell::from(Memoized {
+            value,
+            computed_at: revision,
+            changed_at: revision,
+        });
+    }
+
+    /// The memoized value, computing it at `revision` if there is none.
+    pub(crate) fn get(&self, revision: Revision, compute: impl FnOnce() -> T) -> &T {
+        &self
+            .current
+            .get_or_init(|| Memoized {
+                value: compute(),
+                computed_at: revision,
+                changed_at: revision,
+            })
+            .value
+    }
+
+    /// Revision at which the memoized value last changed, if it is computed.
+    pub(crate) fn changed_at(&self) -> Option<Revision> {
+        self.current.get().map(|memo| memo.changed_at)
+    }
+}
+
+impl<T: PartialEq> Memo<T> {
+    /// Like [`Memo::get`], but a result equal to the invalidated value keeps its `changed_at`.
+    pub(crate) fn get_backdated(&self, revision: Revision, compute: impl FnOnce() -> T) -> &T {
+        &self
+            .current
+            .get_or_init(|| {
+                let value = compute();
+                let changed_at = match &self.previous {
+                    Some(previous) if previous.value == value => previous.changed_at,
+                    _ => revision,
+                };
+                Memoized {
+                    value,
+                    computed_at: revision,
+                    changed_at,
+                }
+            })
+            .value
+    }
+}
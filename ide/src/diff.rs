@@ -0,0 +1,118 @@
+//! Token-aligned diff between two versions of a formula.
+//!
+//! Both sources are split into pieces (a token plus the whitespace before it), the piece
+//! sequences are aligned with a longest-common-subsequence match, and each changed run becomes
+//! one edit shrunk to the bytes that actually differ. Aligning on tokens keeps edits within
+//! syntax nodes: renaming `prop("A")` to `prop("B")` touches only the literal.
+
+use analyzer::{Span, TextEdit};
+
+use crate::edit::minimal_edit;
+
+/// Above this many LCS cells, the changed middle is replaced as one (still trimmed) edit.
+const MAX_LCS_CELLS: usize = 1 << 22;
+
+/// Compute edits that turn `old_source` into `new_source`.
+///
+/// Edits are in `old_source` byte coordinates, sorted and non-overlapping, so they can be
+/// passed straight to `apply_edits`. Identical sources produce no edits.
+pub(crate) fn diff(old_source: &str, new_source: &str) -> Vec<TextEdit> {
+    let old_bounds = piece_bounds(old_source);
+    let new_bounds = piece_bounds(new_source);
+    let old_pieces = pieces(old_source, &old_bounds);
+    let new_pieces = pieces(new_source, &new_bounds);
+
+    let prefix = old_pieces
+        .iter()
+        .zip(&new_pieces)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_pieces[prefix..]
+        .iter()
+        .rev()
+        .zip(new_pieces[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old_pieces[prefix..old_pieces.len() - suffix];
+    let new_mid = &new_pieces[prefix..new_pieces.len() - suffix];
+
+    let runs = if old_mid.len().saturating_mul(new_mid.len()) > MAX_LCS_CELLS {
+        vec![((0, old_mid.len()), (0, new_mid.len()))]
+    } else {
+        changed_runs(old_mid, new_mid)
+    };
+
+    runs.into_iter()
+        .filter_map(|((old_lo, old_hi), (new_lo, new_hi))| {
+            let start = old_bounds[prefix + old_lo];
+            let end = old_bounds[prefix + old_hi];
+            let new_text = &new_source[new_bounds[prefix + new_lo]..new_bounds[prefix + new_hi]];
+            minimal_edit(old_source, start, end, new_text)
+        })
+        .collect()
+}
+
+/// Byte offsets where pieces start, plus `source.len()`.
+///
+/// Each piece ends at a token end, so piece `i` is `source[bounds[i]..bounds[i + 1]]`.
+fn piece_bounds(source: &str) -> Vec<usize> {
+    let mut bounds = vec![0];
+    for token in analyzer::analyze_syntax(source).tokens {
+        let Span { end, .. } = token.span;
+        let end = end as usize;
+        if end > *bounds.last().unwrap_or(&0) && source.is_char_boundary(end) {
+            bounds.push(end);
+        }
+    }
+    if *bounds.last().unwrap_or(&0) < source.len() {
+        bounds.push(source.len());
+    }
+    bounds
+}
+
+fn pieces<'a>(source: &'a str, bounds: &[usize]) -> Vec<&'a str> {
+    bounds.windows(2).map(|w| &source[w[0]..w[1]]).collect()
+}
+
+type Run = ((usize, usize), (usize, usize));
+
+/// Maximal runs of unmatched pieces, as `((old_lo, old_hi), (new_lo, new_hi))` index ranges.
+fn changed_runs(old: &[&str], new: &[&str]) -> Vec<Run> {
+    let (n, m) = (old.len(), new.len());
+    // lcs[i][j] = LCS length of old[i..] and new[j..].
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut runs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut run_start: Option<(usize, usize)> = None;
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] && lcs[i][j] == lcs[i + 1][j + 1] + 1 {
+            if let Some((lo_i, lo_j)) = run_start.take() {
+                runs.push(((lo_i, i), (lo_j, j)));
+            }
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        run_start.get_or_insert((i, j));
+        if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            j += 1;
+        } else {
+            i += 1;
+        }
+    }
+    if let Some((lo_i, lo_j)) = run_start {
+        runs.push(((lo_i, n), (lo_j, m)));
+    }
+    runs
+}
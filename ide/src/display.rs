@@ -4,10 +4,12 @@
 //! canonical place for formatting UI-facing signature help segments.
 
 use analyzer::semantic::Ty;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(tag = "kind", rename_all = "PascalCase")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "PascalCase"))]
 pub enum DisplaySegment {
     Name {
         text: String,
@@ -25,7 +27,10 @@ pub enum DisplaySegment {
     Param {
         name: String,
         ty: String,
-        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Option::is_none")
+        )]
         param_index: Option<u32>,
     },
     ReturnType {
@@ -1,43 +1,94 @@
-use crate::apply_text_edits_bytes_with_cursor;
-use analyzer::{Diagnostic, DiagnosticCode, Span as ByteSpan, TextEdit as ByteTextEdit};
+#[cfg(feature = "format")]
+use crate::format::FormatConfig;
+use crate::text_edit::{apply_text_edits_unchecked, check_edit_order, validate_edit};
+#[cfg(feature = "format")]
+use analyzer::ast::{Expr, ExprKind};
+#[cfg(feature = "format")]
+use analyzer::{CommentKind, DiagnosticCode, DiagnosticKind, Token, TokenKind};
+use analyzer::{Diagnostic, Span as ByteSpan, TextEdit as ByteTextEdit};
 
 /// Result payload for IDE edit operations in byte coordinates.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ApplyResult {
     pub source: String,
     pub cursor: u32,
+    /// Internal diagnostics raised by the operation; empty on success.
+    ///
+    /// `format` reports an `InternalError` here (and returns the source unchanged) when its
+    /// output fails verification.
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 /// Deterministic IDE operation errors.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum IdeError {
+///
+/// Edit indices refer to positions in the edit list the caller passed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum EditError {
+    #[error("Format error")]
     FormatError,
+    #[error("Invalid cursor")]
     InvalidCursor,
-    InvalidEditRange,
-    OverlappingEdits,
+    /// A query range (e.g. the `format_range` selection) is outside the source or splits a char.
+    #[error("Invalid range")]
+    InvalidRange,
+    /// Edit `index` has `start > end` or ends past the source.
+    #[error("Invalid edit range (edit #{index})")]
+    InvalidEditRange { index: usize },
+    /// Edit `index` has an endpoint `offset` inside a UTF-8 character.
+    #[error("Edit not on char boundary (edit #{index}, offset {offset})")]
+    EditNotOnCharBoundary { index: usize, offset: u32 },
+    /// Edits `first` and `second` (with `first < second`) replace overlapping ranges.
+    #[error("Overlapping edits (edits #{first} and #{second})")]
+    OverlappingEdits { first: usize, second: usize },
+    /// Edit `index` starts before the edit preceding it (for APIs requiring sorted edits).
+    #[error("Unsorted edits (edit #{index})")]
+    UnsortedEdits { index: usize },
 }
 
-impl IdeError {
+/// The former name of [`EditError`].
+pub type IdeError = EditError;
+
+impl EditError {
+    /// Stable, index-free category message (used at the WASM boundary).
     pub fn message(self) -> &'static str {
         match self {
-            IdeError::FormatError => "Format error",
-            IdeError::InvalidCursor => "Invalid cursor",
-            IdeError::InvalidEditRange => "Invalid edit range",
-            IdeError::OverlappingEdits => "Overlapping edits",
+            EditError::FormatError => "Format error",
+            EditError::InvalidCursor => "Invalid cursor",
+            EditError::InvalidRange => "Invalid range",
+            EditError::InvalidEditRange { .. } => "Invalid edit range",
+            EditError::EditNotOnCharBoundary { .. } => "Edit not on char boundary",
+            EditError::OverlappingEdits { .. } => "Overlapping edits",
+            EditError::UnsortedEdits { .. } => "Unsorted edits",
         }
     }
 }
 
 /// Format a source string and rebase a byte cursor through the full-document replacement edit.
-pub fn ide_format(source: &str, cursor: u32) -> Result<ApplyResult, IdeError> {
+#[cfg(feature = "format")]
+pub fn ide_format(
+    source: &str,
+    cursor: u32,
+    config: FormatConfig,
+) -> Result<ApplyResult, EditError> {
     let output = analyzer::analyze_syntax(source);
 
     if has_syntax_errors(&output.diagnostics) {
-        return Err(IdeError::FormatError);
+        return Err(EditError::FormatError);
+    }
+
+    let source_len = u32::try_from(source.len()).map_err(|_| EditError::InvalidRange)?;
+    let formatted = crate::format::format_expr(&output.expr, source, &output.tokens, config);
+
+    if let Some(diagnostic) = verify_formatted(&output.expr, &output.tokens, &formatted, source_len)
+    {
+        validate_cursor(source, cursor)?;
+        return Ok(ApplyResult {
+            source: source.to_string(),
+            cursor,
+            diagnostics: vec![diagnostic],
+        });
     }
 
-    let source_len = u32::try_from(source.len()).map_err(|_| IdeError::InvalidEditRange)?;
-    let formatted = crate::format::format_expr(&output.expr, source, &output.tokens);
     let full_document_edit = ByteTextEdit {
         range: ByteSpan {
             start: 0,
@@ -46,78 +97,248 @@ pub fn ide_format(source: &str, cursor: u32) -> Result<ApplyResult, IdeError> {
         new_text: formatted,
     };
 
-    apply_sorted_byte_edits(source, vec![full_document_edit], cursor)
+    apply_edits(source, vec![full_document_edit], cursor)
+}
+
+/// Minify a source string: strip comments and optional whitespace.
+#[cfg(feature = "format")]
+pub fn ide_format_minified(source: &str) -> Result<String, EditError> {
+    let output = analyzer::analyze_syntax(source);
+
+    if has_syntax_errors(&output.diagnostics) {
+        return Err(EditError::FormatError);
+    }
+
+    Ok(crate::format::minify_expr(&output.expr))
+}
+
+/// Reformat the smallest expression enclosing `range` and return minimal byte edits.
+///
+/// The selection is trimmed of surrounding whitespace before snapping, so selecting a whole
+/// line formats the expression on it. An unchanged expression yields no edits, and so does a
+/// result that fails the same verification as [`ide_format`].
+#[cfg(feature = "format")]
+pub fn ide_format_range(
+    source: &str,
+    range: ByteSpan,
+    config: FormatConfig,
+) -> Result<Vec<ByteTextEdit>, EditError> {
+    let (start, end) = (range.start as usize, range.end as usize);
+    if start > end
+        || end > source.len()
+        || !source.is_char_boundary(start)
+        || !source.is_char_boundary(end)
+    {
+        return Err(EditError::InvalidRange);
+    }
+
+    let output = analyzer::analyze_syntax(source);
+    if has_syntax_errors(&output.diagnostics) {
+        return Err(EditError::FormatError);
+    }
+
+    let selected = &source[start..end];
+    let trimmed_start = start + (selected.len() - selected.trim_start().len());
+    let trimmed_end = trimmed_start.max(end - (selected.len() - selected.trim_end().len()));
+    let selection = ByteSpan {
+        start: trimmed_start as u32,
+        end: trimmed_end as u32,
+    };
+
+    let target = enclosing_expr(&output.expr, selection);
+    let target_start = target.span.start as usize;
+    let target_end = target.span.end as usize;
+    let base_indent = line_indent_width(source, target_start) / config.indent_width.max(1);
+    let formatted =
+        crate::format::format_subexpr(target, source, &output.tokens, base_indent, config);
+
+    let Some(edit) = minimal_edit(source, target_start, target_end, &formatted) else {
+        return Ok(Vec::new());
+    };
+    let (updated, _) = apply_text_edits_unchecked(source, std::slice::from_ref(&edit), 0);
+    if verify_formatted(&output.expr, &output.tokens, &updated, source.len() as u32).is_some() {
+        return Ok(Vec::new());
+    }
+    Ok(vec![edit])
 }
 
 /// Apply byte edits in source coordinates and rebase a byte cursor.
+///
+/// Edits may come in any order. Invalid or overlapping edits are rejected (never skipped), and
+/// the error names the offending edit indices in the caller's list.
 pub fn apply_edits(
     source: &str,
-    mut edits: Vec<ByteTextEdit>,
+    edits: Vec<ByteTextEdit>,
     cursor: u32,
-) -> Result<ApplyResult, IdeError> {
-    edits.sort_by(|a, b| {
+) -> Result<ApplyResult, EditError> {
+    validate_cursor(source, cursor)?;
+    for (index, edit) in edits.iter().enumerate() {
+        validate_edit(source, index, edit)?;
+    }
+
+    let mut indexed: Vec<(usize, ByteTextEdit)> = edits.into_iter().enumerate().collect();
+    indexed.sort_by(|(_, a), (_, b)| {
         a.range
             .start
             .cmp(&b.range.start)
             .then(a.range.end.cmp(&b.range.end))
     });
+    check_edit_order(indexed.iter().map(|(index, edit)| (*index, edit)))?;
+
+    let edits: Vec<ByteTextEdit> = indexed.into_iter().map(|(_, edit)| edit).collect();
+    let (updated_source, cursor_after) = apply_text_edits_unchecked(source, &edits, cursor);
+    Ok(ApplyResult {
+        source: updated_source,
+        cursor: cursor_after,
+        diagnostics: Vec::new(),
+    })
+}
+
+/// Re-parse `formatted` and check it has the same AST as `original` (ignoring spans and trivia)
+/// and the same comments as `original_tokens`.
+///
+/// Returns an `InternalError` diagnostic spanning the whole source on mismatch.
+#[cfg(feature = "format")]
+pub(crate) fn verify_formatted(
+    original: &Expr,
+    original_tokens: &[Token],
+    formatted: &str,
+    source_len: u32,
+) -> Option<Diagnostic> {
+    let reparsed = analyzer::analyze_syntax(formatted);
+    if !has_syntax_errors(&reparsed.diagnostics)
+        && crate::format::ast_equivalent(original, &reparsed.expr)
+        && comment_words(original_tokens) == comment_words(&reparsed.tokens)
+    {
+        return None;
+    }
 
-    apply_sorted_byte_edits(source, edits, cursor)
+    Some(Diagnostic {
+        kind: DiagnosticKind::Error,
+        code: DiagnosticCode::InternalError,
+        message: "formatter output does not match the input; formatting skipped".into(),
+        span: ByteSpan {
+            start: 0,
+            end: source_len,
+        },
+        labels: Vec::new(),
+        notes: Vec::new(),
+        actions: Vec::new(),
+    })
 }
 
+/// The comments of `tokens` as a sorted multiset: block comments whole, line comments word by
+/// word, since `wrap_comments` may split a line comment over several lines.
+#[cfg(feature = "format")]
+fn comment_words(tokens: &[Token]) -> Vec<String> {
+    let mut words = Vec::new();
+    for tok in tokens {
+        match &tok.kind {
+            TokenKind::DocComment(CommentKind::Block, sym) => {
+                words.push(format!("/*{}*/", sym.text))
+            }
+            TokenKind::DocComment(CommentKind::Line, sym) => {
+                words.extend(sym.text.split_whitespace().map(|word| format!("//{word}")));
+            }
+            _ => {}
+        }
+    }
+    words.sort_unstable();
+    words
+}
+
+#[cfg(feature = "format")]
 fn has_syntax_errors(diagnostics: &[Diagnostic]) -> bool {
     diagnostics
         .iter()
         .any(|d| matches!(d.code, DiagnosticCode::LexError | DiagnosticCode::Parse(_)))
 }
 
-fn apply_sorted_byte_edits(
-    source: &str,
-    edits: Vec<ByteTextEdit>,
-    cursor: u32,
-) -> Result<ApplyResult, IdeError> {
-    validate_cursor(source, cursor)?;
-    validate_sorted_non_overlapping_edits(source, &edits)?;
-
-    let (updated_source, cursor_after) = apply_text_edits_bytes_with_cursor(source, &edits, cursor);
-    Ok(ApplyResult {
-        source: updated_source,
-        cursor: cursor_after,
-    })
-}
-
-fn validate_cursor(source: &str, cursor: u32) -> Result<(), IdeError> {
+fn validate_cursor(source: &str, cursor: u32) -> Result<(), EditError> {
     let cursor = cursor as usize;
     if cursor > source.len() || !source.is_char_boundary(cursor) {
-        return Err(IdeError::InvalidCursor);
+        return Err(EditError::InvalidCursor);
     }
     Ok(())
 }
 
-fn validate_sorted_non_overlapping_edits(
-    source: &str,
-    edits: &[ByteTextEdit],
-) -> Result<(), IdeError> {
-    let mut prev_end = 0u32;
-    let source_len = u32::try_from(source.len()).map_err(|_| IdeError::InvalidEditRange)?;
-
-    for (index, edit) in edits.iter().enumerate() {
-        if edit.range.end < edit.range.start || edit.range.end > source_len {
-            return Err(IdeError::InvalidEditRange);
+/// Smallest expression whose span covers `selection`, falling back to `root`.
+#[cfg(feature = "format")]
+fn enclosing_expr(root: &Expr, selection: ByteSpan) -> &Expr {
+    let mut current = root;
+    'descend: loop {
+        for child in child_exprs(current) {
+            if child.span.start <= selection.start && selection.end <= child.span.end {
+                current = child;
+                continue 'descend;
+            }
         }
+        return current;
+    }
+}
 
-        if !source.is_char_boundary(edit.range.start as usize)
-            || !source.is_char_boundary(edit.range.end as usize)
-        {
-            return Err(IdeError::InvalidEditRange);
+#[cfg(feature = "format")]
+fn child_exprs(expr: &Expr) -> Vec<&Expr> {
+    match &expr.kind {
+        ExprKind::Group { inner } => vec![inner],
+        ExprKind::List { items } => items.iter().collect(),
+        ExprKind::Call { args, .. } => args.iter().collect(),
+        ExprKind::MemberCall { receiver, args, .. } => {
+            std::iter::once(&**receiver).chain(args.iter()).collect()
         }
+        ExprKind::Unary { expr, .. } => vec![expr],
+        ExprKind::Binary { left, right, .. } => vec![left, right],
+        ExprKind::Ternary {
+            cond,
+            then,
+            otherwise,
+        } => vec![cond, then, otherwise],
+        ExprKind::Ident(_) | ExprKind::Lit(_) | ExprKind::Error => Vec::new(),
+    }
+}
 
-        if index > 0 && edit.range.start < prev_end {
-            return Err(IdeError::OverlappingEdits);
-        }
+/// Width of the leading whitespace on the line containing `offset`.
+#[cfg(feature = "format")]
+fn line_indent_width(source: &str, offset: usize) -> usize {
+    let line_start = source[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+    source[line_start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .count()
+}
 
-        prev_end = edit.range.end;
+/// Shrink a `[start, end) -> new_text` replacement to the bytes that actually change.
+pub(crate) fn minimal_edit(
+    source: &str,
+    start: usize,
+    end: usize,
+    new_text: &str,
+) -> Option<ByteTextEdit> {
+    let old_text = &source[start..end];
+    if old_text == new_text {
+        return None;
     }
 
-    Ok(())
+    let prefix = old_text
+        .char_indices()
+        .zip(new_text.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(old_text.len().min(new_text.len()), |((idx, _), _)| idx);
+    let old_rest = &old_text[prefix..];
+    let new_rest = &new_text[prefix..];
+    let suffix = old_rest
+        .chars()
+        .rev()
+        .zip(new_rest.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+
+    Some(ByteTextEdit {
+        range: ByteSpan {
+            start: (start + prefix) as u32,
+            end: (end - suffix) as u32,
+        },
+        new_text: new_rest[..new_rest.len() - suffix].to_string(),
+    })
 }
@@ -0,0 +1,335 @@
+//! Semantic equivalence of two formulas, for checking refactors and migrations.
+//!
+//! Both formulas are normalized before comparison:
+//! - comments, whitespace, and parentheses are dropped;
+//! - `x.f(a)` becomes `f(x, a)`, `not` becomes `!`, and `a > b` becomes `b < a`;
+//! - `c ? a : b`, `if`, and `ifs` (also nested in the last argument) become one flat `ifs`;
+//! - operators over literals are folded;
+//! - chains of `&&`, `||`, and numeric `+`/`*` are flattened and their operands sorted (literals
+//!   folded together), as are the operands of `==` and `!=`; text `+` chains are only flattened.
+//!
+//! Coordinates are UTF-8 byte offsets.
+
+use analyzer::ast::{BinOpKind, Expr, ExprKind, NotKind, UnOp};
+use analyzer::semantic::{self, Ty};
+use analyzer::{Span, TypeMap};
+
+use crate::rename::string_literal;
+use crate::simplify::{Const, binary, constant, format_number};
+
+/// Result of [`equivalent`](crate::equivalent).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Equivalence {
+    Equivalent,
+    /// The first differing subexpressions after normalization: the innermost pair whose
+    /// operators, names, values, or operand counts differ, taking operands in normalized order.
+    Different {
+        left: Span,
+        right: Span,
+    },
+    /// A formula has syntax errors, so nothing was compared.
+    SyntaxError,
+}
+
+pub(crate) fn equivalent(left: &str, right: &str, ctx: &semantic::Context) -> Equivalence {
+    let (Some(left), Some(right)) = (normalize(left, ctx), normalize(right, ctx)) else {
+        return Equivalence::SyntaxError;
+    };
+    match first_difference(&left, &right) {
+        None => Equivalence::Equivalent,
+        Some((left, right)) => Equivalence::Different { left, right },
+    }
+}
+
+fn normalize(source: &str, ctx: &semantic::Context) -> Option<Node> {
+    let syntax = analyzer::analyze_syntax(source);
+    if !syntax.diagnostics.is_empty() {
+        return None;
+    }
+    let mut type_map = TypeMap::default();
+    semantic::analyze_expr_with_map(&syntax.expr, ctx, &mut type_map);
+    let normalizer = Normalizer {
+        type_map: &type_map,
+    };
+    Some(normalizer.node(&syntax.expr))
+}
+
+fn first_difference(left: &Node, right: &Node) -> Option<(Span, Span)> {
+    if left.head != right.head || left.children.len() != right.children.len() {
+        return Some((left.span, right.span));
+    }
+    left.children
+        .iter()
+        .zip(&right.children)
+        .find_map(|(left, right)| first_difference(left, right))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Head {
+    Const(Const),
+    Ident(String),
+    /// Functions and methods alike.
+    Call(String),
+    /// A well-formed `if`, `ifs`, or `? :`, flattened to `ifs` arguments.
+    Conditional,
+    List,
+    Unary(UnOp),
+    Binary {
+        op: BinOpKind,
+        /// Operands of nested uses are spliced in.
+        associative: bool,
+        /// Operands are sorted.
+        commutative: bool,
+    },
+    Error,
+}
+
+/// A normalized expression, keeping the source span it came from.
+#[derive(Debug, Clone)]
+struct Node {
+    head: Head,
+    span: Span,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn leaf(head: Head, span: Span) -> Self {
+        Node {
+            head,
+            span,
+            children: Vec::new(),
+        }
+    }
+
+    fn constant(&self) -> Option<&Const> {
+        match &self.head {
+            Head::Const(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Canonical text, used as the sort key of commutative operands.
+    fn key(&self) -> String {
+        let children = || {
+            self.children
+                .iter()
+                .map(Node::key)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match &self.head {
+            Head::Const(Const::Number(n)) => format_number(*n),
+            Head::Const(Const::Bool(b)) => b.to_string(),
+            Head::Const(Const::Text(text)) => string_literal(text),
+            Head::Ident(name) => name.clone(),
+            Head::Call(name) => format!("{name}({})", children()),
+            Head::Conditional => format!("ifs({})", children()),
+            Head::List => format!("[{}]", children()),
+            Head::Unary(op) => format!("{}({})", op.as_str(), children()),
+            Head::Binary { op, .. } => format!("{}({})", op.as_str(), children()),
+            Head::Error => "?".to_string(),
+        }
+    }
+}
+
+struct Normalizer<'a> {
+    type_map: &'a TypeMap,
+}
+
+impl Normalizer<'_> {
+    fn node(&self, expr: &Expr) -> Node {
+        let span = expr.span;
+        match &expr.kind {
+            ExprKind::Group { inner } => self.node(inner),
+            ExprKind::Lit(_) => match constant(expr) {
+                Some(value) => Node::leaf(Head::Const(value), span),
+                None => Node::leaf(Head::Error, span),
+            },
+            ExprKind::Ident(sym) => Node::leaf(Head::Ident(sym.text.to_string()), span),
+            ExprKind::Error => Node::leaf(Head::Error, span),
+            ExprKind::List { items } => Node {
+                head: Head::List,
+                span,
+                children: items.iter().map(|item| self.node(item)).collect(),
+            },
+            ExprKind::Call { callee, args } => self.call(callee.text.as_str(), args, span),
+            ExprKind::MemberCall {
+                receiver,
+                method,
+                args,
+            } => {
+                let args: Vec<&Expr> = std::iter::once(&**receiver).chain(args).collect();
+                self.call(method.text.as_str(), args, span)
+            }
+            ExprKind::Ternary {
+                cond,
+                then,
+                otherwise,
+            } => self.conditional([&**cond, &**then, &**otherwise], span),
+            ExprKind::Unary { op, expr } => self.unary(*op, expr, span),
+            ExprKind::Binary { op, left, right } => {
+                self.binary(op.node, left, right, self.ty(expr), span)
+            }
+        }
+    }
+
+    fn call<'e>(&self, name: &str, args: impl IntoIterator<Item = &'e Expr>, span: Span) -> Node {
+        let args: Vec<&Expr> = args.into_iter().collect();
+        let valid_conditional = match name {
+            "if" => args.len() == 3,
+            "ifs" => args.len() >= 3 && args.len() % 2 == 1,
+            _ => false,
+        };
+        if valid_conditional {
+            return self.conditional(args, span);
+        }
+        Node {
+            head: Head::Call(name.to_string()),
+            span,
+            children: args.into_iter().map(|arg| self.node(arg)).collect(),
+        }
+    }
+
+    /// `[condition, value, ..., fallback]` as a flat `ifs`.
+    fn conditional<'e>(&self, args: impl IntoIterator<Item = &'e Expr>, span: Span) -> Node {
+        let mut children: Vec<Node> = args.into_iter().map(|arg| self.node(arg)).collect();
+        while children
+            .last()
+            .is_some_and(|last| last.head == Head::Conditional)
+        {
+            let nested = children.pop().expect("checked above");
+            children.extend(nested.children);
+        }
+        Node {
+            head: Head::Conditional,
+            span,
+            children,
+        }
+    }
+
+    fn unary(&self, op: UnOp, operand: &Expr, span: Span) -> Node {
+        let operand = self.node(operand);
+        let folded = match (op, operand.constant()) {
+            (UnOp::Neg, Some(Const::Number(n))) => Some(Const::Number(-n)),
+            (UnOp::Not(_), Some(Const::Bool(b))) => Some(Const::Bool(!b)),
+            _ => None,
+        };
+        if let Some(value) = folded {
+            return Node::leaf(Head::Const(value), span);
+        }
+        let op = match op {
+            UnOp::Not(_) => UnOp::Not(NotKind::Bang),
+            UnOp::Neg => UnOp::Neg,
+        };
+        Node {
+            head: Head::Unary(op),
+            span,
+            children: vec![operand],
+        }
+    }
+
+    fn binary(&self, op: BinOpKind, left: &Expr, right: &Expr, ty: Ty, span: Span) -> Node {
+        use BinOpKind::*;
+
+        let (op, left, right) = match op {
+            Gt => (Lt, right, left),
+            Ge => (Le, right, left),
+            _ => (op, left, right),
+        };
+        // Numbers and text only chain with operands of their own type, e.g. `a + (b + "c")`.
+        let (associative, commutative) = match op {
+            AndAnd | OrOr => (true, true),
+            Plus | Star if ty == Ty::Number => (true, true),
+            Plus if ty == Ty::String => (true, false),
+            EqEq | Ne => (false, true),
+            _ => (false, false),
+        };
+        let head = Head::Binary {
+            op,
+            associative,
+            commutative,
+        };
+        let mut operands = Vec::new();
+        for operand in [self.node(left), self.node(right)] {
+            if associative && operand.head == head {
+                operands.extend(operand.children);
+            } else {
+                operands.push(operand);
+            }
+        }
+
+        if !associative {
+            if let (Some(a), Some(b)) = (operands[0].constant(), operands[1].constant())
+                && let Some(value) = fold(op, a, b)
+            {
+                return Node::leaf(Head::Const(value), span);
+            }
+        } else if commutative {
+            let (constants, mut rest): (Vec<Node>, Vec<Node>) = operands
+                .into_iter()
+                .partition(|operand| operand.constant().is_some());
+            rest.extend(fold_run(op, constants));
+            operands = rest;
+        } else {
+            let mut merged: Vec<Node> = Vec::new();
+            for operand in operands {
+                match merged.pop() {
+                    Some(last) if last.constant().is_some() && operand.constant().is_some() => {
+                        merged.extend(fold_run(op, vec![last, operand]));
+                    }
+                    Some(last) => merged.extend([last, operand]),
+                    None => merged.push(operand),
+                }
+            }
+            operands = merged;
+        }
+        if commutative {
+            operands.sort_by_cached_key(Node::key);
+        }
+        if operands.len() == 1 {
+            let mut operand = operands.pop().expect("one operand");
+            operand.span = span;
+            return operand;
+        }
+        Node {
+            head,
+            span,
+            children: operands,
+        }
+    }
+
+    fn ty(&self, expr: &Expr) -> Ty {
+        self.type_map.get(expr.id).cloned().unwrap_or(Ty::Unknown)
+    }
+}
+
+/// `op` over two constants, unless the result is not a finite value.
+fn fold(op: BinOpKind, left: &Const, right: &Const) -> Option<Const> {
+    match binary(op, left.clone(), right.clone())? {
+        Const::Number(n) if !n.is_finite() => None,
+        value => Some(value),
+    }
+}
+
+/// Folds constant operands left to right, keeping those that do not fold.
+fn fold_run(op: BinOpKind, constants: Vec<Node>) -> Vec<Node> {
+    let mut out: Vec<Node> = Vec::new();
+    for node in constants {
+        let folded = out
+            .last()
+            .and_then(|last| fold(op, last.constant()?, node.constant()?));
+        match folded {
+            Some(value) => {
+                let last = out.last_mut().expect("folded with the last operand");
+                last.span = Span {
+                    start: last.span.start.min(node.span.start),
+                    end: last.span.end.max(node.span.end),
+                };
+                last.head = Head::Const(value);
+            }
+            None => out.push(node),
+        }
+    }
+    out
+}
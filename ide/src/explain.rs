@@ -0,0 +1,262 @@
+//! Plain-language descriptions of a formula, as accessible summaries for non-technical readers.
+//!
+//! Each node describes one sub-expression in a full phrase; editors can show the root alone or
+//! expand the tree. Coordinates are UTF-8 byte offsets.
+
+use analyzer::ast::{BinOpKind, Expr, ExprKind, UnOp};
+use analyzer::semantic::Ty;
+use analyzer::{LitKind, Span, TypeMap};
+
+/// What an [`Explanation`] node describes relative to its parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExplanationRole {
+    /// The whole formula.
+    Formula,
+    /// A test of `if`, `ifs`, or `? :`.
+    Condition,
+    /// The value shown when the preceding condition holds.
+    Then,
+    /// The value shown when no condition holds.
+    Otherwise,
+    /// An operand of an operator.
+    Operand,
+    /// The value a method is called on.
+    Receiver,
+    Argument,
+    /// A list item.
+    Item,
+}
+
+/// A node of the tree returned by [`explain`](crate::explain).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Explanation {
+    pub role: ExplanationRole,
+    /// Source range of the described expression, without enclosing parentheses.
+    pub span: Span,
+    /// Inferred type (`Unknown` when inference fails).
+    pub ty: Ty,
+    /// The whole expression in words, e.g. `If Status is "Done", show Due formatted as "MMM D",
+    /// otherwise show "Open"`.
+    pub text: String,
+    /// Sub-expressions worth their own line, in source order. Literals, properties, and names
+    /// are only described inline.
+    pub children: Vec<Explanation>,
+}
+
+pub(crate) fn explain(root: &Expr, types: &TypeMap) -> Explanation {
+    node(root, ExplanationRole::Formula, types)
+}
+
+fn node(expr: &Expr, role: ExplanationRole, types: &TypeMap) -> Explanation {
+    let expr = ungroup(expr);
+    Explanation {
+        role,
+        span: expr.span,
+        ty: types.get(expr.id).cloned().unwrap_or(Ty::Unknown),
+        text: capitalize(phrase(expr, types)),
+        children: parts(expr)
+            .into_iter()
+            .filter(|(part, _)| !is_atom(part))
+            .map(|(part, role)| node(part, role, types))
+            .collect(),
+    }
+}
+
+fn ungroup(mut expr: &Expr) -> &Expr {
+    while let ExprKind::Group { inner } = &expr.kind {
+        expr = inner;
+    }
+    expr
+}
+
+/// Expressions described by a single word or literal.
+fn is_atom(expr: &Expr) -> bool {
+    let expr = ungroup(expr);
+    matches!(
+        expr.kind,
+        ExprKind::Lit(_) | ExprKind::Ident(_) | ExprKind::Error
+    ) || prop_name(expr).is_some()
+}
+
+fn prop_name(expr: &Expr) -> Option<&str> {
+    match &expr.kind {
+        ExprKind::Call { callee, args } if callee.text == "prop" => match args.as_slice() {
+            [arg] => match &arg.kind {
+                ExprKind::Lit(lit) if lit.kind == LitKind::String => Some(lit.symbol.text.as_str()),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A call's arguments with the receiver of a method call first.
+fn call_args(expr: &Expr) -> Option<(&str, Vec<&Expr>)> {
+    match &expr.kind {
+        ExprKind::Call { callee, args } => Some((callee.text.as_str(), args.iter().collect())),
+        ExprKind::MemberCall {
+            receiver,
+            method,
+            args,
+        } => Some((
+            method.text.as_str(),
+            std::iter::once(&**receiver).chain(args).collect(),
+        )),
+        _ => None,
+    }
+}
+
+/// `if`/`ifs`/`? :` as `(condition, value)` branches and the fallback value.
+fn conditional(expr: &Expr) -> Option<(Vec<(&Expr, &Expr)>, &Expr)> {
+    if let ExprKind::Ternary {
+        cond,
+        then,
+        otherwise,
+    } = &expr.kind
+    {
+        return Some((vec![(&**cond, &**then)], otherwise));
+    }
+    let (name, args) = call_args(expr)?;
+    let valid = match name {
+        "if" => args.len() == 3,
+        "ifs" => args.len() >= 3 && args.len() % 2 == 1,
+        _ => false,
+    };
+    if !valid {
+        return None;
+    }
+    let (otherwise, branches) = args.split_last()?;
+    let branches = branches.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+    Some((branches, otherwise))
+}
+
+fn parts(expr: &Expr) -> Vec<(&Expr, ExplanationRole)> {
+    use ExplanationRole::*;
+
+    if let Some((branches, otherwise)) = conditional(expr) {
+        let mut out: Vec<_> = branches
+            .into_iter()
+            .flat_map(|(cond, value)| [(cond, Condition), (value, Then)])
+            .collect();
+        out.push((otherwise, Otherwise));
+        return out;
+    }
+    match &expr.kind {
+        ExprKind::Group { inner } => parts(inner),
+        ExprKind::List { items } => items.iter().map(|item| (item, Item)).collect(),
+        ExprKind::Call { .. } if prop_name(expr).is_some() => Vec::new(),
+        ExprKind::Call { args, .. } => args.iter().map(|arg| (arg, Argument)).collect(),
+        ExprKind::MemberCall { receiver, args, .. } => std::iter::once((&**receiver, Receiver))
+            .chain(args.iter().map(|arg| (arg, Argument)))
+            .collect(),
+        ExprKind::Unary { expr, .. } => vec![(&**expr, Operand)],
+        ExprKind::Binary { left, right, .. } => vec![(&**left, Operand), (&**right, Operand)],
+        ExprKind::Ternary { .. } | ExprKind::Ident(_) | ExprKind::Lit(_) | ExprKind::Error => {
+            Vec::new()
+        }
+    }
+}
+
+/// `expr` in words, starting lowercase unless it starts with a name.
+fn phrase(expr: &Expr, types: &TypeMap) -> String {
+    let p = |expr: &Expr| phrase(expr, types);
+
+    if let Some(name) = prop_name(expr) {
+        return name.to_string();
+    }
+    if let Some((branches, otherwise)) = conditional(expr) {
+        let mut clauses: Vec<String> = branches
+            .into_iter()
+            .map(|(cond, value)| format!("if {}, show {}", p(cond), p(value)))
+            .collect();
+        clauses.push(format!("otherwise show {}", p(otherwise)));
+        let separator = if clauses.len() > 2 { "; " } else { ", " };
+        return clauses.join(separator);
+    }
+    match &expr.kind {
+        ExprKind::Ident(sym) => sym.text.to_string(),
+        ExprKind::Lit(lit) => match lit.kind {
+            LitKind::String => format!("\"{}\"", lit.symbol.text),
+            _ => lit.symbol.text.to_string(),
+        },
+        ExprKind::Group { inner } => p(inner),
+        ExprKind::List { items } if items.is_empty() => "an empty list".to_string(),
+        ExprKind::List { items } => format!("a list of {}", join(items.iter().map(p).collect())),
+        ExprKind::Unary { op, expr } => match op {
+            UnOp::Not(_) => format!("not {}", p(expr)),
+            UnOp::Neg => format!("minus {}", p(expr)),
+        },
+        ExprKind::Binary { op, left, right } => {
+            // Joining text, also when the other side's type is unknown.
+            let text_join = [left, right]
+                .iter()
+                .any(|side| matches!(types.get(side.id), Some(Ty::String)));
+            // Parentheses in the source stay, so `(a + 1) * 2` is not read as `a + 1 * 2`.
+            let operand = |side: &Expr| match (&side.kind, &ungroup(side).kind) {
+                (ExprKind::Group { .. }, ExprKind::Binary { .. }) => format!("({})", p(side)),
+                _ => p(side),
+            };
+            let (left, right) = (operand(left), operand(right));
+            match op.node {
+                BinOpKind::EqEq => format!("{left} is {right}"),
+                BinOpKind::Ne => format!("{left} is not {right}"),
+                BinOpKind::Lt => format!("{left} is less than {right}"),
+                BinOpKind::Le => format!("{left} is at most {right}"),
+                BinOpKind::Gt => format!("{left} is greater than {right}"),
+                BinOpKind::Ge => format!("{left} is at least {right}"),
+                BinOpKind::AndAnd => format!("{left} and {right}"),
+                BinOpKind::OrOr => format!("{left} or {right}"),
+                BinOpKind::Plus if text_join => format!("{left} followed by {right}"),
+                BinOpKind::Plus => format!("{left} plus {right}"),
+                BinOpKind::Minus => format!("{left} minus {right}"),
+                BinOpKind::Star => format!("{left} times {right}"),
+                BinOpKind::Slash => format!("{left} divided by {right}"),
+                BinOpKind::Percent => format!("the remainder of {left} divided by {right}"),
+                BinOpKind::Caret => format!("{left} to the power of {right}"),
+            }
+        }
+        ExprKind::Call { .. } | ExprKind::MemberCall { .. } => {
+            let (name, args) = call_args(expr).expect("calls have arguments");
+            let args: Vec<String> = args.into_iter().map(p).collect();
+            call_phrase(name, args)
+        }
+        ExprKind::Ternary { .. } => unreachable!("ternaries are conditionals"),
+        ExprKind::Error => "…".to_string(),
+    }
+}
+
+fn call_phrase(name: &str, args: Vec<String>) -> String {
+    match (name, args.as_slice()) {
+        ("empty", [value]) => format!("{value} is empty"),
+        ("equal", [a, b]) => format!("{a} is {b}"),
+        ("unequal", [a, b]) => format!("{a} is not {b}"),
+        ("format", [value]) => format!("{value} as text"),
+        ("formatDate", [date, format]) => format!("{date} formatted as {format}"),
+        (_, []) => name.to_string(),
+        _ => format!("{name} of {}", join(args)),
+    }
+}
+
+/// `a`, `a and b`, `a, b, and c`.
+fn join(mut items: Vec<String>) -> String {
+    match items.len() {
+        0 => String::new(),
+        1 => items.remove(0),
+        2 => format!("{} and {}", items[0], items[1]),
+        _ => {
+            let last = items.pop().unwrap_or_default();
+            format!("{}, and {last}", items.join(", "))
+        }
+    }
+}
+
+fn capitalize(text: String) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) if first.is_lowercase() => first.to_uppercase().chain(chars).collect(),
+        _ => text,
+    }
+}
@@ -5,11 +5,53 @@
 use std::collections::HashSet;
 
 use analyzer::TokenQuery;
-use analyzer::ast::{BinOp, BinOpKind, Expr, ExprKind, UnOp};
+use analyzer::ast::{BinOp, Expr, ExprKind, UnOp};
 use analyzer::{CommentKind, Lit, LitKind, SourceMap, Span, Token, TokenKind, TokenRange};
 
-const INDENT: usize = 2;
-const MAX_WIDTH: usize = 80;
+/// Default for `FormatConfig.indent_width`.
+pub const DEFAULT_INDENT_WIDTH: usize = 2;
+/// Default for `FormatConfig.max_width`.
+pub const DEFAULT_MAX_WIDTH: usize = 80;
+
+/// Layout knobs for the formatter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatConfig {
+    /// Spaces per indentation level.
+    pub indent_width: usize,
+    /// Preferred maximum line width (in bytes) before breaking.
+    pub max_width: usize,
+    /// Wrap own-line `//` comments that exceed `max_width` at word boundaries.
+    pub wrap_comments: bool,
+    /// Re-join broken calls and lists onto one line when they fit.
+    ///
+    /// Off by default: source line breaks inside calls and lists are kept.
+    /// Sequences containing `//` comments always stay broken.
+    pub collapse_fitting: bool,
+    /// Where the closing delimiter of a broken call or list goes.
+    pub closing_delim: ClosingDelim,
+}
+
+/// Placement of `)` / `]` when a call or list is broken one item per line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClosingDelim {
+    /// On its own line at the call's indent.
+    #[default]
+    OwnLine,
+    /// Directly after the last item (unless that line ends in a `//` comment).
+    SameLine,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            indent_width: DEFAULT_INDENT_WIDTH,
+            max_width: DEFAULT_MAX_WIDTH,
+            wrap_comments: false,
+            collapse_fitting: false,
+            closing_delim: ClosingDelim::OwnLine,
+        }
+    }
+}
 
 fn source_has_newline(span: Span, source: &str) -> bool {
     let len = source.len();
@@ -40,6 +82,7 @@ pub struct Formatter<'a> {
     token_query: TokenQuery<'a>,
     used_comments: HashSet<usize>,
     sm: SourceMap<'a>,
+    config: FormatConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -51,30 +94,32 @@ struct Line {
 #[derive(Debug, Clone, Default)]
 struct Rendered {
     lines: Vec<Line>,
+    /// The last line ends in a `//` comment, so nothing may be appended to it.
+    ends_in_line_comment: bool,
 }
 
 impl Rendered {
     fn single(indent: usize, text: impl Into<String>) -> Self {
-        Self {
-            lines: vec![Line {
-                indent,
-                text: text.into(),
-            }],
-        }
+        let mut out = Self::default();
+        out.push_line(indent, text);
+        out
     }
 
     fn push_line(&mut self, indent: usize, text: impl Into<String>) {
-        self.lines.push(Line {
-            indent,
-            text: text.into(),
-        });
+        let text = text.into();
+        self.ends_in_line_comment = text.starts_with("//");
+        self.lines.push(Line { indent, text });
     }
 
     fn append(&mut self, mut other: Rendered) {
+        if !other.lines.is_empty() {
+            self.ends_in_line_comment = other.ends_in_line_comment;
+        }
         self.lines.append(&mut other.lines);
     }
 
     fn append_trailing(&mut self, text: &str) {
+        self.ends_in_line_comment = text.starts_with("//");
         if let Some(last) = self.lines.last_mut() {
             if !last.text.is_empty() {
                 last.text.push(' ');
@@ -88,13 +133,13 @@ impl Rendered {
         }
     }
 
-    fn render(self) -> String {
+    fn render(self, indent_width: usize) -> String {
         let mut out = String::new();
         for (i, line) in self.lines.into_iter().enumerate() {
             if i > 0 {
                 out.push('\n');
             }
-            for _ in 0..(line.indent * INDENT) {
+            for _ in 0..(line.indent * indent_width) {
                 out.push(' ');
             }
             out.push_str(&line.text);
@@ -106,24 +151,219 @@ impl Rendered {
 /// Formats `expr` into a stable string, ending with a single trailing `\n`.
 ///
 /// `source` and `tokens` must describe the same original text.
-pub fn format_expr(expr: &Expr, source: &str, tokens: &[Token]) -> String {
-    let mut fmt = Formatter::new(source, tokens);
-    let mut s = fmt.format_expr_rendered(expr, 0).render();
+pub fn format_expr(expr: &Expr, source: &str, tokens: &[Token], config: FormatConfig) -> String {
+    let mut fmt = Formatter::new(source, tokens, config);
+    let mut rendered = fmt.format_expr_rendered(expr, 0);
+    // Comments after the last expression token (e.g. a final own-line `// note`).
+    for idx in fmt.unused_comments_from(expr.span.end) {
+        fmt.used_comments.insert(idx);
+        rendered.append(fmt.render_comment_lines(idx, 0));
+    }
+    let mut s = rendered.render(config.indent_width);
     if !s.ends_with('\n') {
         s.push('\n');
     }
     s
 }
 
+/// Formats the sub-expression `expr` for in-place replacement of `expr.span`.
+///
+/// Continuation lines are indented by `base_indent` levels. The first line gets no indent,
+/// because it continues whatever precedes `expr.span.start` on its line.
+/// Comments outside `expr.span` are left to the surrounding text.
+pub fn format_subexpr(
+    expr: &Expr,
+    source: &str,
+    tokens: &[Token],
+    base_indent: usize,
+    config: FormatConfig,
+) -> String {
+    let mut fmt = Formatter::new(source, tokens, config);
+    let outside = tokens.iter().enumerate().filter(|(_, tok)| {
+        tok.kind.is_comment()
+            && (tok.span.end <= expr.span.start || tok.span.start >= expr.span.end)
+    });
+    fmt.used_comments.extend(outside.map(|(idx, _)| idx));
+
+    let mut rendered = fmt.format_expr_rendered(expr, base_indent);
+    if let Some(first) = rendered.lines.first_mut() {
+        first.indent = 0;
+    }
+    rendered.render(config.indent_width)
+}
+
+/// Renders `expr` on one line with comments and optional whitespace removed.
+///
+/// Groups are kept as written, so precedence is unchanged. The only space emitted is the
+/// one after the `not` keyword, which would otherwise merge with its operand.
+pub fn minify_expr(expr: &Expr) -> String {
+    let mut out = String::new();
+    write_minified(expr, &mut out);
+    out
+}
+
+fn write_minified(expr: &Expr, out: &mut String) {
+    match &expr.kind {
+        ExprKind::Ident(sym) => out.push_str(&sym.text),
+        ExprKind::Group { inner } => {
+            out.push('(');
+            write_minified(inner, out);
+            out.push(')');
+        }
+        ExprKind::List { items } => {
+            out.push('[');
+            write_minified_seq(items, out);
+            out.push(']');
+        }
+        ExprKind::Lit(lit) => out.push_str(&render_literal(lit)),
+        ExprKind::Call { callee, args } => {
+            out.push_str(&callee.text);
+            out.push('(');
+            write_minified_seq(args, out);
+            out.push(')');
+        }
+        ExprKind::MemberCall {
+            receiver,
+            method,
+            args,
+        } => {
+            write_minified(receiver, out);
+            out.push('.');
+            out.push_str(&method.text);
+            out.push('(');
+            write_minified_seq(args, out);
+            out.push(')');
+        }
+        ExprKind::Unary { op, expr: inner } => {
+            out.push_str(op.as_str());
+            if matches!(op, UnOp::Not(analyzer::ast::NotKind::Keyword)) {
+                out.push(' ');
+            }
+            write_minified(inner, out);
+        }
+        ExprKind::Binary { op, left, right } => {
+            write_minified(left, out);
+            out.push_str(op.node.as_str());
+            write_minified(right, out);
+        }
+        ExprKind::Ternary {
+            cond,
+            then,
+            otherwise,
+        } => {
+            write_minified(cond, out);
+            out.push('?');
+            write_minified(then, out);
+            out.push(':');
+            write_minified(otherwise, out);
+        }
+        ExprKind::Error => out.push_str("<error>"),
+    }
+}
+
+/// Structural AST equality that ignores node ids, spans, and trivia.
+pub fn ast_equivalent(a: &Expr, b: &Expr) -> bool {
+    match (&a.kind, &b.kind) {
+        (ExprKind::Ident(a), ExprKind::Ident(b)) => a == b,
+        (ExprKind::Group { inner: a }, ExprKind::Group { inner: b }) => ast_equivalent(a, b),
+        (ExprKind::List { items: a }, ExprKind::List { items: b }) => all_equivalent(a, b),
+        (ExprKind::Lit(a), ExprKind::Lit(b)) => a == b,
+        (
+            ExprKind::Call {
+                callee: a_callee,
+                args: a_args,
+            },
+            ExprKind::Call {
+                callee: b_callee,
+                args: b_args,
+            },
+        ) => a_callee == b_callee && all_equivalent(a_args, b_args),
+        (
+            ExprKind::MemberCall {
+                receiver: a_receiver,
+                method: a_method,
+                args: a_args,
+            },
+            ExprKind::MemberCall {
+                receiver: b_receiver,
+                method: b_method,
+                args: b_args,
+            },
+        ) => {
+            a_method == b_method
+                && ast_equivalent(a_receiver, b_receiver)
+                && all_equivalent(a_args, b_args)
+        }
+        (
+            ExprKind::Unary {
+                op: a_op,
+                expr: a_expr,
+            },
+            ExprKind::Unary {
+                op: b_op,
+                expr: b_expr,
+            },
+        ) => a_op == b_op && ast_equivalent(a_expr, b_expr),
+        (
+            ExprKind::Binary {
+                op: a_op,
+                left: a_left,
+                right: a_right,
+            },
+            ExprKind::Binary {
+                op: b_op,
+                left: b_left,
+                right: b_right,
+            },
+        ) => {
+            a_op.node == b_op.node
+                && ast_equivalent(a_left, b_left)
+                && ast_equivalent(a_right, b_right)
+        }
+        (
+            ExprKind::Ternary {
+                cond: a_cond,
+                then: a_then,
+                otherwise: a_otherwise,
+            },
+            ExprKind::Ternary {
+                cond: b_cond,
+                then: b_then,
+                otherwise: b_otherwise,
+            },
+        ) => {
+            ast_equivalent(a_cond, b_cond)
+                && ast_equivalent(a_then, b_then)
+                && ast_equivalent(a_otherwise, b_otherwise)
+        }
+        (ExprKind::Error, ExprKind::Error) => true,
+        _ => false,
+    }
+}
+
+fn all_equivalent(a: &[Expr], b: &[Expr]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| ast_equivalent(a, b))
+}
+
+fn write_minified_seq(items: &[Expr], out: &mut String) {
+    for (idx, item) in items.iter().enumerate() {
+        if idx > 0 {
+            out.push(',');
+        }
+        write_minified(item, out);
+    }
+}
+
 impl<'a> Formatter<'a> {
     /// Creates a formatter for `source` and its `tokens`.
-    pub fn new(source: &'a str, tokens: &'a [Token]) -> Self {
+    pub fn new(source: &'a str, tokens: &'a [Token], config: FormatConfig) -> Self {
         Self {
             source,
             tokens,
             token_query: TokenQuery::new(tokens),
             used_comments: HashSet::new(),
             sm: SourceMap::new(source),
+            config,
         }
     }
 
@@ -144,19 +384,15 @@ impl<'a> Formatter<'a> {
     fn format_delimited_seq(
         &mut self,
         mut out: Rendered,
+        span: Span,
         indent: usize,
         open: String,
-        open_appends_to_last: bool,
         close: &str,
         items: &[Expr],
     ) -> Rendered {
         // Member calls keep `).method(` chained on the receiver's last line.
-        if open_appends_to_last {
-            if let Some(last) = out.lines.last_mut() {
-                last.text.push_str(&open);
-            } else {
-                out.push_line(indent, open);
-            }
+        if let Some(last) = out.lines.last_mut() {
+            last.text.push_str(&open);
         } else {
             out.push_line(indent, open);
         }
@@ -167,26 +403,46 @@ impl<'a> Formatter<'a> {
             if !is_last && let Some(last) = item_r.lines.last_mut() {
                 last.text.push(',');
             }
+            if !is_last && let Some(idx) = self.take_comment_after_comma(item) {
+                item_r.append_trailing(&self.render_comment(idx));
+            }
             out.append(item_r);
         }
 
-        out.push_line(indent, close);
+        // Comments between the last item and the closing delimiter have no item to attach to.
+        for idx in self.unused_comments_in(span) {
+            self.used_comments.insert(idx);
+            out.append(self.render_comment_lines(idx, indent + 1));
+        }
+
+        match self.config.closing_delim {
+            ClosingDelim::SameLine if !items.is_empty() && !out.ends_in_line_comment => {
+                if let Some(last) = out.lines.last_mut() {
+                    last.text.push_str(close);
+                }
+            }
+            _ => out.push_line(indent, close),
+        }
         out
     }
 
     fn format_expr_rendered(&mut self, expr: &Expr, indent: usize) -> Rendered {
         let mut out = Rendered::default();
 
-        let (leading_comments, inline_block_comment) = self.take_leading_comments(expr);
+        let (leading_comments, inline_block_comments) = self.take_leading_comments(expr);
 
         for idx in leading_comments {
-            out.push_line(indent, self.render_comment(idx));
+            out.append(self.render_comment_lines(idx, indent));
         }
 
         let mut body = self.format_expr_kind(expr, indent);
 
-        if let Some(idx) = inline_block_comment {
-            let prefix = format!("{} ", self.render_comment(idx));
+        if !inline_block_comments.is_empty() {
+            let rendered: Vec<String> = inline_block_comments
+                .into_iter()
+                .map(|idx| self.render_comment(idx))
+                .collect();
+            let prefix = format!("{} ", rendered.join(" "));
             if let Some(first) = body.lines.first_mut() {
                 first.text = format!("{}{}", prefix, first.text);
             } else {
@@ -250,7 +506,7 @@ impl<'a> Formatter<'a> {
     }
 
     fn format_list(&mut self, expr: &Expr, indent: usize, items: &[Expr]) -> Rendered {
-        let has_newline = self.expr_has_newline(expr);
+        let has_newline = self.keeps_source_breaks(expr);
 
         if !has_newline
             && let Some(out) = self.try_inline(|this| {
@@ -258,6 +514,9 @@ impl<'a> Formatter<'a> {
                 for item in items {
                     parts.push(this.format_expr_single_line(item, indent)?);
                 }
+                if this.has_unused_comments_in(expr.span) {
+                    return None;
+                }
                 let text = format!("[{}]", parts.join(", "));
                 this.fits_on_line(indent, text.len())
                     .then_some(Rendered::single(indent, text))
@@ -268,9 +527,9 @@ impl<'a> Formatter<'a> {
 
         self.format_delimited_seq(
             Rendered::default(),
+            expr.span,
             indent,
             "[".to_string(),
-            false,
             "]",
             items,
         )
@@ -297,16 +556,24 @@ impl<'a> Formatter<'a> {
             return out;
         }
 
-        let mut out = Rendered::default();
-        let lparen = if needs_space {
-            format!("{op_str} (")
+        // The operator is prefixed to the operand's first line rather than wrapping the operand in
+        // new parentheses, which would change the AST (and double up an existing group).
+        let prefix = if needs_space {
+            format!("{op_str} ")
         } else {
-            format!("{op_str}(")
+            op_str.to_string()
         };
-        out.push_line(indent, lparen);
-        let inner_rendered = self.format_expr_rendered(inner, indent + 1);
-        out.append(inner_rendered);
-        out.push_line(indent, ")");
+        let mut out = self.format_expr_rendered(inner, indent);
+        match out.lines.first_mut() {
+            Some(first) if !first.text.starts_with("//") => {
+                first.text = format!("{prefix}{}", first.text);
+            }
+            _ => {
+                let mut prefixed = Rendered::single(indent, prefix.trim_end().to_string());
+                prefixed.append(out);
+                out = prefixed;
+            }
+        }
         out
     }
 
@@ -318,7 +585,7 @@ impl<'a> Formatter<'a> {
         left: &Expr,
         right: &Expr,
     ) -> Rendered {
-        let op_str = binop_str(op.node);
+        let op_str = op.node.as_str();
         let has_newline = self.expr_has_newline(expr);
         let trailing_line_comment = self
             .available_trailing_comment(expr)
@@ -428,7 +695,7 @@ impl<'a> Formatter<'a> {
     }
 
     fn format_call(&mut self, expr: &Expr, indent: usize, callee: &str, args: &[Expr]) -> Rendered {
-        let has_newline = self.expr_has_newline(expr);
+        let has_newline = self.keeps_source_breaks(expr);
 
         if !has_newline
             && let Some(out) = self.try_inline(|this| {
@@ -436,6 +703,9 @@ impl<'a> Formatter<'a> {
                 for arg in args {
                     parts.push(this.format_expr_single_line(arg, indent)?);
                 }
+                if this.has_unused_comments_in(expr.span) {
+                    return None;
+                }
                 let text = format!("{callee}({})", parts.join(", "));
                 this.fits_on_line(indent, text.len())
                     .then_some(Rendered::single(indent, text))
@@ -446,9 +716,9 @@ impl<'a> Formatter<'a> {
 
         self.format_delimited_seq(
             Rendered::default(),
+            expr.span,
             indent,
             format!("{callee}("),
-            false,
             ")",
             args,
         )
@@ -462,7 +732,7 @@ impl<'a> Formatter<'a> {
         method: &str,
         args: &[Expr],
     ) -> Rendered {
-        let has_newline = self.expr_has_newline(expr);
+        let has_newline = self.keeps_source_breaks(expr);
 
         if !has_newline
             && let Some(out) = self.try_inline(|this| {
@@ -471,6 +741,9 @@ impl<'a> Formatter<'a> {
                 for arg in args {
                     parts.push(this.format_expr_single_line(arg, indent)?);
                 }
+                if this.has_unused_comments_in(expr.span) {
+                    return None;
+                }
                 let text = format!("{receiver_inline}.{method}({})", parts.join(", "));
                 this.fits_on_line(indent, text.len())
                     .then_some(Rendered::single(indent, text))
@@ -480,7 +753,14 @@ impl<'a> Formatter<'a> {
         }
 
         let receiver_r = self.format_expr_rendered(receiver, indent);
-        self.format_delimited_seq(receiver_r, indent, format!(".{method}("), true, ")", args)
+        self.format_delimited_seq(
+            receiver_r,
+            expr.span,
+            indent,
+            format!(".{method}("),
+            ")",
+            args,
+        )
     }
 
     fn format_expr_single_line(&mut self, expr: &Expr, indent: usize) -> Option<String> {
@@ -493,7 +773,7 @@ impl<'a> Formatter<'a> {
     }
 
     fn fits_on_line(&self, indent: usize, text_len: usize) -> bool {
-        indent * INDENT + text_len <= MAX_WIDTH
+        indent * self.config.indent_width + text_len <= self.config.max_width
     }
 
     /// Returns a token index range for an expression using its `Span`.
@@ -567,6 +847,19 @@ impl<'a> Formatter<'a> {
         source_has_newline(span, self.source)
     }
 
+    /// Whether a broken call or list keeps its source line breaks instead of re-joining.
+    fn keeps_source_breaks(&self, expr: &Expr) -> bool {
+        if !self.expr_has_newline(expr) {
+            return false;
+        }
+        !self.config.collapse_fitting
+            || self
+                .tokens
+                .iter()
+                .filter(|tok| expr.span.start <= tok.span.start && tok.span.end <= expr.span.end)
+                .any(|tok| matches!(tok.kind, TokenKind::DocComment(CommentKind::Line, _)))
+    }
+
     fn available_leading_comments(&self, expr: &Expr) -> Vec<usize> {
         let q = self.token_query();
         let range = self.expr_token_range(expr);
@@ -577,10 +870,12 @@ impl<'a> Formatter<'a> {
             .collect()
     }
 
-    fn take_leading_comments(&mut self, expr: &Expr) -> (Vec<usize>, Option<usize>) {
+    /// Marks the comments before `expr` as used and splits them into those printed on their own
+    /// lines and the block comments kept inline in front of it, both in source order.
+    fn take_leading_comments(&mut self, expr: &Expr) -> (Vec<usize>, Vec<usize>) {
         let comments = self.available_leading_comments(expr);
         let expr_line = self.sm.line_col(expr.span.start).0;
-        let mut inline_block = None;
+        let mut inline_blocks = Vec::new();
         let mut leading = Vec::new();
 
         for idx in comments {
@@ -590,7 +885,7 @@ impl<'a> Formatter<'a> {
                 && !self.slice_has_newline(tok.span.end, expr.span.start);
 
             if is_inline_block {
-                inline_block = Some(idx);
+                inline_blocks.push(idx);
             } else {
                 leading.push(idx);
             }
@@ -598,7 +893,7 @@ impl<'a> Formatter<'a> {
             self.used_comments.insert(idx);
         }
 
-        (leading, inline_block)
+        (leading, inline_blocks)
     }
 
     fn available_trailing_comment(&self, expr: &Expr) -> Option<usize> {
@@ -655,6 +950,83 @@ impl<'a> Formatter<'a> {
         Some(idx)
     }
 
+    /// Takes a comment that follows the separator comma after `item` on the same line.
+    fn take_comment_after_comma(&mut self, item: &Expr) -> Option<usize> {
+        let q = self.token_query();
+        let last = q.last_in_range(self.expr_token_range(item))?;
+        let comma = q.next_nontrivia(last + 1)?;
+        if !matches!(self.tokens[comma].kind, TokenKind::Comma) {
+            return None;
+        }
+        let idx = q
+            .trailing_trivia_until_newline_or_nontrivia(comma + 1)
+            .find(|&i| self.tokens[i].kind.is_comment())?;
+        if self.used_comments.contains(&idx)
+            || self.slice_has_newline(self.tokens[idx].span.start, self.tokens[idx].span.end)
+        {
+            return None;
+        }
+        self.used_comments.insert(idx);
+        Some(idx)
+    }
+
+    /// Unattached comment tokens fully inside `span`, in source order.
+    fn unused_comments_in(&self, span: Span) -> Vec<usize> {
+        self.tokens
+            .iter()
+            .enumerate()
+            .filter(|(idx, tok)| {
+                tok.kind.is_comment()
+                    && span.start <= tok.span.start
+                    && tok.span.end <= span.end
+                    && !self.used_comments.contains(idx)
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    fn has_unused_comments_in(&self, span: Span) -> bool {
+        !self.unused_comments_in(span).is_empty()
+    }
+
+    /// Unattached comment tokens starting at or after `offset`, in source order.
+    fn unused_comments_from(&self, offset: u32) -> Vec<usize> {
+        self.unused_comments_in(Span {
+            start: offset,
+            end: u32::MAX,
+        })
+    }
+
+    /// Renders an own-line comment, wrapping long `//` prose when `wrap_comments` is enabled.
+    fn render_comment_lines(&self, idx: usize, indent: usize) -> Rendered {
+        let text = self.render_comment(idx);
+        let width = self
+            .config
+            .max_width
+            .saturating_sub(indent * self.config.indent_width);
+        let wrappable = self.config.wrap_comments
+            && text.len() > width
+            && matches!(
+                &self.tokens[idx].kind,
+                TokenKind::DocComment(CommentKind::Line, sym) if sym.text.starts_with(' ')
+            );
+        if !wrappable {
+            return Rendered::single(indent, text);
+        }
+
+        let mut out = Rendered::default();
+        let mut line = String::from("//");
+        for word in text[2..].split_whitespace() {
+            if line.len() > 2 && line.len() + 1 + word.len() > width {
+                out.push_line(indent, std::mem::replace(&mut line, String::from("//")));
+            }
+            line.push(' ');
+            line.push_str(word);
+        }
+        out.push_line(indent, line);
+        out
+    }
+
     fn render_comment(&self, idx: usize) -> String {
         match &self.tokens[idx].kind {
             TokenKind::DocComment(kind, sym) => match kind {
@@ -679,29 +1051,9 @@ impl<'a> Formatter<'a> {
     }
 }
 
-fn binop_str(op: BinOpKind) -> &'static str {
-    use BinOpKind::*;
-    match op {
-        Lt => "<",
-        Le => "<=",
-        EqEq => "==",
-        Ne => "!=",
-        Ge => ">=",
-        Gt => ">",
-        AndAnd => "&&",
-        OrOr => "||",
-        Plus => "+",
-        Minus => "-",
-        Star => "*",
-        Slash => "/",
-        Percent => "%",
-        Caret => "^",
-    }
-}
-
 fn render_literal(lit: &Lit) -> String {
     match lit.kind {
-        LitKind::Number | LitKind::Bool => lit.symbol.text.clone(),
+        LitKind::Number | LitKind::Bool => lit.symbol.text.to_string(),
         LitKind::String => escape_string(&lit.symbol.text),
     }
 }
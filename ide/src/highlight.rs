@@ -0,0 +1,85 @@
+//! Document highlights: every occurrence of the symbol under the cursor.
+//!
+//! Token-based, so it works on incomplete input. Coordinates are UTF-8 byte offsets.
+//!
+//! Symbols are:
+//! - function names at call sites (`if(`, `prop(`)
+//! - method names after `.` (`.length()`)
+//! - property references (the string literal in `prop("Name")`)
+//! - bare identifiers
+//!
+//! The formula language has no local variables, so there are no binding sites to resolve.
+
+use analyzer::{LitKind, Span, Token, TokenKind, non_trivia};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SymbolKey<'a> {
+    Function(&'a str),
+    Method(&'a str),
+    Property(&'a str),
+    Ident(&'a str),
+}
+
+/// Spans of every occurrence of the symbol at `cursor`, in source order.
+///
+/// The cursor may be inside a token or touching its end. Returns an empty list when the cursor
+/// is not on a symbol.
+pub(crate) fn document_highlights(tokens: &[Token], cursor: u32) -> Vec<Span> {
+    let tokens = symbol_tokens(tokens);
+    let Some(target) = token_at(&tokens, cursor).and_then(|idx| symbol_key(&tokens, idx)) else {
+        return Vec::new();
+    };
+    occurrences(&tokens, &target)
+}
+
+/// Spans of the `prop("...")` string literals (quotes included) naming the property at `cursor`.
+///
+/// `None` when the cursor is not on a property reference.
+pub(crate) fn property_references(tokens: &[Token], cursor: u32) -> Option<Vec<Span>> {
+    let tokens = symbol_tokens(tokens);
+    let target = token_at(&tokens, cursor).and_then(|idx| symbol_key(&tokens, idx))?;
+    matches!(target, SymbolKey::Property(_)).then(|| occurrences(&tokens, &target))
+}
+
+fn symbol_tokens(tokens: &[Token]) -> Vec<&Token> {
+    non_trivia(tokens).map(|(_, token)| token).collect()
+}
+
+fn occurrences(tokens: &[&Token], target: &SymbolKey<'_>) -> Vec<Span> {
+    (0..tokens.len())
+        .filter(|&idx| symbol_key(tokens, idx).as_ref() == Some(target))
+        .map(|idx| tokens[idx].span)
+        .collect()
+}
+
+/// Prefers a token containing `cursor`; falls back to one ending at `cursor`.
+fn token_at(tokens: &[&Token], cursor: u32) -> Option<usize> {
+    tokens
+        .iter()
+        .position(|token| token.span.start <= cursor && cursor < token.span.end)
+        .or_else(|| tokens.iter().position(|token| token.span.end == cursor))
+}
+
+fn symbol_key<'a>(tokens: &[&'a Token], idx: usize) -> Option<SymbolKey<'a>> {
+    let kind_at = |i: Option<usize>| i.and_then(|i| tokens.get(i)).map(|token| &token.kind);
+    let prev = |n: usize| kind_at(idx.checked_sub(n));
+    let next = kind_at(Some(idx + 1));
+
+    match &tokens[idx].kind {
+        TokenKind::Ident(sym) => Some(if matches!(prev(1), Some(TokenKind::Dot)) {
+            SymbolKey::Method(&sym.text)
+        } else if matches!(next, Some(TokenKind::OpenParen)) {
+            SymbolKey::Function(&sym.text)
+        } else {
+            SymbolKey::Ident(&sym.text)
+        }),
+        TokenKind::Literal(lit) if lit.kind == LitKind::String => {
+            let is_prop_arg = matches!(prev(1), Some(TokenKind::OpenParen))
+                && matches!(prev(2), Some(TokenKind::Ident(sym)) if sym.text == "prop")
+                && !matches!(prev(3), Some(TokenKind::Dot))
+                && matches!(next, Some(TokenKind::CloseParen));
+            is_prop_arg.then_some(SymbolKey::Property(&lit.symbol.text))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,277 @@
+//! Stateful analysis session for editors.
+//!
+//! `AnalysisHost` owns the current text and `Context` as revisioned inputs and answers queries
+//! from memos keyed by those revisions (see [`crate::db`]), so repeated queries on an unchanged
+//! document do not re-lex or re-parse.
+//! Coordinates are UTF-8 byte offsets (`[start, end)`), matching `analyzer`.
+
+use analyzer::semantic::{Context, Ty};
+use analyzer::{Diagnostic, LineIndex, SourceMap, Span, SyntaxResult, TextEdit, Token, TypeMap};
+
+use crate::completion::CompletionConfig;
+use crate::db::{Input, Memo, Revision};
+use crate::{EditError, HelpResult, HelpSession, HoverResult, SemanticToken, TypeAtResult};
+
+/// Inferred types for the current text + context.
+#[derive(Debug)]
+struct Semantics {
+    type_map: TypeMap,
+    output_type: Ty,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Holds one formula document and memoizes analysis results across queries.
+///
+/// Queries and the inputs they read:
+/// - `syntax` (tokens, AST, syntax diagnostics): text.
+/// - `line_index`: text.
+/// - `semantics` (types, output type): text and context.
+/// - `diagnostics`: text and context; backdated, see [`AnalysisHost::diagnostics_changed_at`].
+///
+/// Setting an input to an equal value keeps every memo. Edits patch the memoized line index, and
+/// a single edit updates the memoized syntax incrementally.
+#[derive(Debug)]
+pub struct AnalysisHost {
+    revision: Revision,
+    text: Input<String>,
+    context: Input<Context>,
+    syntax: Memo<SyntaxResult>,
+    line_index: Memo<LineIndex>,
+    semantics: Memo<Semantics>,
+    diagnostics: Memo<Vec<Diagnostic>>,
+}
+
+impl AnalysisHost {
+    /// Creates a host with empty text.
+    pub fn new(context: Context) -> Self {
+        Self {
+            revision: Revision::default(),
+            text: Input::new(String::new()),
+            context: Input::new(context),
+            syntax: Memo::default(),
+            line_index: Memo::default(),
+            semantics: Memo::default(),
+            diagnostics: Memo::default(),
+        }
+    }
+
+    /// Current document text.
+    pub fn text(&self) -> &str {
+        self.text.get()
+    }
+
+    /// Current analysis context.
+    pub fn context(&self) -> &Context {
+        self.context.get()
+    }
+
+    /// Revision of the latest input change.
+    pub fn revision(&self) -> Revision {
+        self.revision
+    }
+
+    /// Replaces the whole document.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        if self.text.set(text.into(), self.revision.next()) {
+            self.revision = self.revision.next();
+            self.invalidate();
+        }
+    }
+
+    /// Replaces the context, keeping cached syntax.
+    pub fn set_context(&mut self, context: Context) {
+        if self.context.set(context, self.revision.next()) {
+            self.revision = self.revision.next();
+            self.invalidate();
+        }
+    }
+
+    /// Applies byte edits to the document.
+    ///
+    /// Edits follow `apply_edits` rules; on error the document and caches are unchanged. A cached
+    /// line index is patched in place, and a single edit on a document whose syntax is cached
+    /// updates that syntax incrementally.
+    pub fn apply_change(&mut self, edits: Vec<TextEdit>) -> Result<(), EditError> {
+        if edits.is_empty() {
+            return Ok(());
+        }
+        let single = match edits.as_slice() {
+            [edit] => Some(edit.clone()),
+            _ => None,
+        };
+        let index_edits = self.line_index.is_computed().then(|| edits.clone());
+        let applied = crate::apply_edits(self.text(), edits, 0)?;
+        if applied.source == *self.text() {
+            return Ok(());
+        }
+        let old_syntax = self.syntax.take();
+        let old_line_index = self.line_index.take();
+        self.set_text(applied.source);
+        if let (Some(edit), Some(old)) = (single, old_syntax) {
+            let syntax = analyzer::reparse_syntax(old, self.text.get(), &edit);
+            self.syntax.set(syntax, self.revision);
+        }
+        if let (Some(edits), Some(mut index)) = (index_edits, old_line_index) {
+            index.apply_edits(&edits);
+            self.line_index.set(index, self.revision);
+        }
+        Ok(())
+    }
+
+    /// Tokens, AST, and syntax diagnostics for the current text.
+    pub fn syntax(&self) -> &SyntaxResult {
+        self.syntax
+            .get(self.revision, || analyzer::analyze_syntax(self.text.get()))
+    }
+
+    /// Line starts of the current text.
+    pub fn line_index(&self) -> &LineIndex {
+        self.line_index
+            .get(self.revision, || LineIndex::new(self.text.get()))
+    }
+
+    /// Line/column lookup for the current text, backed by the cached line index.
+    pub fn source_map(&self) -> SourceMap<'_> {
+        SourceMap::with_index(self.text(), self.line_index())
+    }
+
+    /// Tokens for the current text (including trivia and the final `Eof`).
+    pub fn tokens(&self) -> &[Token] {
+        &self.syntax().tokens
+    }
+
+    /// Syntax and semantic diagnostics, in that order.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        self.diagnostics.get_backdated(self.revision, || {
+            collect_diagnostics(
+                self.text(),
+                self.syntax(),
+                &self.semantics().diagnostics,
+                self.context(),
+            )
+        })
+    }
+
+    /// Diagnostics as [`AnalysisHost::diagnostics`] would report them after `edits`, leaving the
+    /// document and caches unchanged; e.g. to warn that a quick fix introduces new errors.
+    ///
+    /// Edits follow `apply_edits` rules, like [`AnalysisHost::apply_change`].
+    pub fn preview(&self, edits: Vec<TextEdit>) -> Result<Vec<Diagnostic>, EditError> {
+        let applied = crate::apply_edits(self.text(), edits, 0)?;
+        if applied.source == *self.text() {
+            return Ok(self.diagnostics().to_vec());
+        }
+        let syntax = analyzer::analyze_syntax(&applied.source);
+        let mut type_map = TypeMap::default();
+        let (_, semantic) =
+            analyzer::semantic::analyze_expr_with_map(&syntax.expr, self.context(), &mut type_map);
+        Ok(collect_diagnostics(
+            &applied.source,
+            &syntax,
+            &semantic,
+            self.context(),
+        ))
+    }
+
+    /// Revision at which [`AnalysisHost::diagnostics`] last changed value.
+    ///
+    /// Input changes that leave the diagnostics equal (e.g. a context change that does not
+    /// affect this formula) keep the earlier revision, so clients can skip republishing.
+    pub fn diagnostics_changed_at(&self) -> Revision {
+        self.diagnostics();
+        self.diagnostics
+            .changed_at()
+            .expect("diagnostics were just computed")
+    }
+
+    /// Types inferred for every AST node.
+    pub fn type_map(&self) -> &TypeMap {
+        &self.semantics().type_map
+    }
+
+    /// Type of the whole formula.
+    pub fn output_type(&self) -> &Ty {
+        &self.semantics().output_type
+    }
+
+    /// Completion and signature help at a byte cursor, reusing the cached syntax and types.
+    pub fn help(&self, cursor: usize, config: CompletionConfig) -> HelpResult {
+        HelpSession::new(self.text(), self.syntax(), cursor, self.context(), config)
+            .with_types(self.type_map())
+            .run()
+    }
+
+    /// Same as `ide::document_highlights`, reusing the cached tokens.
+    pub fn document_highlights(&self, cursor: u32) -> Vec<Span> {
+        crate::highlight::document_highlights(self.tokens(), cursor)
+    }
+
+    /// Same as `ide::semantic_tokens`, reusing the cached tokens.
+    pub fn semantic_tokens(&self) -> Vec<SemanticToken> {
+        crate::semantic_tokens::semantic_tokens(self.tokens(), self.context())
+    }
+
+    /// Same as `ide::hover`, reusing the cached syntax and types.
+    pub fn hover(&self, cursor: usize) -> Option<HoverResult> {
+        let cursor = u32::try_from(cursor).unwrap_or(u32::MAX);
+        let syntax = self.syntax();
+        crate::hover::hover(
+            self.text(),
+            &syntax.tokens,
+            &syntax.expr,
+            self.type_map(),
+            cursor,
+            self.context(),
+        )
+    }
+
+    /// Same as `ide::type_at`, reusing the cached syntax and types.
+    pub fn type_at(&self, cursor: usize) -> Option<TypeAtResult> {
+        let cursor = u32::try_from(cursor).unwrap_or(u32::MAX);
+        let syntax = self.syntax();
+        crate::hover::type_at(&syntax.tokens, &syntax.expr, self.type_map(), cursor)
+    }
+
+    fn semantics(&self) -> &Semantics {
+        self.semantics.get(self.revision, || {
+            let mut type_map = TypeMap::default();
+            let (output_type, diagnostics) = analyzer::semantic::analyze_expr_with_map(
+                &self.syntax().expr,
+                self.context(),
+                &mut type_map,
+            );
+            Semantics {
+                type_map,
+                output_type,
+                diagnostics,
+            }
+        })
+    }
+
+    /// Drops memos whose inputs changed since they were computed.
+    fn invalidate(&mut self) {
+        let text = self.text.changed_at();
+        let both = text.max(self.context.changed_at());
+        self.syntax.invalidate(text);
+        self.line_index.invalidate(text);
+        self.semantics.invalidate(both);
+        self.diagnostics.invalidate(both);
+    }
+}
+
+/// Syntax, semantic, and limit diagnostics, in that order.
+fn collect_diagnostics(
+    text: &str,
+    syntax: &SyntaxResult,
+    semantic: &[Diagnostic],
+    context: &Context,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = syntax.diagnostics.clone();
+    diagnostics.extend(semantic.iter().cloned());
+    diagnostics.extend(analyzer::semantic::limit_diagnostics(
+        text,
+        &syntax.expr,
+        &context.limits,
+    ));
+    diagnostics
+}
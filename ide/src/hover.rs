@@ -0,0 +1,173 @@
+//! Hover info for the token under the cursor: inferred type, and for calls the rendered
+//! signature and docs. `type_at` is the lightweight type-only query.
+//! Coordinates are UTF-8 byte offsets.
+
+use analyzer::ast::{Expr, ExprKind};
+use analyzer::semantic::{self, Ty};
+use analyzer::{LitKind, Span, Token, TokenKind, TypeMap, non_trivia};
+
+use crate::context::CallContext;
+use crate::signature::{
+    SignatureItem, compute_signature_help_if_in_call, find_call_expr_by_lparen,
+};
+
+/// Hover payload for the token under the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoverResult {
+    /// Source range the hover applies to (the callee name for calls).
+    pub range: Span,
+    /// Inferred type of the hovered expression (`Unknown` when inference fails).
+    pub ty: Ty,
+    /// Signature rendered for the call site, when hovering a known function name.
+    pub signature: Option<SignatureItem>,
+    /// Function documentation, when hovering a known function name.
+    pub docs: Option<String>,
+}
+
+/// Inferred type of the expression under the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeAtResult {
+    /// Range of the smallest expression enclosing the cursor token.
+    pub range: Span,
+    pub ty: Ty,
+}
+
+/// Computes the type of the smallest expression enclosing the token at `cursor`.
+///
+/// On a callee or method name this is the whole call. The literal in `prop("Name")` reports
+/// the property's type. Returns `None` when the cursor is not on an expression.
+pub(crate) fn type_at(
+    tokens: &[Token],
+    root: &Expr,
+    type_map: &TypeMap,
+    cursor: u32,
+) -> Option<TypeAtResult> {
+    let token = &tokens[token_at(tokens, cursor)?];
+    let (expr, parent) = innermost_expr(root, token.span)?;
+    // The literal in `prop("Name")` shows the property's type, not `string`.
+    let typed = match (&expr.kind, parent.map(|p| &p.kind)) {
+        (ExprKind::Lit(lit), Some(ExprKind::Call { callee, args }))
+            if lit.kind == LitKind::String && callee.text == "prop" && args.len() == 1 =>
+        {
+            parent.unwrap_or(expr)
+        }
+        _ => expr,
+    };
+
+    Some(TypeAtResult {
+        range: expr.span,
+        ty: type_map.get(typed.id).cloned().unwrap_or(Ty::Unknown),
+    })
+}
+
+/// Computes hover info at `cursor`.
+///
+/// `tokens`/`root`/`type_map` must come from analyzing `source`. The cursor may be inside a
+/// token or touching its end. Returns `None` when the cursor is not on an expression.
+pub(crate) fn hover(
+    source: &str,
+    tokens: &[Token],
+    root: &Expr,
+    type_map: &TypeMap,
+    cursor: u32,
+    ctx: &semantic::Context,
+) -> Option<HoverResult> {
+    let token_idx = token_at(tokens, cursor)?;
+    let token = &tokens[token_idx];
+    let ty_of = |expr: &Expr| type_map.get(expr.id).cloned().unwrap_or(Ty::Unknown);
+
+    if let TokenKind::Ident(sym) = &token.kind
+        && let Some(lparen_idx) = next_non_trivia(tokens, token_idx)
+        && matches!(tokens[lparen_idx].kind, TokenKind::OpenParen)
+    {
+        let lparen = &tokens[lparen_idx];
+        let call = find_call_expr_by_lparen(root, &sym.text, lparen.span.start)?;
+        let func = ctx.function(sym.text.as_str());
+        let signature = func.and_then(|_| {
+            let call_ctx = CallContext {
+                callee: sym.text.to_string(),
+                lparen_idx,
+                arg_index: 0,
+            };
+            let help = compute_signature_help_if_in_call(
+                source,
+                tokens,
+                root,
+                type_map,
+                lparen.span.end,
+                ctx,
+                &call_ctx,
+            )?;
+            help.signatures.into_iter().next()
+        });
+        let docs = func
+            .map(|func| func.detail.clone())
+            .filter(|detail| !detail.is_empty());
+
+        return Some(HoverResult {
+            range: token.span,
+            ty: ty_of(call),
+            signature,
+            docs,
+        });
+    }
+
+    let TypeAtResult { range, ty } = type_at(tokens, root, type_map, cursor)?;
+    Some(HoverResult {
+        range,
+        ty,
+        signature: None,
+        docs: None,
+    })
+}
+
+/// Prefers a non-trivia token containing `cursor`; falls back to one ending at `cursor`.
+fn token_at(tokens: &[Token], cursor: u32) -> Option<usize> {
+    let candidates = || non_trivia(tokens);
+    candidates()
+        .find(|(_, token)| token.span.start <= cursor && cursor < token.span.end)
+        .or_else(|| candidates().find(|(_, token)| token.span.end == cursor))
+        .map(|(idx, _)| idx)
+}
+
+fn next_non_trivia(tokens: &[Token], idx: usize) -> Option<usize> {
+    (idx + 1..tokens.len()).find(|&i| !tokens[i].is_trivia())
+}
+
+/// Smallest expression covering `span`, with its parent.
+fn innermost_expr(root: &Expr, span: Span) -> Option<(&Expr, Option<&Expr>)> {
+    let covers = |expr: &Expr| expr.span.start <= span.start && span.end <= expr.span.end;
+    if !covers(root) || matches!(root.kind, ExprKind::Error) {
+        return None;
+    }
+
+    let mut parent = None;
+    let mut current = root;
+    while let Some(child) = children(current).into_iter().find(|child| covers(child)) {
+        if matches!(child.kind, ExprKind::Error) {
+            break;
+        }
+        parent = Some(current);
+        current = child;
+    }
+    Some((current, parent))
+}
+
+pub(crate) fn children(expr: &Expr) -> Vec<&Expr> {
+    match &expr.kind {
+        ExprKind::Group { inner } => vec![inner],
+        ExprKind::List { items } => items.iter().collect(),
+        ExprKind::Call { args, .. } => args.iter().collect(),
+        ExprKind::MemberCall { receiver, args, .. } => {
+            std::iter::once(&**receiver).chain(args).collect()
+        }
+        ExprKind::Unary { expr, .. } => vec![expr],
+        ExprKind::Binary { left, right, .. } => vec![left, right],
+        ExprKind::Ternary {
+            cond,
+            then,
+            otherwise,
+        } => vec![cond, then, otherwise],
+        ExprKind::Ident(_) | ExprKind::Lit(_) | ExprKind::Error => Vec::new(),
+    }
+}
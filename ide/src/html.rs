@@ -0,0 +1,257 @@
+//! Read-only HTML rendering of a formula: semantic highlighting plus underlined diagnostics, for
+//! docs, diffs, and share views.
+//!
+//! The output is flat: the source is cut at every token and diagnostic boundary, and each piece
+//! becomes at most one `<span>` carrying all of its classes, so overlapping ranges still nest.
+
+use analyzer::semantic;
+use analyzer::{Diagnostic, DiagnosticKind, Span, Token};
+
+use crate::semantic_tokens::{SEMANTIC_TOKEN_TYPES, SemanticToken, semantic_tokens};
+
+/// How [`render_html`](crate::render_html) styles its spans.
+///
+/// The default is class-only output with the `nf-` prefix, for a host stylesheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlTheme {
+    /// Prefix of every class name: `"nf-"` gives `nf-formula`, `nf-function`, `nf-error`, ...
+    pub class_prefix: String,
+    /// Colors written as inline `style` attributes, so the HTML needs no stylesheet.
+    pub palette: Option<HtmlPalette>,
+}
+
+impl Default for HtmlTheme {
+    fn default() -> Self {
+        Self {
+            class_prefix: "nf-".to_string(),
+            palette: None,
+        }
+    }
+}
+
+impl HtmlTheme {
+    /// Classes plus [`HtmlPalette::LIGHT`] inline.
+    pub fn light() -> Self {
+        Self {
+            palette: Some(HtmlPalette::LIGHT),
+            ..Self::default()
+        }
+    }
+
+    /// Classes plus [`HtmlPalette::DARK`] inline.
+    pub fn dark() -> Self {
+        Self {
+            palette: Some(HtmlPalette::DARK),
+            ..Self::default()
+        }
+    }
+}
+
+/// CSS colors per semantic token kind. Methods share the function color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HtmlPalette {
+    pub foreground: &'static str,
+    pub background: &'static str,
+    pub function: &'static str,
+    pub property: &'static str,
+    pub variable: &'static str,
+    pub string: &'static str,
+    pub number: &'static str,
+    pub keyword: &'static str,
+    pub operator: &'static str,
+    pub comment: &'static str,
+    /// Underline of error diagnostics.
+    pub error: &'static str,
+    /// Underline of warning diagnostics.
+    pub warning: &'static str,
+}
+
+impl HtmlPalette {
+    pub const LIGHT: Self = Self {
+        foreground: "#24292f",
+        background: "#ffffff",
+        function: "#8250df",
+        property: "#116329",
+        variable: "#953800",
+        string: "#0a3069",
+        number: "#0550ae",
+        keyword: "#cf222e",
+        operator: "#57606a",
+        comment: "#6e7781",
+        error: "#cf222e",
+        warning: "#9a6700",
+    };
+
+    pub const DARK: Self = Self {
+        foreground: "#e6edf3",
+        background: "#0d1117",
+        function: "#d2a8ff",
+        property: "#7ee787",
+        variable: "#ffa657",
+        string: "#a5d6ff",
+        number: "#79c0ff",
+        keyword: "#ff7b72",
+        operator: "#8b949e",
+        comment: "#8b949e",
+        error: "#f85149",
+        warning: "#d29922",
+    };
+
+    fn color(&self, token: &SemanticToken) -> &'static str {
+        use crate::semantic_tokens::SemanticTokenKind::*;
+        match token.kind {
+            Function | Method => self.function,
+            Property => self.property,
+            Variable => self.variable,
+            String => self.string,
+            Number => self.number,
+            Keyword => self.keyword,
+            Operator => self.operator,
+            Comment => self.comment,
+        }
+    }
+}
+
+/// `source` as a `<pre><code>` block of highlighted, span-annotated pieces.
+///
+/// Each `<span>` has `data-start` / `data-end` (UTF-8 byte offsets of the piece), a class per
+/// [`SEMANTIC_TOKEN_TYPES`] name, and `error` / `warning` with the messages as its `title` when
+/// a diagnostic covers it. An empty diagnostic span underlines the char after it (or before it,
+/// at the end).
+pub(crate) fn render_html(
+    source: &str,
+    tokens: &[Token],
+    diagnostics: &[Diagnostic],
+    ctx: &semantic::Context,
+    theme: &HtmlTheme,
+) -> String {
+    let highlighted = semantic_tokens(tokens, ctx);
+    let diagnostics: Vec<(Span, &Diagnostic)> = diagnostics
+        .iter()
+        .filter_map(|diag| Some((underline(source, diag.span)?, diag)))
+        .collect();
+
+    let mut cuts: Vec<u32> = vec![0, source.len() as u32];
+    cuts.extend(highlighted.iter().flat_map(|t| [t.span.start, t.span.end]));
+    cuts.extend(
+        diagnostics
+            .iter()
+            .flat_map(|(span, _)| [span.start, span.end]),
+    );
+    cuts.retain(|&cut| cut as usize <= source.len());
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let prefix = &theme.class_prefix;
+    let mut out = format!("<pre class=\"{}formula\"", escape(prefix));
+    if let Some(palette) = &theme.palette {
+        out.push_str(&format!(
+            " style=\"color:{};background:{}\"",
+            palette.foreground, palette.background
+        ));
+    }
+    out.push_str("><code>");
+    for piece in cuts.windows(2) {
+        let (start, end) = (piece[0], piece[1]);
+        let text = escape(&source[start as usize..end as usize]);
+        let token = highlighted
+            .iter()
+            .find(|t| t.span.start <= start && end <= t.span.end);
+        let covering: Vec<&Diagnostic> = diagnostics
+            .iter()
+            .filter(|(span, _)| span.start <= start && end <= span.end)
+            .map(|(_, diag)| *diag)
+            .collect();
+        if token.is_none() && covering.is_empty() {
+            out.push_str(&text);
+            continue;
+        }
+
+        let mut classes = Vec::new();
+        let mut styles = Vec::new();
+        if let Some(token) = token {
+            classes.push(format!(
+                "{prefix}{}",
+                SEMANTIC_TOKEN_TYPES[token.kind as usize]
+            ));
+            if let Some(palette) = &theme.palette {
+                styles.push(format!("color:{}", palette.color(token)));
+            }
+        }
+        if !covering.is_empty() {
+            let is_error = covering.iter().any(|d| d.kind == DiagnosticKind::Error);
+            let kind = if is_error {
+                DiagnosticKind::Error
+            } else {
+                DiagnosticKind::Warning
+            };
+            classes.push(format!("{prefix}{}", kind.as_str()));
+            if let Some(palette) = &theme.palette {
+                let color = if is_error {
+                    palette.error
+                } else {
+                    palette.warning
+                };
+                styles.push(format!(
+                    "text-decoration:underline wavy {color};text-decoration-skip-ink:none"
+                ));
+            }
+        }
+
+        out.push_str(&format!(
+            "<span class=\"{}\" data-start=\"{start}\" data-end=\"{end}\"",
+            escape(&classes.join(" "))
+        ));
+        if !covering.is_empty() {
+            let messages: Vec<&str> = covering.iter().map(|d| d.message.as_str()).collect();
+            out.push_str(&format!(" title=\"{}\"", escape(&messages.join("\n"))));
+        }
+        if !styles.is_empty() {
+            out.push_str(&format!(" style=\"{}\"", styles.join(";")));
+        }
+        out.push('>');
+        out.push_str(&text);
+        out.push_str("</span>");
+    }
+    out.push_str("</code></pre>");
+    out
+}
+
+/// The range to underline for a diagnostic at `span`: itself, or one char next to it when empty.
+fn underline(source: &str, span: Span) -> Option<Span> {
+    let start = (span.start as usize).min(source.len());
+    let end = (span.end as usize).min(source.len());
+    if start < end {
+        return Some(Span {
+            start: start as u32,
+            end: end as u32,
+        });
+    }
+    if let Some(ch) = source[start..].chars().next() {
+        return Some(Span {
+            start: start as u32,
+            end: (start + ch.len_utf8()) as u32,
+        });
+    }
+    let ch = source[..start].chars().next_back()?;
+    Some(Span {
+        start: (start - ch.len_utf8()) as u32,
+        end: start as u32,
+    })
+}
+
+/// Escapes text and attribute values.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
@@ -1,36 +1,95 @@
 //! IDE helpers for editor integrations.
 //!
 //! Coordinates are UTF-8 byte offsets (`[start, end)`), matching `analyzer`.
+//! The `*_utf16` entry points take and return UTF-16 code unit offsets instead.
 
+mod code_actions;
 mod completion;
-mod context;
+pub mod context;
+mod db;
+mod diff;
 mod display;
 mod edit;
+mod equivalence;
+mod explain;
+#[cfg(feature = "format")]
 mod format;
+mod highlight;
+mod host;
+mod html;
+mod hover;
+mod migrate;
+mod notion_expression;
+mod paste;
+mod references;
+mod rename;
+mod semantic_tokens;
 mod signature;
+mod simplify;
 mod text_edit;
+mod utf16;
+mod workspace;
 
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+
+use analyzer::ast::Expr;
 use analyzer::semantic;
-use analyzer::{Span, Token, TokenKind};
+use analyzer::{PhaseClock, PhaseTimer, Span, SyntaxResult, Token, TokenKind, TypeMap, non_trivia};
 use context::{CursorContext, PositionKind};
 
-pub use analyzer::TextEdit;
-pub use completion::{CompletionConfig, CompletionData, CompletionItem, CompletionKind};
+pub use analyzer::{CancellationToken, Cancelled, Instrumentation, Phase, PhaseTimings, TextEdit};
+pub use completion::{
+    CompletionConfig, CompletionData, CompletionItem, CompletionKind, MatchMode, MatchStrength,
+    RankingConfig, Snippet, SnippetError, SnippetPlaceholder, SnippetRegistry,
+};
+pub use db::Revision;
 pub use display::DisplaySegment;
-pub use edit::{ApplyResult, IdeError, apply_edits};
+pub use edit::{ApplyResult, EditError, IdeError, apply_edits};
+pub use equivalence::Equivalence;
+pub use explain::{Explanation, ExplanationRole};
+#[cfg(feature = "format")]
+pub use format::{ClosingDelim, FormatConfig};
+pub use host::AnalysisHost;
+pub use html::{HtmlPalette, HtmlTheme};
+pub use hover::{HoverResult, TypeAtResult};
+pub use migrate::{Migration, MigrationNote, MigrationNoteKind};
+pub use notion_expression::NotionExpressionError;
+pub use references::PropertyReference;
+pub use semantic_tokens::{
+    MODIFIER_DEFAULT_LIBRARY, SEMANTIC_TOKEN_MODIFIERS, SEMANTIC_TOKEN_TYPES, SemanticToken,
+    SemanticTokenKind, encode_lsp as encode_semantic_tokens_lsp,
+};
 pub use signature::{SignatureHelp, SignatureItem};
 pub use text_edit::apply_text_edits_bytes_with_cursor;
+pub use utf16::{
+    PositionEncoding, TextEditUtf16, byte_span_to_utf16, byte_to_utf16_offset, completion_cursor,
+    completion_cursor_utf16, text_edit_to_utf16, text_edits_from_utf16, utf16_cursor_to_byte,
+    utf16_to_byte_offset,
+};
+pub use workspace::{TypeChange, Workspace};
 
 /// Completion payload used by `help`.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CompletionResult {
     pub items: Vec<completion::CompletionItem>,
     pub replace: analyzer::Span,
     pub preferred_indices: Vec<usize>,
 }
 
+/// Replace span and query of [`completion_replace_span`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompletionReplace {
+    pub replace: analyzer::Span,
+    /// The text in `replace`, normalized for matching; `None` when empty or not ASCII.
+    pub query: Option<String>,
+}
+
 /// Combined completion + signature help payload for IDE integrations.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HelpResult {
     pub completion: CompletionResult,
     pub signature_help: Option<SignatureHelp>,
@@ -43,25 +102,297 @@ pub fn help(
     ctx: &semantic::Context,
     config: completion::CompletionConfig,
 ) -> HelpResult {
-    HelpSession::new(source, cursor, ctx, config).run()
+    let syntax = analyzer::analyze_syntax(source);
+    HelpSession::new(source, &syntax, cursor, ctx, config).run()
+}
+
+/// Like [`help`], also timing the lex, parse, and complete phases with `now`.
+///
+/// Inference done for completion (e.g. postfix receiver types) counts toward `complete`.
+pub fn help_timed(
+    source: &str,
+    cursor: usize,
+    ctx: &semantic::Context,
+    config: completion::CompletionConfig,
+    now: &mut dyn FnMut() -> f64,
+) -> (HelpResult, PhaseTimings) {
+    let mut timer = PhaseTimer::new(now);
+    let result = help_instrumented(source, cursor, ctx, config, &mut timer);
+    (result, timer.timings)
+}
+
+/// Like [`help`], reporting the lex, parse, and complete phases and the `"tokens"` and
+/// `"completion_items"` counts to `instrumentation`.
+pub fn help_instrumented(
+    source: &str,
+    cursor: usize,
+    ctx: &semantic::Context,
+    config: completion::CompletionConfig,
+    instrumentation: &mut dyn Instrumentation,
+) -> HelpResult {
+    let syntax = analyzer::analyze_syntax_instrumented(source, instrumentation);
+    let mut clock = PhaseClock::start(instrumentation, source.len());
+    let result = HelpSession::new(source, &syntax, cursor, ctx, config).run();
+    clock.lap(Phase::Complete);
+    clock.count("completion_items", result.completion.items.len());
+    result
+}
+
+/// The completion replace span and query at a byte cursor, from the lexer alone.
+///
+/// Cheap enough to run on every keystroke, e.g. to check whether an open completion popup still
+/// filters the word being typed. Without a context, complete names cannot be told apart from
+/// partial ones, so an identifier ending at the cursor is always replaced; `help` replaces
+/// nothing after a name with no longer completion (such as `sum`). Everywhere else the result
+/// matches `help(..).completion.replace` and [`context::CursorContext::query`].
+pub fn completion_replace_span(source: &str, cursor: usize) -> CompletionReplace {
+    let tokens = analyzer::lex(source).tokens;
+    let cursor = u32::try_from(cursor).unwrap_or(u32::MAX);
+    let replace = context::replace_span_without_context(&tokens, cursor);
+    CompletionReplace {
+        replace,
+        query: context::completion_query_for_replace(source, replace),
+    }
+}
+
+/// Browse `config.snippets`: all of them when `query` is empty, else those whose name matches
+/// under `config.match_mode` (best first), then those whose description contains `query`.
+pub fn snippets<'c>(config: &'c CompletionConfig, query: &str) -> Vec<&'c Snippet> {
+    completion::browse_snippets(&config.snippets, query, config.match_mode)
+}
+
+/// Signature help at a byte cursor, without computing completion (e.g. on `(` / `,` triggers).
+///
+/// Same as `help(..).signature_help`.
+pub fn signature_help(
+    source: &str,
+    cursor: usize,
+    ctx: &semantic::Context,
+) -> Option<SignatureHelp> {
+    let syntax = analyzer::analyze_syntax(source);
+    let cursor = u32::try_from(cursor).unwrap_or(u32::MAX);
+    let call_ctx = context::detect_call_context(&syntax.tokens, cursor)?;
+    let mut types = analyzer::TypeMap::default();
+    let _ = analyzer::infer_expr_with_map(&syntax.expr, ctx, &mut types);
+    signature::compute_signature_help_if_in_call(
+        source,
+        &syntax.tokens,
+        &syntax.expr,
+        &types,
+        cursor,
+        ctx,
+        &call_ctx,
+    )
+}
+
+/// Like [`help`], but stops early once `cancel` is cancelled.
+///
+/// The token is checked while parsing and between the completion pipeline steps.
+pub fn help_cancellable(
+    source: &str,
+    cursor: usize,
+    ctx: &semantic::Context,
+    config: completion::CompletionConfig,
+    cancel: &CancellationToken,
+) -> Result<HelpResult, Cancelled> {
+    let syntax = analyzer::analyze_syntax_cancellable(source, cancel)?;
+    HelpSession::new(source, &syntax, cursor, ctx, config)
+        .with_cancellation(cancel)
+        .try_run()
+}
+
+/// Like [`help`], but the cursor and every span in the result are UTF-16 code units.
+///
+/// Completion item cursors are positions in the document after accepting the item, including
+/// its additional edits.
+pub fn help_utf16(
+    source: &str,
+    cursor_utf16: u32,
+    ctx: &semantic::Context,
+    config: completion::CompletionConfig,
+) -> HelpResult {
+    let cursor = utf16::utf16_to_byte_offset(source, cursor_utf16);
+    utf16::help_result_to_utf16(source, help(source, cursor as usize, ctx, config))
+}
+
+/// Spans of every occurrence of the symbol at a byte cursor (function, method, property, or
+/// identifier), for highlighting siblings in the editor.
+pub fn document_highlights(source: &str, cursor: u32) -> Vec<Span> {
+    let tokens = analyzer::analyze_syntax(source).tokens;
+    highlight::document_highlights(&tokens, cursor)
+}
+
+/// Byte edits renaming the property referenced at a byte cursor: every `prop("...")` literal
+/// naming it is replaced with `new_name` (quoted and escaped).
+///
+/// `None` when the cursor is not on a property reference; properties are the only renamable
+/// symbols.
+pub fn rename(source: &str, cursor: u32, new_name: &str) -> Option<Vec<TextEdit>> {
+    let tokens = analyzer::analyze_syntax(source).tokens;
+    rename::rename(&tokens, cursor, new_name)
+}
+
+/// Distinct properties read by `prop("...")` calls in `source`, in order of first reference,
+/// each with the byte spans of its literals.
+pub fn referenced_properties(source: &str) -> Vec<PropertyReference> {
+    let syntax = analyzer::analyze_syntax(source);
+    references::referenced_properties(&syntax.expr)
+}
+
+/// Rewrite a Notion Formula 1.0 formula into 2.0 syntax, reporting constructs that need manual
+/// review.
+///
+/// Formulas with syntax errors are only fixed for single-quoted `prop` names; their syntax errors
+/// are reported as review notes.
+pub fn migrate_legacy(source: &str, ctx: &semantic::Context) -> Migration {
+    migrate::migrate(source, ctx)
+}
+
+/// Formula source for a Notion API `formula.expression`: property-id placeholders become
+/// `prop("Name")`, with names from `schema` (the database's `properties`).
+pub fn from_notion_expression(
+    expression: &str,
+    schema: &BTreeMap<String, semantic::NotionPropertySchema>,
+) -> Result<String, NotionExpressionError> {
+    notion_expression::from_notion_expression(expression, schema)
+}
+
+/// The Notion API `formula.expression` for `source`: each well-formed `prop("Name")` becomes the
+/// property's id placeholder. The inverse of [`from_notion_expression`].
+pub fn to_notion_expression(
+    source: &str,
+    schema: &BTreeMap<String, semantic::NotionPropertySchema>,
+) -> Result<String, NotionExpressionError> {
+    let syntax = analyzer::analyze_syntax(source);
+    notion_expression::to_notion_expression(source, &syntax.expr, schema)
+}
+
+/// Quick fixes from the diagnostics overlapping a byte range (a cursor when empty).
+///
+/// When the range touches one of several pasted lookalikes (see [`normalize_pasted`]), a last
+/// action replaces them all.
+pub fn code_actions(
+    source: &str,
+    range: Span,
+    ctx: &semantic::Context,
+) -> Vec<analyzer::CodeAction> {
+    let diagnostics = analyzer::analyze(source, ctx).diagnostics;
+    let mut actions = code_actions::code_actions(&diagnostics, range);
+    actions.extend(paste::normalize_pasted_action(source, range));
+    actions
+}
+
+/// Edits mapping text pasted from documents to ASCII: smart quotes, non-breaking and other
+/// Unicode spaces, zero-width characters, minus signs and dashes, `×`/`÷`/`≠`/`≤`/`≥`, and
+/// full-width punctuation and digits.
+///
+/// Content of string literals and comments is kept; a string written with smart quotes
+/// (`“Name”`) gets ASCII quotes. Edits are in source order and do not overlap.
+pub fn normalize_pasted(source: &str) -> Vec<TextEdit> {
+    paste::normalize_pasted(source)
+}
+
+/// Simplification suggestions for `source` as code actions, in source order: constant folding,
+/// double negation, boolean `if`s, De Morgan, and nested `if`s merged into `ifs`.
+///
+/// Suggestions may nest (e.g. a fold inside a merged `ifs`); [`simplify_all`] applies them
+/// together. Formulas with syntax errors get none.
+pub fn simplify(source: &str, ctx: &semantic::Context) -> Vec<analyzer::CodeAction> {
+    simplify::simplify(source, ctx)
+}
+
+/// Edits turning `source` into its fully simplified form (sorted, non-overlapping), applying
+/// [`simplify`] suggestions outermost first until none are left.
+pub fn simplify_all(source: &str, ctx: &semantic::Context) -> Vec<TextEdit> {
+    simplify::simplify_all(source, ctx)
+}
+
+/// Whether `left` and `right` compute the same value, comparing normalized forms: layout,
+/// comments, and parentheses are ignored; method and function calls, `?:`/`if`/`ifs`, and
+/// `a > b`/`b < a` are interchangeable; literal operations are folded; and the operands of
+/// commutative operators may be reordered.
+///
+/// Normalization is conservative: `Different` can be reported for formulas that agree on every
+/// input, but not the other way around (up to floating-point rounding in folded literals).
+pub fn equivalent(left: &str, right: &str, ctx: &semantic::Context) -> Equivalence {
+    equivalence::equivalent(left, right, ctx)
 }
 
+/// Describe `source` in plain language, as a tree following its expression structure.
+///
+/// The root's `text` reads as one sentence, e.g. `If Status is "Done", show Due formatted as
+/// "MMM D", otherwise show "Open"`; its children describe the parts, for expandable summaries.
+pub fn explain(source: &str, ctx: &semantic::Context) -> Explanation {
+    let syntax = analyzer::analyze_syntax(source);
+    let mut type_map = analyzer::TypeMap::default();
+    semantic::analyze_expr_with_map(&syntax.expr, ctx, &mut type_map);
+    explain::explain(&syntax.expr, &type_map)
+}
+
+/// Compute hover info (type, and signature/docs for function names) at a byte cursor.
+pub fn hover(source: &str, cursor: usize, ctx: &semantic::Context) -> Option<HoverResult> {
+    let syntax = analyzer::analyze_syntax(source);
+    let mut type_map = analyzer::TypeMap::default();
+    semantic::analyze_expr_with_map(&syntax.expr, ctx, &mut type_map);
+    let cursor = u32::try_from(cursor).unwrap_or(u32::MAX);
+    hover::hover(source, &syntax.tokens, &syntax.expr, &type_map, cursor, ctx)
+}
+
+/// Inferred type and range of the smallest expression enclosing a byte cursor.
+///
+/// A lighter alternative to [`hover`] (no signature rendering).
+pub fn type_at(source: &str, cursor: usize, ctx: &semantic::Context) -> Option<TypeAtResult> {
+    let syntax = analyzer::analyze_syntax(source);
+    let mut type_map = analyzer::TypeMap::default();
+    semantic::analyze_expr_with_map(&syntax.expr, ctx, &mut type_map);
+    let cursor = u32::try_from(cursor).unwrap_or(u32::MAX);
+    hover::type_at(&syntax.tokens, &syntax.expr, &type_map, cursor)
+}
+
+/// Classify tokens for semantic highlighting (byte spans, source order).
+///
+/// Use [`encode_semantic_tokens_lsp`] to get LSP delta-encoded data.
+pub fn semantic_tokens(source: &str, ctx: &semantic::Context) -> Vec<SemanticToken> {
+    let tokens = analyzer::analyze_syntax(source).tokens;
+    semantic_tokens::semantic_tokens(&tokens, ctx)
+}
+
+/// Semantically highlighted HTML of `source` for read-only display (docs, diffs, share views).
+///
+/// A `<pre><code>` block whose `<span>`s carry token-kind classes, byte-offset `data-start` /
+/// `data-end`, and underlined diagnostics with their messages as `title`; see [`HtmlTheme`].
+pub fn render_html(source: &str, ctx: &semantic::Context, theme: &HtmlTheme) -> String {
+    let result = analyzer::analyze(source, ctx);
+    html::render_html(source, &result.tokens, &result.diagnostics, ctx, theme)
+}
+
+/// One help request over a single parse of `source`.
+///
+/// Context detection, completion, and signature help share `tokens` and `root`; node types are
+/// inferred at most once, on first use ([`HelpSession::types`]).
 struct HelpSession<'a> {
     source: &'a str,
     cursor: u32,
     ctx: &'a semantic::Context,
     config: completion::CompletionConfig,
-    tokens: Vec<Token>,
+    tokens: &'a [Token],
+    root: &'a Expr,
+    types: OnceCell<TypeMap>,
+    /// Types already inferred by the caller (e.g. `AnalysisHost`), used instead of `types`.
+    cached_types: Option<&'a TypeMap>,
+    cancel: Option<&'a CancellationToken>,
 }
 
-struct CompletionDraft {
-    items: Vec<CompletionItem>,
+struct CompletionDraft<'a> {
+    items: Vec<completion::Candidate<'a>>,
     replace: Span,
 }
 
 impl<'a> HelpSession<'a> {
+    /// `syntax` must come from parsing `source`.
     fn new(
         source: &'a str,
+        syntax: &'a SyntaxResult,
         cursor: usize,
         ctx: &'a semantic::Context,
         config: completion::CompletionConfig,
@@ -71,59 +402,149 @@ impl<'a> HelpSession<'a> {
             cursor: u32::try_from(cursor).unwrap_or(u32::MAX),
             ctx,
             config,
-            tokens: analyzer::analyze_syntax(source).tokens,
+            tokens: &syntax.tokens,
+            root: &syntax.expr,
+            types: OnceCell::new(),
+            cached_types: None,
+            cancel: None,
+        }
+    }
+
+    /// Reuses `types`, inferred for the same syntax and context, instead of inferring again.
+    fn with_types(mut self, types: &'a TypeMap) -> Self {
+        self.cached_types = Some(types);
+        self
+    }
+
+    fn types(&self) -> &TypeMap {
+        if let Some(types) = self.cached_types {
+            return types;
         }
+        self.types.get_or_init(|| {
+            let mut types = TypeMap::default();
+            let _ = analyzer::infer_expr_with_map(self.root, self.ctx, &mut types);
+            types
+        })
+    }
+
+    fn with_cancellation(mut self, cancel: &'a CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn check_cancelled(&self) -> Result<(), Cancelled> {
+        self.cancel.map_or(Ok(()), CancellationToken::check)
     }
 
     fn run(self) -> HelpResult {
+        match self.try_run() {
+            Ok(result) => result,
+            Err(Cancelled) => unreachable!("help without a cancellation token was cancelled"),
+        }
+    }
+
+    fn try_run(self) -> Result<HelpResult, Cancelled> {
         // 1) Detect call/position/query context at the cursor.
-        let cursor_ctx = context::detect_cursor_context(
-            self.source,
-            self.tokens.as_slice(),
-            self.cursor,
-            self.ctx,
-        );
+        let mut cursor_ctx =
+            context::detect_cursor_context(self.source, self.tokens, self.cursor, self.ctx);
+
+        // Inside a string literal compared with a property that declares options, complete
+        // them, replacing the literal's content and querying the part before the cursor.
+        let option_slot = match cursor_ctx.position_kind {
+            PositionKind::None => {
+                completion::OptionValueSlot::find(self.root, self.cursor, self.ctx)
+            }
+            _ => None,
+        };
+        if let Some(slot) = &option_slot {
+            let typed = Span {
+                start: slot.content.start,
+                end: self.cursor,
+            };
+            cursor_ctx.replace = slot.content;
+            cursor_ctx.query = context::completion_query_for_replace(self.source, typed);
+        }
+
+        self.check_cancelled()?;
 
         // 2) Compute signature help from call context.
-        let signature_help = signature::compute_signature_help_if_in_call(
-            self.source,
-            self.tokens.as_slice(),
-            self.cursor,
-            self.ctx,
-            cursor_ctx.call_ctx.as_ref(),
-        );
-
-        // 3) Build raw completion items for the position kind.
-        let draft = self.build_completion_draft(&cursor_ctx);
-
-        // 4) Attach primary edits and cursor positions.
-        let mut items = draft.items;
-        completion::attach_primary_edits(draft.replace, &mut items);
+        let signature_help = cursor_ctx.call_ctx.as_ref().and_then(|call_ctx| {
+            signature::compute_signature_help_if_in_call(
+                self.source,
+                self.tokens,
+                self.root,
+                self.types(),
+                self.cursor,
+                self.ctx,
+                call_ctx,
+            )
+        });
+
+        self.check_cancelled()?;
+
+        // 3) Collect completion candidates for the position kind.
+        let draft = match &option_slot {
+            Some(slot) => CompletionDraft {
+                items: slot.candidates(),
+                replace: slot.content,
+            },
+            None => self.build_completion_draft(&cursor_ctx),
+        };
 
-        // 5) Rank by query (sort + filter).
-        if let Some(query) = cursor_ctx.query.as_deref() {
-            completion::rank_by_query(query, &mut items, cursor_ctx.position_kind);
+        // 4) Apply config filters.
+        let mut candidates = draft.items;
+        candidates.retain(|item| !self.config.exclude_kinds.contains(&item.kind()));
+
+        self.check_cancelled()?;
+
+        // 5) Rank by query (sort + filter), unless the host ranks raw items itself.
+        if let Some(query) = cursor_ctx.query.as_deref()
+            && !self.config.raw
+        {
+            completion::rank_by_query(
+                query,
+                &mut candidates,
+                cursor_ctx.position_kind,
+                self.config.match_mode,
+                &self.config.ranking,
+            );
         }
 
-        // 6) Pick preferred indices.
+        // 6) Build the surviving items, then attach primary edits and cursor positions.
+        let mut items: Vec<CompletionItem> = candidates
+            .into_iter()
+            .map(|item| item.into_item(self.config.call_parens))
+            .collect();
+        completion::attach_primary_edits(draft.replace, &mut items);
+
+        // 7) Score matches and pick preferred indices.
         let preferred_indices = match cursor_ctx.query.as_deref() {
             Some(query) => {
-                completion::preferred_indices(&items, query, self.config.preferred_limit)
+                completion::attach_match_strengths(&mut items, query, self.config.match_mode);
+                if self.config.raw {
+                    Vec::new()
+                } else {
+                    completion::preferred_indices(
+                        &items,
+                        self.config.preferred_limit,
+                        &self.config.ranking,
+                    )
+                }
             }
             None => Vec::new(),
         };
 
-        HelpResult {
+        Ok(HelpResult {
             completion: CompletionResult {
                 items,
                 replace: draft.replace,
                 preferred_indices,
             },
             signature_help,
-        }
+        })
     }
 
-    fn build_completion_draft(&self, cursor_ctx: &CursorContext) -> CompletionDraft {
+    fn build_completion_draft(&self, cursor_ctx: &CursorContext) -> CompletionDraft<'a> {
         let default_replace = Span {
             start: self.cursor,
             end: self.cursor,
@@ -135,7 +556,9 @@ impl<'a> HelpSession<'a> {
             .all(|token| matches!(token.kind, TokenKind::Eof))
         {
             let items = if self.cursor == 0 {
-                completion::expr_start_items(self.ctx)
+                let mut items = completion::expr_start_items(self.ctx, "");
+                items.extend(completion::snippet_items(&self.config.snippets));
+                items
             } else {
                 Vec::new()
             };
@@ -150,9 +573,19 @@ impl<'a> HelpSession<'a> {
             PositionKind::NeedExpr => {
                 let expected =
                     context::expected_call_arg_ty(cursor_ctx.call_ctx.as_ref(), self.ctx);
-                let mut items = completion::expr_start_items(self.ctx);
-                if expected.is_some() {
-                    completion::apply_type_ranking(&mut items, expected, self.ctx);
+                let prefix = self
+                    .source
+                    .get(cursor_ctx.replace.start as usize..cursor_ctx.replace.end as usize)
+                    .unwrap_or_default();
+                let mut items = completion::expr_start_items(self.ctx, prefix);
+                items.extend(completion::snippet_items(&self.config.snippets));
+                if expected.is_some() && self.config.type_ranking && !self.config.raw {
+                    completion::apply_type_ranking(
+                        &mut items,
+                        expected,
+                        self.ctx,
+                        &self.config.ranking,
+                    );
                 }
                 items
             }
@@ -169,28 +602,33 @@ impl<'a> HelpSession<'a> {
         }
     }
 
+    /// Type of the expression before the `.` at the cursor.
+    ///
+    /// A member access without `(...)` does not survive parse recovery, so the tokens before
+    /// the dot are parsed on their own (reusing the session's lexing).
     fn infer_postfix_receiver_ty(&self) -> semantic::Ty {
-        let Some(dot_idx) =
-            context::postfix_member_access_dot_index(self.tokens.as_slice(), self.cursor)
+        let Some(dot_idx) = context::postfix_member_access_dot_index(self.tokens, self.cursor)
         else {
             return semantic::Ty::Unknown;
         };
         let Some(dot_token) = self.tokens.get(dot_idx) else {
             return semantic::Ty::Unknown;
         };
-        let Ok(dot_start) = usize::try_from(dot_token.span.start) else {
-            return semantic::Ty::Unknown;
-        };
-        if dot_start > self.source.len() || !self.source.is_char_boundary(dot_start) {
-            return semantic::Ty::Unknown;
-        }
-
-        let receiver_source = self.source[..dot_start].trim_end();
-        if receiver_source.is_empty() {
+        if non_trivia(&self.tokens[..dot_idx]).next().is_none() {
             return semantic::Ty::Unknown;
         }
 
-        let parsed = analyzer::analyze_syntax(receiver_source);
+        // Sized up front: `to_vec` + `push` would reallocate for the `Eof`.
+        let mut receiver_tokens = Vec::with_capacity(dot_idx + 1);
+        receiver_tokens.extend_from_slice(&self.tokens[..dot_idx]);
+        receiver_tokens.push(Token {
+            kind: TokenKind::Eof,
+            span: Span {
+                start: dot_token.span.start,
+                end: dot_token.span.start,
+            },
+        });
+        let parsed = analyzer::parse_tokens(self.source, receiver_tokens);
 
         let mut map = analyzer::TypeMap::default();
         analyzer::infer_expr_with_map(&parsed.expr, self.ctx, &mut map)
@@ -198,8 +636,78 @@ impl<'a> HelpSession<'a> {
 }
 
 /// Format a source string and rebase a byte cursor.
-pub fn format(source: &str, cursor_byte: u32) -> Result<ApplyResult, IdeError> {
-    edit::ide_format(source, cursor_byte)
+#[cfg(feature = "format")]
+pub fn format(source: &str, cursor_byte: u32) -> Result<ApplyResult, EditError> {
+    edit::ide_format(source, cursor_byte, FormatConfig::default())
+}
+
+/// Like [`format`], with explicit layout options.
+#[cfg(feature = "format")]
+pub fn format_with_config(
+    source: &str,
+    cursor_byte: u32,
+    config: FormatConfig,
+) -> Result<ApplyResult, EditError> {
+    edit::ide_format(source, cursor_byte, config)
+}
+
+/// Like [`format_with_config`], reporting the whole run as [`Phase::Format`] to
+/// `instrumentation`.
+#[cfg(feature = "format")]
+pub fn format_instrumented(
+    source: &str,
+    cursor_byte: u32,
+    config: FormatConfig,
+    instrumentation: &mut dyn Instrumentation,
+) -> Result<ApplyResult, EditError> {
+    let mut clock = PhaseClock::start(instrumentation, source.len());
+    let result = edit::ide_format(source, cursor_byte, config);
+    clock.lap(Phase::Format);
+    result
+}
+
+/// Like [`format`], but the cursor (input and output) is in UTF-16 code units.
+#[cfg(feature = "format")]
+pub fn format_utf16(source: &str, cursor_utf16: u32) -> Result<ApplyResult, EditError> {
+    let cursor = utf16::utf16_cursor_to_byte(source, cursor_utf16)?;
+    let result = edit::ide_format(source, cursor, FormatConfig::default())?;
+    Ok(utf16::apply_result_to_utf16(source, result))
+}
+
+/// Like [`apply_edits`], but edits and cursor (input and output) are in UTF-16 code units.
+pub fn apply_edits_utf16(
+    source: &str,
+    edits: Vec<TextEditUtf16>,
+    cursor_utf16: u32,
+) -> Result<ApplyResult, EditError> {
+    let edits = utf16::text_edits_from_utf16(source, edits)?;
+    let cursor = utf16::utf16_cursor_to_byte(source, cursor_utf16)?;
+    let result = edit::apply_edits(source, edits, cursor)?;
+    Ok(utf16::apply_result_to_utf16(source, result))
+}
+
+/// Compute minimal, token-aligned byte edits that turn `old_source` into `new_source`.
+///
+/// Edits are sorted, non-overlapping, and in `old_source` coordinates (ready for
+/// [`apply_edits`]). Works on syntax-invalid input too.
+pub fn diff(old_source: &str, new_source: &str) -> Vec<TextEdit> {
+    diff::diff(old_source, new_source)
+}
+
+/// Minify a source string for compact storage (no comments, no optional whitespace).
+#[cfg(feature = "format")]
+pub fn format_minified(source: &str) -> Result<String, EditError> {
+    edit::ide_format_minified(source)
+}
+
+/// Reformat the expression enclosing a byte range and return minimal byte edits.
+#[cfg(feature = "format")]
+pub fn format_range(
+    source: &str,
+    range: Span,
+    config: FormatConfig,
+) -> Result<Vec<TextEdit>, EditError> {
+    edit::ide_format_range(source, range, config)
 }
 
 #[cfg(test)]
@@ -0,0 +1,544 @@
+//! Notion Formula 1.0 → 2.0 migration.
+//!
+//! Rewrites legacy constructs into modern syntax:
+//! - operator functions (`add(a, b)`, `larger(a, b)`, `and(a, b)`, `unaryMinus(x)`, …) become
+//!   operators, parenthesizing operands only where precedence requires it;
+//! - `slice` and `concat` on text become `substring` and `+`;
+//! - `prop(Name)` and `prop('Name')` become `prop("Name")`.
+//!
+//! Single-quoted `prop` names are fixed textually first (the lexer has no single-quoted strings);
+//! everything else is rewritten on the AST by re-rendering changed nodes from source slices, so
+//! comments and layout outside rewritten calls survive. Constructs that cannot be rewritten safely
+//! are reported for manual review instead. Coordinates are UTF-8 byte offsets into the input.
+
+use analyzer::ast::{BinOpKind, Expr, ExprKind, UnOp};
+use analyzer::semantic::{self, Ty};
+use analyzer::{Span, TextEdit, Token, TokenKind, TypeMap};
+
+use crate::diff::diff;
+use crate::hover::children;
+use crate::rename::string_literal;
+
+/// Result of [`migrate_legacy`](crate::migrate_legacy).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    /// The migrated formula.
+    pub source: String,
+    /// Edits turning the input into `source` (input coordinates, sorted, non-overlapping).
+    pub edits: Vec<TextEdit>,
+    /// What was rewritten and what needs manual review, in source order.
+    pub notes: Vec<MigrationNote>,
+}
+
+/// One report entry of a [`Migration`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationNote {
+    /// Span of the legacy construct in the input.
+    pub span: Span,
+    pub kind: MigrationNoteKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationNoteKind {
+    /// The construct was rewritten.
+    Rewritten,
+    /// The construct was left as is and needs a manual decision.
+    NeedsReview,
+}
+
+/// Legacy operator functions and the binary operators replacing them.
+const OPERATOR_FUNCTIONS: &[(&str, BinOpKind)] = &[
+    ("add", BinOpKind::Plus),
+    ("subtract", BinOpKind::Minus),
+    ("multiply", BinOpKind::Star),
+    ("divide", BinOpKind::Slash),
+    ("mod", BinOpKind::Percent),
+    ("pow", BinOpKind::Caret),
+    ("equal", BinOpKind::EqEq),
+    ("unequal", BinOpKind::Ne),
+    ("larger", BinOpKind::Gt),
+    ("largerEq", BinOpKind::Ge),
+    ("smaller", BinOpKind::Lt),
+    ("smallerEq", BinOpKind::Le),
+    ("and", BinOpKind::AndAnd),
+    ("or", BinOpKind::OrOr),
+];
+
+pub(crate) fn migrate(source: &str, ctx: &semantic::Context) -> Migration {
+    let mut notes = Vec::new();
+    let (quoted, quote_edits) = quote_single_quoted_props(source, &mut notes);
+
+    let syntax = analyzer::analyze_syntax(&quoted);
+    let (migrated, ast_notes) = if syntax.diagnostics.is_empty() {
+        let mut type_map = TypeMap::default();
+        semantic::analyze_expr_with_map(&syntax.expr, ctx, &mut type_map);
+        let mut rewriter = Rewriter {
+            source: &quoted,
+            tokens: &syntax.tokens,
+            type_map: &type_map,
+            notes: Vec::new(),
+        };
+        let root = &syntax.expr;
+        let body = rewriter.render(root).text;
+        let migrated = format!(
+            "{}{body}{}",
+            &quoted[..root.span.start as usize],
+            &quoted[root.span.end as usize..]
+        );
+        (migrated, rewriter.notes)
+    } else {
+        let notes = syntax
+            .diagnostics
+            .iter()
+            .map(|d| MigrationNote {
+                span: d.span,
+                kind: MigrationNoteKind::NeedsReview,
+                message: format!("syntax error: {}; formula not migrated", d.message),
+            })
+            .collect();
+        (quoted.clone(), notes)
+    };
+
+    notes.extend(ast_notes.into_iter().map(|note| MigrationNote {
+        span: Span {
+            start: map_back(note.span.start, &quote_edits),
+            end: map_back(note.span.end, &quote_edits),
+        },
+        ..note
+    }));
+    notes.sort_by_key(|note| (note.span.start, note.span.end));
+
+    Migration {
+        edits: diff(source, &migrated),
+        source: migrated,
+        notes,
+    }
+}
+
+/// Rewrites `prop('Name')` to `prop("Name")`, returning the new text and the edits applied.
+///
+/// Skips double-quoted strings and comments so their contents are never touched.
+fn quote_single_quoted_props(
+    source: &str,
+    notes: &mut Vec<MigrationNote>,
+) -> (String, Vec<TextEdit>) {
+    let bytes = source.as_bytes();
+    let mut edits = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = source[i..].find('\n').map_or(bytes.len(), |n| i + n);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = source[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |n| i + 2 + n + 2);
+            }
+            b'p' if source[i..].starts_with("prop") && (i == 0 || !is_ident_byte(bytes[i - 1])) => {
+                let after_name = i + "prop".len();
+                let open = skip_whitespace(source, after_name);
+                if bytes.get(open) == Some(&b'(') {
+                    let quote = skip_whitespace(source, open + 1);
+                    if bytes.get(quote) == Some(&b'\'')
+                        && let Some(len) = source[quote + 1..].find('\'')
+                    {
+                        let end = quote + 1 + len + 1;
+                        let name = &source[quote + 1..end - 1];
+                        let range = Span {
+                            start: quote as u32,
+                            end: end as u32,
+                        };
+                        edits.push(TextEdit {
+                            range,
+                            new_text: string_literal(name),
+                        });
+                        notes.push(MigrationNote {
+                            span: range,
+                            kind: MigrationNoteKind::Rewritten,
+                            message: "single-quoted property name rewritten with double quotes"
+                                .into(),
+                        });
+                        i = end;
+                        continue;
+                    }
+                }
+                i = after_name;
+            }
+            _ => i += 1,
+        }
+    }
+
+    let mut out = String::with_capacity(source.len());
+    let mut pos = 0;
+    for edit in &edits {
+        out.push_str(&source[pos..edit.range.start as usize]);
+        out.push_str(&edit.new_text);
+        pos = edit.range.end as usize;
+    }
+    out.push_str(&source[pos..]);
+    (out, edits)
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || !b.is_ascii()
+}
+
+fn skip_whitespace(source: &str, from: usize) -> usize {
+    from + source[from..].len() - source[from..].trim_start().len()
+}
+
+/// Maps an offset in the text produced by `edits` back to the original text.
+///
+/// Offsets inside a replacement map to its start.
+fn map_back(offset: u32, edits: &[TextEdit]) -> u32 {
+    let mut delta: i64 = 0;
+    for edit in edits {
+        let new_start = i64::from(edit.range.start) + delta;
+        let new_end = new_start + edit.new_text.len() as i64;
+        let offset = i64::from(offset);
+        if offset < new_end {
+            return if offset <= new_start {
+                (offset - delta) as u32
+            } else {
+                edit.range.start
+            };
+        }
+        delta += edit.new_text.len() as i64 - i64::from(edit.range.end - edit.range.start);
+    }
+    (i64::from(offset) - delta) as u32
+}
+
+/// How tightly a rendered expression binds, for deciding where parentheses are needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Prec {
+    /// Literals, identifiers, calls, lists, and parenthesized expressions.
+    Atom,
+    /// `-x`, `!x`, `not x`.
+    Prefix,
+    /// Binary operator with its Pratt binding powers.
+    Binary(u8, u8),
+    Ternary,
+}
+
+/// Where a child expression sits in its parent.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Slot {
+    /// Delimited positions (arguments, list items, group contents, ternary parts).
+    Free,
+    Receiver,
+    UnaryOperand,
+    Left(BinOpKind),
+    Right(BinOpKind),
+}
+
+pub(crate) struct Fragment {
+    pub text: String,
+    pub prec: Prec,
+}
+
+impl Fragment {
+    /// The text for `slot`, parenthesized if it would otherwise parse differently there.
+    pub(crate) fn in_slot(self, slot: Slot) -> String {
+        if fits(self.prec, slot) {
+            self.text
+        } else {
+            format!("({})", self.text)
+        }
+    }
+}
+
+/// Whether text of precedence `prec` parses as one operand in `slot` without parentheses.
+pub(crate) fn fits(prec: Prec, slot: Slot) -> bool {
+    let unary_bp = UnOp::Neg.prefix_binding_power();
+    match (prec, slot) {
+        (Prec::Atom, _) | (_, Slot::Free) => true,
+        (_, Slot::Receiver) | (Prec::Ternary, _) => false,
+        (Prec::Prefix, Slot::UnaryOperand | Slot::Right(_)) => true,
+        (Prec::Prefix, Slot::Left(op)) => binding_power(op).0 < unary_bp,
+        (Prec::Binary(left_bp, _), Slot::UnaryOperand) => left_bp >= unary_bp,
+        (Prec::Binary(_, right_bp), Slot::Left(op)) => binding_power(op).0 < right_bp,
+        (Prec::Binary(left_bp, _), Slot::Right(op)) => left_bp >= binding_power(op).1,
+    }
+}
+
+fn binding_power(op: BinOpKind) -> (u8, u8) {
+    analyzer::ast::BinOp {
+        node: op,
+        span: Span { start: 0, end: 0 },
+    }
+    .infix_binding_power()
+}
+
+struct Rewriter<'a> {
+    source: &'a str,
+    tokens: &'a [Token],
+    type_map: &'a TypeMap,
+    notes: Vec<MigrationNote>,
+}
+
+impl Rewriter<'_> {
+    fn render(&mut self, expr: &Expr) -> Fragment {
+        if let Some(fragment) = self.rewrite(expr) {
+            return fragment;
+        }
+        Fragment {
+            text: self.splice(expr, None),
+            prec: prec_of(expr),
+        }
+    }
+
+    /// `expr`'s source text with rewritten children substituted, and `rename` (a span outside
+    /// the children, or exactly one child's span) replaced.
+    fn splice(&mut self, expr: &Expr, rename: Option<(Span, String)>) -> String {
+        let mut out = String::new();
+        let mut pos = expr.span.start as usize;
+        let mut rename = rename;
+        for (child, slot) in slots(expr) {
+            if let Some((span, _)) = &rename
+                && span.start <= child.span.start
+            {
+                let (span, text) = rename.take().expect("rename");
+                out.push_str(&self.source[pos..span.start as usize]);
+                out.push_str(&text);
+                pos = span.end as usize;
+            }
+            if pos > child.span.start as usize {
+                continue;
+            }
+            out.push_str(&self.source[pos..child.span.start as usize]);
+            out.push_str(&self.render(child).in_slot(slot));
+            pos = child.span.end as usize;
+        }
+        if let Some((span, text)) = rename {
+            out.push_str(&self.source[pos..span.start as usize]);
+            out.push_str(&text);
+            pos = span.end as usize;
+        }
+        out.push_str(&self.source[pos..expr.span.end as usize]);
+        out
+    }
+
+    fn rewrite(&mut self, expr: &Expr) -> Option<Fragment> {
+        match &expr.kind {
+            ExprKind::Call { callee, args } => self.rewrite_call(expr, &callee.text, args),
+            ExprKind::MemberCall {
+                receiver, method, ..
+            } if method.text == "slice" => {
+                let method_span = self.ident_after(&method.text, receiver.span.end)?;
+                self.rewrite_slice(expr, receiver, method_span)
+            }
+            _ => None,
+        }
+    }
+
+    fn rewrite_call(&mut self, expr: &Expr, callee: &str, args: &[Expr]) -> Option<Fragment> {
+        if let Some(&(_, op)) = OPERATOR_FUNCTIONS.iter().find(|(name, _)| *name == callee) {
+            if args.len() != 2 {
+                self.review(expr, format!("`{callee}` takes 2 arguments; not rewritten"));
+                return None;
+            }
+            if self.has_comments_between_args(expr, args) {
+                self.review(
+                    expr,
+                    format!("comments inside `{callee}(…)`; not rewritten"),
+                );
+                return None;
+            }
+            let left = self.render(&args[0]).in_slot(Slot::Left(op));
+            let right = self.render(&args[1]).in_slot(Slot::Right(op));
+            self.rewritten(
+                expr,
+                format!("`{callee}(…)` rewritten to `{}`", op.as_str()),
+            );
+            let (left_bp, right_bp) = binding_power(op);
+            return Some(Fragment {
+                text: format!("{left} {} {right}", op.as_str()),
+                prec: Prec::Binary(left_bp, right_bp),
+            });
+        }
+
+        match (callee, args) {
+            ("unaryMinus", [operand]) => {
+                if self.has_comments_between_args(expr, args) {
+                    self.review(
+                        expr,
+                        "comments inside `unaryMinus(…)`; not rewritten".into(),
+                    );
+                    return None;
+                }
+                let operand = self.render(operand).in_slot(Slot::UnaryOperand);
+                self.rewritten(expr, "`unaryMinus(…)` rewritten to `-`".into());
+                Some(Fragment {
+                    text: format!("-{operand}"),
+                    prec: Prec::Prefix,
+                })
+            }
+            ("unaryPlus", [_]) => {
+                let name = self.ident_after(callee, expr.span.start)?;
+                self.rewritten(expr, "`unaryPlus(…)` rewritten to `toNumber(…)`".into());
+                Some(Fragment {
+                    text: self.splice(expr, Some((name, "toNumber".into()))),
+                    prec: Prec::Atom,
+                })
+            }
+            ("unaryMinus" | "unaryPlus", _) => {
+                self.review(expr, format!("`{callee}` takes 1 argument; not rewritten"));
+                None
+            }
+            ("slice", [text, ..]) => {
+                let name = self.ident_after(callee, expr.span.start)?;
+                self.rewrite_slice(expr, text, name)
+            }
+            ("concat", [_, ..]) => self.rewrite_concat(expr, args),
+            ("prop", [arg]) => match &arg.kind {
+                ExprKind::Ident(name) => {
+                    self.rewritten(expr, "unquoted property name quoted".into());
+                    Some(Fragment {
+                        text: self.splice(expr, Some((arg.span, string_literal(&name.text)))),
+                        prec: Prec::Atom,
+                    })
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// `slice` on text is `substring` in 2.0 (`slice` only takes lists).
+    fn rewrite_slice(&mut self, expr: &Expr, text: &Expr, name: Span) -> Option<Fragment> {
+        match self.ty(text) {
+            Ty::String => {
+                self.rewritten(expr, "`slice` on text rewritten to `substring`".into());
+                Some(Fragment {
+                    text: self.splice(expr, Some((name, "substring".into()))),
+                    prec: Prec::Atom,
+                })
+            }
+            Ty::List(_) => None,
+            _ => {
+                self.review(
+                    expr,
+                    "`slice` on a value of unknown type; use `substring` if it is text".into(),
+                );
+                None
+            }
+        }
+    }
+
+    /// `concat` on text is `+` in 2.0 (`concat` only takes lists).
+    fn rewrite_concat(&mut self, expr: &Expr, args: &[Expr]) -> Option<Fragment> {
+        let types: Vec<Ty> = args.iter().map(|arg| self.ty(arg)).collect();
+        if types.iter().any(|ty| matches!(ty, Ty::List(_))) {
+            return None;
+        }
+        if !types.iter().all(|ty| *ty == Ty::String) {
+            self.review(
+                expr,
+                "`concat` with arguments of unknown type; use `+` if they are text".into(),
+            );
+            return None;
+        }
+        if self.has_comments_between_args(expr, args) {
+            self.review(expr, "comments inside `concat(…)`; not rewritten".into());
+            return None;
+        }
+
+        self.rewritten(expr, "`concat` on text rewritten to `+`".into());
+        if let [only] = args {
+            return Some(self.render(only));
+        }
+        let op = BinOpKind::Plus;
+        let mut text = self.render(&args[0]).in_slot(Slot::Left(op));
+        for arg in &args[1..] {
+            text.push_str(" + ");
+            text.push_str(&self.render(arg).in_slot(Slot::Right(op)));
+        }
+        let (left_bp, right_bp) = binding_power(op);
+        Some(Fragment {
+            text,
+            prec: Prec::Binary(left_bp, right_bp),
+        })
+    }
+
+    fn ty(&self, expr: &Expr) -> Ty {
+        self.type_map.get(expr.id).cloned().unwrap_or(Ty::Unknown)
+    }
+
+    /// Span of the first `name` identifier token starting at or after `from`.
+    fn ident_after(&self, name: &str, from: u32) -> Option<Span> {
+        self.tokens
+            .iter()
+            .find(|t| {
+                t.span.start >= from && matches!(&t.kind, TokenKind::Ident(s) if s.text == name)
+            })
+            .map(|t| t.span)
+    }
+
+    /// Whether a comment in `call` lies outside its arguments (it would be lost on rewrite).
+    fn has_comments_between_args(&self, call: &Expr, args: &[Expr]) -> bool {
+        self.tokens.iter().any(|t| {
+            t.kind.is_comment()
+                && call.span.start <= t.span.start
+                && t.span.end <= call.span.end
+                && !args
+                    .iter()
+                    .any(|a| a.span.start <= t.span.start && t.span.end <= a.span.end)
+        })
+    }
+
+    fn rewritten(&mut self, expr: &Expr, message: String) {
+        self.note(expr, MigrationNoteKind::Rewritten, message);
+    }
+
+    fn review(&mut self, expr: &Expr, message: String) {
+        self.note(expr, MigrationNoteKind::NeedsReview, message);
+    }
+
+    fn note(&mut self, expr: &Expr, kind: MigrationNoteKind, message: String) {
+        self.notes.push(MigrationNote {
+            span: expr.span,
+            kind,
+            message,
+        });
+    }
+}
+
+pub(crate) fn prec_of(expr: &Expr) -> Prec {
+    match &expr.kind {
+        ExprKind::Unary { .. } => Prec::Prefix,
+        ExprKind::Binary { op, .. } => {
+            let (left_bp, right_bp) = op.infix_binding_power();
+            Prec::Binary(left_bp, right_bp)
+        }
+        ExprKind::Ternary { .. } => Prec::Ternary,
+        _ => Prec::Atom,
+    }
+}
+
+/// Children of `expr` in source order, with their slots.
+pub(crate) fn slots(expr: &Expr) -> Vec<(&Expr, Slot)> {
+    match &expr.kind {
+        ExprKind::MemberCall { receiver, args, .. } => {
+            std::iter::once((&**receiver, Slot::Receiver))
+                .chain(args.iter().map(|a| (a, Slot::Free)))
+                .collect()
+        }
+        ExprKind::Unary { expr, .. } => vec![(expr, Slot::UnaryOperand)],
+        ExprKind::Binary { op, left, right } => {
+            vec![(left, Slot::Left(op.node)), (right, Slot::Right(op.node))]
+        }
+        _ => children(expr)
+            .into_iter()
+            .map(|c| (c, Slot::Free))
+            .collect(),
+    }
+}
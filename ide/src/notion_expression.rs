@@ -0,0 +1,114 @@
+//! Conversion between formula source and the Notion API `formula.expression` payload form.
+//!
+//! The API returns property references as placeholders keyed by property id
+//! (`{{notion:block_property:<id>:…}}`, trailing segments ignored) instead of `prop("Name")`.
+//! Ids and names come from the database schema (`NotionPropertySchema::id`). Coordinates in
+//! errors are UTF-8 byte offsets.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use analyzer::LitKind;
+use analyzer::ast::{Expr, ExprKind};
+use analyzer::semantic::NotionPropertySchema;
+
+use crate::hover::children;
+use crate::rename::string_literal;
+
+const PLACEHOLDER_OPEN: &str = "{{notion:block_property:";
+const PLACEHOLDER_CLOSE: &str = "}}";
+
+/// Errors converting to or from the payload form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotionExpressionError {
+    /// A placeholder names a property id missing from the schema.
+    UnknownPropertyId(String),
+    /// A `prop("...")` names a property missing from the schema (or without an id).
+    UnknownProperty(String),
+    /// The placeholder starting at `offset` has no closing `}}`.
+    UnterminatedReference { offset: u32 },
+}
+
+impl fmt::Display for NotionExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotionExpressionError::UnknownPropertyId(id) => {
+                write!(f, "unknown property id `{id}`")
+            }
+            NotionExpressionError::UnknownProperty(name) => {
+                write!(f, "unknown property `{name}`")
+            }
+            NotionExpressionError::UnterminatedReference { offset } => {
+                write!(f, "unterminated property reference at offset {offset}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NotionExpressionError {}
+
+/// Replaces every property placeholder in `expression` with `prop("Name")`.
+pub(crate) fn from_notion_expression(
+    expression: &str,
+    schema: &BTreeMap<String, NotionPropertySchema>,
+) -> Result<String, NotionExpressionError> {
+    let mut out = String::with_capacity(expression.len());
+    let mut rest = expression;
+    while let Some(start) = rest.find(PLACEHOLDER_OPEN) {
+        out.push_str(&rest[..start]);
+        let body = &rest[start + PLACEHOLDER_OPEN.len()..];
+        let Some(close) = body.find(PLACEHOLDER_CLOSE) else {
+            let offset = expression.len() - rest.len() + start;
+            return Err(NotionExpressionError::UnterminatedReference {
+                offset: offset as u32,
+            });
+        };
+        let id = body[..close].split(':').next().unwrap_or_default();
+        let name = schema
+            .iter()
+            .find(|(_, property)| property.id.as_deref() == Some(id))
+            .map(|(name, _)| name)
+            .ok_or_else(|| NotionExpressionError::UnknownPropertyId(id.to_string()))?;
+        out.push_str("prop(");
+        out.push_str(&string_literal(name));
+        out.push(')');
+        rest = &body[close + PLACEHOLDER_CLOSE.len()..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Replaces every well-formed `prop("Name")` call in `source` with its id placeholder.
+pub(crate) fn to_notion_expression(
+    source: &str,
+    root: &Expr,
+    schema: &BTreeMap<String, NotionPropertySchema>,
+) -> Result<String, NotionExpressionError> {
+    let mut out = String::with_capacity(source.len());
+    let mut pos = 0;
+    let mut stack = vec![root];
+    while let Some(expr) = stack.pop() {
+        if let ExprKind::Call { callee, args } = &expr.kind
+            && callee.text == "prop"
+            && let [arg] = args.as_slice()
+            && let ExprKind::Lit(lit) = &arg.kind
+            && lit.kind == LitKind::String
+        {
+            let name = lit.symbol.text.as_str();
+            let id = schema
+                .get(name)
+                .and_then(|property| property.id.as_deref())
+                .ok_or_else(|| NotionExpressionError::UnknownProperty(name.to_string()))?;
+            out.push_str(&source[pos..expr.span.start as usize]);
+            out.push_str(PLACEHOLDER_OPEN);
+            out.push_str(id);
+            out.push_str(PLACEHOLDER_CLOSE);
+            pos = expr.span.end as usize;
+            continue;
+        }
+        // Reversed so children pop in source order.
+        stack.extend(children(expr).into_iter().rev());
+    }
+    out.push_str(&source[pos..]);
+    Ok(out)
+}
@@ -0,0 +1,102 @@
+//! Normalizing text pasted from documents: smart quotes, non-breaking spaces, dashes for minus,
+//! and full-width punctuation become their ASCII equivalents ([`analyzer::ascii_lookalike`]).
+//!
+//! String literals and comments are left alone, except that a string written with smart quotes
+//! gets ASCII quotes. Coordinates are UTF-8 byte offsets.
+
+use analyzer::{CodeAction, Span, TextEdit, ascii_lookalike};
+
+/// What the scanner is inside of.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Code,
+    /// A `"` string; `escaped` after a backslash.
+    String {
+        escaped: bool,
+    },
+    /// A string opened by a smart quote, closed by a quote of the same family (`“…”`, `‘…’`) or
+    /// an ASCII `"`.
+    SmartString {
+        single: bool,
+    },
+    LineComment,
+    BlockComment,
+}
+
+/// Edits replacing every lookalike outside literals and comments, in source order.
+pub(crate) fn normalize_pasted(source: &str) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+    let mut state = State::Code;
+    let mut chars = source.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        let span = Span {
+            start: start as u32,
+            end: (start + ch.len_utf8()) as u32,
+        };
+        let replace = |new_text: &str| TextEdit {
+            range: span,
+            new_text: new_text.to_string(),
+        };
+        state = match state {
+            State::Code => match ch {
+                '"' => State::String { escaped: false },
+                '/' if chars.next_if(|&(_, next)| next == '/').is_some() => State::LineComment,
+                '/' if chars.next_if(|&(_, next)| next == '*').is_some() => State::BlockComment,
+                _ => match ascii_lookalike(ch) {
+                    Some(lookalike) => {
+                        edits.push(replace(lookalike.ascii));
+                        match smart_quote(ch) {
+                            Some(single) => State::SmartString { single },
+                            None => State::Code,
+                        }
+                    }
+                    None => State::Code,
+                },
+            },
+            State::String { escaped: false } => match ch {
+                '\\' => State::String { escaped: true },
+                '"' => State::Code,
+                _ => state,
+            },
+            State::String { escaped: true } => State::String { escaped: false },
+            State::SmartString { single } => match (ch, smart_quote(ch)) {
+                ('"', _) => State::Code,
+                (_, Some(quote)) if quote == single => {
+                    edits.push(replace("\""));
+                    State::Code
+                }
+                _ => state,
+            },
+            State::LineComment if ch == '\n' => State::Code,
+            State::BlockComment if ch == '*' && chars.next_if(|&(_, c)| c == '/').is_some() => {
+                State::Code
+            }
+            State::LineComment | State::BlockComment => state,
+        };
+    }
+    edits
+}
+
+/// `Some(single)` for smart quotes: `true` for the single-quote family (`‘’‚‛′`).
+fn smart_quote(ch: char) -> Option<bool> {
+    match ch {
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' | '\u{00AB}'
+        | '\u{00BB}' | '\u{FF02}' => Some(false),
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => Some(true),
+        _ => None,
+    }
+}
+
+/// "Replace all pasted characters" over the whole source, when `range` touches one of several.
+///
+/// A single lookalike already has its diagnostic's quick fix.
+pub(crate) fn normalize_pasted_action(source: &str, range: Span) -> Option<CodeAction> {
+    let edits = normalize_pasted(source);
+    let touched = edits
+        .iter()
+        .any(|edit| edit.range.start <= range.end && range.start <= edit.range.end);
+    (edits.len() > 1 && touched).then(|| CodeAction {
+        title: format!("Replace all {} pasted characters with ASCII", edits.len()),
+        edits,
+    })
+}
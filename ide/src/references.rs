@@ -0,0 +1,43 @@
+//! Property references: which properties a formula reads, for host dependency graphs.
+//!
+//! AST-based, so names are unescaped and only well-formed `prop("...")` calls count. Coordinates
+//! are UTF-8 byte offsets.
+
+use analyzer::ast::{Expr, ExprKind};
+use analyzer::{LitKind, Span};
+
+use crate::hover::children;
+
+/// A property read by a formula, with every `prop("...")` literal naming it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyReference {
+    /// Property name with escapes resolved.
+    pub name: String,
+    /// Spans of the string literals (quotes included), in source order.
+    pub spans: Vec<Span>,
+}
+
+/// Distinct properties referenced under `root`, in order of first reference.
+pub(crate) fn referenced_properties(root: &Expr) -> Vec<PropertyReference> {
+    let mut out: Vec<PropertyReference> = Vec::new();
+    let mut stack = vec![root];
+    while let Some(expr) = stack.pop() {
+        if let ExprKind::Call { callee, args } = &expr.kind
+            && callee.text == "prop"
+            && let [arg] = args.as_slice()
+            && let ExprKind::Lit(lit) = &arg.kind
+            && lit.kind == LitKind::String
+        {
+            match out.iter_mut().find(|r| r.name == lit.symbol.text) {
+                Some(reference) => reference.spans.push(arg.span),
+                None => out.push(PropertyReference {
+                    name: lit.symbol.text.to_string(),
+                    spans: vec![arg.span],
+                }),
+            }
+        }
+        // Reversed so children pop in source order.
+        stack.extend(children(expr).into_iter().rev());
+    }
+    out
+}
@@ -0,0 +1,24 @@
+//! Rename for property references.
+//!
+//! Properties are the only user-named symbols: functions are builtins and the language has no
+//! local variables. Coordinates are UTF-8 byte offsets.
+
+pub(crate) use analyzer::string_literal;
+use analyzer::{TextEdit, Token};
+
+use crate::highlight::property_references;
+
+/// Edits replacing every `prop("...")` literal naming the property at `cursor` with `new_name`.
+///
+/// `None` when the cursor is not on a property reference.
+pub(crate) fn rename(tokens: &[Token], cursor: u32, new_name: &str) -> Option<Vec<TextEdit>> {
+    let new_text = string_literal(new_name);
+    let edits = property_references(tokens, cursor)?
+        .into_iter()
+        .map(|range| TextEdit {
+            range,
+            new_text: new_text.clone(),
+        })
+        .collect();
+    Some(edits)
+}
@@ -0,0 +1,190 @@
+//! Semantic token classification for editor highlighting.
+//!
+//! Classification is token-based (works on incomplete input) and uses the `Context` to tell
+//! builtin functions from unknown callees. [`encode_lsp`] produces the LSP delta encoding in the
+//! host's position encoding, so hosts can forward it unchanged.
+
+use analyzer::semantic;
+use analyzer::{LitKind, Span, Token, TokenKind};
+
+use crate::utf16::PositionEncoding;
+
+/// Semantic token types. The discriminant is the index into [`SEMANTIC_TOKEN_TYPES`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticTokenKind {
+    Function,
+    Method,
+    Property,
+    Variable,
+    String,
+    Number,
+    Keyword,
+    Operator,
+    Comment,
+}
+
+/// LSP legend for [`SemanticTokenKind`], in discriminant order.
+pub const SEMANTIC_TOKEN_TYPES: &[&str] = &[
+    "function", "method", "property", "variable", "string", "number", "keyword", "operator",
+    "comment",
+];
+
+/// LSP legend for modifier bits (bit `i` is `SEMANTIC_TOKEN_MODIFIERS[i]`).
+pub const SEMANTIC_TOKEN_MODIFIERS: &[&str] = &["defaultLibrary"];
+
+/// Modifier bit for functions and methods found in the `Context`.
+pub const MODIFIER_DEFAULT_LIBRARY: u32 = 1;
+
+/// A classified token in UTF-8 byte coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub span: Span,
+    pub kind: SemanticTokenKind,
+    /// Bit set over [`SEMANTIC_TOKEN_MODIFIERS`].
+    pub modifiers: u32,
+}
+
+/// Classifies `tokens` (from lexing a source) in source order.
+///
+/// Punctuation (parens, commas, dots, ...) is not emitted.
+pub(crate) fn semantic_tokens(tokens: &[Token], ctx: &semantic::Context) -> Vec<SemanticToken> {
+    let tokens: Vec<&Token> = tokens
+        .iter()
+        .filter(|token| !matches!(token.kind, TokenKind::Newline | TokenKind::Eof))
+        .collect();
+    // Comments between tokens do not change how neighbors are classified.
+    let prev_code = |idx: usize| (0..idx).rev().find(|&i| !tokens[i].kind.is_comment());
+    let next_code_kind = |idx: usize| {
+        tokens[idx + 1..]
+            .iter()
+            .find(|token| !token.kind.is_comment())
+            .map(|token| &token.kind)
+    };
+    // `prop` is handled by the analyzer directly rather than listed in the `Context`.
+    let is_builtin = |name: &str| name == "prop" || ctx.function(name).is_some();
+
+    let mut out = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        let prev_idx = prev_code(idx);
+        let prev = prev_idx.map(|i| &tokens[i].kind);
+        let next = next_code_kind(idx);
+        let (kind, modifiers) = match &token.kind {
+            TokenKind::DocComment(..) => (SemanticTokenKind::Comment, 0),
+            TokenKind::Ident(sym) => {
+                let is_call = matches!(next, Some(TokenKind::OpenParen));
+                let library = if is_call && is_builtin(&sym.text) {
+                    MODIFIER_DEFAULT_LIBRARY
+                } else {
+                    0
+                };
+                match (prev, is_call) {
+                    (Some(TokenKind::Dot), _) => (SemanticTokenKind::Method, library),
+                    (_, true) => (SemanticTokenKind::Function, library),
+                    _ => (SemanticTokenKind::Variable, 0),
+                }
+            }
+            TokenKind::Literal(lit) => match lit.kind {
+                LitKind::Number => (SemanticTokenKind::Number, 0),
+                LitKind::Bool => (SemanticTokenKind::Keyword, 0),
+                LitKind::String => {
+                    let is_prop_arg = matches!(prev, Some(TokenKind::OpenParen))
+                        && prev_idx.and_then(prev_code).is_some_and(|i| {
+                            matches!(&tokens[i].kind, TokenKind::Ident(sym) if sym.text == "prop")
+                        })
+                        && matches!(next, Some(TokenKind::CloseParen));
+                    if is_prop_arg {
+                        (SemanticTokenKind::Property, 0)
+                    } else {
+                        (SemanticTokenKind::String, 0)
+                    }
+                }
+            },
+            TokenKind::Not => (SemanticTokenKind::Keyword, 0),
+            kind if is_operator(kind) => (SemanticTokenKind::Operator, 0),
+            _ => continue,
+        };
+        out.push(SemanticToken {
+            span: token.span,
+            kind,
+            modifiers,
+        });
+    }
+    out
+}
+
+fn is_operator(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Lt
+            | TokenKind::Le
+            | TokenKind::EqEq
+            | TokenKind::Ne
+            | TokenKind::Ge
+            | TokenKind::Gt
+            | TokenKind::AndAnd
+            | TokenKind::OrOr
+            | TokenKind::Bang
+            | TokenKind::Plus
+            | TokenKind::Minus
+            | TokenKind::Star
+            | TokenKind::Slash
+            | TokenKind::Percent
+            | TokenKind::Caret
+            | TokenKind::Question
+            | TokenKind::Colon
+    )
+}
+
+/// Encodes tokens as LSP `SemanticTokens.data`: five `u32`s per token
+/// (`deltaLine`, `deltaStartChar`, `length`, `tokenType`, `tokenModifiers`).
+///
+/// Lines split on `\n`; columns and lengths are in `encoding` units. Tokens spanning several
+/// lines (block comments) are split into one entry per line.
+pub fn encode_lsp(source: &str, tokens: &[SemanticToken], encoding: PositionEncoding) -> Vec<u32> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect();
+
+    let mut data = Vec::with_capacity(tokens.len() * 5);
+    let (mut prev_line, mut prev_col) = (0u32, 0u32);
+    for token in tokens {
+        let (start, end) = (token.span.start as usize, token.span.end as usize);
+        if start >= end || end > source.len() {
+            continue;
+        }
+        let first_line = line_starts.partition_point(|&ls| ls <= start) - 1;
+        for line in first_line..line_starts.len() {
+            let line_start = line_starts[line];
+            if line_start >= end {
+                break;
+            }
+            let line_end = line_starts
+                .get(line + 1)
+                .map_or(source.len(), |&next| next - 1);
+            let piece_start = start.max(line_start);
+            let piece_end = end.min(line_end);
+            if piece_start >= piece_end {
+                continue;
+            }
+
+            let line_text = &source[line_start..line_end];
+            let col = encoding.from_byte(line_text, (piece_start - line_start) as u32);
+            let col_end = encoding.from_byte(line_text, (piece_end - line_start) as u32);
+            let line = line as u32;
+            let delta_col = if line == prev_line {
+                col - prev_col
+            } else {
+                col
+            };
+            data.extend([
+                line - prev_line,
+                delta_col,
+                col_end - col,
+                token.kind as u32,
+                token.modifiers,
+            ]);
+            (prev_line, prev_col) = (line, col);
+        }
+    }
+    data
+}
@@ -1,5 +1,5 @@
 //! Signature help for calls under the cursor.
-//! Uses UTF-8 byte offsets (via tokens/spans) and best-effort type inference.
+//! Uses UTF-8 byte offsets (via tokens/spans) and the caller's parse and inferred types.
 //!
 //! Sub-modules:
 //! - [`generics`]: Generic substitution / unification.
@@ -14,19 +14,23 @@ use crate::context::{CallContext, prev_non_trivia_before};
 use crate::display::build_signature_segments;
 use analyzer::ast::{Expr, ExprKind};
 use analyzer::semantic;
-use analyzer::{Token, TokenKind};
+use analyzer::{Token, TokenKind, TypeMap, non_trivia};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use generics::instantiate_sig;
 use param_shape::active_parameter_for_call;
 use render::render_signature;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SignatureItem {
     pub segments: Vec<crate::display::DisplaySegment>,
 }
 
 /// Signature display for a call at the cursor.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SignatureHelp {
     pub signatures: Vec<SignatureItem>,
     pub active_signature: usize,
@@ -35,15 +39,17 @@ pub struct SignatureHelp {
 
 /// Computes signature help when the cursor is inside a call argument list.
 ///
+/// `tokens`/`root` must come from parsing `source`, and `types` from inferring `root`.
 /// Returns `None` if the cursor is before the `(`, or if the callee is unknown.
 pub(crate) fn compute_signature_help_if_in_call(
     source: &str,
     tokens: &[Token],
+    root: &Expr,
+    types: &TypeMap,
     cursor: u32,
     ctx: &semantic::Context,
-    call_ctx: Option<&CallContext>,
+    call_ctx: &CallContext,
 ) -> Option<SignatureHelp> {
-    let call_ctx = call_ctx?;
     let lparen_token = tokens.get(call_ctx.lparen_idx)?;
 
     // Only show signature help if cursor is after the '(' (inside the call)
@@ -51,10 +57,7 @@ pub(crate) fn compute_signature_help_if_in_call(
         return None;
     }
 
-    let func = ctx
-        .functions
-        .iter()
-        .find(|func| func.name == call_ctx.callee)?;
+    let func = ctx.function(&call_ctx.callee)?;
 
     let is_postfix_call = || {
         let (callee_idx, callee_token) = prev_non_trivia_before(tokens, call_ctx.lparen_idx)?;
@@ -78,7 +81,7 @@ pub(crate) fn compute_signature_help_if_in_call(
         && semantic::postfix_capable_builtin_names().contains(func.name.as_str())
         && semantic::is_postfix_capable(func);
 
-    let arg_tys = infer_call_arg_tys_best_effort(source, tokens, ctx, call_ctx, is_method_style);
+    let arg_tys = call_arg_tys(source, tokens, root, types, call_ctx, is_method_style);
     let (inst_param_tys, inst_ret) = instantiate_sig(func, arg_tys.as_slice());
 
     // `receiver.fn(arg1, ...)` is treated as `fn(receiver, arg1, ...)` internally.
@@ -108,7 +111,7 @@ pub(crate) fn compute_signature_help_if_in_call(
 // Call-site argument type inference (stays in mod.rs – uses AST + tokens)
 // ---------------------------------------------------------------------------
 
-fn find_call_expr_by_lparen<'a>(
+pub(crate) fn find_call_expr_by_lparen<'a>(
     root: &'a Expr,
     callee: &str,
     lparen_start: u32,
@@ -176,23 +179,6 @@ fn find_call_expr_by_lparen<'a>(
     best
 }
 
-/// Infers the type of a single argument expression fragment.
-fn infer_one_arg(expr_source: &str, ctx: &semantic::Context) -> Option<semantic::Ty> {
-    let trimmed = expr_source.trim();
-    if trimmed.is_empty() {
-        return None;
-    }
-
-    match trimmed {
-        "true" | "false" => return Some(semantic::Ty::Boolean),
-        _ => {}
-    }
-
-    let parsed = analyzer::analyze_syntax(trimmed);
-    let mut map = analyzer::TypeMap::default();
-    Some(analyzer::infer_expr_with_map(&parsed.expr, ctx, &mut map))
-}
-
 /// Splits the token stream after `lparen_idx` into per-argument byte spans,
 /// respecting nested parentheses and brackets.
 fn arg_spans(tokens: &[Token], lparen_idx: usize, source_len: u32) -> Vec<analyzer::Span> {
@@ -205,11 +191,7 @@ fn arg_spans(tokens: &[Token], lparen_idx: usize, source_len: u32) -> Vec<analyz
     let mut bracket_depth = 0i32;
     let mut start = lparen.span.end;
 
-    for token in tokens.iter().skip(lparen_idx + 1) {
-        if token.is_trivia() || matches!(token.kind, TokenKind::Eof) {
-            continue;
-        }
-
+    for (_, token) in non_trivia(&tokens[lparen_idx + 1..]) {
         match token.kind {
             TokenKind::OpenParen => paren_depth += 1,
             TokenKind::OpenBracket => bracket_depth += 1,
@@ -225,11 +207,7 @@ fn arg_spans(tokens: &[Token], lparen_idx: usize, source_len: u32) -> Vec<analyz
                     paren_depth -= 1;
                 }
             }
-            TokenKind::CloseBracket => {
-                if bracket_depth > 0 {
-                    bracket_depth -= 1;
-                }
-            }
+            TokenKind::CloseBracket if bracket_depth > 0 => bracket_depth -= 1,
             TokenKind::Comma if paren_depth == 0 && bracket_depth == 0 => {
                 spans.push(analyzer::Span {
                     start,
@@ -248,10 +226,16 @@ fn arg_spans(tokens: &[Token], lparen_idx: usize, source_len: u32) -> Vec<analyz
     spans
 }
 
-fn infer_call_arg_tys_best_effort(
+/// Types of the call's arguments, one per comma-separated fragment of the source.
+///
+/// Types come from the shared [`TypeMap`]: a fragment takes the type of the argument node the
+/// parser built inside it. Empty fragments (e.g. after a trailing comma) are `None`; fragments
+/// that parse recovery dropped are `Unknown`.
+fn call_arg_tys(
     source: &str,
     tokens: &[Token],
-    ctx: &semantic::Context,
+    root: &Expr,
+    types: &TypeMap,
     call_ctx: &CallContext,
     include_receiver_as_arg: bool,
 ) -> Vec<Option<semantic::Ty>> {
@@ -261,33 +245,32 @@ fn infer_call_arg_tys_best_effort(
 
     let source_len = u32::try_from(source.len()).unwrap_or(u32::MAX);
     let spans = arg_spans(tokens, call_ctx.lparen_idx, source_len);
+    let call_expr = find_call_expr_by_lparen(root, &call_ctx.callee, lparen_token.span.start);
+    let ty_of = |expr: &Expr| {
+        let ty = types.get(expr.id).cloned().unwrap_or(semantic::Ty::Unknown);
+        if matches!(ty, semantic::Ty::Unknown)
+            && matches!(
+                &expr.kind,
+                ExprKind::Ident(sym) if sym.text == "true" || sym.text == "false"
+            )
+        {
+            return semantic::Ty::Boolean;
+        }
+        ty
+    };
 
     let mut arg_tys: Vec<Option<semantic::Ty>> = Vec::new();
-
-    // If this is a member call, try to include the receiver type as the leading argument.
-    if include_receiver_as_arg {
-        let parsed = analyzer::analyze_syntax(source);
-        if let Some(call_expr) =
-            find_call_expr_by_lparen(&parsed.expr, &call_ctx.callee, lparen_token.span.start)
-            && let ExprKind::MemberCall { receiver, .. } = &call_expr.kind
-        {
-            let mut map = analyzer::TypeMap::default();
-            let _ = analyzer::infer_expr_with_map(&parsed.expr, ctx, &mut map);
-            let mut ty = map
-                .get(receiver.id)
-                .cloned()
-                .unwrap_or(semantic::Ty::Unknown);
-            if matches!(ty, semantic::Ty::Unknown)
-                && matches!(
-                    &receiver.kind,
-                    ExprKind::Ident(sym) if sym.text == "true" || sym.text == "false"
-                )
-            {
-                ty = semantic::Ty::Boolean;
+    let args: &[Expr] = match call_expr.map(|call| &call.kind) {
+        Some(ExprKind::Call { args, .. }) => args,
+        Some(ExprKind::MemberCall { receiver, args, .. }) => {
+            // If this is a member call, include the receiver type as the leading argument.
+            if include_receiver_as_arg {
+                arg_tys.push(Some(ty_of(receiver)));
             }
-            arg_tys.push(Some(ty));
+            args
         }
-    }
+        _ => &[],
+    };
 
     for span in spans {
         let start = span.start as usize;
@@ -295,8 +278,14 @@ fn infer_call_arg_tys_best_effort(
         if start > source.len() || end > source.len() || start > end {
             continue;
         }
-        let frag = &source[start..end];
-        arg_tys.push(infer_one_arg(frag, ctx));
+        if source[start..end].trim().is_empty() {
+            arg_tys.push(None);
+            continue;
+        }
+        let arg = args
+            .iter()
+            .find(|arg| span.start <= arg.span.start && arg.span.end <= span.end);
+        arg_tys.push(Some(arg.map_or(semantic::Ty::Unknown, ty_of)));
     }
 
     arg_tys
@@ -2,7 +2,9 @@
 //!
 //! Functions with variadic (`repeat`) parameters need special logic to
 //! determine how many repeat groups are present and which tail parameters
-//! are in use, given the total number of arguments at the call site.
+//! are in use, given the total number of arguments at the call site. Complete calls split as
+//! in the analyzer ([`ParamShape::tail_used`](semantic::ParamShape::tail_used)); only the
+//! rounding-up of incomplete calls lives here.
 
 use analyzer::semantic;
 
@@ -12,16 +14,6 @@ pub(super) struct CompletedRepeatShape {
     pub(super) repeat_groups: usize,
 }
 
-fn required_tail_prefix_len(tail: &[semantic::ParamSig]) -> usize {
-    let mut required = 0usize;
-    for (idx, p) in tail.iter().enumerate() {
-        if !p.optional {
-            required = idx + 1;
-        }
-    }
-    required
-}
-
 fn ceil_to_multiple(n: usize, m: usize) -> usize {
     if m == 0 {
         return n;
@@ -33,37 +25,6 @@ fn ceil_to_multiple(n: usize, m: usize) -> usize {
     if rem == 0 { n } else { n + (m - rem) }
 }
 
-fn resolve_repeat_tail_used_with_min_groups(
-    params: &semantic::ParamShape,
-    total: usize,
-    repeat_min_groups: usize,
-) -> Option<usize> {
-    if params.repeat.is_empty() {
-        return Some(params.tail.len());
-    }
-
-    let head_len = params.head.len();
-    if total < head_len {
-        return None;
-    }
-
-    let repeat_len = params.repeat.len();
-    let tail_min = required_tail_prefix_len(&params.tail);
-    let min_middle = repeat_len.saturating_mul(repeat_min_groups);
-
-    for tail_used in (tail_min..=params.tail.len()).rev() {
-        if total < head_len + tail_used {
-            continue;
-        }
-        let middle = total - head_len - tail_used;
-        if middle >= min_middle && middle.is_multiple_of(repeat_len) {
-            return Some(tail_used);
-        }
-    }
-
-    None
-}
-
 pub(super) fn complete_repeat_shape(
     params: &semantic::ParamShape,
     total: usize,
@@ -79,9 +40,7 @@ pub(super) fn complete_repeat_shape(
         return None;
     }
 
-    if let Some(tail_used) =
-        resolve_repeat_tail_used_with_min_groups(params, total, repeat_min_groups)
-    {
+    if let Some(tail_used) = params.tail_used(total) {
         let tail_start = total.saturating_sub(tail_used);
         let middle = total.saturating_sub(head_len + tail_used);
         let repeat_groups = middle / repeat_len;
@@ -91,7 +50,7 @@ pub(super) fn complete_repeat_shape(
         });
     }
 
-    let tail_min = required_tail_prefix_len(&params.tail);
+    let tail_min = params.required_tail_len();
     let min_middle = repeat_len.saturating_mul(repeat_min_groups);
 
     let mut best: Option<(usize /* total */, usize /* tail_used */)> = None;
@@ -17,14 +17,6 @@ fn ty_contains_generic(ty: &semantic::Ty) -> bool {
     }
 }
 
-fn format_ty_with_optional(ty: &semantic::Ty, optional: bool) -> String {
-    let mut out = ty.to_string();
-    if optional {
-        out.push('?');
-    }
-    out
-}
-
 fn choose_display_ty<'a>(
     actual: Option<&'a semantic::Ty>,
     declared_template: &'a semantic::Ty,
@@ -132,7 +124,7 @@ pub(super) fn render_signature(
                 &mut next_param_index,
                 is_method_style,
                 p.name.clone(),
-                format_ty_with_optional(ty, p.optional),
+                ty.display().optional(p.optional).to_string(),
             );
         }
         return RenderedSignature { receiver, slots };
@@ -146,7 +138,7 @@ pub(super) fn render_signature(
             &mut next_param_index,
             is_method_style,
             p.name.clone(),
-            format_ty_with_optional(ty, p.optional),
+            ty.display().optional(p.optional).to_string(),
         );
     }
 
@@ -174,7 +166,7 @@ pub(super) fn render_signature(
                 &mut next_param_index,
                 is_method_style,
                 name,
-                format_ty_with_optional(ty, p.optional),
+                ty.display().optional(p.optional).to_string(),
             );
         }
     }
@@ -191,7 +183,7 @@ pub(super) fn render_signature(
             &mut next_param_index,
             is_method_style,
             p.name.clone(),
-            format_ty_with_optional(ty, p.optional),
+            ty.display().optional(p.optional).to_string(),
         );
     }
 
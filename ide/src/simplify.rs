@@ -0,0 +1,466 @@
+//! Simplification suggestions: rewrites that keep a formula's value but shorten or flatten it.
+//!
+//! - constant folding (`1 + 2` → `3`, `"a" + "b"` → `"ab"`), when the result is not longer;
+//! - `!!x` / `not not x` → `x` for boolean `x`;
+//! - `if(c, true, false)` → `c` and `if(c, false, true)` → `!c` (also as `? :`);
+//! - De Morgan: `!a && !b` → `!(a || b)` and `!a || !b` → `!(a && b)`, also with `not` and the
+//!   legacy `and(a, b)` / `or(a, b)`;
+//! - `if`/`ifs` nested in the last argument of another → one flat `ifs`.
+//!
+//! Replacements are spliced from source slices and parenthesized where precedence requires it; a
+//! rewrite inside parentheses that are no longer needed replaces them too. Rewrites that would
+//! drop a comment are not suggested. Coordinates are UTF-8 byte offsets.
+
+use analyzer::ast::{BinOpKind, Expr, ExprKind, NotKind, UnOp};
+use analyzer::semantic::{self, Ty};
+use analyzer::{CodeAction, LitKind, Span, TextEdit, Token, TypeMap};
+
+use crate::migrate::{Fragment, Prec, Slot, fits, prec_of, slots};
+use crate::rename::string_literal;
+
+/// Rounds of [`simplify_all`] before giving up on reaching a fixed point.
+const MAX_ROUNDS: usize = 16;
+
+pub(crate) fn simplify(source: &str, ctx: &semantic::Context) -> Vec<CodeAction> {
+    let syntax = analyzer::analyze_syntax(source);
+    if !syntax.diagnostics.is_empty() {
+        return Vec::new();
+    }
+    let mut type_map = TypeMap::default();
+    semantic::analyze_expr_with_map(&syntax.expr, ctx, &mut type_map);
+    let mut simplifier = Simplifier {
+        source,
+        tokens: &syntax.tokens,
+        type_map: &type_map,
+        actions: Vec::new(),
+        group: None,
+    };
+    simplifier.visit(&syntax.expr, Slot::Free, false, None);
+    simplifier.actions
+}
+
+/// Applies non-overlapping suggestions (outermost first) and re-analyzes until none are left.
+pub(crate) fn simplify_all(source: &str, ctx: &semantic::Context) -> Vec<TextEdit> {
+    let mut current = source.to_string();
+    for _ in 0..MAX_ROUNDS {
+        let mut edits: Vec<TextEdit> = Vec::new();
+        for action in simplify(&current, ctx) {
+            let [edit] = action.edits.as_slice() else {
+                continue;
+            };
+            if edits
+                .last()
+                .is_none_or(|last| last.range.end <= edit.range.start)
+            {
+                edits.push(edit.clone());
+            }
+        }
+        if edits.is_empty() {
+            break;
+        }
+        let mut next = String::with_capacity(current.len());
+        let mut pos = 0;
+        for edit in &edits {
+            next.push_str(&current[pos..edit.range.start as usize]);
+            next.push_str(&edit.new_text);
+            pos = edit.range.end as usize;
+        }
+        next.push_str(&current[pos..]);
+        current = next;
+    }
+    crate::diff::diff(source, &current)
+}
+
+/// The value of an operator expression over literals.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Const {
+    Number(f64),
+    Bool(bool),
+    Text(String),
+}
+
+impl Const {
+    fn fragment(&self) -> Option<Fragment> {
+        let (text, prec) = match self {
+            Const::Number(n) if !n.is_finite() => return None,
+            Const::Number(n) => (format_number(*n), prefix_if(*n < 0.0)),
+            Const::Bool(b) => (b.to_string(), Prec::Atom),
+            Const::Text(text) => (string_literal(text), Prec::Atom),
+        };
+        Some(Fragment { text, prec })
+    }
+}
+
+fn prefix_if(negative: bool) -> Prec {
+    if negative { Prec::Prefix } else { Prec::Atom }
+}
+
+pub(crate) fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        n.to_string()
+    }
+}
+
+struct Simplifier<'a> {
+    source: &'a str,
+    tokens: &'a [Token],
+    type_map: &'a TypeMap,
+    actions: Vec<CodeAction>,
+    /// The parentheses around the expression being visited and their slot, when they hold no
+    /// comment of their own: a rewrite that no longer needs them replaces them as well.
+    group: Option<(Span, Slot)>,
+}
+
+impl Simplifier<'_> {
+    /// `in_else_chain`: `expr` is the last argument of an `if`/`ifs` that merges it.
+    /// `group`: the outermost of the parentheses directly around `expr`, with their slot.
+    fn visit(&mut self, expr: &Expr, slot: Slot, in_else_chain: bool, group: Option<(Span, Slot)>) {
+        self.group = group;
+        if self.fold(expr, slot) {
+            return;
+        }
+        self.double_negation(expr, slot);
+        self.boolean_conditional(expr, slot);
+        self.de_morgan(expr, slot);
+        let merged = !in_else_chain && self.merge_conditionals(expr);
+
+        let chain_last = match expr.kind {
+            ExprKind::Group { .. } => in_else_chain,
+            _ => (merged || in_else_chain) && nested_conditional(expr),
+        };
+        let child_group = match &expr.kind {
+            ExprKind::Group { inner } if !self.drops_comment(expr, &[inner]) => {
+                Some(group.unwrap_or((expr.span, slot)))
+            }
+            _ => None,
+        };
+        let children = slots(expr);
+        let last = children.len().saturating_sub(1);
+        for (i, (child, child_slot)) in children.into_iter().enumerate() {
+            self.visit(child, child_slot, chain_last && i == last, child_group);
+        }
+    }
+
+    fn fold(&mut self, expr: &Expr, slot: Slot) -> bool {
+        if !has_operator(expr) {
+            return false;
+        }
+        let Some(fragment) = constant(expr).as_ref().and_then(Const::fragment) else {
+            return false;
+        };
+        if fragment.text.len() > self.text(expr).len() || self.drops_comment(expr, &[]) {
+            return false;
+        }
+        let title = format!("Fold to `{}`", fragment.text);
+        self.suggest(expr, slot, title, fragment);
+        true
+    }
+
+    fn double_negation(&mut self, expr: &Expr, slot: Slot) {
+        let Some(inner) = negated(expr) else {
+            return;
+        };
+        let Some(operand) = negated(ungroup(inner)) else {
+            return;
+        };
+        if self.ty(operand) != Ty::Boolean || self.drops_comment(expr, &[operand]) {
+            return;
+        }
+        // Parentheses kept only for the negation go with it.
+        let bare = ungroup(operand);
+        let operand = if self.drops_comment(operand, &[bare]) {
+            operand
+        } else {
+            bare
+        };
+        let fragment = self.fragment(operand);
+        self.suggest(expr, slot, "Remove double negation".into(), fragment);
+    }
+
+    fn boolean_conditional(&mut self, expr: &Expr, slot: Slot) {
+        let Some((cond, then, otherwise)) = single_conditional(expr) else {
+            return;
+        };
+        let (Some(then), Some(otherwise)) = (bool_literal(then), bool_literal(otherwise)) else {
+            return;
+        };
+        if then == otherwise || self.drops_comment(expr, &[cond]) {
+            return;
+        }
+        let (title, fragment) = if then {
+            ("Replace with the condition", self.fragment(cond))
+        } else {
+            let operand = self.fragment(cond).in_slot(Slot::UnaryOperand);
+            let fragment = Fragment {
+                text: format!("!{operand}"),
+                prec: Prec::Prefix,
+            };
+            ("Replace with the negated condition", fragment)
+        };
+        self.suggest(expr, slot, title.into(), fragment);
+    }
+
+    fn de_morgan(&mut self, expr: &Expr, slot: Slot) {
+        let Some((op, is_call, left, right)) = junction(expr) else {
+            return;
+        };
+        let flipped = match op {
+            BinOpKind::AndAnd => BinOpKind::OrOr,
+            BinOpKind::OrOr => BinOpKind::AndAnd,
+            _ => return,
+        };
+        let (
+            ExprKind::Unary {
+                op: UnOp::Not(not),
+                expr: a,
+            },
+            ExprKind::Unary {
+                op: UnOp::Not(_),
+                expr: b,
+            },
+        ) = (&left.kind, &right.kind)
+        else {
+            return;
+        };
+        // Arguments need no parentheses, so `not(x)` gives `x`.
+        let (a, b) = if is_call {
+            (ungroup(a), ungroup(b))
+        } else {
+            (&**a, &**b)
+        };
+        if self.drops_comment(expr, &[a, b]) {
+            return;
+        }
+        let not = match not {
+            NotKind::Bang => "!",
+            NotKind::Keyword => "not ",
+        };
+        let text = if is_call {
+            let name = if flipped == BinOpKind::AndAnd {
+                "and"
+            } else {
+                "or"
+            };
+            format!("{not}{name}({}, {})", self.text(a), self.text(b))
+        } else {
+            let a = self.fragment(a).in_slot(Slot::Left(flipped));
+            let b = self.fragment(b).in_slot(Slot::Right(flipped));
+            format!("{not}({a} {} {b})", flipped.as_str())
+        };
+        let fragment = Fragment {
+            text,
+            prec: Prec::Prefix,
+        };
+        self.suggest(expr, slot, "Apply De Morgan's law".into(), fragment);
+    }
+
+    /// Returns whether `expr` was merged, so nested conditionals are not suggested again.
+    fn merge_conditionals(&mut self, expr: &Expr) -> bool {
+        if !nested_conditional(expr) {
+            return false;
+        }
+        let mut args = Vec::new();
+        let mut current = expr;
+        while let Some(call_args) = conditional_args(current) {
+            let (last, rest) = call_args.split_last().expect("conditionals have arguments");
+            args.extend(rest);
+            current = ungroup(last);
+        }
+        args.push(current);
+        if self.drops_comment(expr, &args) {
+            return false;
+        }
+        let args: Vec<String> = args.iter().map(|arg| self.text(arg).to_string()).collect();
+        let fragment = Fragment {
+            text: format!("ifs({})", args.join(", ")),
+            prec: Prec::Atom,
+        };
+        let title = "Merge nested conditions into `ifs`".into();
+        self.suggest(expr, Slot::Free, title, fragment);
+        true
+    }
+
+    fn suggest(&mut self, expr: &Expr, slot: Slot, title: String, fragment: Fragment) {
+        let (range, new_text) = match self.group {
+            Some((group, group_slot)) if fits(fragment.prec, group_slot) => (group, fragment.text),
+            _ => (expr.span, fragment.in_slot(slot)),
+        };
+        self.actions.push(CodeAction {
+            title,
+            edits: vec![TextEdit { range, new_text }],
+        });
+    }
+
+    fn fragment(&self, expr: &Expr) -> Fragment {
+        Fragment {
+            text: self.text(expr).to_string(),
+            prec: prec_of(expr),
+        }
+    }
+
+    fn text(&self, expr: &Expr) -> &str {
+        &self.source[expr.span.start as usize..expr.span.end as usize]
+    }
+
+    fn ty(&self, expr: &Expr) -> Ty {
+        self.type_map.get(expr.id).cloned().unwrap_or(Ty::Unknown)
+    }
+
+    /// Whether a comment in `expr` lies outside every `kept` expression.
+    fn drops_comment(&self, expr: &Expr, kept: &[&Expr]) -> bool {
+        let within =
+            |outer: Span, inner: Span| outer.start <= inner.start && inner.end <= outer.end;
+        self.tokens.iter().any(|t| {
+            t.kind.is_comment()
+                && within(expr.span, t.span)
+                && !kept.iter().any(|k| within(k.span, t.span))
+        })
+    }
+}
+
+fn ungroup(mut expr: &Expr) -> &Expr {
+    while let ExprKind::Group { inner } = &expr.kind {
+        expr = inner;
+    }
+    expr
+}
+
+fn negated(expr: &Expr) -> Option<&Expr> {
+    match &expr.kind {
+        ExprKind::Unary {
+            op: UnOp::Not(_),
+            expr,
+        } => Some(expr),
+        _ => None,
+    }
+}
+
+/// `a && b` / `a || b`, or the legacy `and(a, b)` / `or(a, b)` (then `true`): the operator and
+/// both operands.
+fn junction(expr: &Expr) -> Option<(BinOpKind, bool, &Expr, &Expr)> {
+    match &expr.kind {
+        ExprKind::Binary { op, left, right } => Some((op.node, false, left, right)),
+        ExprKind::Call { callee, args } => {
+            let op = match callee.text.as_str() {
+                "and" => BinOpKind::AndAnd,
+                "or" => BinOpKind::OrOr,
+                _ => return None,
+            };
+            match args.as_slice() {
+                [left, right] => Some((op, true, left, right)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn bool_literal(expr: &Expr) -> Option<bool> {
+    match &ungroup(expr).kind {
+        ExprKind::Lit(lit) if lit.kind == LitKind::Bool => Some(lit.symbol.text == "true"),
+        _ => None,
+    }
+}
+
+/// Arguments of a well-formed `if`/`ifs` call.
+fn conditional_args(expr: &Expr) -> Option<Vec<&Expr>> {
+    let ExprKind::Call { callee, args } = &expr.kind else {
+        return None;
+    };
+    let valid = match callee.text.as_str() {
+        "if" => args.len() == 3,
+        "ifs" => args.len() >= 3 && args.len() % 2 == 1,
+        _ => false,
+    };
+    valid.then(|| args.iter().collect())
+}
+
+/// An `if`/`ifs` whose last argument is itself one.
+fn nested_conditional(expr: &Expr) -> bool {
+    conditional_args(expr)
+        .and_then(|args| args.last().copied())
+        .is_some_and(|last| conditional_args(ungroup(last)).is_some())
+}
+
+/// `if(c, a, b)` or `c ? a : b`.
+fn single_conditional(expr: &Expr) -> Option<(&Expr, &Expr, &Expr)> {
+    match &expr.kind {
+        ExprKind::Ternary {
+            cond,
+            then,
+            otherwise,
+        } => Some((cond, then, otherwise)),
+        ExprKind::Call { callee, args } if callee.text == "if" => match args.as_slice() {
+            [cond, then, otherwise] => Some((cond, then, otherwise)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether folding `expr` would do more than drop parentheses.
+fn has_operator(expr: &Expr) -> bool {
+    match &ungroup(expr).kind {
+        ExprKind::Binary { .. } => true,
+        ExprKind::Unary { op, expr } => *op != UnOp::Neg || has_operator(expr),
+        _ => false,
+    }
+}
+
+pub(crate) fn constant(expr: &Expr) -> Option<Const> {
+    match &expr.kind {
+        ExprKind::Lit(lit) => match lit.kind {
+            LitKind::Number => lit.symbol.text.parse().ok().map(Const::Number),
+            LitKind::Bool => Some(Const::Bool(lit.symbol.text == "true")),
+            LitKind::String => Some(Const::Text(lit.symbol.text.to_string())),
+        },
+        ExprKind::Group { inner } => constant(inner),
+        ExprKind::Unary { op, expr } => match (op, constant(expr)?) {
+            (UnOp::Neg, Const::Number(n)) => Some(Const::Number(-n)),
+            (UnOp::Not(_), Const::Bool(b)) => Some(Const::Bool(!b)),
+            _ => None,
+        },
+        ExprKind::Binary { op, left, right } => binary(op.node, constant(left)?, constant(right)?),
+        _ => None,
+    }
+}
+
+pub(crate) fn binary(op: BinOpKind, left: Const, right: Const) -> Option<Const> {
+    use BinOpKind::*;
+
+    let value = match (left, right) {
+        (Const::Number(a), Const::Number(b)) => match op {
+            Plus => Const::Number(a + b),
+            Minus => Const::Number(a - b),
+            Star => Const::Number(a * b),
+            Slash | Percent if b == 0.0 => return None,
+            Slash => Const::Number(a / b),
+            Percent => Const::Number(a % b),
+            Caret => Const::Number(a.powf(b)),
+            Lt => Const::Bool(a < b),
+            Le => Const::Bool(a <= b),
+            Gt => Const::Bool(a > b),
+            Ge => Const::Bool(a >= b),
+            EqEq => Const::Bool(a == b),
+            Ne => Const::Bool(a != b),
+            AndAnd | OrOr => return None,
+        },
+        (Const::Bool(a), Const::Bool(b)) => match op {
+            AndAnd => Const::Bool(a && b),
+            OrOr => Const::Bool(a || b),
+            EqEq => Const::Bool(a == b),
+            Ne => Const::Bool(a != b),
+            _ => return None,
+        },
+        (Const::Text(a), Const::Text(b)) => match op {
+            Plus => Const::Text(a + &b),
+            EqEq => Const::Bool(a == b),
+            Ne => Const::Bool(a != b),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(value)
+}
@@ -1,16 +1,54 @@
 #[cfg(test)]
-pub(crate) mod completion_dsl;
+mod test_analysis_host;
 #[cfg(test)]
-mod test_completion_position;
+mod test_code_actions;
 #[cfg(test)]
-mod test_completion_ranking;
+mod test_completion_option_values;
 #[cfg(test)]
-mod test_completion_signature_help;
-#[cfg(test)]
-mod test_completion_smoke;
+mod test_diff;
 #[cfg(test)]
 mod test_display_format_ty;
 #[cfg(test)]
+mod test_document_highlights;
+#[cfg(test)]
 mod test_edit_ops;
 #[cfg(test)]
+mod test_equivalence;
+#[cfg(test)]
+mod test_explain;
+#[cfg(test)]
+mod test_format_call_breaking;
+#[cfg(test)]
+mod test_format_comments;
+#[cfg(test)]
 mod test_format_idempotence;
+#[cfg(test)]
+mod test_format_minified;
+#[cfg(test)]
+mod test_format_range;
+#[cfg(test)]
+mod test_hover;
+#[cfg(test)]
+mod test_html;
+#[cfg(test)]
+mod test_migrate;
+#[cfg(test)]
+mod test_notion_expression;
+#[cfg(test)]
+mod test_paste;
+#[cfg(test)]
+mod test_referenced_properties;
+#[cfg(test)]
+mod test_rename;
+#[cfg(test)]
+mod test_semantic_tokens;
+#[cfg(all(test, feature = "serde"))]
+mod test_serde;
+#[cfg(test)]
+mod test_simplify;
+#[cfg(test)]
+mod test_snippets;
+#[cfg(test)]
+mod test_utf16;
+#[cfg(test)]
+mod test_workspace;
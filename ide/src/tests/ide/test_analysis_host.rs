@@ -0,0 +1,215 @@
+use crate::{AnalysisHost, CompletionConfig, EditError, TextEdit, help};
+use analyzer::Span;
+use analyzer::semantic::{Context, Property, Ty};
+
+fn ctx_with(props: &[(&str, Ty)]) -> Context {
+    Context::with_builtins(
+        props
+            .iter()
+            .map(|(name, ty)| Property {
+                name: name.to_string(),
+                ty: ty.clone(),
+                disabled_reason: None,
+                options: Vec::new(),
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn host_reports_diagnostics_and_output_type() {
+    let mut host = AnalysisHost::new(ctx_with(&[("Price", Ty::Number)]));
+    host.set_text(r#"prop("Price") * 2"#);
+
+    assert!(host.diagnostics().is_empty(), "{:?}", host.diagnostics());
+    assert_eq!(host.output_type(), &Ty::Number);
+    let root = host.syntax().expr.id;
+    assert_eq!(host.type_map().get(root), Some(&Ty::Number));
+}
+
+#[test]
+fn host_apply_change_updates_results() {
+    let mut host = AnalysisHost::new(ctx_with(&[]));
+    host.set_text("1 + 2");
+    assert_eq!(host.output_type(), &Ty::Number);
+
+    host.apply_change(vec![TextEdit {
+        range: Span { start: 2, end: 3 },
+        new_text: ">".to_string(),
+    }])
+    .unwrap();
+
+    assert_eq!(host.text(), "1 > 2");
+    assert_eq!(host.output_type(), &Ty::Boolean);
+}
+
+#[test]
+fn host_incremental_edits_match_fresh_analysis() {
+    let ctx = ctx_with(&[("Price", Ty::Number)]);
+    let mut host = AnalysisHost::new(ctx.clone());
+    host.set_text(r#"if(prop("Price") > 1, [1, 2], [3])"#);
+    host.syntax();
+
+    for (start, end, new_text) in [
+        (19, 20, "10"),
+        (24, 25, "prop(\"Price\") * 2"),
+        (0, 2, "ifs"),
+    ] {
+        host.apply_change(vec![TextEdit {
+            range: Span { start, end },
+            new_text: new_text.to_string(),
+        }])
+        .unwrap();
+
+        let mut fresh = AnalysisHost::new(ctx.clone());
+        fresh.set_text(host.text());
+        assert_eq!(host.syntax().expr, fresh.syntax().expr, "{}", host.text());
+        assert_eq!(host.diagnostics(), fresh.diagnostics());
+        assert_eq!(host.output_type(), fresh.output_type());
+    }
+}
+
+#[test]
+fn host_rejects_invalid_change_without_touching_state() {
+    let mut host = AnalysisHost::new(ctx_with(&[]));
+    host.set_text("1 + 2");
+    let tokens_ptr = host.tokens().as_ptr();
+
+    let err = host
+        .apply_change(vec![TextEdit {
+            range: Span { start: 4, end: 99 },
+            new_text: String::new(),
+        }])
+        .unwrap_err();
+
+    assert_eq!(err, EditError::InvalidEditRange { index: 0 });
+    assert_eq!(host.text(), "1 + 2");
+    assert_eq!(host.tokens().as_ptr(), tokens_ptr);
+}
+
+#[test]
+fn host_context_change_keeps_syntax_and_refreshes_semantics() {
+    let mut host = AnalysisHost::new(ctx_with(&[]));
+    host.set_text(r#"prop("Price")"#);
+    assert_eq!(host.diagnostics().len(), 1);
+    let tokens_ptr = host.tokens().as_ptr();
+
+    host.set_context(ctx_with(&[("Price", Ty::Number)]));
+
+    assert_eq!(host.tokens().as_ptr(), tokens_ptr);
+    assert!(host.diagnostics().is_empty(), "{:?}", host.diagnostics());
+    assert_eq!(host.output_type(), &Ty::Number);
+}
+
+#[test]
+fn host_diagnostics_revision_is_backdated() {
+    let mut host = AnalysisHost::new(ctx_with(&[]));
+    host.set_text(r#"prop("Price")"#);
+    let first = host.diagnostics_changed_at();
+    assert_eq!(first, host.revision());
+
+    // Unrelated context change: recomputed, but equal.
+    host.set_context(ctx_with(&[("Other", Ty::String)]));
+    assert!(host.revision() > first);
+    assert_eq!(host.diagnostics_changed_at(), first);
+
+    host.set_context(ctx_with(&[("Price", Ty::Number)]));
+    assert!(host.diagnostics().is_empty());
+    assert_eq!(host.diagnostics_changed_at(), host.revision());
+
+    // Setting equal inputs does not advance the revision.
+    let revision = host.revision();
+    host.set_text(r#"prop("Price")"#);
+    host.set_context(ctx_with(&[("Price", Ty::Number)]));
+    assert_eq!(host.revision(), revision);
+}
+
+#[test]
+fn host_preview_reports_diagnostics_without_applying() {
+    let mut host = AnalysisHost::new(ctx_with(&[("Price", Ty::Number)]));
+    host.set_text(r#"prop("Price") * 2"#);
+    let revision = host.revision();
+    let tokens_ptr = host.tokens().as_ptr();
+
+    // `prop("Prize")` is unknown.
+    let preview = host
+        .preview(vec![TextEdit {
+            range: Span { start: 9, end: 10 },
+            new_text: "z".to_string(),
+        }])
+        .unwrap();
+    let mut fresh = AnalysisHost::new(host.context().clone());
+    fresh.set_text(r#"prop("Prize") * 2"#);
+    assert_eq!(preview, fresh.diagnostics());
+    assert_eq!(preview.len(), 1);
+
+    assert_eq!(host.text(), r#"prop("Price") * 2"#);
+    assert_eq!(host.revision(), revision);
+    assert_eq!(host.tokens().as_ptr(), tokens_ptr);
+    assert!(host.diagnostics().is_empty());
+
+    assert_eq!(
+        host.preview(vec![TextEdit {
+            range: Span { start: 0, end: 99 },
+            new_text: String::new(),
+        }]),
+        Err(EditError::InvalidEditRange { index: 0 })
+    );
+}
+
+#[test]
+fn host_noop_change_keeps_caches() {
+    let mut host = AnalysisHost::new(ctx_with(&[]));
+    host.set_text("1 + 2");
+    let tokens_ptr = host.tokens().as_ptr();
+
+    host.apply_change(vec![TextEdit {
+        range: Span { start: 0, end: 1 },
+        new_text: "1".to_string(),
+    }])
+    .unwrap();
+
+    assert_eq!(host.tokens().as_ptr(), tokens_ptr);
+}
+
+#[test]
+fn host_help_matches_free_function() {
+    let ctx = ctx_with(&[("Title", Ty::String)]);
+    for source in [
+        "if(pro",
+        r#"prop("Title").length()."#,
+        r#"if(true, prop("Title"), "#,
+    ] {
+        let mut host = AnalysisHost::new(ctx.clone());
+        host.set_text(source);
+
+        assert_eq!(
+            host.help(source.len(), CompletionConfig::default()),
+            help(source, source.len(), &ctx, CompletionConfig::default()),
+            "{source:?}"
+        );
+    }
+}
+
+#[test]
+fn host_line_index_is_patched_by_edits() {
+    let mut host = AnalysisHost::new(ctx_with(&[]));
+    host.set_text("if(\n  true,\n  1,\n  2\n)");
+    assert_eq!(host.source_map().line_col(14), (3, 3));
+
+    host.apply_change(vec![
+        TextEdit {
+            range: Span { start: 3, end: 4 },
+            new_text: String::new(),
+        },
+        TextEdit {
+            range: Span { start: 16, end: 16 },
+            new_text: "\n  0,".to_string(),
+        },
+    ])
+    .unwrap();
+
+    assert_eq!(host.text(), "if(  true,\n  1,\n  0,\n  2\n)");
+    assert_eq!(host.line_index(), &analyzer::LineIndex::new(host.text()));
+    assert_eq!(host.source_map().line_col(13), (2, 3));
+}
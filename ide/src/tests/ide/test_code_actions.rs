@@ -0,0 +1,63 @@
+use crate::code_actions;
+use analyzer::Span;
+use analyzer::semantic::{Context, PropertyLookup, Ty};
+
+fn ctx() -> Context {
+    Context::with_builtins(Vec::new())
+}
+
+fn titles(source: &str, start: u32, end: u32) -> Vec<String> {
+    code_actions(source, Span { start, end }, &ctx())
+        .into_iter()
+        .map(|action| action.title)
+        .collect()
+}
+
+#[test]
+fn returns_quick_fixes_of_overlapping_diagnostics() {
+    let source = "if(true 1, 2)";
+    let actions = code_actions(source, Span { start: 0, end: 13 }, &ctx());
+    let insert_comma = actions
+        .iter()
+        .find(|action| action.title == "Insert `,`")
+        .expect("expected insert-comma quick fix");
+    assert_eq!(insert_comma.edits[0].range, Span { start: 8, end: 8 });
+    assert_eq!(insert_comma.edits[0].new_text, ",");
+}
+
+#[test]
+fn cursor_range_matches_touching_diagnostics_only() {
+    let source = "if(true 1, 2)";
+    assert!(titles(source, 8, 8).contains(&"Insert `,`".to_string()));
+    assert!(titles(source, 12, 12).is_empty());
+}
+
+#[test]
+fn valid_source_has_no_actions() {
+    assert!(titles("1 + 2", 0, 5).is_empty());
+}
+
+#[test]
+fn inexact_property_names_offer_the_canonical_name() {
+    let ctx = Context::builder()
+        .prop("Status", Ty::String)
+        .property_lookup(PropertyLookup::Lenient)
+        .with_builtins()
+        .build();
+    let source = r#"prop("status ")"#;
+    let actions = code_actions(source, Span { start: 8, end: 8 }, &ctx);
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].title, "Replace with `Status`");
+    assert_eq!(actions[0].edits[0].range, Span { start: 5, end: 14 });
+    assert_eq!(actions[0].edits[0].new_text, r#""Status""#);
+}
+
+#[test]
+fn single_operator_chars_offer_the_missing_char() {
+    let source = "if(1 = 2 | true, 1, 2)";
+    assert_eq!(titles(source, 5, 5), ["Insert `=`"]);
+    let actions = code_actions(source, Span { start: 9, end: 10 }, &ctx());
+    assert_eq!(actions.len(), 1);
+    let fixed = crate::apply_edits(source, actions[0].edits.clone(), 0).unwrap();
+    assert_eq!(fixed.source, "if(1 = 2 || true, 1, 2)");
+}
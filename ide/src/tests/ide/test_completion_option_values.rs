@@ -0,0 +1,106 @@
+use crate::{CompletionConfig, CompletionKind, apply_edits, help};
+use analyzer::Span;
+use analyzer::semantic::{Context, PropertyLookup, Ty};
+
+fn ctx() -> Context {
+    Context::builder()
+        .with_builtins()
+        .prop_with_options("Status", Ty::String, ["Not started", "In progress", "Done"])
+        .prop_with_options(
+            "Tags",
+            Ty::List(Box::new(Ty::String)),
+            ["Urgent", "Say \"hi\""],
+        )
+        .prop("Title", Ty::String)
+        .build()
+}
+
+/// `source` with `$0` as the cursor.
+fn labels(source: &str) -> Vec<String> {
+    labels_in(source, &ctx())
+}
+
+fn labels_in(source: &str, ctx: &Context) -> Vec<String> {
+    let cursor = source.find("$0").unwrap();
+    let source = source.replace("$0", "");
+    help(&source, cursor, ctx, CompletionConfig::default())
+        .completion
+        .items
+        .into_iter()
+        .map(|item| item.label)
+        .collect()
+}
+
+#[test]
+fn completes_options_in_comparisons() {
+    assert_eq!(
+        labels(r#"prop("Status") == "$0""#),
+        ["Not started", "In progress", "Done"]
+    );
+    assert_eq!(
+        labels(r#"if("$0" != prop("Status"), 1, 2)"#),
+        ["Not started", "In progress", "Done"]
+    );
+}
+
+#[test]
+fn completes_options_in_contains_and_includes() {
+    let expected = ["Urgent", "Say \"hi\""];
+    assert_eq!(labels(r#"contains(prop("Tags"), "$0")"#), expected);
+    assert_eq!(labels(r#"prop("Tags").includes("$0")"#), expected);
+}
+
+#[test]
+fn ranks_by_the_text_before_the_cursor_and_replaces_the_content() {
+    let source = r#"prop("Status") == "do|ne x""#;
+    let cursor = source.find('|').unwrap();
+    let source = source.replace('|', "");
+    let result = help(&source, cursor, &ctx(), CompletionConfig::default());
+
+    let content = Span { start: 19, end: 25 };
+    assert_eq!(result.completion.replace, content);
+    let first = &result.completion.items[0];
+    assert_eq!(first.label, "Done");
+    assert_eq!(first.kind, CompletionKind::Constant);
+    assert_eq!(first.detail.as_deref(), Some("Status option"));
+    assert_eq!(result.completion.preferred_indices, [0]);
+
+    let applied = apply_edits(&source, first.primary_edit.clone().into_iter().collect(), 0);
+    assert_eq!(applied.unwrap().source, r#"prop("Status") == "Done""#);
+}
+
+#[test]
+fn escapes_inserted_options() {
+    let source = r#"prop("Tags").includes("")"#;
+    let result = help(source, 23, &ctx(), CompletionConfig::default());
+    let item = result
+        .completion
+        .items
+        .iter()
+        .find(|item| item.label == "Say \"hi\"")
+        .unwrap();
+    assert_eq!(item.insert_text, r#"Say \"hi\""#);
+}
+
+#[test]
+fn follows_lenient_property_lookup() {
+    let mut ctx = ctx();
+    ctx.property_lookup = PropertyLookup::Lenient;
+    assert_eq!(
+        labels_in(r#"prop("status") == "$0""#, &ctx),
+        ["Not started", "In progress", "Done"]
+    );
+}
+
+#[test]
+fn other_string_literals_complete_nothing() {
+    for source in [
+        r#"prop("Title") == "$0""#,
+        r#"prop("St$0atus") == "Done""#,
+        r#"prop("Status") + "$0""#,
+        r#"prop("Status") == "Done" ? "$0" : "x""#,
+        r#"contains("$0", prop("Tags"))"#,
+    ] {
+        assert!(labels(source).is_empty(), "{source}");
+    }
+}
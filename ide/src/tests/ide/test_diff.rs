@@ -0,0 +1,60 @@
+use crate::{TextEdit, apply_edits, diff};
+use analyzer::Span;
+
+fn apply(source: &str, edits: Vec<TextEdit>) -> String {
+    apply_edits(source, edits, 0)
+        .expect("expected diff edits to apply")
+        .source
+}
+
+fn edit(start: u32, end: u32, new_text: &str) -> TextEdit {
+    TextEdit {
+        range: Span { start, end },
+        new_text: new_text.to_string(),
+    }
+}
+
+#[test]
+fn diff_of_identical_sources_is_empty() {
+    assert!(diff("if(a, 1, 2)", "if(a, 1, 2)").is_empty());
+    assert!(diff("", "").is_empty());
+}
+
+#[test]
+fn diff_touches_only_the_changed_literal() {
+    let old = r#"prop("Price") * 2 + prop("Tax")"#;
+    let new = r#"prop("Cost") * 2 + prop("Tax")"#;
+    assert_eq!(diff(old, new), vec![edit(6, 11, "Cost")]);
+}
+
+#[test]
+fn diff_produces_separate_edits_for_separate_changes() {
+    let old = "if(a, 1, 2) + f(x)";
+    let new = "if(b, 1, 2) + f(y, z)";
+    assert_eq!(diff(old, new), vec![edit(3, 4, "b"), edit(16, 17, "y, z")]);
+}
+
+#[test]
+fn diff_handles_insertions_and_deletions_of_whole_tokens() {
+    assert_eq!(diff("1 + 2", "1 + 2 + 3"), vec![edit(5, 5, " + 3")]);
+    assert_eq!(diff("[1, 2, 3]", "[1, 3]"), vec![edit(3, 6, "")]);
+}
+
+#[test]
+fn diff_edits_always_reproduce_the_new_source() {
+    let cases = [
+        ("", "1 + 2"),
+        ("1 + 2", ""),
+        ("a.b().c()", "a.c().b()"),
+        (
+            "if(x > 1, \"😀\", \"b\")",
+            "if(x >= 1,\n  \"😀!\",\n  \"b\")",
+        ),
+        ("// note\nf(1, /* c */ 2)", "f(1, 2) // note"),
+        ("1 +", "1 + (2"),
+        ("[a, b, c, d]", "[d, c, b, a]"),
+    ];
+    for (old, new) in cases {
+        assert_eq!(apply(old, diff(old, new)), new, "diff({old:?}, {new:?})");
+    }
+}
@@ -11,3 +11,17 @@ fn format_ty_union_prec_does_not_parenthesize_list() {
     let ty = Ty::Union(vec![Ty::Number, Ty::List(Box::new(Ty::String))]);
     assert_eq!(ty.to_string(), "number | string[]");
 }
+
+#[test]
+fn format_ty_optional_parenthesizes_union() {
+    let ty = Ty::Union(vec![Ty::String, Ty::Null]);
+    assert_eq!(ty.display().optional(true).to_string(), "(string | null)?");
+    assert_eq!(
+        Ty::List(Box::new(Ty::Number))
+            .display()
+            .optional(true)
+            .to_string(),
+        "number[]?"
+    );
+    assert_eq!(ty.display().optional(false).to_string(), ty.to_string());
+}
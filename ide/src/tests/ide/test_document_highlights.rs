@@ -0,0 +1,58 @@
+use crate::{AnalysisHost, document_highlights};
+use analyzer::Span;
+use analyzer::semantic::Context;
+
+/// Renders highlighted spans as the source texts they cover.
+fn highlighted(source: &str, cursor_at: &str) -> Vec<String> {
+    let cursor = source.find(cursor_at).expect("cursor marker in source") as u32;
+    document_highlights(source, cursor)
+        .into_iter()
+        .map(|Span { start, end }| source[start as usize..end as usize].to_string())
+        .collect()
+}
+
+#[test]
+fn highlights_property_references() {
+    let source = r#"prop("Price") * 2 + prop("Tax") - prop("Price")"#;
+    assert_eq!(
+        highlighted(source, "Price"),
+        vec![r#""Price""#, r#""Price""#]
+    );
+}
+
+#[test]
+fn highlights_function_calls_but_not_methods_of_the_same_name() {
+    let source = r#"if(a, 1, 2) + if(b, 3, 4) + "x".if()"#;
+    assert_eq!(highlighted(source, "if(b"), vec!["if", "if"]);
+    assert_eq!(highlighted(source, "if()"), vec!["if"]);
+}
+
+#[test]
+fn highlights_methods_across_receivers() {
+    let source = "[1].length() + [2, 3].length() + length([4])";
+    let spans = document_highlights(source, 6);
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0], Span { start: 4, end: 10 });
+}
+
+#[test]
+fn highlights_when_cursor_touches_token_end() {
+    let source = "a + a";
+    assert_eq!(
+        document_highlights(source, 1),
+        vec![Span { start: 0, end: 1 }, Span { start: 4, end: 5 }]
+    );
+}
+
+#[test]
+fn no_highlights_off_symbol_or_for_plain_strings() {
+    assert!(document_highlights("1 + 2", 2).is_empty());
+    assert!(document_highlights(r#""a" + "a""#, 1).is_empty());
+}
+
+#[test]
+fn host_highlights_use_cached_tokens() {
+    let mut host = AnalysisHost::new(Context::with_builtins(Vec::new()));
+    host.set_text(r#"prop("A") + prop("A")"#);
+    assert_eq!(host.document_highlights(7).len(), 2);
+}
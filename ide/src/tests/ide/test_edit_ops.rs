@@ -1,11 +1,26 @@
-use crate::{CompletionConfig, IdeError, TextEdit, apply_edits, format, help};
+use crate::{
+    CompletionConfig, EditError, TextEdit, apply_edits, apply_text_edits_bytes_with_cursor, format,
+    help,
+};
 use analyzer::Span;
-use analyzer::semantic::{Context, builtins_functions};
+use analyzer::semantic::Context;
 
 #[test]
 fn ide_format_reports_error_on_syntax_errors() {
     let err = format("1 +", 0).expect_err("expected format error");
-    assert_eq!(err, IdeError::FormatError);
+    assert_eq!(err, EditError::FormatError);
+}
+
+#[test]
+fn edit_error_display_appends_indices_to_the_message() {
+    let err = EditError::OverlappingEdits {
+        first: 1,
+        second: 2,
+    };
+    assert_eq!(err.message(), "Overlapping edits");
+    assert_eq!(err.to_string(), "Overlapping edits (edits #1 and #2)");
+    let err: &dyn std::error::Error = &EditError::InvalidCursor;
+    assert_eq!(err.to_string(), "Invalid cursor");
 }
 
 #[test]
@@ -28,7 +43,89 @@ fn ide_apply_edits_rejects_overlapping_ranges() {
     ];
 
     let err = apply_edits("abcd", edits, 0).expect_err("expected overlap error");
-    assert_eq!(err, IdeError::OverlappingEdits);
+    assert_eq!(
+        err,
+        EditError::OverlappingEdits {
+            first: 0,
+            second: 1
+        }
+    );
+}
+
+#[test]
+fn ide_apply_edits_reports_original_indices_after_sorting() {
+    let edits = vec![
+        TextEdit {
+            range: Span { start: 0, end: 1 },
+            new_text: "X".to_string(),
+        },
+        TextEdit {
+            range: Span { start: 2, end: 4 },
+            new_text: "Y".to_string(),
+        },
+        TextEdit {
+            range: Span { start: 1, end: 3 },
+            new_text: "Z".to_string(),
+        },
+    ];
+
+    let err = apply_edits("abcd", edits, 0).expect_err("expected overlap error");
+    assert_eq!(
+        err,
+        EditError::OverlappingEdits {
+            first: 1,
+            second: 2
+        }
+    );
+    assert_eq!(err.message(), "Overlapping edits");
+    assert_eq!(err.to_string(), "Overlapping edits (edits #1 and #2)");
+}
+
+#[test]
+fn ide_apply_edits_rejects_out_of_range_and_mid_char_edits() {
+    let out_of_range = vec![
+        TextEdit {
+            range: Span { start: 0, end: 0 },
+            new_text: "X".to_string(),
+        },
+        TextEdit {
+            range: Span { start: 2, end: 9 },
+            new_text: String::new(),
+        },
+    ];
+    let err = apply_edits("aé", out_of_range, 0).expect_err("expected range error");
+    assert_eq!(err, EditError::InvalidEditRange { index: 1 });
+
+    let mid_char = vec![TextEdit {
+        range: Span { start: 2, end: 3 },
+        new_text: String::new(),
+    }];
+    let err = apply_edits("aé", mid_char, 0).expect_err("expected char boundary error");
+    assert_eq!(
+        err,
+        EditError::EditNotOnCharBoundary {
+            index: 0,
+            offset: 2
+        }
+    );
+}
+
+#[test]
+fn apply_text_edits_bytes_with_cursor_rejects_unsorted_edits() {
+    let edits = vec![
+        TextEdit {
+            range: Span { start: 2, end: 3 },
+            new_text: "X".to_string(),
+        },
+        TextEdit {
+            range: Span { start: 0, end: 1 },
+            new_text: "Y".to_string(),
+        },
+    ];
+
+    let err =
+        apply_text_edits_bytes_with_cursor("abcd", &edits, 0).expect_err("expected unsorted error");
+    assert_eq!(err, EditError::UnsortedEdits { index: 1 });
 }
 
 #[test]
@@ -45,10 +142,7 @@ fn ide_apply_edits_applies_and_rebases_cursor() {
 
 #[test]
 fn ide_help_splits_completion_and_signature_help() {
-    let ctx = Context {
-        properties: Vec::new(),
-        functions: builtins_functions(),
-    };
+    let ctx = Context::with_builtins(Vec::new());
     let out = help("if(", 3, &ctx, CompletionConfig::default());
 
     assert!(
@@ -58,3 +152,70 @@ fn ide_help_splits_completion_and_signature_help() {
     assert!(out.completion.replace.start <= 3);
     assert!(out.completion.replace.end >= out.completion.replace.start);
 }
+
+#[test]
+fn ide_format_reports_no_diagnostics_when_verification_passes() {
+    let out = format("if(a,// c\n b,c)", 0).expect("expected formatted output");
+    assert!(out.diagnostics.is_empty(), "{:?}", out.diagnostics);
+}
+
+#[test]
+fn format_verification_ignores_spans_and_trivia() {
+    let original = analyzer::analyze_syntax("(1+2)*f(a,b) // c");
+    let formatted = "( 1 +\n  2 ) * f(\n  a, // c\n  b\n)\n";
+    assert_eq!(
+        crate::edit::verify_formatted(&original.expr, &original.tokens, formatted, 17),
+        None
+    );
+}
+
+#[test]
+fn format_verification_rejects_changed_or_broken_output() {
+    let original = analyzer::analyze_syntax("1+2").expr;
+
+    for formatted in ["1 - 2\n", "(1 + 2)\n", "1 +\n", "1 + /* a */ 2\n"] {
+        let diag = crate::edit::verify_formatted(&original, &[], formatted, 3)
+            .unwrap_or_else(|| panic!("expected verification failure for {formatted:?}"));
+        assert_eq!(diag.code, analyzer::DiagnosticCode::InternalError);
+        assert_eq!(diag.span, Span { start: 0, end: 3 });
+    }
+}
+
+#[test]
+fn format_multiline_unary_reuses_operand_parentheses() {
+    let long_not = format!(
+        "not ({})",
+        (0..12)
+            .map(|i| format!("prop(\"Property {i}\")"))
+            .collect::<Vec<_>>()
+            .join(" && ")
+    );
+    let cases = [
+        ("!\nprop(\"A\")", "!prop(\"A\")\n".to_string()),
+        ("!(\n prop(\"A\")\n)", "!(\n  prop(\"A\")\n)\n".to_string()),
+        ("not (\n a || b\n)", "not (\n  a || b\n)\n".to_string()),
+    ];
+    for (input, expected) in cases {
+        let out = format(input, 0).unwrap();
+        assert_eq!(out.diagnostics, vec![], "input: {input:?}");
+        assert_eq!(out.source, expected, "input: {input:?}");
+    }
+
+    let out = format(&long_not, 0).unwrap();
+    assert_eq!(out.diagnostics, vec![]);
+    assert!(out.source.starts_with("not (\n"), "{}", out.source);
+    assert_ne!(out.source, long_not);
+}
+
+#[test]
+fn ide_help_cancellable_matches_help_until_cancelled() {
+    let ctx = Context::with_builtins(Vec::new());
+    let cancel = crate::CancellationToken::new();
+
+    let out = crate::help_cancellable("if(", 3, &ctx, CompletionConfig::default(), &cancel);
+    assert_eq!(out, Ok(help("if(", 3, &ctx, CompletionConfig::default())));
+
+    cancel.cancel();
+    let out = crate::help_cancellable("if(", 3, &ctx, CompletionConfig::default(), &cancel);
+    assert_eq!(out, Err(crate::Cancelled));
+}
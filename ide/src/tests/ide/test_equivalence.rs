@@ -0,0 +1,121 @@
+use crate::{Equivalence, equivalent};
+use analyzer::semantic::{Context, Ty};
+
+fn ctx() -> Context {
+    Context::builder()
+        .prop("Done", Ty::Boolean)
+        .prop("Late", Ty::Boolean)
+        .prop("Price", Ty::Number)
+        .prop("Qty", Ty::Number)
+        .prop("Title", Ty::String)
+        .with_builtins()
+        .build()
+}
+
+fn check(left: &str, right: &str) -> Equivalence {
+    equivalent(left, right, &ctx())
+}
+
+/// The texts of the first differing subexpressions.
+fn difference<'a>(left: &'a str, right: &'a str) -> (&'a str, &'a str) {
+    match check(left, right) {
+        Equivalence::Different { left: l, right: r } => (
+            &left[l.start as usize..l.end as usize],
+            &right[r.start as usize..r.end as usize],
+        ),
+        other => panic!("expected a difference, got {other:?}"),
+    }
+}
+
+#[test]
+fn ignores_layout_comments_and_parentheses() {
+    assert_eq!(
+        check("prop(\"Price\")*2", "/* total */ (prop(\"Price\")\n  * 2)"),
+        Equivalence::Equivalent
+    );
+}
+
+#[test]
+fn method_and_function_calls_are_interchangeable() {
+    assert_eq!(
+        check("prop(\"Title\").length()", "length(prop(\"Title\"))"),
+        Equivalence::Equivalent
+    );
+    assert_eq!(
+        check("not prop(\"Done\")", "!prop(\"Done\")"),
+        Equivalence::Equivalent
+    );
+    assert_eq!(
+        check("prop(\"Price\") > 1", "1 < prop(\"Price\")"),
+        Equivalence::Equivalent
+    );
+}
+
+#[test]
+fn conditionals_normalize_to_flat_ifs() {
+    assert_eq!(
+        check(
+            "prop(\"Done\") ? 1 : if(prop(\"Late\"), 2, 3)",
+            "ifs(prop(\"Done\"), 1, prop(\"Late\"), 2, 3)"
+        ),
+        Equivalence::Equivalent
+    );
+}
+
+#[test]
+fn folds_constants_and_reorders_commutative_operands() {
+    assert_eq!(
+        check("prop(\"Price\") * (60 * 60)", "3600 * prop(\"Price\")"),
+        Equivalence::Equivalent
+    );
+    assert_eq!(
+        check("1 + prop(\"Price\") + 2", "prop(\"Price\") + 3"),
+        Equivalence::Equivalent
+    );
+    assert_eq!(
+        check(
+            "prop(\"Done\") && (prop(\"Late\") && true)",
+            "true && prop(\"Late\") && prop(\"Done\")"
+        ),
+        Equivalence::Equivalent
+    );
+    assert_eq!(
+        check(
+            "\"a\" + \"b\" + prop(\"Title\")",
+            "\"ab\" + prop(\"Title\")"
+        ),
+        Equivalence::Equivalent
+    );
+}
+
+#[test]
+fn keeps_operand_order_where_it_matters() {
+    assert_eq!(
+        difference("prop(\"Title\") + \"!\"", "\"!\" + prop(\"Title\")"),
+        ("prop(\"Title\")", "\"!\"")
+    );
+    assert_eq!(
+        difference("prop(\"Price\") - 1", "1 - prop(\"Price\")"),
+        ("prop(\"Price\")", "1")
+    );
+}
+
+#[test]
+fn reports_the_innermost_difference() {
+    assert_eq!(
+        difference(
+            "if(prop(\"Done\"), prop(\"Price\") * 2, 0)",
+            "if(prop(\"Done\"), prop(\"Price\") * 3, 0)"
+        ),
+        ("2", "3")
+    );
+    assert_eq!(
+        difference("round(prop(\"Price\"))", "floor(prop(\"Price\"))"),
+        ("round(prop(\"Price\"))", "floor(prop(\"Price\"))")
+    );
+}
+
+#[test]
+fn syntax_errors_are_not_compared() {
+    assert_eq!(check("1 +", "1"), Equivalence::SyntaxError);
+}
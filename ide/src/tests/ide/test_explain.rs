@@ -0,0 +1,109 @@
+use analyzer::semantic::{Context, Ty};
+
+use crate::{Explanation, ExplanationRole, explain};
+
+fn ctx() -> Context {
+    Context::builder()
+        .prop("Status", Ty::String)
+        .prop("Due", Ty::Date)
+        .prop("Price", Ty::Number)
+        .prop("Name", Ty::String)
+        .with_builtins()
+        .build()
+}
+
+fn outline(node: &Explanation) -> Vec<(ExplanationRole, &str)> {
+    node.children
+        .iter()
+        .map(|child| (child.role, child.text.as_str()))
+        .collect()
+}
+
+#[test]
+fn if_reads_as_one_sentence_with_a_part_per_branch() {
+    let source = r#"if(prop("Status") == "Done", prop("Due").formatDate("MMM D"), "Open")"#;
+    let out = explain(source, &ctx());
+
+    assert_eq!(out.role, ExplanationRole::Formula);
+    assert_eq!(out.ty, Ty::String);
+    assert_eq!(
+        out.text,
+        r#"If Status is "Done", show Due formatted as "MMM D", otherwise show "Open""#
+    );
+    // The literal `"Open"` is only described inline.
+    assert_eq!(
+        outline(&out),
+        [
+            (ExplanationRole::Condition, r#"Status is "Done""#),
+            (ExplanationRole::Then, r#"Due formatted as "MMM D""#),
+        ]
+    );
+    assert_eq!(
+        &source[out.children[1].span.start as usize..out.children[1].span.end as usize],
+        r#"prop("Due").formatDate("MMM D")"#
+    );
+}
+
+#[test]
+fn ifs_and_ternaries_list_every_branch() {
+    let out = explain(
+        r#"ifs(prop("Price") > 100, "High", prop("Price") >= 10, "Mid", "Low")"#,
+        &ctx(),
+    );
+    assert_eq!(
+        out.text,
+        r#"If Price is greater than 100, show "High"; if Price is at least 10, show "Mid"; otherwise show "Low""#
+    );
+    assert_eq!(
+        outline(&out),
+        [
+            (ExplanationRole::Condition, "Price is greater than 100"),
+            (ExplanationRole::Condition, "Price is at least 10"),
+        ]
+    );
+
+    let out = explain(r#"prop("Price") > 0 ? "Paid" : "Free""#, &ctx());
+    assert_eq!(
+        out.text,
+        r#"If Price is greater than 0, show "Paid", otherwise show "Free""#
+    );
+}
+
+#[test]
+fn operators_and_calls_nest_through_parentheses() {
+    let out = explain(r#"(prop("Price") + 1) * 2"#, &ctx());
+    assert_eq!(out.text, "(Price plus 1) times 2");
+    assert_eq!(outline(&out), [(ExplanationRole::Operand, "Price plus 1")]);
+
+    let out = explain(r#"prop("Name") + "!""#, &ctx());
+    assert_eq!(out.text, r#"Name followed by "!""#);
+
+    let out = explain(r#"not empty(prop("Name")) && [1, 2].length() > 1"#, &ctx());
+    assert_eq!(
+        out.text,
+        "Not Name is empty and length of a list of 1 and 2 is greater than 1"
+    );
+    let length = &out.children[1].children[0];
+    assert_eq!(length.text, "Length of a list of 1 and 2");
+    assert_eq!(
+        outline(length),
+        [(ExplanationRole::Receiver, "A list of 1 and 2")]
+    );
+}
+
+#[test]
+fn incomplete_formulas_still_explain() {
+    let out = explain("if(", &ctx());
+    assert!(out.text.starts_with("If"), "{}", out.text);
+    assert_eq!(explain("", &ctx()).children, []);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn explanations_serialize_with_roles() {
+    let out = explain("1 + 2", &ctx());
+    let value = serde_json::to_value(&out).unwrap();
+    assert_eq!(value["role"], "Formula");
+    assert_eq!(value["text"], "1 plus 2");
+    assert_eq!(value["span"], serde_json::json!({ "start": 0, "end": 5 }));
+}
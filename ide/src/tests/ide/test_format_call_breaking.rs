@@ -0,0 +1,120 @@
+use crate::format::{ClosingDelim, FormatConfig, format_expr};
+use analyzer::analyze_syntax;
+
+fn format_with(input: &str, config: FormatConfig) -> String {
+    let out = analyze_syntax(input);
+    assert!(out.diagnostics.is_empty(), "{:?}", out.diagnostics);
+    format_expr(&out.expr, input, &out.tokens, config)
+}
+
+fn collapsing() -> FormatConfig {
+    FormatConfig {
+        collapse_fitting: true,
+        ..FormatConfig::default()
+    }
+}
+
+#[test]
+fn long_call_breaks_one_argument_per_line() {
+    let input =
+        r#"ifs(prop("Status") == "Done", "green", prop("Status") == "Blocked", "red", "gray")"#;
+    assert_eq!(
+        format_with(input, FormatConfig::default()),
+        concat!(
+            "ifs(\n",
+            "  prop(\"Status\") == \"Done\",\n",
+            "  \"green\",\n",
+            "  prop(\"Status\") == \"Blocked\",\n",
+            "  \"red\",\n",
+            "  \"gray\"\n",
+            ")\n",
+        )
+    );
+}
+
+#[test]
+fn broken_call_keeps_source_breaks_by_default() {
+    let input = "if(\n  true,\n  1,\n  2\n)";
+    assert_eq!(
+        format_with(input, FormatConfig::default()),
+        format!("{input}\n")
+    );
+}
+
+#[test]
+fn broken_call_collapses_when_it_fits() {
+    let input = "if(\n  true,\n  1,\n  2\n)";
+    assert_eq!(format_with(input, collapsing()), "if(true, 1, 2)\n");
+
+    let input = "[\n  1,\n  2\n].at(\n  0\n)";
+    assert_eq!(format_with(input, collapsing()), "[1, 2].at(0)\n");
+}
+
+#[test]
+fn broken_call_stays_broken_when_too_wide() {
+    let input = "if(\n  true,\n  1,\n  2\n)";
+    let config = FormatConfig {
+        max_width: 10,
+        ..collapsing()
+    };
+    assert_eq!(format_with(input, config), format!("{input}\n"));
+}
+
+#[test]
+fn line_comments_keep_call_broken() {
+    let input = "if(\n  true, // cond\n  1,\n  2\n)";
+    assert_eq!(format_with(input, collapsing()), format!("{input}\n"));
+}
+
+#[test]
+fn closing_delim_same_line_hugs_last_argument() {
+    let input = "if(\n  true,\n  1,\n  2\n)";
+    let config = FormatConfig {
+        closing_delim: ClosingDelim::SameLine,
+        ..FormatConfig::default()
+    };
+    assert_eq!(format_with(input, config), "if(\n  true,\n  1,\n  2)\n");
+}
+
+#[test]
+fn closing_delim_same_line_avoids_trailing_line_comment() {
+    let input = "if(\n  true,\n  1,\n  2 // last\n)";
+    let config = FormatConfig {
+        closing_delim: ClosingDelim::SameLine,
+        ..FormatConfig::default()
+    };
+    assert_eq!(format_with(input, config), format!("{input}\n"));
+}
+
+#[test]
+fn format_with_config_applies_layout() {
+    let input = "if(\n  true,\n  1,\n  2\n)";
+    let config = FormatConfig {
+        indent_width: 4,
+        ..FormatConfig::default()
+    };
+    let out = crate::format_with_config(input, 0, config).unwrap();
+    assert_eq!(out.source, "if(\n    true,\n    1,\n    2\n)\n");
+    assert_eq!(
+        crate::format(input, 0).unwrap().source,
+        format!("{input}\n")
+    );
+}
+
+#[test]
+fn format_instrumented_reports_one_format_phase() {
+    struct Phases(Vec<(crate::Phase, usize)>);
+    impl crate::Instrumentation for Phases {
+        fn now(&mut self) -> f64 {
+            0.0
+        }
+        fn phase(&mut self, phase: crate::Phase, _duration: f64, input_len: usize) {
+            self.0.push((phase, input_len));
+        }
+    }
+
+    let mut phases = Phases(Vec::new());
+    let out = crate::format_instrumented("1+2", 0, FormatConfig::default(), &mut phases).unwrap();
+    assert_eq!(out, crate::format("1+2", 0).unwrap());
+    assert_eq!(phases.0, [(crate::Phase::Format, 3)]);
+}
@@ -0,0 +1,74 @@
+use crate::format::{FormatConfig, format_expr};
+use analyzer::analyze_syntax;
+
+fn format_with(input: &str, config: FormatConfig) -> String {
+    let out = analyze_syntax(input);
+    assert!(out.diagnostics.is_empty(), "{:?}", out.diagnostics);
+    format_expr(&out.expr, input, &out.tokens, config)
+}
+
+fn wrapping(max_width: usize) -> FormatConfig {
+    FormatConfig {
+        max_width,
+        wrap_comments: true,
+        ..FormatConfig::default()
+    }
+}
+
+#[test]
+fn long_leading_comment_is_kept_by_default() {
+    let input = "// one two three four five six\n1";
+    let config = FormatConfig {
+        max_width: 12,
+        ..FormatConfig::default()
+    };
+    assert_eq!(format_with(input, config), format!("{input}\n"));
+}
+
+#[test]
+fn long_leading_comment_wraps_at_word_boundaries() {
+    let input = "// one two three four five six\n1";
+    assert_eq!(
+        format_with(input, wrapping(14)),
+        "// one two\n// three four\n// five six\n1\n"
+    );
+}
+
+#[test]
+fn wrapped_comment_accounts_for_indent() {
+    let input = "f(\n  // alpha beta gamma\n  1\n)";
+    assert_eq!(
+        format_with(input, wrapping(16)),
+        "f(\n  // alpha beta\n  // gamma\n  1\n)\n"
+    );
+}
+
+#[test]
+fn wrapped_comment_is_idempotent() {
+    let input = "// one two three four five six\n1";
+    let first = format_with(input, wrapping(14));
+    assert_eq!(format_with(&first, wrapping(14)), first);
+}
+
+#[test]
+fn trailing_and_block_comments_are_not_wrapped() {
+    let input = "1 // one two three four five six";
+    assert_eq!(format_with(input, wrapping(10)), format!("{input}\n"));
+
+    let input = "/* one two three four five six */\n1";
+    assert_eq!(format_with(input, wrapping(10)), format!("{input}\n"));
+}
+
+#[test]
+fn consecutive_inline_block_comments_are_all_kept() {
+    let cases = [
+        ("/* a */ /* b */ x", "/* a */ /* b */ x\n"),
+        ("f(/* a */ /* b */ 1, 2)", "f(/* a */ /* b */ 1, 2)\n"),
+        ("1 + /* a */ /* b */ 2", "1 + /* a */ /* b */ 2\n"),
+    ];
+    for (input, expected) in cases {
+        let out = crate::format(input, 0).unwrap();
+        assert_eq!(out.diagnostics, vec![], "input: {input:?}");
+        assert_eq!(out.source, expected, "input: {input:?}");
+    }
+}
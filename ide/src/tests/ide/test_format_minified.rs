@@ -0,0 +1,51 @@
+use crate::{EditError, format, format_minified};
+
+#[test]
+fn minified_strips_whitespace_and_comments() {
+    let input = "// lead\nif(\n  prop(\"A\") > 1, /* yes */ \"y\",\n  \"n\" // no\n)\n";
+    assert_eq!(
+        format_minified(input).unwrap(),
+        r#"if(prop("A")>1,"y","n")"#
+    );
+}
+
+#[test]
+fn minified_keeps_space_after_not_keyword() {
+    assert_eq!(format_minified("not  a && ! b").unwrap(), "not a&&!b");
+}
+
+#[test]
+fn minified_keeps_groups_and_escapes() {
+    assert_eq!(
+        format_minified(r#"( 1 + 2 ) * 3 == 9 ? "a \"q\"" : [ 1 , 2 ].at( 0 )"#).unwrap(),
+        r#"(1+2)*3==9?"a \"q\"":[1,2].at(0)"#
+    );
+}
+
+#[test]
+fn minified_output_round_trips_through_formatter() {
+    let cases = [
+        "1 - -2",
+        "a >= -1",
+        "not not a",
+        "- -1",
+        "2 ^ -3 ^ 4",
+        "a ? b ? 1 : 2 : 3",
+        r#"prop("Title").length() + 1.5e3"#,
+    ];
+
+    for input in cases {
+        let minified = format_minified(input).unwrap();
+        assert_eq!(
+            format(&minified, 0).unwrap().source,
+            format(input, 0).unwrap().source,
+            "input: {input}, minified: {minified}"
+        );
+        assert_eq!(format_minified(&minified).unwrap(), minified);
+    }
+}
+
+#[test]
+fn minified_rejects_syntax_errors() {
+    assert_eq!(format_minified("1 +"), Err(EditError::FormatError));
+}
@@ -0,0 +1,103 @@
+use crate::{EditError, FormatConfig, TextEdit, apply_edits, format_range};
+use analyzer::Span;
+
+fn span_of(source: &str, needle: &str) -> Span {
+    let start = source.find(needle).expect("needle in source") as u32;
+    Span {
+        start,
+        end: start + needle.len() as u32,
+    }
+}
+
+fn apply(source: &str, edits: Vec<TextEdit>) -> String {
+    apply_edits(source, edits, 0)
+        .expect("expected edits to apply")
+        .source
+}
+
+#[test]
+fn format_range_only_touches_selected_expression() {
+    let source = "f(1+2, 3*4)";
+    let edits = format_range(source, span_of(source, "3*4"), FormatConfig::default()).unwrap();
+
+    assert_eq!(apply(source, edits), "f(1+2, 3 * 4)");
+}
+
+#[test]
+fn format_range_snaps_to_enclosing_expression() {
+    let source = "f(1+2, 3*4)";
+    // The selection covers `+2, 3`, which only the call encloses.
+    let edits = format_range(source, span_of(source, "+2, 3"), FormatConfig::default()).unwrap();
+
+    assert_eq!(apply(source, edits), "f(1 + 2, 3 * 4)");
+}
+
+#[test]
+fn format_range_returns_minimal_edits() {
+    let source = "if(a,  b, c)";
+    let edits = format_range(source, span_of(source, "a,  b"), FormatConfig::default()).unwrap();
+
+    assert_eq!(
+        edits,
+        vec![TextEdit {
+            range: Span { start: 6, end: 7 },
+            new_text: String::new(),
+        }]
+    );
+}
+
+#[test]
+fn format_range_is_empty_for_formatted_selection() {
+    let source = "1 + 2";
+    let edits = format_range(source, span_of(source, "1 + 2"), FormatConfig::default()).unwrap();
+
+    assert!(edits.is_empty());
+}
+
+#[test]
+fn format_range_keeps_comments_outside_selection() {
+    let source = "// head\nf(1+2) // tail";
+    let edits = format_range(source, span_of(source, "1+2"), FormatConfig::default()).unwrap();
+
+    assert_eq!(apply(source, edits), "// head\nf(1 + 2) // tail");
+}
+
+#[test]
+fn format_range_indents_continuation_lines_from_enclosing_line() {
+    let source = "[\n  f(aaaa, bbbb),\n  2\n]";
+    let config = FormatConfig {
+        max_width: 10,
+        ..FormatConfig::default()
+    };
+    let edits = format_range(source, span_of(source, "f(aaaa, bbbb)"), config).unwrap();
+
+    assert_eq!(
+        apply(source, edits),
+        "[\n  f(\n    aaaa,\n    bbbb\n  ),\n  2\n]"
+    );
+}
+
+#[test]
+fn format_range_rejects_invalid_range() {
+    let err = format_range("1+2", Span { start: 2, end: 9 }, FormatConfig::default())
+        .expect_err("expected range error");
+    assert_eq!(err, EditError::InvalidRange);
+}
+
+#[test]
+fn format_range_rejects_syntax_errors() {
+    let err = format_range("1 +", Span { start: 0, end: 1 }, FormatConfig::default())
+        .expect_err("expected format error");
+    assert_eq!(err, EditError::FormatError);
+}
+
+#[test]
+fn format_range_output_keeps_the_ast() {
+    let source = "!(\n prop(\"A\")\n)";
+    let edits = format_range(source, span_of(source, source), FormatConfig::default()).unwrap();
+    assert_eq!(apply(source, edits), "!(\n  prop(\"A\")\n)");
+
+    let source = "f(!\nprop(\"A\"), 1)";
+    let edits = format_range(source, span_of(source, "!"), FormatConfig::default()).unwrap();
+    assert_eq!(apply(source, edits), "f(!prop(\"A\"), 1)");
+}
@@ -0,0 +1,102 @@
+use crate::{AnalysisHost, DisplaySegment, hover, type_at};
+use analyzer::Span;
+use analyzer::semantic::{Context, Property, Ty};
+
+fn ctx() -> Context {
+    Context::with_builtins(vec![Property {
+        name: "Price".to_string(),
+        ty: Ty::Number,
+        disabled_reason: None,
+        options: Vec::new(),
+    }])
+}
+
+fn cursor_at(source: &str, needle: &str) -> usize {
+    source.find(needle).expect("needle in source")
+}
+
+#[test]
+fn hover_on_function_name_shows_signature_docs_and_call_type() {
+    let source = "if(true, 1, 2) + 1";
+    let out = hover(source, 1, &ctx()).expect("expected hover");
+
+    assert_eq!(out.range, Span { start: 0, end: 2 });
+    assert_eq!(out.ty, Ty::Number);
+    assert_eq!(out.docs.is_some(), cfg!(feature = "docs"));
+    let signature = out.signature.expect("expected signature");
+    assert_eq!(
+        signature.segments.first(),
+        Some(&DisplaySegment::Name {
+            text: "if".to_string()
+        })
+    );
+}
+
+#[test]
+fn hover_on_property_literal_shows_property_type() {
+    let source = r#"prop("Price") * 2"#;
+    let out = hover(source, cursor_at(source, "Price"), &ctx()).expect("expected hover");
+
+    assert_eq!(out.range, Span { start: 5, end: 12 });
+    assert_eq!(out.ty, Ty::Number);
+    assert!(out.signature.is_none());
+}
+
+#[test]
+fn hover_on_operator_shows_expression_type() {
+    let source = "1 > 2";
+    let out = hover(source, cursor_at(source, ">"), &ctx()).expect("expected hover");
+
+    assert_eq!(out.range, Span { start: 0, end: 5 });
+    assert_eq!(out.ty, Ty::Boolean);
+}
+
+#[test]
+fn hover_on_unknown_function_has_no_signature() {
+    let out = hover("nope(1)", 0, &ctx()).expect("expected hover");
+    assert!(out.signature.is_none());
+    assert!(out.docs.is_none());
+}
+
+#[test]
+fn hover_returns_none_off_tokens() {
+    assert!(hover("1 +   2", 4, &ctx()).is_none());
+    assert!(hover("", 0, &ctx()).is_none());
+}
+
+#[test]
+fn host_hover_matches_free_function() {
+    let mut host = AnalysisHost::new(ctx());
+    let source = r#"prop("Price").round()"#;
+    host.set_text(source);
+    let cursor = cursor_at(source, "round");
+    assert_eq!(host.hover(cursor), hover(source, cursor, &ctx()));
+}
+
+#[test]
+fn type_at_on_callee_reports_the_whole_call() {
+    let source = "1 + if(true, 2, 3)";
+    let out = type_at(source, cursor_at(source, "if"), &ctx()).expect("expected type");
+    assert_eq!(out.range, Span { start: 4, end: 18 });
+    assert_eq!(out.ty, Ty::Number);
+}
+
+#[test]
+fn type_at_on_literal_and_property() {
+    let source = r#"[prop("Price"), "a"]"#;
+    let out = type_at(source, cursor_at(source, "Price"), &ctx()).expect("expected type");
+    assert_eq!(out.ty, Ty::Number);
+
+    let out = type_at(source, cursor_at(source, "\"a"), &ctx()).expect("expected type");
+    assert_eq!(out.range, Span { start: 16, end: 19 });
+    assert_eq!(out.ty, Ty::String);
+}
+
+#[test]
+fn host_type_at_matches_free_function() {
+    let mut host = AnalysisHost::new(ctx());
+    let source = "[1, 2].map(current * 2)";
+    host.set_text(source);
+    let cursor = cursor_at(source, "current");
+    assert_eq!(host.type_at(cursor), type_at(source, cursor, &ctx()));
+}
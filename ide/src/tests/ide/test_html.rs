@@ -0,0 +1,66 @@
+use crate::{HtmlPalette, HtmlTheme, render_html};
+use analyzer::semantic::{Context, Ty};
+
+fn ctx() -> Context {
+    Context::builder()
+        .prop("Done", Ty::Boolean)
+        .with_builtins()
+        .build()
+}
+
+#[test]
+fn highlights_tokens_with_their_byte_ranges() {
+    let html = render_html(
+        r#"if(prop("Done"), 1 < 2, "a&b")"#,
+        &ctx(),
+        &HtmlTheme::default(),
+    );
+    assert_eq!(
+        html,
+        concat!(
+            r#"<pre class="nf-formula"><code>"#,
+            r#"<span class="nf-function" data-start="0" data-end="2">if</span>("#,
+            r#"<span class="nf-function" data-start="3" data-end="7">prop</span>("#,
+            r#"<span class="nf-property" data-start="8" data-end="14">&quot;Done&quot;</span>), "#,
+            r#"<span class="nf-number" data-start="17" data-end="18">1</span> "#,
+            r#"<span class="nf-operator" data-start="19" data-end="20">&lt;</span> "#,
+            r#"<span class="nf-number" data-start="21" data-end="22">2</span>, "#,
+            r#"<span class="nf-string" data-start="24" data-end="29">&quot;a&amp;b&quot;</span>)"#,
+            "</code></pre>",
+        )
+    );
+}
+
+#[test]
+fn underlines_diagnostics_with_their_messages() {
+    let html = render_html(r#"prop("Nope") + 1"#, &ctx(), &HtmlTheme::default());
+    assert!(html.contains(concat!(
+        r#"<span class="nf-property nf-error" data-start="5" data-end="11" "#,
+        r#"title="Unknown property: Nope">&quot;Nope&quot;</span>"#,
+    )));
+    // An empty span at the end underlines the char before it.
+    let html = render_html("1 +", &ctx(), &HtmlTheme::default());
+    assert!(html.contains(r#"<span class="nf-operator nf-error" data-start="2" data-end="3""#));
+}
+
+#[test]
+fn palettes_are_written_inline() {
+    let html = render_html("1 +", &ctx(), &HtmlTheme::dark());
+    let dark = HtmlPalette::DARK;
+    assert!(html.starts_with(&format!(
+        r#"<pre class="nf-formula" style="color:{};background:{}"><code>"#,
+        dark.foreground, dark.background
+    )));
+    assert!(html.contains(&format!(
+        r#"style="color:{};text-decoration:underline wavy {};"#,
+        dark.operator, dark.error
+    )));
+    let theme = HtmlTheme {
+        class_prefix: "f-".into(),
+        palette: None,
+    };
+    assert_eq!(
+        render_html("x", &ctx(), &theme),
+        r#"<pre class="f-formula"><code><span class="f-variable" data-start="0" data-end="1">x</span></code></pre>"#
+    );
+}
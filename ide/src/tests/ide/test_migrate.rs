@@ -0,0 +1,169 @@
+use crate::{MigrationNoteKind, apply_edits, migrate_legacy};
+use analyzer::Span;
+use analyzer::semantic::{Context, Property, Ty};
+
+fn ctx() -> Context {
+    let property = |name: &str, ty: Ty| Property {
+        name: name.to_string(),
+        ty,
+        disabled_reason: None,
+        options: Vec::new(),
+    };
+    Context::with_builtins(vec![
+        property("Price", Ty::Number),
+        property("Title", Ty::String),
+        property("Tags", Ty::List(Box::new(Ty::String))),
+    ])
+}
+
+/// The migrated source, checking that the edits produce it too.
+fn migrated(source: &str) -> String {
+    let migration = migrate_legacy(source, &ctx());
+    let applied = apply_edits(source, migration.edits, 0).unwrap().source;
+    assert_eq!(applied, migration.source, "edits disagree with source");
+    migration.source
+}
+
+fn notes(source: &str) -> Vec<(MigrationNoteKind, &str, String)> {
+    migrate_legacy(source, &ctx())
+        .notes
+        .into_iter()
+        .map(|n| {
+            let text = &source[n.span.start as usize..n.span.end as usize];
+            (n.kind, text, n.message)
+        })
+        .collect()
+}
+
+#[test]
+fn operator_functions_become_operators() {
+    assert_eq!(migrated(r#"add(prop("Price"), 1)"#), r#"prop("Price") + 1"#);
+    assert_eq!(migrated("larger(1, 2)"), "1 > 2");
+    assert_eq!(
+        migrated("and(true, or(false, true))"),
+        "true && (false || true)"
+    );
+    assert_eq!(migrated("unaryMinus(5)"), "-5");
+    assert_eq!(migrated("unaryPlus(\"5\")"), "toNumber(\"5\")");
+}
+
+#[test]
+fn parenthesizes_only_where_precedence_requires() {
+    assert_eq!(migrated("add(add(1, 2), 3)"), "1 + 2 + 3");
+    assert_eq!(migrated("add(1, add(2, 3))"), "1 + (2 + 3)");
+    assert_eq!(migrated("multiply(add(1, 2), 3)"), "(1 + 2) * 3");
+    assert_eq!(migrated("add(multiply(1, 2), 3)"), "1 * 2 + 3");
+    assert_eq!(migrated("pow(2, pow(3, 2))"), "2 ^ 3 ^ 2");
+    assert_eq!(migrated("unaryMinus(add(1, 2))"), "-(1 + 2)");
+    assert_eq!(migrated("add(1, 2) * 3"), "(1 + 2) * 3");
+    assert_eq!(migrated("add(1, 2).format()"), "(1 + 2).format()");
+    assert_eq!(migrated("if(add(1, 2) > 2, 1, 0)"), "if(1 + 2 > 2, 1, 0)");
+    assert_eq!(migrated("multiply(true ? 1 : 2, 3)"), "(true ? 1 : 2) * 3");
+}
+
+#[test]
+fn text_slice_and_concat_use_2_0_functions() {
+    assert_eq!(
+        migrated(r#"slice(prop("Title"), 0, 3)"#),
+        r#"substring(prop("Title"), 0, 3)"#
+    );
+    assert_eq!(
+        migrated(r#"prop("Title").slice(1)"#),
+        r#"prop("Title").substring(1)"#
+    );
+    assert_eq!(
+        migrated(r#"slice(prop("Tags"), 1)"#),
+        r#"slice(prop("Tags"), 1)"#
+    );
+    assert_eq!(
+        migrated(r#"concat("a", prop("Title"), "c")"#),
+        r#""a" + prop("Title") + "c""#
+    );
+    assert_eq!(
+        migrated(r#"concat(prop("Tags"), ["x"])"#),
+        r#"concat(prop("Tags"), ["x"])"#
+    );
+}
+
+#[test]
+fn prop_quoting_quirks_are_normalized() {
+    assert_eq!(migrated("prop(Price) + 1"), r#"prop("Price") + 1"#);
+    assert_eq!(migrated("prop('Price') + 1"), r#"prop("Price") + 1"#);
+    assert_eq!(
+        migrated(r#"add(prop('Say "hi"'), 1)"#),
+        r#"prop("Say \"hi\"") + 1"#
+    );
+    assert_eq!(
+        migrated(r#""prop('x')" // prop('y')"#),
+        r#""prop('x')" // prop('y')"#
+    );
+}
+
+#[test]
+fn keeps_comments_and_layout_outside_rewrites() {
+    assert_eq!(
+        migrated("/* total */ add(\n  prop(\"Price\"), // base\n  1\n)"),
+        "/* total */ add(\n  prop(\"Price\"), // base\n  1\n)"
+    );
+    assert_eq!(
+        migrated("if(\n  true, // yes\n  add(1, 2),\n  0\n)"),
+        "if(\n  true, // yes\n  1 + 2,\n  0\n)"
+    );
+}
+
+#[test]
+fn reports_rewrites_and_review_items_in_input_coordinates() {
+    let source = r#"add(prop('Price'), 1) + slice(prop("Missing"), 1) + add(1, 2, 3)"#;
+    assert_eq!(
+        notes(source),
+        [
+            (
+                MigrationNoteKind::Rewritten,
+                "add(prop('Price'), 1)",
+                "`add(…)` rewritten to `+`".into()
+            ),
+            (
+                MigrationNoteKind::Rewritten,
+                "'Price'",
+                "single-quoted property name rewritten with double quotes".into()
+            ),
+            (
+                MigrationNoteKind::NeedsReview,
+                r#"slice(prop("Missing"), 1)"#,
+                "`slice` on a value of unknown type; use `substring` if it is text".into()
+            ),
+            (
+                MigrationNoteKind::NeedsReview,
+                "add(1, 2, 3)",
+                "`add` takes 2 arguments; not rewritten".into()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn syntax_errors_are_reported_not_migrated() {
+    let migration = migrate_legacy("add(1, 2) +", &ctx());
+    assert_eq!(migration.source, "add(1, 2) +");
+    assert!(migration.edits.is_empty());
+    assert!(!migration.notes.is_empty());
+    assert!(
+        migration
+            .notes
+            .iter()
+            .all(|n| n.kind == MigrationNoteKind::NeedsReview
+                && n.message.starts_with("syntax error: ")),
+        "{:?}",
+        migration.notes
+    );
+    assert_eq!(migration.notes[0].span, Span { start: 10, end: 11 });
+}
+
+#[test]
+fn modern_formulas_are_unchanged() {
+    let source = "if(prop(\"Price\") > 10, \"high\", \"low\")  // ok\n";
+    let migration = migrate_legacy(source, &ctx());
+    assert_eq!(migration.source, source);
+    assert!(migration.edits.is_empty());
+    assert!(migration.notes.is_empty());
+}
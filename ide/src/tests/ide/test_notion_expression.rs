@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use crate::{NotionExpressionError, from_notion_expression, to_notion_expression};
+use analyzer::semantic::NotionPropertySchema;
+
+fn schema() -> BTreeMap<String, NotionPropertySchema> {
+    serde_json::from_value(serde_json::json!({
+        "Price": { "id": "%3AUPp", "type": "number", "number": {} },
+        "Say \"hi\"": { "id": "title", "type": "title", "title": {} },
+        "Total": {
+            "id": "xY1z",
+            "type": "formula",
+            "formula": { "expression": "{{notion:block_property:%3AUPp:00000000-0000-0000-0000-000000000000:1b2c}} * 2" },
+        },
+        "Draft": { "type": "checkbox", "checkbox": {} },
+    }))
+    .unwrap()
+}
+
+#[test]
+fn placeholders_become_prop_calls() {
+    let schema = schema();
+    let expression = &schema["Total"].formula.as_ref().unwrap().expression;
+    assert_eq!(
+        from_notion_expression(expression, &schema).as_deref(),
+        Ok(r#"prop("Price") * 2"#)
+    );
+    assert_eq!(
+        from_notion_expression(r#"{{notion:block_property:title}} + "{{x}}""#, &schema).as_deref(),
+        Ok(r#"prop("Say \"hi\"") + "{{x}}""#)
+    );
+}
+
+#[test]
+fn prop_calls_become_placeholders() {
+    let schema = schema();
+    assert_eq!(
+        to_notion_expression(
+            r#"if(prop("Price") > 1, prop("Say \"hi\""), "prop(\"Price\")")"#,
+            &schema
+        )
+        .as_deref(),
+        Ok(
+            r#"if({{notion:block_property:%3AUPp}} > 1, {{notion:block_property:title}}, "prop(\"Price\")")"#
+        )
+    );
+}
+
+#[test]
+fn round_trips() {
+    let schema = schema();
+    let source = "prop(\"Price\") * 2 // doubled\n";
+    let expression = to_notion_expression(source, &schema).unwrap();
+    assert_eq!(
+        from_notion_expression(&expression, &schema).as_deref(),
+        Ok(source)
+    );
+}
+
+#[test]
+fn reports_unresolvable_references() {
+    let schema = schema();
+    assert_eq!(
+        from_notion_expression("{{notion:block_property:nope}}", &schema),
+        Err(NotionExpressionError::UnknownPropertyId("nope".into()))
+    );
+    assert_eq!(
+        from_notion_expression("1 + {{notion:block_property:title", &schema),
+        Err(NotionExpressionError::UnterminatedReference { offset: 4 })
+    );
+    assert_eq!(
+        to_notion_expression(r#"prop("Missing")"#, &schema),
+        Err(NotionExpressionError::UnknownProperty("Missing".into()))
+    );
+    // No id in the schema payload.
+    assert_eq!(
+        to_notion_expression(r#"prop("Draft")"#, &schema),
+        Err(NotionExpressionError::UnknownProperty("Draft".into()))
+    );
+}
@@ -0,0 +1,68 @@
+use crate::{apply_edits, code_actions, normalize_pasted};
+use analyzer::Span;
+use analyzer::semantic::Context;
+
+fn normalized(source: &str) -> String {
+    let edits = normalize_pasted(source);
+    apply_edits(source, edits, 0).unwrap().source
+}
+
+#[test]
+fn normalize_pasted_maps_lookalikes_to_ascii() {
+    assert_eq!(
+        normalized("if(prop(\u{201C}Done\u{201D}),\u{00A0}1\u{00A0}\u{2212}\u{00A0}2, 0)"),
+        r#"if(prop("Done"), 1 - 2, 0)"#
+    );
+    assert_eq!(
+        normalized("sum\u{FF08}1\u{FF0C}\u{FF12}\u{FF09}\u{200B} \u{00D7} 3 \u{2264} 4"),
+        "sum(1,2) * 3 <= 4"
+    );
+    assert_eq!(normalized("prop(\u{2018}Name\u{2019})"), r#"prop("Name")"#);
+    assert!(normalize_pasted("1 + 2").is_empty());
+}
+
+#[test]
+fn normalize_pasted_keeps_literals_and_comments() {
+    // ASCII strings and comments keep their text.
+    let source = "\"\u{201C}a\u{00A0}b\u{201D}\" /* \u{2014} */ // \u{00D7}";
+    assert!(normalize_pasted(source).is_empty());
+    // Smart-quoted strings get ASCII quotes only; the apostrophe is content.
+    assert_eq!(
+        normalized("\u{201C}Today\u{2019}s\u{00A0}tasks\u{201D} + \u{201C}!\u{201D}"),
+        "\"Today\u{2019}s\u{00A0}tasks\" + \"!\""
+    );
+}
+
+#[test]
+fn code_actions_offer_replacing_all_pasted_characters() {
+    let source = "prop(\u{201C}Title\u{201D})\u{00A0}+\u{00A0}1";
+    let ctx = Context::with_builtins(Vec::new());
+    let at = |cursor: u32| {
+        code_actions(
+            source,
+            Span {
+                start: cursor,
+                end: cursor,
+            },
+            &ctx,
+        )
+    };
+
+    let titles: Vec<String> = at(5).into_iter().map(|action| action.title).collect();
+    assert_eq!(
+        titles,
+        [
+            "Replace with '\"'",
+            "Replace all 4 pasted characters with ASCII"
+        ]
+    );
+    // Past the first lookalike the lexer stopped, but the fix-all still applies.
+    let actions = at(source.len() as u32 - 1);
+    assert_eq!(actions.len(), 1);
+    let fixed = apply_edits(source, actions[0].edits.clone(), 0)
+        .unwrap()
+        .source;
+    assert_eq!(fixed, r#"prop("Title") + 1"#);
+    // Away from any lookalike, nothing.
+    assert!(at(0).is_empty());
+}
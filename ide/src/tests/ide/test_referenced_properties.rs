@@ -0,0 +1,47 @@
+use analyzer::Span;
+
+use crate::{PropertyReference, referenced_properties};
+
+fn names(source: &str) -> Vec<String> {
+    referenced_properties(source)
+        .into_iter()
+        .map(|r| r.name)
+        .collect()
+}
+
+#[test]
+fn dedupes_names_in_first_reference_order() {
+    let source = r#"prop("Price") * 2 + prop("Tax") - prop("Price")"#;
+    assert_eq!(
+        referenced_properties(source),
+        [
+            PropertyReference {
+                name: "Price".into(),
+                spans: vec![Span { start: 5, end: 12 }, Span { start: 39, end: 46 }],
+            },
+            PropertyReference {
+                name: "Tax".into(),
+                spans: vec![Span { start: 25, end: 30 }],
+            },
+        ]
+    );
+}
+
+#[test]
+fn walks_nested_expressions_and_unescapes_names() {
+    let source = r#"if(prop("Done"), [prop("A \"b\"")].length(), prop("Done").format())"#;
+    assert_eq!(names(source), ["Done", r#"A "b""#]);
+}
+
+#[test]
+fn ignores_non_literal_and_malformed_references() {
+    assert!(names(r#"prop("A" + "B")"#).is_empty());
+    assert!(names(r#"prop("A", "B")"#).is_empty());
+    assert!(names(r#""A".prop()"#).is_empty());
+    assert!(names(r#"format("prop")"#).is_empty());
+}
+
+#[test]
+fn recovers_references_from_incomplete_input() {
+    assert_eq!(names(r#"prop("A") + prop("B") *"#), ["A", "B"]);
+}
@@ -0,0 +1,33 @@
+use crate::{TextEdit, apply_edits, rename};
+
+fn renamed(source: &str, cursor_at: &str, new_name: &str) -> Option<String> {
+    let cursor = source.find(cursor_at).expect("cursor marker in source") as u32;
+    let edits: Vec<TextEdit> = rename(source, cursor, new_name)?;
+    Some(apply_edits(source, edits, 0).unwrap().source)
+}
+
+#[test]
+fn renames_every_reference_to_the_property() {
+    let source = r#"prop("Price") * 2 + prop("Tax") - prop("Price")"#;
+    assert_eq!(
+        renamed(source, "Price", "Cost").as_deref(),
+        Some(r#"prop("Cost") * 2 + prop("Tax") - prop("Cost")"#)
+    );
+}
+
+#[test]
+fn escapes_the_new_name() {
+    let source = r#"prop("A")"#;
+    assert_eq!(
+        renamed(source, "A", r#"Say "hi" \ there"#).as_deref(),
+        Some(r#"prop("Say \"hi\" \\ there")"#)
+    );
+}
+
+#[test]
+fn non_property_symbols_are_not_renamable() {
+    let source = r#"if(true, "Price", prop("Price"))"#;
+    assert_eq!(renamed(source, "if", "x"), None);
+    assert_eq!(renamed(source, "\"Price\",", "x"), None);
+    assert_eq!(renamed(source, "true", "x"), None);
+}
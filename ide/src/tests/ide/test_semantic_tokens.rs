@@ -0,0 +1,128 @@
+use crate::{
+    MODIFIER_DEFAULT_LIBRARY, PositionEncoding, SEMANTIC_TOKEN_TYPES, SemanticTokenKind as K,
+    encode_semantic_tokens_lsp, semantic_tokens,
+};
+use analyzer::semantic::Context;
+
+fn ctx() -> Context {
+    Context::with_builtins(Vec::new())
+}
+
+/// Renders tokens as `(text, kind, modifiers)`.
+fn classified(source: &str) -> Vec<(String, K, u32)> {
+    semantic_tokens(source, &ctx())
+        .into_iter()
+        .map(|t| {
+            let text = source[t.span.start as usize..t.span.end as usize].to_string();
+            (text, t.kind, t.modifiers)
+        })
+        .collect()
+}
+
+#[test]
+fn classifies_calls_properties_literals_and_operators() {
+    let source = r#"if(prop("A") > 1, "x", not true).length() + foo(y)"#;
+    let lib = MODIFIER_DEFAULT_LIBRARY;
+    assert_eq!(
+        classified(source),
+        vec![
+            ("if".to_string(), K::Function, lib),
+            ("prop".to_string(), K::Function, lib),
+            (r#""A""#.to_string(), K::Property, 0),
+            (">".to_string(), K::Operator, 0),
+            ("1".to_string(), K::Number, 0),
+            (r#""x""#.to_string(), K::String, 0),
+            ("not".to_string(), K::Keyword, 0),
+            ("true".to_string(), K::Keyword, 0),
+            ("length".to_string(), K::Method, lib),
+            ("+".to_string(), K::Operator, 0),
+            ("foo".to_string(), K::Function, 0),
+            ("y".to_string(), K::Variable, 0),
+        ]
+    );
+}
+
+#[test]
+fn comments_are_classified_and_do_not_break_neighbors() {
+    let source = "f /* c */ (1) // end";
+    let kinds: Vec<K> = classified(source).into_iter().map(|(_, k, _)| k).collect();
+    assert_eq!(kinds, vec![K::Function, K::Comment, K::Number, K::Comment]);
+}
+
+#[test]
+fn legend_matches_kind_discriminants() {
+    assert_eq!(SEMANTIC_TOKEN_TYPES[K::Function as usize], "function");
+    assert_eq!(SEMANTIC_TOKEN_TYPES[K::Comment as usize], "comment");
+}
+
+#[test]
+fn lsp_encoding_uses_line_deltas_and_utf16_columns() {
+    let source = "\"😀\" +\n  1";
+    let data = encode_semantic_tokens_lsp(
+        source,
+        &semantic_tokens(source, &ctx()),
+        PositionEncoding::Utf16,
+    );
+    assert_eq!(
+        data,
+        vec![
+            0,
+            0,
+            4,
+            K::String as u32,
+            0, //
+            0,
+            5,
+            1,
+            K::Operator as u32,
+            0, //
+            1,
+            2,
+            1,
+            K::Number as u32,
+            0,
+        ]
+    );
+}
+
+#[test]
+fn lsp_encoding_splits_multiline_block_comments() {
+    let source = "/* a\nbc */ 1";
+    let data = encode_semantic_tokens_lsp(
+        source,
+        &semantic_tokens(source, &ctx()),
+        PositionEncoding::Utf16,
+    );
+    assert_eq!(
+        data,
+        vec![
+            0,
+            0,
+            4,
+            K::Comment as u32,
+            0, //
+            1,
+            0,
+            5,
+            K::Comment as u32,
+            0, //
+            0,
+            6,
+            1,
+            K::Number as u32,
+            0,
+        ]
+    );
+}
+
+#[test]
+fn lsp_encoding_can_use_utf8_columns() {
+    let source = "\"😀\" + 1";
+    let data = encode_semantic_tokens_lsp(
+        source,
+        &semantic_tokens(source, &ctx()),
+        PositionEncoding::Utf8,
+    );
+    assert_eq!(&data[..3], &[0, 0, 6]);
+    assert_eq!(&data[5..8], &[0, 7, 1]);
+}
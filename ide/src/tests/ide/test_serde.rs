@@ -0,0 +1,49 @@
+use analyzer::semantic::{Context, Ty};
+
+use crate::{CompletionConfig, CompletionItem, CompletionKind, HelpResult, SignatureHelp, help};
+
+fn ctx() -> Context {
+    Context::builder()
+        .prop("Price", Ty::Number)
+        .with_builtins()
+        .build()
+}
+
+#[test]
+fn completion_item_serializes_edits_and_kind() {
+    let result = help("Pri", 3, &ctx(), CompletionConfig::default());
+    let item = result
+        .completion
+        .items
+        .iter()
+        .find(|item| item.kind == CompletionKind::Property)
+        .expect("property item");
+    let value = serde_json::to_value(item).unwrap();
+
+    assert_eq!(value["kind"], "Property");
+    assert_eq!(value["label"], "Price");
+    assert_eq!(
+        value["data"],
+        serde_json::json!({ "PropExpr": { "property_name": "Price" } })
+    );
+    let back: CompletionItem = serde_json::from_value(value).unwrap();
+    assert_eq!(&back, item);
+}
+
+#[test]
+fn help_result_round_trips_with_signature_help() {
+    let source = "if(true, ";
+    let result = help(source, source.len(), &ctx(), CompletionConfig::default());
+    let signature = result.signature_help.clone().expect("signature help");
+    let value = serde_json::to_value(&signature).unwrap();
+
+    assert_eq!(value["active_parameter"], 1);
+    assert_eq!(value["signatures"][0]["segments"][0]["kind"], "Name");
+    assert_eq!(
+        serde_json::from_value::<SignatureHelp>(value).unwrap(),
+        signature
+    );
+
+    let value = serde_json::to_value(&result).unwrap();
+    assert_eq!(serde_json::from_value::<HelpResult>(value).unwrap(), result);
+}
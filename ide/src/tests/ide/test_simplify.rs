@@ -0,0 +1,165 @@
+use crate::{apply_edits, simplify, simplify_all};
+use analyzer::semantic::{Context, Ty};
+
+fn ctx() -> Context {
+    Context::builder()
+        .prop("Done", Ty::Boolean)
+        .prop("Late", Ty::Boolean)
+        .prop("Price", Ty::Number)
+        .prop("Title", Ty::String)
+        .with_builtins()
+        .build()
+}
+
+/// Each suggestion as `(title, source with only it applied)`.
+fn suggestions(source: &str) -> Vec<(String, String)> {
+    simplify(source, &ctx())
+        .into_iter()
+        .map(|action| {
+            let applied = apply_edits(source, action.edits, 0).unwrap().source;
+            (action.title, applied)
+        })
+        .collect()
+}
+
+fn simplified(source: &str) -> String {
+    apply_edits(source, simplify_all(source, &ctx()), 0)
+        .unwrap()
+        .source
+}
+
+#[test]
+fn folds_constant_operations() {
+    assert_eq!(
+        suggestions("prop(\"Price\") * (60 * 60)"),
+        [(
+            "Fold to `3600`".to_string(),
+            "prop(\"Price\") * 3600".to_string()
+        )]
+    );
+    assert_eq!(simplified(r#""a" + "b" == "ab""#), "true");
+    assert_eq!(simplified("1 - 3"), "-2");
+    assert_eq!(simplified("(1 - 3) ^ 2"), "4");
+    assert_eq!(simplified("(1 + 2).format()"), "3.format()");
+    assert_eq!(simplified("(1 - 3).abs()"), "(-2).abs()");
+}
+
+#[test]
+fn keeps_constants_that_would_grow_or_fail() {
+    assert_eq!(suggestions("0.1 + 0.2"), []);
+    assert_eq!(suggestions("1 / 0"), []);
+    assert_eq!(suggestions("-1"), []);
+    assert_eq!(suggestions("(1)"), []);
+}
+
+#[test]
+fn removes_double_negation_of_booleans() {
+    assert_eq!(simplified(r#"!!prop("Done")"#), r#"prop("Done")"#);
+    assert_eq!(simplified(r#"not not prop("Done")"#), r#"prop("Done")"#);
+    assert_eq!(
+        simplified(r#"!(!(prop("Price") > 1)) && prop("Late")"#),
+        r#"prop("Price") > 1 && prop("Late")"#
+    );
+    assert_eq!(
+        simplified(r#"!(!(prop("Price") > 1 || prop("Done"))) && prop("Late")"#),
+        r#"(prop("Price") > 1 || prop("Done")) && prop("Late")"#
+    );
+    assert_eq!(suggestions(r#"!!prop("Price")"#), []);
+}
+
+#[test]
+fn boolean_conditionals_become_their_condition() {
+    assert_eq!(
+        simplified(r#"if(prop("Price") > 10, true, false)"#),
+        r#"prop("Price") > 10"#
+    );
+    assert_eq!(
+        simplified(r#"if(prop("Price") > 10, false, true) || prop("Done")"#),
+        r#"!(prop("Price") > 10) || prop("Done")"#
+    );
+    assert_eq!(
+        simplified(r#"(prop("Done") ? true : false) && prop("Late")"#),
+        r#"prop("Done") && prop("Late")"#
+    );
+    assert_eq!(suggestions(r#"if(prop("Done"), true, true)"#), []);
+}
+
+#[test]
+fn de_morgan_collects_negations() {
+    assert_eq!(
+        suggestions(r#"!prop("Done") && !prop("Late")"#),
+        [(
+            "Apply De Morgan's law".to_string(),
+            r#"!(prop("Done") || prop("Late"))"#.to_string()
+        )]
+    );
+    assert_eq!(
+        simplified(r#"not prop("Done") || not (prop("Price") > 1 && prop("Late"))"#),
+        r#"not (prop("Done") && (prop("Price") > 1 && prop("Late")))"#
+    );
+    assert_eq!(
+        simplified(r#"not prop("Done") && not prop("Late")"#),
+        r#"not (prop("Done") || prop("Late"))"#
+    );
+    assert_eq!(
+        simplified(r#"and(not(prop("Done")), not prop("Late"))"#),
+        r#"not or(prop("Done"), prop("Late"))"#
+    );
+    assert_eq!(
+        simplified(r#"or(!prop("Done"), !(prop("Price") > 1))"#),
+        r#"!and(prop("Done"), prop("Price") > 1)"#
+    );
+}
+
+#[test]
+fn drops_parentheses_a_rewrite_no_longer_needs() {
+    assert_eq!(
+        simplified(r#"!(!prop("Done") || !prop("Late"))"#),
+        r#"prop("Done") && prop("Late")"#
+    );
+    assert_eq!(
+        simplified(r#"prop("Price") > 1 && (!prop("Done") || !prop("Late"))"#),
+        r#"prop("Price") > 1 && !(prop("Done") && prop("Late"))"#
+    );
+    assert_eq!(
+        simplified(r#"(if(prop("Done"), false, true)) || prop("Late")"#),
+        r#"!prop("Done") || prop("Late")"#
+    );
+    // Still needed, or holding a comment: only the contents change.
+    assert_eq!(
+        simplified(r#"(if(prop("Done"), true, false) || prop("Late")) && prop("Done")"#),
+        r#"(prop("Done") || prop("Late")) && prop("Done")"#
+    );
+    assert_eq!(
+        simplified(r#"prop("Late") && (/* c */ !prop("Done") || !prop("Late"))"#),
+        r#"prop("Late") && (/* c */ !(prop("Done") && prop("Late")))"#
+    );
+}
+
+#[test]
+fn nested_ifs_merge_into_one_ifs() {
+    let source = r#"if(prop("Price") > 100, "high", if(prop("Price") > 10, "mid", ifs(prop("Done"), "done", "low")))"#;
+    assert_eq!(
+        suggestions(source),
+        [(
+            "Merge nested conditions into `ifs`".to_string(),
+            r#"ifs(prop("Price") > 100, "high", prop("Price") > 10, "mid", prop("Done"), "done", "low")"#
+                .to_string()
+        )]
+    );
+    assert_eq!(
+        simplified(r#"if(prop("Done"), 1, (if(prop("Late"), 2 + 2, 3)))"#),
+        r#"ifs(prop("Done"), 1, prop("Late"), 4, 3)"#
+    );
+}
+
+#[test]
+fn skips_rewrites_that_drop_comments_or_have_syntax_errors() {
+    assert_eq!(suggestions("1 + /* one */ 1"), []);
+    assert_eq!(
+        suggestions(r#"if(prop("Done"), /* yes */ true, false)"#),
+        []
+    );
+    assert_eq!(suggestions("1 + 1 +"), []);
+    assert_eq!(simplify_all(r#"prop("Title")"#, &ctx()), []);
+}
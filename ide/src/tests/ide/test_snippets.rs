@@ -0,0 +1,144 @@
+use crate::{
+    CompletionConfig, CompletionKind, Snippet, SnippetError, SnippetRegistry, help, snippets,
+};
+use analyzer::Span;
+use analyzer::semantic::{Context, Ty};
+
+fn ctx() -> Context {
+    Context::builder()
+        .prop("Progress", Ty::Number)
+        .prop("Due", Ty::Date)
+        .prop("Name", Ty::String)
+        .with_builtins()
+        .build()
+}
+
+fn config() -> CompletionConfig {
+    CompletionConfig {
+        snippets: SnippetRegistry::common(),
+        ..CompletionConfig::default()
+    }
+}
+
+#[test]
+fn templates_link_repeated_placeholders() {
+    let snippet = Snippet::new(
+        "pct",
+        "Percent",
+        r#"round(prop("${P}") * 100) + "${%}" + "$""#,
+    )
+    .unwrap();
+    assert_eq!(snippet.body, r#"round(prop("P") * 100) + "%" + "$""#);
+    let ranges: Vec<Vec<Span>> = snippet
+        .placeholders
+        .iter()
+        .map(|p| p.ranges.clone())
+        .collect();
+    assert_eq!(
+        ranges,
+        [
+            vec![Span { start: 12, end: 13 }],
+            vec![Span { start: 26, end: 27 }]
+        ]
+    );
+
+    let repeated = Snippet::new("twice", "", "${x} + ${x}").unwrap();
+    assert_eq!(repeated.placeholders.len(), 1);
+    assert_eq!(repeated.placeholders[0].ranges.len(), 2);
+
+    assert_eq!(
+        Snippet::new("bad", "", "1 + ${x").unwrap_err(),
+        SnippetError::UnterminatedPlaceholder { offset: 4 }
+    );
+    assert_eq!(
+        Snippet::new("bad", "", "${}").unwrap_err(),
+        SnippetError::EmptyPlaceholder { offset: 0 }
+    );
+}
+
+#[test]
+fn common_snippets_type_check() {
+    for snippet in SnippetRegistry::common().iter() {
+        let result = analyzer::analyze(&snippet.body, &ctx());
+        assert!(
+            result.diagnostics.is_empty(),
+            "{}: {:?}",
+            snippet.name,
+            result.diagnostics
+        );
+    }
+}
+
+#[test]
+fn snippets_complete_at_expression_start_only() {
+    let kinds = |source: &str, config: CompletionConfig| -> Vec<String> {
+        help(source, source.len(), &ctx(), config)
+            .completion
+            .items
+            .into_iter()
+            .filter(|item| item.kind == CompletionKind::Snippet)
+            .map(|item| item.label)
+            .collect()
+    };
+    assert_eq!(
+        kinds("", config()),
+        ["progressBar", "dueCountdown", "initials"]
+    );
+    assert_eq!(kinds("1 + ", config()).len(), 3);
+    assert!(kinds("1 ", config()).is_empty());
+    assert!(kinds("", CompletionConfig::default()).is_empty());
+}
+
+#[test]
+fn snippet_items_insert_with_placeholders() {
+    let source = "1 + prog";
+    let result = help(source, source.len(), &ctx(), config());
+    let item = result
+        .completion
+        .items
+        .iter()
+        .find(|item| item.label == "progressBar")
+        .unwrap();
+    assert_eq!(
+        item.detail.as_deref(),
+        Some("Ten-dot progress bar and percentage of a 0-1 number")
+    );
+    let edit = item.primary_edit.as_ref().unwrap();
+    assert_eq!(edit.range, Span { start: 4, end: 8 });
+    assert_eq!(item.cursor, Some(4 + edit.new_text.len() as u32));
+
+    // Ranges are in the document after the edit.
+    let edited = format!("1 + {}", edit.new_text);
+    let placeholder = &item.placeholders[0];
+    assert_eq!(placeholder.ranges.len(), 3);
+    for range in &placeholder.ranges {
+        assert_eq!(
+            &edited[range.start as usize..range.end as usize],
+            "Progress"
+        );
+    }
+    assert_eq!(
+        item.snippet_text().unwrap(),
+        concat!(
+            r#"repeat("●", round(prop("${1:Progress}") * 10)) + "#,
+            r#"repeat("○", 10 - round(prop("${1:Progress}") * 10)) + " " + "#,
+            r#"format(round(prop("${1:Progress}") * 100)) + "%""#,
+        )
+    );
+}
+
+#[test]
+fn browsing_matches_names_then_descriptions() {
+    let config = config();
+    let names = |query: &str| -> Vec<&str> {
+        snippets(&config, query)
+            .into_iter()
+            .map(|snippet| snippet.name.as_str())
+            .collect()
+    };
+    assert_eq!(names(""), ["progressBar", "dueCountdown", "initials"]);
+    assert_eq!(names("due"), ["dueCountdown"]);
+    // `initials` matches by its description only.
+    assert_eq!(names("name"), ["initials"]);
+    assert!(names("zzz").is_empty());
+}
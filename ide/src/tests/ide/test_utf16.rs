@@ -0,0 +1,88 @@
+use crate::{
+    CompletionConfig, EditError, TextEditUtf16, apply_edits_utf16, byte_to_utf16_offset,
+    format_utf16, help, help_utf16, text_edits_from_utf16, utf16_to_byte_offset,
+};
+use analyzer::Span;
+use analyzer::semantic::{Context, Property, Ty};
+
+fn edit(start: u32, end: u32, new_text: &str) -> TextEditUtf16 {
+    TextEditUtf16 {
+        range: Span { start, end },
+        new_text: new_text.to_string(),
+    }
+}
+
+#[test]
+fn offsets_convert_and_floor_inside_surrogate_pairs() {
+    let source = "a😀b";
+    assert_eq!(utf16_to_byte_offset(source, 1), 1);
+    assert_eq!(utf16_to_byte_offset(source, 2), 1);
+    assert_eq!(utf16_to_byte_offset(source, 3), 5);
+    assert_eq!(utf16_to_byte_offset(source, 99), source.len() as u32);
+
+    assert_eq!(byte_to_utf16_offset(source, 5), 3);
+    assert_eq!(byte_to_utf16_offset(source, 3), 1);
+    assert_eq!(byte_to_utf16_offset(source, 99), 4);
+}
+
+#[test]
+fn text_edits_from_utf16_reports_index_of_bad_edit() {
+    let source = "a😀b";
+    let err = text_edits_from_utf16(source, vec![edit(0, 1, "x"), edit(2, 3, "y")])
+        .expect_err("expected mid-surrogate error");
+    assert_eq!(
+        err,
+        EditError::EditNotOnCharBoundary {
+            index: 1,
+            offset: 2
+        }
+    );
+
+    let err =
+        text_edits_from_utf16(source, vec![edit(3, 5, "")]).expect_err("expected range error");
+    assert_eq!(err, EditError::InvalidEditRange { index: 0 });
+}
+
+#[test]
+fn apply_edits_utf16_uses_utf16_cursor_and_ranges() {
+    let out = apply_edits_utf16("😀a", vec![edit(2, 3, "bc")], 3).expect("expected edits to apply");
+    assert_eq!(out.source, "😀bc");
+    assert_eq!(out.cursor, 4);
+}
+
+#[test]
+fn format_utf16_validates_cursor_and_formats() {
+    let err = format_utf16("\"😀\"", 5).expect_err("expected invalid cursor");
+    assert_eq!(err, EditError::InvalidCursor);
+
+    let out = format_utf16("\"😀\"+1", 4).expect("expected formatted output");
+    assert_eq!(out.source, "\"😀\" + 1\n");
+}
+
+#[test]
+fn help_utf16_matches_byte_help_in_utf16_coordinates() {
+    let ctx = Context::with_builtins(vec![Property {
+        name: "prop".to_string(),
+        ty: Ty::Number,
+        disabled_reason: None,
+        options: Vec::new(),
+    }]);
+    let source = "\"😀\" + pr";
+
+    let bytes = help(source, source.len(), &ctx, CompletionConfig::default());
+    let utf16 = help_utf16(source, 9, &ctx, CompletionConfig::default());
+
+    assert_eq!(bytes.completion.replace, Span { start: 9, end: 11 });
+    assert_eq!(utf16.completion.replace, Span { start: 7, end: 9 });
+    assert_eq!(bytes.completion.items.len(), utf16.completion.items.len());
+
+    let item = utf16
+        .completion
+        .items
+        .iter()
+        .find(|item| item.label == "prop")
+        .expect("expected prop item");
+    let primary = item.primary_edit.as_ref().expect("expected primary edit");
+    assert_eq!(primary.range, Span { start: 7, end: 9 });
+    assert_eq!(item.cursor, Some(7 + primary.new_text.len() as u32));
+}
@@ -0,0 +1,169 @@
+use crate::{TypeChange, Workspace};
+use analyzer::semantic::{Context, PropertyLookup, Ty};
+use analyzer::{DiagnosticCode, Span};
+
+fn ctx_with(props: &[(&str, Ty)]) -> Context {
+    props
+        .iter()
+        .fold(Context::builder(), |builder, (name, ty)| {
+            builder.prop(*name, ty.clone())
+        })
+        .with_builtins()
+        .build()
+}
+
+fn change(name: &str, before: Option<Ty>, after: Option<Ty>) -> TypeChange {
+    TypeChange {
+        name: name.to_string(),
+        before,
+        after,
+    }
+}
+
+fn circular_messages(workspace: &Workspace, name: &str) -> Vec<(String, Span)> {
+    workspace
+        .formula_diagnostics(name)
+        .unwrap()
+        .into_iter()
+        .filter(|diag| diag.code == DiagnosticCode::CircularReference)
+        .map(|diag| (diag.message, diag.span))
+        .collect()
+}
+
+#[test]
+fn workspace_types_formulas_after_the_formulas_they_read() {
+    let mut workspace = Workspace::new(ctx_with(&[("Price", Ty::Number)]));
+    workspace.set_formula("Label", r#"format(prop("Total"))"#);
+    workspace.set_formula("Total", r#"prop("Price") * prop("Quantity")"#);
+    workspace.set_formula("Quantity", "3");
+
+    assert_eq!(workspace.output_type("Total"), Some(&Ty::Number));
+    assert_eq!(workspace.output_type("Label"), Some(&Ty::String));
+    assert_eq!(workspace.dependencies("Total"), vec!["Quantity"]);
+    assert_eq!(workspace.dependents("Total"), vec!["Label"]);
+    assert_eq!(workspace.evaluation_order(), ["Quantity", "Total", "Label"]);
+    assert!(workspace.cycles().is_empty());
+    assert!(
+        workspace.diagnostics().values().all(Vec::is_empty),
+        "{:?}",
+        workspace.diagnostics()
+    );
+
+    // Hosts see every formula as a property, e.g. for completion.
+    let host = workspace.host("Label").unwrap();
+    assert_eq!(host.context().lookup("Quantity"), Some(Ty::Number));
+    assert_eq!(host.output_type(), &Ty::String);
+}
+
+#[test]
+fn workspace_reports_cascading_type_changes() {
+    let mut workspace = Workspace::new(ctx_with(&[]));
+    assert_eq!(
+        workspace.set_formula("Total", "1 + 2"),
+        vec![change("Total", None, Some(Ty::Number))]
+    );
+    workspace.set_formula("Copy", r#"prop("Total")"#);
+    workspace.set_formula("Rounded", r#"round(prop("Total"))"#);
+    workspace.set_formula("Other", "true");
+
+    let changes = workspace.set_formula("Total", r#""three""#);
+    assert_eq!(
+        changes,
+        vec![
+            change("Total", Some(Ty::Number), Some(Ty::String)),
+            change("Copy", Some(Ty::Number), Some(Ty::String)),
+        ]
+    );
+    // `Other` is untouched; `Rounded` keeps its type but now reports the mismatch.
+    let diagnostics = workspace.diagnostics();
+    assert_eq!(diagnostics.len(), 4);
+    assert!(!diagnostics["Rounded"].is_empty());
+    assert!(diagnostics["Copy"].is_empty());
+
+    // Editing without changing the type reports nothing.
+    assert!(workspace.set_formula("Total", r#""four""#).is_empty());
+
+    assert_eq!(
+        workspace.remove_formula("Total"),
+        vec![
+            change("Total", Some(Ty::String), None),
+            change("Copy", Some(Ty::String), Some(Ty::Unknown)),
+        ]
+    );
+    assert!(workspace.remove_formula("Total").is_empty());
+    assert_eq!(workspace.dependents("Total"), Vec::<&str>::new());
+}
+
+#[test]
+fn workspace_reports_cycles() {
+    let mut workspace = Workspace::new(ctx_with(&[]));
+    workspace.set_formula("A", r#"prop("B") + 1"#);
+    workspace.set_formula("B", r#"prop("A") + prop("A")"#);
+    workspace.set_formula("C", r#"prop("C")"#);
+    workspace.set_formula("D", r#"prop("A")"#);
+
+    assert_eq!(
+        workspace.cycles(),
+        [
+            vec!["A".to_string(), "B".to_string()],
+            vec!["C".to_string()]
+        ]
+    );
+    assert_eq!(workspace.evaluation_order(), ["A", "B", "C", "D"]);
+    assert_eq!(workspace.output_type("A"), Some(&Ty::Unknown));
+    assert_eq!(workspace.output_type("C"), Some(&Ty::Unknown));
+
+    let message = "Circular reference: A → B → A".to_string();
+    assert_eq!(
+        circular_messages(&workspace, "A"),
+        vec![(message, Span { start: 5, end: 8 })]
+    );
+    let message = "Circular reference: B → A → B".to_string();
+    assert_eq!(
+        circular_messages(&workspace, "B"),
+        vec![
+            (message.clone(), Span { start: 5, end: 8 }),
+            (message, Span { start: 17, end: 20 }),
+        ]
+    );
+    let message = "Circular reference: C → C".to_string();
+    assert_eq!(
+        circular_messages(&workspace, "C"),
+        vec![(message, Span { start: 5, end: 8 })]
+    );
+    // Reading a cycle is not part of it.
+    assert!(circular_messages(&workspace, "D").is_empty());
+
+    // Breaking the cycle types both formulas again.
+    let changes = workspace.set_formula("B", "2");
+    assert_eq!(
+        changes,
+        vec![
+            change("B", Some(Ty::Unknown), Some(Ty::Number)),
+            change("A", Some(Ty::Unknown), Some(Ty::Number)),
+            change("D", Some(Ty::Unknown), Some(Ty::Number)),
+        ]
+    );
+    assert_eq!(workspace.cycles(), [vec!["C".to_string()]]);
+    assert!(circular_messages(&workspace, "A").is_empty());
+}
+
+#[test]
+fn workspace_formulas_win_over_context_properties_and_resolve_leniently() {
+    let mut context = ctx_with(&[("Total", Ty::String)]);
+    context.property_lookup = PropertyLookup::Lenient;
+    let mut workspace = Workspace::new(context);
+    workspace.set_formula("Total", "1");
+    workspace.set_formula("Copy", r#"prop(" total")"#);
+
+    assert_eq!(workspace.dependencies("Copy"), vec!["Total"]);
+    assert_eq!(workspace.output_type("Copy"), Some(&Ty::Number));
+    assert_eq!(workspace.context().lookup("Total"), Some(Ty::String));
+
+    let changes = workspace.set_context(ctx_with(&[("Total", Ty::String)]));
+    assert_eq!(
+        changes,
+        vec![change("Copy", Some(Ty::Number), Some(Ty::Unknown))]
+    );
+    assert!(workspace.dependencies("Copy").is_empty());
+}
@@ -1,8 +1,11 @@
 use analyzer::TextEdit;
 
+use crate::EditError;
+
 /// Applies byte-offset text edits and rebases a byte cursor through them.
 ///
-/// The caller must provide non-overlapping edits sorted by `(start, end)`.
+/// Edits must be non-overlapping and sorted by `(start, end)`; otherwise an `EditError` naming
+/// the offending edit index is returned and nothing is applied.
 /// Edits are applied in descending order to avoid shifting later offsets.
 ///
 /// Cursor rules:
@@ -12,6 +15,55 @@ pub fn apply_text_edits_bytes_with_cursor(
     source: &str,
     edits: &[TextEdit],
     cursor: u32,
+) -> Result<(String, u32), EditError> {
+    for (index, edit) in edits.iter().enumerate() {
+        validate_edit(source, index, edit)?;
+    }
+    check_edit_order(edits.iter().enumerate())?;
+    Ok(apply_text_edits_unchecked(source, edits, cursor))
+}
+
+/// Checks that edit `index` lies within `source` on char boundaries.
+pub(crate) fn validate_edit(source: &str, index: usize, edit: &TextEdit) -> Result<(), EditError> {
+    let (start, end) = (edit.range.start, edit.range.end);
+    if start > end || end as usize > source.len() {
+        return Err(EditError::InvalidEditRange { index });
+    }
+    for offset in [start, end] {
+        if !source.is_char_boundary(offset as usize) {
+            return Err(EditError::EditNotOnCharBoundary { index, offset });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `(index, edit)` pairs are sorted by `(start, end)` and do not overlap.
+pub(crate) fn check_edit_order<'e>(
+    edits: impl IntoIterator<Item = (usize, &'e TextEdit)>,
+) -> Result<(), EditError> {
+    let mut prev: Option<(usize, &TextEdit)> = None;
+    for (index, edit) in edits {
+        if let Some((prev_index, prev_edit)) = prev {
+            if edit.range.start < prev_edit.range.start {
+                return Err(EditError::UnsortedEdits { index });
+            }
+            if edit.range.start < prev_edit.range.end {
+                return Err(EditError::OverlappingEdits {
+                    first: prev_index.min(index),
+                    second: prev_index.max(index),
+                });
+            }
+        }
+        prev = Some((index, edit));
+    }
+    Ok(())
+}
+
+/// Applies already-validated edits (see [`apply_text_edits_bytes_with_cursor`]).
+pub(crate) fn apply_text_edits_unchecked(
+    source: &str,
+    edits: &[TextEdit],
+    cursor: u32,
 ) -> (String, u32) {
     let mut updated = source.to_string();
     let mut cursor = cursor;
@@ -0,0 +1,261 @@
+//! UTF-16 position encoding for editor hosts.
+//!
+//! Core IDE APIs use UTF-8 byte offsets. Editors (LSP, Monaco, CodeMirror, N-API bindings)
+//! usually count UTF-16 code units. These helpers convert at the boundary so every host shares
+//! one implementation.
+//!
+//! Offset conversion clamps out-of-range values and floors offsets inside a character, so it
+//! never panics. Edit conversion is strict and reports invalid edits as `EditError`.
+
+use analyzer::{Span, TextEdit};
+
+use crate::edit::{ApplyResult, EditError};
+use crate::{CompletionItem, HelpResult};
+
+/// A text edit whose `range` is in UTF-16 code units (or host units, for
+/// [`PositionEncoding::text_edits_to_byte`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEditUtf16 {
+    pub range: Span,
+    pub new_text: String,
+}
+
+/// Unit for offsets exchanged with a host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionEncoding {
+    /// UTF-8 bytes (the core representation).
+    Utf8,
+    /// UTF-16 code units (JS strings, LSP default).
+    Utf16,
+}
+
+impl PositionEncoding {
+    /// Convert a host offset into a byte offset, clamping and flooring to a char boundary.
+    pub fn to_byte(self, source: &str, offset: u32) -> u32 {
+        match self {
+            PositionEncoding::Utf8 => floor_char_boundary(source, offset),
+            PositionEncoding::Utf16 => utf16_to_byte_offset(source, offset),
+        }
+    }
+
+    /// Convert a byte offset into a host offset (flooring to a char boundary).
+    pub fn from_byte(self, source: &str, byte: u32) -> u32 {
+        match self {
+            PositionEncoding::Utf8 => floor_char_boundary(source, byte),
+            PositionEncoding::Utf16 => byte_to_utf16_offset(source, byte),
+        }
+    }
+
+    /// Convert a byte span into host offsets.
+    pub fn span_from_byte(self, source: &str, span: Span) -> Span {
+        Span {
+            start: self.from_byte(source, span.start),
+            end: self.from_byte(source, span.end),
+        }
+    }
+
+    /// Length of `source` in host units.
+    pub fn source_len(self, source: &str) -> u32 {
+        self.from_byte(source, source.len() as u32)
+    }
+
+    /// Convert a host cursor to a byte cursor, rejecting cursors past the end of `source`.
+    pub fn cursor_to_byte(self, source: &str, cursor: u32) -> Result<u32, EditError> {
+        if cursor > self.source_len(source) {
+            return Err(EditError::InvalidCursor);
+        }
+        Ok(self.to_byte(source, cursor))
+    }
+
+    /// Convert host edits to byte edits (see [`text_edits_from_utf16`] for the UTF-16 rules).
+    ///
+    /// UTF-8 edits pass through unchanged; `apply_edits` validates them.
+    pub fn text_edits_to_byte(
+        self,
+        source: &str,
+        edits: Vec<TextEditUtf16>,
+    ) -> Result<Vec<TextEdit>, EditError> {
+        match self {
+            PositionEncoding::Utf8 => Ok(edits
+                .into_iter()
+                .map(|edit| TextEdit {
+                    range: edit.range,
+                    new_text: edit.new_text,
+                })
+                .collect()),
+            PositionEncoding::Utf16 => text_edits_from_utf16(source, edits),
+        }
+    }
+}
+
+fn floor_char_boundary(source: &str, byte: u32) -> u32 {
+    let mut byte = (byte as usize).min(source.len());
+    while !source.is_char_boundary(byte) {
+        byte -= 1;
+    }
+    byte as u32
+}
+
+/// Convert a UTF-16 code unit offset into a UTF-8 byte offset.
+///
+/// Out-of-range values are clamped. If the offset lands inside a scalar's UTF-16 encoding (for
+/// example, inside a surrogate pair), it is floored to the scalar start.
+pub fn utf16_to_byte_offset(source: &str, utf16: u32) -> u32 {
+    analyzer::utf16_offset_to_byte(source, utf16)
+}
+
+/// Convert a UTF-8 byte offset into a UTF-16 code unit offset.
+///
+/// If `byte` is not a UTF-8 char boundary, it is floored to the previous boundary.
+/// If `byte` is past the end, this returns the UTF-16 length of `source`.
+pub fn byte_to_utf16_offset(source: &str, byte: u32) -> u32 {
+    analyzer::byte_to_utf16_offset(source, byte)
+}
+
+/// Convert a byte span to a UTF-16 span (each end floored to a char boundary).
+pub fn byte_span_to_utf16(source: &str, span: Span) -> Span {
+    Span {
+        start: byte_to_utf16_offset(source, span.start),
+        end: byte_to_utf16_offset(source, span.end),
+    }
+}
+
+/// Convert a UTF-16 cursor to a byte cursor, rejecting cursors past the end of `source`.
+pub fn utf16_cursor_to_byte(source: &str, cursor_utf16: u32) -> Result<u32, EditError> {
+    if cursor_utf16 as usize > source.encode_utf16().count() {
+        return Err(EditError::InvalidCursor);
+    }
+    Ok(utf16_to_byte_offset(source, cursor_utf16))
+}
+
+/// Convert UTF-16 edits to byte edits.
+///
+/// Unlike offset conversion, this is strict: ranges must lie within `source` and must not split
+/// a surrogate pair. Errors carry the index of the offending edit.
+pub fn text_edits_from_utf16(
+    source: &str,
+    edits: Vec<TextEditUtf16>,
+) -> Result<Vec<TextEdit>, EditError> {
+    let utf16_len = source.encode_utf16().count();
+
+    let mut byte_edits = Vec::with_capacity(edits.len());
+    for (index, edit) in edits.into_iter().enumerate() {
+        let Span { start, end } = edit.range;
+        if end < start || end as usize > utf16_len {
+            return Err(EditError::InvalidEditRange { index });
+        }
+
+        let start_byte = utf16_to_byte_offset(source, start);
+        let end_byte = utf16_to_byte_offset(source, end);
+
+        // Offsets inside a surrogate pair floor to the char start; reject them instead.
+        for (offset, byte) in [(start, start_byte), (end, end_byte)] {
+            if byte_to_utf16_offset(source, byte) != offset {
+                return Err(EditError::EditNotOnCharBoundary { index, offset });
+            }
+        }
+
+        byte_edits.push(TextEdit {
+            range: Span {
+                start: start_byte,
+                end: end_byte,
+            },
+            new_text: edit.new_text,
+        });
+    }
+
+    Ok(byte_edits)
+}
+
+/// Convert a byte edit to a UTF-16 edit.
+pub fn text_edit_to_utf16(source: &str, edit: &TextEdit) -> TextEditUtf16 {
+    TextEditUtf16 {
+        range: byte_span_to_utf16(source, edit.range),
+        new_text: edit.new_text.clone(),
+    }
+}
+
+/// UTF-16 cursor in the document after accepting `item`, or `None` when it has no primary edit
+/// or its edits cannot be applied.
+///
+/// The byte `item.cursor` is a position after the primary edit only; this also shifts it by
+/// additional edits placed before the primary edit.
+pub fn completion_cursor_utf16(source: &str, item: &CompletionItem) -> Option<u32> {
+    completion_cursor(source, item, PositionEncoding::Utf16)
+}
+
+/// Like [`completion_cursor_utf16`], in the given encoding.
+pub fn completion_cursor(
+    source: &str,
+    item: &CompletionItem,
+    encoding: PositionEncoding,
+) -> Option<u32> {
+    let primary_edit = item.primary_edit.as_ref()?;
+
+    let mut edits = Vec::with_capacity(1 + item.additional_edits.len());
+    edits.push(primary_edit.clone());
+    edits.extend(item.additional_edits.iter().cloned());
+    edits.sort_by(|a, b| {
+        a.range
+            .start
+            .cmp(&b.range.start)
+            .then(a.range.end.cmp(&b.range.end))
+    });
+
+    let mut cursor_byte: i64 = i64::from(item.cursor.unwrap_or_else(|| {
+        primary_edit
+            .range
+            .start
+            .saturating_add(primary_edit.new_text.len() as u32)
+    }));
+    for edit in &item.additional_edits {
+        if edit.range.end <= primary_edit.range.start {
+            let replaced_len = edit.range.end.saturating_sub(edit.range.start) as i64;
+            let inserted_len = edit.new_text.len() as i64;
+            cursor_byte = cursor_byte.saturating_add(inserted_len.saturating_sub(replaced_len));
+        }
+    }
+
+    let (updated, _) = crate::apply_text_edits_bytes_with_cursor(source, &edits, 0).ok()?;
+    let cursor_byte = u32::try_from(cursor_byte)
+        .unwrap_or(0)
+        .min(updated.len() as u32);
+    Some(encoding.from_byte(&updated, cursor_byte))
+}
+
+/// Re-express an `ApplyResult` computed on `source` in UTF-16 code units.
+///
+/// The cursor refers to the updated source; diagnostic spans refer to `source`.
+pub(crate) fn apply_result_to_utf16(source: &str, mut result: ApplyResult) -> ApplyResult {
+    result.cursor = byte_to_utf16_offset(&result.source, result.cursor);
+    for diag in &mut result.diagnostics {
+        diag.span = byte_span_to_utf16(source, diag.span);
+        for label in &mut diag.labels {
+            label.span = byte_span_to_utf16(source, label.span);
+        }
+        for edit in diag.actions.iter_mut().flat_map(|action| &mut action.edits) {
+            edit.range = byte_span_to_utf16(source, edit.range);
+        }
+    }
+    result
+}
+
+/// Re-express a `HelpResult` computed on `source` in UTF-16 code units.
+///
+/// Item cursors become positions in the document after accepting the item (see
+/// [`completion_cursor_utf16`]).
+pub(crate) fn help_result_to_utf16(source: &str, mut result: HelpResult) -> HelpResult {
+    let completion = &mut result.completion;
+    completion.replace = byte_span_to_utf16(source, completion.replace);
+    for item in &mut completion.items {
+        item.cursor = completion_cursor_utf16(source, item);
+        for edit in item
+            .primary_edit
+            .iter_mut()
+            .chain(&mut item.additional_edits)
+        {
+            edit.range = byte_span_to_utf16(source, edit.range);
+        }
+    }
+    result
+}
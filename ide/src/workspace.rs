@@ -0,0 +1,403 @@
+//! Many named formulas over one `Context`, such as the formula properties of a database.
+//!
+//! Each formula is also a property: `prop("Name")` in one formula reads another formula's output.
+//! The workspace builds this property-reference graph, types each formula after the formulas it
+//! reads, and reports reference cycles. Coordinates are UTF-8 byte offsets.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use analyzer::semantic::{Context, Property, Ty};
+use analyzer::{Diagnostic, DiagnosticCode, DiagnosticKind, Span, TypeMap};
+
+use crate::AnalysisHost;
+
+/// A formula whose output type changed, as returned by [`Workspace`] updates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeChange {
+    pub name: String,
+    /// `None` when the formula was added.
+    pub before: Option<Ty>,
+    /// `None` when the formula was removed.
+    pub after: Option<Ty>,
+}
+
+/// Holds named formulas and the context they share, keeping each formula's analysis current as
+/// formulas and the context change.
+///
+/// Formulas are the properties of their names: for every formula, the context it is analyzed
+/// with has one property per formula, typed by that formula's output (over a context property of
+/// the same name). Names in `prop("...")` resolve as [`Context::property`] does, so lenient
+/// lookup also finds formulas. Formulas in a reference cycle are typed `Unknown`, and each
+/// `prop("...")` closing a cycle gets a `CircularReference` error.
+///
+/// Every update re-resolves the graph from the memoized syntax of each formula's
+/// [`AnalysisHost`]; hosts whose text and context stay equal keep their memos.
+#[derive(Debug)]
+pub struct Workspace {
+    context: Context,
+    formulas: BTreeMap<String, Formula>,
+    /// Formula names, each after the formulas it reads.
+    order: Vec<String>,
+    cycles: Vec<Vec<String>>,
+}
+
+#[derive(Debug)]
+struct Formula {
+    host: AnalysisHost,
+    /// Formulas read by this one, with the spans of the literals naming them.
+    dependencies: BTreeMap<String, Vec<Span>>,
+    output_type: Ty,
+    cycle_diagnostics: Vec<Diagnostic>,
+}
+
+impl Workspace {
+    /// An empty workspace over `context`.
+    pub fn new(context: Context) -> Self {
+        Self {
+            context,
+            formulas: BTreeMap::new(),
+            order: Vec::new(),
+            cycles: Vec::new(),
+        }
+    }
+
+    /// The shared context, without the formula properties.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Replaces the shared context, returning the formulas whose output type changed.
+    pub fn set_context(&mut self, context: Context) -> Vec<TypeChange> {
+        let before = self.output_types();
+        self.context = context;
+        self.update(before)
+    }
+
+    /// Adds the formula `name` or replaces its source, returning the formulas whose output type
+    /// changed (this one and those reading it, directly or not) in evaluation order.
+    pub fn set_formula(
+        &mut self,
+        name: impl Into<String>,
+        source: impl Into<String>,
+    ) -> Vec<TypeChange> {
+        let before = self.output_types();
+        let formula = self.formulas.entry(name.into()).or_insert_with(|| Formula {
+            host: AnalysisHost::new(self.context.clone()),
+            dependencies: BTreeMap::new(),
+            output_type: Ty::Unknown,
+            cycle_diagnostics: Vec::new(),
+        });
+        formula.host.set_text(source);
+        self.update(before)
+    }
+
+    /// Removes the formula `name`, returning the formulas whose output type changed, including
+    /// the removed one (empty when there is no such formula).
+    pub fn remove_formula(&mut self, name: &str) -> Vec<TypeChange> {
+        let before = self.output_types();
+        if self.formulas.remove(name).is_none() {
+            return Vec::new();
+        }
+        self.update(before)
+    }
+
+    /// Formula names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.formulas.keys().map(String::as_str)
+    }
+
+    /// Source of the formula `name`.
+    pub fn formula(&self, name: &str) -> Option<&str> {
+        Some(self.formulas.get(name)?.host.text())
+    }
+
+    /// The host analyzing the formula `name` with the formula properties, for editor queries
+    /// (completion, hover, ...).
+    pub fn host(&self, name: &str) -> Option<&AnalysisHost> {
+        Some(&self.formulas.get(name)?.host)
+    }
+
+    /// Output type of the formula `name`; `Unknown` in a reference cycle.
+    pub fn output_type(&self, name: &str) -> Option<&Ty> {
+        Some(&self.formulas.get(name)?.output_type)
+    }
+
+    /// Formulas read directly by the formula `name`, sorted.
+    pub fn dependencies(&self, name: &str) -> Vec<&str> {
+        self.formulas.get(name).map_or_else(Vec::new, |formula| {
+            formula.dependencies.keys().map(String::as_str).collect()
+        })
+    }
+
+    /// Formulas reading the formula `name` directly, sorted.
+    pub fn dependents(&self, name: &str) -> Vec<&str> {
+        self.formulas
+            .iter()
+            .filter(|(_, formula)| formula.dependencies.contains_key(name))
+            .map(|(dependent, _)| dependent.as_str())
+            .collect()
+    }
+
+    /// Formula names, each after the formulas it reads; members of a cycle are adjacent, sorted.
+    pub fn evaluation_order(&self) -> &[String] {
+        &self.order
+    }
+
+    /// Sets of formulas reading each other in a cycle (a formula reading itself is one), each
+    /// sorted, in evaluation order.
+    pub fn cycles(&self) -> &[Vec<String>] {
+        &self.cycles
+    }
+
+    /// Diagnostics of the formula `name`: its host's, then `CircularReference` errors.
+    pub fn formula_diagnostics(&self, name: &str) -> Option<Vec<Diagnostic>> {
+        let formula = self.formulas.get(name)?;
+        let mut diagnostics = formula.host.diagnostics().to_vec();
+        diagnostics.extend(formula.cycle_diagnostics.iter().cloned());
+        Some(diagnostics)
+    }
+
+    /// [`Workspace::formula_diagnostics`] of every formula, by name.
+    pub fn diagnostics(&self) -> BTreeMap<String, Vec<Diagnostic>> {
+        self.names()
+            .filter_map(|name| Some((name.to_string(), self.formula_diagnostics(name)?)))
+            .collect()
+    }
+
+    fn output_types(&self) -> BTreeMap<String, Ty> {
+        self.formulas
+            .iter()
+            .map(|(name, formula)| (name.clone(), formula.output_type.clone()))
+            .collect()
+    }
+
+    /// The shared context with a property per formula, typed from `types` (`Unknown` if absent).
+    fn context_with(&self, types: &BTreeMap<String, Ty>) -> Context {
+        let mut context = self.context.clone();
+        let formulas = self.formulas.keys().map(|name| Property {
+            name: name.clone(),
+            ty: types.get(name).cloned().unwrap_or(Ty::Unknown),
+            disabled_reason: None,
+            options: Vec::new(),
+        });
+        // Listed first, so they win over context properties of the same name.
+        let mut properties: Vec<Property> = formulas.collect();
+        properties.extend(self.context.properties.iter().cloned());
+        context.properties = properties.into();
+        context
+    }
+
+    /// Re-resolves the graph, types, and cycle diagnostics, and hands every host the context with
+    /// the formula properties. `before` holds the output types before the change.
+    fn update(&mut self, before: BTreeMap<String, Ty>) -> Vec<TypeChange> {
+        // 1) Resolve `prop("...")` names as the hosts will, keeping those naming formulas.
+        let lookup = self.context_with(&BTreeMap::new());
+        let names: Vec<String> = self.formulas.keys().cloned().collect();
+        for formula in self.formulas.values_mut() {
+            let root = &formula.host.syntax().expr;
+            let mut dependencies: BTreeMap<String, Vec<Span>> = BTreeMap::new();
+            for reference in crate::references::referenced_properties(root) {
+                if let Some(property) = lookup.property(&reference.name)
+                    && names.binary_search(&property.name).is_ok()
+                {
+                    let spans = dependencies.entry(property.name.clone()).or_default();
+                    spans.extend(reference.spans);
+                    spans.sort_by_key(|span| span.start);
+                }
+            }
+            formula.dependencies = dependencies;
+        }
+
+        // 2) Order the formulas and find cycles.
+        let graph: Vec<Vec<usize>> = self
+            .formulas
+            .values()
+            .map(|formula| {
+                let dependencies = formula.dependencies.keys();
+                dependencies
+                    .map(|name| names.binary_search(name).expect("a formula name"))
+                    .collect()
+            })
+            .collect();
+        let components = components(&graph);
+        let mut component_of = vec![0; names.len()];
+        for (index, component) in components.iter().enumerate() {
+            component
+                .iter()
+                .for_each(|&node| component_of[node] = index);
+        }
+        let cyclic =
+            |node: usize| components[component_of[node]].len() > 1 || graph[node].contains(&node);
+        let named = |nodes: &[usize]| nodes.iter().map(|&node| names[node].clone()).collect();
+        self.order = named(&components.concat());
+        self.cycles = components
+            .iter()
+            .filter(|component| cyclic(component[0]))
+            .map(|component| named(component))
+            .collect();
+
+        // 3) Type each formula after those it reads.
+        let mut types: BTreeMap<String, Ty> = BTreeMap::new();
+        for &node in components.iter().flatten() {
+            let ty = if cyclic(node) {
+                Ty::Unknown
+            } else {
+                let context = self.context_with(&types);
+                let root = &self.formulas[&names[node]].host.syntax().expr;
+                analyzer::infer_expr_with_map(root, &context, &mut TypeMap::default())
+            };
+            types.insert(names[node].clone(), ty);
+        }
+
+        // 4) Hand out the final context and report cycles.
+        let context = self.context_with(&types);
+        for (node, (name, formula)) in self.formulas.iter_mut().enumerate() {
+            formula.host.set_context(context.clone());
+            formula.output_type = types[name].clone();
+            formula.cycle_diagnostics = Vec::new();
+            for (dependency, spans) in &formula.dependencies {
+                let target = names.binary_search(dependency).expect("a formula name");
+                if component_of[target] != component_of[node] {
+                    continue;
+                }
+                let mut path = vec![name.as_str()];
+                path.extend(
+                    shortest_path(&graph, target, node)
+                        .into_iter()
+                        .map(|node| names[node].as_str()),
+                );
+                let message = format!("Circular reference: {}", path.join(" → "));
+                formula.cycle_diagnostics.extend(
+                    spans
+                        .iter()
+                        .map(|&span| circular_reference(message.clone(), span)),
+                );
+            }
+        }
+
+        // 5) Removed formulas first, then changed ones in evaluation order.
+        let removed = before
+            .iter()
+            .filter(|(name, _)| !self.formulas.contains_key(*name))
+            .map(|(name, ty)| TypeChange {
+                name: name.clone(),
+                before: Some(ty.clone()),
+                after: None,
+            });
+        let changed = self.order.iter().filter_map(|name| {
+            let after = &types[name];
+            (before.get(name) != Some(after)).then(|| TypeChange {
+                name: name.clone(),
+                before: before.get(name).cloned(),
+                after: Some(after.clone()),
+            })
+        });
+        removed.chain(changed).collect()
+    }
+}
+
+fn circular_reference(message: String, span: Span) -> Diagnostic {
+    Diagnostic {
+        kind: DiagnosticKind::Error,
+        code: DiagnosticCode::CircularReference,
+        message,
+        span,
+        labels: vec![],
+        notes: vec![],
+        actions: vec![],
+    }
+}
+
+/// Strongly connected components of `graph` (Tarjan's algorithm), each sorted and listed after
+/// the components its edges lead to.
+fn components(graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct Tarjan<'a> {
+        graph: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        low: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next: usize,
+        out: Vec<Vec<usize>>,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, node: usize) {
+            self.index[node] = Some(self.next);
+            self.low[node] = self.next;
+            self.next += 1;
+            self.stack.push(node);
+            self.on_stack[node] = true;
+            let graph = self.graph;
+            for &next in &graph[node] {
+                match self.index[next] {
+                    None => {
+                        self.visit(next);
+                        self.low[node] = self.low[node].min(self.low[next]);
+                    }
+                    Some(index) if self.on_stack[next] => {
+                        self.low[node] = self.low[node].min(index);
+                    }
+                    Some(_) => {}
+                }
+            }
+            if Some(self.low[node]) == self.index[node] {
+                let mut component = Vec::new();
+                loop {
+                    let member = self.stack.pop().expect("the node is on the stack");
+                    self.on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                self.out.push(component);
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        graph,
+        index: vec![None; graph.len()],
+        low: vec![0; graph.len()],
+        on_stack: vec![false; graph.len()],
+        stack: Vec::new(),
+        next: 0,
+        out: Vec::new(),
+    };
+    for node in 0..graph.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.out
+}
+
+/// Nodes on a shortest path from `from` to `to` along `graph` edges, both included; `to` must be
+/// reachable.
+fn shortest_path(graph: &[Vec<usize>], from: usize, to: usize) -> Vec<usize> {
+    let mut previous: Vec<Option<usize>> = vec![None; graph.len()];
+    let mut seen = vec![false; graph.len()];
+    seen[from] = true;
+    let mut queue = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            break;
+        }
+        for &next in &graph[node] {
+            if !seen[next] {
+                seen[next] = true;
+                previous[next] = Some(node);
+                queue.push_back(next);
+            }
+        }
+    }
+    let mut path = vec![to];
+    while let Some(node) = previous[*path.last().expect("the path starts with `to`")] {
+        path.push(node);
+    }
+    path.reverse();
+    path
+}
@@ -0,0 +1,285 @@
+use ide::{CompletionConfig, CompletionKind, MatchMode, MatchStrength, RankingConfig};
+use notion_formula_testing::{Builtin, Func, Item, complete, ctx, t};
+
+#[test]
+fn call_parens_off_inserts_bare_function_names() {
+    let c = ctx().build();
+    let config = CompletionConfig {
+        call_parens: false,
+        ..CompletionConfig::default()
+    };
+
+    t("ab$0")
+        .ctx(c.clone())
+        .config(config.clone())
+        .expect_item_insert_text("abs()", "abs");
+    t(r#""x".rep$0"#)
+        .ctx(c)
+        .config(config)
+        .expect_item_insert_text(".repeat()", "repeat");
+}
+
+#[test]
+fn exclude_kinds_drops_matching_items() {
+    let c = ctx().build();
+
+    t("(1+1)$0")
+        .ctx(c)
+        .config(CompletionConfig {
+            exclude_kinds: vec![CompletionKind::Operator],
+            ..CompletionConfig::default()
+        })
+        .expect_not_contains(&[Item::Builtin(Builtin::EqEq), Item::Builtin(Builtin::Plus)])
+        .expect_postfix(Func::If);
+}
+
+#[test]
+fn exclude_kinds_separates_keywords_from_constants() {
+    let c = ctx().build();
+
+    t("$0")
+        .ctx(c)
+        .config(CompletionConfig {
+            exclude_kinds: vec![CompletionKind::Constant],
+            ..CompletionConfig::default()
+        })
+        .expect_not_contains(&[Item::Builtin(Builtin::True), Item::Builtin(Builtin::False)])
+        .expect_contains_builtins(&[Builtin::Not]);
+}
+
+#[test]
+fn type_ranking_off_keeps_expression_start_order_in_calls() {
+    let c = ctx().props_demo_basic().build();
+    let start = t("$0").ctx(c.clone()).items_kinds_labels();
+
+    let ranked = t("sum($0)").ctx(c.clone()).items_kinds_labels();
+    assert_ne!(ranked, start);
+
+    t("sum($0)")
+        .ctx(c)
+        .config(CompletionConfig {
+            type_ranking: false,
+            ..CompletionConfig::default()
+        })
+        .expect_items_kinds_labels(&start);
+}
+
+#[test]
+fn primary_edits_follow_configured_insert_text() {
+    let c = ctx().props_demo_basic().build();
+    for (source, call_parens) in [("if(tr", true), ("if(tr", false), (r#""x".rep"#, false)] {
+        let output = complete(
+            source,
+            source.len(),
+            &c,
+            CompletionConfig {
+                call_parens,
+                ..CompletionConfig::default()
+            },
+        );
+        assert!(!output.items.is_empty(), "{source:?}");
+        for item in &output.items {
+            let edit = item.primary_edit.as_ref();
+            if item.is_disabled {
+                assert_eq!(edit, None, "{}", item.label);
+            } else {
+                assert_eq!(
+                    edit.map(|edit| edit.new_text.as_str()),
+                    Some(item.insert_text.as_str()),
+                    "{}",
+                    item.label
+                );
+            }
+        }
+    }
+}
+
+fn preferred_labels(source: &str, match_mode: MatchMode) -> Vec<String> {
+    preferred_labels_with(
+        source,
+        CompletionConfig {
+            match_mode,
+            ..CompletionConfig::default()
+        },
+    )
+}
+
+fn preferred_labels_with(source: &str, config: CompletionConfig) -> Vec<String> {
+    let c = ctx().props_demo_basic().build();
+    let output = complete(
+        source,
+        source.len(),
+        &c,
+        CompletionConfig {
+            preferred_limit: 100,
+            ..config
+        },
+    );
+    output
+        .preferred_indices
+        .iter()
+        .map(|&idx| output.items[idx].label.clone())
+        .collect()
+}
+
+#[test]
+fn match_mode_controls_which_labels_a_query_matches() {
+    let fuzzy = preferred_labels("rep", MatchMode::Fuzzy);
+    assert!(fuzzy.contains(&"fromTimestamp()".to_string()), "{fuzzy:?}");
+    assert_eq!(
+        preferred_labels("rep", MatchMode::Contains),
+        ["repeat()", "replace()", "replaceAll()"]
+    );
+    assert_eq!(
+        preferred_labels("rep", MatchMode::Prefix),
+        ["repeat()", "replace()", "replaceAll()"]
+    );
+
+    let contains = preferred_labels("date", MatchMode::Contains);
+    assert!(
+        contains.contains(&"formatDate()".to_string()),
+        "{contains:?}"
+    );
+    let prefix = preferred_labels("date", MatchMode::Prefix);
+    assert!(!prefix.is_empty());
+    assert!(
+        prefix.iter().all(|label| label.starts_with("date")),
+        "{prefix:?}"
+    );
+}
+
+#[test]
+fn prefix_match_mode_orders_non_prefix_items_last() {
+    let c = ctx().build();
+
+    t("rep$0")
+        .ctx(c)
+        .config(CompletionConfig {
+            match_mode: MatchMode::Prefix,
+            ..CompletionConfig::default()
+        })
+        .expect_top_labels(&["repeat()", "replace()", "replaceAll()"])
+        .expect_order("replaceAll()", "fromTimestamp()");
+}
+
+fn boost_properties() -> CompletionConfig {
+    CompletionConfig {
+        ranking: RankingConfig {
+            kind_boosts: vec![(CompletionKind::Property, 10)],
+            ..RankingConfig::default()
+        },
+        ..CompletionConfig::default()
+    }
+}
+
+#[test]
+fn ranking_kind_boosts_prefer_properties_over_functions() {
+    let default = preferred_labels_with("t", CompletionConfig::default());
+    assert_ne!(
+        default.first().map(String::as_str),
+        Some("Title"),
+        "{default:?}"
+    );
+
+    let boosted = preferred_labels_with("t", boost_properties());
+    assert_eq!(
+        boosted.first().map(String::as_str),
+        Some("Title"),
+        "{boosted:?}"
+    );
+    assert_eq!(boosted.len(), default.len());
+
+    let c = ctx().props_demo_basic().build();
+    t("sum($0)")
+        .ctx(c)
+        .config(boost_properties())
+        .expect_top_labels(&["Age", "Title", "Flag"]);
+}
+
+#[test]
+fn ranking_preferred_min_match_drops_weaker_matches() {
+    let config = CompletionConfig {
+        ranking: RankingConfig {
+            preferred_min_match: MatchStrength::Prefix,
+            ..RankingConfig::default()
+        },
+        ..CompletionConfig::default()
+    };
+    assert_eq!(
+        preferred_labels_with("rep", config),
+        ["repeat()", "replace()", "replaceAll()"]
+    );
+}
+
+#[test]
+fn ranking_type_bonuses_reorder_call_argument_groups() {
+    let c = ctx().props_demo_basic().build();
+    let output = complete("sum(", 4, &c, CompletionConfig::default());
+    let first = output.items.first().expect("items");
+    assert_eq!(first.label, "Age");
+
+    let config = CompletionConfig {
+        ranking: RankingConfig {
+            type_match_bonus: -1,
+            type_mismatch_bonus: 2,
+            ..RankingConfig::default()
+        },
+        ..CompletionConfig::default()
+    };
+    let output = complete("sum(", 4, &c, config);
+    let first = output.items.first().expect("items");
+    assert_ne!(first.label, "Age");
+}
+
+fn raw() -> CompletionConfig {
+    CompletionConfig {
+        raw: true,
+        ..CompletionConfig::default()
+    }
+}
+
+fn labels(source: &str, config: CompletionConfig) -> Vec<String> {
+    let c = ctx().props_demo_basic().build();
+    complete(source, source.len(), &c, config)
+        .items
+        .into_iter()
+        .map(|item| item.label)
+        .collect()
+}
+
+#[test]
+fn raw_mode_keeps_catalog_order_and_attaches_match_strengths() {
+    let catalog = labels("", CompletionConfig::default());
+    assert_eq!(labels("rep", raw()), catalog);
+    assert_eq!(labels("sum(", raw()), catalog);
+    assert_ne!(labels("rep", CompletionConfig::default()), catalog);
+
+    let c = ctx().props_demo_basic().build();
+    let output = complete("rep", 3, &c, raw());
+    assert_eq!(output.preferred_indices, Vec::<usize>::new());
+    let strength = |label: &str| {
+        output
+            .items
+            .iter()
+            .find(|item| item.label == label)
+            .unwrap_or_else(|| panic!("missing {label}"))
+            .match_strength
+    };
+    assert_eq!(strength("repeat()"), Some(MatchStrength::Prefix));
+    assert_eq!(strength("fromTimestamp()"), Some(MatchStrength::Fuzzy));
+    assert_eq!(strength("abs()"), None);
+}
+
+#[test]
+fn raw_mode_keeps_non_matching_methods_after_dot() {
+    let raw_labels = labels(r#""x".rep"#, raw());
+    assert!(
+        raw_labels.iter().any(|label| label == ".split()"),
+        "{raw_labels:?}"
+    );
+    let ranked = labels(r#""x".rep"#, CompletionConfig::default());
+    assert!(
+        !ranked.iter().any(|label| label == ".split()"),
+        "{ranked:?}"
+    );
+}
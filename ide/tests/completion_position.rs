@@ -1,7 +1,7 @@
-use crate::CompletionConfig;
-use crate::tests::completion_dsl::{Builtin, Func, Item, Prop, ctx, t};
 use analyzer::Span;
 use analyzer::semantic::Ty;
+use ide::CompletionConfig;
+use notion_formula_testing::{Builtin, Func, Item, Prop, complete, ctx, t};
 
 #[test]
 fn completion_when_expecting_separator_in_call_shows_after_atom_operators() {
@@ -106,7 +106,7 @@ fn completion_ident_end_before_close_paren_treats_ident_as_query() {
 
     let source = "if(d)";
     let cursor = source.find(')').unwrap();
-    let out = crate::completion::complete(source, cursor, &c, CompletionConfig::default());
+    let out = complete(source, cursor, &c, CompletionConfig::default());
 
     assert_eq!(
         out.replace,
@@ -1,7 +1,7 @@
-use crate::completion::{CompletionConfig, CompletionData, complete};
-use crate::tests::completion_dsl::{Builtin, Func, Item, Prop, ctx, t};
 use analyzer::semantic::Ty;
 use analyzer::semantic::{Context, builtins_functions};
+use ide::{CompletionConfig, CompletionData};
+use notion_formula_testing::{Builtin, Func, Item, Prop, complete, ctx, t};
 
 #[test]
 fn completion_after_atom_postfix_if_requires_if_in_context() {
@@ -131,8 +131,11 @@ fn completion_ranking_exact_beats_contains() {
         }
     }
     let c = Context {
-        properties: Vec::new(),
-        functions: vec![replace_all.unwrap(), replace.unwrap()],
+        properties: Vec::new().into(),
+        functions: vec![replace_all.unwrap().clone(), replace.unwrap().clone()].into(),
+        property_resolver: None,
+        property_lookup: Default::default(),
+        limits: Default::default(),
     };
 
     t("replace$0")
@@ -1,4 +1,4 @@
-use crate::tests::completion_dsl::{ctx, t};
+use notion_formula_testing::{ctx, t};
 
 #[test]
 fn completion_signature_help_active_param_first_arg() {
@@ -311,3 +311,32 @@ fn signature_help_if_list_of_union_is_parenthesized() {
             "if(condition: boolean, then: number, else: (number | string)[]) -> number | (number | string)[]",
         );
 }
+
+#[test]
+fn signature_help_arg_types_survive_comments_and_newlines() {
+    let c = ctx().build();
+    t("if(\n  true, // condition\n  [1, 2],\n  $0")
+        .ctx(c)
+        .expect_sig_active(2)
+        .expect_sig_label("if(condition: boolean, then: number[], else: number[]) -> number[]");
+}
+
+#[test]
+fn standalone_signature_help_matches_help() {
+    let c = ctx().build();
+    for (source, cursor) in [
+        ("if(true, ", 9),
+        ("if(true, sum(1,2,3), ", 21),
+        ("[1, 2].map(", 11),
+        ("true.sum(", 9),
+        ("sum", 3),
+        ("", 0),
+    ] {
+        let help = ide::help(source, cursor, &c, ide::CompletionConfig::default());
+        assert_eq!(
+            ide::signature_help(source, cursor, &c),
+            help.signature_help,
+            "{source:?} at {cursor}"
+        );
+    }
+}
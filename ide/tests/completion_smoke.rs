@@ -1,6 +1,6 @@
-use crate::completion::CompletionData;
-use crate::tests::completion_dsl::{Builtin, Func, Item, Prop, ctx, t};
 use analyzer::semantic::Ty;
+use ide::CompletionData;
+use notion_formula_testing::{Builtin, Func, Item, Prop, ctx, t};
 
 #[test]
 fn completion_at_document_start() {
@@ -260,3 +260,61 @@ fn completion_ignore_props_filters_property_items() {
         .expect_contains_funcs(&[Func::If, Func::Sum])
         .expect_replace_contains_cursor();
 }
+
+#[test]
+fn help_timed_matches_help_and_times_completion() {
+    let ctx = ctx().build();
+    let mut t = 0.0;
+    let mut now = || {
+        t += 1.0;
+        t
+    };
+    let (out, timings) =
+        ide::help_timed("if(tr", 5, &ctx, ide::CompletionConfig::default(), &mut now);
+    assert_eq!(
+        out,
+        ide::help("if(tr", 5, &ctx, ide::CompletionConfig::default())
+    );
+    assert_eq!(
+        (timings.lex, timings.parse, timings.infer, timings.complete),
+        (1.0, 1.0, 0.0, 1.0)
+    );
+}
+
+#[test]
+fn help_instrumented_reports_phases_and_counts_items() {
+    #[derive(Default)]
+    struct Recording {
+        t: f64,
+        phases: Vec<ide::Phase>,
+        items: Option<usize>,
+    }
+    impl ide::Instrumentation for Recording {
+        fn now(&mut self) -> f64 {
+            self.t += 1.0;
+            self.t
+        }
+        fn phase(&mut self, phase: ide::Phase, _duration: f64, input_len: usize) {
+            assert_eq!(input_len, 5);
+            self.phases.push(phase);
+        }
+        fn counter(&mut self, name: &'static str, value: usize) {
+            if name == "completion_items" {
+                self.items = Some(value);
+            }
+        }
+    }
+
+    let ctx = ctx().build();
+    let mut recording = Recording::default();
+    let out = ide::help_instrumented(
+        "if(tr",
+        5,
+        &ctx,
+        ide::CompletionConfig::default(),
+        &mut recording,
+    );
+    use ide::Phase::*;
+    assert_eq!(recording.phases, [Lex, Parse, Complete]);
+    assert_eq!(recording.items, Some(out.completion.items.len()));
+}
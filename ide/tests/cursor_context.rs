@@ -0,0 +1,80 @@
+use analyzer::Span;
+use analyzer::semantic::Context;
+use ide::context::{CallContext, CursorContext, PositionKind, detect_cursor_context};
+
+/// `$0` marks the cursor.
+fn at(marked: &str) -> CursorContext {
+    let cursor = marked.find("$0").expect("cursor marker");
+    let source = marked.replace("$0", "");
+    let tokens = analyzer::analyze_syntax(&source).tokens;
+    let ctx = Context::with_builtins(Vec::new());
+    detect_cursor_context(&source, &tokens, cursor as u32, &ctx)
+}
+
+#[test]
+fn position_kinds() {
+    assert_eq!(at("$0").position_kind, PositionKind::NeedExpr);
+    assert_eq!(at("1 + $0").position_kind, PositionKind::NeedExpr);
+    assert_eq!(at("su$0").position_kind, PositionKind::NeedExpr);
+    assert_eq!(at("sum$0").position_kind, PositionKind::AfterAtom);
+    assert_eq!(at("1$0").position_kind, PositionKind::AfterAtom);
+    assert_eq!(at("(1)$0").position_kind, PositionKind::AfterAtom);
+    assert_eq!(at(r#""a".$0"#).position_kind, PositionKind::AfterDot);
+    assert_eq!(at(r#""a$0b""#).position_kind, PositionKind::None);
+}
+
+#[test]
+fn replace_and_query_cover_the_typed_identifier() {
+    let cursor = at("1 + formatD$0");
+    assert_eq!(cursor.replace, Span { start: 4, end: 11 });
+    assert_eq!(cursor.query.as_deref(), Some("formatd"));
+
+    let cursor = at("1 + $0");
+    assert_eq!(cursor.replace, Span { start: 4, end: 4 });
+    assert_eq!(cursor.query, None);
+}
+
+#[test]
+fn call_context_tracks_the_innermost_call_and_argument() {
+    let cursor = at("if(true, sum(1, [2, 3], $0), 0)");
+    assert_eq!(
+        cursor.call_ctx,
+        Some(CallContext {
+            callee: "sum".into(),
+            lparen_idx: 5,
+            arg_index: 2,
+        })
+    );
+    assert_eq!(at("1 + $0").call_ctx, None);
+}
+
+#[test]
+fn replace_span_query_matches_help_except_after_complete_names() {
+    let ctx = Context::with_builtins(Vec::new());
+    let source = r#"if(prop("Title").lengt > 1, formatD(now(), "a b"), su + 2)"#;
+    for cursor in 0..=source.len() {
+        let cheap = ide::completion_replace_span(source, cursor);
+        let help = ide::help(source, cursor, &ctx, ide::CompletionConfig::default());
+        if cheap.replace != help.completion.replace {
+            // `prop` and `now` are complete names, which `help` does not replace.
+            let word = &source[cheap.replace.start as usize..cheap.replace.end as usize];
+            assert!(matches!(word, "prop" | "now"), "cursor {cursor}: {word:?}");
+            assert_eq!(cheap.replace.end as usize, cursor);
+            continue;
+        }
+        let tokens = analyzer::analyze_syntax(source).tokens;
+        let full = detect_cursor_context(source, &tokens, cursor as u32, &ctx);
+        assert_eq!(cheap.query, full.query, "cursor {cursor}");
+    }
+}
+
+#[test]
+fn replace_span_query_covers_the_word_being_typed() {
+    let out = ide::completion_replace_span("1 + sum", 7);
+    assert_eq!(out.replace, Span { start: 4, end: 7 });
+    assert_eq!(out.query.as_deref(), Some("sum"));
+
+    let out = ide::completion_replace_span(r#""a b""#, 2);
+    assert_eq!(out.replace, Span { start: 2, end: 2 });
+    assert_eq!(out.query, None);
+}
@@ -0,0 +1,53 @@
+use analyzer::semantic::{Property, Ty};
+
+use ide::CompletionKind;
+use notion_formula_testing::{ctx, t};
+
+fn prop(name: &str, ty: Ty) -> Property {
+    Property {
+        name: name.into(),
+        ty,
+        disabled_reason: None,
+        options: Vec::new(),
+    }
+}
+
+fn remote() -> Vec<Property> {
+    vec![
+        prop("Reviewer", Ty::Boolean),
+        prop("Remote", Ty::Date),
+        prop("Title", Ty::Number),
+    ]
+}
+
+#[test]
+fn resolver_properties_complete_by_prefix() {
+    let c = ctx()
+        .prop("Title", Ty::String)
+        .property_resolver(remote())
+        .build();
+
+    t("Re$0")
+        .ctx(c.clone())
+        .expect_contains_labels(&["Remote", "Reviewer"]);
+
+    let titles: Vec<_> = t("Ti$0")
+        .ctx(c)
+        .items_kinds_labels()
+        .into_iter()
+        .filter(|(kind, label)| *kind == CompletionKind::Property && label == "Title")
+        .collect();
+    assert_eq!(titles.len(), 1, "eager properties shadow resolver ones");
+}
+
+#[test]
+fn resolver_property_prefixes_keep_completing() {
+    let c = ctx().property_resolver(remote()).build();
+
+    t("Rem$0")
+        .ctx(c.clone())
+        .expect_contains_labels(&["Remote"]);
+    t("if(true, Revi$0")
+        .ctx(c)
+        .expect_contains_labels(&["Reviewer"]);
+}
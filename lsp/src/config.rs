@@ -0,0 +1,140 @@
+//! Workspace settings: the properties each document's formula can reference.
+//!
+//! Read from `initializationOptions` and `workspace/didChangeConfiguration`, either bare or
+//! under a `notionFormula` section:
+//!
+//! ```json
+//! {
+//!   "notionFormula": {
+//!     "properties": [{ "name": "Price", "type": "Number" }],
+//!     "documents": {
+//!       "file:///invoices/total.formula": {
+//!         "properties": [{ "name": "Tax", "type": "Number" }]
+//!       }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Apart from `documents`, the settings are a serialized [`Context`], and so is each `documents`
+//! entry, which replaces the workspace-wide context for that URI. A context without `functions`
+//! gets the builtins.
+
+use std::collections::HashMap;
+
+use analyzer::semantic::{Context, builtins_functions};
+use serde::Deserialize;
+use serde_json::Value;
+
+const SECTION: &str = "notionFormula";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Settings {
+    #[serde(flatten)]
+    pub context: Context,
+    #[serde(default)]
+    pub documents: HashMap<String, Context>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            context: Context::with_builtins(Vec::new()),
+            documents: HashMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Parses settings; `null` means defaults.
+    pub fn from_value(value: &Value) -> Result<Self, String> {
+        let value = value.get(SECTION).unwrap_or(value);
+        if value.is_null() {
+            return Ok(Self::default());
+        }
+        let mut settings =
+            Self::deserialize(value).map_err(|err| format!("invalid {SECTION} settings: {err}"))?;
+        for context in std::iter::once(&mut settings.context).chain(settings.documents.values_mut())
+        {
+            if context.functions.is_empty() {
+                context.functions = builtins_functions().into();
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Analysis context for the document at `uri`.
+    pub fn context_for(&self, uri: &str) -> Context {
+        self.documents.get(uri).unwrap_or(&self.context).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use analyzer::semantic::{Context, PropertyLookup, Ty};
+    use serde_json::json;
+
+    use super::Settings;
+
+    #[test]
+    fn reads_bare_or_sectioned_settings_with_document_overrides() {
+        let bare = json!({
+            "properties": [{ "name": "Price", "type": "Number" }],
+            "documents": {
+                "file:///a.formula": { "properties": [{ "name": "Tags", "type": { "List": "String" } }] },
+            },
+        });
+        let settings = Settings::from_value(&bare).unwrap();
+        assert_eq!(
+            settings,
+            Settings::from_value(&json!({ "notionFormula": bare })).unwrap()
+        );
+
+        let names = |uri: &str| -> Vec<(String, Ty)> {
+            settings
+                .context_for(uri)
+                .properties
+                .into_iter()
+                .map(|p| (p.name, p.ty))
+                .collect()
+        };
+        assert_eq!(names("file:///b.formula"), [("Price".into(), Ty::Number)]);
+        assert_eq!(
+            names("file:///a.formula"),
+            [("Tags".into(), Ty::List(Box::new(Ty::String)))]
+        );
+    }
+
+    #[test]
+    fn null_is_default_and_unknown_fields_are_ignored() {
+        assert_eq!(Settings::from_value(&json!(null)), Ok(Settings::default()));
+        assert_eq!(
+            Settings::from_value(&json!({ "props": [] })),
+            Ok(Settings::default())
+        );
+        assert!(
+            Settings::from_value(&json!({ "properties": 1 }))
+                .unwrap_err()
+                .starts_with("invalid notionFormula settings")
+        );
+    }
+
+    #[test]
+    fn reads_serialized_contexts() {
+        let ctx = Context::builder()
+            .prop("Price", Ty::Number)
+            .property_lookup(PropertyLookup::Lenient)
+            .with_builtins()
+            .build();
+        let value = serde_json::to_value(&ctx).unwrap();
+        assert_eq!(value["version"], 1);
+        let settings = Settings::from_value(&json!({ "notionFormula": value })).unwrap();
+        assert_eq!(settings.context_for("file:///a.formula"), ctx);
+
+        let settings = Settings::from_value(&json!({
+            "documents": { "file:///a.formula": value },
+        }))
+        .unwrap();
+        assert_eq!(settings.context_for("file:///a.formula"), ctx);
+    }
+}
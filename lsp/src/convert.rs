@@ -0,0 +1,310 @@
+//! Byte offsets ↔ LSP positions, and ide results → LSP JSON.
+//!
+//! LSP positions are 0-based `(line, character)`; `character` is in the negotiated position
+//! encoding (UTF-16 unless the client offers UTF-8).
+
+use analyzer::Span;
+use ide::{DisplaySegment, PositionEncoding};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// `DiagnosticSeverity.Error`.
+const SEVERITY_ERROR: u32 = 1;
+/// `InsertTextFormat.PlainText` / `InsertTextFormat.Snippet`.
+const PLAIN_TEXT: u32 = 1;
+const SNIPPET: u32 = 2;
+const SOURCE: &str = "notion-formula";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Line starts of one document, for converting in both directions.
+pub struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+    encoding: PositionEncoding,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str, encoding: PositionEncoding) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            source,
+            line_starts,
+            encoding,
+        }
+    }
+
+    pub fn position(&self, byte: u32) -> Position {
+        let byte = (byte as usize).min(self.source.len());
+        let line = self.line_starts.partition_point(|&start| start <= byte) - 1;
+        let line_text = &self.source[self.line_starts[line]..];
+        Position {
+            line: line as u32,
+            character: self
+                .encoding
+                .from_byte(line_text, (byte - self.line_starts[line]) as u32),
+        }
+    }
+
+    pub fn range(&self, span: Span) -> Range {
+        Range {
+            start: self.position(span.start),
+            end: self.position(span.end),
+        }
+    }
+
+    /// Byte offset of `position`, clamped to the end of its line (or of the document).
+    pub fn offset(&self, position: Position) -> u32 {
+        let Some(&start) = self.line_starts.get(position.line as usize) else {
+            return self.source.len() as u32;
+        };
+        let end = self
+            .line_starts
+            .get(position.line as usize + 1)
+            .map_or(self.source.len(), |&next| next - 1);
+        let line_text = &self.source[start..end];
+        start as u32 + self.encoding.to_byte(line_text, position.character)
+    }
+}
+
+pub fn diagnostic(lines: &LineIndex<'_>, diag: &analyzer::Diagnostic) -> Value {
+    json!({
+        "range": lines.range(diag.span),
+        "severity": SEVERITY_ERROR,
+        "source": SOURCE,
+        "message": diag.message,
+    })
+}
+
+pub fn text_edit(lines: &LineIndex<'_>, edit: &analyzer::TextEdit) -> Value {
+    json!({ "range": lines.range(edit.range), "newText": edit.new_text })
+}
+
+pub fn completion_list(lines: &LineIndex<'_>, completion: &ide::CompletionResult) -> Value {
+    let items: Vec<Value> = completion
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| !item.is_disabled)
+        .map(|(i, item)| {
+            let sort_text = match completion.preferred_indices.iter().position(|&p| p == i) {
+                Some(rank) => format!("0{rank:04}"),
+                None => format!("1{i:04}"),
+            };
+            completion_item(lines, completion.replace, item, sort_text)
+        })
+        .collect();
+    json!({ "isIncomplete": false, "items": items })
+}
+
+fn completion_item(
+    lines: &LineIndex<'_>,
+    replace: Span,
+    item: &ide::CompletionItem,
+    sort_text: String,
+) -> Value {
+    let (range, text) = match &item.primary_edit {
+        Some(edit) => (edit.range, edit.new_text.as_str()),
+        None => (replace, item.insert_text.as_str()),
+    };
+
+    // Tab stops (the cursor, snippet placeholders) are in the document after the primary edit.
+    let (new_text, format) = match item.snippet_text() {
+        Some(snippet) => (snippet, SNIPPET),
+        None => (text.to_string(), PLAIN_TEXT),
+    };
+
+    json!({
+        "label": item.label,
+        "kind": completion_kind(item.kind),
+        "detail": item.detail,
+        "sortText": sort_text,
+        "filterText": item.label,
+        "insertTextFormat": format,
+        "textEdit": { "range": lines.range(range), "newText": new_text },
+        "additionalTextEdits": item
+            .additional_edits
+            .iter()
+            .map(|edit| text_edit(lines, edit))
+            .collect::<Vec<_>>(),
+    })
+}
+
+pub fn signature_help(sig: &ide::SignatureHelp, encoding: PositionEncoding) -> Value {
+    let mut active_parameter = None;
+    let signatures: Vec<Value> = sig
+        .signatures
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let (label, parameters) = signature_label(&item.segments, encoding);
+            let active = parameters.iter().position(|&(_, _, index)| {
+                index.is_some_and(|p| p as usize == sig.active_parameter)
+            });
+            if i == sig.active_signature {
+                active_parameter = active;
+            }
+            json!({
+                "label": label,
+                "parameters": parameters
+                    .iter()
+                    .map(|&(start, end, _)| json!({ "label": [start, end] }))
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    json!({
+        "signatures": signatures,
+        "activeSignature": sig.active_signature,
+        // Out of range highlights no parameter.
+        "activeParameter": active_parameter.map_or(u32::MAX, |p| p as u32),
+    })
+}
+
+/// The signature label (`name: type` for parameters) and each parameter's `[start, end)` offsets
+/// in it, with its parameter index.
+fn signature_label(
+    segments: &[DisplaySegment],
+    encoding: PositionEncoding,
+) -> (String, Vec<(u32, u32, Option<u32>)>) {
+    let mut label = String::new();
+    let mut parameters = Vec::new();
+    for seg in segments {
+        let start = encoding.source_len(&label);
+        match seg {
+            DisplaySegment::Name { text }
+            | DisplaySegment::Punct { text }
+            | DisplaySegment::Separator { text }
+            | DisplaySegment::Arrow { text }
+            | DisplaySegment::ReturnType { text } => label.push_str(text),
+            DisplaySegment::Ellipsis => label.push_str("..."),
+            DisplaySegment::Param {
+                name,
+                ty,
+                param_index,
+            } => {
+                label.push_str(&format!("{name}: {ty}"));
+                parameters.push((start, encoding.source_len(&label), *param_index));
+            }
+        }
+    }
+    (label, parameters)
+}
+
+pub fn hover(lines: &LineIndex<'_>, hover: &ide::HoverResult, encoding: PositionEncoding) -> Value {
+    let mut value = match &hover.signature {
+        Some(signature) => format!(
+            "```notion-formula\n{}\n```",
+            signature_label(&signature.segments, encoding).0
+        ),
+        None => format!("```notion-formula\n{}\n```", hover.ty),
+    };
+    if let Some(docs) = &hover.docs {
+        value.push_str("\n\n");
+        value.push_str(docs);
+    }
+    json!({
+        "contents": { "kind": "markdown", "value": value },
+        "range": lines.range(hover.range),
+    })
+}
+
+fn completion_kind(kind: ide::CompletionKind) -> u32 {
+    use ide::CompletionKind::*;
+    // `CompletionItemKind` values.
+    match kind {
+        FunctionGeneral | FunctionText | FunctionNumber | FunctionDate | FunctionPeople
+        | FunctionList | FunctionSpecial => 3,
+        Variable => 6,
+        Property => 10,
+        Keyword => 14,
+        Snippet => 15,
+        Constant => 21,
+        Operator => 24,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use analyzer::Span;
+    use ide::PositionEncoding;
+
+    use super::{LineIndex, Position};
+
+    #[test]
+    fn positions_round_trip_in_both_encodings() {
+        let source = "\"😀\"\n  1";
+        let utf16 = LineIndex::new(source, PositionEncoding::Utf16);
+        assert_eq!(
+            utf16.position(5),
+            Position {
+                line: 0,
+                character: 3
+            }
+        );
+        assert_eq!(
+            utf16.position(9),
+            Position {
+                line: 1,
+                character: 2
+            }
+        );
+        assert_eq!(
+            utf16.offset(Position {
+                line: 0,
+                character: 3
+            }),
+            5
+        );
+        assert_eq!(
+            utf16.offset(Position {
+                line: 1,
+                character: 2
+            }),
+            9
+        );
+
+        let utf8 = LineIndex::new(source, PositionEncoding::Utf8);
+        assert_eq!(
+            utf8.position(5),
+            Position {
+                line: 0,
+                character: 5
+            }
+        );
+        let range = utf8.range(Span { start: 1, end: 5 });
+        assert_eq!((utf8.offset(range.start), utf8.offset(range.end)), (1, 5));
+    }
+
+    #[test]
+    fn offsets_clamp_to_line_and_document_end() {
+        let lines = LineIndex::new("ab\ncd", PositionEncoding::Utf16);
+        assert_eq!(
+            lines.offset(Position {
+                line: 0,
+                character: 99
+            }),
+            2
+        );
+        assert_eq!(
+            lines.offset(Position {
+                line: 7,
+                character: 0
+            }),
+            5
+        );
+    }
+}
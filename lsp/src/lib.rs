@@ -0,0 +1,42 @@
+//! Language server for Notion formulas over stdio.
+//!
+//! Speaks JSON-RPC 2.0 with `Content-Length` framing and serves diagnostics, completion,
+//! signature help, hover, formatting, rename, and semantic tokens from `ide`. The properties
+//! each document can reference come from workspace settings (see `config`).
+
+mod config;
+mod convert;
+mod server;
+mod transport;
+
+use std::io::{BufRead, ErrorKind, Write};
+
+use serde_json::Value;
+
+pub use server::Server;
+
+/// Serves one client until `exit` or end of input, returning the process exit code.
+pub fn run(mut input: impl BufRead, mut output: impl Write) -> i32 {
+    let mut server = Server::new();
+    loop {
+        let messages = match transport::read_message(&mut input) {
+            Ok(Some(message)) => server.handle(message),
+            // End of input without `exit`.
+            Ok(None) => return 1,
+            Err(err) if err.kind() == ErrorKind::InvalidData => vec![server::error_response(
+                Value::Null,
+                server::PARSE_ERROR,
+                &err.to_string(),
+            )],
+            Err(_) => return 1,
+        };
+        for message in &messages {
+            if transport::write_message(&mut output, message).is_err() {
+                return 1;
+            }
+        }
+        if let Some(code) = server.exit_code() {
+            return code;
+        }
+    }
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let code = notion_formula_lsp::run(std::io::stdin().lock(), std::io::stdout().lock());
+    std::process::exit(code);
+}
@@ -0,0 +1,430 @@
+//! Request and notification dispatch.
+//!
+//! One [`AnalysisHost`] per open document; every request is answered synchronously.
+
+use std::collections::HashMap;
+
+use ide::{AnalysisHost, CompletionConfig, FormatConfig, PositionEncoding, Revision};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+use crate::config::Settings;
+use crate::convert::{self, LineIndex, Position};
+
+/// JSON-RPC error codes.
+pub const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+/// `TextDocumentSyncKind.Full`.
+const SYNC_FULL: u32 = 1;
+
+/// Language server state.
+pub struct Server {
+    settings: Settings,
+    encoding: PositionEncoding,
+    documents: HashMap<String, AnalysisHost>,
+    /// Per document, the diagnostics revision last sent to the client.
+    published: HashMap<String, Revision>,
+    initialized: bool,
+    shutdown: bool,
+    exit: Option<i32>,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self {
+            settings: Settings::default(),
+            encoding: PositionEncoding::Utf16,
+            documents: HashMap::new(),
+            published: HashMap::new(),
+            initialized: false,
+            shutdown: false,
+            exit: None,
+        }
+    }
+
+    /// Process exit code, once `exit` was received: 0 after `shutdown`, 1 otherwise.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit
+    }
+
+    /// Handles one incoming message, returning the messages to send back: the response for a
+    /// request, or notifications such as `textDocument/publishDiagnostics`.
+    pub fn handle(&mut self, message: Value) -> Vec<Value> {
+        let method = message.get("method").and_then(Value::as_str);
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        match (message.get("id").cloned(), method) {
+            (Some(id), Some(method)) => {
+                let response = match self.request(method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, message)) => error_response(id, code, &message),
+                };
+                vec![response]
+            }
+            (None, Some(method)) => self.notification(method, params),
+            // Responses to server requests (we send none) are ignored.
+            (Some(_), None)
+                if message.get("result").is_some() || message.get("error").is_some() =>
+            {
+                Vec::new()
+            }
+            (id, None) => vec![error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "message has no method",
+            )],
+        }
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, (i64, String)> {
+        if method == "initialize" {
+            return self.initialize(params);
+        }
+        if !self.initialized {
+            return Err((SERVER_NOT_INITIALIZED, "server not initialized".into()));
+        }
+        match method {
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/completion" => self.completion(parse(params)?),
+            "textDocument/signatureHelp" => self.signature_help(parse(params)?),
+            "textDocument/hover" => self.hover(parse(params)?),
+            "textDocument/formatting" => self.formatting(parse(params)?),
+            "textDocument/rename" => self.rename(parse(params)?),
+            "textDocument/prepareRename" => self.prepare_rename(parse(params)?),
+            "textDocument/semanticTokens/full" => self.semantic_tokens(parse(params)?),
+            _ => Err((METHOD_NOT_FOUND, format!("unhandled method `{method}`"))),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: Value) -> Vec<Value> {
+        if method == "exit" {
+            self.exit = Some(if self.shutdown { 0 } else { 1 });
+            return Vec::new();
+        }
+        if !self.initialized {
+            return Vec::new();
+        }
+        match method {
+            "textDocument/didOpen" => {
+                let Some(params) = parse::<DidOpenParams>(params).ok() else {
+                    return Vec::new();
+                };
+                let uri = params.text_document.uri;
+                let mut host = AnalysisHost::new(self.settings.context_for(&uri));
+                host.set_text(params.text_document.text);
+                self.documents.insert(uri.clone(), host);
+                vec![self.publish_diagnostics(&uri)]
+            }
+            "textDocument/didChange" => {
+                let Some(params) = parse::<DidChangeParams>(params).ok() else {
+                    return Vec::new();
+                };
+                let uri = params.text_document.uri;
+                // Full sync: the last change carries the whole text.
+                let (Some(host), Some(change)) = (
+                    self.documents.get_mut(&uri),
+                    params.content_changes.into_iter().last(),
+                ) else {
+                    return Vec::new();
+                };
+                host.set_text(change.text);
+                vec![self.publish_diagnostics(&uri)]
+            }
+            "textDocument/didClose" => {
+                let Some(params) = parse::<DocumentParams>(params).ok() else {
+                    return Vec::new();
+                };
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                self.published.remove(&uri);
+                vec![notification(
+                    "textDocument/publishDiagnostics",
+                    json!({ "uri": uri, "diagnostics": [] }),
+                )]
+            }
+            "workspace/didChangeConfiguration" => {
+                let settings = params.get("settings").unwrap_or(&Value::Null);
+                match Settings::from_value(settings) {
+                    Ok(settings) => self.update_settings(settings),
+                    Err(message) => vec![show_error(&message)],
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn initialize(&mut self, params: Value) -> Result<Value, (i64, String)> {
+        if self.initialized {
+            return Err((INVALID_REQUEST, "server already initialized".into()));
+        }
+        let offers_utf8 = params
+            .pointer("/capabilities/general/positionEncodings")
+            .and_then(Value::as_array)
+            .is_some_and(|encodings| encodings.iter().any(|e| e == "utf-8"));
+        self.encoding = if offers_utf8 {
+            PositionEncoding::Utf8
+        } else {
+            PositionEncoding::Utf16
+        };
+        let options = params.get("initializationOptions").unwrap_or(&Value::Null);
+        self.settings = Settings::from_value(options).map_err(|err| (INVALID_PARAMS, err))?;
+        self.initialized = true;
+
+        Ok(json!({
+            "capabilities": {
+                "positionEncoding": if offers_utf8 { "utf-8" } else { "utf-16" },
+                "textDocumentSync": SYNC_FULL,
+                "completionProvider": {},
+                "signatureHelpProvider": { "triggerCharacters": ["(", ","] },
+                "hoverProvider": true,
+                "documentFormattingProvider": true,
+                "renameProvider": { "prepareProvider": true },
+                "semanticTokensProvider": {
+                    "legend": {
+                        "tokenTypes": ide::SEMANTIC_TOKEN_TYPES,
+                        "tokenModifiers": ide::SEMANTIC_TOKEN_MODIFIERS,
+                    },
+                    "full": true,
+                },
+            },
+            "serverInfo": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        }))
+    }
+
+    /// Applies new settings to every open document and republishes the diagnostics that changed.
+    fn update_settings(&mut self, settings: Settings) -> Vec<Value> {
+        self.settings = settings;
+        let mut uris: Vec<String> = self.documents.keys().cloned().collect();
+        uris.sort();
+        for uri in &uris {
+            let context = self.settings.context_for(uri);
+            if let Some(host) = self.documents.get_mut(uri) {
+                host.set_context(context);
+            }
+        }
+        // The text is unchanged, so equal diagnostics also map to equal positions.
+        uris.retain(|uri| {
+            let host = &self.documents[uri];
+            self.published.get(uri) != Some(&host.diagnostics_changed_at())
+        });
+        uris.iter()
+            .map(|uri| self.publish_diagnostics(uri))
+            .collect()
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> Value {
+        let diagnostics: Vec<Value> = match self.documents.get(uri) {
+            Some(host) => {
+                self.published
+                    .insert(uri.to_string(), host.diagnostics_changed_at());
+                let lines = LineIndex::new(host.text(), self.encoding);
+                host.diagnostics()
+                    .iter()
+                    .map(|diag| convert::diagnostic(&lines, diag))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        notification(
+            "textDocument/publishDiagnostics",
+            json!({ "uri": uri, "diagnostics": diagnostics }),
+        )
+    }
+
+    fn document(&self, uri: &str) -> Result<&AnalysisHost, (i64, String)> {
+        self.documents
+            .get(uri)
+            .ok_or_else(|| (INVALID_PARAMS, format!("document `{uri}` is not open")))
+    }
+
+    /// The document and the byte offset of `params.position`.
+    fn locate(&self, params: &PositionParams) -> Result<(&AnalysisHost, u32), (i64, String)> {
+        let host = self.document(&params.text_document.uri)?;
+        let cursor = LineIndex::new(host.text(), self.encoding).offset(params.position);
+        Ok((host, cursor))
+    }
+
+    fn completion(&self, params: PositionParams) -> Result<Value, (i64, String)> {
+        let (host, cursor) = self.locate(&params)?;
+        let help = host.help(cursor as usize, CompletionConfig::default());
+        let lines = LineIndex::new(host.text(), self.encoding);
+        Ok(convert::completion_list(&lines, &help.completion))
+    }
+
+    fn signature_help(&self, params: PositionParams) -> Result<Value, (i64, String)> {
+        let (host, cursor) = self.locate(&params)?;
+        Ok(
+            ide::signature_help(host.text(), cursor as usize, host.context())
+                .map_or(Value::Null, |sig| {
+                    convert::signature_help(&sig, self.encoding)
+                }),
+        )
+    }
+
+    fn hover(&self, params: PositionParams) -> Result<Value, (i64, String)> {
+        let (host, cursor) = self.locate(&params)?;
+        let lines = LineIndex::new(host.text(), self.encoding);
+        Ok(host.hover(cursor as usize).map_or(Value::Null, |hover| {
+            convert::hover(&lines, &hover, self.encoding)
+        }))
+    }
+
+    fn formatting(&self, params: FormattingParams) -> Result<Value, (i64, String)> {
+        let host = self.document(&params.text_document.uri)?;
+        let config = FormatConfig {
+            indent_width: params.options.tab_size as usize,
+            ..FormatConfig::default()
+        };
+        let Ok(formatted) = ide::format_with_config(host.text(), 0, config) else {
+            return Ok(Value::Null);
+        };
+        if formatted.source == host.text() {
+            return Ok(json!([]));
+        }
+        let lines = LineIndex::new(host.text(), self.encoding);
+        let whole = analyzer::Span {
+            start: 0,
+            end: host.text().len() as u32,
+        };
+        Ok(json!([{ "range": lines.range(whole), "newText": formatted.source }]))
+    }
+
+    fn rename(&self, params: RenameParams) -> Result<Value, (i64, String)> {
+        if params.new_name.is_empty() {
+            return Err((INVALID_PARAMS, "property name must not be empty".into()));
+        }
+        let (host, cursor) = self.locate(&params.position)?;
+        let Some(edits) = ide::rename(host.text(), cursor, &params.new_name) else {
+            return Ok(Value::Null);
+        };
+        let lines = LineIndex::new(host.text(), self.encoding);
+        let edits: Vec<Value> = edits
+            .iter()
+            .map(|edit| convert::text_edit(&lines, edit))
+            .collect();
+        Ok(json!({ "changes": { params.position.text_document.uri: edits } }))
+    }
+
+    fn prepare_rename(&self, params: PositionParams) -> Result<Value, (i64, String)> {
+        let (host, cursor) = self.locate(&params)?;
+        // The new name only shapes the edit text; any placeholder finds the same ranges.
+        let range = ide::rename(host.text(), cursor, "_")
+            .into_iter()
+            .flatten()
+            .find(|edit| edit.range.start <= cursor && cursor <= edit.range.end)
+            .map(|edit| edit.range);
+        let lines = LineIndex::new(host.text(), self.encoding);
+        Ok(range.map_or(Value::Null, |range| json!(lines.range(range))))
+    }
+
+    fn semantic_tokens(&self, params: DocumentParams) -> Result<Value, (i64, String)> {
+        let host = self.document(&params.text_document.uri)?;
+        let data =
+            ide::encode_semantic_tokens_lsp(host.text(), &host.semantic_tokens(), self.encoding);
+        Ok(json!({ "data": data }))
+    }
+}
+
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|err| (INVALID_PARAMS, err.to_string()))
+}
+
+pub fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn show_error(message: &str) -> Value {
+    // `MessageType.Error`.
+    notification(
+        "window/showMessage",
+        json!({ "type": 1, "message": message }),
+    )
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TextDocumentIdentifier {
+    uri: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TextDocumentItem {
+    uri: String,
+    text: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentParams {
+    text_document: TextDocumentIdentifier,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidOpenParams {
+    text_document: TextDocumentItem,
+}
+
+#[derive(serde::Deserialize)]
+struct ContentChange {
+    text: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidChangeParams {
+    text_document: TextDocumentIdentifier,
+    content_changes: Vec<ContentChange>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionParams {
+    text_document: TextDocumentIdentifier,
+    position: Position,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RenameParams {
+    #[serde(flatten)]
+    position: PositionParams,
+    new_name: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FormattingOptions {
+    tab_size: u32,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FormattingParams {
+    text_document: TextDocumentIdentifier,
+    options: FormattingOptions,
+}
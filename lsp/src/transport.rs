@@ -0,0 +1,99 @@
+//! JSON-RPC framing over a byte stream: `Content-Length: N\r\n\r\n<json>`.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+/// Largest body [`read_message`] accepts, so a bogus `Content-Length` cannot exhaust memory.
+pub const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Reads one message. `Ok(None)` at end of input.
+///
+/// A body that is not valid JSON is returned as `Err` with kind `InvalidData`; the stream stays
+/// positioned at the next message. A `Content-Length` above [`MAX_CONTENT_LENGTH`] is also
+/// `InvalidData`, returned before any of the body is read.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let Some(len) = content_length else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "missing Content-Length header",
+        ));
+    };
+    if len > MAX_CONTENT_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Content-Length {len} exceeds {MAX_CONTENT_LENGTH} bytes"),
+        ));
+    }
+    let mut body = vec![0; len];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use serde_json::json;
+
+    use super::{MAX_CONTENT_LENGTH, read_message, write_message};
+
+    #[test]
+    fn round_trips_framed_messages() {
+        let mut buf = Vec::new();
+        write_message(&mut buf, &json!({ "id": 1, "text": "😀" })).unwrap();
+        write_message(&mut buf, &json!({ "id": 2 })).unwrap();
+
+        let mut input = Cursor::new(buf);
+        assert_eq!(
+            read_message(&mut input).unwrap(),
+            Some(json!({ "id": 1, "text": "😀" }))
+        );
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({ "id": 2 })));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn ignores_other_headers_and_rejects_bad_json() {
+        let raw = "Content-Type: application/vscode-jsonrpc\r\ncontent-length: 2\r\n\r\n{]";
+        let err = read_message(&mut Cursor::new(raw)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_oversized_content_length() {
+        let raw = "Content-Length: 18446744073709551615\r\n\r\n{}";
+        let err = read_message(&mut Cursor::new(raw)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let raw = format!("Content-Length: {}\r\n\r\n{{}}", MAX_CONTENT_LENGTH + 1);
+        let err = read_message(&mut Cursor::new(raw)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,271 @@
+use notion_formula_lsp::Server;
+use serde_json::{Value, json};
+
+const URI: &str = "file:///total.formula";
+
+fn request(server: &mut Server, id: u64, method: &str, params: Value) -> Value {
+    let mut out =
+        server.handle(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+    assert_eq!(out.len(), 1, "{out:?}");
+    let response = out.remove(0);
+    assert_eq!(response["id"], id);
+    response
+}
+
+fn notify(server: &mut Server, method: &str, params: Value) -> Vec<Value> {
+    server.handle(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+}
+
+fn initialized(capabilities: Value) -> (Server, Value) {
+    let mut server = Server::new();
+    let response = request(
+        &mut server,
+        0,
+        "initialize",
+        json!({
+            "capabilities": capabilities,
+            "initializationOptions": {
+                "notionFormula": { "properties": [{ "name": "Price", "type": "Number" }] },
+            },
+        }),
+    );
+    notify(&mut server, "initialized", json!({}));
+    (server, response["result"].clone())
+}
+
+fn open(server: &mut Server, text: &str) -> Vec<Value> {
+    notify(
+        server,
+        "textDocument/didOpen",
+        json!({ "textDocument": { "uri": URI, "languageId": "notion-formula", "version": 1, "text": text } }),
+    )
+}
+
+fn at(line: u32, character: u32) -> Value {
+    json!({ "textDocument": { "uri": URI }, "position": { "line": line, "character": character } })
+}
+
+#[test]
+fn initialize_negotiates_encoding_and_advertises_capabilities() {
+    let (_, result) = initialized(json!({}));
+    let caps = &result["capabilities"];
+    assert_eq!(caps["positionEncoding"], "utf-16");
+    assert_eq!(caps["textDocumentSync"], 1);
+    assert_eq!(caps["renameProvider"]["prepareProvider"], true);
+    assert_eq!(
+        caps["semanticTokensProvider"]["legend"]["tokenTypes"],
+        json!(ide::SEMANTIC_TOKEN_TYPES)
+    );
+
+    let (_, result) =
+        initialized(json!({ "general": { "positionEncodings": ["utf-16", "utf-8"] } }));
+    assert_eq!(result["capabilities"]["positionEncoding"], "utf-8");
+}
+
+#[test]
+fn requests_before_initialize_are_rejected() {
+    let mut server = Server::new();
+    let response = request(&mut server, 1, "textDocument/hover", at(0, 0));
+    assert_eq!(response["error"]["code"], -32002);
+}
+
+#[test]
+fn diagnostics_follow_open_change_and_close() {
+    let (mut server, _) = initialized(json!({}));
+    let published = open(&mut server, "\"😀\" + prop(\"Nope\")");
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0]["method"], "textDocument/publishDiagnostics");
+    let diags = published[0]["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diags.len(), 1, "{diags:?}");
+    // UTF-16 columns: the emoji counts as two units.
+    assert_eq!(
+        diags[0]["range"]["start"],
+        json!({ "line": 0, "character": 12 })
+    );
+    assert_eq!(diags[0]["severity"], 1);
+    assert_eq!(diags[0]["source"], "notion-formula");
+
+    let published = notify(
+        &mut server,
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "prop(\"Price\") * 2" }],
+        }),
+    );
+    assert_eq!(published[0]["params"]["diagnostics"], json!([]));
+
+    let published = notify(
+        &mut server,
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(
+        published[0]["params"],
+        json!({ "uri": URI, "diagnostics": [] })
+    );
+}
+
+#[test]
+fn configuration_changes_update_document_contexts() {
+    let (mut server, _) = initialized(json!({}));
+    open(&mut server, "prop(\"Tax\")");
+
+    let published = notify(
+        &mut server,
+        "workspace/didChangeConfiguration",
+        json!({ "settings": { "notionFormula": {
+            "documents": { URI: { "properties": [{ "name": "Tax", "type": "Number" }] } },
+        } } }),
+    );
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0]["params"]["diagnostics"], json!([]));
+
+    // A new context that leaves the diagnostics unchanged is not republished.
+    let published = notify(
+        &mut server,
+        "workspace/didChangeConfiguration",
+        json!({ "settings": { "notionFormula": {
+            "documents": { URI: { "properties": [
+                { "name": "Tax", "type": "Number" },
+                { "name": "Unused", "type": "String" },
+            ] } },
+        } } }),
+    );
+    assert_eq!(published, Vec::<Value>::new());
+
+    let published = notify(
+        &mut server,
+        "workspace/didChangeConfiguration",
+        json!({ "settings": { "properties": 1 } }),
+    );
+    assert_eq!(published[0]["method"], "window/showMessage");
+}
+
+#[test]
+fn completion_offers_properties_and_functions() {
+    let (mut server, _) = initialized(json!({}));
+    open(&mut server, "");
+    let response = request(&mut server, 1, "textDocument/completion", at(0, 0));
+    let items = response["result"]["items"].as_array().unwrap();
+
+    let price = items.iter().find(|item| item["label"] == "Price").unwrap();
+    assert_eq!(price["kind"], 10);
+    assert_eq!(price["textEdit"]["newText"], "prop(\"Price\")");
+
+    let if_item = items.iter().find(|item| item["label"] == "if()").unwrap();
+    assert_eq!(if_item["kind"], 3);
+    assert_eq!(if_item["insertTextFormat"], 2);
+    assert_eq!(if_item["textEdit"]["newText"], "if($0)");
+}
+
+#[test]
+fn signature_help_and_hover_use_the_document_context() {
+    let (mut server, _) = initialized(json!({}));
+    open(&mut server, "if(true, prop(\"Price\"), 0)");
+
+    let response = request(&mut server, 1, "textDocument/signatureHelp", at(0, 10));
+    let help = &response["result"];
+    let signature = &help["signatures"][0];
+    assert!(
+        signature["label"].as_str().unwrap().starts_with("if("),
+        "{signature}"
+    );
+    let active = help["activeParameter"].as_u64().unwrap() as usize;
+    let [start, end] = [0, 1].map(|i| {
+        signature["parameters"][active]["label"][i]
+            .as_u64()
+            .unwrap() as usize
+    });
+    assert!(signature["label"].as_str().unwrap()[start..end].starts_with("then"));
+
+    let response = request(&mut server, 2, "textDocument/hover", at(0, 1));
+    let hover = &response["result"];
+    assert_eq!(hover["contents"]["kind"], "markdown");
+    assert!(hover["contents"]["value"].as_str().unwrap().contains("if("));
+    assert_eq!(hover["range"]["end"], json!({ "line": 0, "character": 2 }));
+}
+
+#[test]
+fn formatting_returns_one_whole_document_edit() {
+    let (mut server, _) = initialized(json!({}));
+    open(&mut server, "1+2");
+    let params = json!({ "textDocument": { "uri": URI }, "options": { "tabSize": 2, "insertSpaces": true } });
+    let response = request(&mut server, 1, "textDocument/formatting", params.clone());
+    assert_eq!(
+        response["result"],
+        json!([{
+            "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 3 } },
+            "newText": "1 + 2\n",
+        }])
+    );
+
+    notify(
+        &mut server,
+        "textDocument/didChange",
+        json!({ "textDocument": { "uri": URI }, "contentChanges": [{ "text": "1 + 2\n" }] }),
+    );
+    let response = request(&mut server, 2, "textDocument/formatting", params);
+    assert_eq!(response["result"], json!([]));
+}
+
+#[test]
+fn rename_rewrites_every_property_reference() {
+    let (mut server, _) = initialized(json!({}));
+    open(&mut server, "prop(\"Price\") +\nprop(\"Price\")");
+
+    let response = request(&mut server, 1, "textDocument/prepareRename", at(1, 7));
+    assert_eq!(
+        response["result"],
+        json!({ "start": { "line": 1, "character": 5 }, "end": { "line": 1, "character": 12 } })
+    );
+
+    let mut params = at(0, 7);
+    params["newName"] = json!("Cost");
+    let response = request(&mut server, 2, "textDocument/rename", params.clone());
+    let edits = response["result"]["changes"][URI].as_array().unwrap();
+    assert_eq!(edits.len(), 2);
+    assert!(edits.iter().all(|edit| edit["newText"] == "\"Cost\""));
+
+    params["newName"] = json!("");
+    let response = request(&mut server, 3, "textDocument/rename", params);
+    assert_eq!(response["error"]["code"], -32602);
+
+    let response = request(&mut server, 4, "textDocument/prepareRename", at(0, 14));
+    assert_eq!(response["result"], Value::Null);
+}
+
+#[test]
+fn semantic_tokens_are_lsp_encoded() {
+    let (mut server, _) = initialized(json!({}));
+    open(&mut server, "if(true, 1, 2)");
+    let response = request(
+        &mut server,
+        1,
+        "textDocument/semanticTokens/full",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let data = response["result"]["data"].as_array().unwrap();
+    assert_eq!(data.len() % 5, 0);
+    // `if` at (0, 0), length 2.
+    assert_eq!(data[..3], [json!(0), json!(0), json!(2)]);
+}
+
+#[test]
+fn unknown_methods_and_exit_codes() {
+    let (mut server, _) = initialized(json!({}));
+    let response = request(&mut server, 1, "textDocument/references", at(0, 0));
+    assert_eq!(response["error"]["code"], -32601);
+    assert!(notify(&mut server, "$/cancelRequest", json!({ "id": 1 })).is_empty());
+
+    notify(&mut server, "exit", Value::Null);
+    assert_eq!(server.exit_code(), Some(1));
+
+    let (mut server, _) = initialized(json!({}));
+    assert_eq!(
+        request(&mut server, 1, "shutdown", Value::Null)["result"],
+        Value::Null
+    );
+    notify(&mut server, "exit", Value::Null);
+    assert_eq!(server.exit_code(), Some(0));
+}
@@ -0,0 +1,7 @@
+fn main() {
+    // Node-API symbols are resolved against the host `node` process at load time.
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("macos") {
+        println!("cargo:rustc-cdylib-link-arg=-undefined");
+        println!("cargo:rustc-cdylib-link-arg=dynamic_lookup");
+    }
+}
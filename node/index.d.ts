@@ -0,0 +1,80 @@
+// Types for `index.js`. Spans and cursors are UTF-8 byte offsets, half-open `[start, end)`.
+
+export type Ty = 'Number' | 'String' | 'Boolean' | 'Date' | 'Unknown' | { List: Ty };
+
+export interface Property {
+  name: string;
+  type: Ty;
+  disabled_reason?: string | null;
+}
+
+export interface Span {
+  start: number;
+  end: number;
+}
+
+export interface Diagnostic {
+  code: string;
+  message: string;
+  span: Span;
+}
+
+export interface TextEdit {
+  span: Span;
+  new_text: string;
+}
+
+export interface CompletionItem {
+  label: string;
+  /** The WASM `CompletionItemKind` names (e.g. `FunctionText`, `Property`). */
+  kind: string;
+  insert_text: string;
+  /** Replaces `replace` when set. */
+  primary_edit: TextEdit | null;
+  /** Cursor in the document after applying the edits. */
+  cursor: number | null;
+  additional_edits: TextEdit[];
+  detail: string | null;
+  is_disabled: boolean;
+  disabled_reason: string | null;
+}
+
+export type DisplaySegment =
+  | { kind: 'Name' | 'Punct' | 'Separator' | 'Arrow' | 'ReturnType'; text: string }
+  | { kind: 'Ellipsis' }
+  | { kind: 'Param'; name: string; ty: string; param_index?: number };
+
+export interface SignatureHelp {
+  signatures: DisplaySegment[][];
+  active_signature: number;
+  active_parameter: number;
+}
+
+/** A request object, or its JSON text as a string or UTF-8 `Buffer`. */
+export type Request<T> = T | string | Buffer;
+
+export function analyze(
+  request: Request<{ source: string; properties?: Property[] }>,
+): { output_type: string; diagnostics: Diagnostic[] };
+
+export function complete(
+  request: Request<{ source: string; cursor: number; properties?: Property[] }>,
+): {
+  replace: Span;
+  items: CompletionItem[];
+  preferred_indices: number[];
+  signature_help: SignatureHelp | null;
+};
+
+export function format(
+  request: Request<{ source: string; cursor?: number; indent_width?: number; max_width?: number }>,
+): { source: string; cursor: number };
+
+/** Dates are epoch milliseconds; `null` (or a missing property) is empty. */
+export function evaluate(
+  request: Request<{ source: string; properties?: Property[]; row?: Record<string, unknown> }>,
+): { value: unknown; type: string };
+
+export class NotionFormulaError extends Error {
+  readonly code: string;
+}
@@ -0,0 +1,77 @@
+'use strict';
+
+// Loads the Node-API addon (`libnotion_formula_node`) and wraps its JSON entry points.
+// Spans and cursors are UTF-8 byte offsets, as in the C ABI (`ffi/`).
+
+const fs = require('node:fs');
+const path = require('node:path');
+
+const LIB_ENV = 'NOTION_FORMULA_NODE_LIB';
+
+function libraryName() {
+  switch (process.platform) {
+    case 'win32':
+      return 'notion_formula_node.dll';
+    case 'darwin':
+      return 'libnotion_formula_node.dylib';
+    default:
+      return 'libnotion_formula_node.so';
+  }
+}
+
+function candidates() {
+  if (process.env[LIB_ENV]) return [process.env[LIB_ENV]];
+  const target = path.join(__dirname, '..', 'target');
+  // Packaged next to this file, then a checkout's cargo output.
+  return [
+    path.join(__dirname, 'notion_formula.node'),
+    path.join(target, 'release', libraryName()),
+    path.join(target, 'debug', libraryName()),
+  ];
+}
+
+function load() {
+  const found = candidates().find((file) => fs.existsSync(file));
+  if (!found) {
+    throw new Error(
+      `libnotion_formula_node not found (searched ${candidates().join(', ')}); build it with ` +
+        `\`cargo build -p notion-formula-node --release\` or set ${LIB_ENV}`,
+    );
+  }
+  const addon = { exports: {} };
+  process.dlopen(addon, found);
+  return addon.exports;
+}
+
+const native = load();
+
+/** An `{"error": ...}` response; `code` is the C ABI error code. */
+class NotionFormulaError extends Error {
+  constructor(code, message) {
+    super(message);
+    this.name = 'NotionFormulaError';
+    this.code = code;
+  }
+}
+
+/**
+ * Sends `request` to the native entry point and returns the `ok` payload.
+ *
+ * A string or `Buffer` is passed through as JSON text (a `Buffer` is read without a copy);
+ * anything else is serialized with `JSON.stringify`.
+ */
+function call(entry, request) {
+  const text =
+    typeof request === 'string' || Buffer.isBuffer(request) ? request : JSON.stringify(request);
+  const response = JSON.parse(native[entry](text));
+  if (response.error) throw new NotionFormulaError(response.error.code, response.error.message);
+  return response.ok;
+}
+
+module.exports = {
+  NotionFormulaError,
+  analyze: (request) => call('analyze', request),
+  complete: (request) => call('complete', request),
+  format: (request) => call('format', request),
+  evaluate: (request) => call('evaluate', request),
+};
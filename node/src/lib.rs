@@ -0,0 +1,146 @@
+//! Node-API addon: the `ffi` JSON entry points as `analyze`, `complete`, `format`, and `evaluate`.
+//!
+//! Each function takes the JSON request as a string or as a UTF-8 `Buffer` (read in place, without
+//! a copy) and returns the JSON response string, `{"ok": ...}` or `{"error": ...}`. Spans and
+//! cursors are UTF-8 byte offsets. `index.js` wraps the functions with objects and exceptions.
+
+mod napi;
+
+use std::ffi::{CStr, c_void};
+use std::ptr;
+
+use napi::{napi_callback, napi_callback_info, napi_env, napi_value};
+use notion_formula::Entry;
+
+const FUNCTIONS: [(&CStr, napi_callback); 4] = [
+    (c"analyze", Some(analyze)),
+    (c"complete", Some(complete)),
+    (c"format", Some(format)),
+    (c"evaluate", Some(evaluate)),
+];
+
+/// Module initializer looked up by Node when the addon is loaded.
+///
+/// # Safety
+///
+/// Called by Node with a live `env` and `exports` object.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn napi_register_module_v1(env: napi_env, exports: napi_value) -> napi_value {
+    for (name, callback) in FUNCTIONS {
+        let mut function = ptr::null_mut();
+        unsafe {
+            let status = napi::napi_create_function(
+                env,
+                name.as_ptr(),
+                napi::AUTO_LENGTH,
+                callback,
+                ptr::null_mut(),
+                &mut function,
+            );
+            if status != napi::NAPI_OK
+                || napi::napi_set_named_property(env, exports, name.as_ptr(), function)
+                    != napi::NAPI_OK
+            {
+                return ptr::null_mut();
+            }
+        }
+    }
+    exports
+}
+
+unsafe extern "C" fn analyze(env: napi_env, info: napi_callback_info) -> napi_value {
+    unsafe { call(env, info, Entry::Analyze) }
+}
+
+unsafe extern "C" fn complete(env: napi_env, info: napi_callback_info) -> napi_value {
+    unsafe { call(env, info, Entry::Complete) }
+}
+
+unsafe extern "C" fn format(env: napi_env, info: napi_callback_info) -> napi_value {
+    unsafe { call(env, info, Entry::Format) }
+}
+
+unsafe extern "C" fn evaluate(env: napi_env, info: napi_callback_info) -> napi_value {
+    unsafe { call(env, info, Entry::Evaluate) }
+}
+
+/// Answers the request in the first argument; throws a `TypeError` when it is not a string or a
+/// UTF-8 `Buffer`.
+unsafe fn call(env: napi_env, info: napi_callback_info, entry: Entry) -> napi_value {
+    let mut argc = 1;
+    let mut argv = [ptr::null_mut(); 1];
+    let status = unsafe {
+        napi::napi_get_cb_info(
+            env,
+            info,
+            &mut argc,
+            argv.as_mut_ptr(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        )
+    };
+    let request = match (status, argc) {
+        (napi::NAPI_OK, 1..) => unsafe {
+            with_request(env, argv[0], |r| notion_formula::respond(entry, r))
+        },
+        _ => None,
+    };
+    let Some(response) = request else {
+        unsafe {
+            napi::napi_throw_type_error(
+                env,
+                c"ERR_INVALID_ARG_TYPE".as_ptr(),
+                c"expected a JSON request string or UTF-8 Buffer".as_ptr(),
+            );
+        }
+        return ptr::null_mut();
+    };
+
+    let mut result = ptr::null_mut();
+    unsafe {
+        napi::napi_create_string_utf8(env, response.as_ptr().cast(), response.len(), &mut result);
+    }
+    result
+}
+
+/// Runs `f` on the request text: a `Buffer`'s bytes in place, or a copy of a string's UTF-8.
+unsafe fn with_request(
+    env: napi_env,
+    value: napi_value,
+    f: impl FnOnce(&str) -> String,
+) -> Option<String> {
+    let mut is_buffer = false;
+    if unsafe { napi::napi_is_buffer(env, value, &mut is_buffer) } != napi::NAPI_OK {
+        return None;
+    }
+    if is_buffer {
+        let mut data: *mut c_void = ptr::null_mut();
+        let mut len = 0;
+        if unsafe { napi::napi_get_buffer_info(env, value, &mut data, &mut len) } != napi::NAPI_OK {
+            return None;
+        }
+        let bytes = match len {
+            0 => &[][..],
+            _ => unsafe { std::slice::from_raw_parts(data.cast::<u8>(), len) },
+        };
+        return std::str::from_utf8(bytes).ok().map(f);
+    }
+
+    let mut len = 0;
+    let status =
+        unsafe { napi::napi_get_value_string_utf8(env, value, ptr::null_mut(), 0, &mut len) };
+    if status != napi::NAPI_OK {
+        return None;
+    }
+    // Room for the NUL terminator Node-API writes.
+    let mut buf = vec![0_u8; len + 1];
+    let status = unsafe {
+        napi::napi_get_value_string_utf8(env, value, buf.as_mut_ptr().cast(), buf.len(), &mut len)
+    };
+    if status != napi::NAPI_OK {
+        return None;
+    }
+    buf.truncate(len);
+    // Node-API writes UTF-8 (lone surrogates become U+FFFD).
+    String::from_utf8(buf).ok().map(|request| f(&request))
+}
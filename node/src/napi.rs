@@ -0,0 +1,89 @@
+//! The subset of Node-API (`node_api.h`, ABI-stable since Node 8) this addon calls.
+
+#![allow(non_camel_case_types)]
+
+use std::ffi::{c_char, c_void};
+
+#[repr(C)]
+pub struct Env {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct Value {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct CallbackInfo {
+    _private: [u8; 0],
+}
+
+pub type napi_env = *mut Env;
+pub type napi_value = *mut Value;
+pub type napi_callback_info = *mut CallbackInfo;
+pub type napi_callback = Option<unsafe extern "C" fn(napi_env, napi_callback_info) -> napi_value>;
+
+/// `napi_status`; only `napi_ok` is inspected.
+pub type napi_status = i32;
+pub const NAPI_OK: napi_status = 0;
+
+/// `NAPI_AUTO_LENGTH`: the name or string is NUL-terminated.
+pub const AUTO_LENGTH: usize = usize::MAX;
+
+unsafe extern "C" {
+    pub fn napi_create_function(
+        env: napi_env,
+        utf8name: *const c_char,
+        length: usize,
+        cb: napi_callback,
+        data: *mut c_void,
+        result: *mut napi_value,
+    ) -> napi_status;
+
+    pub fn napi_set_named_property(
+        env: napi_env,
+        object: napi_value,
+        utf8name: *const c_char,
+        value: napi_value,
+    ) -> napi_status;
+
+    pub fn napi_get_cb_info(
+        env: napi_env,
+        cbinfo: napi_callback_info,
+        argc: *mut usize,
+        argv: *mut napi_value,
+        this_arg: *mut napi_value,
+        data: *mut *mut c_void,
+    ) -> napi_status;
+
+    pub fn napi_get_value_string_utf8(
+        env: napi_env,
+        value: napi_value,
+        buf: *mut c_char,
+        bufsize: usize,
+        result: *mut usize,
+    ) -> napi_status;
+
+    pub fn napi_create_string_utf8(
+        env: napi_env,
+        str: *const c_char,
+        length: usize,
+        result: *mut napi_value,
+    ) -> napi_status;
+
+    pub fn napi_is_buffer(env: napi_env, value: napi_value, result: *mut bool) -> napi_status;
+
+    pub fn napi_get_buffer_info(
+        env: napi_env,
+        value: napi_value,
+        data: *mut *mut c_void,
+        length: *mut usize,
+    ) -> napi_status;
+
+    pub fn napi_throw_type_error(
+        env: napi_env,
+        code: *const c_char,
+        msg: *const c_char,
+    ) -> napi_status;
+}
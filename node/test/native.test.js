@@ -0,0 +1,56 @@
+'use strict';
+
+const assert = require('node:assert/strict');
+const test = require('node:test');
+
+const nf = require('..');
+
+const properties = [
+  { name: 'Price', type: 'Number' },
+  { name: 'Tags', type: { List: 'String' } },
+];
+
+test('analyze reports byte spans', () => {
+  assert.deepEqual(nf.analyze({ source: 'prop("Price") * 2', properties }), {
+    output_type: 'number',
+    diagnostics: [],
+  });
+  const [diagnostic] = nf.analyze({ source: 'é + prop("Tax")' }).diagnostics;
+  assert.equal(diagnostic.code, 'semantic_error');
+  // `é` is two UTF-8 bytes.
+  assert.deepEqual(diagnostic.span, { start: 10, end: 15 });
+});
+
+test('complete returns items and signature help', () => {
+  const result = nf.complete({ source: 'if(', cursor: 3, properties });
+  assert.deepEqual(result.replace, { start: 3, end: 3 });
+  assert.ok(result.items.some((item) => item.label === 'Price' && item.kind === 'Property'));
+  assert.equal(result.signature_help.active_parameter, 0);
+});
+
+test('format and evaluate', () => {
+  assert.deepEqual(nf.format({ source: '1+2' }), { source: '1 + 2\n', cursor: 0 });
+  assert.deepEqual(
+    nf.evaluate({ source: 'prop("Price") * 2', properties, row: { Price: 2.5 } }),
+    { value: 5, type: 'number' },
+  );
+});
+
+test('JSON text and Buffers are passed through', () => {
+  const request = JSON.stringify({ source: '1 + 2' });
+  assert.deepEqual(nf.evaluate(request), { value: 3, type: 'number' });
+  assert.deepEqual(nf.evaluate(Buffer.from(request)), { value: 3, type: 'number' });
+});
+
+test('errors carry the C ABI code', () => {
+  assert.throws(() => nf.evaluate({ source: '1 / 0' }), {
+    name: 'NotionFormulaError',
+    code: 'evaluation_error',
+    message: 'division by zero',
+  });
+  assert.throws(() => nf.analyze('{'), { code: 'invalid_request' });
+  assert.throws(() => nf.analyze(Buffer.from([0xff])), {
+    name: 'TypeError',
+    code: 'ERR_INVALID_ARG_TYPE',
+  });
+});
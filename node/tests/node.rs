@@ -0,0 +1,31 @@
+//! Runs the JavaScript tests in `test/` against the freshly built addon (skipped without `node`).
+
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn javascript_tests_pass() {
+    if Command::new("node").arg("--version").output().is_err() {
+        eprintln!("skipping: `node` is not on PATH");
+        return;
+    }
+    // `target/<profile>/deps/<test binary>` -> `target/<profile>`.
+    let exe = std::env::current_exe().unwrap();
+    let profile_dir = exe.parent().and_then(Path::parent).unwrap();
+    let addon = profile_dir.join(format!("{DLL_PREFIX}notion_formula_node{DLL_SUFFIX}"));
+    assert!(addon.is_file(), "addon not built at {}", addon.display());
+
+    let output = Command::new("node")
+        .args(["--test", "test/"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env("NOTION_FORMULA_NODE_LIB", &addon)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,238 @@
+"""Notion formula analysis, completion, and evaluation for Python.
+
+A thin layer over ``libnotion_formula`` (the ``ffi`` crate). Offsets are ``str`` indices, so
+``source[d.span.start:d.span.end]`` is the text a diagnostic points at; the C ABI's UTF-8 byte
+offsets are converted on the way in and out.
+
+``properties`` maps property names to types in the context JSON form: ``"Number"``, ``"String"``,
+``"Boolean"``, ``"Date"``, ``"Unknown"``, or ``{"List": <type>}``.
+"""
+
+from __future__ import annotations
+
+import datetime as _dt
+from collections.abc import Mapping
+from dataclasses import dataclass, field
+from typing import Any, Optional, Union
+
+from ._native import FormulaError, call
+
+__all__ = [
+    "Analysis",
+    "Completion",
+    "CompletionItem",
+    "Diagnostic",
+    "Edit",
+    "FormulaError",
+    "Span",
+    "analyze",
+    "complete",
+    "evaluate",
+]
+
+TypeSpec = Union[str, Mapping[str, Any]]
+Properties = Optional[Mapping[str, TypeSpec]]
+
+_EPOCH = _dt.datetime(1970, 1, 1, tzinfo=_dt.timezone.utc)
+
+
+@dataclass(frozen=True)
+class Span:
+    """Half-open ``[start, end)`` range of ``str`` indices."""
+
+    start: int
+    end: int
+
+
+@dataclass(frozen=True)
+class Diagnostic:
+    code: str
+    message: str
+    span: Span
+
+
+@dataclass(frozen=True)
+class Analysis:
+    output_type: str
+    diagnostics: list[Diagnostic]
+
+    @property
+    def ok(self) -> bool:
+        return not self.diagnostics
+
+
+@dataclass(frozen=True)
+class Edit:
+    span: Span
+    new_text: str
+
+
+@dataclass(frozen=True)
+class CompletionItem:
+    label: str
+    kind: str
+    insert_text: str
+    detail: Optional[str] = None
+    is_disabled: bool = False
+    disabled_reason: Optional[str] = None
+    # Spans are in the source passed to `complete`.
+    edits: list[Edit] = field(default_factory=list)
+    # Cursor in the document after `apply`, when the item sets one.
+    cursor: Optional[int] = None
+
+    def apply(self, source: str) -> tuple[str, Optional[int]]:
+        """The document after accepting this item, and the cursor in it (if the item sets one)."""
+        out = source
+        for edit in sorted(self.edits, key=lambda e: e.span.start, reverse=True):
+            out = out[: edit.span.start] + edit.new_text + out[edit.span.end :]
+        return out, self.cursor
+
+
+@dataclass(frozen=True)
+class Completion:
+    replace: Span
+    items: list[CompletionItem]
+    # Indices into `items` for the default selection.
+    preferred: list[int]
+    # Rendered signatures (`name(p: type, ...) -> ret`) when the cursor is inside a call.
+    signatures: list[str]
+    active_signature: int = 0
+    active_parameter: int = 0
+
+
+def analyze(source: str, properties: Properties = None) -> Analysis:
+    """Type-checks ``source`` and returns its output type and diagnostics."""
+    ok = call("analyze", {"source": source, "properties": _properties(properties)})
+    offsets = _Offsets(source)
+    return Analysis(
+        output_type=ok["output_type"],
+        diagnostics=[
+            Diagnostic(d["code"], d["message"], offsets.span(d["span"])) for d in ok["diagnostics"]
+        ],
+    )
+
+
+def complete(source: str, cursor: int, properties: Properties = None) -> Completion:
+    """Completion items and signature help at ``cursor`` (a ``str`` index)."""
+    if not 0 <= cursor <= len(source):
+        raise FormulaError("invalid_cursor", f"cursor {cursor} is outside the source")
+    offsets = _Offsets(source)
+    ok = call(
+        "complete",
+        {
+            "source": source,
+            "cursor": len(source[:cursor].encode()),
+            "properties": _properties(properties),
+        },
+    )
+    replace = offsets.span(ok["replace"])
+    items = [_completion_item(item, source, offsets, replace) for item in ok["items"]]
+    help_ = ok["signature_help"]
+    return Completion(
+        replace=replace,
+        items=items,
+        preferred=ok["preferred_indices"],
+        signatures=[_signature_label(s) for s in help_["signatures"]] if help_ else [],
+        active_signature=help_["active_signature"] if help_ else 0,
+        active_parameter=help_["active_parameter"] if help_ else 0,
+    )
+
+
+def evaluate(
+    source: str, properties: Properties = None, row: Optional[Mapping[str, Any]] = None
+) -> Any:
+    """Evaluates ``source`` for one row and returns a Python value.
+
+    ``row`` maps property names to ``float``/``int``, ``str``, ``bool``, ``datetime``/``date``
+    (UTC when naive), lists, or ``None``; missing properties are empty. The result is ``None``
+    when empty. Raises ``FormulaError`` (``invalid_formula``, ``evaluation_error``, ...).
+    """
+    request = {
+        "source": source,
+        "properties": _properties(properties),
+        "row": {name: _to_json(value) for name, value in (row or {}).items()},
+    }
+    ok = call("evaluate", request)
+    return _from_json(ok["value"], ok["type"])
+
+
+def _properties(properties: Properties) -> list[dict[str, Any]]:
+    return [{"name": name, "type": ty} for name, ty in (properties or {}).items()]
+
+
+class _Offsets:
+    """UTF-8 byte offset -> ``str`` index for one source."""
+
+    def __init__(self, source: str) -> None:
+        self._bytes = source.encode()
+
+    def index(self, byte: int) -> int:
+        return len(self._bytes[:byte].decode())
+
+    def span(self, span: Mapping[str, int]) -> Span:
+        return Span(self.index(span["start"]), self.index(span["end"]))
+
+
+def _completion_item(
+    item: Mapping[str, Any], source: str, offsets: _Offsets, replace: Span
+) -> CompletionItem:
+    primary = item["primary_edit"]
+    edits = [
+        Edit(offsets.span(primary["span"]), primary["new_text"])
+        if primary
+        else Edit(replace, item["insert_text"])
+    ]
+    edits += [Edit(offsets.span(e["span"]), e["new_text"]) for e in item["additional_edits"]]
+    cursor = None
+    if item["cursor"] is not None:
+        # A byte offset in the edited document; re-apply the edits in bytes to convert it.
+        updated = source.encode()
+        for edit in sorted(edits, key=lambda e: e.span.start, reverse=True):
+            start = len(source[: edit.span.start].encode())
+            end = len(source[: edit.span.end].encode())
+            updated = updated[:start] + edit.new_text.encode() + updated[end:]
+        cursor = len(updated[: item["cursor"]].decode())
+    return CompletionItem(
+        label=item["label"],
+        kind=item["kind"],
+        insert_text=item["insert_text"],
+        detail=item["detail"],
+        is_disabled=item["is_disabled"],
+        disabled_reason=item["disabled_reason"],
+        edits=edits,
+        cursor=cursor,
+    )
+
+
+def _signature_label(segments: list[Mapping[str, Any]]) -> str:
+    parts = []
+    for seg in segments:
+        if seg["kind"] == "Param":
+            parts.append(f"{seg['name']}: {seg['ty']}")
+        elif seg["kind"] == "Ellipsis":
+            parts.append("...")
+        else:
+            parts.append(seg["text"])
+    return "".join(parts)
+
+
+def _to_json(value: Any) -> Any:
+    if isinstance(value, _dt.datetime):
+        if value.tzinfo is None:
+            value = value.replace(tzinfo=_dt.timezone.utc)
+        return (value - _EPOCH) // _dt.timedelta(milliseconds=1)
+    if isinstance(value, _dt.date):
+        return _to_json(_dt.datetime(value.year, value.month, value.day))
+    if isinstance(value, (list, tuple)):
+        return [_to_json(item) for item in value]
+    return value
+
+
+def _from_json(value: Any, ty: str) -> Any:
+    if value is None:
+        return None
+    if ty == "date":
+        return _EPOCH + _dt.timedelta(milliseconds=value)
+    if ty.endswith("[]") and isinstance(value, list):
+        return [_from_json(item, ty[:-2]) for item in value]
+    return value
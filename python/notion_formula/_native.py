@@ -0,0 +1,78 @@
+"""Loads ``libnotion_formula`` (the ``ffi`` crate) and calls its JSON entry points."""
+
+from __future__ import annotations
+
+import ctypes
+import json
+import os
+import sys
+from pathlib import Path
+from typing import Any
+
+_LIB_ENV = "NOTION_FORMULA_LIB"
+
+
+class FormulaError(Exception):
+    """An ``{"error": ...}`` response; ``code`` is the C ABI error code."""
+
+    def __init__(self, code: str, message: str) -> None:
+        super().__init__(f"{code}: {message}")
+        self.code = code
+        self.message = message
+
+
+def _library_name() -> str:
+    if sys.platform == "win32":
+        return "notion_formula.dll"
+    if sys.platform == "darwin":
+        return "libnotion_formula.dylib"
+    return "libnotion_formula.so"
+
+
+def _candidates() -> list[Path]:
+    override = os.environ.get(_LIB_ENV)
+    if override:
+        return [Path(override)]
+    name = _library_name()
+    package = Path(__file__).resolve().parent
+    # Bundled next to the package (wheels), then a checkout's cargo output.
+    target = package.parents[1] / "target"
+    return [package / name, target / "release" / name, target / "debug" / name]
+
+
+def _load() -> ctypes.CDLL:
+    candidates = _candidates()
+    for path in candidates:
+        if path.is_file():
+            lib = ctypes.CDLL(str(path))
+            break
+    else:
+        searched = ", ".join(str(p) for p in candidates)
+        raise ImportError(
+            f"libnotion_formula not found (searched {searched}); build it with "
+            f"`cargo build -p notion-formula-ffi --release` or set {_LIB_ENV}"
+        )
+    for entry in ("nf_analyze", "nf_complete", "nf_format", "nf_evaluate"):
+        function = getattr(lib, entry)
+        function.argtypes = [ctypes.c_char_p]
+        # Not `c_char_p`: the pointer must reach `nf_string_free` unchanged.
+        function.restype = ctypes.c_void_p
+    lib.nf_string_free.argtypes = [ctypes.c_void_p]
+    lib.nf_string_free.restype = None
+    return lib
+
+
+_lib = _load()
+
+
+def call(entry: str, request: dict[str, Any]) -> Any:
+    """Sends ``request`` to ``nf_<entry>`` and returns the ``ok`` payload."""
+    response = getattr(_lib, f"nf_{entry}")(json.dumps(request).encode())
+    try:
+        text = ctypes.string_at(response).decode()
+    finally:
+        _lib.nf_string_free(response)
+    payload = json.loads(text)
+    if "error" in payload:
+        raise FormulaError(payload["error"]["code"], payload["error"]["message"])
+    return payload["ok"]
@@ -0,0 +1,70 @@
+import datetime as dt
+import unittest
+
+import notion_formula as nf
+
+PROPERTIES = {"Price": "Number", "Due": "Date", "Tags": {"List": "String"}}
+
+
+class AnalyzeTest(unittest.TestCase):
+    def test_output_type(self):
+        analysis = nf.analyze('prop("Price") * 2', PROPERTIES)
+        self.assertTrue(analysis.ok)
+        self.assertEqual(analysis.output_type, "number")
+
+    def test_spans_are_str_indices(self):
+        source = 'é + prop("Tax")'
+        [diagnostic] = nf.analyze(source).diagnostics
+        self.assertEqual(diagnostic.code, "semantic_error")
+        self.assertEqual(source[diagnostic.span.start : diagnostic.span.end], '"Tax"')
+
+
+class CompleteTest(unittest.TestCase):
+    def test_items_apply_with_cursor(self):
+        source = "é + pr"
+        completion = nf.complete(source, len(source), PROPERTIES)
+        self.assertEqual(completion.replace, nf.Span(4, 6))
+        [price] = [item for item in completion.items if item.label == "Price"]
+        self.assertEqual(price.kind, "Property")
+        self.assertEqual(price.apply(source), ('é + prop("Price")', 17))
+
+    def test_signature_help(self):
+        completion = nf.complete("if(true, ", 9)
+        self.assertEqual(len(completion.signatures), 1)
+        self.assertTrue(completion.signatures[0].startswith("if(condition: boolean"))
+        self.assertEqual(completion.active_parameter, 1)
+
+    def test_cursor_out_of_range(self):
+        with self.assertRaises(nf.FormulaError) as raised:
+            nf.complete("1", 2)
+        self.assertEqual(raised.exception.code, "invalid_cursor")
+
+
+class EvaluateTest(unittest.TestCase):
+    def test_row_values(self):
+        self.assertEqual(nf.evaluate('prop("Price") * 2', PROPERTIES, {"Price": 2.5}), 5.0)
+        self.assertEqual(nf.evaluate('prop("Tags")', PROPERTIES, {"Tags": ["a"]}), ["a"])
+        self.assertIsNone(nf.evaluate('prop("Price")', PROPERTIES))
+
+    def test_dates_round_trip(self):
+        due = dt.datetime(2024, 1, 2, 3, 4, tzinfo=dt.timezone.utc)
+        self.assertEqual(nf.evaluate('prop("Due")', PROPERTIES, {"Due": due}), due)
+        self.assertEqual(
+            nf.evaluate('prop("Due")', PROPERTIES, {"Due": dt.date(2024, 1, 2)}),
+            dt.datetime(2024, 1, 2, tzinfo=dt.timezone.utc),
+        )
+
+    def test_errors(self):
+        cases = [
+            ("1 / 0", {}, "evaluation_error"),
+            ('prop("Tax")', {}, "invalid_formula"),
+            ('prop("Price")', {"Price": "2"}, "invalid_request"),
+        ]
+        for source, row, code in cases:
+            with self.subTest(source=source), self.assertRaises(nf.FormulaError) as raised:
+                nf.evaluate(source, PROPERTIES, row)
+            self.assertEqual(raised.exception.code, code)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -1,308 +1,78 @@
-use crate::completion::{
-    CompletionConfig, CompletionData, CompletionItem, CompletionKind, CompletionOutput, TextEdit,
-    complete,
-};
-use analyzer::semantic::{Context, FunctionSig, Property, Ty, builtins_functions};
-use std::collections::HashSet;
-
-// ----------------------------
-// Demo Properties
-// ----------------------------
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Prop {
-    Title,
-    Age,
-    Flag,
-}
-
-// ----------------------------
-// Demo Functions
-// ----------------------------
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Func {
-    If,
-    Sum,
-}
-
-#[allow(dead_code)]
-impl Func {
-    pub fn name(&self) -> &'static str {
-        match self {
-            Func::If => "if",
-            Func::Sum => "sum",
-        }
-    }
-
-    pub fn label(&self) -> String {
-        format!("{}()", self.name())
-    }
-
-    pub fn data(&self) -> CompletionData {
-        CompletionData::Function {
-            name: self.name().to_string(),
-        }
-    }
+//! [`t`]: the chained completion DSL, and [`complete`], its one-shot counterpart.
 
-    pub fn kind(&self) -> CompletionKind {
-        match self {
-            Func::If => CompletionKind::FunctionGeneral,
-            Func::Sum => CompletionKind::FunctionNumber,
-        }
-    }
-}
-
-// ----------------------------
-// Builtins (Notion-style identifiers + operators)
-// ----------------------------
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(dead_code)]
-pub enum Builtin {
-    Not,
-    True,
-    False,
-    EqEq,
-    Ne,
-    Gt,
-    Ge,
-    Lt,
-    Le,
-    Plus,
-    Minus,
-    Star,
-    Slash,
-}
-
-#[allow(dead_code)]
-impl Builtin {
-    pub fn label(&self) -> &'static str {
-        match self {
-            Builtin::Not => "not",
-            Builtin::True => "true",
-            Builtin::False => "false",
-            Builtin::EqEq => "==",
-            Builtin::Ne => "!=",
-            Builtin::Gt => ">",
-            Builtin::Ge => ">=",
-            Builtin::Lt => "<",
-            Builtin::Le => "<=",
-            Builtin::Plus => "+",
-            Builtin::Minus => "-",
-            Builtin::Star => "*",
-            Builtin::Slash => "/",
-        }
-    }
-
-    pub fn kind(&self) -> CompletionKind {
-        match self {
-            Builtin::Not | Builtin::True | Builtin::False => CompletionKind::Builtin,
-            _ => CompletionKind::Operator,
-        }
-    }
-
-    pub fn data(&self) -> Option<CompletionData> {
-        None
-    }
-}
-
-// ----------------------------
-// Demo Item (unified enum for all completion items)
-// ----------------------------
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Item {
-    Prop(Prop),
-    Func(Func),
-    Builtin(Builtin),
-}
-
-impl Item {
-    pub fn label(&self) -> String {
-        match self {
-            Item::Prop(p) => p.name().to_string(),
-            Item::Func(f) => f.label(),
-            Item::Builtin(b) => b.label().to_string(),
-        }
-    }
-
-    pub fn matches(&self, item: &CompletionItem) -> bool {
-        match self {
-            Item::Prop(prop) => {
-                item.kind == CompletionKind::Property
-                    && item.data == Some(prop.prop_expr_data())
-                    && item.label == prop.name()
-            }
-            Item::Func(func) => {
-                item.kind == func.kind()
-                    && item.data == Some(func.data())
-                    && item.label == func.label()
-            }
-            Item::Builtin(b) => {
-                item.label == b.label() && item.kind == b.kind() && item.data.is_none()
-            }
-        }
-    }
-}
-
-impl Prop {
-    pub fn name(&self) -> &'static str {
-        match self {
-            Prop::Title => "Title",
-            Prop::Age => "Age",
-            Prop::Flag => "Flag",
-        }
-    }
-
-    pub fn ty(&self) -> Ty {
-        match self {
-            Prop::Title => Ty::String,
-            Prop::Age => Ty::Number,
-            Prop::Flag => Ty::Boolean,
-        }
-    }
-
-    pub fn prop_expr_data(&self) -> CompletionData {
-        CompletionData::PropExpr {
-            property_name: self.name().to_string(),
-        }
-    }
-}
-
-// ----------------------------
-// Context Builder Extensions
-// ----------------------------
-
-#[derive(Clone)]
-pub struct ContextBuilder {
-    properties: Vec<Property>,
-    functions: Vec<FunctionSig>,
-}
+use std::collections::HashSet;
 
-pub fn ctx() -> ContextBuilder {
-    ContextBuilder::default()
+use analyzer::Span;
+use analyzer::semantic::Context;
+use ide::{CompletionConfig, CompletionData, CompletionItem, CompletionKind, SignatureHelp};
+
+use crate::{Builtin, Fixture, Func, Item, Prop, apply_text_edits};
+
+/// [`ide::help`] flattened into one struct: the completion list and signature help.
+///
+/// `replace` and all edit ranges are UTF-8 byte spans, half-open `[start, end)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionOutput {
+    pub items: Vec<CompletionItem>,
+    pub replace: Span,
+    pub signature_help: Option<SignatureHelp>,
+    /// Indices into `items` for the UI default selection.
+    pub preferred_indices: Vec<usize>,
 }
 
-impl Default for ContextBuilder {
-    fn default() -> Self {
-        Self {
-            properties: Vec::new(),
-            functions: builtins_functions(),
-        }
-    }
-}
-
-impl ContextBuilder {
-    pub fn prop(mut self, name: impl Into<String>, ty: Ty) -> Self {
-        self.properties.push(Property {
-            name: name.into(),
-            ty,
-            disabled_reason: None,
-        });
-        self
-    }
-
-    pub fn props_demo_basic(self) -> Self {
-        self.props(&[Prop::Title, Prop::Age, Prop::Flag])
-    }
-
-    pub fn props(mut self, props: &[Prop]) -> Self {
-        for prop in props {
-            self.properties.push(Property {
-                name: prop.name().to_string(),
-                ty: prop.ty(),
-                disabled_reason: None,
-            });
-        }
-        self
-    }
-
-    pub fn only_funcs(mut self, names: &[&str]) -> Self {
-        let mut remaining: HashSet<&str> = names.iter().copied().collect();
-        let funcs = builtins_functions()
-            .into_iter()
-            .filter(|f| remaining.remove(f.name.as_str()))
-            .collect::<Vec<_>>();
-        assert!(
-            remaining.is_empty(),
-            "unknown builtin function(s): {:?}",
-            remaining
-        );
-        self.functions = funcs;
-        self
-    }
-
-    pub fn without_funcs(mut self, names: &[&str]) -> Self {
-        let remove: HashSet<&str> = names.iter().copied().collect();
-        self.functions.retain(|f| !remove.contains(f.name.as_str()));
-        self
-    }
-
-    pub fn disabled_prop(
-        mut self,
-        name: impl Into<String>,
-        ty: Ty,
-        reason: impl Into<String>,
-    ) -> Self {
-        self.properties.push(Property {
-            name: name.into(),
-            ty,
-            disabled_reason: Some(reason.into()),
-        });
-        self
-    }
-
-    pub fn build(self) -> Context {
-        Context {
-            properties: self.properties,
-            functions: self.functions,
-        }
+/// Computes completion items and signature help at a cursor position.
+///
+/// `cursor` is a UTF-8 byte offset into `text`.
+pub fn complete(
+    text: &str,
+    cursor: usize,
+    ctx: &Context,
+    config: CompletionConfig,
+) -> CompletionOutput {
+    let help = ide::help(text, cursor, ctx, config);
+    CompletionOutput {
+        items: help.completion.items,
+        replace: help.completion.replace,
+        signature_help: help.signature_help,
+        preferred_indices: help.completion.preferred_indices,
     }
 }
 
-// ----------------------------
-// Completion Test DSL
-// ----------------------------
-
-pub fn t(input_with_cursor: &str) -> CompletionTestBuilder {
-    CompletionTestBuilder::new(input_with_cursor)
+/// Starts a completion test on `fixture`, a source text with one `$0` cursor marker.
+///
+/// # Panics
+///
+/// If `fixture` does not contain exactly one `$0`.
+pub fn t(fixture: &str) -> CompletionTest {
+    CompletionTest::new(fixture)
 }
 
-pub struct CompletionTestBuilder {
-    #[allow(dead_code)]
-    input_with_cursor: String,
-    replaced: String,
-    cursor: u32,
+/// A completion request on a `$0` fixture, checked with chained `expect_*` assertions.
+///
+/// `ide::help` runs once, on the first assertion; the context defaults to an empty one (no
+/// builtins), so tests state what they rely on with [`CompletionTest::ctx`].
+pub struct CompletionTest {
+    fixture: Fixture,
     ctx: Context,
     config: Option<CompletionConfig>,
     output: Option<CompletionOutput>,
     ignore_props: bool,
 }
 
-impl CompletionTestBuilder {
+impl CompletionTest {
     fn empty_context() -> Context {
         Context {
-            properties: Vec::new(),
-            functions: Vec::new(),
+            properties: Vec::new().into(),
+            functions: Vec::new().into(),
+            property_resolver: None,
+            property_lookup: Default::default(),
+            limits: Default::default(),
         }
     }
 
-    fn new(input_with_cursor: &str) -> Self {
-        let cursor = input_with_cursor
-            .find("$0")
-            .expect("fixture must contain $0 marker");
-        let text = input_with_cursor.to_string();
-        let replaced = text.replace("$0", "");
-        assert!(
-            replaced.len() + 2 == text.len(),
-            "fixture must contain exactly one $0 marker"
-        );
-
+    fn new(fixture: &str) -> Self {
         Self {
-            input_with_cursor: text,
-            replaced,
-            cursor: cursor as u32,
+            fixture: Fixture::parse(fixture),
             ctx: Self::empty_context(),
             config: None,
             output: None,
@@ -321,7 +91,15 @@ impl CompletionTestBuilder {
     }
 
     pub fn preferred_limit(mut self, preferred_limit: usize) -> Self {
-        self.config = Some(CompletionConfig { preferred_limit });
+        self.config = Some(CompletionConfig {
+            preferred_limit,
+            ..CompletionConfig::default()
+        });
+        self
+    }
+
+    pub fn config(mut self, config: CompletionConfig) -> Self {
+        self.config = Some(config);
         self
     }
 
@@ -355,17 +133,23 @@ impl CompletionTestBuilder {
             .unwrap_or_default()
     }
 
+    /// The completion output, for checks the `expect_*` methods do not cover.
+    pub fn output(&mut self) -> &CompletionOutput {
+        self.ensure_run()
+    }
+
     fn ensure_run(&mut self) -> &CompletionOutput {
         if self.output.is_none() {
-            let config = self.config.unwrap_or_default();
-            let out = complete(&self.replaced, self.cursor as usize, &self.ctx, config);
+            let config = self.config.clone().unwrap_or_default();
+            let fixture = &self.fixture;
+            let out = complete(&fixture.text, fixture.cursor as usize, &self.ctx, config);
             self.output = Some(out);
         }
         self.output.as_ref().unwrap()
     }
 
     pub fn expect_replace_contains_cursor(mut self) -> Self {
-        let cursor = self.cursor;
+        let cursor = self.fixture.cursor;
         let out = self.ensure_run();
         assert!(
             out.replace.start <= cursor && cursor <= out.replace.end,
@@ -385,7 +169,6 @@ impl CompletionTestBuilder {
         self
     }
 
-    #[allow(dead_code)]
     pub fn expect_empty(mut self) -> Self {
         let out = self.ensure_run();
         assert!(
@@ -661,7 +444,7 @@ impl CompletionTestBuilder {
             .segments
             .iter()
             .find_map(|seg| match seg {
-                crate::DisplaySegment::Param {
+                ide::DisplaySegment::Param {
                     name,
                     param_index: Some(i),
                     ..
@@ -687,7 +470,7 @@ impl CompletionTestBuilder {
             .expect("expected at least one signature");
         let mut rendered = String::new();
         for seg in &active.segments {
-            use crate::DisplaySegment as S;
+            use ide::DisplaySegment as S;
             match seg {
                 S::Name { text }
                 | S::Punct { text }
@@ -719,7 +502,7 @@ impl CompletionTestBuilder {
             .expect("expected at least one signature");
         let mut rendered = String::new();
         for seg in &active.segments {
-            use crate::DisplaySegment as S;
+            use ide::DisplaySegment as S;
             match seg {
                 S::Name { text }
                 | S::Punct { text }
@@ -898,8 +681,8 @@ impl CompletionTestBuilder {
 
     // ----- apply completion -----
 
-    pub fn apply(mut self, label: &str) -> ApplyResult {
-        let replaced = self.replaced.clone();
+    pub fn apply(mut self, label: &str) -> AppliedCompletion {
+        let text = self.fixture.text.clone();
         let out = self.ensure_run();
         let item = out
             .items
@@ -924,7 +707,7 @@ impl CompletionTestBuilder {
         edits.push(primary.clone());
         edits.extend(item.additional_edits.iter().cloned());
 
-        let updated = apply_text_edits(&replaced, &edits);
+        let updated = apply_text_edits(&text, &edits);
         let new_cursor = item.cursor.unwrap_or_else(|| {
             out.replace
                 .start
@@ -932,19 +715,21 @@ impl CompletionTestBuilder {
         });
         assert!((new_cursor as usize) <= updated.len());
 
-        ApplyResult {
+        AppliedCompletion {
             updated,
             cursor: new_cursor,
         }
     }
 }
 
-pub struct ApplyResult {
+/// The document after [`CompletionTest::apply`], with the cursor where the editor puts it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedCompletion {
     pub updated: String,
     pub cursor: u32,
 }
 
-impl ApplyResult {
+impl AppliedCompletion {
     /// If `expected` contains exactly one `$0`, this asserts both:
     /// - updated text equals expected with `$0` removed
     /// - cursor equals the byte index where `$0` was
@@ -974,37 +759,3 @@ impl ApplyResult {
         self
     }
 }
-
-fn apply_text_edits(original: &str, edits: &[TextEdit]) -> String {
-    let mut edits_with_idx = edits.iter().enumerate().collect::<Vec<_>>();
-    edits_with_idx.sort_by(|(a_idx, a), (b_idx, b)| {
-        let a_key = (
-            std::cmp::Reverse(a.range.start),
-            std::cmp::Reverse(a.range.end),
-            *a_idx,
-        );
-        let b_key = (
-            std::cmp::Reverse(b.range.start),
-            std::cmp::Reverse(b.range.end),
-            *b_idx,
-        );
-        a_key.cmp(&b_key)
-    });
-
-    let mut updated = original.to_string();
-    for (_, edit) in edits_with_idx {
-        let start = edit.range.start as usize;
-        let end = edit.range.end as usize;
-        assert!(start <= end);
-        assert!(end <= updated.len());
-        assert!(updated.is_char_boundary(start));
-        assert!(updated.is_char_boundary(end));
-
-        let mut next = String::with_capacity(updated.len() - (end - start) + edit.new_text.len());
-        next.push_str(&updated[..start]);
-        next.push_str(&edit.new_text);
-        next.push_str(&updated[end..]);
-        updated = next;
-    }
-    updated
-}
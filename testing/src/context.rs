@@ -0,0 +1,121 @@
+//! [`ctx`]: contexts for completion tests, preloaded with the builtins.
+
+use analyzer::semantic::{
+    self, Context, FormulaLimits, FunctionSig, PropertyLookup, PropertyResolver, Ty,
+    builtins_functions,
+};
+
+use crate::Prop;
+
+/// `semantic::ContextBuilder` preloaded with the builtins, plus the demo-property shorthands.
+#[derive(Clone)]
+pub struct ContextBuilder {
+    inner: semantic::ContextBuilder,
+}
+
+/// A builder with every builtin function and no properties.
+pub fn ctx() -> ContextBuilder {
+    ContextBuilder::default()
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        Self {
+            inner: Context::builder().with_builtins(),
+        }
+    }
+}
+
+impl ContextBuilder {
+    pub fn prop(mut self, name: impl Into<String>, ty: Ty) -> Self {
+        self.inner = self.inner.prop(name, ty);
+        self
+    }
+
+    pub fn prop_with_options<S: Into<String>>(
+        mut self,
+        name: impl Into<String>,
+        ty: Ty,
+        options: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.inner = self.inner.prop_with_options(name, ty, options);
+        self
+    }
+
+    pub fn property_resolver(mut self, resolver: impl PropertyResolver + 'static) -> Self {
+        self.inner = self.inner.property_resolver(resolver);
+        self
+    }
+
+    pub fn property_lookup(mut self, lookup: PropertyLookup) -> Self {
+        self.inner = self.inner.property_lookup(lookup);
+        self
+    }
+
+    pub fn limits(mut self, limits: FormulaLimits) -> Self {
+        self.inner = self.inner.limits(limits);
+        self
+    }
+
+    /// Adds a custom function next to the builtins.
+    pub fn func(mut self, sig: FunctionSig) -> Self {
+        self.inner = self.inner.func(sig);
+        self
+    }
+
+    /// Adds [`Prop::Title`], [`Prop::Age`], and [`Prop::Flag`].
+    pub fn props_demo_basic(self) -> Self {
+        self.props(&[Prop::Title, Prop::Age, Prop::Flag])
+    }
+
+    pub fn props(mut self, props: &[Prop]) -> Self {
+        for prop in props {
+            self.inner = self.inner.prop(prop.name(), prop.ty());
+        }
+        self
+    }
+
+    /// Keeps only the builtin functions in `names`.
+    ///
+    /// # Panics
+    ///
+    /// If a name is not a builtin function.
+    pub fn only_funcs(mut self, names: &[&str]) -> Self {
+        let unknown: Vec<&str> = names
+            .iter()
+            .copied()
+            .filter(|name| builtins_functions().iter().all(|f| f.name != *name))
+            .collect();
+        assert!(
+            unknown.is_empty(),
+            "unknown builtin function(s): {:?}",
+            unknown
+        );
+        let others: Vec<&str> = builtins_functions()
+            .iter()
+            .map(|f| f.name.as_str())
+            .filter(|name| !names.contains(name))
+            .collect();
+        self.inner = self.inner.without(&others);
+        self
+    }
+
+    pub fn without_funcs(mut self, names: &[&str]) -> Self {
+        self.inner = self.inner.without(names);
+        self
+    }
+
+    pub fn disabled_prop(
+        mut self,
+        name: impl Into<String>,
+        ty: Ty,
+        reason: impl Into<String>,
+    ) -> Self {
+        self.inner = self.inner.disabled_prop(name, ty, reason);
+        self
+    }
+
+    pub fn build(self) -> Context {
+        self.inner.build()
+    }
+}
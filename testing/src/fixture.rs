@@ -0,0 +1,80 @@
+//! `$0` cursor fixtures and text-edit application.
+
+use ide::TextEdit;
+
+/// The cursor marker in fixtures.
+pub const CURSOR: &str = "$0";
+
+/// A source text with a cursor, written as one string with a single `$0` marker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    /// The text without the marker.
+    pub text: String,
+    /// UTF-8 byte offset of the marker in `text`.
+    pub cursor: u32,
+}
+
+impl Fixture {
+    /// Splits `fixture` at its `$0` marker.
+    ///
+    /// # Panics
+    ///
+    /// If `fixture` does not contain exactly one `$0`.
+    pub fn parse(fixture: &str) -> Self {
+        Self::try_parse(fixture).expect("fixture must contain exactly one $0 marker")
+    }
+
+    /// Like [`Fixture::parse`], `None` unless `fixture` contains exactly one `$0`.
+    pub fn try_parse(fixture: &str) -> Option<Self> {
+        let cursor = fixture.find(CURSOR)?;
+        if fixture.matches(CURSOR).count() != 1 {
+            return None;
+        }
+        Some(Self {
+            text: fixture.replace(CURSOR, ""),
+            cursor: cursor as u32,
+        })
+    }
+}
+
+/// `original` with `edits` applied, as an editor applies one completion's edits.
+///
+/// Ranges are byte spans into `original` and may come in any order; they are applied from the
+/// end of the text backwards.
+///
+/// # Panics
+///
+/// If a range is reversed, out of bounds, or not on a char boundary.
+pub fn apply_text_edits(original: &str, edits: &[TextEdit]) -> String {
+    let mut edits_with_idx = edits.iter().enumerate().collect::<Vec<_>>();
+    edits_with_idx.sort_by(|(a_idx, a), (b_idx, b)| {
+        let a_key = (
+            std::cmp::Reverse(a.range.start),
+            std::cmp::Reverse(a.range.end),
+            *a_idx,
+        );
+        let b_key = (
+            std::cmp::Reverse(b.range.start),
+            std::cmp::Reverse(b.range.end),
+            *b_idx,
+        );
+        a_key.cmp(&b_key)
+    });
+
+    let mut updated = original.to_string();
+    for (_, edit) in edits_with_idx {
+        let start = edit.range.start as usize;
+        let end = edit.range.end as usize;
+        assert!(start <= end);
+        assert!(end <= updated.len());
+        assert!(updated.is_char_boundary(start));
+        assert!(updated.is_char_boundary(end));
+
+        let mut next = String::with_capacity(updated.len() - (end - start) + edit.new_text.len());
+        next.push_str(&updated[..start]);
+        next.push_str(&edit.new_text);
+        next.push_str(&updated[end..]);
+        updated = next;
+    }
+    updated
+}
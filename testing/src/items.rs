@@ -0,0 +1,171 @@
+//! Typed matchers for the sample properties, functions, and builtins the DSL knows.
+
+use analyzer::semantic::Ty;
+use ide::{CompletionData, CompletionItem, CompletionKind};
+
+// ----------------------------
+// Demo Properties
+// ----------------------------
+
+/// The demo properties of [`ContextBuilder::props_demo_basic`](crate::ContextBuilder::props_demo_basic).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prop {
+    Title,
+    Age,
+    Flag,
+}
+
+// ----------------------------
+// Demo Functions
+// ----------------------------
+
+/// Builtin functions with a known completion kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    If,
+    Sum,
+}
+
+impl Func {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Func::If => "if",
+            Func::Sum => "sum",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        format!("{}()", self.name())
+    }
+
+    pub fn data(&self) -> CompletionData {
+        CompletionData::Function {
+            name: self.name().to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> CompletionKind {
+        match self {
+            Func::If => CompletionKind::FunctionGeneral,
+            Func::Sum => CompletionKind::FunctionNumber,
+        }
+    }
+}
+
+// ----------------------------
+// Builtins (Notion-style identifiers + operators)
+// ----------------------------
+
+/// Keywords, constants, and operators offered after an expression or at its start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Builtin {
+    Not,
+    True,
+    False,
+    EqEq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+}
+
+impl Builtin {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Builtin::Not => "not",
+            Builtin::True => "true",
+            Builtin::False => "false",
+            Builtin::EqEq => "==",
+            Builtin::Ne => "!=",
+            Builtin::Gt => ">",
+            Builtin::Ge => ">=",
+            Builtin::Lt => "<",
+            Builtin::Le => "<=",
+            Builtin::Plus => "+",
+            Builtin::Minus => "-",
+            Builtin::Star => "*",
+            Builtin::Slash => "/",
+        }
+    }
+
+    pub fn kind(&self) -> CompletionKind {
+        match self {
+            Builtin::Not => CompletionKind::Keyword,
+            Builtin::True | Builtin::False => CompletionKind::Constant,
+            _ => CompletionKind::Operator,
+        }
+    }
+
+    pub fn data(&self) -> Option<CompletionData> {
+        None
+    }
+}
+
+// ----------------------------
+// Demo Item (unified enum for all completion items)
+// ----------------------------
+
+/// Any of the above, matched against a [`CompletionItem`] by label, kind, and data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    Prop(Prop),
+    Func(Func),
+    Builtin(Builtin),
+}
+
+impl Item {
+    pub fn label(&self) -> String {
+        match self {
+            Item::Prop(p) => p.name().to_string(),
+            Item::Func(f) => f.label(),
+            Item::Builtin(b) => b.label().to_string(),
+        }
+    }
+
+    pub fn matches(&self, item: &CompletionItem) -> bool {
+        match self {
+            Item::Prop(prop) => {
+                item.kind == CompletionKind::Property
+                    && item.data == Some(prop.prop_expr_data())
+                    && item.label == prop.name()
+            }
+            Item::Func(func) => {
+                item.kind == func.kind()
+                    && item.data == Some(func.data())
+                    && item.label == func.label()
+            }
+            Item::Builtin(b) => {
+                item.label == b.label() && item.kind == b.kind() && item.data.is_none()
+            }
+        }
+    }
+}
+
+impl Prop {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Prop::Title => "Title",
+            Prop::Age => "Age",
+            Prop::Flag => "Flag",
+        }
+    }
+
+    pub fn ty(&self) -> Ty {
+        match self {
+            Prop::Title => Ty::String,
+            Prop::Age => Ty::Number,
+            Prop::Flag => Ty::Boolean,
+        }
+    }
+
+    pub fn prop_expr_data(&self) -> CompletionData {
+        CompletionData::PropExpr {
+            property_name: self.name().to_string(),
+        }
+    }
+}
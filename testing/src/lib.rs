@@ -0,0 +1,25 @@
+//! Test support for embedders of `ide`: `$0` cursor fixtures, a chained completion DSL, and
+//! completion-edit application.
+//!
+//! ```
+//! use notion_formula_testing::{Prop, ctx, t};
+//!
+//! t("Ti$0")
+//!     .ctx(ctx().props_demo_basic().build())
+//!     .expect_prop(Prop::Title)
+//!     .apply("Title")
+//!     .expect_text(r#"prop("Title")$0"#);
+//! ```
+//!
+//! Assertions panic with the completion list or the text that did not match, so they read
+//! like `assert_eq!` failures inside `#[test]` functions.
+
+mod completion;
+mod context;
+mod fixture;
+mod items;
+
+pub use completion::{AppliedCompletion, CompletionOutput, CompletionTest, complete, t};
+pub use context::{ContextBuilder, ctx};
+pub use fixture::{Fixture, apply_text_edits};
+pub use items::{Builtin, Func, Item, Prop};
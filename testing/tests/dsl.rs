@@ -0,0 +1,64 @@
+use analyzer::Span;
+use analyzer::semantic::{FunctionSig, Ty};
+use ide::TextEdit;
+use notion_formula_testing::{Fixture, Item, Prop, apply_text_edits, ctx, t};
+
+#[test]
+fn fixture_splits_at_the_cursor_marker() {
+    let fixture = Fixture::parse("1 + $0");
+    assert_eq!(fixture.text, "1 + ");
+    assert_eq!(fixture.cursor, 4);
+    assert_eq!(Fixture::try_parse("1 + 2"), None);
+    assert_eq!(Fixture::try_parse("$0 + $0"), None);
+}
+
+#[test]
+#[should_panic(expected = "exactly one $0 marker")]
+fn fixture_without_marker_panics() {
+    t("sum(");
+}
+
+#[test]
+fn text_edits_apply_from_the_end() {
+    let edit = |start, end, new_text: &str| TextEdit {
+        range: Span { start, end },
+        new_text: new_text.to_string(),
+    };
+    let edits = [edit(0, 2, "sum"), edit(3, 3, "1, 2")];
+    assert_eq!(apply_text_edits("su()", &edits), "sum(1, 2)");
+}
+
+#[test]
+fn custom_functions_complete_next_to_builtins() {
+    let double = FunctionSig::builder("double")
+        .param("value", Ty::Number)
+        .returns(Ty::Number)
+        .build()
+        .unwrap();
+    let c = ctx().props_demo_basic().func(double).build();
+
+    t("dou$0")
+        .ctx(c.clone())
+        .expect_contains_labels(&["double()"])
+        .apply("double()")
+        .expect_text("double($0)");
+    t("double($0")
+        .ctx(c)
+        .expect_sig_active_param_name("value")
+        .expect_contains_items(&[Item::Prop(Prop::Age)]);
+}
+
+#[test]
+fn option_values_complete_from_the_context() {
+    let c = ctx()
+        .prop_with_options("Status", Ty::String, ["Done", "Doing"])
+        .build();
+    let mut test = t(r#"prop("Status") == "$0""#).ctx(c);
+    let labels: Vec<&str> = test
+        .output()
+        .items
+        .iter()
+        .map(|item| item.label.as_str())
+        .collect();
+    assert_eq!(labels, ["Done", "Doing"]);
+}